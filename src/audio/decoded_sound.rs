@@ -0,0 +1,118 @@
+use std::{fs::File, path::Path};
+
+use kira::{playable::PlayableSettings, sound::Sound, AudioError, AudioResult, Frame};
+use lewton::inside_ogg::OggStreamReader;
+
+/// Converts a packet of samples decoded by lewton (one `Vec`
+/// per channel) to frames.
+pub fn packet_to_frames(packet: Vec<Vec<f32>>) -> AudioResult<Vec<Frame>> {
+	match packet.len() {
+		1 => Ok(packet[0]
+			.iter()
+			.map(|sample| Frame::from_mono(*sample))
+			.collect()),
+		2 => Ok(packet[0]
+			.iter()
+			.zip(&packet[1])
+			.map(|(left, right)| Frame::new(*left, *right))
+			.collect()),
+		_ => Err(AudioError::UnsupportedChannelConfiguration),
+	}
+}
+
+/// Audio data decoded on the main thread.
+///
+/// Kira doesn't give us access to the samples of a `Sound`
+/// once it's been created, so screens that need to look at
+/// the audio (for example, to draw a waveform) decode it
+/// themselves and create the `Sound` from the result.
+#[derive(Debug, Clone)]
+pub struct DecodedSound {
+	sample_rate: u32,
+	frames: Vec<Frame>,
+}
+
+impl DecodedSound {
+	pub fn from_ogg_file<P: AsRef<Path>>(path: P) -> AudioResult<Self> {
+		let mut reader = OggStreamReader::new(File::open(path)?)?;
+		let mut frames = vec![];
+		while let Some(packet) = reader.read_dec_packet_generic::<Vec<Vec<f32>>>()? {
+			frames.extend(packet_to_frames(packet)?);
+		}
+		Ok(Self {
+			sample_rate: reader.ident_hdr.audio_sample_rate,
+			frames,
+		})
+	}
+
+	/// Sums several sounds into one. The sounds are expected
+	/// to share the same sample rate.
+	pub fn mix(sounds: &[&DecodedSound]) -> Self {
+		let sample_rate = sounds.first().map_or(44100, |sound| sound.sample_rate);
+		let length = sounds
+			.iter()
+			.map(|sound| sound.frames.len())
+			.max()
+			.unwrap_or(0);
+		let mut frames = vec![Frame::from_mono(0.0); length];
+		for sound in sounds {
+			for (output, input) in frames.iter_mut().zip(&sound.frames) {
+				*output += *input;
+			}
+		}
+		Self {
+			sample_rate,
+			frames,
+		}
+	}
+
+	/// Averages the two channels into one, so the sound plays
+	/// the same out of both speakers.
+	pub fn to_mono(&self) -> Self {
+		Self {
+			sample_rate: self.sample_rate,
+			frames: self
+				.frames
+				.iter()
+				.map(|frame| Frame::from_mono((frame.left + frame.right) / 2.0))
+				.collect(),
+		}
+	}
+
+	pub fn sample_rate(&self) -> u32 {
+		self.sample_rate
+	}
+
+	pub fn frames(&self) -> &[Frame] {
+		&self.frames
+	}
+
+	pub fn num_frames(&self) -> usize {
+		self.frames.len()
+	}
+
+	pub fn duration(&self) -> f64 {
+		self.frames.len() as f64 / self.sample_rate as f64
+	}
+
+	/// Gets the loudest absolute sample value in each of
+	/// `num_peaks` evenly sized chunks of the sound.
+	pub fn peaks(&self, num_peaks: usize) -> Vec<f32> {
+		if self.frames.is_empty() || num_peaks == 0 {
+			return vec![0.0; num_peaks];
+		}
+		let chunk_size = (self.frames.len() as f64 / num_peaks as f64).ceil() as usize;
+		self.frames
+			.chunks(chunk_size.max(1))
+			.map(|chunk| {
+				chunk.iter().fold(0.0f32, |peak, frame| {
+					peak.max(frame.left.abs()).max(frame.right.abs())
+				})
+			})
+			.collect()
+	}
+
+	pub fn to_sound(&self, settings: PlayableSettings) -> Sound {
+		Sound::from_frames(self.sample_rate, self.frames.clone(), settings)
+	}
+}
@@ -0,0 +1,163 @@
+use std::sync::{
+	atomic::{AtomicU32, Ordering},
+	Arc,
+};
+
+use kira::{mixer::effect::Effect, parameter::Parameters, CachedValue, Frame, Value};
+
+// quieter than this counts as silence, which keeps the level
+// in decibels from heading off to negative infinity
+const MIN_AMPLITUDE: f64 = 0.00001;
+
+#[derive(Debug, Copy, Clone)]
+pub struct CompressorSettings {
+	/// The level (in decibels) above which the signal gets
+	/// turned down.
+	pub threshold: Value<f64>,
+	/// How many decibels the input has to rise over the threshold
+	/// for the output to rise by one. 1 leaves the signal alone,
+	/// and very high ratios hold the output at the threshold,
+	/// which makes the compressor a limiter.
+	pub ratio: Value<f64>,
+	/// How long the compressor takes to react to the signal
+	/// getting louder (in seconds).
+	pub attack_time: f64,
+	/// How long the compressor takes to let go once the signal
+	/// gets quieter (in seconds).
+	pub release_time: f64,
+	/// How much the output is turned back up after compressing
+	/// (in decibels).
+	pub makeup_gain: Value<f64>,
+}
+
+impl CompressorSettings {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn threshold<V: Into<Value<f64>>>(self, threshold: V) -> Self {
+		Self {
+			threshold: threshold.into(),
+			..self
+		}
+	}
+
+	pub fn ratio<V: Into<Value<f64>>>(self, ratio: V) -> Self {
+		Self {
+			ratio: ratio.into(),
+			..self
+		}
+	}
+
+	pub fn attack_time(self, attack_time: f64) -> Self {
+		Self {
+			attack_time,
+			..self
+		}
+	}
+
+	pub fn release_time(self, release_time: f64) -> Self {
+		Self {
+			release_time,
+			..self
+		}
+	}
+
+	pub fn makeup_gain<V: Into<Value<f64>>>(self, makeup_gain: V) -> Self {
+		Self {
+			makeup_gain: makeup_gain.into(),
+			..self
+		}
+	}
+}
+
+impl Default for CompressorSettings {
+	fn default() -> Self {
+		Self {
+			threshold: (-12.0).into(),
+			ratio: 4.0.into(),
+			attack_time: 0.005,
+			release_time: 0.15,
+			makeup_gain: 0.0.into(),
+		}
+	}
+}
+
+/// Turns the signal down when it gets louder than a threshold,
+/// which evens out the difference between the loud and quiet
+/// parts.
+///
+/// The level is followed with separate attack and release times
+/// rather than reacting to every sample, so the compressor
+/// rides the shape of each hit instead of distorting the
+/// waveform. How much it's reducing the gain is shared with a
+/// [`GainReductionReader`] for metering, the same way a
+/// [`Tap`](super::tap::Tap) shares its samples.
+#[derive(Debug)]
+pub struct Compressor {
+	threshold: CachedValue<f64>,
+	ratio: CachedValue<f64>,
+	attack_time: f64,
+	release_time: f64,
+	makeup_gain: CachedValue<f64>,
+	/// The current gain reduction in decibels.
+	gain_reduction: f64,
+	shared_gain_reduction: Arc<AtomicU32>,
+}
+
+/// Reads how much a [`Compressor`] is turning the signal down.
+#[derive(Debug, Clone)]
+pub struct GainReductionReader {
+	shared_gain_reduction: Arc<AtomicU32>,
+}
+
+impl Compressor {
+	pub fn new(settings: CompressorSettings) -> (Self, GainReductionReader) {
+		let shared_gain_reduction = Arc::new(AtomicU32::new(0.0f32.to_bits()));
+		(
+			Self {
+				threshold: CachedValue::new(settings.threshold, -12.0),
+				ratio: CachedValue::new(settings.ratio, 4.0),
+				attack_time: settings.attack_time,
+				release_time: settings.release_time,
+				makeup_gain: CachedValue::new(settings.makeup_gain, 0.0),
+				gain_reduction: 0.0,
+				shared_gain_reduction: shared_gain_reduction.clone(),
+			},
+			GainReductionReader {
+				shared_gain_reduction,
+			},
+		)
+	}
+}
+
+impl Effect for Compressor {
+	fn process(&mut self, dt: f64, input: Frame, parameters: &Parameters) -> Frame {
+		self.threshold.update(parameters);
+		self.ratio.update(parameters);
+		self.makeup_gain.update(parameters);
+		let peak = (input.left.abs().max(input.right.abs()) as f64).max(MIN_AMPLITUDE);
+		let level = 20.0 * peak.log10();
+		let overshoot = (level - self.threshold.value()).max(0.0);
+		let ratio = self.ratio.value().max(1.0);
+		let target = overshoot * (1.0 - 1.0 / ratio);
+		let time = if target > self.gain_reduction {
+			self.attack_time
+		} else {
+			self.release_time
+		};
+		self.gain_reduction += (target - self.gain_reduction) * (1.0 - (-dt / time.max(dt)).exp());
+		self.shared_gain_reduction
+			.store((self.gain_reduction as f32).to_bits(), Ordering::Relaxed);
+		let gain = self.makeup_gain.value() - self.gain_reduction;
+		input * 10.0f64.powf(gain / 20.0) as f32
+	}
+}
+
+impl GainReductionReader {
+	/// How many decibels the compressor is currently turning the
+	/// signal down by, not counting the makeup gain.
+	pub fn gain_reduction(&self) -> f32 {
+		f32::from_bits(self.shared_gain_reduction.load(Ordering::Relaxed))
+	}
+}
@@ -0,0 +1,101 @@
+use kira::{mixer::effect::Effect, parameter::Parameters, CachedValue, Frame, Value};
+
+// the buffer is allocated up front (effects run on the audio
+// thread), so it's sized for the highest sample rate we expect
+const MAX_SAMPLE_RATE: f64 = 192000.0;
+
+#[derive(Debug, Copy, Clone)]
+pub struct DelaySettings {
+	/// The longest delay time (in seconds) the effect can be set to.
+	pub max_delay_time: f64,
+	/// The delay time in seconds.
+	pub delay_time: Value<f64>,
+	/// How much of the output is fed back into the delay (from 0 to 1).
+	pub feedback: Value<f64>,
+}
+
+impl DelaySettings {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn max_delay_time(self, max_delay_time: f64) -> Self {
+		Self {
+			max_delay_time,
+			..self
+		}
+	}
+
+	pub fn delay_time<V: Into<Value<f64>>>(self, delay_time: V) -> Self {
+		Self {
+			delay_time: delay_time.into(),
+			..self
+		}
+	}
+
+	pub fn feedback<V: Into<Value<f64>>>(self, feedback: V) -> Self {
+		Self {
+			feedback: feedback.into(),
+			..self
+		}
+	}
+}
+
+impl Default for DelaySettings {
+	fn default() -> Self {
+		Self {
+			max_delay_time: 2.0,
+			delay_time: 0.5.into(),
+			feedback: 0.5.into(),
+		}
+	}
+}
+
+/// A feedback delay (echo) effect.
+///
+/// The output only contains the delayed signal, so the effect
+/// is meant to be mixed back in with the dry signal using
+/// the mixer (for example, on a return track).
+#[derive(Debug, Clone)]
+pub struct Delay {
+	delay_time: CachedValue<f64>,
+	feedback: CachedValue<f64>,
+	buffer: Vec<Frame>,
+	write_position: usize,
+}
+
+impl Delay {
+	pub fn new(settings: DelaySettings) -> Self {
+		Self {
+			delay_time: CachedValue::new(settings.delay_time, 0.5),
+			feedback: CachedValue::new(settings.feedback, 0.5),
+			buffer: vec![
+				Frame::from_mono(0.0);
+				(settings.max_delay_time * MAX_SAMPLE_RATE) as usize + 2
+			],
+			write_position: 0,
+		}
+	}
+}
+
+impl Effect for Delay {
+	fn process(&mut self, dt: f64, input: Frame, parameters: &Parameters) -> Frame {
+		self.delay_time.update(parameters);
+		self.feedback.update(parameters);
+		let buffer_length = self.buffer.len();
+		// read between samples so the delay time can be changed
+		// smoothly without clicks
+		let delay_samples = (self.delay_time.value() / dt).clamp(1.0, (buffer_length - 2) as f64);
+		let read_position =
+			(self.write_position as f64 - delay_samples).rem_euclid(buffer_length as f64);
+		let index = read_position as usize;
+		let fraction = (read_position - index as f64) as f32;
+		let current = self.buffer[index % buffer_length];
+		let next = self.buffer[(index + 1) % buffer_length];
+		let output = current + (next - current) * fraction;
+		self.buffer[self.write_position] =
+			input + output * self.feedback.value().clamp(0.0, 1.0) as f32;
+		self.write_position = (self.write_position + 1) % buffer_length;
+		output
+	}
+}
@@ -0,0 +1,56 @@
+use kira::{mixer::effect::Effect, parameter::Parameters, CachedValue, Frame, Value};
+
+#[derive(Debug, Copy, Clone)]
+pub struct DistortionSettings {
+	/// How much the input is boosted before it's clipped (in
+	/// decibels).
+	pub drive: Value<f64>,
+}
+
+impl DistortionSettings {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn drive<V: Into<Value<f64>>>(self, drive: V) -> Self {
+		Self {
+			drive: drive.into(),
+		}
+	}
+}
+
+impl Default for DistortionSettings {
+	fn default() -> Self {
+		Self { drive: 12.0.into() }
+	}
+}
+
+/// A saturation effect that boosts the signal and then rounds
+/// off the peaks with a tanh curve.
+///
+/// Quiet sounds come out louder the more drive there is, so a
+/// gain effect after this one can be used to bring the level
+/// back down.
+#[derive(Debug, Clone)]
+pub struct Distortion {
+	drive: CachedValue<f64>,
+}
+
+impl Distortion {
+	pub fn new(settings: DistortionSettings) -> Self {
+		Self {
+			drive: CachedValue::new(settings.drive, 0.0),
+		}
+	}
+}
+
+impl Effect for Distortion {
+	fn process(&mut self, _dt: f64, input: Frame, parameters: &Parameters) -> Frame {
+		self.drive.update(parameters);
+		let amplitude = 10.0f64.powf(self.drive.value().max(0.0) / 20.0) as f32;
+		Frame::new(
+			(input.left * amplitude).tanh(),
+			(input.right * amplitude).tanh(),
+		)
+	}
+}
@@ -0,0 +1,52 @@
+use kira::{
+	mixer::effect::{
+		filter::{Filter, FilterSettings},
+		Effect,
+	},
+	parameter::Parameters,
+	CachedValue, Frame, Value,
+};
+
+/// Runs several filters side by side on the same input and adds
+/// up their outputs, each turned up or down by its own volume.
+///
+/// A filter's mode is fixed when it's created, so this lets one
+/// track switch between modes by crossfading the volumes. Doing
+/// it inside one effect (instead of with one track per mode)
+/// keeps the whole thing on a single track, which can then be
+/// the wet track of a [`WetDry`](crate::audio::wet_dry::WetDry).
+#[derive(Debug, Clone)]
+pub struct FilterBank {
+	filters: Vec<(Filter, CachedValue<f64>)>,
+}
+
+impl FilterBank {
+	/// Creates a bank from filter settings paired with the
+	/// volume of each filter's output (as a linear amplitude).
+	pub fn new<V: Into<Value<f64>>>(
+		filters: impl IntoIterator<Item = (FilterSettings, V)>,
+	) -> Self {
+		Self {
+			filters: filters
+				.into_iter()
+				.map(|(settings, volume)| {
+					(Filter::new(settings), CachedValue::new(volume.into(), 0.0))
+				})
+				.collect(),
+		}
+	}
+}
+
+impl Effect for FilterBank {
+	fn process(&mut self, dt: f64, input: Frame, parameters: &Parameters) -> Frame {
+		let mut output = Frame::from_mono(0.0);
+		for (filter, volume) in &mut self.filters {
+			volume.update(parameters);
+			// every filter keeps running while it's silent, so it's
+			// already settled when it gets faded in
+			let filtered = filter.process(dt, input, parameters);
+			output += filtered * volume.value().max(0.0) as f32;
+		}
+		output
+	}
+}
@@ -0,0 +1,40 @@
+use kira::{mixer::effect::Effect, parameter::Parameters, CachedValue, Frame, Value};
+
+/// How long the gain takes to catch up with its target (in
+/// seconds), which keeps fader moves from crackling.
+const SMOOTHING_TIME: f64 = 0.01;
+
+/// Turns the track it's on up or down.
+///
+/// A track's own volume is fixed when the track is created, so
+/// tracks that need a fader get one of these instead, usually
+/// with the volume tied to a parameter.
+#[derive(Debug, Clone)]
+pub struct Gain {
+	/// The volume in decibels.
+	volume: CachedValue<f64>,
+	amplitude: f64,
+}
+
+impl Gain {
+	pub fn new<V: Into<Value<f64>>>(volume: V) -> Self {
+		let volume = CachedValue::new(volume.into(), 0.0);
+		Self {
+			amplitude: decibels_to_amplitude(volume.value()),
+			volume,
+		}
+	}
+}
+
+impl Effect for Gain {
+	fn process(&mut self, dt: f64, input: Frame, parameters: &Parameters) -> Frame {
+		self.volume.update(parameters);
+		let target = decibels_to_amplitude(self.volume.value());
+		self.amplitude += (target - self.amplitude) * (dt / SMOOTHING_TIME).min(1.0);
+		input * self.amplitude as f32
+	}
+}
+
+fn decibels_to_amplitude(decibels: f64) -> f64 {
+	10.0f64.powf(decibels / 20.0)
+}
@@ -0,0 +1,7 @@
+pub mod compressor;
+pub mod delay;
+pub mod distortion;
+pub mod filter_bank;
+pub mod gain;
+pub mod reverb;
+pub mod tap;
@@ -0,0 +1,163 @@
+use kira::{mixer::effect::Effect, parameter::Parameters, CachedValue, Frame, Value};
+
+// the classic Freeverb tunings (in samples at 44.1kHz). the
+// right channel's delays are slightly longer to decorrelate
+// the two sides.
+const COMB_LENGTHS: [usize; 8] = [1116, 1188, 1277, 1356, 1422, 1491, 1557, 1617];
+const ALL_PASS_LENGTHS: [usize; 4] = [556, 441, 341, 225];
+const STEREO_SPREAD: usize = 23;
+const ALL_PASS_FEEDBACK: f32 = 0.5;
+const INPUT_GAIN: f32 = 0.015;
+
+#[derive(Debug, Copy, Clone)]
+pub struct ReverbSettings {
+	/// How long the reverb tail rings out (from 0 to 1).
+	pub room_size: Value<f64>,
+	/// How quickly high frequencies die out (from 0 to 1).
+	pub damping: Value<f64>,
+}
+
+impl ReverbSettings {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn room_size<V: Into<Value<f64>>>(self, room_size: V) -> Self {
+		Self {
+			room_size: room_size.into(),
+			..self
+		}
+	}
+
+	pub fn damping<V: Into<Value<f64>>>(self, damping: V) -> Self {
+		Self {
+			damping: damping.into(),
+			..self
+		}
+	}
+}
+
+impl Default for ReverbSettings {
+	fn default() -> Self {
+		Self {
+			room_size: 0.8.into(),
+			damping: 0.5.into(),
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+struct Comb {
+	buffer: Vec<f32>,
+	position: usize,
+	filter_state: f32,
+}
+
+impl Comb {
+	fn new(length: usize) -> Self {
+		Self {
+			buffer: vec![0.0; length],
+			position: 0,
+			filter_state: 0.0,
+		}
+	}
+
+	fn process(&mut self, input: f32, feedback: f32, damping: f32) -> f32 {
+		let output = self.buffer[self.position];
+		self.filter_state = output * (1.0 - damping) + self.filter_state * damping;
+		self.buffer[self.position] = input + self.filter_state * feedback;
+		self.position = (self.position + 1) % self.buffer.len();
+		output
+	}
+}
+
+#[derive(Debug, Clone)]
+struct AllPass {
+	buffer: Vec<f32>,
+	position: usize,
+}
+
+impl AllPass {
+	fn new(length: usize) -> Self {
+		Self {
+			buffer: vec![0.0; length],
+			position: 0,
+		}
+	}
+
+	fn process(&mut self, input: f32) -> f32 {
+		let buffered = self.buffer[self.position];
+		self.buffer[self.position] = input + buffered * ALL_PASS_FEEDBACK;
+		self.position = (self.position + 1) % self.buffer.len();
+		buffered - input
+	}
+}
+
+#[derive(Debug, Clone)]
+struct Channel {
+	combs: Vec<Comb>,
+	all_passes: Vec<AllPass>,
+}
+
+impl Channel {
+	fn new(spread: usize) -> Self {
+		Self {
+			combs: COMB_LENGTHS
+				.iter()
+				.map(|length| Comb::new(length + spread))
+				.collect(),
+			all_passes: ALL_PASS_LENGTHS
+				.iter()
+				.map(|length| AllPass::new(length + spread))
+				.collect(),
+		}
+	}
+
+	fn process(&mut self, input: f32, feedback: f32, damping: f32) -> f32 {
+		let output = self
+			.combs
+			.iter_mut()
+			.map(|comb| comb.process(input, feedback, damping))
+			.sum();
+		self.all_passes
+			.iter_mut()
+			.fold(output, |output, all_pass| all_pass.process(output))
+	}
+}
+
+/// A simple Freeverb-style reverb.
+///
+/// Like the `Delay`, the output only contains the reverberated
+/// signal, so it works best on a return track.
+#[derive(Debug, Clone)]
+pub struct Reverb {
+	room_size: CachedValue<f64>,
+	damping: CachedValue<f64>,
+	left: Channel,
+	right: Channel,
+}
+
+impl Reverb {
+	pub fn new(settings: ReverbSettings) -> Self {
+		Self {
+			room_size: CachedValue::new(settings.room_size, 0.8),
+			damping: CachedValue::new(settings.damping, 0.5),
+			left: Channel::new(0),
+			right: Channel::new(STEREO_SPREAD),
+		}
+	}
+}
+
+impl Effect for Reverb {
+	fn process(&mut self, _dt: f64, input: Frame, parameters: &Parameters) -> Frame {
+		self.room_size.update(parameters);
+		self.damping.update(parameters);
+		let feedback = 0.7 + 0.28 * self.room_size.value().clamp(0.0, 1.0) as f32;
+		let damping = 0.4 * self.damping.value().clamp(0.0, 1.0) as f32;
+		let input = (input.left + input.right) * INPUT_GAIN;
+		Frame::new(
+			self.left.process(input, feedback, damping),
+			self.right.process(input, feedback, damping),
+		)
+	}
+}
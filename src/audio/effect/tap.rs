@@ -0,0 +1,97 @@
+use std::sync::{
+	atomic::{AtomicU32, AtomicUsize, Ordering},
+	Arc,
+};
+
+use kira::{mixer::effect::Effect, parameter::Parameters, Frame};
+
+#[derive(Debug)]
+struct Shared {
+	/// The latest samples as `f32` bits, written round and round.
+	samples: Vec<AtomicU32>,
+	/// How many samples have been written in total.
+	num_written: AtomicUsize,
+	sample_rate: AtomicU32,
+}
+
+/// Copies the audio passing through a track so the UI can look
+/// at it, without changing it.
+///
+/// The audio thread can't wait on a lock, so the tap writes
+/// into a ring of atomics that the [`TapReader`] reads from
+/// whenever it likes. A read that overlaps a write can get a
+/// few samples from the next block, which is fine for drawing.
+#[derive(Debug)]
+pub struct Tap {
+	shared: Arc<Shared>,
+}
+
+/// Reads the latest audio from a [`Tap`].
+#[derive(Debug, Clone)]
+pub struct TapReader {
+	shared: Arc<Shared>,
+}
+
+impl Tap {
+	/// Creates a tap that keeps the latest `capacity` samples
+	/// (mixed down to mono), along with its reader.
+	pub fn new(capacity: usize) -> (Self, TapReader) {
+		let shared = Arc::new(Shared {
+			samples: (0..capacity.max(1)).map(|_| AtomicU32::new(0)).collect(),
+			num_written: AtomicUsize::new(0),
+			sample_rate: AtomicU32::new(0),
+		});
+		(
+			Self {
+				shared: shared.clone(),
+			},
+			TapReader { shared },
+		)
+	}
+}
+
+impl Effect for Tap {
+	fn process(&mut self, dt: f64, input: Frame, _parameters: &Parameters) -> Frame {
+		let shared = &self.shared;
+		shared
+			.sample_rate
+			.store((1.0 / dt).round() as u32, Ordering::Relaxed);
+		let index = shared.num_written.load(Ordering::Relaxed);
+		let mono = (input.left + input.right) / 2.0;
+		shared.samples[index % shared.samples.len()].store(mono.to_bits(), Ordering::Relaxed);
+		shared.num_written.store(index + 1, Ordering::Release);
+		input
+	}
+}
+
+impl TapReader {
+	/// The sample rate of the track, once the tap has heard any
+	/// audio.
+	pub fn sample_rate(&self) -> Option<u32> {
+		match self.shared.sample_rate.load(Ordering::Relaxed) {
+			0 => None,
+			sample_rate => Some(sample_rate),
+		}
+	}
+
+	/// Fills `samples` with the latest audio, oldest first. If the
+	/// tap hasn't heard (or can't hold) that much yet, the start is
+	/// left silent.
+	pub fn read_latest(&self, samples: &mut [f32]) {
+		let shared = &self.shared;
+		let capacity = shared.samples.len();
+		let num_written = shared.num_written.load(Ordering::Acquire);
+		let num_samples = samples.len();
+		let num_silent = num_samples.saturating_sub(capacity.min(num_written));
+		for (i, sample) in samples.iter_mut().enumerate() {
+			if i < num_silent {
+				*sample = 0.0;
+				continue;
+			}
+			// the last sample in the slice is the newest one
+			let age = num_samples - i;
+			let index = (num_written - age) % capacity;
+			*sample = f32::from_bits(shared.samples[index].load(Ordering::Relaxed));
+		}
+	}
+}
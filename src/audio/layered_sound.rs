@@ -0,0 +1,101 @@
+use kira::{
+	instance::{InstanceId, InstanceSettings},
+	manager::AudioManager,
+	mixer::SubTrackId,
+	parameter::{ParameterId, Tween},
+	AudioResult,
+};
+
+use super::{effect::gain::Gain, sound_container::SoundContainer};
+
+/// One part of a [`LayeredSound`].
+pub struct Layer {
+	pub container: SoundContainer,
+	track_id: SubTrackId,
+	volume_parameter_id: ParameterId,
+	/// The layer's volume (in decibels).
+	volume: f64,
+}
+
+impl Layer {
+	pub fn volume(&self) -> f64 {
+		self.volume
+	}
+}
+
+/// A one-shot sound built from several layers that always play
+/// together, like the crack, body and echo of a gunshot.
+///
+/// Each layer is a [`SoundContainer`], so every layer picks its
+/// own variation and nudges its own pitch and volume, and the
+/// combinations add up to far more variety than any one layer
+/// has. Each layer also plays on its own track with a gain
+/// effect, so the balance between layers can be changed while
+/// they ring out.
+pub struct LayeredSound {
+	layers: Vec<Layer>,
+}
+
+impl LayeredSound {
+	pub fn new(
+		audio_manager: &mut AudioManager,
+		containers: Vec<SoundContainer>,
+	) -> AudioResult<Self> {
+		let mut layers = vec![];
+		for container in containers {
+			let volume_parameter_id = audio_manager.add_parameter(0.0)?;
+			let track_id = audio_manager.add_sub_track(Default::default())?;
+			audio_manager.add_effect_to_track(
+				track_id,
+				Gain::new(volume_parameter_id),
+				Default::default(),
+			)?;
+			layers.push(Layer {
+				container,
+				track_id,
+				volume_parameter_id,
+				volume: 0.0,
+			});
+		}
+		Ok(Self { layers })
+	}
+
+	pub fn layers(&self) -> &[Layer] {
+		&self.layers
+	}
+
+	pub fn layers_mut(&mut self) -> &mut [Layer] {
+		&mut self.layers
+	}
+
+	/// Sets a layer's volume (in decibels).
+	pub fn set_layer_volume(
+		&mut self,
+		audio_manager: &mut AudioManager,
+		layer: usize,
+		volume: f64,
+	) -> AudioResult<()> {
+		let layer = &mut self.layers[layer];
+		layer.volume = volume;
+		audio_manager.set_parameter(layer.volume_parameter_id, volume, Some(Tween::linear(0.05)))
+	}
+
+	/// Plays every layer at once. The track in the settings is
+	/// replaced by each layer's own track, and the pitch and
+	/// volume by each layer's randomized ones.
+	pub fn play(
+		&mut self,
+		audio_manager: &mut AudioManager,
+		settings: InstanceSettings,
+	) -> AudioResult<Vec<InstanceId>> {
+		let mut instance_ids = vec![];
+		for layer in &mut self.layers {
+			instance_ids.push(
+				layer
+					.container
+					.play(audio_manager, settings.track(layer.track_id))?,
+			);
+		}
+		Ok(instance_ids)
+	}
+}
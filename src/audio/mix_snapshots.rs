@@ -0,0 +1,127 @@
+use kira::{
+	manager::AudioManager,
+	parameter::{ParameterId, Tween},
+	AudioResult,
+};
+
+/// A linear tween that lasts `duration` seconds. A tween with no
+/// length would divide by zero, so a duration of 0 gives `None`
+/// to set values directly.
+fn tween(duration: f64) -> Option<Tween> {
+	if duration > 0.0 {
+		Some(Tween::linear(duration))
+	} else {
+		None
+	}
+}
+
+/// A named copy of every value in a mix.
+#[derive(Debug, Clone)]
+pub struct MixSnapshot {
+	pub name: String,
+	values: Vec<f64>,
+}
+
+/// Keeps track of the values of a mix's parameters (faders,
+/// sends, and effect settings) so they can be saved as
+/// snapshots and recalled later.
+///
+/// Kira doesn't report the current value of a parameter, so
+/// every change to the mix should go through [`MixSnapshots::set`]
+/// for the snapshots to capture it.
+#[derive(Debug, Clone)]
+pub struct MixSnapshots {
+	parameter_ids: Vec<ParameterId>,
+	values: Vec<f64>,
+	snapshots: Vec<MixSnapshot>,
+}
+
+impl MixSnapshots {
+	/// Takes the parameters that make up the mix along with
+	/// the values they were created with.
+	pub fn new(parameters: &[(ParameterId, f64)]) -> Self {
+		Self {
+			parameter_ids: parameters.iter().map(|(id, _)| *id).collect(),
+			values: parameters.iter().map(|(_, value)| *value).collect(),
+			snapshots: vec![],
+		}
+	}
+
+	pub fn value(&self, index: usize) -> f64 {
+		self.values[index]
+	}
+
+	/// Sets one of the mix's parameters right away.
+	pub fn set(
+		&mut self,
+		audio_manager: &mut AudioManager,
+		index: usize,
+		value: f64,
+	) -> AudioResult<()> {
+		self.values[index] = value;
+		audio_manager.set_parameter(self.parameter_ids[index], value, None)
+	}
+
+	/// Glides one of the mix's parameters to a new value over
+	/// `duration` seconds.
+	pub fn glide(
+		&mut self,
+		audio_manager: &mut AudioManager,
+		index: usize,
+		value: f64,
+		duration: f64,
+	) -> AudioResult<()> {
+		self.values[index] = value;
+		audio_manager.set_parameter(self.parameter_ids[index], value, tween(duration))
+	}
+
+	pub fn snapshots(&self) -> &[MixSnapshot] {
+		&self.snapshots
+	}
+
+	/// Saves the current mix and returns the index of the new
+	/// snapshot.
+	pub fn capture(&mut self, name: impl Into<String>) -> usize {
+		self.snapshots.push(MixSnapshot {
+			name: name.into(),
+			values: self.values.clone(),
+		});
+		self.snapshots.len() - 1
+	}
+
+	pub fn remove(&mut self, index: usize) {
+		self.snapshots.remove(index);
+	}
+
+	/// Glides every parameter from its current value to the
+	/// snapshot's over `duration` seconds. The stored values
+	/// change straight away, so faders showing them will jump
+	/// to where the mix is heading.
+	pub fn recall(
+		&mut self,
+		audio_manager: &mut AudioManager,
+		index: usize,
+		duration: f64,
+	) -> AudioResult<()> {
+		let tween = tween(duration);
+		self.values = self.snapshots[index].values.clone();
+		for (parameter_id, value) in self.parameter_ids.iter().zip(&self.values) {
+			audio_manager.set_parameter(*parameter_id, *value, tween)?;
+		}
+		Ok(())
+	}
+
+	/// Jumps to snapshot `a` and glides to snapshot `b` over
+	/// `duration` seconds, so the same A/B morph can be heard
+	/// again no matter where the mix was left.
+	pub fn morph(
+		&mut self,
+		audio_manager: &mut AudioManager,
+		a: usize,
+		b: usize,
+		duration: f64,
+	) -> AudioResult<()> {
+		self.recall(audio_manager, a, 0.0)?;
+		self.recall(audio_manager, b, duration)
+	}
+}
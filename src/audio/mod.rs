@@ -0,0 +1,50 @@
+#[cfg(feature = "tray")]
+use kira::mixer::TrackIndex;
+use kira::{
+	manager::{AudioManager, AudioManagerSettings},
+	AudioResult,
+};
+
+pub mod decoded_sound;
+pub mod effect;
+pub mod layered_sound;
+pub mod mix_snapshots;
+#[cfg(feature = "tray")]
+pub mod mute;
+#[cfg(test)]
+pub mod offline;
+pub mod signal_generator;
+pub mod sound_bank;
+pub mod sound_cache;
+pub mod sound_container;
+pub mod spatializer;
+pub mod spectrum;
+pub mod streaming_sound;
+pub mod surround;
+pub mod synth;
+pub mod tempo_detection;
+#[cfg(feature = "debug-clock")]
+pub mod virtual_clock;
+pub mod wet_dry;
+
+/// Creates the audio manager for a screen. With the `debug-clock`
+/// feature, the audio manager runs on the virtual clock instead of
+/// playing through the audio device. With the `tray` feature, the
+/// main track is hooked up to the tray icon's mute switch.
+pub fn create_audio_manager(settings: AudioManagerSettings) -> AudioResult<AudioManager> {
+	#[cfg(feature = "debug-clock")]
+	let audio_manager = virtual_clock::create_audio_manager(settings)?;
+	#[cfg(not(feature = "debug-clock"))]
+	let audio_manager = AudioManager::new(settings)?;
+	#[cfg(feature = "tray")]
+	let audio_manager = {
+		let mut audio_manager = audio_manager;
+		audio_manager.add_effect_to_track(
+			TrackIndex::Main,
+			mute::MuteSwitch::new(),
+			Default::default(),
+		)?;
+		audio_manager
+	};
+	Ok(audio_manager)
+}
@@ -0,0 +1,49 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use kira::{mixer::effect::Effect, parameter::Parameters, Frame};
+
+/// How long (in seconds) muting and unmuting takes, which keeps
+/// the sound from clicking.
+const FADE_TIME: f64 = 0.01;
+
+static MUTED: AtomicBool = AtomicBool::new(false);
+
+/// Mutes or unmutes every audio manager at once.
+pub fn set_muted(muted: bool) {
+	MUTED.store(muted, Ordering::Relaxed);
+}
+
+pub fn is_muted() -> bool {
+	MUTED.load(Ordering::Relaxed)
+}
+
+/// Silences the track it's on while the app is muted.
+///
+/// Each screen creates its own audio manager, so rather than
+/// keeping track of all of them, every main track gets one of
+/// these, and they all check the same switch.
+#[derive(Debug, Clone)]
+pub struct MuteSwitch {
+	gain: f32,
+}
+
+impl MuteSwitch {
+	pub fn new() -> Self {
+		Self {
+			gain: if is_muted() { 0.0 } else { 1.0 },
+		}
+	}
+}
+
+impl Effect for MuteSwitch {
+	fn process(&mut self, dt: f64, input: Frame, _parameters: &Parameters) -> Frame {
+		let target = if is_muted() { 0.0 } else { 1.0 };
+		let step = (dt / FADE_TIME) as f32;
+		self.gain = if self.gain < target {
+			(self.gain + step).min(target)
+		} else {
+			(self.gain - step).max(target)
+		};
+		input * self.gain
+	}
+}
@@ -0,0 +1,172 @@
+//! Renders audio without an audio device, so the demos' audio
+//! logic can be checked by tests.
+//!
+//! A test creates an [`OfflineRenderer`], gives the audio manager
+//! to a screen, and then alternates between sending the screen
+//! messages and rendering a bit of audio. Kira's backend is only
+//! advanced by `render`, so the same test always produces the
+//! same output.
+//!
+//! The result is compared against a golden file in `tests/golden`.
+//! To record new golden files after an intended change, run the
+//! tests with the `UPDATE_GOLDEN` environment variable set.
+//!
+//! Tests can also replay sessions recorded in the app (saved in
+//! `tests/sessions`), which turns a bug report into a test: the
+//! first run records what the session does, and later runs check
+//! that it still does the same thing.
+
+use std::{fmt::Write, fs, path::PathBuf};
+
+use kira::{
+	manager::{AudioManager, AudioManagerSettings, Backend},
+	AudioResult, Frame,
+};
+
+use crate::ui::session_player::SessionPlayer;
+
+/// The sample rate Kira uses when running without an audio thread.
+pub const SAMPLE_RATE: u32 = 48000;
+
+/// How many frames of audio each value in the envelope covers.
+const ENVELOPE_BLOCK_SIZE: usize = 480;
+
+/// How far the rendered envelope can be from the golden one
+/// before the test fails. This leaves room for floating point
+/// differences between platforms.
+const ENVELOPE_TOLERANCE: f32 = 0.001;
+
+pub struct OfflineRenderer {
+	backend: Backend,
+	frames: Vec<Frame>,
+	trace: Vec<String>,
+}
+
+impl OfflineRenderer {
+	pub fn new(settings: AudioManagerSettings) -> AudioResult<(AudioManager, Self)> {
+		let (audio_manager, backend) = AudioManager::new_without_audio_thread(settings)?;
+		Ok((
+			audio_manager,
+			Self {
+				backend,
+				frames: vec![],
+				trace: vec![],
+			},
+		))
+	}
+
+	/// The time (in seconds) of the next frame to be rendered.
+	pub fn time(&self) -> f64 {
+		self.frames.len() as f64 / SAMPLE_RATE as f64
+	}
+
+	/// Renders the next `duration` seconds of audio.
+	pub fn render(&mut self, duration: f64) {
+		let num_frames = (duration * SAMPLE_RATE as f64).round() as usize;
+		for _ in 0..num_frames {
+			let frame = self.backend.process();
+			self.frames.push(frame);
+		}
+	}
+
+	/// Adds a line to the event trace, labeled with the
+	/// current time.
+	pub fn log(&mut self, event: impl AsRef<str>) {
+		let line = format!("{:.3} {}", self.time(), event.as_ref());
+		self.trace.push(line);
+	}
+
+	/// The peak level of each block of rendered audio. This is
+	/// enough to tell when sounds start and stop without storing
+	/// every sample in the golden files.
+	fn envelope(&self) -> Vec<f32> {
+		self.frames
+			.chunks(ENVELOPE_BLOCK_SIZE)
+			.map(|block| {
+				block
+					.iter()
+					.map(|frame| frame.left.abs().max(frame.right.abs()))
+					.fold(0.0, f32::max)
+			})
+			.collect()
+	}
+
+	fn to_golden(&self, include_envelope: bool) -> String {
+		let mut golden = String::new();
+		for line in &self.trace {
+			writeln!(golden, "{}", line).unwrap();
+		}
+		if include_envelope {
+			writeln!(golden, "---").unwrap();
+			for value in self.envelope() {
+				writeln!(golden, "{:.4}", value).unwrap();
+			}
+		}
+		golden
+	}
+
+	/// Checks the event trace and rendered audio against
+	/// `tests/golden/<name>.txt`.
+	pub fn assert_matches_golden(&self, name: &str) {
+		self.check_golden(name, true);
+	}
+
+	/// Checks only the event trace against `tests/golden/<name>.txt`.
+	/// This is for tests about the order things happen in, where
+	/// the audio itself isn't interesting.
+	pub fn assert_trace_matches_golden(&self, name: &str) {
+		self.check_golden(name, false);
+	}
+
+	fn check_golden(&self, name: &str, include_envelope: bool) {
+		let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+			.join("tests/golden")
+			.join(format!("{}.txt", name));
+		let actual = self.to_golden(include_envelope);
+		if std::env::var_os("UPDATE_GOLDEN").is_some() {
+			fs::create_dir_all(path.parent().unwrap()).unwrap();
+			fs::write(&path, actual).unwrap();
+			return;
+		}
+		let expected = fs::read_to_string(&path).unwrap_or_else(|error| {
+			panic!(
+				"could not read {} ({}), run with UPDATE_GOLDEN=1 to create it",
+				path.display(),
+				error
+			)
+		});
+		let (expected_trace, expected_envelope) = split_golden(&expected);
+		let (actual_trace, actual_envelope) = split_golden(&actual);
+		assert_eq!(actual_trace, expected_trace, "event trace changed");
+		assert_eq!(
+			actual_envelope.len(),
+			expected_envelope.len(),
+			"rendered length changed"
+		);
+		for (i, (actual, expected)) in actual_envelope.iter().zip(&expected_envelope).enumerate() {
+			assert!(
+				(actual - expected).abs() <= ENVELOPE_TOLERANCE,
+				"rendered audio changed at {:.2}s (expected a peak of {}, got {})",
+				(i * ENVELOPE_BLOCK_SIZE) as f64 / SAMPLE_RATE as f64,
+				expected,
+				actual
+			);
+		}
+	}
+}
+
+/// Loads the recorded session in `tests/sessions/<name>.json`.
+pub fn load_session(name: &str) -> SessionPlayer {
+	let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+		.join("tests/sessions")
+		.join(format!("{}.json", name));
+	SessionPlayer::load(&path)
+		.unwrap_or_else(|error| panic!("could not load {} ({})", path.display(), error))
+}
+
+fn split_golden(golden: &str) -> (Vec<&str>, Vec<f32>) {
+	let mut lines = golden.lines();
+	let trace = lines.by_ref().take_while(|line| *line != "---").collect();
+	let envelope = lines.map(|line| line.parse().unwrap()).collect();
+	(trace, envelope)
+}
@@ -0,0 +1,235 @@
+use std::{
+	f64::consts::PI,
+	sync::{
+		atomic::{AtomicBool, AtomicU32, AtomicU8, Ordering},
+		Arc,
+	},
+};
+
+use kira::{audio_stream::AudioStream, Frame};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// How long the sine takes to glide to a new frequency (in
+/// seconds), so sweeping the frequency doesn't click.
+const FREQUENCY_SMOOTHING_TIME: f64 = 0.02;
+/// How long starting, stopping, and switching signals or
+/// channels take to fade (in seconds), which keeps them from
+/// clicking.
+const FADE_TIME: f64 = 0.01;
+
+/// What a [`SignalGenerator`] plays.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Signal {
+	/// Equal energy at every frequency, which sounds like hiss.
+	WhiteNoise,
+	/// Equal energy in every octave, which sounds more balanced
+	/// (like rain or a waterfall).
+	PinkNoise,
+	/// A pure tone at a single frequency.
+	Sine,
+}
+
+impl Signal {
+	pub const ALL: [Self; 3] = [Self::WhiteNoise, Self::PinkNoise, Self::Sine];
+
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::WhiteNoise => "White noise",
+			Self::PinkNoise => "Pink noise",
+			Self::Sine => "Sine",
+		}
+	}
+
+	fn from_u8(value: u8) -> Self {
+		match value {
+			0 => Self::WhiteNoise,
+			1 => Self::PinkNoise,
+			_ => Self::Sine,
+		}
+	}
+}
+
+/// Which speakers a [`SignalGenerator`] plays out of.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Channels {
+	Left,
+	Both,
+	Right,
+}
+
+impl Channels {
+	pub const ALL: [Self; 3] = [Self::Left, Self::Both, Self::Right];
+
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Left => "Left",
+			Self::Both => "Both",
+			Self::Right => "Right",
+		}
+	}
+
+	fn from_u8(value: u8) -> Self {
+		match value {
+			0 => Self::Left,
+			1 => Self::Both,
+			_ => Self::Right,
+		}
+	}
+
+	fn gains(self) -> (f64, f64) {
+		match self {
+			Self::Left => (1.0, 0.0),
+			Self::Both => (1.0, 1.0),
+			Self::Right => (0.0, 1.0),
+		}
+	}
+}
+
+#[derive(Debug)]
+struct Shared {
+	playing: AtomicBool,
+	signal: AtomicU8,
+	channels: AtomicU8,
+	/// The sine's frequency in Hz, as `f32` bits.
+	frequency: AtomicU32,
+}
+
+/// Pink noise made by running white noise through a set of
+/// one-pole filters that each roll off a different range, which
+/// adds up to the -3 dB per octave slope (Paul Kellet's
+/// "economy" filter).
+#[derive(Debug, Default)]
+struct PinkFilter {
+	b0: f64,
+	b1: f64,
+	b2: f64,
+}
+
+impl PinkFilter {
+	fn process(&mut self, white: f64) -> f64 {
+		self.b0 = 0.99765 * self.b0 + white * 0.0990460;
+		self.b1 = 0.96300 * self.b1 + white * 0.2965164;
+		self.b2 = 0.57000 * self.b2 + white * 1.0526913;
+		// scaled to come out about as loud as the white noise
+		(self.b0 + self.b1 + self.b2 + white * 0.1848) * 0.25
+	}
+}
+
+/// Plays white noise, pink noise, or a sine wave at full scale
+/// whenever it's switched on.
+///
+/// Kira's sounds are fixed buffers, so a signal that runs
+/// forever and changes on request is written as an audio
+/// stream instead: kira asks it for the next frame whenever it
+/// needs one. Streams don't read parameters, so the generator
+/// is controlled through a [`SignalGeneratorHandle`] that
+/// shares a few atomics with it. The level is left to the track
+/// the stream plays on.
+#[derive(Debug)]
+pub struct SignalGenerator {
+	shared: Arc<Shared>,
+	rng: StdRng,
+	pink_filter: PinkFilter,
+	signal: Signal,
+	channels: Channels,
+	/// How much of the current signal is let through. This rests
+	/// at 0 while the generator is off, and dips to 0 and back
+	/// when the signal or channels change.
+	fade: f64,
+	frequency: f64,
+	phase: f64,
+}
+
+/// Changes what a [`SignalGenerator`] plays from outside the
+/// audio thread.
+#[derive(Debug, Clone)]
+pub struct SignalGeneratorHandle {
+	shared: Arc<Shared>,
+}
+
+impl SignalGenerator {
+	/// Creates a generator, switched off, along with its handle.
+	pub fn new(
+		signal: Signal,
+		channels: Channels,
+		frequency: f64,
+	) -> (Self, SignalGeneratorHandle) {
+		let shared = Arc::new(Shared {
+			playing: AtomicBool::new(false),
+			signal: AtomicU8::new(signal as u8),
+			channels: AtomicU8::new(channels as u8),
+			frequency: AtomicU32::new((frequency as f32).to_bits()),
+		});
+		(
+			Self {
+				shared: shared.clone(),
+				rng: StdRng::seed_from_u64(0),
+				pink_filter: PinkFilter::default(),
+				signal,
+				channels,
+				fade: 0.0,
+				frequency,
+				phase: 0.0,
+			},
+			SignalGeneratorHandle { shared },
+		)
+	}
+}
+
+impl AudioStream for SignalGenerator {
+	fn next(&mut self, dt: f64) -> Frame {
+		let signal = Signal::from_u8(self.shared.signal.load(Ordering::Relaxed));
+		let channels = Channels::from_u8(self.shared.channels.load(Ordering::Relaxed));
+		let playing = self.shared.playing.load(Ordering::Relaxed);
+		// fade out, switch over while silent, then fade back in
+		if !playing || signal != self.signal || channels != self.channels {
+			self.fade -= dt / FADE_TIME;
+			if self.fade <= 0.0 {
+				self.fade = 0.0;
+				self.signal = signal;
+				self.channels = channels;
+			}
+		} else {
+			self.fade = (self.fade + dt / FADE_TIME).min(1.0);
+		}
+		let target_frequency = f32::from_bits(self.shared.frequency.load(Ordering::Relaxed)) as f64;
+		self.frequency +=
+			(target_frequency - self.frequency) * (dt / FREQUENCY_SMOOTHING_TIME).min(1.0);
+		let white = self.rng.gen_range(-1.0, 1.0);
+		let pink = self.pink_filter.process(white);
+		let sample = match self.signal {
+			Signal::WhiteNoise => white,
+			Signal::PinkNoise => pink,
+			Signal::Sine => {
+				self.phase = (self.phase + self.frequency * dt).fract();
+				(2.0 * PI * self.phase).sin()
+			}
+		} * self.fade;
+		let (left_gain, right_gain) = self.channels.gains();
+		Frame::new((sample * left_gain) as f32, (sample * right_gain) as f32)
+	}
+}
+
+impl SignalGeneratorHandle {
+	pub fn set_playing(&self, playing: bool) {
+		self.shared.playing.store(playing, Ordering::Relaxed);
+	}
+
+	pub fn set_signal(&self, signal: Signal) {
+		self.shared.signal.store(signal as u8, Ordering::Relaxed);
+	}
+
+	pub fn set_channels(&self, channels: Channels) {
+		self.shared
+			.channels
+			.store(channels as u8, Ordering::Relaxed);
+	}
+
+	/// Sets the sine's frequency (in Hz).
+	pub fn set_frequency(&self, frequency: f64) {
+		self.shared
+			.frequency
+			.store((frequency as f32).to_bits(), Ordering::Relaxed);
+	}
+}
@@ -0,0 +1,72 @@
+use std::{
+	collections::HashMap,
+	path::Path,
+	time::{Duration, Instant},
+};
+
+use kira::{manager::AudioManager, playable::PlayableSettings, sound::SoundId, AudioResult};
+
+/// A set of sounds that's loaded and unloaded together, like
+/// all of the voice lines for one language.
+///
+/// Every bank has the same file names in a different directory,
+/// and sounds are looked up by file name (without the extension),
+/// so switching banks doesn't change the code that plays them.
+pub struct SoundBank {
+	sound_ids: HashMap<String, SoundId>,
+	load_time: Duration,
+}
+
+impl SoundBank {
+	pub fn load(
+		audio_manager: &mut AudioManager,
+		directory: &Path,
+		file_names: &[&str],
+	) -> AudioResult<Self> {
+		let start = Instant::now();
+		let mut bank = Self {
+			sound_ids: HashMap::new(),
+			load_time: Duration::default(),
+		};
+		for file_name in file_names {
+			let path = directory.join(file_name);
+			let sound_id = match audio_manager.load_sound(&path, PlayableSettings::new()) {
+				Ok(sound_id) => sound_id,
+				Err(error) => {
+					// don't leave half of a bank loaded
+					bank.unload(audio_manager)?;
+					return Err(error);
+				}
+			};
+			let key = path
+				.file_stem()
+				.map(|stem| stem.to_string_lossy().into_owned())
+				.unwrap_or_default();
+			bank.sound_ids.insert(key, sound_id);
+		}
+		bank.load_time = start.elapsed();
+		Ok(bank)
+	}
+
+	/// Removes the bank's sounds from the audio manager, which
+	/// also stops any of them that are playing.
+	pub fn unload(self, audio_manager: &mut AudioManager) -> AudioResult<()> {
+		for sound_id in self.sound_ids.values() {
+			audio_manager.remove_sound(*sound_id)?;
+		}
+		Ok(())
+	}
+
+	pub fn sound_id(&self, key: &str) -> Option<SoundId> {
+		self.sound_ids.get(key).copied()
+	}
+
+	pub fn len(&self) -> usize {
+		self.sound_ids.len()
+	}
+
+	/// How long it took to decode the bank's files.
+	pub fn load_time(&self) -> Duration {
+		self.load_time
+	}
+}
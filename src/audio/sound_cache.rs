@@ -0,0 +1,111 @@
+use std::{
+	collections::HashMap,
+	path::{Path, PathBuf},
+	sync::{Arc, Condvar, Mutex},
+	thread,
+};
+
+use kira::AudioResult;
+
+use super::decoded_sound::DecodedSound;
+
+#[derive(Debug)]
+enum Entry {
+	Loading,
+	Loaded(Arc<DecodedSound>),
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+	entries: Mutex<HashMap<PathBuf, Entry>>,
+	finished_loading: Condvar,
+}
+
+/// Keeps the demos' decoded assets around for the lifetime
+/// of the app, so entering a demo (or coming back to one)
+/// doesn't have to read and decode its sounds again.
+///
+/// The demos' assets start decoding on background threads
+/// when the app starts. Anything else is decoded the first
+/// time it's asked for.
+#[derive(Debug, Clone)]
+pub struct SoundCache {
+	assets_dir: PathBuf,
+	shared: Arc<Shared>,
+}
+
+impl SoundCache {
+	/// Creates a cache and starts decoding each of the assets
+	/// (given as paths relative to the assets folder) on its
+	/// own thread.
+	pub fn preload<'a>(assets_dir: PathBuf, assets: impl IntoIterator<Item = &'a str>) -> Self {
+		let cache = Self {
+			assets_dir,
+			shared: Default::default(),
+		};
+		for asset in assets {
+			let path = cache.assets_dir.join(asset);
+			let mut entries = cache.shared.entries.lock().unwrap();
+			if entries.contains_key(&path) {
+				continue;
+			}
+			entries.insert(path.clone(), Entry::Loading);
+			drop(entries);
+			let shared = cache.shared.clone();
+			thread::spawn(move || {
+				// if preloading fails, the screen will run into the
+				// same error when it loads the file itself and can
+				// report it then
+				let sound = DecodedSound::from_ogg_file(&path).ok().map(Arc::new);
+				Self::finish_loading(&shared, path, sound);
+			});
+		}
+		cache
+	}
+
+	fn finish_loading(shared: &Shared, path: PathBuf, sound: Option<Arc<DecodedSound>>) {
+		let mut entries = shared.entries.lock().unwrap();
+		match sound {
+			Some(sound) => {
+				entries.insert(path, Entry::Loaded(sound));
+			}
+			None => {
+				entries.remove(&path);
+			}
+		}
+		shared.finished_loading.notify_all();
+	}
+
+	/// Returns true if none of the assets (given as paths relative
+	/// to the assets folder) are still being decoded.
+	pub fn is_loaded(&self, assets: &[&str]) -> bool {
+		let entries = self.shared.entries.lock().unwrap();
+		assets.iter().all(|asset| {
+			!matches!(
+				entries.get(&self.assets_dir.join(asset)),
+				Some(Entry::Loading)
+			)
+		})
+	}
+
+	/// Gets a decoded sound from the cache. If the sound is still
+	/// being preloaded, this waits for it to finish instead of
+	/// decoding it a second time.
+	pub fn get(&self, path: &Path) -> AudioResult<Arc<DecodedSound>> {
+		let mut entries = self.shared.entries.lock().unwrap();
+		loop {
+			match entries.get(path) {
+				Some(Entry::Loaded(sound)) => return Ok(sound.clone()),
+				Some(Entry::Loading) => {
+					entries = self.shared.finished_loading.wait(entries).unwrap();
+				}
+				None => break,
+			}
+		}
+		entries.insert(path.into(), Entry::Loading);
+		drop(entries);
+		let result = DecodedSound::from_ogg_file(path).map(Arc::new);
+		Self::finish_loading(&self.shared, path.into(), result.as_ref().ok().cloned());
+		result
+	}
+}
@@ -0,0 +1,215 @@
+use kira::{
+	instance::{InstanceId, InstanceSettings},
+	manager::AudioManager,
+	sound::SoundId,
+	AudioResult,
+};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// How a [`SoundContainer`] picks the variation to play next.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Selection {
+	/// Plays the variations in order, starting over after the
+	/// last one.
+	RoundRobin,
+	/// Picks any variation, even the one that just played.
+	Random,
+	/// Picks any variation except the one that just played.
+	RandomNoRepeat,
+	/// Plays every variation once in a random order before any of
+	/// them plays again.
+	Shuffle,
+}
+
+impl Selection {
+	pub const ALL: [Self; 4] = [
+		Self::RoundRobin,
+		Self::Random,
+		Self::RandomNoRepeat,
+		Self::Shuffle,
+	];
+
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::RoundRobin => "Round robin",
+			Self::Random => "Random",
+			Self::RandomNoRepeat => "Random (no repeat)",
+			Self::Shuffle => "Shuffle",
+		}
+	}
+}
+
+/// What one trigger of a [`SoundContainer`] played.
+#[derive(Debug, Copy, Clone)]
+pub struct PlayedVariation {
+	/// Which of the container's sounds played.
+	pub index: usize,
+	pub pitch: f64,
+	pub volume: f64,
+}
+
+/// A set of variations of one sound that's played as if it were a
+/// single sound, like the middleware containers games use for
+/// footsteps and impacts.
+///
+/// Every time the container is played, it picks one of its sounds
+/// and nudges the pitch and volume of the instance by a random
+/// amount, so the same sound is never heard exactly the same way
+/// twice in a row.
+pub struct SoundContainer {
+	sound_ids: Vec<SoundId>,
+	selection: Selection,
+	/// The playback rate the variations are centered on.
+	pitch: f64,
+	/// How far the pitch can stray from the original (in
+	/// semitones, up or down).
+	pitch_variation: f64,
+	/// How much quieter than the original an instance can be (in
+	/// decibels).
+	volume_variation: f64,
+	rng: StdRng,
+	last_played: Option<PlayedVariation>,
+	/// The variations left to play before a shuffle starts over.
+	shuffled: Vec<usize>,
+}
+
+impl SoundContainer {
+	pub fn new(sound_ids: Vec<SoundId>, selection: Selection) -> Self {
+		assert!(
+			!sound_ids.is_empty(),
+			"a sound container needs at least one sound"
+		);
+		Self {
+			sound_ids,
+			selection,
+			pitch: 1.0,
+			pitch_variation: 0.0,
+			volume_variation: 0.0,
+			rng: StdRng::from_entropy(),
+			last_played: None,
+			shuffled: vec![],
+		}
+	}
+
+	pub fn num_variations(&self) -> usize {
+		self.sound_ids.len()
+	}
+
+	pub fn sound_id(&self, index: usize) -> SoundId {
+		self.sound_ids[index]
+	}
+
+	pub fn selection(&self) -> Selection {
+		self.selection
+	}
+
+	pub fn set_selection(&mut self, selection: Selection) {
+		self.selection = selection;
+		self.shuffled.clear();
+	}
+
+	pub fn set_pitch(&mut self, pitch: f64) {
+		self.pitch = pitch;
+	}
+
+	pub fn pitch_variation(&self) -> f64 {
+		self.pitch_variation
+	}
+
+	pub fn set_pitch_variation(&mut self, semitones: f64) {
+		self.pitch_variation = semitones.max(0.0);
+	}
+
+	pub fn volume_variation(&self) -> f64 {
+		self.volume_variation
+	}
+
+	pub fn set_volume_variation(&mut self, decibels: f64) {
+		self.volume_variation = decibels.max(0.0);
+	}
+
+	/// The variation that played most recently, if any.
+	pub fn last_played(&self) -> Option<PlayedVariation> {
+		self.last_played
+	}
+
+	/// Picks the variation to play next. [`play`](Self::play)
+	/// does this itself, but a screen that needs to know the pick
+	/// ahead of time (to record it, for example) can pick with
+	/// this and play the result with
+	/// [`play_variation`](Self::play_variation).
+	pub fn next_variation(&mut self) -> usize {
+		let len = self.sound_ids.len();
+		let last_index = self.last_played.map(|played| played.index);
+		match (self.selection, last_index) {
+			(Selection::RoundRobin, Some(last_index)) => (last_index + 1) % len,
+			(Selection::RoundRobin, None) => 0,
+			(Selection::RandomNoRepeat, Some(last_index)) if len > 1 => {
+				// pick from all but one and skip over the last one
+				let index = self.rng.gen_range(0, len - 1);
+				if index >= last_index {
+					index + 1
+				} else {
+					index
+				}
+			}
+			(Selection::Random, _) | (Selection::RandomNoRepeat, _) => self.rng.gen_range(0, len),
+			(Selection::Shuffle, _) => {
+				if self.shuffled.is_empty() {
+					self.shuffled = (0..len).collect();
+					self.shuffled.shuffle(&mut self.rng);
+					// the indices are taken from the end, so keep the
+					// last one from playing twice across the refill
+					if len > 1 && self.shuffled.last().copied() == last_index {
+						self.shuffled.swap(0, len - 1);
+					}
+				}
+				self.shuffled.pop().unwrap()
+			}
+		}
+	}
+
+	/// Picks a variation and plays it. Any pitch and volume in the
+	/// settings are replaced by the randomized ones.
+	pub fn play(
+		&mut self,
+		audio_manager: &mut AudioManager,
+		settings: InstanceSettings,
+	) -> AudioResult<InstanceId> {
+		let index = self.next_variation();
+		self.play_variation(audio_manager, index, settings)
+	}
+
+	/// Plays a specific variation, with the same randomized pitch
+	/// and volume as [`play`](Self::play). The next pick still
+	/// avoids repeating it.
+	pub fn play_variation(
+		&mut self,
+		audio_manager: &mut AudioManager,
+		index: usize,
+		settings: InstanceSettings,
+	) -> AudioResult<InstanceId> {
+		let semitones = if self.pitch_variation > 0.0 {
+			self.rng
+				.gen_range(-self.pitch_variation, self.pitch_variation)
+		} else {
+			0.0
+		};
+		let pitch = self.pitch * 2.0f64.powf(semitones / 12.0);
+		let decibels = if self.volume_variation > 0.0 {
+			self.rng.gen_range(0.0, self.volume_variation)
+		} else {
+			0.0
+		};
+		let volume = 10.0f64.powf(-decibels / 20.0);
+		let instance_id =
+			audio_manager.play(self.sound_ids[index], settings.pitch(pitch).volume(volume))?;
+		self.last_played = Some(PlayedVariation {
+			index,
+			pitch,
+			volume,
+		});
+		Ok(instance_id)
+	}
+}
@@ -0,0 +1,76 @@
+use crate::preferences::OutputMode;
+
+/// The distance at which sounds play at full volume. Sounds
+/// closer than this aren't made any louder.
+const REFERENCE_DISTANCE: f64 = 1.0;
+
+/// The panning and volume to play a sound with so it seems to
+/// come from a certain direction.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Spatialized {
+	/// The panning to pass to Kira (from 0 for hard left to 1
+	/// for hard right).
+	pub panning: f64,
+	pub volume: f64,
+}
+
+/// Turns positions into panning and volume according to the
+/// output mode, so every screen that places sounds in space
+/// does it the same way.
+///
+/// On speakers, sounds are panned across the full width with
+/// a constant-power (-3dB) law, since both ears hear both
+/// speakers anyway. On headphones, each ear only hears one
+/// side, so hard panning sounds unnatural and the center
+/// sounds too loud; the width is narrowed and the center is
+/// dipped further (-4.5dB).
+#[derive(Debug, Copy, Clone)]
+pub struct Spatializer {
+	width: f64,
+	/// The exponent of the panning curve: 0.5 gives -3dB at
+	/// the center, 1.0 gives -6dB.
+	law: f64,
+}
+
+impl Spatializer {
+	pub fn new(output_mode: OutputMode) -> Self {
+		match output_mode {
+			OutputMode::Speakers => Self {
+				width: 1.0,
+				law: 0.5,
+			},
+			OutputMode::Headphones => Self {
+				width: 0.7,
+				law: 0.75,
+			},
+		}
+	}
+
+	/// Pans a sound from -1 (left) to 1 (right).
+	pub fn pan(&self, pan: f64) -> Spatialized {
+		let position = (pan.clamp(-1.0, 1.0) * self.width + 1.0) / 2.0;
+		let left = (1.0 - position).powf(self.law);
+		let right = position.powf(self.law);
+		// Kira always pans with a -3dB law, so the balance between
+		// the sides goes in the panning and the rest of the
+		// curve goes in the volume
+		let power = left * left + right * right;
+		Spatialized {
+			panning: right * right / power,
+			volume: power.sqrt(),
+		}
+	}
+
+	/// Places a sound relative to the listener, who faces
+	/// towards negative y. Sounds get quieter with distance
+	/// and are panned by their direction.
+	pub fn spatialize(&self, x: f64, y: f64) -> Spatialized {
+		let distance = (x * x + y * y).sqrt();
+		let pan = if distance > 0.0 { x / distance } else { 0.0 };
+		let spatialized = self.pan(pan);
+		Spatialized {
+			volume: spatialized.volume * REFERENCE_DISTANCE / distance.max(REFERENCE_DISTANCE),
+			..spatialized
+		}
+	}
+}
@@ -0,0 +1,110 @@
+use std::f32::consts::PI;
+
+/// The quietest level the bands show (in decibels below full
+/// scale). Anything quieter reads as 0.
+const FLOOR: f32 = -70.0;
+
+/// Measures how loud each frequency is in a block of samples.
+///
+/// The block's length has to be a power of two. It's shaped with
+/// a Hann window first so the edges of the block don't smear
+/// energy across the whole spectrum. The result has one
+/// magnitude for each frequency bin up to the Nyquist frequency,
+/// scaled so a full-scale sine reads as about 1.
+pub fn magnitudes(samples: &[f32]) -> Vec<f32> {
+	let len = samples.len();
+	assert!(
+		len.is_power_of_two(),
+		"the block length has to be a power of two"
+	);
+	let mut real: Vec<f32> = samples
+		.iter()
+		.enumerate()
+		.map(|(i, sample)| sample * (0.5 - 0.5 * (2.0 * PI * i as f32 / len as f32).cos()))
+		.collect();
+	let mut imaginary = vec![0.0; len];
+	fft(&mut real, &mut imaginary);
+	// the window halves the level, and a real sine's energy is
+	// split between the positive and negative frequencies
+	let scale = 4.0 / len as f32;
+	real.iter()
+		.zip(&imaginary)
+		.take(len / 2)
+		.map(|(re, im)| (re * re + im * im).sqrt() * scale)
+		.collect()
+}
+
+/// An in-place radix-2 fast Fourier transform.
+fn fft(real: &mut [f32], imaginary: &mut [f32]) {
+	let len = real.len();
+	// put the samples in bit-reversed order so each pass can
+	// combine neighbors
+	let mut j = 0;
+	for i in 1..len {
+		let mut bit = len >> 1;
+		while j & bit != 0 {
+			j ^= bit;
+			bit >>= 1;
+		}
+		j |= bit;
+		if i < j {
+			real.swap(i, j);
+			imaginary.swap(i, j);
+		}
+	}
+	let mut size = 2;
+	while size <= len {
+		let angle = -2.0 * PI / size as f32;
+		for start in (0..len).step_by(size) {
+			for k in 0..size / 2 {
+				let (sin, cos) = (angle * k as f32).sin_cos();
+				let even = start + k;
+				let odd = even + size / 2;
+				let odd_re = real[odd] * cos - imaginary[odd] * sin;
+				let odd_im = real[odd] * sin + imaginary[odd] * cos;
+				real[odd] = real[even] - odd_re;
+				imaginary[odd] = imaginary[even] - odd_im;
+				real[even] += odd_re;
+				imaginary[even] += odd_im;
+			}
+		}
+		size *= 2;
+	}
+}
+
+/// Groups the bins from [`magnitudes`] into bands spaced evenly
+/// in pitch between two frequencies, the way ears hear them,
+/// and scales each band's level in decibels to the range 0
+/// to 1.
+///
+/// Each band takes the loudest bin it covers. The lowest bands
+/// can be narrower than one bin, in which case they use the
+/// nearest one.
+pub fn bands(
+	magnitudes: &[f32],
+	sample_rate: u32,
+	num_bands: usize,
+	min_frequency: f32,
+	max_frequency: f32,
+) -> Vec<f32> {
+	if magnitudes.is_empty() {
+		return vec![0.0; num_bands];
+	}
+	let bin_width = sample_rate as f32 / 2.0 / magnitudes.len() as f32;
+	let ratio = max_frequency / min_frequency;
+	let edge = |band: usize| min_frequency * ratio.powf(band as f32 / num_bands as f32);
+	(0..num_bands)
+		.map(|band| {
+			let first_bin = ((edge(band) / bin_width) as usize).min(magnitudes.len() - 1);
+			let last_bin = ((edge(band + 1) / bin_width) as usize)
+				.max(first_bin)
+				.min(magnitudes.len() - 1);
+			let magnitude = magnitudes[first_bin..=last_bin]
+				.iter()
+				.copied()
+				.fold(0.0, f32::max);
+			let decibels = 20.0 * magnitude.max(1e-9).log10();
+			(1.0 - decibels / FLOOR).clamp(0.0, 1.0)
+		})
+		.collect()
+}
@@ -0,0 +1,111 @@
+use std::{
+	fs::File,
+	path::Path,
+	sync::mpsc::{sync_channel, Receiver},
+	thread,
+};
+
+use kira::{audio_stream::AudioStream, AudioResult, Frame};
+use lewton::inside_ogg::OggStreamReader;
+
+use super::decoded_sound::packet_to_frames;
+
+/// How many packets the decoding thread is allowed to get
+/// ahead of playback.
+const BUFFERED_PACKETS: usize = 16;
+/// The most frames a Vorbis packet can decode to (half of the
+/// largest block size the format allows).
+const MAX_PACKET_FRAMES: usize = 4096;
+
+/// Plays an OGG file on loop, decoding it a little bit at a time
+/// on a background thread instead of loading the whole file into
+/// memory first.
+///
+/// This is worthwhile for long pieces of music, which take a while
+/// to decode and a lot of memory to keep around, but it means the
+/// audio can't be played with the usual instance controls.
+#[derive(Debug)]
+pub struct StreamingSound {
+	sample_rate: u32,
+	packets: Receiver<Vec<Frame>>,
+	current_packet: Vec<Frame>,
+	position: usize,
+	fractional_position: f64,
+}
+
+impl StreamingSound {
+	/// The most audio (in frames) that's kept in memory at once:
+	/// the packets waiting to be played, plus the one playing.
+	pub const MAX_BUFFERED_FRAMES: usize = (BUFFERED_PACKETS + 1) * MAX_PACKET_FRAMES;
+
+	/// Opens the file and starts decoding it. Only the headers
+	/// are read before this returns.
+	pub fn new<P: AsRef<Path>>(path: P) -> AudioResult<Self> {
+		let path = path.as_ref().to_path_buf();
+		let reader = OggStreamReader::new(File::open(&path)?)?;
+		let sample_rate = reader.ident_hdr.audio_sample_rate;
+		let (packet_sender, packets) = sync_channel(BUFFERED_PACKETS);
+		thread::spawn(move || {
+			let mut reader = reader;
+			loop {
+				let packet = match reader.read_dec_packet_generic::<Vec<Vec<f32>>>() {
+					Ok(Some(packet)) => packet,
+					// start over from the beginning of the file
+					Ok(None) => match Self::reopen(&path) {
+						Ok(new_reader) => {
+							reader = new_reader;
+							continue;
+						}
+						Err(_) => break,
+					},
+					Err(_) => break,
+				};
+				let frames = match packet_to_frames(packet) {
+					Ok(frames) => frames,
+					Err(_) => break,
+				};
+				// sending fails once the stream has been removed
+				// from the audio manager, which is our cue to stop
+				if packet_sender.send(frames).is_err() {
+					break;
+				}
+			}
+		});
+		Ok(Self {
+			sample_rate,
+			packets,
+			current_packet: vec![],
+			position: 0,
+			fractional_position: 0.0,
+		})
+	}
+
+	fn reopen(path: &Path) -> AudioResult<OggStreamReader<File>> {
+		Ok(OggStreamReader::new(File::open(path)?)?)
+	}
+}
+
+impl AudioStream for StreamingSound {
+	fn next(&mut self, dt: f64) -> Frame {
+		self.fractional_position += dt * self.sample_rate as f64;
+		while self.fractional_position >= 1.0 {
+			self.fractional_position -= 1.0;
+			self.position += 1;
+		}
+		while self.position >= self.current_packet.len() {
+			match self.packets.try_recv() {
+				Ok(packet) => {
+					self.position -= self.current_packet.len();
+					self.current_packet = packet;
+				}
+				// if the decoder falls behind, output silence until
+				// it catches up
+				Err(_) => {
+					self.position = self.current_packet.len();
+					return Frame::from_mono(0.0);
+				}
+			}
+		}
+		self.current_packet[self.position]
+	}
+}
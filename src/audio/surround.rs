@@ -0,0 +1,280 @@
+use std::error::Error;
+
+use kira::{
+	instance::{InstanceId, InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::Tween,
+	sound::{Sound, SoundId},
+	AudioResult,
+};
+
+use super::spatializer::{Spatialized, Spatializer};
+use crate::preferences::OutputMode;
+
+/// The speakers sounds can be panned between.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+// the surround layouts are only picked when there's a
+// multi-channel stream to play them on
+#[cfg_attr(
+	not(all(feature = "surround", not(feature = "debug-clock"))),
+	allow(dead_code)
+)]
+pub enum SpeakerLayout {
+	Stereo,
+	Quad,
+	/// 5.1 (or 7.1) with the center and LFE channels left silent.
+	FivePointOne,
+}
+
+impl SpeakerLayout {
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Stereo => "Stereo",
+			Self::Quad => "Quad",
+			Self::FivePointOne => "5.1",
+		}
+	}
+
+	/// The angles of the speakers (in degrees clockwise from
+	/// straight ahead) and their names.
+	pub fn speakers(self) -> &'static [(f32, &'static str)] {
+		match self {
+			Self::Stereo => &[(-30.0, "L"), (30.0, "R")],
+			Self::Quad => &[(-45.0, "FL"), (45.0, "FR"), (-135.0, "RL"), (135.0, "RR")],
+			Self::FivePointOne => &[(-30.0, "L"), (30.0, "R"), (-110.0, "SL"), (110.0, "SR")],
+		}
+	}
+
+	/// Picks a layout for a device with the given number of
+	/// channels and the first channel the rear speakers use.
+	#[cfg(all(feature = "surround", not(feature = "debug-clock")))]
+	fn from_channels(channels: u16) -> Option<(Self, usize)> {
+		match channels {
+			4 => Some((Self::Quad, 2)),
+			// the surround channels come after the center and LFE
+			6..=8 => Some((Self::FivePointOne, 4)),
+			_ => None,
+		}
+	}
+}
+
+/// A sound that's been added to every audio manager of a
+/// [`SurroundOutput`].
+#[derive(Debug, Copy, Clone)]
+pub struct SurroundSound {
+	front: SoundId,
+	rear: Option<SoundId>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct SurroundInstance {
+	front: InstanceId,
+	rear: Option<InstanceId>,
+}
+
+/// Plays sounds from positions around the listener on as many
+/// speakers as the output device has.
+///
+/// Kira only ever outputs two channels, so with the `surround`
+/// feature, a pair of audio managers are driven from one
+/// multi-channel stream: one for the front speakers and one for
+/// the rear speakers. When there's no device with enough channels
+/// (or the feature is off), sounds are panned in stereo with a
+/// [`Spatializer`] instead.
+pub struct SurroundOutput {
+	layout: SpeakerLayout,
+	/// Why the output fell back to stereo, if it did.
+	fallback_reason: Option<String>,
+	spatializer: Spatializer,
+	front: AudioManager,
+	rear: Option<AudioManager>,
+	#[cfg(all(feature = "surround", not(feature = "debug-clock")))]
+	_stream: Option<cpal::Stream>,
+}
+
+impl SurroundOutput {
+	/// Opens the output. `output_mode` is only used for the
+	/// stereo fallback.
+	pub fn new(
+		audio_manager_settings: AudioManagerSettings,
+		output_mode: OutputMode,
+	) -> Result<Self, Box<dyn Error>> {
+		#[cfg(all(feature = "surround", not(feature = "debug-clock")))]
+		let fallback_reason = match Self::new_surround(audio_manager_settings.clone()) {
+			Ok(output) => return Ok(output),
+			Err(reason) => reason,
+		};
+		#[cfg(not(feature = "surround"))]
+		let fallback_reason = "the app was built without the surround feature".to_string();
+		#[cfg(all(feature = "surround", feature = "debug-clock"))]
+		let fallback_reason = "the virtual clock only drives stereo audio managers".to_string();
+		Ok(Self {
+			layout: SpeakerLayout::Stereo,
+			fallback_reason: Some(fallback_reason),
+			spatializer: Spatializer::new(output_mode),
+			front: super::create_audio_manager(audio_manager_settings)?,
+			rear: None,
+			#[cfg(all(feature = "surround", not(feature = "debug-clock")))]
+			_stream: None,
+		})
+	}
+
+	/// Opens the default output device with four or more channels.
+	/// Kira's backends always run at 48kHz when they're driven
+	/// manually, so the device has to support that too.
+	#[cfg(all(feature = "surround", not(feature = "debug-clock")))]
+	fn new_surround(audio_manager_settings: AudioManagerSettings) -> Result<Self, String> {
+		use cpal::{
+			traits::{DeviceTrait, HostTrait, StreamTrait},
+			SampleFormat, SampleRate,
+		};
+
+		const SAMPLE_RATE: SampleRate = SampleRate(48000);
+
+		let device = cpal::default_host()
+			.default_output_device()
+			.ok_or_else(|| "there's no output device".to_string())?;
+		let supported_config = device
+			.supported_output_configs()
+			.map_err(|error| error.to_string())?
+			.filter(|config| {
+				config.sample_format() == SampleFormat::F32
+					&& config.min_sample_rate() <= SAMPLE_RATE
+					&& config.max_sample_rate() >= SAMPLE_RATE
+					&& SpeakerLayout::from_channels(config.channels()).is_some()
+			})
+			.min_by_key(|config| config.channels())
+			.ok_or_else(|| {
+				"the output device doesn't have 4, 6, or 8 channels at 48kHz".to_string()
+			})?;
+		let config = supported_config.with_sample_rate(SAMPLE_RATE).config();
+		let channels = config.channels as usize;
+		let (layout, rear_channel) = SpeakerLayout::from_channels(config.channels).unwrap();
+		let (front, mut front_backend) =
+			AudioManager::new_without_audio_thread(audio_manager_settings.clone())
+				.map_err(|error| error.to_string())?;
+		let (rear, mut rear_backend) =
+			AudioManager::new_without_audio_thread(audio_manager_settings)
+				.map_err(|error| error.to_string())?;
+		let stream = device
+			.build_output_stream(
+				&config,
+				move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+					// both backends are processed in the same callback,
+					// so the two halves of each sound stay in sync
+					for frame in data.chunks_exact_mut(channels) {
+						for sample in frame.iter_mut() {
+							*sample = 0.0;
+						}
+						let front = front_backend.process();
+						let rear = rear_backend.process();
+						frame[0] = front.left;
+						frame[1] = front.right;
+						frame[rear_channel] = rear.left;
+						frame[rear_channel + 1] = rear.right;
+					}
+				},
+				|_| {},
+			)
+			.map_err(|error| error.to_string())?;
+		stream.play().map_err(|error| error.to_string())?;
+		Ok(Self {
+			layout,
+			fallback_reason: None,
+			spatializer: Spatializer::new(OutputMode::Speakers),
+			front,
+			rear: Some(rear),
+			_stream: Some(stream),
+		})
+	}
+
+	pub fn layout(&self) -> SpeakerLayout {
+		self.layout
+	}
+
+	pub fn fallback_reason(&self) -> Option<&str> {
+		self.fallback_reason.as_deref()
+	}
+
+	pub fn add_sound(&mut self, sound: Sound) -> AudioResult<SurroundSound> {
+		let rear = match &mut self.rear {
+			Some(rear) => Some(rear.add_sound(sound.clone())?),
+			None => None,
+		};
+		Ok(SurroundSound {
+			front: self.front.add_sound(sound)?,
+			rear,
+		})
+	}
+
+	/// Gets the panning and volume for the front and rear speakers
+	/// for a sound at a position relative to the listener (who
+	/// faces towards negative y).
+	fn speaker_settings(&self, x: f64, y: f64) -> (Spatialized, Spatialized) {
+		let spatialized = self.spatializer.spatialize(x, y);
+		if self.rear.is_none() {
+			return (spatialized, spatialized);
+		}
+		let distance = (x * x + y * y).sqrt();
+		// how far towards the back the sound is, from 0 to 1
+		let back = if distance > 0.0 {
+			(y / distance + 1.0) / 2.0
+		} else {
+			0.5
+		};
+		(
+			Spatialized {
+				volume: spatialized.volume * (1.0 - back).sqrt(),
+				..spatialized
+			},
+			Spatialized {
+				volume: spatialized.volume * back.sqrt(),
+				..spatialized
+			},
+		)
+	}
+
+	pub fn play(&mut self, sound: SurroundSound, x: f64, y: f64) -> AudioResult<SurroundInstance> {
+		let (front_settings, rear_settings) = self.speaker_settings(x, y);
+		let front = self.front.play(
+			sound.front,
+			InstanceSettings::new()
+				.panning(front_settings.panning)
+				.volume(front_settings.volume),
+		)?;
+		let rear = match (&mut self.rear, sound.rear) {
+			(Some(rear), Some(sound_id)) => Some(
+				rear.play(
+					sound_id,
+					InstanceSettings::new()
+						.panning(rear_settings.panning)
+						.volume(rear_settings.volume),
+				)?,
+			),
+			_ => None,
+		};
+		Ok(SurroundInstance { front, rear })
+	}
+
+	pub fn set_position(&mut self, instance: SurroundInstance, x: f64, y: f64) -> AudioResult<()> {
+		let (front_settings, rear_settings) = self.speaker_settings(x, y);
+		self.front
+			.set_instance_panning(instance.front, front_settings.panning)?;
+		self.front
+			.set_instance_volume(instance.front, front_settings.volume)?;
+		if let (Some(rear), Some(instance_id)) = (&mut self.rear, instance.rear) {
+			rear.set_instance_panning(instance_id, rear_settings.panning)?;
+			rear.set_instance_volume(instance_id, rear_settings.volume)?;
+		}
+		Ok(())
+	}
+
+	pub fn stop(&mut self, instance: SurroundInstance) -> AudioResult<()> {
+		let settings = StopInstanceSettings::new().fade_tween(Tween::linear(0.1));
+		self.front.stop_instance(instance.front, settings)?;
+		if let (Some(rear), Some(instance_id)) = (&mut self.rear, instance.rear) {
+			rear.stop_instance(instance_id, settings)?;
+		}
+		Ok(())
+	}
+}
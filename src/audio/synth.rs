@@ -0,0 +1,355 @@
+use std::f64::consts::PI;
+
+use kira::{playable::PlayableSettings, sound::Sound, Frame};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+const SAMPLE_RATE: u32 = 44100;
+/// How many harmonics square and saw waves are built from.
+/// Adding up sines keeps the waves free of aliasing, and
+/// stopping at 20 leaves room to pitch a middle C tone up two
+/// octaves before the top harmonic passes the Nyquist frequency.
+const NUM_HARMONICS: usize = 20;
+/// How many frames the noise beds fade over where they loop.
+const LOOP_CROSSFADE_FRAMES: usize = 4410;
+
+/// The basic shapes [`tone`] can generate.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WaveShape {
+	Sine,
+	Square,
+	Saw,
+}
+
+impl WaveShape {
+	pub const ALL: [Self; 3] = [Self::Sine, Self::Square, Self::Saw];
+
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Sine => "Sine",
+			Self::Square => "Square",
+			Self::Saw => "Saw",
+		}
+	}
+
+	/// How loud each harmonic is (the first one is the base
+	/// frequency).
+	fn harmonic_amplitude(self, harmonic: usize) -> f64 {
+		match self {
+			Self::Sine if harmonic == 1 => 1.0,
+			Self::Sine => 0.0,
+			Self::Square if harmonic % 2 == 1 => 1.0 / harmonic as f64,
+			Self::Square => 0.0,
+			Self::Saw => 1.0 / harmonic as f64,
+		}
+	}
+}
+
+/// Generates a short sine tone with an exponential decay,
+/// for demos that need a simple sound effect.
+pub fn blip(frequency: f64, duration: f64, settings: PlayableSettings) -> Sound {
+	let num_frames = (duration * SAMPLE_RATE as f64) as usize;
+	let frames = (0..num_frames)
+		.map(|i| {
+			let time = i as f64 / SAMPLE_RATE as f64;
+			let envelope = (-time * 5.0 / duration).exp();
+			Frame::from_mono(((2.0 * PI * frequency * time).sin() * envelope * 0.5) as f32)
+		})
+		.collect();
+	Sound::from_frames(SAMPLE_RATE, frames, settings)
+}
+
+/// Generates one second of an engine running at a steady speed:
+/// a train of decaying pulses, one per cylinder firing, each a
+/// little louder or quieter than the last. Roughness (0 to 1)
+/// makes the firings more uneven and adds grit. Like [`hum`], the
+/// firing rate is rounded to a whole number per second so the
+/// sound loops without a click.
+pub fn engine(firing_rate: f64, roughness: f64, settings: PlayableSettings) -> Sound {
+	let firing_rate = firing_rate.round().max(1.0);
+	let mut rng = StdRng::seed_from_u64(0);
+	let firing_volumes: Vec<f64> = (0..firing_rate as usize)
+		.map(|_| 1.0 - 0.5 * roughness * rng.gen::<f64>())
+		.collect();
+	let frames = (0..SAMPLE_RATE)
+		.map(|i| {
+			let time = i as f64 / SAMPLE_RATE as f64;
+			let cycles = time * firing_rate;
+			let firing = cycles as usize % firing_volumes.len();
+			// every harmonic is back at zero at the end of a cycle,
+			// so the pulses join up without a click
+			let phase = cycles.fract();
+			let pulse = (1..=6)
+				.map(|harmonic| {
+					let harmonic = harmonic as f64;
+					(2.0 * PI * harmonic * phase).sin() / harmonic
+				})
+				.sum::<f64>()
+				* (-phase * 4.0).exp();
+			let grit = rng.gen_range(-1.0, 1.0) * roughness * (-phase * 8.0).exp();
+			Frame::from_mono(((pulse + grit * 0.3) * firing_volumes[firing] * 0.35) as f32)
+		})
+		.collect();
+	Sound::from_frames(SAMPLE_RATE, frames, settings)
+}
+
+/// Generates one beat of a resting heart: a low thump for the
+/// "lub" and a slightly higher, quieter one for the "dub", with
+/// silence after them to fill out a second. Looping it gives a
+/// heartbeat at 60 beats per minute.
+pub fn heartbeat(settings: PlayableSettings) -> Sound {
+	// (start time, frequency, volume) of each thump
+	let thumps = [(0.0, 50.0, 1.0), (0.28, 65.0, 0.7)];
+	let frames = (0..SAMPLE_RATE)
+		.map(|i| {
+			let time = i as f64 / SAMPLE_RATE as f64;
+			let value = thumps
+				.iter()
+				.filter(|(start, _, _)| time >= *start)
+				.map(|(start, frequency, volume)| {
+					let time = time - start;
+					(2.0 * PI * frequency * time).sin() * (-time * 25.0).exp() * volume
+				})
+				.sum::<f64>();
+			Frame::from_mono((value * 0.9) as f32)
+		})
+		.collect();
+	Sound::from_frames(SAMPLE_RATE, frames, settings)
+}
+
+/// Generates one second of a steady tone with a few harmonics,
+/// like an engine or a siren. The frequency is rounded to a whole
+/// number of cycles per second so the sound loops without a click.
+pub fn hum(frequency: f64, settings: PlayableSettings) -> Sound {
+	let frequency = frequency.round();
+	let frames = (0..SAMPLE_RATE)
+		.map(|i| {
+			let time = i as f64 / SAMPLE_RATE as f64;
+			let value = (1..=4)
+				.map(|harmonic| {
+					let harmonic = harmonic as f64;
+					(2.0 * PI * frequency * harmonic * time).sin() / harmonic
+				})
+				.sum::<f64>();
+			Frame::from_mono((value * 0.25) as f32)
+		})
+		.collect();
+	Sound::from_frames(SAMPLE_RATE, frames, settings)
+}
+
+/// Generates a drum hit: a sine tone that quickly drops in pitch
+/// from twice the given frequency. Low frequencies sound like a
+/// kick drum, higher ones like toms.
+pub fn kick(frequency: f64, duration: f64, settings: PlayableSettings) -> Sound {
+	let num_frames = (duration * SAMPLE_RATE as f64) as usize;
+	let mut phase = 0.0;
+	let frames = (0..num_frames)
+		.map(|i| {
+			let time = i as f64 / SAMPLE_RATE as f64;
+			let pitch_envelope = 1.0 + (-time * 30.0).exp();
+			phase += 2.0 * PI * frequency * pitch_envelope / SAMPLE_RATE as f64;
+			let envelope = (-time * 5.0 / duration).exp();
+			Frame::from_mono((phase.sin() * envelope * 0.8) as f32)
+		})
+		.collect();
+	Sound::from_frames(SAMPLE_RATE, frames, settings)
+}
+
+/// Generates a steady bed of white noise that loops without a
+/// seam, for shaping into other sounds with effects. Each
+/// channel gets its own noise, so the bed sounds wide.
+pub fn noise(duration: f64, settings: PlayableSettings) -> Sound {
+	let num_frames = (duration * SAMPLE_RATE as f64) as usize;
+	let channel = |seed| {
+		let mut rng = StdRng::seed_from_u64(seed);
+		(0..num_frames + LOOP_CROSSFADE_FRAMES)
+			.map(|_| rng.gen_range(-0.5, 0.5) as f32)
+			.collect::<Vec<_>>()
+	};
+	let frames = channel(0)
+		.into_iter()
+		.zip(channel(1))
+		.map(|(left, right)| Frame::new(left, right))
+		.collect();
+	Sound::from_frames(SAMPLE_RATE, seamless_loop(frames), settings)
+}
+
+/// Generates a burst of decaying noise, like a hi-hat or a
+/// shaker. Brightness (from 0 to 1) fades from plain white noise
+/// to only the highest frequencies. The noise is seeded, so the
+/// same settings always give the same sound.
+pub fn noise_hit(duration: f64, brightness: f64, settings: PlayableSettings) -> Sound {
+	let num_frames = (duration * SAMPLE_RATE as f64) as usize;
+	let mut rng = StdRng::seed_from_u64(0);
+	let mut previous = 0.0;
+	let frames = (0..num_frames)
+		.map(|i| {
+			let time = i as f64 / SAMPLE_RATE as f64;
+			let noise = rng.gen_range(-1.0, 1.0);
+			// the difference between neighboring samples cuts
+			// out the low end
+			let high = (noise - previous) / 2.0;
+			previous = noise;
+			let envelope = (-time * 5.0 / duration).exp();
+			let value = noise + (high - noise) * brightness;
+			Frame::from_mono((value * envelope * 0.4) as f32)
+		})
+		.collect();
+	Sound::from_frames(SAMPLE_RATE, frames, settings)
+}
+
+/// Generates a bed of rain that loops without a seam: a
+/// bright hiss with random drops landing on top. Each channel
+/// gets its own drops, so the rain sounds wide.
+pub fn rain(duration: f64, settings: PlayableSettings) -> Sound {
+	let num_frames = (duration * SAMPLE_RATE as f64) as usize;
+	let channel = |seed| {
+		let mut rng = StdRng::seed_from_u64(seed);
+		let mut previous = 0.0;
+		let mut drop = 0.0;
+		(0..num_frames + LOOP_CROSSFADE_FRAMES)
+			.map(|_| {
+				let noise = rng.gen_range(-1.0, 1.0);
+				let high = (noise - previous) / 2.0;
+				previous = noise;
+				// about 35 drops a second
+				if rng.gen_range(0.0, 1.0) < 0.0008 {
+					drop = rng.gen_range(0.3, 1.0);
+				}
+				drop *= 0.995;
+				(noise * 0.05 + high * 0.15 + high * drop * 0.6) as f32
+			})
+			.collect::<Vec<_>>()
+	};
+	let frames = channel(0)
+		.into_iter()
+		.zip(channel(1))
+		.map(|(left, right)| Frame::new(left, right))
+		.collect();
+	Sound::from_frames(SAMPLE_RATE, seamless_loop(frames), settings)
+}
+
+/// Generates a low, endless rumble that loops without a seam,
+/// like a storm far away. The rumble rises and falls twice over
+/// the length of the sound.
+pub fn rumble(duration: f64, settings: PlayableSettings) -> Sound {
+	let num_frames = (duration * SAMPLE_RATE as f64) as usize;
+	let mut rng = StdRng::seed_from_u64(0);
+	let mut low = 0.0;
+	let frames = (0..num_frames + LOOP_CROSSFADE_FRAMES)
+		.map(|i| {
+			let time = i as f64 / SAMPLE_RATE as f64;
+			let swell = 0.5 - 0.5 * (2.0 * PI * 2.0 * time / duration).cos();
+			low += (rng.gen_range(-1.0, 1.0) - low) * 0.003;
+			Frame::from_mono((low * (0.4 + 0.6 * swell) * 12.0) as f32)
+		})
+		.collect();
+	Sound::from_frames(SAMPLE_RATE, seamless_loop(frames), settings)
+}
+
+/// Generates a snare-like hit: a short tone for the drum head
+/// with noise on top for the snares.
+pub fn snare(frequency: f64, duration: f64, settings: PlayableSettings) -> Sound {
+	let num_frames = (duration * SAMPLE_RATE as f64) as usize;
+	let mut rng = StdRng::seed_from_u64(0);
+	let frames = (0..num_frames)
+		.map(|i| {
+			let time = i as f64 / SAMPLE_RATE as f64;
+			let tone = (2.0 * PI * frequency * time).sin() * (-time * 30.0).exp();
+			let noise = rng.gen_range(-1.0, 1.0) * (-time * 5.0 / duration).exp();
+			Frame::from_mono(((tone * 0.5 + noise * 0.35) * 0.8) as f32)
+		})
+		.collect();
+	Sound::from_frames(SAMPLE_RATE, frames, settings)
+}
+
+/// Generates a thunder clap: a sharp crack followed by a low
+/// rumble that rolls a few times as it dies away. Different
+/// seeds give different claps.
+pub fn thunder(duration: f64, seed: u64, settings: PlayableSettings) -> Sound {
+	let num_frames = (duration * SAMPLE_RATE as f64) as usize;
+	let mut rng = StdRng::seed_from_u64(seed);
+	let roll_rate = rng.gen_range(0.8, 2.0);
+	let roll_phase = rng.gen_range(0.0, 2.0 * PI);
+	let mut previous = 0.0;
+	let mut low = 0.0;
+	let frames = (0..num_frames)
+		.map(|i| {
+			let time = i as f64 / SAMPLE_RATE as f64;
+			let noise = rng.gen_range(-1.0, 1.0);
+			let high = (noise - previous) / 2.0;
+			previous = noise;
+			low += (noise - low) * 0.01;
+			let crack = high * (-time * 30.0).exp();
+			let attack = (time / 0.05).min(1.0);
+			let roll = 0.6 + 0.4 * (2.0 * PI * roll_rate * time + roll_phase).sin();
+			let rumble = low * 10.0 * attack * roll * (-time * 4.0 / duration).exp();
+			Frame::from_mono(((crack * 0.5 + rumble) * 0.6) as f32)
+		})
+		.collect();
+	Sound::from_frames(SAMPLE_RATE, frames, settings)
+}
+
+/// Generates one second of a steady tone with the given shape.
+/// Like [`hum`], the frequency is rounded to a whole number of
+/// cycles per second so the sound loops without a click.
+pub fn tone(shape: WaveShape, frequency: f64, settings: PlayableSettings) -> Sound {
+	let frequency = frequency.round();
+	let harmonics: Vec<(f64, f64)> = (1..=NUM_HARMONICS)
+		.map(|harmonic| (harmonic as f64, shape.harmonic_amplitude(harmonic)))
+		.filter(|(_, amplitude)| *amplitude > 0.0)
+		.collect();
+	let frames = (0..SAMPLE_RATE)
+		.map(|i| {
+			let time = i as f64 / SAMPLE_RATE as f64;
+			let value = harmonics
+				.iter()
+				.map(|(harmonic, amplitude)| {
+					(2.0 * PI * frequency * harmonic * time).sin() * amplitude
+				})
+				.sum::<f64>();
+			Frame::from_mono((value * 0.3) as f32)
+		})
+		.collect();
+	Sound::from_frames(SAMPLE_RATE, frames, settings)
+}
+
+/// Generates a bed of wind that loops without a seam. The wind
+/// gusts twice over the length of the sound, getting louder and
+/// brighter with each gust, and the right channel gusts a little
+/// after the left.
+pub fn wind(duration: f64, settings: PlayableSettings) -> Sound {
+	let num_frames = (duration * SAMPLE_RATE as f64) as usize;
+	let channel = |seed, offset: f64| {
+		let mut rng = StdRng::seed_from_u64(seed);
+		let mut low = 0.0;
+		(0..num_frames + LOOP_CROSSFADE_FRAMES)
+			.map(|i| {
+				let time = i as f64 / SAMPLE_RATE as f64;
+				let gust = 0.5 - 0.5 * (2.0 * PI * (2.0 * time / duration - offset)).cos();
+				low += (rng.gen_range(-1.0, 1.0) - low) * (0.005 + 0.03 * gust);
+				(low * (0.3 + 0.7 * gust) * 3.5) as f32
+			})
+			.collect::<Vec<_>>()
+	};
+	let frames = channel(0, 0.0)
+		.into_iter()
+		.zip(channel(1, 0.1))
+		.map(|(left, right)| Frame::new(left, right))
+		.collect();
+	Sound::from_frames(SAMPLE_RATE, seamless_loop(frames), settings)
+}
+
+/// Fades the extra frames at the end of some noise into the
+/// start, so the sound can loop without a click. The fade keeps
+/// the power even, since the two ends of the noise have nothing
+/// in common.
+fn seamless_loop(mut frames: Vec<Frame>) -> Vec<Frame> {
+	let length = frames.len() - LOOP_CROSSFADE_FRAMES;
+	for i in 0..LOOP_CROSSFADE_FRAMES {
+		let fade = i as f32 / LOOP_CROSSFADE_FRAMES as f32;
+		frames[i] = frames[i] * fade.sqrt() + frames[length + i] * (1.0 - fade).sqrt();
+	}
+	frames.truncate(length);
+	frames
+}
@@ -0,0 +1,111 @@
+use kira::Tempo;
+
+use super::decoded_sound::DecodedSound;
+
+/// The number of frames in each window the loudness is measured over.
+const HOP_SIZE: usize = 512;
+const MIN_BPM: f64 = 70.0;
+const MAX_BPM: f64 = 180.0;
+const BEATS_PER_BAR: f64 = 4.0;
+/// How far (as a fraction) the tempo that makes the loop a
+/// whole number of bars can be from the detected tempo before
+/// the loop is assumed to have a tail after the last bar.
+const WHOLE_BAR_TOLERANCE: f64 = 0.01;
+
+/// A guess at the tempo and length of a loop.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TempoEstimate {
+	pub tempo: Tempo,
+	pub bars: usize,
+}
+
+/// Measures how much louder each window is than the one
+/// before it, which peaks wherever a note or drum hit starts.
+fn onset_strengths(sound: &DecodedSound) -> Vec<f64> {
+	let energies: Vec<f64> = sound
+		.frames()
+		.chunks(HOP_SIZE)
+		.map(|chunk| {
+			let energy: f64 = chunk
+				.iter()
+				.map(|frame| {
+					let mono = (frame.left + frame.right) as f64 / 2.0;
+					mono * mono
+				})
+				.sum();
+			(energy / chunk.len() as f64 + 1e-9).ln()
+		})
+		.collect();
+	let mut strengths: Vec<f64> = energies
+		.windows(2)
+		.map(|pair| (pair[1] - pair[0]).max(0.0))
+		.collect();
+	let mean = strengths.iter().sum::<f64>() / strengths.len().max(1) as f64;
+	for strength in &mut strengths {
+		*strength -= mean;
+	}
+	strengths
+}
+
+/// Guesses the tempo of a loop by finding the beat length
+/// that the onsets line up with best, then fits a whole number
+/// of bars into the loop.
+///
+/// Like most beat detection, this can pick half or double the
+/// real tempo, so the result should be checked by ear.
+pub fn estimate_tempo(sound: &DecodedSound) -> Option<TempoEstimate> {
+	let strengths = onset_strengths(sound);
+	let hop_duration = HOP_SIZE as f64 / sound.sample_rate() as f64;
+	let min_lag = (60.0 / MAX_BPM / hop_duration).floor() as usize;
+	let max_lag = (60.0 / MIN_BPM / hop_duration).ceil() as usize;
+	if strengths.len() <= max_lag {
+		return None;
+	}
+	// the sound is a loop, so the correlation wraps around
+	// the end instead of running out of samples
+	let correlation = |lag: usize| -> f64 {
+		(0..strengths.len())
+			.map(|i| strengths[i] * strengths[(i + lag) % strengths.len()])
+			.sum()
+	};
+	let correlations: Vec<f64> = (min_lag.max(1)..=max_lag + 1).map(correlation).collect();
+	let best_index = (1..correlations.len() - 1)
+		.max_by(|a, b| correlations[*a].partial_cmp(&correlations[*b]).unwrap())?;
+	// the best lag is only as precise as the window size, so
+	// fit a parabola through its neighbors to find the real peak
+	let (before, peak, after) = (
+		correlations[best_index - 1],
+		correlations[best_index],
+		correlations[best_index + 1],
+	);
+	let curvature = before - 2.0 * peak + after;
+	let offset = if curvature < 0.0 {
+		0.5 * (before - after) / curvature
+	} else {
+		0.0
+	};
+	let best_lag = (min_lag.max(1) + best_index) as f64 + offset;
+	let detected_bpm = 60.0 / (best_lag * hop_duration);
+	let duration = sound.duration();
+	let bars = (duration * detected_bpm / 60.0 / BEATS_PER_BAR)
+		.round()
+		.max(1.0);
+	let whole_bar_bpm = bars * BEATS_PER_BAR * 60.0 / duration;
+	// if the loop is very close to a whole number of bars, it
+	// probably has no tail, and the tempo that fits it exactly
+	// is a better guess
+	if (whole_bar_bpm - detected_bpm).abs() / detected_bpm <= WHOLE_BAR_TOLERANCE {
+		return Some(TempoEstimate {
+			tempo: Tempo(whole_bar_bpm),
+			bars: bars as usize,
+		});
+	}
+	// otherwise the loop has a tail, and music is usually a
+	// power of two bars long, so take the longest one that fits
+	let bpm = detected_bpm.round();
+	let bars_that_fit = ((duration * bpm / 60.0 / BEATS_PER_BAR).floor() as usize).max(1);
+	Some(TempoEstimate {
+		tempo: Tempo(bpm),
+		bars: 1 << (usize::BITS - 1 - bars_that_fit.leading_zeros()),
+	})
+}
@@ -0,0 +1,71 @@
+use std::sync::Mutex;
+
+use kira::{
+	manager::{AudioManager, AudioManagerSettings, Backend},
+	AudioResult,
+};
+
+/// The sample rate Kira uses when running without an audio thread.
+const SAMPLE_RATE: f64 = 48000.0;
+
+/// The backends of the audio managers created since the clock
+/// last checked, waiting to be picked up by it. Most screens have
+/// one audio manager, but some run a few side by side.
+static NEW_BACKENDS: Mutex<Vec<Backend>> = Mutex::new(Vec::new());
+
+/// Creates an audio manager whose backend is only advanced
+/// by a [`VirtualClock`] instead of an audio device.
+pub fn create_audio_manager(settings: AudioManagerSettings) -> AudioResult<AudioManager> {
+	let (audio_manager, backend) = AudioManager::new_without_audio_thread(settings)?;
+	NEW_BACKENDS.lock().unwrap().push(backend);
+	Ok(audio_manager)
+}
+
+/// Drives the current screen's audio backends, so time only passes
+/// as fast as the developer wants it to.
+///
+/// The rendered audio is thrown away - the point is to be able
+/// to watch the demos' state change one step at a time.
+#[derive(Default)]
+pub struct VirtualClock {
+	backends: Vec<Backend>,
+	time: f64,
+	unrendered_frames: f64,
+}
+
+impl VirtualClock {
+	/// Switches to the backends of the audio managers that were
+	/// created since the last call, if there are any.
+	pub fn attach_new_backends(&mut self) {
+		let new_backends = std::mem::take(&mut *NEW_BACKENDS.lock().unwrap());
+		if !new_backends.is_empty() {
+			self.backends = new_backends;
+			self.time = 0.0;
+			self.unrendered_frames = 0.0;
+		}
+	}
+
+	pub fn is_running(&self) -> bool {
+		!self.backends.is_empty()
+	}
+
+	/// How much virtual time (in seconds) has passed since the
+	/// current screen's audio managers were created.
+	pub fn time(&self) -> f64 {
+		self.time
+	}
+
+	pub fn advance(&mut self, duration: f64) {
+		if self.backends.is_empty() {
+			return;
+		}
+		self.time += duration;
+		self.unrendered_frames += duration * SAMPLE_RATE;
+		while self.unrendered_frames >= 1.0 {
+			for backend in &mut self.backends {
+				backend.process();
+			}
+			self.unrendered_frames -= 1.0;
+		}
+	}
+}
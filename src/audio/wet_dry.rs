@@ -0,0 +1,141 @@
+use std::hash::Hash;
+
+use kira::{
+	instance::{InstanceId, InstanceSettings},
+	manager::AudioManager,
+	mixer::SubTrackId,
+	parameter::{Mapping, ParameterId, Tween},
+	playable::Playable,
+	sequence::Sequence,
+	AudioResult, Value,
+};
+
+// short enough to feel instant, long enough to avoid a click
+const BYPASS_FADE_DURATION: f64 = 0.05;
+
+/// Blends the output of an effect chain with the unprocessed
+/// signal.
+///
+/// Kira's effects don't have a mix control, so sounds are
+/// played twice in parallel: once on a dry track and once on
+/// a wet track (where the effects go), with each instance's
+/// volume tied to a shared mix parameter.
+///
+/// Bypassing works the same way: rather than removing the
+/// effects (which would cut off the signal abruptly), the mix
+/// is quickly faded to fully dry and back.
+#[derive(Debug, Clone)]
+pub struct WetDry {
+	mix_parameter_id: ParameterId,
+	dry_track_id: SubTrackId,
+	wet_track_id: SubTrackId,
+	mix: f64,
+	bypassed: bool,
+}
+
+impl WetDry {
+	pub fn new(audio_manager: &mut AudioManager, mix: f64) -> AudioResult<Self> {
+		Ok(Self {
+			mix_parameter_id: audio_manager.add_parameter(mix)?,
+			dry_track_id: audio_manager.add_sub_track(Default::default())?,
+			wet_track_id: audio_manager.add_sub_track(Default::default())?,
+			mix,
+			bypassed: false,
+		})
+	}
+
+	/// Gets the track that effects should be added to.
+	pub fn wet_track_id(&self) -> SubTrackId {
+		self.wet_track_id
+	}
+
+	fn dry_settings(&self, settings: InstanceSettings) -> InstanceSettings {
+		settings.track(self.dry_track_id).volume(Value::Parameter(
+			self.mix_parameter_id,
+			Mapping {
+				input_range: (0.0, 1.0),
+				output_range: (1.0, 0.0),
+				clamp_bottom: true,
+				clamp_top: true,
+			},
+		))
+	}
+
+	fn wet_settings(&self, settings: InstanceSettings) -> InstanceSettings {
+		settings.track(self.wet_track_id).volume(Value::Parameter(
+			self.mix_parameter_id,
+			Mapping {
+				clamp_bottom: true,
+				clamp_top: true,
+				..Default::default()
+			},
+		))
+	}
+
+	/// Plays something on both the dry and wet tracks. The
+	/// volume of `settings` is replaced by the mix.
+	///
+	/// The playable should have its cooldown disabled,
+	/// otherwise the second instance will be ignored.
+	pub fn play<P: Into<Playable> + Copy>(
+		&self,
+		audio_manager: &mut AudioManager,
+		playable: P,
+		settings: InstanceSettings,
+	) -> AudioResult<[InstanceId; 2]> {
+		let dry_instance_id = audio_manager.play(playable, self.dry_settings(settings))?;
+		let wet_instance_id = audio_manager.play(playable, self.wet_settings(settings))?;
+		Ok([dry_instance_id, wet_instance_id])
+	}
+
+	/// Adds steps to a sequence that play something on both
+	/// the dry and wet tracks, like [`WetDry::play`].
+	pub fn play_in_sequence<P: Into<Playable> + Copy, CustomEvent: Clone + Eq + Hash>(
+		&self,
+		sequence: &mut Sequence<CustomEvent>,
+		playable: P,
+		settings: InstanceSettings,
+	) -> [InstanceId; 2] {
+		[
+			sequence.play(playable, self.dry_settings(settings)),
+			sequence.play(playable, self.wet_settings(settings)),
+		]
+	}
+
+	pub fn mix(&self) -> f64 {
+		self.mix
+	}
+
+	pub fn bypassed(&self) -> bool {
+		self.bypassed
+	}
+
+	/// Sets how much of the output is processed (from 0 to 1).
+	/// While bypassed, the new mix is remembered and applied
+	/// once the bypass is turned off.
+	pub fn set_mix(
+		&mut self,
+		audio_manager: &mut AudioManager,
+		mix: f64,
+		tween: Option<Tween>,
+	) -> AudioResult<()> {
+		self.mix = mix;
+		if self.bypassed {
+			return Ok(());
+		}
+		audio_manager.set_parameter(self.mix_parameter_id, mix, tween)
+	}
+
+	pub fn set_bypassed(
+		&mut self,
+		audio_manager: &mut AudioManager,
+		bypassed: bool,
+	) -> AudioResult<()> {
+		self.bypassed = bypassed;
+		audio_manager.set_parameter(
+			self.mix_parameter_id,
+			if bypassed { 0.0 } else { self.mix },
+			Some(Tween::linear(BYPASS_FADE_DURATION)),
+		)
+	}
+}
@@ -1,35 +1,325 @@
+mod audio;
+#[cfg(feature = "midi")]
+mod midi;
+mod net_sync;
+mod preferences;
+#[cfg(feature = "remote")]
+mod remote;
+#[cfg(feature = "tray")]
+mod tray;
 mod ui;
 
-use std::{error::Error, time::Duration};
+use std::{
+	error::Error,
+	path::PathBuf,
+	time::{Duration, Instant},
+};
 
-use iced::{executor, Application, Command, Container, Length, Subscription};
+use audio::sound_cache::SoundCache;
+use iced::{
+	executor, Align, Application, Button, Command, Container, Length, Row, Subscription, Text,
+};
+use kira::{AudioError, Tempo};
+use preferences::Preferences;
+#[cfg(feature = "debug-clock")]
+use ui::debug_clock::DebugClock;
+#[cfg(feature = "midi")]
+use ui::midi_learn::MidiLearn;
+#[cfg(feature = "remote")]
+use ui::remote_control::RemoteControl;
 use ui::{
+	media_keys::{MediaControls, MediaKey},
 	screen::{
+		arrangement_editor_demo::{self, ArrangementEditorDemo},
+		beat_repeat_demo::{self, BeatRepeatDemo},
+		branching_music_demo::{self, BranchingMusicDemo},
+		chord_progression_demo::{self, ChordProgressionDemo},
+		combat_music_demo::{self, CombatMusicDemo},
+		compressor_demo::{self, CompressorDemo},
+		count_in_demo::{self, CountInDemo},
+		crossfade_demo::{self, CrossfadeDemo},
+		custom_loop_demo::{self, CustomLoopDemo},
+		delay_demo::{self, DelayDemo},
 		demo_select,
 		demo_select::DemoSelect,
+		dialogue_demo::{self, DialogueDemo},
+		distortion_demo::{self, DistortionDemo},
+		doppler_demo::{self, DopplerDemo},
 		drum_fill_demo,
 		drum_fill_demo::DrumFillDemo,
+		drum_pad_demo::{self, DrumPadDemo},
+		dual_metronome_demo::{self, DualMetronomeDemo},
+		ducking_demo::{self, DuckingDemo},
+		easing_gallery::{self, EasingGallery},
+		engine_demo::{self, EngineDemo},
+		filter_demo::{self, FilterDemo},
+		footstep_demo::{self, FootstepDemo},
+		footstep_surface_demo::{self, FootstepSurfaceDemo},
+		generative_ambient_demo::{self, GenerativeAmbientDemo},
+		granular_demo::{self, GranularDemo},
+		group_hierarchy_demo::{self, GroupHierarchyDemo},
+		group_pause_demo::{self, GroupPauseDemo},
+		jukebox_demo::{self, JukeboxDemo},
+		karaoke_demo::{self, KaraokeDemo},
+		layering_demo::{self, LayeringDemo},
+		lfo_demo::{self, LfoDemo},
+		limits_demo::{self, LimitsDemo},
+		loop_region_demo::{self, LoopRegionDemo},
+		low_health_demo::{self, LowHealthDemo},
+		mapping_playground::{self, MappingPlayground},
+		mixer_console_demo::{self, MixerConsoleDemo},
+		mixer_snapshot_demo::{self, MixerSnapshotDemo},
+		net_sync_demo::{self, NetSyncDemo},
+		occlusion_demo::{self, OcclusionDemo},
+		odd_time_signature_demo::{self, OddTimeSignatureDemo},
+		panning_demo::{self, PanningDemo},
+		parametric_eq_demo::{self, ParametricEqDemo},
+		pause_menu_demo::{self, PauseMenuDemo},
+		pitch_playground::{self, PitchPlayground},
+		polyphony_demo::{self, PolyphonyDemo},
+		radio_voice_demo::{self, RadioVoiceDemo},
+		retrigger_cooldown_demo::{self, RetriggerCooldownDemo},
+		reverb_demo::{self, ReverbDemo},
+		rhythm_game_demo::{self, RhythmGameDemo},
+		scheduling_accuracy_demo::{self, SchedulingAccuracyDemo},
+		seek_demo::{self, SeekDemo},
+		send_return_demo::{self, SendReturnDemo},
+		settings::{self, Settings},
+		signal_generator_demo::{self, SignalGeneratorDemo},
+		sound_container_demo::{self, SoundContainerDemo},
+		spatial_demo::{self, SpatialDemo},
+		spectrum_demo::{self, SpectrumDemo},
+		start_offset_demo::{self, StartOffsetDemo},
+		stem_mute_solo_demo::{self, StemMuteSoloDemo},
+		step_sequencer_demo::{self, StepSequencerDemo},
+		stereo_width_demo::{self, StereoWidthDemo},
+		stinger_demo::{self, StingerDemo},
+		stop_fade_demo::{self, StopFadeDemo},
+		streaming_demo::{self, StreamingDemo},
+		surround_demo::{self, SurroundDemo},
+		swing_demo::{self, SwingDemo},
+		synth_keyboard_demo::{self, SynthKeyboardDemo},
+		tempo_ramp_demo::{self, TempoRampDemo},
+		transition_matrix_demo::{self, TransitionMatrixDemo},
+		tuning_demo::{self, TuningDemo},
+		ui_sounds_demo::{self, UiSoundsDemo},
 		underwater_demo::{self, UnderwaterDemo},
+		vinyl_brake_demo::{self, VinylBrakeDemo},
+		voice_over_demo::{self, VoiceOverDemo},
+		weapon_demo::{self, WeaponDemo},
+		weather_demo::{self, WeatherDemo},
+		wind_demo::{self, WindDemo},
 	},
+	screen_states::ScreenStates,
+	session_player::SessionPlayer,
+	session_recorder::SessionRecorder,
 	style::AppStyles,
+	undo_stack::{UndoControls, UndoShortcut},
 };
+#[cfg(feature = "tray")]
+use {
+	audio::mute,
+	tray::{Tray, TrayAction},
+};
+
+const ACTIVE_TICK_INTERVAL: Duration = Duration::from_millis(16);
+const MINIMIZED_TICK_INTERVAL: Duration = Duration::from_millis(250);
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 enum Message {
 	CheckForEvents,
+	SetMinimized(bool),
+	FileDropped(PathBuf),
+	DismissError,
+	MediaKeyPressed(MediaKey),
+	UndoShortcutPressed(UndoShortcut),
+	ArrowKeyPressed(footstep_surface_demo::Direction),
+	ToggleRecording,
+	#[cfg(feature = "midi")]
+	ToggleMidiLearn,
+	#[cfg(feature = "debug-clock")]
+	AdvanceVirtualClock(f64),
+	#[cfg(feature = "debug-clock")]
+	SetTimeScale(f64),
 	DemoSelect(demo_select::Message),
 	DrumFillDemo(drum_fill_demo::Message),
 	UnderwaterDemo(underwater_demo::Message),
+	StartOffsetDemo(start_offset_demo::Message),
+	RetriggerCooldownDemo(retrigger_cooldown_demo::Message),
+	GroupHierarchyDemo(group_hierarchy_demo::Message),
+	SendReturnDemo(send_return_demo::Message),
+	SchedulingAccuracyDemo(scheduling_accuracy_demo::Message),
+	StreamingDemo(streaming_demo::Message),
+	Settings(settings::Message),
+	LimitsDemo(limits_demo::Message),
+	DualMetronomeDemo(dual_metronome_demo::Message),
+	VoiceOverDemo(voice_over_demo::Message),
+	TuningDemo(tuning_demo::Message),
+	StopFadeDemo(stop_fade_demo::Message),
+	EasingGallery(easing_gallery::Message),
+	MappingPlayground(mapping_playground::Message),
+	CustomLoopDemo(custom_loop_demo::Message),
+	SurroundDemo(surround_demo::Message),
+	NetSyncDemo(net_sync_demo::Message),
+	CrossfadeDemo(crossfade_demo::Message),
+	LayeringDemo(layering_demo::Message),
+	StingerDemo(stinger_demo::Message),
+	SpatialDemo(spatial_demo::Message),
+	DopplerDemo(doppler_demo::Message),
+	FootstepDemo(footstep_demo::Message),
+	ReverbDemo(reverb_demo::Message),
+	DuckingDemo(ducking_demo::Message),
+	DialogueDemo(dialogue_demo::Message),
+	PitchPlayground(pitch_playground::Message),
+	GranularDemo(granular_demo::Message),
+	TempoRampDemo(tempo_ramp_demo::Message),
+	LoopRegionDemo(loop_region_demo::Message),
+	JukeboxDemo(jukebox_demo::Message),
+	DrumPadDemo(drum_pad_demo::Message),
+	SynthKeyboardDemo(synth_keyboard_demo::Message),
+	StepSequencerDemo(step_sequencer_demo::Message),
+	ArrangementEditorDemo(arrangement_editor_demo::Message),
+	MixerConsoleDemo(mixer_console_demo::Message),
+	FilterDemo(filter_demo::Message),
+	DelayDemo(delay_demo::Message),
+	DistortionDemo(distortion_demo::Message),
+	LfoDemo(lfo_demo::Message),
+	BranchingMusicDemo(branching_music_demo::Message),
+	OcclusionDemo(occlusion_demo::Message),
+	WeatherDemo(weather_demo::Message),
+	CombatMusicDemo(combat_music_demo::Message),
+	SoundContainerDemo(sound_container_demo::Message),
+	SeekDemo(seek_demo::Message),
+	PanningDemo(panning_demo::Message),
+	GroupPauseDemo(group_pause_demo::Message),
+	PolyphonyDemo(polyphony_demo::Message),
+	TransitionMatrixDemo(transition_matrix_demo::Message),
+	RhythmGameDemo(rhythm_game_demo::Message),
+	SpectrumDemo(spectrum_demo::Message),
+	GenerativeAmbientDemo(generative_ambient_demo::Message),
+	ChordProgressionDemo(chord_progression_demo::Message),
+	SwingDemo(swing_demo::Message),
+	OddTimeSignatureDemo(odd_time_signature_demo::Message),
+	StereoWidthDemo(stereo_width_demo::Message),
+	ParametricEqDemo(parametric_eq_demo::Message),
+	CompressorDemo(compressor_demo::Message),
+	SignalGeneratorDemo(signal_generator_demo::Message),
+	MixerSnapshotDemo(mixer_snapshot_demo::Message),
+	PauseMenuDemo(pause_menu_demo::Message),
+	RadioVoiceDemo(radio_voice_demo::Message),
+	FootstepSurfaceDemo(footstep_surface_demo::Message),
+	EngineDemo(engine_demo::Message),
+	WeaponDemo(weapon_demo::Message),
+	UiSoundsDemo(ui_sounds_demo::Message),
+	LowHealthDemo(low_health_demo::Message),
+	KaraokeDemo(karaoke_demo::Message),
+	CountInDemo(count_in_demo::Message),
+	StemMuteSoloDemo(stem_mute_solo_demo::Message),
+	BeatRepeatDemo(beat_repeat_demo::Message),
+	VinylBrakeDemo(vinyl_brake_demo::Message),
+	WindDemo(wind_demo::Message),
 }
 
 enum Screen {
 	DemoSelect(DemoSelect),
 	DrumFillDemo(DrumFillDemo),
 	UnderwaterDemo(UnderwaterDemo),
+	StartOffsetDemo(StartOffsetDemo),
+	RetriggerCooldownDemo(RetriggerCooldownDemo),
+	GroupHierarchyDemo(GroupHierarchyDemo),
+	SendReturnDemo(SendReturnDemo),
+	SchedulingAccuracyDemo(SchedulingAccuracyDemo),
+	StreamingDemo(StreamingDemo),
+	Settings(Settings),
+	LimitsDemo(LimitsDemo),
+	DualMetronomeDemo(DualMetronomeDemo),
+	VoiceOverDemo(VoiceOverDemo),
+	TuningDemo(TuningDemo),
+	StopFadeDemo(StopFadeDemo),
+	EasingGallery(EasingGallery),
+	MappingPlayground(MappingPlayground),
+	CustomLoopDemo(CustomLoopDemo),
+	SurroundDemo(SurroundDemo),
+	NetSyncDemo(NetSyncDemo),
+	CrossfadeDemo(CrossfadeDemo),
+	LayeringDemo(LayeringDemo),
+	StingerDemo(StingerDemo),
+	SpatialDemo(SpatialDemo),
+	DopplerDemo(DopplerDemo),
+	FootstepDemo(FootstepDemo),
+	ReverbDemo(ReverbDemo),
+	DuckingDemo(DuckingDemo),
+	DialogueDemo(DialogueDemo),
+	PitchPlayground(PitchPlayground),
+	GranularDemo(GranularDemo),
+	TempoRampDemo(TempoRampDemo),
+	LoopRegionDemo(LoopRegionDemo),
+	JukeboxDemo(JukeboxDemo),
+	DrumPadDemo(DrumPadDemo),
+	SynthKeyboardDemo(SynthKeyboardDemo),
+	StepSequencerDemo(StepSequencerDemo),
+	ArrangementEditorDemo(ArrangementEditorDemo),
+	MixerConsoleDemo(MixerConsoleDemo),
+	FilterDemo(FilterDemo),
+	DelayDemo(DelayDemo),
+	DistortionDemo(DistortionDemo),
+	LfoDemo(LfoDemo),
+	BranchingMusicDemo(BranchingMusicDemo),
+	OcclusionDemo(OcclusionDemo),
+	WeatherDemo(WeatherDemo),
+	CombatMusicDemo(CombatMusicDemo),
+	SoundContainerDemo(SoundContainerDemo),
+	SeekDemo(SeekDemo),
+	PanningDemo(PanningDemo),
+	GroupPauseDemo(GroupPauseDemo),
+	PolyphonyDemo(PolyphonyDemo),
+	TransitionMatrixDemo(TransitionMatrixDemo),
+	RhythmGameDemo(RhythmGameDemo),
+	SpectrumDemo(SpectrumDemo),
+	GenerativeAmbientDemo(GenerativeAmbientDemo),
+	ChordProgressionDemo(ChordProgressionDemo),
+	SwingDemo(SwingDemo),
+	OddTimeSignatureDemo(OddTimeSignatureDemo),
+	StereoWidthDemo(StereoWidthDemo),
+	ParametricEqDemo(ParametricEqDemo),
+	CompressorDemo(CompressorDemo),
+	SignalGeneratorDemo(SignalGeneratorDemo),
+	MixerSnapshotDemo(MixerSnapshotDemo),
+	PauseMenuDemo(PauseMenuDemo),
+	RadioVoiceDemo(RadioVoiceDemo),
+	FootstepSurfaceDemo(FootstepSurfaceDemo),
+	EngineDemo(EngineDemo),
+	WeaponDemo(WeaponDemo),
+	UiSoundsDemo(UiSoundsDemo),
+	LowHealthDemo(LowHealthDemo),
+	KaraokeDemo(KaraokeDemo),
+	CountInDemo(CountInDemo),
+	StemMuteSoloDemo(StemMuteSoloDemo),
+	BeatRepeatDemo(BeatRepeatDemo),
+	VinylBrakeDemo(VinylBrakeDemo),
+	WindDemo(WindDemo),
 }
 
 struct App {
+	preferences: Preferences,
+	sound_cache: SoundCache,
 	screen: Screen,
+	screen_states: ScreenStates,
+	session_recorder: SessionRecorder,
+	/// The recorded session being replayed on the current screen
+	/// and when the replay started.
+	session_replay: Option<(SessionPlayer, Instant)>,
+	minimized: bool,
+	error: Option<String>,
+	dismiss_error_button: iced::button::State,
+	#[cfg(feature = "midi")]
+	midi_learn: MidiLearn,
+	#[cfg(feature = "remote")]
+	remote_control: RemoteControl,
+	#[cfg(feature = "tray")]
+	tray: Result<Tray, String>,
+	#[cfg(feature = "debug-clock")]
+	debug_clock: DebugClock,
 }
 
 impl Application for App {
@@ -38,81 +328,3582 @@ impl Application for App {
 	type Flags = ();
 
 	fn new(_: ()) -> (Self, Command<Self::Message>) {
+		let preferences = Preferences::load().unwrap_or_default();
+		#[cfg(feature = "remote")]
+		let remote_control = RemoteControl::new(
+			&preferences.remote_allowed_commands,
+			&preferences.remote_allowed_origins,
+		);
+		#[cfg(feature = "tray")]
+		let tray = Tray::new(demo_select::demo_names().map(String::from).collect())
+			.map_err(|error| error.to_string());
+		// there's nowhere else to show why the tray icon is missing
+		#[cfg(feature = "tray")]
+		let error = tray.as_ref().err().cloned();
+		#[cfg(not(feature = "tray"))]
+		let error = None;
+		let sound_cache = SoundCache::preload(
+			std::env::current_dir().unwrap().join("assets"),
+			demo_select::all_assets(),
+		);
 		(
 			Self {
-				screen: Screen::DemoSelect(DemoSelect::new()),
+				preferences,
+				screen: Screen::DemoSelect(DemoSelect::new(sound_cache.clone())),
+				sound_cache,
+				screen_states: ScreenStates::default(),
+				session_recorder: SessionRecorder::new(),
+				session_replay: None,
+				minimized: false,
+				error,
+				dismiss_error_button: iced::button::State::new(),
+				#[cfg(feature = "midi")]
+				midi_learn: MidiLearn::new(),
+				#[cfg(feature = "remote")]
+				remote_control,
+				#[cfg(feature = "tray")]
+				tray,
+				#[cfg(feature = "debug-clock")]
+				debug_clock: DebugClock::new(),
 			},
 			Command::none(),
 		)
 	}
 
 	fn title(&self) -> String {
-		"Kira demo".into()
+		let title = match self.now_playing() {
+			Some(now_playing) => format!("Kira demo - {}", now_playing),
+			None => "Kira demo".into(),
+		};
+		#[cfg(feature = "tray")]
+		if mute::is_muted() {
+			return format!("{} (muted)", title);
+		}
+		title
 	}
 
 	fn update(&mut self, message: Self::Message) -> Command<Self::Message> {
-		match message {
-			Message::CheckForEvents => match &mut self.screen {
-				Screen::DrumFillDemo(screen) => {
-					screen.check_for_events().unwrap();
-				}
-				_ => {}
-			},
-			Message::DemoSelect(message) => match message {
-				demo_select::Message::GoToDrumFillDemo => {
-					self.screen = Screen::DrumFillDemo(DrumFillDemo::new().unwrap());
-				}
-				demo_select::Message::GoToUnderwaterDemo => {
-					self.screen = Screen::UnderwaterDemo(UnderwaterDemo::new().unwrap());
-				}
-			},
-			Message::DrumFillDemo(message) => match message {
-				drum_fill_demo::Message::GoToDemoSelect => {
-					self.screen = Screen::DemoSelect(DemoSelect::new());
-				}
-				message => {
-					if let Screen::DrumFillDemo(screen) = &mut self.screen {
-						screen.update(message).unwrap();
-					}
-				}
-			},
-			Message::UnderwaterDemo(message) => match message {
-				underwater_demo::Message::GoToDemoSelect => {
-					self.screen = Screen::DemoSelect(DemoSelect::new());
-				}
-				message => {
-					if let Screen::UnderwaterDemo(screen) = &mut self.screen {
-						screen.update(message).unwrap();
-					}
-				}
-			},
+		// errors (for example, from going over one of the audio
+		// manager's capacities) are shown instead of crashing
+		#[cfg(feature = "debug-clock")]
+		if let Message::CheckForEvents = message {
+			self.debug_clock.tick();
 		}
+		if let Err(error) = self.try_update(message) {
+			self.error = Some(describe_error(&*error));
+		}
+		// if the message opened a new screen, the clock should
+		// drive its audio manager from now on
+		#[cfg(feature = "debug-clock")]
+		self.debug_clock.attach_new_backends();
 		Command::none()
 	}
 
 	fn subscription(&self) -> Subscription<Self::Message> {
-		match &self.screen {
-			Screen::DrumFillDemo(_) => {
-				iced::time::every(Duration::from_millis(16)).map(|_| Message::CheckForEvents)
+		let window_events = iced_native::subscription::events_with(|event, _| match event {
+			// iced doesn't report focus changes, but minimizing the
+			// window resizes it to zero
+			iced_native::Event::Window(iced_native::window::Event::Resized { width, height }) => {
+				Some(Message::SetMinimized(width == 0 || height == 0))
 			}
-			_ => Subscription::none(),
+			iced_native::Event::Window(iced_native::window::Event::FileDropped(path)) => {
+				Some(Message::FileDropped(path))
+			}
+			iced_native::Event::Keyboard(iced_native::keyboard::Event::KeyPressed {
+				key_code,
+				modifiers,
+			}) => MediaKey::from_key_code(key_code)
+				.map(Message::MediaKeyPressed)
+				.or_else(|| {
+					UndoShortcut::from_key_press(key_code, modifiers)
+						.map(Message::UndoShortcutPressed)
+				})
+				.or_else(|| {
+					footstep_surface_demo::Direction::from_key_code(key_code)
+						.map(Message::ArrowKeyPressed)
+				}),
+			_ => None,
+		});
+		let needs_ticks = match &self.screen {
+			// keeps the readiness indicators up to date
+			Screen::DemoSelect(screen) => screen.is_loading(),
+			Screen::DrumFillDemo(_)
+			| Screen::StartOffsetDemo(_)
+			| Screen::RetriggerCooldownDemo(_)
+			| Screen::SchedulingAccuracyDemo(_)
+			| Screen::LimitsDemo(_)
+			| Screen::UnderwaterDemo(_)
+			| Screen::SendReturnDemo(_)
+			| Screen::StreamingDemo(_)
+			| Screen::DualMetronomeDemo(_)
+			| Screen::TuningDemo(_)
+			| Screen::StopFadeDemo(_)
+			| Screen::EasingGallery(_)
+			| Screen::CustomLoopDemo(_)
+			| Screen::SurroundDemo(_)
+			| Screen::NetSyncDemo(_)
+			| Screen::CrossfadeDemo(_)
+			| Screen::LayeringDemo(_)
+			| Screen::StingerDemo(_)
+			| Screen::DopplerDemo(_)
+			| Screen::FootstepDemo(_)
+			| Screen::DuckingDemo(_)
+			| Screen::DialogueDemo(_)
+			| Screen::GranularDemo(_)
+			| Screen::TempoRampDemo(_)
+			| Screen::LoopRegionDemo(_)
+			| Screen::JukeboxDemo(_)
+			| Screen::DrumPadDemo(_)
+			| Screen::StepSequencerDemo(_)
+			| Screen::ArrangementEditorDemo(_)
+			| Screen::MixerConsoleDemo(_)
+			| Screen::DelayDemo(_)
+			| Screen::LfoDemo(_)
+			| Screen::BranchingMusicDemo(_)
+			| Screen::WeatherDemo(_)
+			| Screen::CombatMusicDemo(_)
+			| Screen::SoundContainerDemo(_)
+			| Screen::SeekDemo(_)
+			| Screen::PolyphonyDemo(_)
+			| Screen::TransitionMatrixDemo(_)
+			| Screen::RhythmGameDemo(_)
+			| Screen::SpectrumDemo(_)
+			| Screen::GenerativeAmbientDemo(_)
+			| Screen::ChordProgressionDemo(_)
+			| Screen::SwingDemo(_)
+			| Screen::OddTimeSignatureDemo(_)
+			| Screen::CompressorDemo(_)
+			| Screen::MixerSnapshotDemo(_)
+			| Screen::RadioVoiceDemo(_)
+			| Screen::EngineDemo(_)
+			| Screen::WeaponDemo(_)
+			| Screen::KaraokeDemo(_)
+			| Screen::CountInDemo(_)
+			| Screen::StemMuteSoloDemo(_)
+			| Screen::BeatRepeatDemo(_)
+			| Screen::VinylBrakeDemo(_)
+			| Screen::WindDemo(_) => true,
+			// MIDI input, remote commands, and the tray icon are
+			// checked for and the virtual clock is advanced on
+			// every screen
+			#[cfg(any(
+				feature = "midi",
+				feature = "debug-clock",
+				feature = "remote",
+				feature = "tray"
+			))]
+			_ => true,
+			#[cfg(not(any(
+				feature = "midi",
+				feature = "debug-clock",
+				feature = "remote",
+				feature = "tray"
+			)))]
+			_ => false,
+		};
+		if !needs_ticks && self.session_replay.is_none() {
+			return window_events;
 		}
+		// the audio keeps playing while the window is minimized, and the
+		// screens catch up on any events they missed on the next tick,
+		// so there's no need to check as often. screens that play sounds
+		// from the UI timer are the exception, since slowing the timer
+		// down would slow the sounds down too.
+		let tick_interval = if self.minimized && !self.plays_from_ui_timer() {
+			MINIMIZED_TICK_INTERVAL
+		} else {
+			ACTIVE_TICK_INTERVAL
+		};
+		Subscription::batch(vec![
+			window_events,
+			iced::time::every(tick_interval).map(|_| Message::CheckForEvents),
+		])
 	}
 
 	fn view(&mut self) -> iced::Element<'_, Self::Message> {
-		Container::new(match &mut self.screen {
-			Screen::DemoSelect(screen) => screen.view().map(|message| Message::DemoSelect(message)),
-			Screen::DrumFillDemo(screen) => {
-				screen.view().map(|message| Message::DrumFillDemo(message))
+		#[cfg(feature = "debug-clock")]
+		let tempo = self.tempo();
+		// only the demos can be recorded
+		let recordable = matches!(
+			self.screen,
+			Screen::DrumFillDemo(_)
+				| Screen::UnderwaterDemo(_)
+				| Screen::StartOffsetDemo(_)
+				| Screen::RetriggerCooldownDemo(_)
+				| Screen::GroupHierarchyDemo(_)
+				| Screen::SendReturnDemo(_)
+				| Screen::SchedulingAccuracyDemo(_)
+				| Screen::StreamingDemo(_)
+				| Screen::LimitsDemo(_)
+				| Screen::DualMetronomeDemo(_)
+				| Screen::VoiceOverDemo(_)
+				| Screen::TuningDemo(_)
+				| Screen::StopFadeDemo(_)
+				| Screen::EasingGallery(_)
+				| Screen::MappingPlayground(_)
+				| Screen::SurroundDemo(_)
+				| Screen::CrossfadeDemo(_)
+				| Screen::LayeringDemo(_)
+				| Screen::StingerDemo(_)
+				| Screen::SpatialDemo(_)
+				| Screen::DopplerDemo(_)
+				| Screen::FootstepDemo(_)
+				| Screen::ReverbDemo(_)
+				| Screen::DuckingDemo(_)
+				| Screen::PitchPlayground(_)
+				| Screen::GranularDemo(_)
+				| Screen::TempoRampDemo(_)
+				| Screen::LoopRegionDemo(_)
+				| Screen::JukeboxDemo(_)
+				| Screen::DrumPadDemo(_)
+				| Screen::SynthKeyboardDemo(_)
+				| Screen::StepSequencerDemo(_)
+				| Screen::ArrangementEditorDemo(_)
+				| Screen::MixerConsoleDemo(_)
+				| Screen::FilterDemo(_)
+				| Screen::DelayDemo(_)
+				| Screen::DistortionDemo(_)
+				| Screen::LfoDemo(_)
+				| Screen::BranchingMusicDemo(_)
+				| Screen::OcclusionDemo(_)
+				| Screen::WeatherDemo(_)
+				| Screen::CombatMusicDemo(_)
+				| Screen::SoundContainerDemo(_)
+				| Screen::SeekDemo(_)
+				| Screen::PanningDemo(_)
+				| Screen::GroupPauseDemo(_)
+				| Screen::PolyphonyDemo(_)
+				| Screen::TransitionMatrixDemo(_)
+				| Screen::RhythmGameDemo(_)
+				| Screen::SpectrumDemo(_)
+				| Screen::GenerativeAmbientDemo(_)
+				| Screen::ChordProgressionDemo(_)
+				| Screen::SwingDemo(_)
+				| Screen::OddTimeSignatureDemo(_)
+				| Screen::StereoWidthDemo(_)
+				| Screen::ParametricEqDemo(_)
+				| Screen::CompressorDemo(_)
+				| Screen::SignalGeneratorDemo(_)
+				| Screen::MixerSnapshotDemo(_)
+				| Screen::PauseMenuDemo(_)
+				| Screen::RadioVoiceDemo(_)
+				| Screen::FootstepSurfaceDemo(_)
+				| Screen::EngineDemo(_)
+				| Screen::WeaponDemo(_)
+				| Screen::UiSoundsDemo(_)
+				| Screen::LowHealthDemo(_)
+				| Screen::KaraokeDemo(_)
+				| Screen::CountInDemo(_)
+				| Screen::StemMuteSoloDemo(_)
+				| Screen::BeatRepeatDemo(_)
+				| Screen::VinylBrakeDemo(_)
+				| Screen::WindDemo(_)
+		);
+		let screen = match &mut self.screen {
+			Screen::DemoSelect(screen) => screen.view().map(Message::DemoSelect),
+			Screen::DrumFillDemo(screen) => screen.view().map(Message::DrumFillDemo),
+			Screen::UnderwaterDemo(screen) => screen.view().map(Message::UnderwaterDemo),
+			Screen::StartOffsetDemo(screen) => screen.view().map(Message::StartOffsetDemo),
+			Screen::RetriggerCooldownDemo(screen) => {
+				screen.view().map(Message::RetriggerCooldownDemo)
+			}
+			Screen::GroupHierarchyDemo(screen) => screen.view().map(Message::GroupHierarchyDemo),
+			Screen::SendReturnDemo(screen) => screen.view().map(Message::SendReturnDemo),
+			Screen::SchedulingAccuracyDemo(screen) => {
+				screen.view().map(Message::SchedulingAccuracyDemo)
+			}
+			Screen::StreamingDemo(screen) => screen.view().map(Message::StreamingDemo),
+			Screen::Settings(screen) => screen.view().map(Message::Settings),
+			Screen::LimitsDemo(screen) => screen.view().map(Message::LimitsDemo),
+			Screen::DualMetronomeDemo(screen) => screen.view().map(Message::DualMetronomeDemo),
+			Screen::VoiceOverDemo(screen) => screen.view().map(Message::VoiceOverDemo),
+			Screen::TuningDemo(screen) => screen.view().map(Message::TuningDemo),
+			Screen::StopFadeDemo(screen) => screen.view().map(Message::StopFadeDemo),
+			Screen::EasingGallery(screen) => screen.view().map(Message::EasingGallery),
+			Screen::MappingPlayground(screen) => screen.view().map(Message::MappingPlayground),
+			Screen::CustomLoopDemo(screen) => screen.view().map(Message::CustomLoopDemo),
+			Screen::SurroundDemo(screen) => screen.view().map(Message::SurroundDemo),
+			Screen::NetSyncDemo(screen) => screen.view().map(Message::NetSyncDemo),
+			Screen::CrossfadeDemo(screen) => screen.view().map(Message::CrossfadeDemo),
+			Screen::LayeringDemo(screen) => screen.view().map(Message::LayeringDemo),
+			Screen::StingerDemo(screen) => screen.view().map(Message::StingerDemo),
+			Screen::SpatialDemo(screen) => screen.view().map(Message::SpatialDemo),
+			Screen::DopplerDemo(screen) => screen.view().map(Message::DopplerDemo),
+			Screen::FootstepDemo(screen) => screen.view().map(Message::FootstepDemo),
+			Screen::ReverbDemo(screen) => screen.view().map(Message::ReverbDemo),
+			Screen::DuckingDemo(screen) => screen.view().map(Message::DuckingDemo),
+			Screen::DialogueDemo(screen) => screen.view().map(Message::DialogueDemo),
+			Screen::PitchPlayground(screen) => screen.view().map(Message::PitchPlayground),
+			Screen::GranularDemo(screen) => screen.view().map(Message::GranularDemo),
+			Screen::TempoRampDemo(screen) => screen.view().map(Message::TempoRampDemo),
+			Screen::LoopRegionDemo(screen) => screen.view().map(Message::LoopRegionDemo),
+			Screen::JukeboxDemo(screen) => screen.view().map(Message::JukeboxDemo),
+			Screen::DrumPadDemo(screen) => screen.view().map(Message::DrumPadDemo),
+			Screen::SynthKeyboardDemo(screen) => screen.view().map(Message::SynthKeyboardDemo),
+			Screen::StepSequencerDemo(screen) => screen.view().map(Message::StepSequencerDemo),
+			Screen::ArrangementEditorDemo(screen) => {
+				screen.view().map(Message::ArrangementEditorDemo)
+			}
+			Screen::MixerConsoleDemo(screen) => screen.view().map(Message::MixerConsoleDemo),
+			Screen::FilterDemo(screen) => screen.view().map(Message::FilterDemo),
+			Screen::DelayDemo(screen) => screen.view().map(Message::DelayDemo),
+			Screen::DistortionDemo(screen) => screen.view().map(Message::DistortionDemo),
+			Screen::LfoDemo(screen) => screen.view().map(Message::LfoDemo),
+			Screen::BranchingMusicDemo(screen) => screen.view().map(Message::BranchingMusicDemo),
+			Screen::OcclusionDemo(screen) => screen.view().map(Message::OcclusionDemo),
+			Screen::WeatherDemo(screen) => screen.view().map(Message::WeatherDemo),
+			Screen::CombatMusicDemo(screen) => screen.view().map(Message::CombatMusicDemo),
+			Screen::SoundContainerDemo(screen) => screen.view().map(Message::SoundContainerDemo),
+			Screen::SeekDemo(screen) => screen.view().map(Message::SeekDemo),
+			Screen::PanningDemo(screen) => screen.view().map(Message::PanningDemo),
+			Screen::GroupPauseDemo(screen) => screen.view().map(Message::GroupPauseDemo),
+			Screen::PolyphonyDemo(screen) => screen.view().map(Message::PolyphonyDemo),
+			Screen::TransitionMatrixDemo(screen) => {
+				screen.view().map(Message::TransitionMatrixDemo)
+			}
+			Screen::RhythmGameDemo(screen) => screen.view().map(Message::RhythmGameDemo),
+			Screen::SpectrumDemo(screen) => screen.view().map(Message::SpectrumDemo),
+			Screen::GenerativeAmbientDemo(screen) => {
+				screen.view().map(Message::GenerativeAmbientDemo)
+			}
+			Screen::ChordProgressionDemo(screen) => {
+				screen.view().map(Message::ChordProgressionDemo)
+			}
+			Screen::SwingDemo(screen) => screen.view().map(Message::SwingDemo),
+			Screen::OddTimeSignatureDemo(screen) => {
+				screen.view().map(Message::OddTimeSignatureDemo)
 			}
+			Screen::StereoWidthDemo(screen) => screen.view().map(Message::StereoWidthDemo),
+			Screen::ParametricEqDemo(screen) => screen.view().map(Message::ParametricEqDemo),
+			Screen::CompressorDemo(screen) => screen.view().map(Message::CompressorDemo),
+			Screen::SignalGeneratorDemo(screen) => screen.view().map(Message::SignalGeneratorDemo),
+			Screen::MixerSnapshotDemo(screen) => screen.view().map(Message::MixerSnapshotDemo),
+			Screen::PauseMenuDemo(screen) => screen.view().map(Message::PauseMenuDemo),
+			Screen::RadioVoiceDemo(screen) => screen.view().map(Message::RadioVoiceDemo),
+			Screen::FootstepSurfaceDemo(screen) => screen.view().map(Message::FootstepSurfaceDemo),
+			Screen::EngineDemo(screen) => screen.view().map(Message::EngineDemo),
+			Screen::WeaponDemo(screen) => screen.view().map(Message::WeaponDemo),
+			Screen::UiSoundsDemo(screen) => screen.view().map(Message::UiSoundsDemo),
+			Screen::LowHealthDemo(screen) => screen.view().map(Message::LowHealthDemo),
+			Screen::KaraokeDemo(screen) => screen.view().map(Message::KaraokeDemo),
+			Screen::CountInDemo(screen) => screen.view().map(Message::CountInDemo),
+			Screen::StemMuteSoloDemo(screen) => screen.view().map(Message::StemMuteSoloDemo),
+			Screen::BeatRepeatDemo(screen) => screen.view().map(Message::BeatRepeatDemo),
+			Screen::VinylBrakeDemo(screen) => screen.view().map(Message::VinylBrakeDemo),
+			Screen::WindDemo(screen) => screen.view().map(Message::WindDemo),
+		};
+		let screen = match &self.error {
+			Some(error) => iced::Column::new()
+				.push(
+					Row::new()
+						.padding(8)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(Text::new(format!("Error: {}", error)).width(Length::Fill))
+						.push(
+							Button::new(&mut self.dismiss_error_button, Text::new("Dismiss"))
+								.on_press(Message::DismissError)
+								.style(AppStyles),
+						),
+				)
+				.push(Container::new(screen).height(Length::Fill))
+				.into(),
+			None => screen,
+		};
+		let screen = if recordable {
+			iced::Column::new()
+				.push(Container::new(screen).height(Length::Fill))
+				.push(
+					self.session_recorder.view(
+						Message::ToggleRecording,
+						self.session_replay
+							.as_ref()
+							.map(|(player, _)| player.progress()),
+					),
+				)
+				.into()
+		} else {
+			screen
+		};
+		#[cfg(feature = "midi")]
+		let screen = iced::Column::new()
+			.push(Container::new(screen).height(Length::Fill))
+			.push(self.midi_learn.view(Message::ToggleMidiLearn));
+		#[cfg(feature = "remote")]
+		let screen = iced::Column::new()
+			.push(Container::new(screen).height(Length::Fill))
+			.push(self.remote_control.view());
+		#[cfg(feature = "debug-clock")]
+		let screen = iced::Column::new()
+			.push(Container::new(screen).height(Length::Fill))
+			.push(self.debug_clock.view(
+				tempo,
+				Message::AdvanceVirtualClock,
+				Message::SetTimeScale,
+			));
+		Container::new(screen)
+			.width(Length::Fill)
+			.height(Length::Fill)
+			.style(AppStyles)
+			.into()
+	}
+}
+
+impl App {
+	/// The tempo of the current demo's music, which is used for
+	/// the virtual clock's "advance" buttons and the beat
+	/// timestamps of recorded sessions.
+	fn tempo(&self) -> Tempo {
+		match &self.screen {
+			Screen::DrumFillDemo(_) => drum_fill_demo::TEMPO,
+			Screen::UnderwaterDemo(_) | Screen::StreamingDemo(_) => underwater_demo::TEMPO,
+			Screen::StartOffsetDemo(_) => start_offset_demo::TEMPO,
+			Screen::GroupHierarchyDemo(_) => group_hierarchy_demo::TEMPO,
+			Screen::SendReturnDemo(_) => send_return_demo::TEMPO,
+			Screen::TuningDemo(_) => tuning_demo::TEMPO,
+			Screen::LayeringDemo(_) => layering_demo::TEMPO,
+			Screen::StingerDemo(_) => stinger_demo::TEMPO,
+			Screen::TempoRampDemo(_) => tempo_ramp_demo::TEMPO,
+			Screen::MixerConsoleDemo(_) => mixer_console_demo::TEMPO,
+			Screen::BranchingMusicDemo(_) => branching_music_demo::TEMPO,
+			Screen::CombatMusicDemo(_) => combat_music_demo::TEMPO,
+			Screen::SeekDemo(_) => seek_demo::TEMPO,
+			Screen::TransitionMatrixDemo(_) => transition_matrix_demo::TEMPO,
+			Screen::RhythmGameDemo(_) => rhythm_game_demo::TEMPO,
+			Screen::ChordProgressionDemo(_) => chord_progression_demo::TEMPO,
+			Screen::SwingDemo(_) => swing_demo::TEMPO,
+			Screen::OddTimeSignatureDemo(demo) => demo.tempo(),
+			Screen::MixerSnapshotDemo(_) => mixer_snapshot_demo::TEMPO,
+			Screen::CountInDemo(_) => drum_fill_demo::TEMPO,
+			Screen::StemMuteSoloDemo(_) => underwater_demo::TEMPO,
+			Screen::BeatRepeatDemo(_) => drum_fill_demo::TEMPO,
+			// the scheduling accuracy demo clicks every half second
+			_ => Tempo(120.0),
+		}
+	}
+
+	/// Gets the messages a media key press sends to the current
+	/// screen.
+	fn media_key_messages(&self, key: MediaKey) -> Vec<Message> {
+		match &self.screen {
+			Screen::DrumFillDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::DrumFillDemo)
+				.collect(),
 			Screen::UnderwaterDemo(screen) => screen
-				.view()
-				.map(|message| Message::UnderwaterDemo(message)),
-		})
-		.width(Length::Fill)
-		.height(Length::Fill)
-		.style(AppStyles)
-		.into()
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::UnderwaterDemo)
+				.collect(),
+			Screen::SendReturnDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::SendReturnDemo)
+				.collect(),
+			Screen::StreamingDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::StreamingDemo)
+				.collect(),
+			Screen::TuningDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::TuningDemo)
+				.collect(),
+			Screen::CustomLoopDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::CustomLoopDemo)
+				.collect(),
+			Screen::CrossfadeDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::CrossfadeDemo)
+				.collect(),
+			Screen::LayeringDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::LayeringDemo)
+				.collect(),
+			Screen::StingerDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::StingerDemo)
+				.collect(),
+			Screen::FootstepDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::FootstepDemo)
+				.collect(),
+			Screen::ReverbDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::ReverbDemo)
+				.collect(),
+			Screen::DuckingDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::DuckingDemo)
+				.collect(),
+			Screen::DialogueDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::DialogueDemo)
+				.collect(),
+			Screen::PitchPlayground(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::PitchPlayground)
+				.collect(),
+			Screen::GranularDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::GranularDemo)
+				.collect(),
+			Screen::TempoRampDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::TempoRampDemo)
+				.collect(),
+			Screen::LoopRegionDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::LoopRegionDemo)
+				.collect(),
+			Screen::JukeboxDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::JukeboxDemo)
+				.collect(),
+			Screen::StepSequencerDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::StepSequencerDemo)
+				.collect(),
+			Screen::ArrangementEditorDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::ArrangementEditorDemo)
+				.collect(),
+			Screen::MixerConsoleDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::MixerConsoleDemo)
+				.collect(),
+			Screen::FilterDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::FilterDemo)
+				.collect(),
+			Screen::DelayDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::DelayDemo)
+				.collect(),
+			Screen::DistortionDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::DistortionDemo)
+				.collect(),
+			Screen::LfoDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::LfoDemo)
+				.collect(),
+			Screen::BranchingMusicDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::BranchingMusicDemo)
+				.collect(),
+			Screen::OcclusionDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::OcclusionDemo)
+				.collect(),
+			Screen::WeatherDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::WeatherDemo)
+				.collect(),
+			Screen::CombatMusicDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::CombatMusicDemo)
+				.collect(),
+			Screen::SoundContainerDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::SoundContainerDemo)
+				.collect(),
+			Screen::SeekDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::SeekDemo)
+				.collect(),
+			Screen::PanningDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::PanningDemo)
+				.collect(),
+			Screen::TransitionMatrixDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::TransitionMatrixDemo)
+				.collect(),
+			Screen::RhythmGameDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::RhythmGameDemo)
+				.collect(),
+			Screen::SpectrumDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::SpectrumDemo)
+				.collect(),
+			Screen::GenerativeAmbientDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::GenerativeAmbientDemo)
+				.collect(),
+			Screen::ChordProgressionDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::ChordProgressionDemo)
+				.collect(),
+			Screen::SwingDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::SwingDemo)
+				.collect(),
+			Screen::OddTimeSignatureDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::OddTimeSignatureDemo)
+				.collect(),
+			Screen::StereoWidthDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::StereoWidthDemo)
+				.collect(),
+			Screen::ParametricEqDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::ParametricEqDemo)
+				.collect(),
+			Screen::CompressorDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::CompressorDemo)
+				.collect(),
+			Screen::SignalGeneratorDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::SignalGeneratorDemo)
+				.collect(),
+			Screen::MixerSnapshotDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::MixerSnapshotDemo)
+				.collect(),
+			Screen::PauseMenuDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::PauseMenuDemo)
+				.collect(),
+			Screen::RadioVoiceDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::RadioVoiceDemo)
+				.collect(),
+			Screen::EngineDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::EngineDemo)
+				.collect(),
+			Screen::WeaponDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::WeaponDemo)
+				.collect(),
+			Screen::LowHealthDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::LowHealthDemo)
+				.collect(),
+			Screen::KaraokeDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::KaraokeDemo)
+				.collect(),
+			Screen::CountInDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::CountInDemo)
+				.collect(),
+			Screen::StemMuteSoloDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::StemMuteSoloDemo)
+				.collect(),
+			Screen::BeatRepeatDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::BeatRepeatDemo)
+				.collect(),
+			Screen::VinylBrakeDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::VinylBrakeDemo)
+				.collect(),
+			Screen::WindDemo(screen) => screen
+				.media_key_messages(key)
+				.into_iter()
+				.map(Message::WindDemo)
+				.collect(),
+			_ => vec![],
+		}
+	}
+
+	/// Whether the current screen is playing sounds from the UI
+	/// timer (instead of scheduling them with kira).
+	fn plays_from_ui_timer(&self) -> bool {
+		match &self.screen {
+			Screen::RetriggerCooldownDemo(screen) => screen.plays_from_ui_timer(),
+			Screen::SchedulingAccuracyDemo(screen) => screen.plays_from_ui_timer(),
+			Screen::PolyphonyDemo(screen) => screen.plays_from_ui_timer(),
+			Screen::FootstepDemo(screen) => screen.plays_from_ui_timer(),
+			Screen::SoundContainerDemo(screen) => screen.plays_from_ui_timer(),
+			Screen::GranularDemo(screen) => screen.plays_from_ui_timer(),
+			Screen::WeaponDemo(screen) => screen.plays_from_ui_timer(),
+			_ => false,
+		}
+	}
+
+	/// Gets the message an undo shortcut sends to the current
+	/// screen, if it's an editor.
+	fn undo_shortcut_message(&self, shortcut: UndoShortcut) -> Option<Message> {
+		match &self.screen {
+			Screen::StepSequencerDemo(screen) => screen
+				.undo_shortcut_message(shortcut)
+				.map(Message::StepSequencerDemo),
+			Screen::ArrangementEditorDemo(screen) => screen
+				.undo_shortcut_message(shortcut)
+				.map(Message::ArrangementEditorDemo),
+			Screen::MixerConsoleDemo(screen) => screen
+				.undo_shortcut_message(shortcut)
+				.map(Message::MixerConsoleDemo),
+			_ => None,
+		}
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		match &self.screen {
+			Screen::DrumFillDemo(screen) => screen.now_playing(),
+			Screen::UnderwaterDemo(screen) => screen.now_playing(),
+			Screen::SendReturnDemo(screen) => screen.now_playing(),
+			Screen::StreamingDemo(screen) => screen.now_playing(),
+			Screen::TuningDemo(screen) => screen.now_playing(),
+			Screen::CustomLoopDemo(screen) => screen.now_playing(),
+			Screen::CrossfadeDemo(screen) => screen.now_playing(),
+			Screen::LayeringDemo(screen) => screen.now_playing(),
+			Screen::StingerDemo(screen) => screen.now_playing(),
+			Screen::FootstepDemo(screen) => screen.now_playing(),
+			Screen::ReverbDemo(screen) => screen.now_playing(),
+			Screen::DuckingDemo(screen) => screen.now_playing(),
+			Screen::DialogueDemo(screen) => screen.now_playing(),
+			Screen::PitchPlayground(screen) => screen.now_playing(),
+			Screen::GranularDemo(screen) => screen.now_playing(),
+			Screen::TempoRampDemo(screen) => screen.now_playing(),
+			Screen::LoopRegionDemo(screen) => screen.now_playing(),
+			Screen::JukeboxDemo(screen) => screen.now_playing(),
+			Screen::StepSequencerDemo(screen) => screen.now_playing(),
+			Screen::ArrangementEditorDemo(screen) => screen.now_playing(),
+			Screen::MixerConsoleDemo(screen) => screen.now_playing(),
+			Screen::FilterDemo(screen) => screen.now_playing(),
+			Screen::DelayDemo(screen) => screen.now_playing(),
+			Screen::DistortionDemo(screen) => screen.now_playing(),
+			Screen::LfoDemo(screen) => screen.now_playing(),
+			Screen::BranchingMusicDemo(screen) => screen.now_playing(),
+			Screen::OcclusionDemo(screen) => screen.now_playing(),
+			Screen::WeatherDemo(screen) => screen.now_playing(),
+			Screen::CombatMusicDemo(screen) => screen.now_playing(),
+			Screen::SoundContainerDemo(screen) => screen.now_playing(),
+			Screen::SeekDemo(screen) => screen.now_playing(),
+			Screen::PanningDemo(screen) => screen.now_playing(),
+			Screen::TransitionMatrixDemo(screen) => screen.now_playing(),
+			Screen::RhythmGameDemo(screen) => screen.now_playing(),
+			Screen::SpectrumDemo(screen) => screen.now_playing(),
+			Screen::GenerativeAmbientDemo(screen) => screen.now_playing(),
+			Screen::ChordProgressionDemo(screen) => screen.now_playing(),
+			Screen::SwingDemo(screen) => screen.now_playing(),
+			Screen::OddTimeSignatureDemo(screen) => screen.now_playing(),
+			Screen::StereoWidthDemo(screen) => screen.now_playing(),
+			Screen::ParametricEqDemo(screen) => screen.now_playing(),
+			Screen::CompressorDemo(screen) => screen.now_playing(),
+			Screen::SignalGeneratorDemo(screen) => screen.now_playing(),
+			Screen::MixerSnapshotDemo(screen) => screen.now_playing(),
+			Screen::PauseMenuDemo(screen) => screen.now_playing(),
+			Screen::RadioVoiceDemo(screen) => screen.now_playing(),
+			Screen::EngineDemo(screen) => screen.now_playing(),
+			Screen::WeaponDemo(screen) => screen.now_playing(),
+			Screen::LowHealthDemo(screen) => screen.now_playing(),
+			Screen::KaraokeDemo(screen) => screen.now_playing(),
+			Screen::CountInDemo(screen) => screen.now_playing(),
+			Screen::StemMuteSoloDemo(screen) => screen.now_playing(),
+			Screen::BeatRepeatDemo(screen) => screen.now_playing(),
+			Screen::VinylBrakeDemo(screen) => screen.now_playing(),
+			Screen::WindDemo(screen) => screen.now_playing(),
+			_ => None,
+		}
+	}
+
+	/// Goes back to the demo select screen, remembering the
+	/// controls of the screen the user is leaving.
+	fn go_to_demo_select(&mut self) {
+		self.session_recorder.stop();
+		self.session_replay = None;
+		match &self.screen {
+			Screen::DrumFillDemo(screen) => self.screen_states.save(screen),
+			Screen::UnderwaterDemo(screen) => self.screen_states.save(screen),
+			Screen::StartOffsetDemo(screen) => self.screen_states.save(screen),
+			Screen::RetriggerCooldownDemo(screen) => self.screen_states.save(screen),
+			Screen::GroupHierarchyDemo(screen) => self.screen_states.save(screen),
+			Screen::SendReturnDemo(screen) => self.screen_states.save(screen),
+			Screen::SchedulingAccuracyDemo(screen) => self.screen_states.save(screen),
+			Screen::StreamingDemo(screen) => self.screen_states.save(screen),
+			Screen::DualMetronomeDemo(screen) => self.screen_states.save(screen),
+			Screen::VoiceOverDemo(screen) => self.screen_states.save(screen),
+			Screen::TuningDemo(screen) => self.screen_states.save(screen),
+			Screen::EasingGallery(screen) => self.screen_states.save(screen),
+			Screen::MappingPlayground(screen) => self.screen_states.save(screen),
+			Screen::SurroundDemo(screen) => self.screen_states.save(screen),
+			Screen::WindDemo(screen) => self.screen_states.save(screen),
+			Screen::VinylBrakeDemo(screen) => self.screen_states.save(screen),
+			Screen::BeatRepeatDemo(screen) => self.screen_states.save(screen),
+			Screen::StemMuteSoloDemo(screen) => self.screen_states.save(screen),
+			Screen::CountInDemo(screen) => self.screen_states.save(screen),
+			Screen::KaraokeDemo(screen) => self.screen_states.save(screen),
+			Screen::LowHealthDemo(screen) => self.screen_states.save(screen),
+			Screen::UiSoundsDemo(screen) => self.screen_states.save(screen),
+			Screen::WeaponDemo(screen) => self.screen_states.save(screen),
+			Screen::EngineDemo(screen) => self.screen_states.save(screen),
+			Screen::FootstepSurfaceDemo(screen) => self.screen_states.save(screen),
+			Screen::RadioVoiceDemo(screen) => self.screen_states.save(screen),
+			Screen::PauseMenuDemo(screen) => self.screen_states.save(screen),
+			Screen::MixerSnapshotDemo(screen) => self.screen_states.save(screen),
+			Screen::SignalGeneratorDemo(screen) => self.screen_states.save(screen),
+			Screen::CompressorDemo(screen) => self.screen_states.save(screen),
+			Screen::ParametricEqDemo(screen) => self.screen_states.save(screen),
+			Screen::StereoWidthDemo(screen) => self.screen_states.save(screen),
+			Screen::OddTimeSignatureDemo(screen) => self.screen_states.save(screen),
+			Screen::SwingDemo(screen) => self.screen_states.save(screen),
+			Screen::ChordProgressionDemo(screen) => self.screen_states.save(screen),
+			Screen::GenerativeAmbientDemo(screen) => self.screen_states.save(screen),
+			Screen::SpectrumDemo(screen) => self.screen_states.save(screen),
+			Screen::RhythmGameDemo(screen) => self.screen_states.save(screen),
+			Screen::TransitionMatrixDemo(screen) => self.screen_states.save(screen),
+			Screen::PolyphonyDemo(screen) => self.screen_states.save(screen),
+			Screen::GroupPauseDemo(screen) => self.screen_states.save(screen),
+			Screen::PanningDemo(screen) => self.screen_states.save(screen),
+			Screen::SeekDemo(screen) => self.screen_states.save(screen),
+			Screen::SoundContainerDemo(screen) => self.screen_states.save(screen),
+			Screen::CombatMusicDemo(screen) => self.screen_states.save(screen),
+			Screen::WeatherDemo(screen) => self.screen_states.save(screen),
+			Screen::OcclusionDemo(screen) => self.screen_states.save(screen),
+			Screen::BranchingMusicDemo(screen) => self.screen_states.save(screen),
+			Screen::LfoDemo(screen) => self.screen_states.save(screen),
+			Screen::DistortionDemo(screen) => self.screen_states.save(screen),
+			Screen::DelayDemo(screen) => self.screen_states.save(screen),
+			Screen::FilterDemo(screen) => self.screen_states.save(screen),
+			Screen::MixerConsoleDemo(screen) => self.screen_states.save(screen),
+			Screen::ArrangementEditorDemo(screen) => self.screen_states.save(screen),
+			Screen::StepSequencerDemo(screen) => self.screen_states.save(screen),
+			Screen::SynthKeyboardDemo(screen) => self.screen_states.save(screen),
+			Screen::DrumPadDemo(screen) => self.screen_states.save(screen),
+			Screen::JukeboxDemo(screen) => self.screen_states.save(screen),
+			Screen::LoopRegionDemo(screen) => self.screen_states.save(screen),
+			Screen::TempoRampDemo(screen) => self.screen_states.save(screen),
+			Screen::GranularDemo(screen) => self.screen_states.save(screen),
+			Screen::PitchPlayground(screen) => self.screen_states.save(screen),
+			Screen::DuckingDemo(screen) => self.screen_states.save(screen),
+			Screen::ReverbDemo(screen) => self.screen_states.save(screen),
+			Screen::FootstepDemo(screen) => self.screen_states.save(screen),
+			Screen::DopplerDemo(screen) => self.screen_states.save(screen),
+			Screen::SpatialDemo(screen) => self.screen_states.save(screen),
+			Screen::StingerDemo(screen) => self.screen_states.save(screen),
+			Screen::LayeringDemo(screen) => self.screen_states.save(screen),
+			Screen::CrossfadeDemo(screen) => self.screen_states.save(screen),
+			_ => {}
+		}
+		self.screen = Screen::DemoSelect(DemoSelect::new(self.sound_cache.clone()));
+	}
+
+	/// Sends the current screen the messages from the session
+	/// being replayed that have come due.
+	fn play_session_replay(&mut self) -> Result<(), Box<dyn Error>> {
+		// the replay is taken out while it plays, so if it doesn't
+		// fit the screen, the error is only shown once
+		let (mut player, start_time) = match self.session_replay.take() {
+			Some(replay) => replay,
+			None => return Ok(()),
+		};
+		let time = start_time.elapsed().as_secs_f64();
+		match &mut self.screen {
+			Screen::DrumFillDemo(screen) => {
+				for message in player.due::<DrumFillDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::UnderwaterDemo(screen) => {
+				for message in player.due::<UnderwaterDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::StartOffsetDemo(screen) => {
+				for message in player.due::<StartOffsetDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::RetriggerCooldownDemo(screen) => {
+				for message in player.due::<RetriggerCooldownDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::GroupHierarchyDemo(screen) => {
+				for message in player.due::<GroupHierarchyDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::SendReturnDemo(screen) => {
+				for message in player.due::<SendReturnDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::SchedulingAccuracyDemo(screen) => {
+				for message in player.due::<SchedulingAccuracyDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::StreamingDemo(screen) => {
+				for message in player.due::<StreamingDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::LimitsDemo(screen) => {
+				for message in player.due::<LimitsDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::DualMetronomeDemo(screen) => {
+				for message in player.due::<DualMetronomeDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::VoiceOverDemo(screen) => {
+				for message in player.due::<VoiceOverDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::TuningDemo(screen) => {
+				for message in player.due::<TuningDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::StopFadeDemo(screen) => {
+				for message in player.due::<StopFadeDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::EasingGallery(screen) => {
+				for message in player.due::<EasingGallery>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::MappingPlayground(screen) => {
+				for message in player.due::<MappingPlayground>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::SurroundDemo(screen) => {
+				for message in player.due::<SurroundDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::CrossfadeDemo(screen) => {
+				for message in player.due::<CrossfadeDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::LayeringDemo(screen) => {
+				for message in player.due::<LayeringDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::StingerDemo(screen) => {
+				for message in player.due::<StingerDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::SpatialDemo(screen) => {
+				for message in player.due::<SpatialDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::DopplerDemo(screen) => {
+				for message in player.due::<DopplerDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::FootstepDemo(screen) => {
+				for message in player.due::<FootstepDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::ReverbDemo(screen) => {
+				for message in player.due::<ReverbDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::DuckingDemo(screen) => {
+				for message in player.due::<DuckingDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::PitchPlayground(screen) => {
+				for message in player.due::<PitchPlayground>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::GranularDemo(screen) => {
+				for message in player.due::<GranularDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::TempoRampDemo(screen) => {
+				for message in player.due::<TempoRampDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::LoopRegionDemo(screen) => {
+				for message in player.due::<LoopRegionDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::JukeboxDemo(screen) => {
+				for message in player.due::<JukeboxDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::DrumPadDemo(screen) => {
+				for message in player.due::<DrumPadDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::SynthKeyboardDemo(screen) => {
+				for message in player.due::<SynthKeyboardDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::StepSequencerDemo(screen) => {
+				for message in player.due::<StepSequencerDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::ArrangementEditorDemo(screen) => {
+				for message in player.due::<ArrangementEditorDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::MixerConsoleDemo(screen) => {
+				for message in player.due::<MixerConsoleDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::FilterDemo(screen) => {
+				for message in player.due::<FilterDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::DelayDemo(screen) => {
+				for message in player.due::<DelayDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::DistortionDemo(screen) => {
+				for message in player.due::<DistortionDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::LfoDemo(screen) => {
+				for message in player.due::<LfoDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::BranchingMusicDemo(screen) => {
+				for message in player.due::<BranchingMusicDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::OcclusionDemo(screen) => {
+				for message in player.due::<OcclusionDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::WeatherDemo(screen) => {
+				for message in player.due::<WeatherDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::CombatMusicDemo(screen) => {
+				for message in player.due::<CombatMusicDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::SoundContainerDemo(screen) => {
+				for message in player.due::<SoundContainerDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::SeekDemo(screen) => {
+				for message in player.due::<SeekDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::PanningDemo(screen) => {
+				for message in player.due::<PanningDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::GroupPauseDemo(screen) => {
+				for message in player.due::<GroupPauseDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::PolyphonyDemo(screen) => {
+				for message in player.due::<PolyphonyDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::TransitionMatrixDemo(screen) => {
+				for message in player.due::<TransitionMatrixDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::RhythmGameDemo(screen) => {
+				for message in player.due::<RhythmGameDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::SpectrumDemo(screen) => {
+				for message in player.due::<SpectrumDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::GenerativeAmbientDemo(screen) => {
+				for message in player.due::<GenerativeAmbientDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::ChordProgressionDemo(screen) => {
+				for message in player.due::<ChordProgressionDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::SwingDemo(screen) => {
+				for message in player.due::<SwingDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::OddTimeSignatureDemo(screen) => {
+				for message in player.due::<OddTimeSignatureDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::StereoWidthDemo(screen) => {
+				for message in player.due::<StereoWidthDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::ParametricEqDemo(screen) => {
+				for message in player.due::<ParametricEqDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::CompressorDemo(screen) => {
+				for message in player.due::<CompressorDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::SignalGeneratorDemo(screen) => {
+				for message in player.due::<SignalGeneratorDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::MixerSnapshotDemo(screen) => {
+				for message in player.due::<MixerSnapshotDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::PauseMenuDemo(screen) => {
+				for message in player.due::<PauseMenuDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::RadioVoiceDemo(screen) => {
+				for message in player.due::<RadioVoiceDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::FootstepSurfaceDemo(screen) => {
+				for message in player.due::<FootstepSurfaceDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::EngineDemo(screen) => {
+				for message in player.due::<EngineDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::WeaponDemo(screen) => {
+				for message in player.due::<WeaponDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::UiSoundsDemo(screen) => {
+				for message in player.due::<UiSoundsDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::LowHealthDemo(screen) => {
+				for message in player.due::<LowHealthDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::KaraokeDemo(screen) => {
+				for message in player.due::<KaraokeDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::CountInDemo(screen) => {
+				for message in player.due::<CountInDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::StemMuteSoloDemo(screen) => {
+				for message in player.due::<StemMuteSoloDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::BeatRepeatDemo(screen) => {
+				for message in player.due::<BeatRepeatDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::VinylBrakeDemo(screen) => {
+				for message in player.due::<VinylBrakeDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::WindDemo(screen) => {
+				for message in player.due::<WindDemo>(time)? {
+					screen.update(message)?;
+				}
+			}
+			_ => {
+				return Err(format!(
+					"sessions can't be replayed on this screen ({})",
+					player.screen()
+				)
+				.into());
+			}
+		}
+		if !player.is_finished() {
+			self.session_replay = Some((player, start_time));
+		}
+		Ok(())
+	}
+
+	fn try_update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		#[cfg(feature = "midi")]
+		let check_midi = matches!(message, Message::CheckForEvents);
+		#[cfg(feature = "remote")]
+		let check_remote = matches!(message, Message::CheckForEvents);
+		#[cfg(feature = "tray")]
+		let check_tray = matches!(message, Message::CheckForEvents);
+		if let Message::CheckForEvents = message {
+			self.play_session_replay()?;
+		}
+		match message {
+			Message::CheckForEvents => match &mut self.screen {
+				Screen::DrumFillDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::StartOffsetDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::RetriggerCooldownDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::SchedulingAccuracyDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::LimitsDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::UnderwaterDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::SendReturnDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::StreamingDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::DualMetronomeDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::TuningDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::StopFadeDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::EasingGallery(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::CustomLoopDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::SurroundDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::NetSyncDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::CrossfadeDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::LayeringDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::StingerDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::DopplerDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::FootstepDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::DuckingDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::DialogueDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::GranularDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::TempoRampDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::LoopRegionDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::JukeboxDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::DrumPadDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::StepSequencerDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::ArrangementEditorDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::MixerConsoleDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::DelayDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::LfoDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::BranchingMusicDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::WeatherDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::CombatMusicDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::SoundContainerDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::SeekDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::PolyphonyDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::TransitionMatrixDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::RhythmGameDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::SpectrumDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::GenerativeAmbientDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::ChordProgressionDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::SwingDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::OddTimeSignatureDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::CompressorDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::MixerSnapshotDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::RadioVoiceDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::EngineDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::WeaponDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::KaraokeDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::CountInDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::StemMuteSoloDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::BeatRepeatDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::VinylBrakeDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				Screen::WindDemo(screen) => {
+					screen.check_for_events()?;
+				}
+				_ => {}
+			},
+			Message::SetMinimized(minimized) => {
+				self.minimized = minimized;
+			}
+			// the custom loop demo opens audio files, and recorded
+			// sessions are replayed on the demo they're dropped on
+			Message::FileDropped(path) => {
+				if let Screen::CustomLoopDemo(screen) = &mut self.screen {
+					screen.open(path)?;
+				} else if path
+					.extension()
+					.is_some_and(|extension| extension == "json")
+				{
+					self.session_replay = Some((SessionPlayer::load(&path)?, Instant::now()));
+				}
+			}
+			Message::DismissError => {
+				self.error = None;
+			}
+			// the key presses are turned into the messages the
+			// screen's own buttons send, so they're recorded too
+			Message::MediaKeyPressed(key) => {
+				for message in self.media_key_messages(key) {
+					self.try_update(message)?;
+				}
+			}
+			Message::UndoShortcutPressed(shortcut) => {
+				if let Some(message) = self.undo_shortcut_message(shortcut) {
+					self.try_update(message)?;
+				}
+			}
+			// only the footstep surface demo is walked around with
+			// the arrow keys
+			Message::ArrowKeyPressed(direction) => {
+				if let Screen::FootstepSurfaceDemo(_) = &self.screen {
+					self.try_update(Message::FootstepSurfaceDemo(
+						footstep_surface_demo::Message::Walk(direction),
+					))?;
+				}
+			}
+			Message::ToggleRecording => {
+				if self.session_recorder.is_recording() {
+					self.session_recorder.stop();
+				} else {
+					let tempo = self.tempo();
+					match &self.screen {
+						Screen::DrumFillDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::UnderwaterDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::StartOffsetDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::RetriggerCooldownDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::GroupHierarchyDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::SendReturnDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::SchedulingAccuracyDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::StreamingDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::LimitsDemo(screen) => self.session_recorder.start(screen, tempo)?,
+						Screen::DualMetronomeDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::VoiceOverDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::TuningDemo(screen) => self.session_recorder.start(screen, tempo)?,
+						Screen::StopFadeDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::EasingGallery(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::MappingPlayground(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::SurroundDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::CrossfadeDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::LayeringDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::StingerDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::SpatialDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::DopplerDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::FootstepDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::ReverbDemo(screen) => self.session_recorder.start(screen, tempo)?,
+						Screen::DuckingDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::PitchPlayground(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::GranularDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::TempoRampDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::LoopRegionDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::JukeboxDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::DrumPadDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::SynthKeyboardDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::StepSequencerDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::ArrangementEditorDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::MixerConsoleDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::FilterDemo(screen) => self.session_recorder.start(screen, tempo)?,
+						Screen::DelayDemo(screen) => self.session_recorder.start(screen, tempo)?,
+						Screen::DistortionDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::LfoDemo(screen) => self.session_recorder.start(screen, tempo)?,
+						Screen::BranchingMusicDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::OcclusionDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::WeatherDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::CombatMusicDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::SoundContainerDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::SeekDemo(screen) => self.session_recorder.start(screen, tempo)?,
+						Screen::PanningDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::GroupPauseDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::PolyphonyDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::TransitionMatrixDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::RhythmGameDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::SpectrumDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::GenerativeAmbientDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::ChordProgressionDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::SwingDemo(screen) => self.session_recorder.start(screen, tempo)?,
+						Screen::OddTimeSignatureDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::StereoWidthDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::ParametricEqDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::CompressorDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::SignalGeneratorDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::MixerSnapshotDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::PauseMenuDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::RadioVoiceDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::FootstepSurfaceDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::EngineDemo(screen) => self.session_recorder.start(screen, tempo)?,
+						Screen::WeaponDemo(screen) => self.session_recorder.start(screen, tempo)?,
+						Screen::UiSoundsDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::LowHealthDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::KaraokeDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::CountInDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::StemMuteSoloDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::BeatRepeatDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::VinylBrakeDemo(screen) => {
+							self.session_recorder.start(screen, tempo)?
+						}
+						Screen::WindDemo(screen) => self.session_recorder.start(screen, tempo)?,
+						_ => {}
+					}
+				}
+			}
+			#[cfg(feature = "midi")]
+			Message::ToggleMidiLearn => {
+				self.midi_learn.toggle_learning();
+			}
+			#[cfg(feature = "debug-clock")]
+			Message::AdvanceVirtualClock(duration) => {
+				self.debug_clock.advance(duration);
+			}
+			#[cfg(feature = "debug-clock")]
+			Message::SetTimeScale(time_scale) => {
+				self.debug_clock.set_time_scale(time_scale);
+			}
+			Message::DemoSelect(message) => {
+				let audio_manager_settings =
+					self.preferences.audio_capacities.audio_manager_settings();
+				match message {
+					demo_select::Message::GoToDrumFillDemo => {
+						let mut screen = DrumFillDemo::new(
+							&self.sound_cache,
+							audio_manager_settings,
+							self.preferences.beat_display_style,
+						)?;
+						for message in self.screen_states.take::<DrumFillDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::DrumFillDemo(screen);
+					}
+					demo_select::Message::GoToUnderwaterDemo => {
+						let mut screen =
+							UnderwaterDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<UnderwaterDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::UnderwaterDemo(screen);
+					}
+					demo_select::Message::GoToStartOffsetDemo => {
+						let mut screen =
+							StartOffsetDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<StartOffsetDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::StartOffsetDemo(screen);
+					}
+					demo_select::Message::GoToRetriggerCooldownDemo => {
+						let mut screen = RetriggerCooldownDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<RetriggerCooldownDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::RetriggerCooldownDemo(screen);
+					}
+					demo_select::Message::GoToGroupHierarchyDemo => {
+						let mut screen =
+							GroupHierarchyDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<GroupHierarchyDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::GroupHierarchyDemo(screen);
+					}
+					demo_select::Message::GoToSendReturnDemo => {
+						let mut screen =
+							SendReturnDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<SendReturnDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::SendReturnDemo(screen);
+					}
+					demo_select::Message::GoToSchedulingAccuracyDemo => {
+						let mut screen = SchedulingAccuracyDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<SchedulingAccuracyDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::SchedulingAccuracyDemo(screen);
+					}
+					demo_select::Message::GoToStreamingDemo => {
+						let mut screen = StreamingDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<StreamingDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::StreamingDemo(screen);
+					}
+					demo_select::Message::GoToSettings => {
+						self.screen = Screen::Settings(Settings::new(self.preferences.clone()));
+					}
+					demo_select::Message::GoToLimitsDemo => {
+						self.screen = Screen::LimitsDemo(LimitsDemo::new(audio_manager_settings)?);
+					}
+					demo_select::Message::GoToDualMetronomeDemo => {
+						let mut screen = DualMetronomeDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<DualMetronomeDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::DualMetronomeDemo(screen);
+					}
+					demo_select::Message::GoToVoiceOverDemo => {
+						let mut screen = VoiceOverDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<VoiceOverDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::VoiceOverDemo(screen);
+					}
+					demo_select::Message::GoToTuningDemo => {
+						let mut screen = TuningDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<TuningDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::TuningDemo(screen);
+					}
+					demo_select::Message::GoToStopFadeDemo => {
+						self.screen = Screen::StopFadeDemo(StopFadeDemo::new(
+							&self.sound_cache,
+							audio_manager_settings,
+						)?);
+					}
+					demo_select::Message::GoToEasingGallery => {
+						let mut screen =
+							EasingGallery::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<EasingGallery>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::EasingGallery(screen);
+					}
+					demo_select::Message::GoToMappingPlayground => {
+						let mut screen =
+							MappingPlayground::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<MappingPlayground>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::MappingPlayground(screen);
+					}
+					demo_select::Message::GoToCustomLoopDemo => {
+						self.screen = Screen::CustomLoopDemo(CustomLoopDemo::new(
+							audio_manager_settings,
+							self.preferences.custom_loop.clone(),
+						)?);
+					}
+					demo_select::Message::GoToSurroundDemo => {
+						let mut screen = SurroundDemo::new(
+							&self.sound_cache,
+							audio_manager_settings,
+							self.preferences.output_mode,
+						)?;
+						for message in self.screen_states.take::<SurroundDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::SurroundDemo(screen);
+					}
+					demo_select::Message::GoToNetSyncDemo => {
+						self.screen = Screen::NetSyncDemo(NetSyncDemo::new(
+							&self.sound_cache,
+							audio_manager_settings,
+						)?);
+					}
+					demo_select::Message::GoToCrossfadeDemo => {
+						let mut screen =
+							CrossfadeDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<CrossfadeDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::CrossfadeDemo(screen);
+					}
+					demo_select::Message::GoToLayeringDemo => {
+						let mut screen =
+							LayeringDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<LayeringDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::LayeringDemo(screen);
+					}
+					demo_select::Message::GoToStingerDemo => {
+						let mut screen =
+							StingerDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<StingerDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::StingerDemo(screen);
+					}
+					demo_select::Message::GoToSpatialDemo => {
+						let mut screen = SpatialDemo::new(
+							&self.sound_cache,
+							audio_manager_settings,
+							self.preferences.output_mode,
+						)?;
+						for message in self.screen_states.take::<SpatialDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::SpatialDemo(screen);
+					}
+					demo_select::Message::GoToDopplerDemo => {
+						let mut screen =
+							DopplerDemo::new(audio_manager_settings, self.preferences.output_mode)?;
+						for message in self.screen_states.take::<DopplerDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::DopplerDemo(screen);
+					}
+					demo_select::Message::GoToFootstepDemo => {
+						let mut screen = FootstepDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<FootstepDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::FootstepDemo(screen);
+					}
+					demo_select::Message::GoToReverbDemo => {
+						let mut screen =
+							ReverbDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<ReverbDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::ReverbDemo(screen);
+					}
+					demo_select::Message::GoToDuckingDemo => {
+						let mut screen =
+							DuckingDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<DuckingDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::DuckingDemo(screen);
+					}
+					demo_select::Message::GoToDialogueDemo => {
+						self.screen =
+							Screen::DialogueDemo(DialogueDemo::new(audio_manager_settings)?);
+					}
+					demo_select::Message::GoToPitchPlayground => {
+						let mut screen =
+							PitchPlayground::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<PitchPlayground>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::PitchPlayground(screen);
+					}
+					demo_select::Message::GoToGranularDemo => {
+						let mut screen =
+							GranularDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<GranularDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::GranularDemo(screen);
+					}
+					demo_select::Message::GoToTempoRampDemo => {
+						let mut screen =
+							TempoRampDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<TempoRampDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::TempoRampDemo(screen);
+					}
+					demo_select::Message::GoToLoopRegionDemo => {
+						let mut screen =
+							LoopRegionDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<LoopRegionDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::LoopRegionDemo(screen);
+					}
+					demo_select::Message::GoToJukeboxDemo => {
+						let mut screen =
+							JukeboxDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<JukeboxDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::JukeboxDemo(screen);
+					}
+					demo_select::Message::GoToDrumPadDemo => {
+						let mut screen = DrumPadDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<DrumPadDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::DrumPadDemo(screen);
+					}
+					demo_select::Message::GoToSynthKeyboardDemo => {
+						let mut screen = SynthKeyboardDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<SynthKeyboardDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::SynthKeyboardDemo(screen);
+					}
+					demo_select::Message::GoToStepSequencerDemo => {
+						let mut screen = StepSequencerDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<StepSequencerDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::StepSequencerDemo(screen);
+					}
+					demo_select::Message::GoToArrangementEditorDemo => {
+						let mut screen =
+							ArrangementEditorDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<ArrangementEditorDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::ArrangementEditorDemo(screen);
+					}
+					demo_select::Message::GoToMixerConsoleDemo => {
+						let mut screen =
+							MixerConsoleDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<MixerConsoleDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::MixerConsoleDemo(screen);
+					}
+					demo_select::Message::GoToFilterDemo => {
+						let mut screen =
+							FilterDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<FilterDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::FilterDemo(screen);
+					}
+					demo_select::Message::GoToDelayDemo => {
+						let mut screen = DelayDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<DelayDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::DelayDemo(screen);
+					}
+					demo_select::Message::GoToDistortionDemo => {
+						let mut screen =
+							DistortionDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<DistortionDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::DistortionDemo(screen);
+					}
+					demo_select::Message::GoToLfoDemo => {
+						let mut screen = LfoDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<LfoDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::LfoDemo(screen);
+					}
+					demo_select::Message::GoToBranchingMusicDemo => {
+						let mut screen =
+							BranchingMusicDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<BranchingMusicDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::BranchingMusicDemo(screen);
+					}
+					demo_select::Message::GoToOcclusionDemo => {
+						let mut screen = OcclusionDemo::new(
+							&self.sound_cache,
+							audio_manager_settings,
+							self.preferences.output_mode,
+						)?;
+						for message in self.screen_states.take::<OcclusionDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::OcclusionDemo(screen);
+					}
+					demo_select::Message::GoToWeatherDemo => {
+						let mut screen = WeatherDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<WeatherDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::WeatherDemo(screen);
+					}
+					demo_select::Message::GoToCombatMusicDemo => {
+						let mut screen =
+							CombatMusicDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<CombatMusicDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::CombatMusicDemo(screen);
+					}
+					demo_select::Message::GoToSoundContainerDemo => {
+						let mut screen = SoundContainerDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<SoundContainerDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::SoundContainerDemo(screen);
+					}
+					demo_select::Message::GoToSeekDemo => {
+						let mut screen = SeekDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<SeekDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::SeekDemo(screen);
+					}
+					demo_select::Message::GoToPanningDemo => {
+						let mut screen =
+							PanningDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<PanningDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::PanningDemo(screen);
+					}
+					demo_select::Message::GoToGroupPauseDemo => {
+						let mut screen =
+							GroupPauseDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<GroupPauseDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::GroupPauseDemo(screen);
+					}
+					demo_select::Message::GoToPolyphonyDemo => {
+						let mut screen = PolyphonyDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<PolyphonyDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::PolyphonyDemo(screen);
+					}
+					demo_select::Message::GoToTransitionMatrixDemo => {
+						let mut screen =
+							TransitionMatrixDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<TransitionMatrixDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::TransitionMatrixDemo(screen);
+					}
+					demo_select::Message::GoToRhythmGameDemo => {
+						let mut screen = RhythmGameDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<RhythmGameDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::RhythmGameDemo(screen);
+					}
+					demo_select::Message::GoToSpectrumDemo => {
+						let mut screen =
+							SpectrumDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<SpectrumDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::SpectrumDemo(screen);
+					}
+					demo_select::Message::GoToGenerativeAmbientDemo => {
+						let mut screen = GenerativeAmbientDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<GenerativeAmbientDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::GenerativeAmbientDemo(screen);
+					}
+					demo_select::Message::GoToChordProgressionDemo => {
+						let mut screen = ChordProgressionDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<ChordProgressionDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::ChordProgressionDemo(screen);
+					}
+					demo_select::Message::GoToSwingDemo => {
+						let mut screen = SwingDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<SwingDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::SwingDemo(screen);
+					}
+					demo_select::Message::GoToOddTimeSignatureDemo => {
+						let mut screen = OddTimeSignatureDemo::new(
+							audio_manager_settings,
+							self.preferences.beat_display_style,
+						)?;
+						for message in self.screen_states.take::<OddTimeSignatureDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::OddTimeSignatureDemo(screen);
+					}
+					demo_select::Message::GoToStereoWidthDemo => {
+						let mut screen =
+							StereoWidthDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<StereoWidthDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::StereoWidthDemo(screen);
+					}
+					demo_select::Message::GoToParametricEqDemo => {
+						let mut screen =
+							ParametricEqDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<ParametricEqDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::ParametricEqDemo(screen);
+					}
+					demo_select::Message::GoToCompressorDemo => {
+						let mut screen =
+							CompressorDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<CompressorDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::CompressorDemo(screen);
+					}
+					demo_select::Message::GoToSignalGeneratorDemo => {
+						let mut screen = SignalGeneratorDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<SignalGeneratorDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::SignalGeneratorDemo(screen);
+					}
+					demo_select::Message::GoToMixerSnapshotDemo => {
+						let mut screen =
+							MixerSnapshotDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<MixerSnapshotDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::MixerSnapshotDemo(screen);
+					}
+					demo_select::Message::GoToPauseMenuDemo => {
+						let mut screen =
+							PauseMenuDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<PauseMenuDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::PauseMenuDemo(screen);
+					}
+					demo_select::Message::GoToRadioVoiceDemo => {
+						let mut screen = RadioVoiceDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<RadioVoiceDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::RadioVoiceDemo(screen);
+					}
+					demo_select::Message::GoToFootstepSurfaceDemo => {
+						let mut screen = FootstepSurfaceDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<FootstepSurfaceDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::FootstepSurfaceDemo(screen);
+					}
+					demo_select::Message::GoToEngineDemo => {
+						let mut screen = EngineDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<EngineDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::EngineDemo(screen);
+					}
+					demo_select::Message::GoToWeaponDemo => {
+						let mut screen = WeaponDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<WeaponDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::WeaponDemo(screen);
+					}
+					demo_select::Message::GoToUiSoundsDemo => {
+						let mut screen = UiSoundsDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<UiSoundsDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::UiSoundsDemo(screen);
+					}
+					demo_select::Message::GoToLowHealthDemo => {
+						let mut screen =
+							LowHealthDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<LowHealthDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::LowHealthDemo(screen);
+					}
+					demo_select::Message::GoToKaraokeDemo => {
+						let mut screen = KaraokeDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<KaraokeDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::KaraokeDemo(screen);
+					}
+					demo_select::Message::GoToCountInDemo => {
+						let mut screen =
+							CountInDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<CountInDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::CountInDemo(screen);
+					}
+					demo_select::Message::GoToStemMuteSoloDemo => {
+						let mut screen =
+							StemMuteSoloDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<StemMuteSoloDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::StemMuteSoloDemo(screen);
+					}
+					demo_select::Message::GoToBeatRepeatDemo => {
+						let mut screen =
+							BeatRepeatDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<BeatRepeatDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::BeatRepeatDemo(screen);
+					}
+					demo_select::Message::GoToVinylBrakeDemo => {
+						let mut screen =
+							VinylBrakeDemo::new(&self.sound_cache, audio_manager_settings)?;
+						for message in self.screen_states.take::<VinylBrakeDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::VinylBrakeDemo(screen);
+					}
+					demo_select::Message::GoToWindDemo => {
+						let mut screen = WindDemo::new(audio_manager_settings)?;
+						for message in self.screen_states.take::<WindDemo>() {
+							screen.update(message)?;
+						}
+						self.screen = Screen::WindDemo(screen);
+					}
+				}
+			}
+			Message::DrumFillDemo(message) => match message {
+				drum_fill_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder.record::<DrumFillDemo>(&message)?;
+					if let Screen::DrumFillDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::UnderwaterDemo(message) => match message {
+				underwater_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder.record::<UnderwaterDemo>(&message)?;
+					if let Screen::UnderwaterDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::StartOffsetDemo(message) => match message {
+				start_offset_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder.record::<StartOffsetDemo>(&message)?;
+					if let Screen::StartOffsetDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::RetriggerCooldownDemo(message) => match message {
+				retrigger_cooldown_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<RetriggerCooldownDemo>(&message);
+					self.session_recorder
+						.record::<RetriggerCooldownDemo>(&message)?;
+					if let Screen::RetriggerCooldownDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::GroupHierarchyDemo(message) => match message {
+				group_hierarchy_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder
+						.record::<GroupHierarchyDemo>(&message)?;
+					if let Screen::GroupHierarchyDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::SendReturnDemo(message) => match message {
+				send_return_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<SendReturnDemo>(&message);
+					self.session_recorder.record::<SendReturnDemo>(&message)?;
+					if let Screen::SendReturnDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::SchedulingAccuracyDemo(message) => match message {
+				scheduling_accuracy_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder
+						.record::<SchedulingAccuracyDemo>(&message)?;
+					if let Screen::SchedulingAccuracyDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::StreamingDemo(message) => match message {
+				streaming_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder.record::<StreamingDemo>(&message)?;
+					if let Screen::StreamingDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::Settings(message) => match message {
+				settings::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					if let Screen::Settings(screen) = &mut self.screen {
+						screen.update(message)?;
+						self.preferences = screen.preferences().clone();
+						self.preferences.save()?;
+						#[cfg(feature = "remote")]
+						self.remote_control
+							.set_allowed_commands(&self.preferences.remote_allowed_commands);
+						#[cfg(feature = "remote")]
+						self.remote_control
+							.set_allowed_origins(&self.preferences.remote_allowed_origins);
+					}
+				}
+			},
+			Message::LimitsDemo(message) => match message {
+				limits_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder.record::<LimitsDemo>(&message)?;
+					if let Screen::LimitsDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::DualMetronomeDemo(message) => match message {
+				dual_metronome_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder
+						.record::<DualMetronomeDemo>(&message)?;
+					if let Screen::DualMetronomeDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::VoiceOverDemo(message) => match message {
+				voice_over_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder.record::<VoiceOverDemo>(&message)?;
+					if let Screen::VoiceOverDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::TuningDemo(message) => match message {
+				tuning_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder.record::<TuningDemo>(&message)?;
+					if let Screen::TuningDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::StopFadeDemo(message) => match message {
+				stop_fade_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder.record::<StopFadeDemo>(&message)?;
+					if let Screen::StopFadeDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::EasingGallery(message) => match message {
+				easing_gallery::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder.record::<EasingGallery>(&message)?;
+					if let Screen::EasingGallery(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::MappingPlayground(message) => match message {
+				mapping_playground::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder
+						.record::<MappingPlayground>(&message)?;
+					if let Screen::MappingPlayground(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::CustomLoopDemo(message) => match message {
+				custom_loop_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					if let Screen::CustomLoopDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+						if screen.custom_loop() != self.preferences.custom_loop.as_ref() {
+							self.preferences.custom_loop = screen.custom_loop().cloned();
+							self.preferences.save()?;
+						}
+					}
+				}
+			},
+			Message::SurroundDemo(message) => match message {
+				surround_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder.record::<SurroundDemo>(&message)?;
+					if let Screen::SurroundDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::NetSyncDemo(message) => match message {
+				net_sync_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					if let Screen::NetSyncDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::CrossfadeDemo(message) => match message {
+				crossfade_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<CrossfadeDemo>(&message);
+					self.session_recorder.record::<CrossfadeDemo>(&message)?;
+					if let Screen::CrossfadeDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::LayeringDemo(message) => match message {
+				layering_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<LayeringDemo>(&message);
+					self.session_recorder.record::<LayeringDemo>(&message)?;
+					if let Screen::LayeringDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::StingerDemo(message) => match message {
+				stinger_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder.record::<StingerDemo>(&message)?;
+					if let Screen::StingerDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::SpatialDemo(message) => match message {
+				spatial_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder.record::<SpatialDemo>(&message)?;
+					if let Screen::SpatialDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::DopplerDemo(message) => match message {
+				doppler_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<DopplerDemo>(&message);
+					self.session_recorder.record::<DopplerDemo>(&message)?;
+					if let Screen::DopplerDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::FootstepDemo(message) => match message {
+				footstep_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<FootstepDemo>(&message);
+					self.session_recorder.record::<FootstepDemo>(&message)?;
+					if let Screen::FootstepDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::ReverbDemo(message) => match message {
+				reverb_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<ReverbDemo>(&message);
+					self.session_recorder.record::<ReverbDemo>(&message)?;
+					if let Screen::ReverbDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::DuckingDemo(message) => match message {
+				ducking_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<DuckingDemo>(&message);
+					self.session_recorder.record::<DuckingDemo>(&message)?;
+					if let Screen::DuckingDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::DialogueDemo(message) => match message {
+				dialogue_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					if let Screen::DialogueDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::PitchPlayground(message) => match message {
+				pitch_playground::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<PitchPlayground>(&message);
+					self.session_recorder.record::<PitchPlayground>(&message)?;
+					if let Screen::PitchPlayground(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::GranularDemo(message) => match message {
+				granular_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<GranularDemo>(&message);
+					self.session_recorder.record::<GranularDemo>(&message)?;
+					if let Screen::GranularDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::TempoRampDemo(message) => match message {
+				tempo_ramp_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder.record::<TempoRampDemo>(&message)?;
+					if let Screen::TempoRampDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::LoopRegionDemo(message) => match message {
+				loop_region_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<LoopRegionDemo>(&message);
+					self.session_recorder.record::<LoopRegionDemo>(&message)?;
+					if let Screen::LoopRegionDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::JukeboxDemo(message) => match message {
+				jukebox_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder.record::<JukeboxDemo>(&message)?;
+					if let Screen::JukeboxDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::DrumPadDemo(message) => match message {
+				drum_pad_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<DrumPadDemo>(&message);
+					self.session_recorder.record::<DrumPadDemo>(&message)?;
+					if let Screen::DrumPadDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::SynthKeyboardDemo(message) => match message {
+				synth_keyboard_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<SynthKeyboardDemo>(&message);
+					self.session_recorder
+						.record::<SynthKeyboardDemo>(&message)?;
+					if let Screen::SynthKeyboardDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::StepSequencerDemo(message) => match message {
+				step_sequencer_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder
+						.record::<StepSequencerDemo>(&message)?;
+					if let Screen::StepSequencerDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::ArrangementEditorDemo(message) => match message {
+				arrangement_editor_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder
+						.record::<ArrangementEditorDemo>(&message)?;
+					if let Screen::ArrangementEditorDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::MixerConsoleDemo(message) => match message {
+				mixer_console_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<MixerConsoleDemo>(&message);
+					self.session_recorder.record::<MixerConsoleDemo>(&message)?;
+					if let Screen::MixerConsoleDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::FilterDemo(message) => match message {
+				filter_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<FilterDemo>(&message);
+					self.session_recorder.record::<FilterDemo>(&message)?;
+					if let Screen::FilterDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::DelayDemo(message) => match message {
+				delay_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<DelayDemo>(&message);
+					self.session_recorder.record::<DelayDemo>(&message)?;
+					if let Screen::DelayDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::DistortionDemo(message) => match message {
+				distortion_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<DistortionDemo>(&message);
+					self.session_recorder.record::<DistortionDemo>(&message)?;
+					if let Screen::DistortionDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::LfoDemo(message) => match message {
+				lfo_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<LfoDemo>(&message);
+					self.session_recorder.record::<LfoDemo>(&message)?;
+					if let Screen::LfoDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::BranchingMusicDemo(message) => match message {
+				branching_music_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder
+						.record::<BranchingMusicDemo>(&message)?;
+					if let Screen::BranchingMusicDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::OcclusionDemo(message) => match message {
+				occlusion_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder.record::<OcclusionDemo>(&message)?;
+					if let Screen::OcclusionDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::WeatherDemo(message) => match message {
+				weather_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<WeatherDemo>(&message);
+					self.session_recorder.record::<WeatherDemo>(&message)?;
+					if let Screen::WeatherDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::CombatMusicDemo(message) => match message {
+				combat_music_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<CombatMusicDemo>(&message);
+					self.session_recorder.record::<CombatMusicDemo>(&message)?;
+					if let Screen::CombatMusicDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::SoundContainerDemo(message) => match message {
+				sound_container_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<SoundContainerDemo>(&message);
+					self.session_recorder
+						.record::<SoundContainerDemo>(&message)?;
+					if let Screen::SoundContainerDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::SeekDemo(message) => match message {
+				seek_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder.record::<SeekDemo>(&message)?;
+					if let Screen::SeekDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::PanningDemo(message) => match message {
+				panning_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<PanningDemo>(&message);
+					self.session_recorder.record::<PanningDemo>(&message)?;
+					if let Screen::PanningDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::GroupPauseDemo(message) => match message {
+				group_pause_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<GroupPauseDemo>(&message);
+					self.session_recorder.record::<GroupPauseDemo>(&message)?;
+					if let Screen::GroupPauseDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::PolyphonyDemo(message) => match message {
+				polyphony_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<PolyphonyDemo>(&message);
+					self.session_recorder.record::<PolyphonyDemo>(&message)?;
+					if let Screen::PolyphonyDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::TransitionMatrixDemo(message) => match message {
+				transition_matrix_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder
+						.record::<TransitionMatrixDemo>(&message)?;
+					if let Screen::TransitionMatrixDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::RhythmGameDemo(message) => match message {
+				rhythm_game_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<RhythmGameDemo>(&message);
+					self.session_recorder.record::<RhythmGameDemo>(&message)?;
+					if let Screen::RhythmGameDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::SpectrumDemo(message) => match message {
+				spectrum_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<SpectrumDemo>(&message);
+					self.session_recorder.record::<SpectrumDemo>(&message)?;
+					if let Screen::SpectrumDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::GenerativeAmbientDemo(message) => match message {
+				generative_ambient_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<GenerativeAmbientDemo>(&message);
+					self.session_recorder
+						.record::<GenerativeAmbientDemo>(&message)?;
+					if let Screen::GenerativeAmbientDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::ChordProgressionDemo(message) => match message {
+				chord_progression_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder
+						.record::<ChordProgressionDemo>(&message)?;
+					if let Screen::ChordProgressionDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::SwingDemo(message) => match message {
+				swing_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<SwingDemo>(&message);
+					self.session_recorder.record::<SwingDemo>(&message)?;
+					if let Screen::SwingDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::OddTimeSignatureDemo(message) => match message {
+				odd_time_signature_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					self.session_recorder
+						.record::<OddTimeSignatureDemo>(&message)?;
+					if let Screen::OddTimeSignatureDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::StereoWidthDemo(message) => match message {
+				stereo_width_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<StereoWidthDemo>(&message);
+					self.session_recorder.record::<StereoWidthDemo>(&message)?;
+					if let Screen::StereoWidthDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::ParametricEqDemo(message) => match message {
+				parametric_eq_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<ParametricEqDemo>(&message);
+					self.session_recorder.record::<ParametricEqDemo>(&message)?;
+					if let Screen::ParametricEqDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::CompressorDemo(message) => match message {
+				compressor_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<CompressorDemo>(&message);
+					self.session_recorder.record::<CompressorDemo>(&message)?;
+					if let Screen::CompressorDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::SignalGeneratorDemo(message) => match message {
+				signal_generator_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<SignalGeneratorDemo>(&message);
+					self.session_recorder
+						.record::<SignalGeneratorDemo>(&message)?;
+					if let Screen::SignalGeneratorDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::MixerSnapshotDemo(message) => match message {
+				mixer_snapshot_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<MixerSnapshotDemo>(&message);
+					self.session_recorder
+						.record::<MixerSnapshotDemo>(&message)?;
+					if let Screen::MixerSnapshotDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::PauseMenuDemo(message) => match message {
+				pause_menu_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<PauseMenuDemo>(&message);
+					self.session_recorder.record::<PauseMenuDemo>(&message)?;
+					if let Screen::PauseMenuDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::RadioVoiceDemo(message) => match message {
+				radio_voice_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<RadioVoiceDemo>(&message);
+					self.session_recorder.record::<RadioVoiceDemo>(&message)?;
+					if let Screen::RadioVoiceDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::FootstepSurfaceDemo(message) => match message {
+				footstep_surface_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<FootstepSurfaceDemo>(&message);
+					self.session_recorder
+						.record::<FootstepSurfaceDemo>(&message)?;
+					if let Screen::FootstepSurfaceDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::EngineDemo(message) => match message {
+				engine_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<EngineDemo>(&message);
+					self.session_recorder.record::<EngineDemo>(&message)?;
+					if let Screen::EngineDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::WeaponDemo(message) => match message {
+				weapon_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<WeaponDemo>(&message);
+					self.session_recorder.record::<WeaponDemo>(&message)?;
+					if let Screen::WeaponDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::UiSoundsDemo(message) => match message {
+				ui_sounds_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<UiSoundsDemo>(&message);
+					self.session_recorder.record::<UiSoundsDemo>(&message)?;
+					if let Screen::UiSoundsDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::LowHealthDemo(message) => match message {
+				low_health_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<LowHealthDemo>(&message);
+					self.session_recorder.record::<LowHealthDemo>(&message)?;
+					if let Screen::LowHealthDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::KaraokeDemo(message) => match message {
+				karaoke_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<KaraokeDemo>(&message);
+					self.session_recorder.record::<KaraokeDemo>(&message)?;
+					if let Screen::KaraokeDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::CountInDemo(message) => match message {
+				count_in_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<CountInDemo>(&message);
+					self.session_recorder.record::<CountInDemo>(&message)?;
+					if let Screen::CountInDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::StemMuteSoloDemo(message) => match message {
+				stem_mute_solo_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<StemMuteSoloDemo>(&message);
+					self.session_recorder.record::<StemMuteSoloDemo>(&message)?;
+					if let Screen::StemMuteSoloDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::BeatRepeatDemo(message) => match message {
+				beat_repeat_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<BeatRepeatDemo>(&message);
+					self.session_recorder.record::<BeatRepeatDemo>(&message)?;
+					if let Screen::BeatRepeatDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::VinylBrakeDemo(message) => match message {
+				vinyl_brake_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<VinylBrakeDemo>(&message);
+					self.session_recorder.record::<VinylBrakeDemo>(&message)?;
+					if let Screen::VinylBrakeDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+			Message::WindDemo(message) => match message {
+				wind_demo::Message::GoToDemoSelect => {
+					self.go_to_demo_select();
+				}
+				message => {
+					#[cfg(feature = "midi")]
+					self.midi_learn.observe::<WindDemo>(&message);
+					self.session_recorder.record::<WindDemo>(&message)?;
+					if let Screen::WindDemo(screen) = &mut self.screen {
+						screen.update(message)?;
+					}
+				}
+			},
+		}
+		#[cfg(feature = "midi")]
+		if check_midi {
+			self.poll_midi()?;
+		}
+		#[cfg(feature = "remote")]
+		if check_remote {
+			self.poll_remote()?;
+		}
+		#[cfg(feature = "tray")]
+		if check_tray {
+			self.poll_tray()?;
+		}
+		Ok(())
+	}
+
+	#[cfg(feature = "midi")]
+	fn poll_midi(&mut self) -> Result<(), Box<dyn Error>> {
+		match &mut self.screen {
+			Screen::RetriggerCooldownDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::SendReturnDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::CrossfadeDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::LayeringDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::DopplerDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::FootstepDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::ReverbDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::DuckingDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::PitchPlayground(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::GranularDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::LoopRegionDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::DrumPadDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::SynthKeyboardDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::MixerConsoleDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::FilterDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::DelayDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::DistortionDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::LfoDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::WeatherDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::CombatMusicDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::SoundContainerDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::PanningDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::GroupPauseDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::PolyphonyDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::RhythmGameDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::SpectrumDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::GenerativeAmbientDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::SwingDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::StereoWidthDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::ParametricEqDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::CompressorDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::SignalGeneratorDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::MixerSnapshotDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::PauseMenuDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::RadioVoiceDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::FootstepSurfaceDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::EngineDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::WeaponDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::UiSoundsDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::LowHealthDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::KaraokeDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::CountInDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::StemMuteSoloDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::BeatRepeatDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::VinylBrakeDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			Screen::WindDemo(screen) => {
+				for message in self.midi_learn.poll(&mut self.preferences, &*screen)? {
+					screen.update(message)?;
+				}
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	#[cfg(feature = "remote")]
+	fn poll_remote(&mut self) -> Result<(), Box<dyn Error>> {
+		match &mut self.screen {
+			Screen::DrumFillDemo(screen) => {
+				for message in self.remote_control.poll(&*screen) {
+					screen.update(message)?;
+				}
+			}
+			Screen::UnderwaterDemo(screen) => {
+				for message in self.remote_control.poll(&*screen) {
+					screen.update(message)?;
+				}
+			}
+			Screen::StingerDemo(screen) => {
+				for message in self.remote_control.poll(&*screen) {
+					screen.update(message)?;
+				}
+			}
+			_ => self.remote_control.ignore_commands(),
+		}
+		Ok(())
+	}
+
+	#[cfg(feature = "tray")]
+	fn poll_tray(&mut self) -> Result<(), Box<dyn Error>> {
+		let actions = match &self.tray {
+			Ok(tray) => tray.actions(),
+			Err(_) => return Ok(()),
+		};
+		for action in actions {
+			match action {
+				TrayAction::ToggleMute => mute::set_muted(!mute::is_muted()),
+				// every audio manager belongs to a screen, so leaving
+				// the demo stops everything it was playing
+				TrayAction::StopAll => self.go_to_demo_select(),
+				TrayAction::OpenDemo(index) => {
+					if let Some(message) = demo_select::demo_message(index) {
+						self.go_to_demo_select();
+						self.try_update(Message::DemoSelect(message))?;
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Gets the text to show for an error, explaining how to
+/// avoid it next time when there's something the user can do.
+fn describe_error(error: &(dyn Error + 'static)) -> String {
+	match error.downcast_ref::<AudioError>() {
+		Some(AudioError::CommandQueueFull) => "The command queue is full - this demo sent \
+			more commands than the audio thread could keep up with. Raise \"Commands\" \
+			on the settings screen to give the queue more room."
+			.into(),
+		_ => error.to_string(),
 	}
 }
 
@@ -0,0 +1,75 @@
+use std::{
+	error::Error,
+	sync::mpsc::{channel, Receiver},
+};
+
+use midir::{MidiInput, MidiInputConnection};
+
+const CONTROL_CHANGE_STATUS: u8 = 0xB0;
+
+/// A control change message, which is what knobs, faders,
+/// and so on send when they're moved.
+#[derive(Debug, Copy, Clone)]
+pub struct ControlChange {
+	pub channel: u8,
+	pub controller: u8,
+	/// The position of the control (from 0 to 127).
+	pub value: u8,
+}
+
+/// Listens for control changes from a MIDI input port.
+///
+/// midir calls us back on its own thread, so messages are
+/// sent over a channel and picked up by the UI when it
+/// checks for events.
+pub struct MidiController {
+	port_name: String,
+	receiver: Receiver<ControlChange>,
+	_connection: MidiInputConnection<()>,
+}
+
+impl MidiController {
+	/// Connects to the first available MIDI input port.
+	pub fn connect() -> Result<Self, Box<dyn Error>> {
+		let input = MidiInput::new("kira-demo")?;
+		let port = input
+			.ports()
+			.into_iter()
+			.next()
+			.ok_or("no MIDI input ports found")?;
+		let port_name = input.port_name(&port)?;
+		let (sender, receiver) = channel();
+		let connection = input.connect(
+			&port,
+			"kira-demo-input",
+			move |_, message, _| {
+				if let [status, controller, value] = *message {
+					if status & 0xF0 == CONTROL_CHANGE_STATUS {
+						sender
+							.send(ControlChange {
+								channel: status & 0x0F,
+								controller,
+								value,
+							})
+							.ok();
+					}
+				}
+			},
+			(),
+		)?;
+		Ok(Self {
+			port_name,
+			receiver,
+			_connection: connection,
+		})
+	}
+
+	pub fn port_name(&self) -> &str {
+		&self.port_name
+	}
+
+	/// Gets the control changes received since the last call.
+	pub fn control_changes(&self) -> Vec<ControlChange> {
+		self.receiver.try_iter().collect()
+	}
+}
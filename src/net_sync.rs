@@ -0,0 +1,240 @@
+//! Keeps the music of two copies of the app in time over the
+//! local network.
+//!
+//! The leader answers pings from followers with how far its
+//! music has played. A follower measures how long the round trip
+//! took, assumes the reply took half of that to arrive, and works
+//! out where the leader is right now. The quickest round trips
+//! are the ones that got held up the least, so the estimate uses
+//! the shortest of the last few.
+
+use std::{
+	collections::{HashMap, VecDeque},
+	io::{self, ErrorKind},
+	net::{SocketAddr, ToSocketAddrs, UdpSocket},
+	time::{Duration, Instant},
+};
+
+use kira::Tempo;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_PORT: u16 = 47474;
+/// How often followers ask the leader where it is (in seconds).
+const PING_INTERVAL: f64 = 0.25;
+/// How many round trips the latency estimate is taken from.
+const NUM_ROUND_TRIPS: usize = 8;
+/// How long the other side can go quiet before it's considered
+/// disconnected.
+const TIMEOUT: Duration = Duration::from_secs(2);
+const MAX_PACKET_SIZE: usize = 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+enum Packet {
+	/// Sent by a follower. `sent` is the follower's time when it
+	/// sent the ping.
+	Ping { sent: f64 },
+	/// The leader's reply, with the follower's time echoed back
+	/// and how far the leader's music is (in seconds), if it's
+	/// playing.
+	Pong {
+		ping_sent: f64,
+		position: Option<f64>,
+		tempo: f64,
+	},
+}
+
+fn send(socket: &UdpSocket, packet: &Packet, address: SocketAddr) -> io::Result<()> {
+	socket.send_to(&serde_json::to_vec(packet)?, address)?;
+	Ok(())
+}
+
+/// Gets all the packets that have arrived since the last call.
+/// Packets that aren't from another copy of the app are ignored.
+fn receive(socket: &UdpSocket) -> io::Result<Vec<(Packet, SocketAddr)>> {
+	let mut packets = vec![];
+	let mut buffer = [0; MAX_PACKET_SIZE];
+	loop {
+		match socket.recv_from(&mut buffer) {
+			Ok((size, address)) => {
+				if let Ok(packet) = serde_json::from_slice(&buffer[..size]) {
+					packets.push((packet, address));
+				}
+			}
+			Err(error) if error.kind() == ErrorKind::WouldBlock => return Ok(packets),
+			// on Windows, an unreachable follower shows up as an
+			// error on the next receive
+			Err(error) if error.kind() == ErrorKind::ConnectionReset => {}
+			Err(error) => return Err(error),
+		}
+	}
+}
+
+/// Tells followers where the music is.
+pub struct SyncLeader {
+	socket: UdpSocket,
+	/// When each follower last pinged.
+	followers: HashMap<SocketAddr, Instant>,
+}
+
+impl SyncLeader {
+	pub fn bind(port: u16) -> io::Result<Self> {
+		let socket = UdpSocket::bind(("0.0.0.0", port))?;
+		socket.set_nonblocking(true)?;
+		Ok(Self {
+			socket,
+			followers: HashMap::new(),
+		})
+	}
+
+	pub fn port(&self) -> io::Result<u16> {
+		Ok(self.socket.local_addr()?.port())
+	}
+
+	/// How many followers have been heard from recently.
+	pub fn num_followers(&self) -> usize {
+		self.followers
+			.values()
+			.filter(|last_heard| last_heard.elapsed() < TIMEOUT)
+			.count()
+	}
+
+	/// Answers the pings that came in since the last call.
+	/// `position` is how far the music has played, if it's playing.
+	pub fn update(&mut self, position: Option<f64>, tempo: Tempo) -> io::Result<()> {
+		for (packet, address) in receive(&self.socket)? {
+			if let Packet::Ping { sent } = packet {
+				self.followers.insert(address, Instant::now());
+				send(
+					&self.socket,
+					&Packet::Pong {
+						ping_sent: sent,
+						position,
+						tempo: tempo.0,
+					},
+					address,
+				)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// The last thing the leader said about its music.
+#[derive(Debug, Copy, Clone)]
+struct LeaderReport {
+	position: Option<f64>,
+	tempo: Tempo,
+	received_at: Instant,
+}
+
+/// Follows a leader's music.
+pub struct SyncFollower {
+	socket: UdpSocket,
+	leader_address: SocketAddr,
+	/// The follower's clock, which pings are timestamped with.
+	start_time: Instant,
+	last_ping_time: Option<f64>,
+	round_trips: VecDeque<f64>,
+	last_report: Option<LeaderReport>,
+}
+
+impl SyncFollower {
+	/// Starts following the leader at the given address. The port
+	/// can be left out if it's the default one.
+	pub fn connect(leader_address: &str) -> io::Result<Self> {
+		let leader_address = match leader_address.to_socket_addrs() {
+			Ok(mut addresses) => addresses.next(),
+			Err(_) => (leader_address, DEFAULT_PORT).to_socket_addrs()?.next(),
+		}
+		.ok_or_else(|| io::Error::new(ErrorKind::NotFound, "the address couldn't be found"))?;
+		let socket = UdpSocket::bind(("0.0.0.0", 0))?;
+		socket.set_nonblocking(true)?;
+		Ok(Self {
+			socket,
+			leader_address,
+			start_time: Instant::now(),
+			last_ping_time: None,
+			round_trips: VecDeque::new(),
+			last_report: None,
+		})
+	}
+
+	pub fn leader_address(&self) -> SocketAddr {
+		self.leader_address
+	}
+
+	fn time(&self) -> f64 {
+		self.start_time.elapsed().as_secs_f64()
+	}
+
+	/// Pings the leader (if it's time to) and reads its replies.
+	pub fn update(&mut self) -> io::Result<()> {
+		let time = self.time();
+		if self
+			.last_ping_time
+			.is_none_or(|last_ping_time| time - last_ping_time >= PING_INTERVAL)
+		{
+			send(
+				&self.socket,
+				&Packet::Ping { sent: time },
+				self.leader_address,
+			)?;
+			self.last_ping_time = Some(time);
+		}
+		for (packet, address) in receive(&self.socket)? {
+			if address != self.leader_address {
+				continue;
+			}
+			if let Packet::Pong {
+				ping_sent,
+				position,
+				tempo,
+			} = packet
+			{
+				self.round_trips.push_back(self.time() - ping_sent);
+				if self.round_trips.len() > NUM_ROUND_TRIPS {
+					self.round_trips.pop_front();
+				}
+				self.last_report = Some(LeaderReport {
+					position,
+					tempo: Tempo(tempo),
+					received_at: Instant::now(),
+				});
+			}
+		}
+		Ok(())
+	}
+
+	pub fn is_connected(&self) -> bool {
+		self.last_report
+			.is_some_and(|report| report.received_at.elapsed() < TIMEOUT)
+	}
+
+	/// How long (in seconds) messages take to get from the
+	/// leader to the follower.
+	pub fn latency(&self) -> Option<f64> {
+		self.round_trips
+			.iter()
+			.copied()
+			.min_by(|a, b| a.partial_cmp(b).unwrap())
+			.map(|round_trip| round_trip / 2.0)
+	}
+
+	pub fn leader_tempo(&self) -> Option<Tempo> {
+		self.last_report.map(|report| report.tempo)
+	}
+
+	/// How far the leader's music has played right now, if it's
+	/// playing.
+	pub fn leader_position(&self) -> Option<f64> {
+		if !self.is_connected() {
+			return None;
+		}
+		let report = self.last_report?;
+		Some(
+			report.position?
+				+ self.latency().unwrap_or(0.0)
+				+ report.received_at.elapsed().as_secs_f64(),
+		)
+	}
+}
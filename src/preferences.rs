@@ -0,0 +1,161 @@
+use std::{collections::HashMap, error::Error, fs, io::ErrorKind, path::PathBuf};
+
+use kira::manager::AudioManagerSettings;
+use serde::{Deserialize, Serialize};
+
+/// A MIDI control change (CC) that's bound to an on-screen control.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MidiBinding {
+	pub channel: u8,
+	pub controller: u8,
+}
+
+/// How many of each kind of resource the demos' audio managers
+/// have room for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioCapacities {
+	pub num_instances: usize,
+	pub num_sequences: usize,
+	pub num_parameters: usize,
+	pub num_commands: usize,
+}
+
+impl AudioCapacities {
+	pub fn audio_manager_settings(&self) -> AudioManagerSettings {
+		AudioManagerSettings {
+			num_instances: self.num_instances,
+			num_sequences: self.num_sequences,
+			num_parameters: self.num_parameters,
+			num_commands: self.num_commands,
+			..Default::default()
+		}
+	}
+}
+
+impl Default for AudioCapacities {
+	fn default() -> Self {
+		let settings = AudioManagerSettings::default();
+		Self {
+			num_instances: settings.num_instances,
+			num_sequences: settings.num_sequences,
+			num_parameters: settings.num_parameters,
+			num_commands: settings.num_commands,
+		}
+	}
+}
+
+/// The colors the beat displays use.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BeatDisplayPalette {
+	/// White for the current beat, red for marked beats (like
+	/// the drum fill).
+	Classic,
+	/// Orange for the current beat, blue for marked beats,
+	/// which stay distinct with the common kinds of color
+	/// blindness.
+	OrangeAndBlue,
+	/// Everything in shades of white, for use with shapes.
+	Monochrome,
+}
+
+impl BeatDisplayPalette {
+	pub const ALL: [Self; 3] = [Self::Classic, Self::OrangeAndBlue, Self::Monochrome];
+
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Classic => "Classic",
+			Self::OrangeAndBlue => "Orange and blue",
+			Self::Monochrome => "Monochrome",
+		}
+	}
+}
+
+/// How the beat displays tell the current beat apart from the
+/// marked ones (like the beats a drum fill will cover).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BeatDisplayStyle {
+	pub palette: BeatDisplayPalette,
+	/// Whether beats are also drawn as filled, outlined, or
+	/// hatched circles so they can be read without color.
+	pub shapes: bool,
+}
+
+impl Default for BeatDisplayStyle {
+	fn default() -> Self {
+		Self {
+			palette: BeatDisplayPalette::Classic,
+			shapes: false,
+		}
+	}
+}
+
+/// What the app's audio is being listened on, which changes
+/// how the spatial demos pan sounds.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OutputMode {
+	#[default]
+	Speakers,
+	Headphones,
+}
+
+impl OutputMode {
+	pub const ALL: [Self; 2] = [Self::Speakers, Self::Headphones];
+
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Speakers => "Speakers",
+			Self::Headphones => "Headphones",
+		}
+	}
+}
+
+/// A loop the user opened in the custom loop demo, along with
+/// the tempo and length they confirmed for it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomLoop {
+	pub path: PathBuf,
+	pub bpm: f64,
+	pub bars: usize,
+}
+
+/// Settings that are remembered between runs of the app.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+	pub audio_capacities: AudioCapacities,
+	pub beat_display_style: BeatDisplayStyle,
+	pub output_mode: OutputMode,
+	pub custom_loop: Option<CustomLoop>,
+	/// MIDI bindings keyed by "screen name/control name".
+	pub midi_bindings: HashMap<String, MidiBinding>,
+	/// The remote commands external tools are allowed to send.
+	/// Nothing is allowed until the user turns it on.
+	pub remote_allowed_commands: Vec<String>,
+	/// The web pages (like "http://localhost:8080") that are
+	/// allowed to connect to the remote control server. Tools
+	/// that aren't running in a browser don't need to be listed.
+	pub remote_allowed_origins: Vec<String>,
+}
+
+impl Preferences {
+	fn path() -> Result<PathBuf, Box<dyn Error>> {
+		Ok(std::env::current_dir()?.join("preferences.json"))
+	}
+
+	/// Loads the saved preferences, or the defaults if
+	/// nothing has been saved yet.
+	pub fn load() -> Result<Self, Box<dyn Error>> {
+		match fs::read_to_string(Self::path()?) {
+			Ok(contents) => Ok(serde_json::from_str(&contents)?),
+			Err(error) if error.kind() == ErrorKind::NotFound => Ok(Self::default()),
+			Err(error) => Err(error.into()),
+		}
+	}
+
+	pub fn save(&self) -> Result<(), Box<dyn Error>> {
+		fs::write(Self::path()?, serde_json::to_string_pretty(self)?)?;
+		Ok(())
+	}
+}
@@ -0,0 +1,482 @@
+//! A WebSocket server that lets external tools (stream overlays,
+//! chat bots, and so on) trigger actions in the demos.
+//!
+//! Clients send one JSON command per text message, like
+//! `{"command": "set_underwater", "amount": 0.5}`, and get back
+//! `{"ok": true}` or `{"ok": false, "error": "..."}`. Only the
+//! commands on the allow-list are accepted.
+//!
+//! Browsers let any web page open a WebSocket to localhost, so
+//! connections from a web page (which browsers mark with an
+//! `Origin` header) are turned away unless the page's origin is on
+//! a second allow-list. Other tools don't send an origin and are
+//! always let in.
+
+use std::{
+	collections::HashSet,
+	error::Error,
+	io::{self, BufRead, BufReader, Read, Write},
+	net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		mpsc::{channel, Receiver, Sender},
+		Arc, Mutex,
+	},
+	thread,
+	time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_PORT: u16 = 47475;
+/// Used to work out the handshake response (see RFC 6455).
+const HANDSHAKE_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+/// Messages bigger than this are refused, since no command
+/// needs anywhere near this much.
+const MAX_MESSAGE_SIZE: u64 = 64 * 1024;
+/// Handshakes longer than this are refused. Real ones are well
+/// under 1 KiB.
+const MAX_HANDSHAKE_SIZE: u64 = 8 * 1024;
+/// How long a client gets to finish the handshake before it's
+/// disconnected.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+const OPCODE_CONTINUATION: u8 = 0x0;
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_CLOSE: u8 = 0x8;
+const OPCODE_PING: u8 = 0x9;
+const OPCODE_PONG: u8 = 0xA;
+
+/// An action an external tool can ask the app to do.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum RemoteCommand {
+	/// Plays a drum fill in the drum fill demo.
+	TriggerFill,
+	/// Sets how far underwater the underwater demo is (from 0 to 1).
+	SetUnderwater { amount: f64 },
+	/// Plays a stinger over the drum fill demo's loop.
+	PlayStinger,
+}
+
+impl RemoteCommand {
+	pub const NAMES: [&'static str; 3] = ["trigger_fill", "set_underwater", "play_stinger"];
+
+	/// The name clients send the command with, which is also
+	/// what goes on the allow-list.
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::TriggerFill => "trigger_fill",
+			Self::SetUnderwater { .. } => "set_underwater",
+			Self::PlayStinger => "play_stinger",
+		}
+	}
+}
+
+#[derive(Debug, Serialize)]
+struct Reply {
+	ok: bool,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	error: Option<String>,
+}
+
+/// The state the server's threads share with the UI.
+struct Shared {
+	allowed_commands: Mutex<HashSet<String>>,
+	allowed_origins: Mutex<HashSet<String>>,
+	num_clients: AtomicUsize,
+}
+
+/// Accepts WebSocket connections on the local machine.
+///
+/// Like the MIDI controller, the server runs on its own threads
+/// (one for accepting connections and one per client) and sends
+/// commands over a channel for the UI to pick up when it checks
+/// for events.
+pub struct RemoteServer {
+	address: SocketAddr,
+	shared: Arc<Shared>,
+	receiver: Receiver<RemoteCommand>,
+}
+
+impl RemoteServer {
+	/// Starts listening on localhost, so only programs on the
+	/// same machine can connect.
+	pub fn start(
+		port: u16,
+		allowed_commands: &[String],
+		allowed_origins: &[String],
+	) -> Result<Self, Box<dyn Error>> {
+		let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+		let address = listener.local_addr()?;
+		let shared = Arc::new(Shared {
+			allowed_commands: Mutex::new(allowed_commands.iter().cloned().collect()),
+			allowed_origins: Mutex::new(allowed_origins.iter().cloned().collect()),
+			num_clients: AtomicUsize::new(0),
+		});
+		let (sender, receiver) = channel();
+		{
+			let shared = shared.clone();
+			thread::spawn(move || {
+				for stream in listener.incoming().flatten() {
+					let shared = shared.clone();
+					let sender = sender.clone();
+					thread::spawn(move || {
+						shared.num_clients.fetch_add(1, Ordering::SeqCst);
+						// a client going away without saying goodbye
+						// isn't worth reporting
+						handle_client(stream, &shared, &sender).ok();
+						shared.num_clients.fetch_sub(1, Ordering::SeqCst);
+					});
+				}
+			});
+		}
+		Ok(Self {
+			address,
+			shared,
+			receiver,
+		})
+	}
+
+	pub fn address(&self) -> SocketAddr {
+		self.address
+	}
+
+	pub fn num_clients(&self) -> usize {
+		self.shared.num_clients.load(Ordering::SeqCst)
+	}
+
+	pub fn set_allowed_commands(&self, allowed_commands: &[String]) {
+		*self.shared.allowed_commands.lock().unwrap() = allowed_commands.iter().cloned().collect();
+	}
+
+	/// Only affects clients that connect after this is called.
+	pub fn set_allowed_origins(&self, allowed_origins: &[String]) {
+		*self.shared.allowed_origins.lock().unwrap() = allowed_origins.iter().cloned().collect();
+	}
+
+	/// Gets the accepted commands received since the last call.
+	pub fn commands(&self) -> Vec<RemoteCommand> {
+		self.receiver.try_iter().collect()
+	}
+}
+
+fn handle_client(
+	stream: TcpStream,
+	shared: &Shared,
+	sender: &Sender<RemoteCommand>,
+) -> Result<(), Box<dyn Error>> {
+	let mut reader = BufReader::new(stream.try_clone()?);
+	let mut writer = stream;
+	// a client that never finishes the handshake would hold on to
+	// this thread forever. once it has, it can take as long as it
+	// likes between commands.
+	writer.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+	let allowed_origins = shared.allowed_origins.lock().unwrap().clone();
+	handshake(&mut reader, &mut writer, &allowed_origins)?;
+	writer.set_read_timeout(None)?;
+	while let Some(text) = read_message(&mut reader, &mut writer)? {
+		let reply = match serde_json::from_str::<RemoteCommand>(&text) {
+			Ok(command) => {
+				if shared
+					.allowed_commands
+					.lock()
+					.unwrap()
+					.contains(command.name())
+				{
+					sender.send(command)?;
+					Reply {
+						ok: true,
+						error: None,
+					}
+				} else {
+					Reply {
+						ok: false,
+						error: Some(format!("\"{}\" isn't on the allow-list", command.name())),
+					}
+				}
+			}
+			Err(error) => Reply {
+				ok: false,
+				error: Some(error.to_string()),
+			},
+		};
+		write_frame(
+			&mut writer,
+			OPCODE_TEXT,
+			serde_json::to_string(&reply)?.as_bytes(),
+		)?;
+	}
+	Ok(())
+}
+
+/// Reads the client's HTTP upgrade request and agrees to switch
+/// to the WebSocket protocol, unless it came from a web page that
+/// isn't on the allow-list.
+fn handshake(
+	reader: &mut impl BufRead,
+	writer: &mut impl Write,
+	allowed_origins: &HashSet<String>,
+) -> Result<(), Box<dyn Error>> {
+	let mut reader = reader.take(MAX_HANDSHAKE_SIZE);
+	let mut key = None;
+	let mut origin = None;
+	loop {
+		let mut line = String::new();
+		if reader.read_line(&mut line)? == 0 {
+			return Err("the connection closed during the handshake".into());
+		}
+		// the limit cuts the line off before its line break
+		if !line.ends_with('\n') && reader.limit() == 0 {
+			writer.write_all(b"HTTP/1.1 431 Request Header Fields Too Large\r\n\r\n")?;
+			return Err("the handshake is too long".into());
+		}
+		let line = line.trim_end();
+		if line.is_empty() {
+			break;
+		}
+		if let Some((name, value)) = line.split_once(':') {
+			if name.trim().eq_ignore_ascii_case("sec-websocket-key") {
+				key = Some(value.trim().to_string());
+			} else if name.trim().eq_ignore_ascii_case("origin") {
+				origin = Some(value.trim().to_string());
+			}
+		}
+	}
+	if let Some(origin) = origin {
+		if !allowed_origins.contains(&origin) {
+			writer.write_all(b"HTTP/1.1 403 Forbidden\r\n\r\n")?;
+			return Err(format!("{} isn't on the allow-list", origin).into());
+		}
+	}
+	let key = match key {
+		Some(key) => key,
+		None => {
+			writer.write_all(b"HTTP/1.1 400 Bad Request\r\n\r\n")?;
+			return Err("the client didn't ask for a WebSocket connection".into());
+		}
+	};
+	write!(
+		writer,
+		"HTTP/1.1 101 Switching Protocols\r\n\
+		Upgrade: websocket\r\n\
+		Connection: Upgrade\r\n\
+		Sec-WebSocket-Accept: {}\r\n\r\n",
+		accept_key(&key)
+	)?;
+	Ok(())
+}
+
+fn accept_key(key: &str) -> String {
+	base64::encode(sha1(format!("{}{}", key, HANDSHAKE_GUID).as_bytes()))
+}
+
+/// Reads frames until a whole text message has come in, answering
+/// pings along the way. Returns `None` once the client closes the
+/// connection.
+fn read_message(
+	reader: &mut impl Read,
+	writer: &mut impl Write,
+) -> Result<Option<String>, Box<dyn Error>> {
+	let mut message = vec![];
+	loop {
+		let mut header = [0; 2];
+		reader.read_exact(&mut header)?;
+		let fin = header[0] & 0x80 != 0;
+		let opcode = header[0] & 0x0F;
+		let masked = header[1] & 0x80 != 0;
+		let length = match header[1] & 0x7F {
+			126 => {
+				let mut length = [0; 2];
+				reader.read_exact(&mut length)?;
+				u16::from_be_bytes(length) as u64
+			}
+			127 => {
+				let mut length = [0; 8];
+				reader.read_exact(&mut length)?;
+				u64::from_be_bytes(length)
+			}
+			length => length as u64,
+		};
+		// the message so far is never over the limit, so this
+		// can't wrap around like adding the length could
+		if length > MAX_MESSAGE_SIZE - message.len() as u64 {
+			return Err("the client sent a message that was too big".into());
+		}
+		// clients always have to mask what they send
+		if !masked {
+			return Err("the client sent an unmasked frame".into());
+		}
+		let mut mask = [0; 4];
+		reader.read_exact(&mut mask)?;
+		let mut payload = vec![0; length as usize];
+		reader.read_exact(&mut payload)?;
+		for (i, byte) in payload.iter_mut().enumerate() {
+			*byte ^= mask[i % 4];
+		}
+		match opcode {
+			OPCODE_TEXT | OPCODE_CONTINUATION => {
+				message.extend(payload);
+				if fin {
+					return Ok(Some(String::from_utf8(message)?));
+				}
+			}
+			OPCODE_PING => write_frame(writer, OPCODE_PONG, &payload)?,
+			OPCODE_CLOSE => {
+				write_frame(writer, OPCODE_CLOSE, &payload)?;
+				return Ok(None);
+			}
+			// binary messages and unasked-for pongs are ignored
+			_ => {}
+		}
+	}
+}
+
+fn write_frame(writer: &mut impl Write, opcode: u8, payload: &[u8]) -> io::Result<()> {
+	let mut frame = vec![0x80 | opcode];
+	match payload.len() {
+		length if length < 126 => frame.push(length as u8),
+		length if length <= u16::MAX as usize => {
+			frame.push(126);
+			frame.extend(&(length as u16).to_be_bytes());
+		}
+		length => {
+			frame.push(127);
+			frame.extend(&(length as u64).to_be_bytes());
+		}
+	}
+	frame.extend(payload);
+	writer.write_all(&frame)
+}
+
+/// SHA-1, which the handshake needs and nothing else does, so it's
+/// not worth another dependency.
+fn sha1(data: &[u8]) -> [u8; 20] {
+	let mut state: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+	let mut padded = data.to_vec();
+	padded.push(0x80);
+	while padded.len() % 64 != 56 {
+		padded.push(0);
+	}
+	padded.extend(&(data.len() as u64 * 8).to_be_bytes());
+	for chunk in padded.chunks(64) {
+		let mut words = [0u32; 80];
+		for (i, word) in chunk.chunks(4).enumerate() {
+			words[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+		}
+		for i in 16..80 {
+			words[i] = (words[i - 3] ^ words[i - 8] ^ words[i - 14] ^ words[i - 16]).rotate_left(1);
+		}
+		let [mut a, mut b, mut c, mut d, mut e] = state;
+		for (i, word) in words.iter().enumerate() {
+			let (f, k) = match i {
+				0..=19 => ((b & c) | (!b & d), 0x5A827999),
+				20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+				40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+				_ => (b ^ c ^ d, 0xCA62C1D6),
+			};
+			let temp = a
+				.rotate_left(5)
+				.wrapping_add(f)
+				.wrapping_add(e)
+				.wrapping_add(k)
+				.wrapping_add(*word);
+			e = d;
+			d = c;
+			c = b.rotate_left(30);
+			b = a;
+			a = temp;
+		}
+		for (value, new) in state.iter_mut().zip([a, b, c, d, e]) {
+			*value = value.wrapping_add(new);
+		}
+	}
+	let mut hash = [0; 20];
+	for (bytes, value) in hash.chunks_mut(4).zip(state.iter()) {
+		bytes.copy_from_slice(&value.to_be_bytes());
+	}
+	hash
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn handshake_matches_the_spec_example() {
+		// from section 1.3 of RFC 6455
+		assert_eq!(
+			accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+			"s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+		);
+	}
+
+	#[test]
+	fn handshakes_from_web_pages_need_an_allowed_origin() {
+		let request = |origin: &str| {
+			format!(
+				"GET / HTTP/1.1\r\n\
+				Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+				{}\r\n",
+				origin
+			)
+		};
+		let allowed_origins = ["http://localhost:8080".to_string()]
+			.iter()
+			.cloned()
+			.collect();
+		let mut response = vec![];
+		assert!(handshake(
+			&mut request("Origin: https://example.com\r\n").as_bytes(),
+			&mut response,
+			&allowed_origins
+		)
+		.is_err());
+		assert!(response.starts_with(b"HTTP/1.1 403"));
+		for origin in &["", "Origin: http://localhost:8080\r\n"] {
+			let mut response = vec![];
+			handshake(
+				&mut request(origin).as_bytes(),
+				&mut response,
+				&allowed_origins,
+			)
+			.unwrap();
+			assert!(response.starts_with(b"HTTP/1.1 101"));
+		}
+	}
+
+	#[test]
+	fn huge_handshakes_are_refused() {
+		let request = format!(
+			"GET / HTTP/1.1\r\nX-Padding: {}\r\n\r\n",
+			"a".repeat(MAX_HANDSHAKE_SIZE as usize)
+		);
+		let mut response = vec![];
+		assert!(handshake(&mut request.as_bytes(), &mut response, &HashSet::new()).is_err());
+		assert!(response.starts_with(b"HTTP/1.1 431"));
+	}
+
+	#[test]
+	fn huge_frame_lengths_are_refused() {
+		let mut frame = vec![0x81, 0x80 | 127];
+		frame.extend(&u64::MAX.to_be_bytes());
+		frame.extend(&[1, 2, 3, 4]);
+		assert!(read_message(&mut frame.as_slice(), &mut vec![]).is_err());
+	}
+
+	#[test]
+	fn commands_are_read_from_masked_frames() {
+		let text = br#"{"command": "set_underwater", "amount": 0.5}"#;
+		let mask = [1, 2, 3, 4];
+		let mut frame = vec![0x81, 0x80 | text.len() as u8];
+		frame.extend(&mask);
+		frame.extend(text.iter().enumerate().map(|(i, byte)| byte ^ mask[i % 4]));
+		let message = read_message(&mut frame.as_slice(), &mut vec![])
+			.unwrap()
+			.unwrap();
+		assert_eq!(
+			serde_json::from_str::<RemoteCommand>(&message).unwrap(),
+			RemoteCommand::SetUnderwater { amount: 0.5 }
+		);
+	}
+}
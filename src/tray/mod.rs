@@ -0,0 +1,56 @@
+//! A system tray icon with quick controls, so the demos can be
+//! muted, stopped, or switched without bringing up the window.
+//!
+//! Only Windows is supported so far. Other platforms each need
+//! their own tray integration, which can be added next to the
+//! Windows one.
+
+#[cfg(windows)]
+mod windows;
+
+use std::{error::Error, sync::mpsc::Receiver};
+
+/// Something the user picked from the tray icon's menu.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(not(windows), allow(dead_code))]
+pub enum TrayAction {
+	ToggleMute,
+	StopAll,
+	/// Opens the demo at this index in the demo list.
+	OpenDemo(usize),
+}
+
+/// The tray icon. Like the MIDI controller, its menu is handled
+/// on another thread, which sends the actions over a channel
+/// for the UI to pick up when it checks for events.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub struct Tray {
+	receiver: Receiver<TrayAction>,
+	#[cfg(windows)]
+	_icon: windows::TrayIcon,
+}
+
+impl Tray {
+	/// Adds the icon to the tray. `demo_names` are listed in
+	/// its "Open demo" menu.
+	pub fn new(demo_names: Vec<String>) -> Result<Self, Box<dyn Error>> {
+		#[cfg(windows)]
+		{
+			let (sender, receiver) = std::sync::mpsc::channel();
+			Ok(Self {
+				receiver,
+				_icon: windows::TrayIcon::new(demo_names, sender)?,
+			})
+		}
+		#[cfg(not(windows))]
+		{
+			let _ = demo_names;
+			Err("the tray icon is only supported on Windows so far".into())
+		}
+	}
+
+	/// Gets the actions picked since the last call.
+	pub fn actions(&self) -> Vec<TrayAction> {
+		self.receiver.try_iter().collect()
+	}
+}
@@ -0,0 +1,239 @@
+use std::{
+	cell::RefCell, error::Error, ffi::OsStr, iter::once, mem, os::windows::ffi::OsStrExt, ptr,
+	sync::mpsc::Sender, thread,
+};
+
+use winapi::{
+	shared::{
+		minwindef::{LPARAM, LRESULT, UINT, WPARAM},
+		windef::{HWND, POINT},
+	},
+	um::{
+		libloaderapi::GetModuleHandleW,
+		shellapi::{
+			Shell_NotifyIconW, NIF_ICON, NIF_MESSAGE, NIF_TIP, NIM_ADD, NIM_DELETE, NOTIFYICONDATAW,
+		},
+		winuser::{
+			AppendMenuW, CreatePopupMenu, CreateWindowExW, DefWindowProcW, DestroyMenu,
+			DestroyWindow, DispatchMessageW, GetCursorPos, GetMessageW, LoadIconW, PostMessageW,
+			PostQuitMessage, RegisterClassW, SetForegroundWindow, TrackPopupMenu, TranslateMessage,
+			HWND_MESSAGE, IDI_APPLICATION, MF_CHECKED, MF_POPUP, MF_SEPARATOR, MF_STRING, MSG,
+			TPM_NONOTIFY, TPM_RETURNCMD, TPM_RIGHTBUTTON, WM_APP, WM_CLOSE, WM_DESTROY,
+			WM_LBUTTONUP, WM_RBUTTONUP, WNDCLASSW,
+		},
+	},
+};
+
+use super::TrayAction;
+use crate::audio::mute;
+
+const CLASS_NAME: &str = "kira-demo-tray";
+const TOOLTIP: &str = "Kira demo";
+/// The message Windows sends the window when the icon is clicked.
+const WM_TRAY_ICON: UINT = WM_APP + 1;
+const ICON_ID: UINT = 1;
+
+// menu item IDs. 0 means nothing was picked.
+const ID_MUTE: usize = 1;
+const ID_STOP_ALL: usize = 2;
+const ID_FIRST_DEMO: usize = 100;
+
+/// What the window procedure needs to build the menu and report
+/// what was picked. Windows calls the window procedure on the
+/// thread that created the window, so this is kept per thread.
+struct MenuState {
+	demo_names: Vec<String>,
+	sender: Sender<TrayAction>,
+}
+
+thread_local! {
+	static MENU_STATE: RefCell<Option<MenuState>> = RefCell::new(None);
+}
+
+/// Converts a string to the null-terminated UTF-16 that the
+/// Windows API takes.
+fn wide(text: &str) -> Vec<u16> {
+	OsStr::new(text).encode_wide().chain(once(0)).collect()
+}
+
+fn notify_icon_data(window: HWND) -> NOTIFYICONDATAW {
+	let mut data: NOTIFYICONDATAW = unsafe { mem::zeroed() };
+	data.cbSize = mem::size_of::<NOTIFYICONDATAW>() as u32;
+	data.hWnd = window;
+	data.uID = ICON_ID;
+	data
+}
+
+/// The icon, along with a hidden window on its own thread that
+/// receives the icon's clicks.
+pub struct TrayIcon {
+	/// The hidden window's handle, which (unlike the `HWND`
+	/// itself) can be sent between threads.
+	window: usize,
+}
+
+impl TrayIcon {
+	pub fn new(
+		demo_names: Vec<String>,
+		sender: Sender<TrayAction>,
+	) -> Result<Self, Box<dyn Error>> {
+		let (ready_sender, ready_receiver) = std::sync::mpsc::channel();
+		thread::spawn(move || unsafe { run(demo_names, sender, ready_sender) });
+		let window = ready_receiver.recv()??;
+		Ok(Self { window })
+	}
+}
+
+impl Drop for TrayIcon {
+	fn drop(&mut self) {
+		unsafe {
+			PostMessageW(self.window as HWND, WM_CLOSE, 0, 0);
+		}
+	}
+}
+
+/// Creates the window and the icon, then handles the window's
+/// messages until it's closed.
+unsafe fn run(
+	demo_names: Vec<String>,
+	sender: Sender<TrayAction>,
+	ready_sender: Sender<Result<usize, String>>,
+) {
+	let instance = GetModuleHandleW(ptr::null());
+	let class_name = wide(CLASS_NAME);
+	let class = WNDCLASSW {
+		lpfnWndProc: Some(window_proc),
+		hInstance: instance,
+		lpszClassName: class_name.as_ptr(),
+		..mem::zeroed()
+	};
+	RegisterClassW(&class);
+	// a message-only window never shows up on screen
+	let window = CreateWindowExW(
+		0,
+		class_name.as_ptr(),
+		class_name.as_ptr(),
+		0,
+		0,
+		0,
+		0,
+		0,
+		HWND_MESSAGE,
+		ptr::null_mut(),
+		instance,
+		ptr::null_mut(),
+	);
+	if window.is_null() {
+		ready_sender
+			.send(Err("couldn't create the tray icon's window".into()))
+			.ok();
+		return;
+	}
+	let mut data = notify_icon_data(window);
+	data.uFlags = NIF_ICON | NIF_MESSAGE | NIF_TIP;
+	data.uCallbackMessage = WM_TRAY_ICON;
+	data.hIcon = LoadIconW(ptr::null_mut(), IDI_APPLICATION);
+	for (character, tip_character) in wide(TOOLTIP).iter().zip(data.szTip.iter_mut()) {
+		*tip_character = *character;
+	}
+	if Shell_NotifyIconW(NIM_ADD, &mut data) == 0 {
+		DestroyWindow(window);
+		ready_sender
+			.send(Err("couldn't add the icon to the tray".into()))
+			.ok();
+		return;
+	}
+	MENU_STATE.with(|state| *state.borrow_mut() = Some(MenuState { demo_names, sender }));
+	ready_sender.send(Ok(window as usize)).ok();
+	let mut message: MSG = mem::zeroed();
+	while GetMessageW(&mut message, ptr::null_mut(), 0, 0) > 0 {
+		TranslateMessage(&message);
+		DispatchMessageW(&message);
+	}
+}
+
+unsafe extern "system" fn window_proc(
+	window: HWND,
+	message: UINT,
+	wparam: WPARAM,
+	lparam: LPARAM,
+) -> LRESULT {
+	match message {
+		WM_TRAY_ICON => {
+			let event = lparam as UINT;
+			if event == WM_LBUTTONUP || event == WM_RBUTTONUP {
+				show_menu(window);
+			}
+			0
+		}
+		WM_CLOSE => {
+			Shell_NotifyIconW(NIM_DELETE, &mut notify_icon_data(window));
+			DestroyWindow(window);
+			0
+		}
+		WM_DESTROY => {
+			PostQuitMessage(0);
+			0
+		}
+		_ => DefWindowProcW(window, message, wparam, lparam),
+	}
+}
+
+/// Shows the menu where the user clicked and reports what
+/// they picked.
+unsafe fn show_menu(window: HWND) {
+	MENU_STATE.with(|state| {
+		let state = state.borrow();
+		let state = match &*state {
+			Some(state) => state,
+			None => return,
+		};
+		let demos_menu = CreatePopupMenu();
+		for (index, name) in state.demo_names.iter().enumerate() {
+			AppendMenuW(
+				demos_menu,
+				MF_STRING,
+				ID_FIRST_DEMO + index,
+				wide(name).as_ptr(),
+			);
+		}
+		let menu = CreatePopupMenu();
+		let mute_flags = if mute::is_muted() {
+			MF_STRING | MF_CHECKED
+		} else {
+			MF_STRING
+		};
+		AppendMenuW(menu, mute_flags, ID_MUTE, wide("Mute").as_ptr());
+		AppendMenuW(menu, MF_STRING, ID_STOP_ALL, wide("Stop all").as_ptr());
+		AppendMenuW(menu, MF_SEPARATOR, 0, ptr::null());
+		AppendMenuW(
+			menu,
+			MF_POPUP,
+			demos_menu as usize,
+			wide("Open demo...").as_ptr(),
+		);
+		let mut cursor = POINT { x: 0, y: 0 };
+		GetCursorPos(&mut cursor);
+		// otherwise the menu doesn't close when the user clicks
+		// somewhere else
+		SetForegroundWindow(window);
+		let id = TrackPopupMenu(
+			menu,
+			TPM_RETURNCMD | TPM_NONOTIFY | TPM_RIGHTBUTTON,
+			cursor.x,
+			cursor.y,
+			0,
+			window,
+			ptr::null(),
+		) as usize;
+		// this destroys the demos menu too
+		DestroyMenu(menu);
+		let action = match id {
+			ID_MUTE => TrayAction::ToggleMute,
+			ID_STOP_ALL => TrayAction::StopAll,
+			id if id >= ID_FIRST_DEMO => TrayAction::OpenDemo(id - ID_FIRST_DEMO),
+			_ => return,
+		};
+		state.sender.send(action).ok();
+	});
+}
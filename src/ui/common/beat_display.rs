@@ -0,0 +1,211 @@
+use iced::{mouse::Interaction, Background, Color, Length, Point, Rectangle, Size};
+use iced_graphics::{Backend, Defaults, Primitive, Renderer};
+use iced_native::{
+	layout::{Limits, Node},
+	Element, Layout, Widget,
+};
+
+use crate::preferences::{BeatDisplayPalette, BeatDisplayStyle};
+
+/// How much width each beat takes up, so a bar of four is 200
+/// pixels wide.
+const WIDTH_PER_BEAT: f32 = 50.0;
+const DEFAULT_HEIGHT: f32 = 10.0;
+// the circles are drawn bigger with shapes so the
+// hatching is easier to see
+const SHAPES_HEIGHT: f32 = 16.0;
+const OUTLINE_WIDTH: f32 = 1.5;
+const HATCH_SPACING: f32 = 3.0;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum BeatState {
+	Idle,
+	Current,
+	Marked,
+}
+
+struct Palette {
+	idle: [f32; 4],
+	current: [f32; 4],
+	marked: [f32; 4],
+}
+
+impl Palette {
+	fn new(palette: BeatDisplayPalette) -> Self {
+		match palette {
+			BeatDisplayPalette::Classic => Self {
+				idle: [1.0, 1.0, 1.0, 0.25],
+				current: [1.0, 1.0, 1.0, 1.0],
+				marked: [1.0, 0.0, 0.0, 0.5],
+			},
+			BeatDisplayPalette::OrangeAndBlue => Self {
+				idle: [1.0, 1.0, 1.0, 0.25],
+				current: [0.9, 0.6, 0.0, 1.0],
+				marked: [0.34, 0.71, 0.91, 0.75],
+			},
+			BeatDisplayPalette::Monochrome => Self {
+				idle: [1.0, 1.0, 1.0, 0.25],
+				current: [1.0, 1.0, 1.0, 1.0],
+				marked: [1.0, 1.0, 1.0, 0.6],
+			},
+		}
+	}
+
+	fn color(&self, state: BeatState) -> Color {
+		match state {
+			BeatState::Idle => self.idle,
+			BeatState::Current => self.current,
+			BeatState::Marked => self.marked,
+		}
+		.into()
+	}
+}
+
+/// A row of circles, one for each beat of a bar, with the beat
+/// that's playing lit up.
+///
+/// Beats can also be marked in a second color, like the ones a
+/// drum fill will take up or the ones that start a group in an
+/// odd meter.
+pub struct BeatDisplay {
+	pub beats_per_bar: usize,
+	/// The beat that's playing (counting from 0), if any.
+	pub current: Option<usize>,
+	/// The beats to mark (counting from 0).
+	pub marked: Vec<usize>,
+	pub style: BeatDisplayStyle,
+}
+
+impl BeatDisplay {
+	fn beat_state(&self, beat_index: usize) -> BeatState {
+		if self.current == Some(beat_index) {
+			BeatState::Current
+		} else if self.marked.contains(&beat_index) {
+			BeatState::Marked
+		} else {
+			BeatState::Idle
+		}
+	}
+
+	fn circle(bounds: Rectangle, background: Color, border_color: Color) -> Primitive {
+		Primitive::Quad {
+			bounds,
+			background: Background::Color(background),
+			border_radius: bounds.height / 2.0,
+			border_width: if border_color == Color::TRANSPARENT {
+				0.0
+			} else {
+				OUTLINE_WIDTH
+			},
+			border_color,
+		}
+	}
+
+	/// Horizontal lines across a circle, each as wide as the
+	/// circle is at that height.
+	fn hatching(bounds: Rectangle, color: Color) -> Vec<Primitive> {
+		let radius = bounds.height / 2.0;
+		let center = bounds.center();
+		let mut primitives = vec![];
+		let mut offset = -radius + HATCH_SPACING / 2.0;
+		while offset < radius {
+			let half_width = (radius * radius - offset * offset).sqrt();
+			primitives.push(Primitive::Quad {
+				bounds: Rectangle::new(
+					Point::new(center.x - half_width, center.y + offset - 0.5),
+					Size::new(half_width * 2.0, 1.0),
+				),
+				background: Background::Color(color),
+				border_radius: 0.0,
+				border_width: 0.0,
+				border_color: Color::TRANSPARENT,
+			});
+			offset += HATCH_SPACING;
+		}
+		primitives
+	}
+
+	fn draw_beat(&self, bounds: Rectangle, state: BeatState, palette: &Palette) -> Primitive {
+		let color = palette.color(state);
+		if !self.style.shapes {
+			return Self::circle(bounds, color, Color::TRANSPARENT);
+		}
+		match state {
+			BeatState::Current => Self::circle(bounds, color, Color::TRANSPARENT),
+			BeatState::Idle => Self::circle(bounds, Color::TRANSPARENT, color),
+			BeatState::Marked => {
+				let mut primitives = Self::hatching(bounds, color);
+				primitives.push(Self::circle(bounds, Color::TRANSPARENT, color));
+				Primitive::Group { primitives }
+			}
+		}
+	}
+}
+
+impl<Message, B: Backend> Widget<Message, Renderer<B>> for BeatDisplay {
+	fn width(&self) -> Length {
+		Length::Shrink
+	}
+
+	fn height(&self) -> Length {
+		Length::Shrink
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, _limits: &Limits) -> Node {
+		let height = if self.style.shapes {
+			SHAPES_HEIGHT
+		} else {
+			DEFAULT_HEIGHT
+		};
+		Node::new(Size::new(
+			WIDTH_PER_BEAT * self.beats_per_bar as f32,
+			height,
+		))
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		_defaults: &Defaults,
+		layout: Layout<'_>,
+		_cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let circle_radius = bounds.height / 2.0;
+		let palette = Palette::new(self.style.palette);
+		let last_beat = (self.beats_per_bar.max(2) - 1) as f32;
+		(
+			Primitive::Group {
+				primitives: (0..self.beats_per_bar)
+					.map(|i| {
+						let x = bounds.x
+							+ (bounds.width - circle_radius * 2.0) * (i as f32) / last_beat;
+						let y = bounds.y;
+						self.draw_beat(
+							Rectangle::new(
+								Point::new(x, y),
+								Size::new(circle_radius * 2.0, circle_radius * 2.0),
+							),
+							self.beat_state(i),
+							&palette,
+						)
+					})
+					.collect(),
+			},
+			Interaction::default(),
+		)
+	}
+
+	fn hash_layout(&self, state: &mut iced_native::Hasher) {
+		use std::hash::Hash;
+		self.style.shapes.hash(state);
+		self.beats_per_bar.hash(state);
+	}
+}
+
+impl<'a, Message, B: Backend> From<BeatDisplay> for Element<'a, Message, Renderer<B>> {
+	fn from(beat_display: BeatDisplay) -> Self {
+		Element::new(beat_display)
+	}
+}
@@ -0,0 +1,175 @@
+use iced::{mouse::Interaction, Background, Color, Length, Point, Rectangle, Size, Vector};
+use iced_graphics::{
+	triangle::{Mesh2D, Vertex2D},
+	Backend, Defaults, Primitive, Renderer,
+};
+use iced_native::{
+	layout::{Limits, Node},
+	Element, Layout, Widget,
+};
+
+const BACKGROUND_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.05];
+const LINE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.75];
+const MARKER_COLOR: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+const LINE_WIDTH: f32 = 2.0;
+const MARKER_RADIUS: f32 = 4.0;
+// keeps the ends of the curve from being cut off by the edges
+const PADDING: f32 = MARKER_RADIUS;
+
+/// Plots a curve from evenly spaced samples (from 0 at the
+/// bottom to 1 at the top), with an optional dot following
+/// the curve.
+pub struct CurvePlot {
+	points: Vec<f32>,
+	marker: Option<f32>,
+	width: u16,
+	height: u16,
+}
+
+impl CurvePlot {
+	pub fn new(points: Vec<f32>, width: u16, height: u16) -> Self {
+		Self {
+			points,
+			marker: None,
+			width,
+			height,
+		}
+	}
+
+	/// Draws a dot on the curve at the given position (from 0 to 1).
+	pub fn marker(self, position: f32) -> Self {
+		Self {
+			marker: Some(position.clamp(0.0, 1.0)),
+			..self
+		}
+	}
+
+	/// Gets the point on the plot (relative to its top-left
+	/// corner) at a position along the curve.
+	fn point_at(&self, size: Size, position: f32) -> Point {
+		let last = self.points.len().saturating_sub(1);
+		let index = position * last as f32;
+		let before = self
+			.points
+			.get(index.floor() as usize)
+			.copied()
+			.unwrap_or(0.0);
+		let after = self
+			.points
+			.get(index.ceil() as usize)
+			.copied()
+			.unwrap_or(before);
+		let value = before + (after - before) * index.fract();
+		Point::new(
+			PADDING + (size.width - PADDING * 2.0) * position,
+			PADDING + (size.height - PADDING * 2.0) * (1.0 - value),
+		)
+	}
+
+	fn line_mesh(&self, size: Size) -> Mesh2D {
+		let mut vertices = vec![];
+		let mut indices = vec![];
+		let last = self.points.len().saturating_sub(1).max(1) as f32;
+		for i in 1..self.points.len() {
+			let start = self.point_at(size, (i - 1) as f32 / last);
+			let end = self.point_at(size, i as f32 / last);
+			let direction = end - start;
+			let length = (direction.x * direction.x + direction.y * direction.y).sqrt();
+			if length == 0.0 {
+				continue;
+			}
+			let normal = Vector::new(
+				-direction.y / length * LINE_WIDTH / 2.0,
+				direction.x / length * LINE_WIDTH / 2.0,
+			);
+			let first_index = vertices.len() as u32;
+			for point in &[start + normal, start - normal, end - normal, end + normal] {
+				vertices.push(Vertex2D {
+					position: [point.x, point.y],
+					color: LINE_COLOR,
+				});
+			}
+			indices.extend_from_slice(&[
+				first_index,
+				first_index + 1,
+				first_index + 2,
+				first_index,
+				first_index + 2,
+				first_index + 3,
+			]);
+		}
+		Mesh2D { vertices, indices }
+	}
+}
+
+impl<Message, B: Backend> Widget<Message, Renderer<B>> for CurvePlot {
+	fn width(&self) -> Length {
+		Length::Units(self.width)
+	}
+
+	fn height(&self) -> Length {
+		Length::Units(self.height)
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, limits: &Limits) -> Node {
+		Node::new(
+			limits
+				.width(Length::Units(self.width))
+				.height(Length::Units(self.height))
+				.resolve(Size::ZERO),
+		)
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		_defaults: &Defaults,
+		layout: Layout<'_>,
+		_cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let mut primitives = vec![
+			Primitive::Quad {
+				bounds,
+				background: Background::Color(BACKGROUND_COLOR.into()),
+				border_radius: 0.0,
+				border_width: 0.0,
+				border_color: Color::TRANSPARENT,
+			},
+			Primitive::Translate {
+				translation: Vector::new(bounds.x, bounds.y),
+				content: Box::new(Primitive::Mesh2D {
+					buffers: self.line_mesh(bounds.size()),
+					size: bounds.size(),
+				}),
+			},
+		];
+		if let Some(position) = self.marker {
+			let point = self.point_at(bounds.size(), position) + Vector::new(bounds.x, bounds.y);
+			primitives.push(Primitive::Quad {
+				bounds: Rectangle::new(
+					Point::new(point.x - MARKER_RADIUS, point.y - MARKER_RADIUS),
+					Size::new(MARKER_RADIUS * 2.0, MARKER_RADIUS * 2.0),
+				),
+				background: Background::Color(MARKER_COLOR.into()),
+				border_radius: MARKER_RADIUS,
+				border_width: 0.0,
+				border_color: Color::TRANSPARENT,
+			});
+		}
+		(Primitive::Group { primitives }, Interaction::default())
+	}
+
+	fn hash_layout(&self, state: &mut iced_native::Hasher) {
+		use std::hash::Hash;
+		self.width.hash(state);
+		self.height.hash(state);
+	}
+}
+
+impl<'a, Message, B: Backend> From<CurvePlot> for Element<'a, Message, Renderer<B>> {
+	fn from(curve_plot: CurvePlot) -> Self {
+		Element::new(curve_plot)
+	}
+}
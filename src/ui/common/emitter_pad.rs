@@ -0,0 +1,226 @@
+use iced::{mouse::Interaction, Background, Color, Length, Point, Rectangle, Size};
+use iced_graphics::{Backend, Defaults, Primitive, Renderer};
+use iced_native::{
+	event::Status,
+	layout::{Limits, Node},
+	mouse, Clipboard, Element, Event, Layout, Widget,
+};
+
+const SIZE: u16 = 300;
+const BACKGROUND_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.05];
+const RING_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.2];
+const LISTENER_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const EMITTER_COLOR: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+const DOT_RADIUS: f32 = 6.0;
+/// How big the emitter is for grabbing it, which is a bit
+/// more forgiving than the dot itself.
+const GRAB_RADIUS: f32 = 12.0;
+
+/// Whether the emitter is being dragged. This has to outlive
+/// the widget, which is rebuilt every time the screen is drawn.
+#[derive(Debug, Default)]
+pub struct State {
+	dragging: bool,
+}
+
+/// A top-down view of a listener at the origin (facing towards
+/// negative y) and a sound emitter the user can drag around.
+/// Clicking anywhere on the pad moves the emitter there.
+pub struct EmitterPad<'a, Message> {
+	state: &'a mut State,
+	/// How far the edge of the pad is from the listener, in
+	/// the same units as positions.
+	radius: f32,
+	emitter: (f32, f32),
+	rings: Vec<f32>,
+	on_drag: Option<Box<dyn Fn(f32, f32) -> Message + 'a>>,
+}
+
+impl<'a, Message> EmitterPad<'a, Message> {
+	pub fn new(state: &'a mut State, radius: f32, emitter: (f32, f32)) -> Self {
+		Self {
+			state,
+			radius,
+			emitter,
+			rings: vec![],
+			on_drag: None,
+		}
+	}
+
+	/// Draws circles around the listener at the given distances.
+	pub fn rings(self, rings: Vec<f32>) -> Self {
+		Self { rings, ..self }
+	}
+
+	/// Reports the emitter's new position while it's dragged.
+	pub fn on_drag(self, on_drag: impl Fn(f32, f32) -> Message + 'a) -> Self {
+		Self {
+			on_drag: Some(Box::new(on_drag)),
+			..self
+		}
+	}
+
+	fn scale(&self, bounds: Rectangle) -> f32 {
+		bounds.width / 2.0 / self.radius
+	}
+
+	fn screen_point(&self, bounds: Rectangle, x: f32, y: f32) -> Point {
+		let scale = self.scale(bounds);
+		let center = bounds.center();
+		Point::new(center.x + x * scale, center.y + y * scale)
+	}
+
+	/// Converts a point on the screen to a position, keeping it
+	/// on the pad even if the cursor has left it.
+	fn position_at(&self, bounds: Rectangle, point: Point) -> (f32, f32) {
+		let scale = self.scale(bounds);
+		let center = bounds.center();
+		(
+			((point.x - center.x) / scale).clamp(-self.radius, self.radius),
+			((point.y - center.y) / scale).clamp(-self.radius, self.radius),
+		)
+	}
+
+	fn circle(center: Point, radius: f32, background: Color, border_color: Color) -> Primitive {
+		Primitive::Quad {
+			bounds: Rectangle::new(
+				Point::new(center.x - radius, center.y - radius),
+				Size::new(radius * 2.0, radius * 2.0),
+			),
+			background: Background::Color(background),
+			border_radius: radius,
+			border_width: if border_color == Color::TRANSPARENT {
+				0.0
+			} else {
+				1.0
+			},
+			border_color,
+		}
+	}
+}
+
+impl<'a, Message, B: Backend> Widget<Message, Renderer<B>> for EmitterPad<'a, Message> {
+	fn width(&self) -> Length {
+		Length::Units(SIZE)
+	}
+
+	fn height(&self) -> Length {
+		Length::Units(SIZE)
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, limits: &Limits) -> Node {
+		Node::new(
+			limits
+				.width(Length::Units(SIZE))
+				.height(Length::Units(SIZE))
+				.resolve(Size::ZERO),
+		)
+	}
+
+	fn on_event(
+		&mut self,
+		event: Event,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		messages: &mut Vec<Message>,
+		_renderer: &Renderer<B>,
+		_clipboard: Option<&dyn Clipboard>,
+	) -> Status {
+		let bounds = layout.bounds();
+		let on_drag = match &self.on_drag {
+			Some(on_drag) => on_drag,
+			None => return Status::Ignored,
+		};
+		match event {
+			Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+				if bounds.contains(cursor_position) =>
+			{
+				self.state.dragging = true;
+				let (x, y) = self.position_at(bounds, cursor_position);
+				messages.push(on_drag(x, y));
+				Status::Captured
+			}
+			Event::Mouse(mouse::Event::CursorMoved { .. }) if self.state.dragging => {
+				let (x, y) = self.position_at(bounds, cursor_position);
+				messages.push(on_drag(x, y));
+				Status::Captured
+			}
+			Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
+				if self.state.dragging =>
+			{
+				self.state.dragging = false;
+				Status::Captured
+			}
+			_ => Status::Ignored,
+		}
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		_defaults: &Defaults,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let center = bounds.center();
+		let mut primitives = vec![Primitive::Quad {
+			bounds,
+			background: Background::Color(BACKGROUND_COLOR.into()),
+			border_radius: 0.0,
+			border_width: 0.0,
+			border_color: Color::TRANSPARENT,
+		}];
+		for ring in &self.rings {
+			primitives.push(Self::circle(
+				center,
+				ring * self.scale(bounds),
+				Color::TRANSPARENT,
+				RING_COLOR.into(),
+			));
+		}
+		primitives.push(Self::circle(
+			center,
+			DOT_RADIUS,
+			LISTENER_COLOR.into(),
+			Color::TRANSPARENT,
+		));
+		// shows which way the listener is facing
+		primitives.push(Self::circle(
+			Point::new(center.x, center.y - DOT_RADIUS * 2.0),
+			DOT_RADIUS / 3.0,
+			LISTENER_COLOR.into(),
+			Color::TRANSPARENT,
+		));
+		let (x, y) = self.emitter;
+		let emitter = self.screen_point(bounds, x, y);
+		primitives.push(Self::circle(
+			emitter,
+			DOT_RADIUS,
+			EMITTER_COLOR.into(),
+			Color::TRANSPARENT,
+		));
+		let over_emitter = emitter.distance(cursor_position) <= GRAB_RADIUS;
+		let interaction = match &self.on_drag {
+			Some(_) if self.state.dragging => Interaction::Grabbing,
+			Some(_) if over_emitter => Interaction::Grab,
+			Some(_) if bounds.contains(cursor_position) => Interaction::Pointer,
+			_ => Interaction::default(),
+		};
+		(Primitive::Group { primitives }, interaction)
+	}
+
+	fn hash_layout(&self, state: &mut iced_native::Hasher) {
+		use std::hash::Hash;
+		SIZE.hash(state)
+	}
+}
+
+impl<'a, Message: 'a, B: Backend + 'a> From<EmitterPad<'a, Message>>
+	for Element<'a, Message, Renderer<B>>
+{
+	fn from(emitter_pad: EmitterPad<'a, Message>) -> Self {
+		Element::new(emitter_pad)
+	}
+}
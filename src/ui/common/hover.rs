@@ -0,0 +1,110 @@
+use iced::{Length, Point, Rectangle};
+use iced_graphics::{Backend, Defaults, Primitive, Renderer};
+use iced_native::{
+	event::Status,
+	layout::{Limits, Node},
+	mouse, Clipboard, Element, Event, Layout, Widget,
+};
+
+/// Whether the cursor is over the widget. This has to outlive
+/// the widget, which is rebuilt every time the screen is drawn.
+#[derive(Debug, Default)]
+pub struct State {
+	hovered: bool,
+}
+
+/// Wraps another widget and reports when the cursor moves onto
+/// it, which iced's own widgets don't do. Everything else is
+/// passed through to the wrapped widget untouched.
+pub struct Hover<'a, Message, B: Backend> {
+	state: &'a mut State,
+	content: Element<'a, Message, Renderer<B>>,
+	on_enter: Option<Message>,
+}
+
+impl<'a, Message, B: Backend> Hover<'a, Message, B> {
+	pub fn new(
+		state: &'a mut State,
+		content: impl Into<Element<'a, Message, Renderer<B>>>,
+	) -> Self {
+		Self {
+			state,
+			content: content.into(),
+			on_enter: None,
+		}
+	}
+
+	/// Sends a message when the cursor moves onto the widget.
+	pub fn on_enter(self, message: Message) -> Self {
+		Self {
+			on_enter: Some(message),
+			..self
+		}
+	}
+}
+
+impl<'a, Message: Clone, B: Backend> Widget<Message, Renderer<B>> for Hover<'a, Message, B> {
+	fn width(&self) -> Length {
+		self.content.width()
+	}
+
+	fn height(&self) -> Length {
+		self.content.height()
+	}
+
+	fn layout(&self, renderer: &Renderer<B>, limits: &Limits) -> Node {
+		self.content.layout(renderer, limits)
+	}
+
+	fn on_event(
+		&mut self,
+		event: Event,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		messages: &mut Vec<Message>,
+		renderer: &Renderer<B>,
+		clipboard: Option<&dyn Clipboard>,
+	) -> Status {
+		if let Event::Mouse(mouse::Event::CursorMoved { .. }) = event {
+			let hovered = layout.bounds().contains(cursor_position);
+			if hovered && !self.state.hovered {
+				if let Some(message) = &self.on_enter {
+					messages.push(message.clone());
+				}
+			}
+			self.state.hovered = hovered;
+		}
+		self.content.on_event(
+			event,
+			layout,
+			cursor_position,
+			messages,
+			renderer,
+			clipboard,
+		)
+	}
+
+	fn draw(
+		&self,
+		renderer: &mut Renderer<B>,
+		defaults: &Defaults,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		viewport: &Rectangle,
+	) -> (Primitive, iced::mouse::Interaction) {
+		self.content
+			.draw(renderer, defaults, layout, cursor_position, viewport)
+	}
+
+	fn hash_layout(&self, state: &mut iced_native::Hasher) {
+		self.content.hash_layout(state)
+	}
+}
+
+impl<'a, Message: Clone + 'a, B: Backend + 'a> From<Hover<'a, Message, B>>
+	for Element<'a, Message, Renderer<B>>
+{
+	fn from(hover: Hover<'a, Message, B>) -> Self {
+		Element::new(hover)
+	}
+}
@@ -0,0 +1,226 @@
+use iced::{mouse::Interaction, Background, Length, Point, Rectangle, Size, Vector};
+use iced_graphics::{
+	triangle::{Mesh2D, Vertex2D},
+	Backend, Defaults, Primitive, Renderer,
+};
+use iced_native::{
+	layout::{Limits, Node},
+	Element, HorizontalAlignment, Layout, VerticalAlignment, Widget,
+};
+
+const NODE_WIDTH: f32 = 120.0;
+const NODE_HEIGHT: f32 = 32.0;
+const ROW_HEIGHT: f32 = 48.0;
+const NODE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.1];
+const NODE_BORDER_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.5];
+const EDGE_COLOR: [f32; 3] = [1.0, 1.0, 1.0];
+const MIN_EDGE_WIDTH: f32 = 1.0;
+const MAX_EDGE_WIDTH: f32 = 5.0;
+
+struct GraphNode {
+	label: String,
+	column: usize,
+}
+
+struct Edge {
+	from: usize,
+	to: usize,
+	amount: f32,
+}
+
+/// Draws mixer routing as boxes (sources, tracks, and so on)
+/// arranged in columns, connected by lines whose thickness
+/// shows how much signal flows along them (from 0 to 1).
+///
+/// Nodes are referred to by the order they were added in.
+pub struct MixerGraph {
+	nodes: Vec<GraphNode>,
+	edges: Vec<Edge>,
+}
+
+impl MixerGraph {
+	pub fn new() -> Self {
+		Self {
+			nodes: vec![],
+			edges: vec![],
+		}
+	}
+
+	pub fn node(mut self, label: impl Into<String>, column: usize) -> Self {
+		self.nodes.push(GraphNode {
+			label: label.into(),
+			column,
+		});
+		self
+	}
+
+	pub fn edge(mut self, from: usize, to: usize, amount: f32) -> Self {
+		self.edges.push(Edge { from, to, amount });
+		self
+	}
+
+	fn num_columns(&self) -> usize {
+		self.nodes
+			.iter()
+			.map(|node| node.column + 1)
+			.max()
+			.unwrap_or(0)
+	}
+
+	fn num_rows(&self, column: usize) -> usize {
+		self.nodes
+			.iter()
+			.filter(|node| node.column == column)
+			.count()
+	}
+
+	fn height(&self) -> f32 {
+		let max_rows = (0..self.num_columns())
+			.map(|column| self.num_rows(column))
+			.max()
+			.unwrap_or(0);
+		max_rows as f32 * ROW_HEIGHT
+	}
+
+	/// Gets the bounds of each node relative to the top-left
+	/// corner of the widget. Columns are spread evenly across
+	/// the width and centered vertically.
+	fn node_bounds(&self, size: Size) -> Vec<Rectangle> {
+		let num_columns = self.num_columns();
+		let column_spacing = if num_columns > 1 {
+			(size.width - NODE_WIDTH) / (num_columns - 1) as f32
+		} else {
+			0.0
+		};
+		let mut rows_used = vec![0; num_columns];
+		self.nodes
+			.iter()
+			.map(|node| {
+				let row = rows_used[node.column];
+				rows_used[node.column] += 1;
+				let column_height = self.num_rows(node.column) as f32 * ROW_HEIGHT;
+				let top = (size.height - column_height) / 2.0;
+				Rectangle::new(
+					Point::new(
+						node.column as f32 * column_spacing,
+						top + row as f32 * ROW_HEIGHT + (ROW_HEIGHT - NODE_HEIGHT) / 2.0,
+					),
+					Size::new(NODE_WIDTH, NODE_HEIGHT),
+				)
+			})
+			.collect()
+	}
+
+	fn edge_mesh(&self, node_bounds: &[Rectangle]) -> Mesh2D {
+		let mut vertices = vec![];
+		let mut indices = vec![];
+		for edge in &self.edges {
+			let amount = edge.amount.clamp(0.0, 1.0);
+			let from = node_bounds[edge.from];
+			let to = node_bounds[edge.to];
+			let start = Point::new(from.x + from.width, from.y + from.height / 2.0);
+			let end = Point::new(to.x, to.y + to.height / 2.0);
+			let direction = end - start;
+			let length = (direction.x * direction.x + direction.y * direction.y).sqrt();
+			if length == 0.0 {
+				continue;
+			}
+			let half_width = (MIN_EDGE_WIDTH + (MAX_EDGE_WIDTH - MIN_EDGE_WIDTH) * amount) / 2.0;
+			let normal = Vector::new(
+				-direction.y / length * half_width,
+				direction.x / length * half_width,
+			);
+			let color = [
+				EDGE_COLOR[0],
+				EDGE_COLOR[1],
+				EDGE_COLOR[2],
+				0.15 + 0.85 * amount,
+			];
+			let first_index = vertices.len() as u32;
+			for point in &[start + normal, start - normal, end - normal, end + normal] {
+				vertices.push(Vertex2D {
+					position: [point.x, point.y],
+					color,
+				});
+			}
+			indices.extend_from_slice(&[
+				first_index,
+				first_index + 1,
+				first_index + 2,
+				first_index,
+				first_index + 2,
+				first_index + 3,
+			]);
+		}
+		Mesh2D { vertices, indices }
+	}
+}
+
+impl<Message, B: Backend> Widget<Message, Renderer<B>> for MixerGraph {
+	fn width(&self) -> Length {
+		Length::Fill
+	}
+
+	fn height(&self) -> Length {
+		Length::Shrink
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, limits: &Limits) -> Node {
+		Node::new(
+			limits
+				.width(Length::Fill)
+				.height(Length::Units(self.height() as u16))
+				.resolve(Size::ZERO),
+		)
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		defaults: &Defaults,
+		layout: Layout<'_>,
+		_cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let node_bounds = self.node_bounds(bounds.size());
+		let mut primitives = vec![Primitive::Translate {
+			translation: Vector::new(bounds.x, bounds.y),
+			content: Box::new(Primitive::Mesh2D {
+				buffers: self.edge_mesh(&node_bounds),
+				size: bounds.size(),
+			}),
+		}];
+		for (node, node_bounds) in self.nodes.iter().zip(&node_bounds) {
+			let node_bounds = *node_bounds + Vector::new(bounds.x, bounds.y);
+			primitives.push(Primitive::Quad {
+				bounds: node_bounds,
+				background: Background::Color(NODE_COLOR.into()),
+				border_radius: 4.0,
+				border_width: 1.0,
+				border_color: NODE_BORDER_COLOR.into(),
+			});
+			primitives.push(Primitive::Text {
+				content: node.label.clone(),
+				bounds: Rectangle::new(node_bounds.center(), node_bounds.size()),
+				color: defaults.text.color,
+				size: 16.0,
+				font: Default::default(),
+				horizontal_alignment: HorizontalAlignment::Center,
+				vertical_alignment: VerticalAlignment::Center,
+			});
+		}
+		(Primitive::Group { primitives }, Interaction::default())
+	}
+
+	fn hash_layout(&self, state: &mut iced_native::Hasher) {
+		use std::hash::Hash;
+		self.height().to_bits().hash(state)
+	}
+}
+
+impl<'a, Message, B: Backend> From<MixerGraph> for Element<'a, Message, Renderer<B>> {
+	fn from(mixer_graph: MixerGraph) -> Self {
+		Element::new(mixer_graph)
+	}
+}
@@ -1,2 +1,10 @@
+pub mod beat_display;
+pub mod curve_plot;
+pub mod emitter_pad;
 pub mod header;
+pub mod hover;
+pub mod mixer_graph;
 pub mod screen_wrapper;
+pub mod transport;
+pub mod waveform;
+pub mod wet_dry_control;
@@ -17,8 +17,32 @@ impl<Message: Clone> ScreenWrapper<Message> {
 		&'a mut self,
 		contents: C,
 	) -> iced::Element<'a, Message> {
-		Column::new()
-			.push(self.header.view())
+		Self::layout(self.header.view(), None, contents)
+	}
+
+	/// Shows the transport bar between the header and the screen's
+	/// contents.
+	pub fn view_with_transport<'a, C: Into<iced::Element<'a, Message>>>(
+		&'a mut self,
+		transport: iced::Element<'a, Message>,
+		contents: C,
+	) -> iced::Element<'a, Message> {
+		Self::layout(self.header.view(), Some(transport), contents)
+	}
+
+	fn layout<'a, C: Into<iced::Element<'a, Message>>>(
+		header: iced::Element<'a, Message>,
+		transport: Option<iced::Element<'a, Message>>,
+		contents: C,
+	) -> iced::Element<'a, Message>
+	where
+		Message: 'a,
+	{
+		let mut column = Column::new().push(header);
+		if let Some(transport) = transport {
+			column = column.push(transport);
+		}
+		column
 			.push(
 				Container::new(contents)
 					.width(Length::Fill)
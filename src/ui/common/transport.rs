@@ -0,0 +1,206 @@
+use std::time::Instant;
+
+use iced::{Align, Button, Checkbox, HorizontalAlignment, Length, Row, Text};
+use kira::{
+	manager::AudioManager,
+	playable::PlayableSettings,
+	sequence::{Sequence, SequenceInstanceId},
+	sound::SoundId,
+	AudioResult, Duration, Event, MetronomeSettings, Tempo,
+};
+
+use crate::{audio::synth, ui::style::AppStyles};
+
+const BEATS_PER_BAR: usize = 4;
+
+/// The play/stop button, position, tempo, and metronome click
+/// that the music demos share.
+///
+/// The position is counted from the audio manager's metronome,
+/// so the manager should be created with
+/// [`Transport::metronome_settings`], and the screen should start
+/// and stop its music at the same time as the transport.
+pub struct Transport {
+	tempo: Tempo,
+	click_sound_id: SoundId,
+	click_sequence_id: Option<SequenceInstanceId>,
+	click_enabled: bool,
+	playing: bool,
+	beats_passed: usize,
+	/// When the last beat was reported, for estimating
+	/// how far the music is between beats.
+	last_beat_time: Option<Instant>,
+	play_button: iced::button::State,
+}
+
+impl Transport {
+	/// Sets the metronome to the music's tempo and makes it
+	/// report every beat.
+	pub fn metronome_settings(tempo: Tempo) -> MetronomeSettings {
+		MetronomeSettings {
+			tempo: tempo.into(),
+			interval_events_to_emit: vec![1.0],
+		}
+	}
+
+	pub fn new(audio_manager: &mut AudioManager, tempo: Tempo) -> AudioResult<Self> {
+		let click_sound_id =
+			audio_manager.add_sound(synth::blip(1760.0, 0.05, PlayableSettings::new()))?;
+		Ok(Self {
+			tempo,
+			click_sound_id,
+			click_sequence_id: None,
+			click_enabled: false,
+			playing: false,
+			beats_passed: 0,
+			last_beat_time: None,
+			play_button: iced::button::State::new(),
+		})
+	}
+
+	/// Changes the tempo of the transport and the audio
+	/// manager's metronome.
+	pub fn set_tempo(&mut self, audio_manager: &mut AudioManager, tempo: Tempo) -> AudioResult<()> {
+		self.tempo = tempo;
+		audio_manager.set_metronome_tempo(tempo)
+	}
+
+	/// Updates the tempo the transport shows (and uses to
+	/// estimate the position between beats) without changing
+	/// the metronome, for screens that drive the metronome's
+	/// tempo with a parameter.
+	pub fn set_live_tempo(&mut self, tempo: Tempo) {
+		self.tempo = tempo;
+	}
+
+	pub fn is_playing(&self) -> bool {
+		self.playing
+	}
+
+	pub fn click_enabled(&self) -> bool {
+		self.click_enabled
+	}
+
+	pub fn start(&mut self, audio_manager: &mut AudioManager) -> AudioResult<()> {
+		self.playing = true;
+		self.beats_passed = 0;
+		self.last_beat_time = None;
+		if self.click_enabled {
+			self.start_click(audio_manager)?;
+		}
+		audio_manager.start_metronome()
+	}
+
+	pub fn stop(&mut self, audio_manager: &mut AudioManager) -> AudioResult<()> {
+		self.playing = false;
+		self.stop_click(audio_manager)?;
+		audio_manager.stop_metronome()
+	}
+
+	pub fn set_click_enabled(
+		&mut self,
+		audio_manager: &mut AudioManager,
+		enabled: bool,
+	) -> AudioResult<()> {
+		self.click_enabled = enabled;
+		match (self.playing, enabled) {
+			(true, true) => self.start_click(audio_manager),
+			(true, false) => self.stop_click(audio_manager),
+			_ => Ok(()),
+		}
+	}
+
+	fn start_click(&mut self, audio_manager: &mut AudioManager) -> AudioResult<()> {
+		self.stop_click(audio_manager)?;
+		let mut sequence = Sequence::<()>::new(Default::default());
+		sequence.wait_for_interval(1.0);
+		sequence.start_loop();
+		sequence.play(self.click_sound_id, Default::default());
+		sequence.wait(Duration::Beats(1.0));
+		let (sequence_id, _) = audio_manager.start_sequence(sequence, Default::default())?;
+		self.click_sequence_id = Some(sequence_id);
+		Ok(())
+	}
+
+	fn stop_click(&mut self, audio_manager: &mut AudioManager) -> AudioResult<()> {
+		if let Some(sequence_id) = self.click_sequence_id.take() {
+			audio_manager.stop_sequence(sequence_id)?;
+		}
+		Ok(())
+	}
+
+	/// Counts the beats the metronome has passed since the last call.
+	pub fn check_for_events(&mut self, audio_manager: &mut AudioManager) {
+		while let Some(event) = audio_manager.pop_event() {
+			match event {
+				Event::MetronomeIntervalPassed(_) => {
+					if self.playing {
+						self.beats_passed += 1;
+						self.last_beat_time = Some(Instant::now());
+					}
+				}
+			}
+		}
+	}
+
+	/// How many beats the music has played, if it's playing.
+	/// The metronome only reports whole beats, so the part
+	/// since the last beat is estimated from the time that's
+	/// passed since then.
+	pub fn beat_position(&self) -> Option<f64> {
+		let beat = self.beats_passed.checked_sub(1)?;
+		let last_beat_time = self.last_beat_time?;
+		if !self.playing {
+			return None;
+		}
+		let since_last_beat =
+			last_beat_time.elapsed().as_secs_f64() / self.tempo.beats_to_seconds(1.0);
+		Some(beat as f64 + since_last_beat.min(1.0))
+	}
+
+	fn position_text(&self) -> String {
+		// the metronome reports the first beat as soon as it starts
+		match self.beats_passed.checked_sub(1) {
+			Some(beat) if self.playing => {
+				format!("{}:{}", beat / BEATS_PER_BAR + 1, beat % BEATS_PER_BAR + 1)
+			}
+			_ => "-:-".into(),
+		}
+	}
+
+	pub fn view<Message: Clone + 'static>(
+		&mut self,
+		play_message: Message,
+		stop_message: Message,
+		set_click_enabled_message: impl Fn(bool) -> Message + 'static,
+	) -> iced::Element<'_, Message> {
+		let position_text = self.position_text();
+		Row::new()
+			.padding(8)
+			.spacing(16)
+			.align_items(Align::Center)
+			.push(
+				Button::new(
+					&mut self.play_button,
+					Text::new(if self.playing { "Stop" } else { "Play" })
+						.width(Length::Fill)
+						.horizontal_alignment(HorizontalAlignment::Center),
+				)
+				.on_press(if self.playing {
+					stop_message
+				} else {
+					play_message
+				})
+				.width(Length::Units(100))
+				.style(AppStyles),
+			)
+			.push(Text::new(position_text).width(Length::Units(60)))
+			.push(Text::new(format!("{} BPM", self.tempo.0)))
+			.push(Checkbox::new(
+				self.click_enabled,
+				"Metronome",
+				set_click_enabled_message,
+			))
+			.into()
+	}
+}
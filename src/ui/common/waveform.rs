@@ -0,0 +1,212 @@
+use iced::{mouse::Interaction, Background, Color, Length, Point, Rectangle, Size};
+use iced_graphics::{Backend, Defaults, Primitive, Renderer};
+use iced_native::{
+	event::Status,
+	layout::{Limits, Node},
+	mouse, Clipboard, Element, Event, Layout, Widget,
+};
+
+const HEIGHT: u16 = 80;
+const BACKGROUND_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.05];
+const PEAK_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.5];
+const GRID_LINE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.1];
+const MARKER_WIDTH: f32 = 2.0;
+
+/// Whether the mouse is held down on a draggable waveform. This
+/// has to outlive the widget, which is rebuilt every time the
+/// screen is drawn.
+#[derive(Debug, Default)]
+pub struct State {
+	dragging: bool,
+}
+
+/// Draws the peaks of a sound and reports where the user
+/// clicks (as a fraction of the width, from 0 to 1).
+pub struct Waveform<'a, Message> {
+	peaks: &'a [f32],
+	grid_lines: Vec<f32>,
+	markers: Vec<(f32, [f32; 4])>,
+	on_press: Option<Box<dyn Fn(f32) -> Message + 'a>>,
+	drag_state: Option<&'a mut State>,
+}
+
+impl<'a, Message> Waveform<'a, Message> {
+	pub fn new(peaks: &'a [f32]) -> Self {
+		Self {
+			peaks,
+			grid_lines: vec![],
+			markers: vec![],
+			on_press: None,
+			drag_state: None,
+		}
+	}
+
+	/// Draws faint vertical lines at the given positions (from 0 to 1).
+	pub fn grid_lines(self, grid_lines: Vec<f32>) -> Self {
+		Self { grid_lines, ..self }
+	}
+
+	/// Draws a colored vertical line at the given position (from 0 to 1).
+	pub fn marker(mut self, position: f32, color: [f32; 4]) -> Self {
+		self.markers.push((position, color));
+		self
+	}
+
+	pub fn on_press(self, on_press: impl Fn(f32) -> Message + 'a) -> Self {
+		Self {
+			on_press: Some(Box::new(on_press)),
+			..self
+		}
+	}
+
+	/// Keeps reporting the cursor position while the mouse is
+	/// held down after a press, so the user can drag along the
+	/// waveform.
+	pub fn draggable(self, state: &'a mut State) -> Self {
+		Self {
+			drag_state: Some(state),
+			..self
+		}
+	}
+
+	fn fraction_at(bounds: Rectangle, cursor_position: Point) -> f32 {
+		((cursor_position.x - bounds.x) / bounds.width).clamp(0.0, 1.0)
+	}
+
+	fn vertical_line(bounds: Rectangle, position: f32, width: f32, color: [f32; 4]) -> Primitive {
+		Primitive::Quad {
+			bounds: Rectangle::new(
+				Point::new(
+					bounds.x + bounds.width * position.clamp(0.0, 1.0) - width / 2.0,
+					bounds.y,
+				),
+				Size::new(width, bounds.height),
+			),
+			background: Background::Color(color.into()),
+			border_radius: 0.0,
+			border_width: 0.0,
+			border_color: Color::TRANSPARENT,
+		}
+	}
+}
+
+impl<'a, Message, B: Backend> Widget<Message, Renderer<B>> for Waveform<'a, Message> {
+	fn width(&self) -> Length {
+		Length::Fill
+	}
+
+	fn height(&self) -> Length {
+		Length::Units(HEIGHT)
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, limits: &Limits) -> Node {
+		Node::new(
+			limits
+				.width(Length::Fill)
+				.height(Length::Units(HEIGHT))
+				.resolve(Size::ZERO),
+		)
+	}
+
+	fn on_event(
+		&mut self,
+		event: Event,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		messages: &mut Vec<Message>,
+		_renderer: &Renderer<B>,
+		_clipboard: Option<&dyn Clipboard>,
+	) -> Status {
+		let bounds = layout.bounds();
+		let on_press = match &self.on_press {
+			Some(on_press) => on_press,
+			None => return Status::Ignored,
+		};
+		match event {
+			Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+				if bounds.contains(cursor_position) =>
+			{
+				if let Some(state) = &mut self.drag_state {
+					state.dragging = true;
+				}
+				messages.push(on_press(Self::fraction_at(bounds, cursor_position)));
+				Status::Captured
+			}
+			Event::Mouse(mouse::Event::CursorMoved { .. }) if matches!(&self.drag_state, Some(state) if state.dragging) =>
+			{
+				messages.push(on_press(Self::fraction_at(bounds, cursor_position)));
+				Status::Captured
+			}
+			Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+				match &mut self.drag_state {
+					Some(state) if state.dragging => {
+						state.dragging = false;
+						Status::Captured
+					}
+					_ => Status::Ignored,
+				}
+			}
+			_ => Status::Ignored,
+		}
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		_defaults: &Defaults,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let mut primitives = vec![Primitive::Quad {
+			bounds,
+			background: Background::Color(BACKGROUND_COLOR.into()),
+			border_radius: 0.0,
+			border_width: 0.0,
+			border_color: Color::TRANSPARENT,
+		}];
+		for position in &self.grid_lines {
+			primitives.push(Self::vertical_line(bounds, *position, 1.0, GRID_LINE_COLOR));
+		}
+		let peak_width = bounds.width / self.peaks.len().max(1) as f32;
+		for (i, peak) in self.peaks.iter().enumerate() {
+			let peak_height = bounds.height * peak.min(1.0);
+			primitives.push(Primitive::Quad {
+				bounds: Rectangle::new(
+					Point::new(
+						bounds.x + peak_width * i as f32,
+						bounds.y + (bounds.height - peak_height) / 2.0,
+					),
+					Size::new(peak_width.max(1.0), peak_height),
+				),
+				background: Background::Color(PEAK_COLOR.into()),
+				border_radius: 0.0,
+				border_width: 0.0,
+				border_color: Color::TRANSPARENT,
+			});
+		}
+		for (position, color) in &self.markers {
+			primitives.push(Self::vertical_line(bounds, *position, MARKER_WIDTH, *color));
+		}
+		let interaction = if self.on_press.is_some() && bounds.contains(cursor_position) {
+			Interaction::Pointer
+		} else {
+			Interaction::default()
+		};
+		(Primitive::Group { primitives }, interaction)
+	}
+
+	fn hash_layout(&self, state: &mut iced_native::Hasher) {
+		use std::hash::Hash;
+		HEIGHT.hash(state)
+	}
+}
+
+impl<'a, Message: 'a, B: Backend + 'a> From<Waveform<'a, Message>>
+	for Element<'a, Message, Renderer<B>>
+{
+	fn from(waveform: Waveform<'a, Message>) -> Self {
+		Element::new(waveform)
+	}
+}
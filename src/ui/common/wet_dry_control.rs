@@ -0,0 +1,49 @@
+use iced::{Align, Button, Length, Row, Slider, Text};
+
+use crate::ui::style::AppStyles;
+
+/// A slider for blending between an effect's dry and wet
+/// signal, plus a button for bypassing the effect, shared by
+/// the effect demos.
+pub struct WetDryControl<Message: Clone + 'static> {
+	slider: iced::slider::State,
+	bypass_button: iced::button::State,
+	on_change: fn(f64) -> Message,
+	on_set_bypassed: fn(bool) -> Message,
+}
+
+impl<Message: Clone + 'static> WetDryControl<Message> {
+	pub fn new(on_change: fn(f64) -> Message, on_set_bypassed: fn(bool) -> Message) -> Self {
+		Self {
+			slider: iced::slider::State::new(),
+			bypass_button: iced::button::State::new(),
+			on_change,
+			on_set_bypassed,
+		}
+	}
+
+	pub fn view(&mut self, mix: f64, bypassed: bool) -> iced::Element<'_, Message> {
+		Row::new()
+			.max_width(500)
+			.spacing(16)
+			.align_items(Align::Center)
+			.push(
+				Button::new(
+					&mut self.bypass_button,
+					Text::new(if bypassed { "Bypassed" } else { "Bypass" }),
+				)
+				.on_press((self.on_set_bypassed)(!bypassed))
+				.width(Length::Units(100))
+				.style(AppStyles),
+			)
+			.push(Text::new("Dry"))
+			.push(
+				Slider::new(&mut self.slider, 0.0..=1.0, mix, self.on_change)
+					.step(0.01)
+					.style(AppStyles),
+			)
+			.push(Text::new("Wet"))
+			.push(Text::new(format!("{}%", (mix * 100.0).round())).width(Length::Units(48)))
+			.into()
+	}
+}
@@ -0,0 +1,98 @@
+use std::time::Instant;
+
+use iced::{Align, Button, Row, Slider, Text};
+use kira::Tempo;
+
+use crate::{audio::virtual_clock::VirtualClock, ui::style::AppStyles};
+
+const BEATS_PER_BAR: f64 = 4.0;
+const MAX_TIME_SCALE: f64 = 2.0;
+
+/// A panel for stepping through the current demo on the
+/// virtual clock.
+pub struct DebugClock {
+	clock: VirtualClock,
+	time_scale: f64,
+	last_tick: Instant,
+	advance_beat_button: iced::button::State,
+	advance_bar_button: iced::button::State,
+	time_scale_slider: iced::slider::State,
+}
+
+impl DebugClock {
+	pub fn new() -> Self {
+		Self {
+			clock: VirtualClock::default(),
+			time_scale: 1.0,
+			last_tick: Instant::now(),
+			advance_beat_button: iced::button::State::new(),
+			advance_bar_button: iced::button::State::new(),
+			time_scale_slider: iced::slider::State::new(),
+		}
+	}
+
+	/// Should be called after a screen is created so the
+	/// clock drives that screen's audio managers.
+	pub fn attach_new_backends(&mut self) {
+		self.clock.attach_new_backends();
+	}
+
+	/// Advances the clock by the real time that passed since
+	/// the last tick, scaled by the time scale.
+	pub fn tick(&mut self) {
+		let now = Instant::now();
+		let elapsed = (now - self.last_tick).as_secs_f64();
+		self.last_tick = now;
+		self.clock.advance(elapsed * self.time_scale);
+	}
+
+	pub fn advance(&mut self, duration: f64) {
+		self.clock.advance(duration);
+	}
+
+	pub fn set_time_scale(&mut self, time_scale: f64) {
+		self.time_scale = time_scale;
+	}
+
+	pub fn view<Message: Clone + 'static>(
+		&mut self,
+		tempo: Tempo,
+		advance_message: fn(f64) -> Message,
+		set_time_scale_message: fn(f64) -> Message,
+	) -> iced::Element<'_, Message> {
+		let mut advance_beat_button =
+			Button::new(&mut self.advance_beat_button, Text::new("+1 beat")).style(AppStyles);
+		let mut advance_bar_button =
+			Button::new(&mut self.advance_bar_button, Text::new("+1 bar")).style(AppStyles);
+		if self.clock.is_running() {
+			advance_beat_button =
+				advance_beat_button.on_press(advance_message(tempo.beats_to_seconds(1.0)));
+			advance_bar_button =
+				advance_bar_button.on_press(advance_message(tempo.beats_to_seconds(BEATS_PER_BAR)));
+		}
+		let status = if self.clock.is_running() {
+			format!("Virtual time: {:.2}s ({} BPM)", self.clock.time(), tempo.0)
+		} else {
+			"No audio running".into()
+		};
+		Row::new()
+			.padding(8)
+			.spacing(16)
+			.align_items(Align::Center)
+			.push(advance_beat_button)
+			.push(advance_bar_button)
+			.push(Text::new(format!("Speed: {:.2}x", self.time_scale)).size(16))
+			.push(
+				Slider::new(
+					&mut self.time_scale_slider,
+					0.0..=MAX_TIME_SCALE,
+					self.time_scale,
+					set_time_scale_message,
+				)
+				.step(0.05)
+				.style(AppStyles),
+			)
+			.push(Text::new(status).size(16))
+			.into()
+	}
+}
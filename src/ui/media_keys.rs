@@ -0,0 +1,52 @@
+use iced::keyboard::KeyCode;
+
+/// One of the media keys found on many keyboards and headsets.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MediaKey {
+	PlayPause,
+	Stop,
+	Next,
+	Previous,
+}
+
+impl MediaKey {
+	pub fn from_key_code(key_code: KeyCode) -> Option<Self> {
+		match key_code {
+			KeyCode::PlayPause => Some(Self::PlayPause),
+			KeyCode::MediaStop | KeyCode::Stop => Some(Self::Stop),
+			KeyCode::NextTrack => Some(Self::Next),
+			KeyCode::PrevTrack => Some(Self::Previous),
+			_ => None,
+		}
+	}
+}
+
+/// Implemented by screens whose playback can be controlled
+/// with the media keys.
+pub trait MediaControls {
+	type Message;
+
+	/// Creates the messages a media key press should send.
+	fn media_key_messages(&self, key: MediaKey) -> Vec<Self::Message>;
+
+	/// Describes what's playing (if anything) for the window title,
+	/// which is where the OS picks up what the app is doing.
+	fn now_playing(&self) -> Option<String>;
+}
+
+/// The messages for screens where play/pause starts and stops
+/// the transport. There's no pausing in Kira, so play/pause
+/// starts the music over.
+pub fn transport_messages<Message>(
+	key: MediaKey,
+	playing: bool,
+	play_message: Message,
+	stop_message: Message,
+) -> Vec<Message> {
+	match key {
+		MediaKey::PlayPause if playing => vec![stop_message],
+		MediaKey::PlayPause => vec![play_message],
+		MediaKey::Stop if playing => vec![stop_message],
+		_ => vec![],
+	}
+}
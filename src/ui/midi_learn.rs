@@ -0,0 +1,148 @@
+use std::{collections::HashMap, error::Error};
+
+use iced::{Align, Button, Row, Text};
+
+use crate::{
+	midi::MidiController,
+	preferences::{MidiBinding, Preferences},
+	ui::style::AppStyles,
+};
+
+/// Implemented by screens with controls that can be bound to
+/// a MIDI controller.
+pub trait MidiControls {
+	type Message;
+
+	/// A name that keeps the screen's bindings separate from
+	/// other screens'.
+	const NAME: &'static str;
+
+	/// Gets the control that a message adjusts (if any). In
+	/// learn mode, this is how we tell which control the user
+	/// clicked.
+	fn control_for_message(message: &Self::Message) -> Option<String>;
+
+	/// Creates the messages that set a control to a value
+	/// (from 0 to 1).
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Self::Message>;
+}
+
+/// Connects MIDI control changes to on-screen controls.
+///
+/// In learn mode, the next control the user touches is bound
+/// to the next control change that comes in, and the binding
+/// is saved in the preferences.
+pub struct MidiLearn {
+	controller: Result<MidiController, String>,
+	learning: bool,
+	pending_control: Option<String>,
+	learn_button: iced::button::State,
+}
+
+impl MidiLearn {
+	pub fn new() -> Self {
+		Self {
+			controller: MidiController::connect().map_err(|error| error.to_string()),
+			learning: false,
+			pending_control: None,
+			learn_button: iced::button::State::new(),
+		}
+	}
+
+	pub fn toggle_learning(&mut self) {
+		self.learning = !self.learning;
+		self.pending_control = None;
+	}
+
+	/// Should be called with every message sent to a screen
+	/// so learn mode knows which control was touched last.
+	pub fn observe<S: MidiControls>(&mut self, message: &S::Message) {
+		if self.learning {
+			if let Some(control) = S::control_for_message(message) {
+				self.pending_control = Some(control);
+			}
+		}
+	}
+
+	/// Handles the control changes that came in since the last
+	/// call, returning the messages the screen should receive.
+	/// New bindings are added to the preferences and saved.
+	pub fn poll<S: MidiControls>(
+		&mut self,
+		preferences: &mut Preferences,
+		screen: &S,
+	) -> Result<Vec<S::Message>, Box<dyn Error>> {
+		let control_changes = match &self.controller {
+			Ok(controller) => controller.control_changes(),
+			Err(_) => return Ok(vec![]),
+		};
+		let prefix = format!("{}/", S::NAME);
+		// controllers send lots of messages while a knob is being
+		// turned, so only the latest value for each control is used
+		let mut values = HashMap::new();
+		for control_change in control_changes {
+			let binding = MidiBinding {
+				channel: control_change.channel,
+				controller: control_change.controller,
+			};
+			if self.learning {
+				if let Some(control) = self.pending_control.take() {
+					preferences.midi_bindings.retain(|key, existing_binding| {
+						!(key.starts_with(&prefix) && *existing_binding == binding)
+					});
+					preferences
+						.midi_bindings
+						.insert(format!("{}{}", prefix, control), binding);
+					preferences.save()?;
+					self.learning = false;
+				}
+				continue;
+			}
+			for (key, existing_binding) in &preferences.midi_bindings {
+				if *existing_binding == binding && key.starts_with(&prefix) {
+					values.insert(
+						key[prefix.len()..].to_string(),
+						control_change.value as f64 / 127.0,
+					);
+				}
+			}
+		}
+		Ok(values
+			.iter()
+			.flat_map(|(control, value)| screen.control_messages(control, *value))
+			.collect())
+	}
+
+	pub fn view<Message: Clone + 'static>(
+		&mut self,
+		toggle_learning_message: Message,
+	) -> iced::Element<'_, Message> {
+		let status = match (&self.controller, self.learning, &self.pending_control) {
+			(Err(error), _, _) => format!("MIDI unavailable: {}", error),
+			(Ok(_), true, None) => "Click a control to bind it".into(),
+			(Ok(_), true, Some(control)) => {
+				format!("Move a knob or fader to bind it to \"{}\"", control)
+			}
+			(Ok(controller), false, _) => format!("MIDI input: {}", controller.port_name()),
+		};
+		let mut learn_button = Button::new(
+			&mut self.learn_button,
+			Text::new(if self.learning {
+				"Cancel"
+			} else {
+				"MIDI learn"
+			}),
+		)
+		.style(AppStyles);
+		if self.controller.is_ok() {
+			learn_button = learn_button.on_press(toggle_learning_message);
+		}
+		Row::new()
+			.padding(8)
+			.spacing(16)
+			.align_items(Align::Center)
+			.push(learn_button)
+			.push(Text::new(status).size(16))
+			.into()
+	}
+}
@@ -1,3 +1,14 @@
 pub mod common;
+#[cfg(feature = "debug-clock")]
+pub mod debug_clock;
+pub mod media_keys;
+#[cfg(feature = "midi")]
+pub mod midi_learn;
+#[cfg(feature = "remote")]
+pub mod remote_control;
 pub mod screen;
+pub mod screen_states;
+pub mod session_player;
+pub mod session_recorder;
 pub mod style;
+pub mod undo_stack;
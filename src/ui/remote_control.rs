@@ -0,0 +1,104 @@
+use iced::{Row, Text};
+
+use crate::remote::{RemoteCommand, RemoteServer, DEFAULT_PORT};
+
+/// Implemented by screens with actions that external tools can
+/// trigger over the remote control server.
+pub trait RemoteControls {
+	type Message;
+
+	/// Creates the messages that carry out a command, or none if
+	/// the screen doesn't have that action.
+	fn remote_messages(&self, command: RemoteCommand) -> Vec<Self::Message>;
+}
+
+/// Hands commands from the remote control server to the
+/// current screen and shows whether anything is connected.
+pub struct RemoteControl {
+	server: Result<RemoteServer, String>,
+	/// The last command that came in, and whether the screen
+	/// that was open had that action.
+	last_command: Option<(RemoteCommand, bool)>,
+}
+
+impl RemoteControl {
+	pub fn new(allowed_commands: &[String], allowed_origins: &[String]) -> Self {
+		Self {
+			server: RemoteServer::start(DEFAULT_PORT, allowed_commands, allowed_origins)
+				.map_err(|error| error.to_string()),
+			last_command: None,
+		}
+	}
+
+	/// Should be called whenever the allow-list in the
+	/// preferences changes.
+	pub fn set_allowed_commands(&self, allowed_commands: &[String]) {
+		if let Ok(server) = &self.server {
+			server.set_allowed_commands(allowed_commands);
+		}
+	}
+
+	/// Should be called whenever the web pages allowed to
+	/// connect in the preferences change.
+	pub fn set_allowed_origins(&self, allowed_origins: &[String]) {
+		if let Ok(server) = &self.server {
+			server.set_allowed_origins(allowed_origins);
+		}
+	}
+
+	fn commands(&self) -> Vec<RemoteCommand> {
+		match &self.server {
+			Ok(server) => server.commands(),
+			Err(_) => vec![],
+		}
+	}
+
+	/// Handles the commands that came in since the last call,
+	/// returning the messages the screen should receive.
+	pub fn poll<S: RemoteControls>(&mut self, screen: &S) -> Vec<S::Message> {
+		let mut messages = vec![];
+		for command in self.commands() {
+			let command_messages = screen.remote_messages(command);
+			self.last_command = Some((command, !command_messages.is_empty()));
+			messages.extend(command_messages);
+		}
+		messages
+	}
+
+	/// Throws away the commands that came in since the last call,
+	/// for screens that don't have any remote actions.
+	pub fn ignore_commands(&mut self) {
+		if let Some(command) = self.commands().pop() {
+			self.last_command = Some((command, false));
+		}
+	}
+
+	pub fn view<Message: 'static>(&self) -> iced::Element<'_, Message> {
+		let mut status = match &self.server {
+			Err(error) => format!("Remote control unavailable: {}", error),
+			Ok(server) => match server.num_clients() {
+				0 => format!("Remote control: listening on ws://{}", server.address()),
+				1 => format!(
+					"Remote control: 1 client connected to ws://{}",
+					server.address()
+				),
+				num_clients => format!(
+					"Remote control: {} clients connected to ws://{}",
+					num_clients,
+					server.address()
+				),
+			},
+		};
+		match self.last_command {
+			Some((command, true)) => status += &format!(" - last command: {}", command.name()),
+			Some((command, false)) => {
+				status += &format!(" - last command: {} (not on this screen)", command.name())
+			}
+			None => {}
+		}
+		Row::new()
+			.padding(8)
+			.push(Text::new(status).size(16))
+			.into()
+	}
+}
@@ -0,0 +1,512 @@
+mod timeline;
+
+use std::{error::Error, time::Instant};
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Row, Text};
+use kira::{
+	arrangement::{Arrangement, ArrangementId, SoundClip},
+	instance::{InstanceId, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::Tween,
+	playable::PlayableSettings,
+	sound::SoundId,
+	Tempo,
+};
+use serde::{Deserialize, Serialize};
+
+use self::timeline::{Clip, Lane, Timeline};
+use crate::{
+	audio::{create_audio_manager, sound_cache::SoundCache},
+	ui::{
+		common::screen_wrapper::ScreenWrapper,
+		style::AppStyles,
+		undo_stack::{UndoShortcut, UndoStack},
+	},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &[
+	"underwater demo/drums.ogg",
+	"underwater demo/bass.ogg",
+	"underwater demo/pad.ogg",
+	"underwater demo/lead.ogg",
+];
+
+const EXPLANATION_TEXT: &str = "Click an empty spot on a lane to \
+add a clip there, drag clips to move them, and press Delete to \
+remove the selected one. Ctrl+Z and Ctrl+Y undo and redo.
+
+An arrangement is a list of clips, each of which plays a sound \
+(or part of one) starting at a certain time. Kira mixes the clips \
+together as the arrangement plays, and the whole thing is a single \
+instance, so it can be stopped or faded as one sound. The loops \
+in the underwater demo are arrangements too, with the clips laid \
+out by Arrangement::new_loop.
+
+Arrangements can't be changed once they're added to the audio \
+manager, so pressing play builds a new one from the clips on the \
+timeline. Each sound rings out a little past the end of its \
+phrase, and the arrangement lasts until the last clip has \
+finished.";
+
+const TEMPO: Tempo = Tempo(85.0);
+const NUM_BEATS: usize = 64;
+/// How long each stem lasts before its tail (in beats).
+const PHRASE_LENGTH: f64 = 16.0;
+const NUM_PEAKS: usize = 200;
+const LANE_NAMES: [&str; 4] = ["Drums", "Bass", "Pad", "Lead"];
+/// How long a stopped arrangement fades out (in seconds).
+const STOP_FADE_TIME: f64 = 0.1;
+
+/// The pad on its own, then the other parts coming in one at a
+/// time.
+fn default_clips() -> Vec<Clip> {
+	[
+		(2, 0.0),
+		(2, 16.0),
+		(2, 32.0),
+		(2, 48.0),
+		(1, 16.0),
+		(1, 32.0),
+		(1, 48.0),
+		(0, 32.0),
+		(0, 48.0),
+		(3, 48.0),
+	]
+	.iter()
+	.map(|(lane, start)| Clip {
+		lane: *lane,
+		start: *start,
+	})
+	.collect()
+}
+
+/// Rounds a clip's start to the nearest beat, keeping the whole
+/// clip on the timeline.
+fn snap_clip_start(start: f64) -> f64 {
+	start.round().clamp(0.0, NUM_BEATS as f64 - PHRASE_LENGTH)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	/// Adds a clip to a lane, starting at a beat.
+	AddClip(usize, f64),
+	/// Moves a clip (by its index) to start at a beat.
+	MoveClip(usize, f64),
+	RemoveClip(usize),
+	SelectClip(usize),
+	SetClips(Vec<Clip>),
+	Undo,
+	Redo,
+}
+
+struct Stem {
+	sound_id: SoundId,
+	peaks: Vec<f32>,
+	/// How long the sound rings out after its phrase (in beats).
+	tail_length: f64,
+}
+
+struct Playback {
+	arrangement_id: ArrangementId,
+	instance_id: InstanceId,
+	duration: f64,
+	start_time: Instant,
+	/// Set when the clips are edited while the arrangement plays,
+	/// since the edits won't be heard until it's rebuilt.
+	outdated: bool,
+}
+
+pub struct ArrangementEditorDemo {
+	audio_manager: AudioManager,
+	stems: Vec<Stem>,
+	clips: Vec<Clip>,
+	history: UndoStack<Vec<Clip>>,
+	selected: Option<usize>,
+	playback: Option<Playback>,
+	/// Arrangements that are fading out, with when they can be
+	/// removed.
+	old_arrangements: Vec<(ArrangementId, Instant)>,
+	screen_wrapper: ScreenWrapper<Message>,
+	timeline: timeline::State,
+	play_button: iced::button::State,
+	remove_button: iced::button::State,
+	clear_button: iced::button::State,
+	reset_button: iced::button::State,
+	undo_button: iced::button::State,
+	redo_button: iced::button::State,
+}
+
+impl ArrangementEditorDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let assets_base_dir = std::env::current_dir()?.join("assets");
+		let mut stems = vec![];
+		for asset in ASSETS {
+			let sound = sound_cache.get(&assets_base_dir.join(asset))?;
+			stems.push(Stem {
+				sound_id: audio_manager.add_sound(sound.to_sound(PlayableSettings::new()))?,
+				peaks: sound.peaks(NUM_PEAKS),
+				tail_length: (sound.duration() / TEMPO.beats_to_seconds(1.0) - PHRASE_LENGTH)
+					.max(0.0),
+			});
+		}
+		Ok(Self {
+			audio_manager,
+			stems,
+			clips: default_clips(),
+			history: UndoStack::new(),
+			selected: None,
+			playback: None,
+			old_arrangements: vec![],
+			screen_wrapper: ScreenWrapper::new(
+				"Arrangement editor demo".into(),
+				Message::GoToDemoSelect,
+			),
+			timeline: Default::default(),
+			play_button: iced::button::State::new(),
+			remove_button: iced::button::State::new(),
+			clear_button: iced::button::State::new(),
+			reset_button: iced::button::State::new(),
+			undo_button: iced::button::State::new(),
+			redo_button: iced::button::State::new(),
+		})
+	}
+
+	fn play(&mut self) -> Result<(), Box<dyn Error>> {
+		self.stop()?;
+		if self.clips.is_empty() {
+			return Ok(());
+		}
+		let mut arrangement = Arrangement::new(PlayableSettings::new());
+		for clip in &self.clips {
+			arrangement.add_clip(SoundClip::new(
+				self.stems[clip.lane].sound_id,
+				TEMPO.beats_to_seconds(clip.start),
+			));
+		}
+		let duration = arrangement.duration();
+		let arrangement_id = self.audio_manager.add_arrangement(arrangement)?;
+		let instance_id = self
+			.audio_manager
+			.play(arrangement_id, Default::default())?;
+		self.playback = Some(Playback {
+			arrangement_id,
+			instance_id,
+			duration,
+			start_time: Instant::now(),
+			outdated: false,
+		});
+		Ok(())
+	}
+
+	/// Fades out the arrangement. Removing an arrangement stops
+	/// its instances right away, so it's only removed once the
+	/// fade is over.
+	fn stop(&mut self) -> Result<(), Box<dyn Error>> {
+		if let Some(playback) = self.playback.take() {
+			self.audio_manager.stop_instance(
+				playback.instance_id,
+				StopInstanceSettings::new().fade_tween(Tween::linear(STOP_FADE_TIME)),
+			)?;
+			self.old_arrangements.push((
+				playback.arrangement_id,
+				Instant::now() + std::time::Duration::from_secs_f64(STOP_FADE_TIME * 2.0),
+			));
+		}
+		Ok(())
+	}
+
+	/// Kira doesn't report the playback position of instances,
+	/// so we estimate it from when the arrangement started
+	/// (in beats).
+	fn playback_position(&self) -> Option<f64> {
+		self.playback.as_ref().map(|playback| {
+			playback.start_time.elapsed().as_secs_f64() / TEMPO.beats_to_seconds(1.0)
+		})
+	}
+
+	fn edit(&mut self, clips: Vec<Clip>) {
+		if clips == self.clips {
+			return;
+		}
+		self.history.push(std::mem::replace(&mut self.clips, clips));
+		self.clips_changed();
+	}
+
+	fn clips_changed(&mut self) {
+		if let Some(playback) = &mut self.playback {
+			playback.outdated = true;
+		}
+		if let Some(selected) = self.selected {
+			if selected >= self.clips.len() {
+				self.selected = None;
+			}
+		}
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play => self.play()?,
+			Message::Stop => self.stop()?,
+			Message::AddClip(lane, start) if lane < self.stems.len() => {
+				let mut clips = self.clips.clone();
+				clips.push(Clip {
+					lane,
+					start: snap_clip_start(start),
+				});
+				self.edit(clips);
+				self.selected = Some(self.clips.len() - 1);
+			}
+			Message::MoveClip(index, start) if index < self.clips.len() => {
+				let mut clips = self.clips.clone();
+				clips[index].start = snap_clip_start(start);
+				self.edit(clips);
+			}
+			Message::RemoveClip(index) if index < self.clips.len() => {
+				let mut clips = self.clips.clone();
+				clips.remove(index);
+				self.selected = None;
+				self.edit(clips);
+			}
+			Message::SelectClip(index) if index < self.clips.len() => {
+				self.selected = Some(index);
+			}
+			Message::SetClips(clips) => {
+				let clips = clips
+					.into_iter()
+					.filter(|clip| clip.lane < self.stems.len())
+					.map(|clip| Clip {
+						start: snap_clip_start(clip.start),
+						..clip
+					})
+					.collect();
+				self.edit(clips);
+			}
+			// the clips might not line up with the old indices
+			// anymore, so the selection is cleared
+			Message::Undo if self.history.undo(&mut self.clips) => {
+				self.selected = None;
+				self.clips_changed();
+			}
+			Message::Redo if self.history.redo(&mut self.clips) => {
+				self.selected = None;
+				self.clips_changed();
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	/// Lets go of the arrangement once it's finished playing,
+	/// and removes the ones that have faded out.
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		if let Some(playback) = &self.playback {
+			if playback.start_time.elapsed().as_secs_f64() >= playback.duration {
+				let playback = self.playback.take().unwrap();
+				self.old_arrangements
+					.push((playback.arrangement_id, Instant::now()));
+			}
+		}
+		let now = Instant::now();
+		while let Some(index) = self
+			.old_arrangements
+			.iter()
+			.position(|(_, remove_time)| *remove_time <= now)
+		{
+			let (arrangement_id, _) = self.old_arrangements.remove(index);
+			self.audio_manager.remove_arrangement(arrangement_id)?;
+		}
+		self.audio_manager.free_unused_resources();
+		Ok(())
+	}
+
+	fn status_text(&self) -> String {
+		let arrangement_end = self
+			.clips
+			.iter()
+			.map(|clip| clip.start + PHRASE_LENGTH + self.stems[clip.lane].tail_length)
+			.fold(0.0, f64::max);
+		let mut text = format!(
+			"{} clips, {:.1}s long",
+			self.clips.len(),
+			TEMPO.beats_to_seconds(arrangement_end)
+		);
+		if let Some(clip) = self.selected.map(|index| self.clips[index]) {
+			text += &format!(
+				" - {} clip selected, starting on beat {} ({:.2}s)",
+				LANE_NAMES[clip.lane],
+				clip.start + 1.0,
+				TEMPO.beats_to_seconds(clip.start),
+			);
+		}
+		text
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.playback.is_some();
+		let outdated = matches!(&self.playback, Some(playback) if playback.outdated);
+		let playhead = self.playback_position();
+		let status_text = self.status_text();
+		let selected = self.selected;
+		let lanes = LANE_NAMES
+			.iter()
+			.zip(self.stems.iter())
+			.map(|(name, stem)| Lane {
+				name,
+				peaks: &stem.peaks,
+				clip_length: PHRASE_LENGTH,
+				tail_length: stem.tail_length,
+			})
+			.collect();
+		let mut buttons = Row::new().spacing(16).push(
+			Button::new(
+				&mut self.play_button,
+				Text::new(if playing { "Stop" } else { "Play" })
+					.width(Length::Fill)
+					.horizontal_alignment(HorizontalAlignment::Center),
+			)
+			.on_press(if playing {
+				Message::Stop
+			} else {
+				Message::Play
+			})
+			.width(Length::Units(100))
+			.style(AppStyles),
+		);
+		let mut remove_button = Button::new(
+			&mut self.remove_button,
+			Text::new("Remove clip")
+				.width(Length::Fill)
+				.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.width(Length::Units(140))
+		.style(AppStyles);
+		if let Some(index) = selected {
+			remove_button = remove_button.on_press(Message::RemoveClip(index));
+		}
+		let mut undo_button = Button::new(
+			&mut self.undo_button,
+			Text::new("Undo")
+				.width(Length::Fill)
+				.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.width(Length::Units(100))
+		.style(AppStyles);
+		if self.history.can_undo() {
+			undo_button = undo_button.on_press(Message::Undo);
+		}
+		let mut redo_button = Button::new(
+			&mut self.redo_button,
+			Text::new("Redo")
+				.width(Length::Fill)
+				.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.width(Length::Units(100))
+		.style(AppStyles);
+		if self.history.can_redo() {
+			redo_button = redo_button.on_press(Message::Redo);
+		}
+		buttons = buttons
+			.push(remove_button)
+			.push(undo_button)
+			.push(redo_button)
+			.push(
+				Button::new(
+					&mut self.clear_button,
+					Text::new("Clear")
+						.width(Length::Fill)
+						.horizontal_alignment(HorizontalAlignment::Center),
+				)
+				.on_press(Message::SetClips(vec![]))
+				.width(Length::Units(100))
+				.style(AppStyles),
+			)
+			.push(
+				Button::new(
+					&mut self.reset_button,
+					Text::new("Reset")
+						.width(Length::Fill)
+						.horizontal_alignment(HorizontalAlignment::Center),
+				)
+				.on_press(Message::SetClips(default_clips()))
+				.width(Length::Units(100))
+				.style(AppStyles),
+			);
+		let mut column = Column::new()
+			.spacing(16)
+			.align_items(Align::Center)
+			.push(
+				Timeline::new(&mut self.timeline, lanes, &self.clips, NUM_BEATS)
+					.selected(selected)
+					.playhead(playhead)
+					.on_select(Message::SelectClip)
+					.on_add(Message::AddClip)
+					.on_move(Message::MoveClip)
+					.on_remove(Message::RemoveClip),
+			)
+			.push(buttons)
+			.push(Text::new(status_text));
+		if outdated {
+			column = column.push(
+				Text::new("The clips have changed since this started playing - press play again to hear them")
+					.size(16),
+			);
+		}
+		self.screen_wrapper.view(
+			column.push(
+				Column::new()
+					.width(Length::Fill)
+					.max_width(600)
+					.push(Text::new(EXPLANATION_TEXT)),
+			),
+		)
+	}
+}
+
+impl crate::ui::undo_stack::UndoControls for ArrangementEditorDemo {
+	type Message = Message;
+
+	fn undo_shortcut_message(&self, shortcut: UndoShortcut) -> Option<Message> {
+		match shortcut {
+			UndoShortcut::Undo if self.history.can_undo() => Some(Message::Undo),
+			UndoShortcut::Redo if self.history.can_redo() => Some(Message::Redo),
+			_ => None,
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for ArrangementEditorDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "arrangement_editor_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![Message::SetClips(self.clips.clone())]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for ArrangementEditorDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.playback.is_some(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		self.playback
+			.as_ref()
+			.map(|_| "Arrangement editor demo".into())
+	}
+}
@@ -0,0 +1,460 @@
+use iced::{
+	keyboard::KeyCode, mouse::Interaction, Background, Color, Font, HorizontalAlignment, Length,
+	Point, Rectangle, Size, VerticalAlignment,
+};
+use iced_graphics::{Backend, Defaults, Primitive, Renderer};
+use iced_native::{
+	event::Status,
+	keyboard,
+	layout::{Limits, Node},
+	mouse, Clipboard, Element, Event, Layout, Widget,
+};
+use serde::{Deserialize, Serialize};
+
+const NAME_WIDTH: f32 = 70.0;
+const LANE_HEIGHT: f32 = 50.0;
+const LANE_SPACING: f32 = 4.0;
+const BEATS_PER_BAR: usize = 4;
+const LANE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.05];
+const BEAT_LINE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.05];
+const BAR_LINE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.15];
+const CLIP_COLOR: [f32; 4] = [0.2, 0.5, 0.9, 0.8];
+const SELECTED_CLIP_COLOR: [f32; 4] = [0.3, 0.65, 1.0, 1.0];
+const SELECTED_BORDER_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.8];
+/// The part of a sound that rings out past the end of its clip
+/// is drawn faintly, since it overlaps whatever comes next.
+const TAIL_COLOR: [f32; 4] = [0.2, 0.5, 0.9, 0.25];
+const PEAK_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.4];
+const PLAYHEAD_COLOR: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+const NAME_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const NAME_SIZE: f32 = 16.0;
+
+/// One row of the timeline. Every clip on a lane plays the
+/// same sound.
+pub struct Lane<'a> {
+	pub name: &'a str,
+	/// The peaks of the sound, for drawing its waveform on
+	/// the clips.
+	pub peaks: &'a [f32],
+	/// How long each clip is (in beats).
+	pub clip_length: f64,
+	/// How long the sound rings out after the end of a clip
+	/// (in beats).
+	pub tail_length: f64,
+}
+
+/// A clip on the timeline.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Clip {
+	pub lane: usize,
+	/// Where the clip starts (in beats).
+	pub start: f64,
+}
+
+#[derive(Debug)]
+struct Drag {
+	clip: usize,
+	/// How far into the clip it was grabbed (in beats), so it
+	/// doesn't jump to line its start up with the cursor.
+	grab_offset: f64,
+	/// Where the clip will start if it's dropped now.
+	start: f64,
+}
+
+/// The clip being dragged. This has to outlive the widget,
+/// which is rebuilt every time the screen is drawn.
+#[derive(Debug, Default)]
+pub struct State {
+	dragging: Option<Drag>,
+}
+
+/// Shows clips laid out on lanes in time, measured in beats.
+/// Clips can be dragged along their lane (snapping to beats),
+/// clicking an empty spot on a lane adds a clip there, and
+/// pressing Delete or Backspace removes the selected clip.
+pub struct Timeline<'a, Message> {
+	state: &'a mut State,
+	lanes: Vec<Lane<'a>>,
+	clips: &'a [Clip],
+	num_beats: usize,
+	selected: Option<usize>,
+	/// The playback position (in beats).
+	playhead: Option<f64>,
+	on_select: Option<Box<dyn Fn(usize) -> Message + 'a>>,
+	on_add: Option<Box<dyn Fn(usize, f64) -> Message + 'a>>,
+	on_move: Option<Box<dyn Fn(usize, f64) -> Message + 'a>>,
+	on_remove: Option<Box<dyn Fn(usize) -> Message + 'a>>,
+}
+
+impl<'a, Message> Timeline<'a, Message> {
+	pub fn new(
+		state: &'a mut State,
+		lanes: Vec<Lane<'a>>,
+		clips: &'a [Clip],
+		num_beats: usize,
+	) -> Self {
+		Self {
+			state,
+			lanes,
+			clips,
+			num_beats,
+			selected: None,
+			playhead: None,
+			on_select: None,
+			on_add: None,
+			on_move: None,
+			on_remove: None,
+		}
+	}
+
+	pub fn selected(self, selected: Option<usize>) -> Self {
+		Self { selected, ..self }
+	}
+
+	pub fn playhead(self, playhead: Option<f64>) -> Self {
+		Self { playhead, ..self }
+	}
+
+	pub fn on_select(self, on_select: impl Fn(usize) -> Message + 'a) -> Self {
+		Self {
+			on_select: Some(Box::new(on_select)),
+			..self
+		}
+	}
+
+	/// Sets the message to send when an empty spot is clicked,
+	/// given the lane and the beat to start the new clip on.
+	pub fn on_add(self, on_add: impl Fn(usize, f64) -> Message + 'a) -> Self {
+		Self {
+			on_add: Some(Box::new(on_add)),
+			..self
+		}
+	}
+
+	/// Sets the message to send when a clip is dropped somewhere
+	/// new, given the clip and its new start (in beats). Nothing
+	/// is sent while it's being dragged, so each drag is one edit.
+	pub fn on_move(self, on_move: impl Fn(usize, f64) -> Message + 'a) -> Self {
+		Self {
+			on_move: Some(Box::new(on_move)),
+			..self
+		}
+	}
+
+	pub fn on_remove(self, on_remove: impl Fn(usize) -> Message + 'a) -> Self {
+		Self {
+			on_remove: Some(Box::new(on_remove)),
+			..self
+		}
+	}
+
+	fn height(&self) -> f32 {
+		let lanes = self.lanes.len() as f32;
+		LANE_HEIGHT * lanes + LANE_SPACING * (lanes - 1.0).max(0.0)
+	}
+
+	fn beat_width(&self, bounds: Rectangle) -> f32 {
+		(bounds.width - NAME_WIDTH) / self.num_beats.max(1) as f32
+	}
+
+	fn x(&self, bounds: Rectangle, beat: f64) -> f32 {
+		bounds.x + NAME_WIDTH + self.beat_width(bounds) * beat as f32
+	}
+
+	fn beat_at(&self, bounds: Rectangle, point: Point) -> f64 {
+		((point.x - bounds.x - NAME_WIDTH) / self.beat_width(bounds)) as f64
+	}
+
+	fn lane_bounds(&self, bounds: Rectangle, lane: usize) -> Rectangle {
+		Rectangle::new(
+			Point::new(
+				bounds.x + NAME_WIDTH,
+				bounds.y + lane as f32 * (LANE_HEIGHT + LANE_SPACING),
+			),
+			Size::new(bounds.width - NAME_WIDTH, LANE_HEIGHT),
+		)
+	}
+
+	fn lane_at(&self, bounds: Rectangle, point: Point) -> Option<usize> {
+		(0..self.lanes.len()).find(|lane| self.lane_bounds(bounds, *lane).contains(point))
+	}
+
+	/// Where a clip is drawn, which follows the cursor while the
+	/// clip is being dragged.
+	fn clip_start(&self, index: usize) -> f64 {
+		match &self.state.dragging {
+			Some(drag) if drag.clip == index => drag.start,
+			_ => self.clips[index].start,
+		}
+	}
+
+	fn clip_bounds(&self, bounds: Rectangle, index: usize) -> Rectangle {
+		let clip = self.clips[index];
+		let lane_bounds = self.lane_bounds(bounds, clip.lane);
+		let start = self.clip_start(index);
+		let end = start + self.lanes[clip.lane].clip_length;
+		Rectangle::new(
+			Point::new(self.x(bounds, start), lane_bounds.y),
+			Size::new(self.x(bounds, end) - self.x(bounds, start), LANE_HEIGHT),
+		)
+	}
+
+	/// Finds the clip under a point. Later clips are drawn on top,
+	/// so they're checked first.
+	fn clip_at(&self, bounds: Rectangle, point: Point) -> Option<usize> {
+		(0..self.clips.len())
+			.rev()
+			.filter(|index| self.clips[*index].lane < self.lanes.len())
+			.find(|index| self.clip_bounds(bounds, *index).contains(point))
+	}
+
+	/// Rounds a start position to the nearest beat, keeping the
+	/// whole clip on the timeline.
+	fn snapped_start(&self, lane: usize, start: f64) -> f64 {
+		let latest_start = (self.num_beats as f64 - self.lanes[lane].clip_length).max(0.0);
+		start.round().clamp(0.0, latest_start)
+	}
+
+	fn quad(bounds: Rectangle, color: [f32; 4]) -> Primitive {
+		Primitive::Quad {
+			bounds,
+			background: Background::Color(color.into()),
+			border_radius: 0.0,
+			border_width: 0.0,
+			border_color: Color::TRANSPARENT,
+		}
+	}
+
+	fn draw_clip(&self, bounds: Rectangle, index: usize, primitives: &mut Vec<Primitive>) {
+		let lane = &self.lanes[self.clips[index].lane];
+		let clip_bounds = self.clip_bounds(bounds, index);
+		let beat_width = self.beat_width(bounds);
+		let tail_width = (beat_width * lane.tail_length as f32)
+			.min(bounds.x + bounds.width - (clip_bounds.x + clip_bounds.width))
+			.max(0.0);
+		primitives.push(Self::quad(
+			Rectangle::new(
+				Point::new(clip_bounds.x + clip_bounds.width, clip_bounds.y),
+				Size::new(tail_width, clip_bounds.height),
+			),
+			TAIL_COLOR,
+		));
+		let selected = self.selected == Some(index);
+		primitives.push(Primitive::Quad {
+			bounds: clip_bounds,
+			background: Background::Color(
+				if selected {
+					SELECTED_CLIP_COLOR
+				} else {
+					CLIP_COLOR
+				}
+				.into(),
+			),
+			border_radius: 4.0,
+			border_width: if selected { 2.0 } else { 0.0 },
+			border_color: if selected {
+				SELECTED_BORDER_COLOR.into()
+			} else {
+				Color::TRANSPARENT
+			},
+		});
+		// the peaks cover the sound's tail too, so they're spread
+		// over the clip and the tail together
+		let sound_width = beat_width * (lane.clip_length + lane.tail_length) as f32;
+		let peak_width = sound_width / lane.peaks.len().max(1) as f32;
+		for (i, peak) in lane.peaks.iter().enumerate() {
+			let x = clip_bounds.x + peak_width * i as f32;
+			if x >= bounds.x + bounds.width {
+				break;
+			}
+			let peak_height = (LANE_HEIGHT - 8.0) * peak.min(1.0);
+			primitives.push(Self::quad(
+				Rectangle::new(
+					Point::new(x, clip_bounds.y + (LANE_HEIGHT - peak_height) / 2.0),
+					Size::new(peak_width.max(1.0), peak_height),
+				),
+				PEAK_COLOR,
+			));
+		}
+	}
+}
+
+impl<'a, Message, B: Backend> Widget<Message, Renderer<B>> for Timeline<'a, Message> {
+	fn width(&self) -> Length {
+		Length::Fill
+	}
+
+	fn height(&self) -> Length {
+		Length::Units(self.height() as u16)
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, limits: &Limits) -> Node {
+		Node::new(
+			limits
+				.width(Length::Fill)
+				.height(Length::Units(self.height() as u16))
+				.resolve(Size::ZERO),
+		)
+	}
+
+	fn on_event(
+		&mut self,
+		event: Event,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		messages: &mut Vec<Message>,
+		_renderer: &Renderer<B>,
+		_clipboard: Option<&dyn Clipboard>,
+	) -> Status {
+		let bounds = layout.bounds();
+		match event {
+			Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+				if let Some(index) = self.clip_at(bounds, cursor_position) {
+					let start = self.clips[index].start;
+					self.state.dragging = Some(Drag {
+						clip: index,
+						grab_offset: self.beat_at(bounds, cursor_position) - start,
+						start,
+					});
+					if let Some(on_select) = &self.on_select {
+						messages.push(on_select(index));
+					}
+					return Status::Captured;
+				}
+				let lane = match self.lane_at(bounds, cursor_position) {
+					Some(lane) => lane,
+					None => return Status::Ignored,
+				};
+				if let Some(on_add) = &self.on_add {
+					let start = self.snapped_start(lane, self.beat_at(bounds, cursor_position));
+					messages.push(on_add(lane, start));
+				}
+				Status::Captured
+			}
+			Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+				let (clip, grab_offset) = match &self.state.dragging {
+					Some(drag) => (drag.clip, drag.grab_offset),
+					None => return Status::Ignored,
+				};
+				let start = self.snapped_start(
+					self.clips[clip].lane,
+					self.beat_at(bounds, cursor_position) - grab_offset,
+				);
+				if let Some(drag) = &mut self.state.dragging {
+					drag.start = start;
+				}
+				Status::Captured
+			}
+			Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+				let drag = match self.state.dragging.take() {
+					Some(drag) => drag,
+					None => return Status::Ignored,
+				};
+				if drag.start != self.clips[drag.clip].start {
+					if let Some(on_move) = &self.on_move {
+						messages.push(on_move(drag.clip, drag.start));
+					}
+				}
+				Status::Captured
+			}
+			Event::Keyboard(keyboard::Event::KeyPressed {
+				key_code: KeyCode::Delete,
+				..
+			})
+			| Event::Keyboard(keyboard::Event::KeyPressed {
+				key_code: KeyCode::Backspace,
+				..
+			}) => match (self.selected, &self.on_remove) {
+				(Some(index), Some(on_remove)) if self.state.dragging.is_none() => {
+					messages.push(on_remove(index));
+					Status::Captured
+				}
+				_ => Status::Ignored,
+			},
+			_ => Status::Ignored,
+		}
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		_defaults: &Defaults,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let mut primitives = vec![];
+		for (index, lane) in self.lanes.iter().enumerate() {
+			let lane_bounds = self.lane_bounds(bounds, index);
+			primitives.push(Self::quad(lane_bounds, LANE_COLOR));
+			primitives.push(Primitive::Text {
+				content: lane.name.to_string(),
+				bounds: Rectangle::new(
+					Point::new(bounds.x, lane_bounds.center_y()),
+					Size::new(NAME_WIDTH, 0.0),
+				),
+				color: NAME_COLOR.into(),
+				size: NAME_SIZE,
+				font: Font::Default,
+				horizontal_alignment: HorizontalAlignment::Left,
+				vertical_alignment: VerticalAlignment::Center,
+			});
+		}
+		for beat in 0..=self.num_beats {
+			let color = if beat % BEATS_PER_BAR == 0 {
+				BAR_LINE_COLOR
+			} else {
+				BEAT_LINE_COLOR
+			};
+			primitives.push(Self::quad(
+				Rectangle::new(
+					Point::new(self.x(bounds, beat as f64), bounds.y),
+					Size::new(1.0, bounds.height),
+				),
+				color,
+			));
+		}
+		// the dragged clip is drawn last so it's on top
+		let dragged = self.state.dragging.as_ref().map(|drag| drag.clip);
+		for index in (0..self.clips.len())
+			.filter(|index| Some(*index) != dragged)
+			.chain(dragged)
+			.filter(|index| self.clips[*index].lane < self.lanes.len())
+		{
+			self.draw_clip(bounds, index, &mut primitives);
+		}
+		if let Some(playhead) = self.playhead {
+			primitives.push(Self::quad(
+				Rectangle::new(
+					Point::new(self.x(bounds, playhead) - 1.0, bounds.y),
+					Size::new(2.0, bounds.height),
+				),
+				PLAYHEAD_COLOR,
+			));
+		}
+		let interaction = if self.state.dragging.is_some() {
+			Interaction::Grabbing
+		} else if self.clip_at(bounds, cursor_position).is_some() {
+			Interaction::Grab
+		} else if self.lane_at(bounds, cursor_position).is_some() {
+			Interaction::Pointer
+		} else {
+			Interaction::default()
+		};
+		(Primitive::Group { primitives }, interaction)
+	}
+
+	fn hash_layout(&self, state: &mut iced_native::Hasher) {
+		use std::hash::Hash;
+		self.lanes.len().hash(state)
+	}
+}
+
+impl<'a, Message: 'a, B: Backend + 'a> From<Timeline<'a, Message>>
+	for Element<'a, Message, Renderer<B>>
+{
+	fn from(timeline: Timeline<'a, Message>) -> Self {
+		Element::new(timeline)
+	}
+}
@@ -0,0 +1,426 @@
+mod repeat_pads;
+
+use std::error::Error;
+
+use iced::{keyboard::KeyCode, Align, Column, Length, Text};
+use kira::{
+	arrangement::{Arrangement, ArrangementId, SoundClip},
+	instance::{InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::{ParameterId, Tween},
+	playable::PlayableSettings,
+	sequence::{EventReceiver, Sequence, SequenceInstanceId},
+	sound::SoundId,
+	AudioResult, Duration,
+};
+use serde::{Deserialize, Serialize};
+
+use self::repeat_pads::RepeatPads;
+use crate::{
+	audio::{create_audio_manager, sound_cache::SoundCache},
+	ui::{
+		common::{screen_wrapper::ScreenWrapper, transport::Transport},
+		screen::drum_fill_demo::TEMPO,
+	},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &["drum fill demo/loop.ogg"];
+
+const BEATS_PER_BAR: usize = 4;
+
+const EXPLANATION_TEXT: &str = "Press Play, then hold one of the \
+pads (or the 1, 2 and 3 keys) to stutter the drums. Holding a pad \
+grabs the slice of the loop that starts at the next 1/4, 1/8 or \
+1/16 note and plays it over and over until you let go.
+
+Each slice is an arrangement with a single clip cut out of the \
+loop, made ahead of time for every spot in the bar. Holding a pad \
+starts a looping sequence that waits for the next grid point with \
+wait_for_interval, turns the main loop down, and then retriggers \
+the slice once per grid step.
+
+The main loop never stops underneath, so letting go only has to \
+turn it back up. That happens in a second sequence that waits for \
+the next grid point too, so the drums come back in time with where \
+the song would have been.";
+
+/// The repeat lengths, with the keys that hold them.
+const DIVISIONS: [(&str, KeyCode, &str); 3] = [
+	("1/4", KeyCode::Key1, "1"),
+	("1/8", KeyCode::Key2, "2"),
+	("1/16", KeyCode::Key3, "3"),
+];
+/// How long turning the main loop down and back up takes (in
+/// seconds). This keeps the switch from clicking.
+const SWITCH_FADE_TIME: f64 = 0.005;
+
+/// How long a repeat is (in beats).
+fn division_beats(division: usize) -> f64 {
+	1.0 / (1 << division) as f64
+}
+
+/// The events the repeat sequences emit.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum RepeatEvent {
+	Started,
+	Retriggered,
+	Released,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetMetronomeClick(bool),
+	/// Starts holding a repeat (by its index in the divisions).
+	PressPad(usize),
+	ReleasePad(usize),
+}
+
+/// A repeat that's held down.
+struct Repeat {
+	division: usize,
+	/// Which slice of the bar is repeating.
+	slice: usize,
+	sequence_id: SequenceInstanceId,
+	event_receiver: EventReceiver<RepeatEvent>,
+	/// Whether the sequence has reached the grid point and
+	/// started repeating.
+	started: bool,
+	retriggers: usize,
+	/// The sequence waiting to let go of the repeat on the next
+	/// grid point, if the pad has been released.
+	release: Option<(SequenceInstanceId, EventReceiver<RepeatEvent>)>,
+}
+
+pub struct BeatRepeatDemo {
+	audio_manager: AudioManager,
+	loop_sound_id: SoundId,
+	/// The slices of the loop for each division, in order.
+	slices: Vec<Vec<ArrangementId>>,
+	/// The volume of the main loop, which is turned down while
+	/// a repeat is playing.
+	main_volume_parameter_id: ParameterId,
+	loop_sequence_id: Option<SequenceInstanceId>,
+	repeat: Option<Repeat>,
+	transport: Transport,
+	screen_wrapper: ScreenWrapper<Message>,
+	pads: repeat_pads::State,
+}
+
+impl BeatRepeatDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(AudioManagerSettings {
+			metronome_settings: Transport::metronome_settings(TEMPO),
+			..audio_manager_settings
+		})?;
+		let transport = Transport::new(&mut audio_manager, TEMPO)?;
+		let loop_sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&std::env::current_dir()?.join("assets/drum fill demo/loop.ogg"))?
+				.to_sound(PlayableSettings::new()),
+		)?;
+		// the slices can overlap a little when they're retriggered
+		// right as the last one is ending
+		let slice_settings = PlayableSettings {
+			cooldown: None,
+			..Default::default()
+		};
+		let mut slices = vec![];
+		for division in 0..DIVISIONS.len() {
+			let slice_length = TEMPO.beats_to_seconds(division_beats(division));
+			let num_slices = (BEATS_PER_BAR as f64 / division_beats(division)) as usize;
+			let mut division_slices = vec![];
+			for slice in 0..num_slices {
+				let slice_start = slice_length * slice as f64;
+				let mut arrangement = Arrangement::new(slice_settings.clone());
+				arrangement.add_clip(SoundClip {
+					sound_id: loop_sound_id,
+					clip_time_range: (0.0, slice_length),
+					sound_time_range: (slice_start, slice_start + slice_length),
+				});
+				division_slices.push(audio_manager.add_arrangement(arrangement)?);
+			}
+			slices.push(division_slices);
+		}
+		let main_volume_parameter_id = audio_manager.add_parameter(1.0)?;
+		Ok(Self {
+			audio_manager,
+			loop_sound_id,
+			slices,
+			main_volume_parameter_id,
+			loop_sequence_id: None,
+			repeat: None,
+			transport,
+			screen_wrapper: ScreenWrapper::new("Beat repeat demo".into(), Message::GoToDemoSelect),
+			pads: Default::default(),
+		})
+	}
+
+	fn start_loop_sequence(&mut self) -> AudioResult<SequenceInstanceId> {
+		let mut sequence = Sequence::<()>::new(Default::default());
+		sequence.wait_for_interval(1.0);
+		sequence.start_loop();
+		sequence.play(
+			self.loop_sound_id,
+			InstanceSettings::new().volume(self.main_volume_parameter_id),
+		);
+		sequence.wait(Duration::Beats(BEATS_PER_BAR as f64));
+		let (sequence_id, _) = self
+			.audio_manager
+			.start_sequence(sequence, Default::default())?;
+		Ok(sequence_id)
+	}
+
+	fn start_repeat(&mut self, division: usize) -> Result<(), Box<dyn Error>> {
+		let position = match self.transport.beat_position() {
+			Some(position) => position,
+			None => return Ok(()),
+		};
+		// the sequence will start on the next grid point, so that's
+		// the slice to repeat. the position is a guess from the
+		// last beat the screen heard about, so right before a grid
+		// point this can pick the slice before the one the
+		// sequence actually starts on.
+		let beats = division_beats(division);
+		let next_grid_point = (position / beats).ceil() * beats;
+		let slice = ((next_grid_point % BEATS_PER_BAR as f64) / beats).round() as usize
+			% self.slices[division].len();
+		let mut sequence = Sequence::new(Default::default());
+		sequence.wait_for_interval(beats);
+		sequence.set_parameter(
+			self.main_volume_parameter_id,
+			0.0,
+			Some(Tween::linear(SWITCH_FADE_TIME)),
+		);
+		sequence.emit(RepeatEvent::Started);
+		sequence.start_loop();
+		sequence.play(self.slices[division][slice], Default::default());
+		sequence.emit(RepeatEvent::Retriggered);
+		sequence.wait(Duration::Beats(beats));
+		let (sequence_id, event_receiver) = self
+			.audio_manager
+			.start_sequence(sequence, Default::default())?;
+		self.repeat = Some(Repeat {
+			division,
+			slice,
+			sequence_id,
+			event_receiver,
+			started: false,
+			retriggers: 0,
+			release: None,
+		});
+		Ok(())
+	}
+
+	fn release_repeat(&mut self) -> Result<(), Box<dyn Error>> {
+		let repeat = match &mut self.repeat {
+			Some(repeat) if repeat.release.is_none() => repeat,
+			_ => return Ok(()),
+		};
+		// if the repeat hasn't started yet, there's nothing to
+		// come back from, so it can be called off right away
+		if !repeat.started {
+			self.audio_manager
+				.stop_sequence_and_instances(repeat.sequence_id, Default::default())?;
+			self.audio_manager
+				.set_parameter(self.main_volume_parameter_id, 1.0, None)?;
+			self.repeat = None;
+			return Ok(());
+		}
+		let mut sequence = Sequence::new(Default::default());
+		sequence.wait_for_interval(division_beats(repeat.division));
+		sequence.stop_sequence_and_instances(
+			repeat.sequence_id,
+			StopInstanceSettings::new().fade_tween(Tween::linear(SWITCH_FADE_TIME)),
+		);
+		sequence.set_parameter(
+			self.main_volume_parameter_id,
+			1.0,
+			Some(Tween::linear(SWITCH_FADE_TIME)),
+		);
+		sequence.emit(RepeatEvent::Released);
+		repeat.release = Some(
+			self.audio_manager
+				.start_sequence(sequence, Default::default())?,
+		);
+		Ok(())
+	}
+
+	fn stop(&mut self) -> Result<(), Box<dyn Error>> {
+		if let Some(sequence_id) = self.loop_sequence_id.take() {
+			self.audio_manager
+				.stop_sequence_and_instances(sequence_id, Default::default())?;
+		}
+		if let Some(repeat) = self.repeat.take() {
+			self.audio_manager
+				.stop_sequence_and_instances(repeat.sequence_id, Default::default())?;
+			if let Some((sequence_id, _)) = repeat.release {
+				self.audio_manager.stop_sequence(sequence_id)?;
+			}
+		}
+		self.audio_manager
+			.set_parameter(self.main_volume_parameter_id, 1.0, None)?;
+		self.transport.stop(&mut self.audio_manager)?;
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if !self.transport.is_playing() => {
+				self.loop_sequence_id = Some(self.start_loop_sequence()?);
+				self.transport.start(&mut self.audio_manager)?;
+			}
+			Message::Stop => self.stop()?,
+			Message::SetMetronomeClick(enabled) => {
+				self.transport
+					.set_click_enabled(&mut self.audio_manager, enabled)?;
+			}
+			// only one repeat can be held at a time
+			Message::PressPad(division) if division < DIVISIONS.len() && self.repeat.is_none() => {
+				self.start_repeat(division)?;
+			}
+			Message::ReleasePad(division)
+				if self.repeat.as_ref().map(|repeat| repeat.division) == Some(division) =>
+			{
+				self.release_repeat()?;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		self.transport.check_for_events(&mut self.audio_manager);
+		let mut released = false;
+		if let Some(repeat) = &mut self.repeat {
+			while let Some(event) = repeat.event_receiver.pop().copied() {
+				match event {
+					RepeatEvent::Started => repeat.started = true,
+					RepeatEvent::Retriggered => repeat.retriggers += 1,
+					RepeatEvent::Released => {}
+				}
+			}
+			if let Some((_, event_receiver)) = &mut repeat.release {
+				while let Some(event) = event_receiver.pop().copied() {
+					released |= event == RepeatEvent::Released;
+				}
+			}
+		}
+		if released {
+			self.repeat = None;
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let status_text = match &self.repeat {
+			Some(repeat) if repeat.release.is_some() => {
+				"Letting go on the next grid point...".to_string()
+			}
+			Some(repeat) if repeat.started => format!(
+				"Repeating {} note {} of the bar ({} times)",
+				DIVISIONS[repeat.division].0,
+				repeat.slice + 1,
+				repeat.retriggers
+			),
+			Some(repeat) => format!(
+				"Waiting for the next {} note...",
+				DIVISIONS[repeat.division].0
+			),
+			None if self.transport.is_playing() => "Hold a pad to stutter the drums".into(),
+			None => "Press Play to start the drums".into(),
+		};
+		let active = self
+			.repeat
+			.as_ref()
+			.map(|repeat| (repeat.division, repeat.started));
+		self.screen_wrapper.view_with_transport(
+			self.transport
+				.view(Message::Play, Message::Stop, Message::SetMetronomeClick),
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					RepeatPads::new(&mut self.pads, &DIVISIONS, active)
+						.on_press(Message::PressPad)
+						.on_release(Message::ReleasePad),
+				)
+				.push(Text::new(status_text))
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for BeatRepeatDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "beat_repeat_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::PressPad(division) | Message::ReleasePad(division) => DIVISIONS
+				.get(*division)
+				.map(|(name, _, _)| format!("{} repeat", name)),
+			_ => None,
+		}
+	}
+
+	// the pads are held like a sustain pedal: pushing the control
+	// past halfway holds the pad and pulling it back lets go
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		DIVISIONS
+			.iter()
+			.position(|(name, _, _)| control == format!("{} repeat", name))
+			.map(|division| {
+				if value >= 0.5 {
+					Message::PressPad(division)
+				} else {
+					Message::ReleasePad(division)
+				}
+			})
+			.into_iter()
+			.collect()
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for BeatRepeatDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "beat_repeat_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![Message::SetMetronomeClick(self.transport.click_enabled())]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for BeatRepeatDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.transport.is_playing(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if !self.transport.is_playing() {
+			return None;
+		}
+		Some("Beat repeat demo".into())
+	}
+}
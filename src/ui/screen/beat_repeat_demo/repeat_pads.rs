@@ -0,0 +1,260 @@
+use iced::{
+	keyboard::KeyCode, mouse::Interaction, Background, Color, Font, HorizontalAlignment, Length,
+	Point, Rectangle, Size, VerticalAlignment,
+};
+use iced_graphics::{Backend, Defaults, Primitive, Renderer};
+use iced_native::{
+	event::Status,
+	keyboard,
+	layout::{Limits, Node},
+	mouse, Clipboard, Element, Event, Layout, Widget,
+};
+
+const PAD_SIZE: Size = Size {
+	width: 100.0,
+	height: 100.0,
+};
+const GAP: f32 = 16.0;
+const IDLE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.1];
+/// The color of a pad that's held down but waiting for the next
+/// grid point to start repeating.
+const WAITING_COLOR: [f32; 4] = [1.0, 0.8, 0.0, 0.5];
+const REPEATING_COLOR: [f32; 4] = [1.0, 0.4, 0.0, 1.0];
+const LABEL_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const KEY_LABEL_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.5];
+const LABEL_SIZE: f32 = 28.0;
+const KEY_LABEL_SIZE: f32 = 14.0;
+
+/// The pad being held with the mouse and the keyboard keys
+/// being held down (which the OS repeats, but only the first
+/// press should count). This has to outlive the widget, which is
+/// rebuilt every time the screen is drawn.
+#[derive(Debug, Default)]
+pub struct State {
+	mouse_pad: Option<usize>,
+	held_keys: Vec<KeyCode>,
+}
+
+/// A row of pads that do something for as long as they're held,
+/// either with the mouse or with a key on the keyboard.
+pub struct RepeatPads<'a, Message> {
+	state: &'a mut State,
+	/// The label of each pad, the key that holds it, and the
+	/// label for that key.
+	pads: &'a [(&'a str, KeyCode, &'a str)],
+	/// The pad that's held down and whether it's repeating yet.
+	active: Option<(usize, bool)>,
+	on_press: Option<Box<dyn Fn(usize) -> Message + 'a>>,
+	on_release: Option<Box<dyn Fn(usize) -> Message + 'a>>,
+}
+
+impl<'a, Message> RepeatPads<'a, Message> {
+	pub fn new(
+		state: &'a mut State,
+		pads: &'a [(&'a str, KeyCode, &'a str)],
+		active: Option<(usize, bool)>,
+	) -> Self {
+		Self {
+			state,
+			pads,
+			active,
+			on_press: None,
+			on_release: None,
+		}
+	}
+
+	pub fn on_press(self, on_press: impl Fn(usize) -> Message + 'a) -> Self {
+		Self {
+			on_press: Some(Box::new(on_press)),
+			..self
+		}
+	}
+
+	pub fn on_release(self, on_release: impl Fn(usize) -> Message + 'a) -> Self {
+		Self {
+			on_release: Some(Box::new(on_release)),
+			..self
+		}
+	}
+
+	fn size(&self) -> Size {
+		let num_pads = self.pads.len() as f32;
+		Size::new(
+			PAD_SIZE.width * num_pads + GAP * (num_pads - 1.0).max(0.0),
+			PAD_SIZE.height,
+		)
+	}
+
+	fn pad_bounds(bounds: Rectangle, pad: usize) -> Rectangle {
+		Rectangle::new(
+			Point::new(bounds.x + (PAD_SIZE.width + GAP) * pad as f32, bounds.y),
+			PAD_SIZE,
+		)
+	}
+
+	fn pad_at(&self, bounds: Rectangle, point: Point) -> Option<usize> {
+		(0..self.pads.len()).find(|pad| Self::pad_bounds(bounds, *pad).contains(point))
+	}
+
+	fn press(&self, pad: usize, messages: &mut Vec<Message>) {
+		if let Some(on_press) = &self.on_press {
+			messages.push(on_press(pad));
+		}
+	}
+
+	fn release(&self, pad: usize, messages: &mut Vec<Message>) {
+		if let Some(on_release) = &self.on_release {
+			messages.push(on_release(pad));
+		}
+	}
+}
+
+impl<'a, Message, B: Backend> Widget<Message, Renderer<B>> for RepeatPads<'a, Message> {
+	fn width(&self) -> Length {
+		Length::Units(self.size().width as u16)
+	}
+
+	fn height(&self) -> Length {
+		Length::Units(self.size().height as u16)
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, limits: &Limits) -> Node {
+		let size = self.size();
+		Node::new(
+			limits
+				.width(Length::Units(size.width as u16))
+				.height(Length::Units(size.height as u16))
+				.resolve(Size::ZERO),
+		)
+	}
+
+	fn on_event(
+		&mut self,
+		event: Event,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		messages: &mut Vec<Message>,
+		_renderer: &Renderer<B>,
+		_clipboard: Option<&dyn Clipboard>,
+	) -> Status {
+		match event {
+			Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+				match self.pad_at(layout.bounds(), cursor_position) {
+					Some(pad) => {
+						self.state.mouse_pad = Some(pad);
+						self.press(pad, messages);
+						Status::Captured
+					}
+					None => Status::Ignored,
+				}
+			}
+			// the pad stays held if the cursor leaves it, until the
+			// button is released
+			Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+				match self.state.mouse_pad.take() {
+					Some(pad) => {
+						self.release(pad, messages);
+						Status::Captured
+					}
+					None => Status::Ignored,
+				}
+			}
+			Event::Keyboard(keyboard::Event::KeyPressed { key_code, .. }) => {
+				let pad = match self.pads.iter().position(|(_, key, _)| *key == key_code) {
+					Some(pad) => pad,
+					None => return Status::Ignored,
+				};
+				if !self.state.held_keys.contains(&key_code) {
+					self.state.held_keys.push(key_code);
+					self.press(pad, messages);
+				}
+				Status::Captured
+			}
+			Event::Keyboard(keyboard::Event::KeyReleased { key_code, .. }) => {
+				if !self.state.held_keys.contains(&key_code) {
+					return Status::Ignored;
+				}
+				self.state.held_keys.retain(|key| *key != key_code);
+				if let Some(pad) = self.pads.iter().position(|(_, key, _)| *key == key_code) {
+					self.release(pad, messages);
+				}
+				Status::Captured
+			}
+			_ => Status::Ignored,
+		}
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		_defaults: &Defaults,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let mut primitives = vec![];
+		for (pad, (label, _, key_label)) in self.pads.iter().enumerate() {
+			let pad_bounds = Self::pad_bounds(bounds, pad);
+			let color = match self.active {
+				Some((active_pad, true)) if active_pad == pad => REPEATING_COLOR,
+				Some((active_pad, false)) if active_pad == pad => WAITING_COLOR,
+				_ => IDLE_COLOR,
+			};
+			primitives.push(Primitive::Quad {
+				bounds: pad_bounds,
+				background: Background::Color(color.into()),
+				border_radius: 8.0,
+				border_width: 0.0,
+				border_color: Color::TRANSPARENT,
+			});
+			primitives.push(Primitive::Text {
+				content: label.to_string(),
+				bounds: Rectangle::new(
+					Point::new(pad_bounds.center_x(), pad_bounds.center_y()),
+					Size::new(pad_bounds.width, 0.0),
+				),
+				color: LABEL_COLOR.into(),
+				size: LABEL_SIZE,
+				font: Font::Default,
+				horizontal_alignment: HorizontalAlignment::Center,
+				vertical_alignment: VerticalAlignment::Center,
+			});
+			primitives.push(Primitive::Text {
+				content: key_label.to_string(),
+				bounds: Rectangle::new(
+					Point::new(
+						pad_bounds.center_x(),
+						pad_bounds.y + pad_bounds.height - KEY_LABEL_SIZE,
+					),
+					Size::new(pad_bounds.width, 0.0),
+				),
+				color: KEY_LABEL_COLOR.into(),
+				size: KEY_LABEL_SIZE,
+				font: Font::Default,
+				horizontal_alignment: HorizontalAlignment::Center,
+				vertical_alignment: VerticalAlignment::Center,
+			});
+		}
+		let interaction =
+			if self.on_press.is_some() && self.pad_at(bounds, cursor_position).is_some() {
+				Interaction::Pointer
+			} else {
+				Interaction::default()
+			};
+		(Primitive::Group { primitives }, interaction)
+	}
+
+	fn hash_layout(&self, state: &mut iced_native::Hasher) {
+		use std::hash::Hash;
+		self.pads.len().hash(state)
+	}
+}
+
+impl<'a, Message: 'a, B: Backend + 'a> From<RepeatPads<'a, Message>>
+	for Element<'a, Message, Renderer<B>>
+{
+	fn from(pads: RepeatPads<'a, Message>) -> Self {
+		Element::new(pads)
+	}
+}
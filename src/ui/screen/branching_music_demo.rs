@@ -0,0 +1,355 @@
+mod story_graph;
+
+use std::error::Error;
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Row, Text};
+use kira::{
+	group::GroupId,
+	instance::StopInstanceSettings,
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::Tween,
+	playable::PlayableSettings,
+	sequence::{EventReceiver, Sequence, SequenceInstanceId, SequenceSettings},
+	sound::SoundId,
+	AudioResult, Duration, Tempo,
+};
+use serde::{Deserialize, Serialize};
+
+use self::story_graph::StoryGraph;
+use crate::{
+	audio::{create_audio_manager, sound_cache::SoundCache},
+	ui::{
+		common::{screen_wrapper::ScreenWrapper, transport::Transport},
+		style::AppStyles,
+	},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &[
+	"underwater demo/drums.ogg",
+	"underwater demo/bass.ogg",
+	"underwater demo/pad.ogg",
+	"underwater demo/lead.ogg",
+];
+
+const EXPLANATION_TEXT: &str = "Each section of the story has \
+its own mix of the underwater stems, and the buttons move to the \
+sections the current one leads to. Moves always happen at the \
+start of a bar, so the music never changes mid-phrase.
+
+Like the drum fill demo, every move starts a new sequence. It \
+waits for the next bar on the metronome, stops the old section's \
+sequence and sounds, and then loops the new section. The move is \
+shown as pending until the sequence emits an event saying the new \
+section has started. The resolve section doesn't loop: its \
+sequence plays it once and then carries on into exploring, \
+without the screen having to step in.";
+
+pub const TEMPO: Tempo = Tempo(85.0);
+const BEATS_PER_BAR: f64 = 4.0;
+/// How long each stem is (in beats).
+const PHRASE_LENGTH: f64 = 16.0;
+const DRUMS: usize = 0;
+const BASS: usize = 1;
+const PAD: usize = 2;
+const LEAD: usize = 3;
+
+/// A section of the music, which is a point in the story.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Node {
+	Explore,
+	Tension,
+	Combat,
+	Resolve,
+}
+
+impl Node {
+	const ALL: [Self; 4] = [Self::Explore, Self::Tension, Self::Combat, Self::Resolve];
+	const NAMES: [&'static str; 4] = ["Explore", "Tension", "Combat", "Resolve"];
+
+	fn index(self) -> usize {
+		Self::ALL.iter().position(|node| *node == self).unwrap()
+	}
+
+	fn name(self) -> &'static str {
+		Self::NAMES[self.index()]
+	}
+
+	/// The stems that play during the section.
+	fn stems(self) -> &'static [usize] {
+		match self {
+			Self::Explore => &[PAD, LEAD],
+			Self::Tension => &[PAD, BASS],
+			Self::Combat => &[DRUMS, BASS, PAD, LEAD],
+			Self::Resolve => &[PAD],
+		}
+	}
+
+	/// The sections the player can choose to move to from this one.
+	fn exits(self) -> &'static [Self] {
+		match self {
+			Self::Explore => &[Self::Tension],
+			Self::Tension => &[Self::Combat, Self::Explore],
+			Self::Combat => &[Self::Resolve],
+			Self::Resolve => &[],
+		}
+	}
+
+	/// The section that follows this one on its own after it's
+	/// played once. Sections without one loop until the player
+	/// moves on.
+	fn followed_by(self) -> Option<Self> {
+		match self {
+			Self::Resolve => Some(Self::Explore),
+			_ => None,
+		}
+	}
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetMetronomeClick(bool),
+	GoTo(Node),
+}
+
+pub struct BranchingMusicDemo {
+	audio_manager: AudioManager,
+	group_id: GroupId,
+	stem_sound_ids: Vec<SoundId>,
+	/// The newest sequence, which emits each section as it
+	/// starts.
+	sequence: Option<(SequenceInstanceId, EventReceiver<Node>)>,
+	/// The section that's playing, once the music has started.
+	current: Option<Node>,
+	/// The section the music will move to at the next bar.
+	pending: Option<Node>,
+	transport: Transport,
+	screen_wrapper: ScreenWrapper<Message>,
+	exit_buttons: [iced::button::State; 2],
+}
+
+impl BranchingMusicDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(AudioManagerSettings {
+			metronome_settings: Transport::metronome_settings(TEMPO),
+			..audio_manager_settings
+		})?;
+		let transport = Transport::new(&mut audio_manager, TEMPO)?;
+		let group_id = audio_manager.add_group([])?;
+		let assets_base_dir = std::env::current_dir()?.join("assets");
+		let mut stem_sound_ids = vec![];
+		for path in ASSETS {
+			// a move on the first bar of a phrase can play a stem
+			// at the same moment the old section loops it, which
+			// a cooldown would block
+			stem_sound_ids.push(
+				audio_manager.add_sound(sound_cache.get(&assets_base_dir.join(path))?.to_sound(
+					PlayableSettings {
+						cooldown: None,
+						..PlayableSettings::new().groups([group_id])
+					},
+				))?,
+			);
+		}
+		Ok(Self {
+			audio_manager,
+			group_id,
+			stem_sound_ids,
+			sequence: None,
+			current: None,
+			pending: None,
+			transport,
+			screen_wrapper: ScreenWrapper::new(
+				"Branching music demo".into(),
+				Message::GoToDemoSelect,
+			),
+			exit_buttons: Default::default(),
+		})
+	}
+
+	fn add_section(&self, sequence: &mut Sequence<Node>, node: Node) {
+		sequence.emit(node);
+		for stem in node.stems() {
+			sequence.play(self.stem_sound_ids[*stem], Default::default());
+		}
+		sequence.wait(Duration::Beats(PHRASE_LENGTH));
+	}
+
+	/// Starts a sequence that waits for the next bar, stops the
+	/// sequence that was playing (if any), and plays a section.
+	fn start_section(&mut self, node: Node) -> AudioResult<()> {
+		let mut sequence = Sequence::new(SequenceSettings::new().groups([self.group_id]));
+		sequence.wait_for_interval(BEATS_PER_BAR);
+		if let Some((previous_sequence_id, _)) = self.sequence.take() {
+			sequence.stop_sequence_and_instances(
+				previous_sequence_id,
+				StopInstanceSettings::new().fade_tween(Tween::linear(0.05)),
+			);
+		}
+		let mut node = node;
+		while let Some(next) = node.followed_by() {
+			self.add_section(&mut sequence, node);
+			node = next;
+		}
+		sequence.start_loop();
+		self.add_section(&mut sequence, node);
+		self.sequence = Some(
+			self.audio_manager
+				.start_sequence(sequence, Default::default())?,
+		);
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if self.sequence.is_none() => {
+				self.pending = Some(Node::Explore);
+				self.start_section(Node::Explore)?;
+				self.transport.start(&mut self.audio_manager)?;
+			}
+			Message::Stop => {
+				self.audio_manager
+					.stop_group(self.group_id, Default::default())?;
+				self.transport.stop(&mut self.audio_manager)?;
+				self.sequence = None;
+				self.current = None;
+				self.pending = None;
+			}
+			Message::SetMetronomeClick(enabled) => {
+				self.transport
+					.set_click_enabled(&mut self.audio_manager, enabled)?;
+			}
+			// the buttons are disabled while a move is pending, but
+			// a replayed session can still press them
+			Message::GoTo(node)
+				if self.pending.is_none()
+					&& matches!(self.current, Some(current) if current.exits().contains(&node)) =>
+			{
+				self.pending = Some(node);
+				self.start_section(node)?;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		self.transport.check_for_events(&mut self.audio_manager);
+		if let Some((_, events)) = &mut self.sequence {
+			while let Some(node) = events.pop().copied() {
+				self.current = Some(node);
+				self.pending = node.followed_by();
+			}
+		}
+		Ok(())
+	}
+
+	fn status_text(&self) -> String {
+		match (self.current, self.pending) {
+			(Some(current), Some(pending)) if current.followed_by() == Some(pending) => format!(
+				"{} plays once, then moves to {}",
+				current.name(),
+				pending.name()
+			),
+			(Some(current), Some(pending)) => format!(
+				"Moving from {} to {} at the next bar",
+				current.name(),
+				pending.name()
+			),
+			(Some(current), None) => format!("Playing {}", current.name()),
+			(None, Some(pending)) => format!("Starting with {}", pending.name()),
+			(None, None) => "Stopped".into(),
+		}
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let transitions = Node::ALL
+			.iter()
+			.flat_map(|node| {
+				node.exits()
+					.iter()
+					.chain(node.followed_by().iter())
+					.map(move |next| (node.index(), next.index()))
+					.collect::<Vec<_>>()
+			})
+			.collect();
+		let graph = StoryGraph::new(&Node::NAMES, transitions)
+			.current(self.current.map(Node::index))
+			.pending(self.pending.map(Node::index));
+		let status_text = self.status_text();
+		let exits = self.current.map_or(&[][..], Node::exits);
+		let can_move = self.pending.is_none();
+		let exit_buttons = exits.iter().zip(self.exit_buttons.iter_mut()).fold(
+			Row::new().spacing(16),
+			|row, (node, state)| {
+				let mut button = Button::new(
+					state,
+					Text::new(format!("Go to {}", node.name()))
+						.width(Length::Fill)
+						.horizontal_alignment(HorizontalAlignment::Center),
+				)
+				.width(Length::Units(150))
+				.style(AppStyles);
+				if can_move {
+					button = button.on_press(Message::GoTo(*node));
+				}
+				row.push(button)
+			},
+		);
+		self.screen_wrapper.view_with_transport(
+			self.transport
+				.view(Message::Play, Message::Stop, Message::SetMetronomeClick),
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(Column::new().max_width(600).push(graph))
+				.push(Text::new(status_text))
+				.push(exit_buttons)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for BranchingMusicDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "branching_music_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![Message::SetMetronomeClick(self.transport.click_enabled())]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for BranchingMusicDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.transport.is_playing(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if !self.transport.is_playing() {
+			return None;
+		}
+		Some(match self.current {
+			Some(node) => format!("Branching music demo - {}", node.name()),
+			None => "Branching music demo".into(),
+		})
+	}
+}
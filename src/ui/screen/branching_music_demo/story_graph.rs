@@ -0,0 +1,239 @@
+use iced::{mouse::Interaction, Background, Length, Point, Rectangle, Size, Vector};
+use iced_graphics::{
+	triangle::{Mesh2D, Vertex2D},
+	Backend, Defaults, Primitive, Renderer,
+};
+use iced_native::{
+	layout::{Limits, Node},
+	Element, HorizontalAlignment, Layout, VerticalAlignment, Widget,
+};
+
+const NODE_WIDTH: f32 = 110.0;
+const NODE_HEIGHT: f32 = 40.0;
+/// How far below the nodes each step of a backward edge is
+/// drawn, so edges that skip more nodes don't overlap.
+const RETURN_EDGE_DEPTH: f32 = 16.0;
+const NODE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.1];
+const CURRENT_NODE_COLOR: [f32; 4] = [1.0, 0.6, 0.0, 0.8];
+const NODE_BORDER_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.3];
+const PENDING_BORDER_COLOR: [f32; 4] = [1.0, 0.6, 0.0, 1.0];
+const EDGE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.25];
+const PENDING_EDGE_COLOR: [f32; 4] = [1.0, 0.6, 0.0, 1.0];
+const EDGE_WIDTH: f32 = 2.0;
+const PENDING_EDGE_WIDTH: f32 = 4.0;
+
+/// Draws the sections of a piece of music as a row of boxes,
+/// with lines for the transitions between them. Transitions
+/// to a later section go straight across, and transitions
+/// back to an earlier one loop around underneath.
+///
+/// Sections are referred to by their position in the row.
+pub struct StoryGraph<'a> {
+	sections: &'a [&'a str],
+	transitions: Vec<(usize, usize)>,
+	current: Option<usize>,
+	pending: Option<usize>,
+}
+
+impl<'a> StoryGraph<'a> {
+	pub fn new(sections: &'a [&'a str], transitions: Vec<(usize, usize)>) -> Self {
+		Self {
+			sections,
+			transitions,
+			current: None,
+			pending: None,
+		}
+	}
+
+	/// Highlights the section that's playing.
+	pub fn current(self, current: Option<usize>) -> Self {
+		Self { current, ..self }
+	}
+
+	/// Highlights the section the music is about to move to,
+	/// along with the transition to it.
+	pub fn pending(self, pending: Option<usize>) -> Self {
+		Self { pending, ..self }
+	}
+
+	/// The deepest any backward edge goes below the nodes.
+	fn return_edges_depth(&self) -> f32 {
+		self.transitions
+			.iter()
+			.filter(|(from, to)| to < from)
+			.map(|(from, to)| (from - to) as f32 * RETURN_EDGE_DEPTH)
+			.fold(0.0, f32::max)
+	}
+
+	fn height(&self) -> f32 {
+		NODE_HEIGHT + self.return_edges_depth() + PENDING_EDGE_WIDTH
+	}
+
+	/// Gets the bounds of each node relative to the top-left
+	/// corner of the widget, spread evenly across the width.
+	fn node_bounds(&self, size: Size) -> Vec<Rectangle> {
+		let spacing = if self.sections.len() > 1 {
+			(size.width - NODE_WIDTH) / (self.sections.len() - 1) as f32
+		} else {
+			0.0
+		};
+		(0..self.sections.len())
+			.map(|i| {
+				Rectangle::new(
+					Point::new(i as f32 * spacing, 0.0),
+					Size::new(NODE_WIDTH, NODE_HEIGHT),
+				)
+			})
+			.collect()
+	}
+
+	fn is_pending(&self, transition: (usize, usize)) -> bool {
+		matches!((self.current, self.pending), (Some(from), Some(to)) if (from, to) == transition)
+	}
+
+	fn edge_mesh(&self, node_bounds: &[Rectangle]) -> Mesh2D {
+		let mut mesh = Mesh2D {
+			vertices: vec![],
+			indices: vec![],
+		};
+		for (from, to) in self.transitions.iter().copied() {
+			let (width, color) = if self.is_pending((from, to)) {
+				(PENDING_EDGE_WIDTH, PENDING_EDGE_COLOR)
+			} else {
+				(EDGE_WIDTH, EDGE_COLOR)
+			};
+			let from_bounds = node_bounds[from];
+			let to_bounds = node_bounds[to];
+			if to > from {
+				let start = Point::new(
+					from_bounds.x + from_bounds.width,
+					from_bounds.y + from_bounds.height / 2.0,
+				);
+				let end = Point::new(to_bounds.x, to_bounds.y + to_bounds.height / 2.0);
+				push_line(&mut mesh, start, end, width, color);
+			} else {
+				let bottom = NODE_HEIGHT + (from - to) as f32 * RETURN_EDGE_DEPTH;
+				let points = [
+					Point::new(from_bounds.center_x(), NODE_HEIGHT),
+					Point::new(from_bounds.center_x(), bottom),
+					Point::new(to_bounds.center_x(), bottom),
+					Point::new(to_bounds.center_x(), NODE_HEIGHT),
+				];
+				for pair in points.windows(2) {
+					push_line(&mut mesh, pair[0], pair[1], width, color);
+				}
+			}
+		}
+		mesh
+	}
+}
+
+/// Adds a straight line to a mesh as a pair of triangles.
+fn push_line(mesh: &mut Mesh2D, start: Point, end: Point, width: f32, color: [f32; 4]) {
+	let direction = end - start;
+	let length = (direction.x * direction.x + direction.y * direction.y).sqrt();
+	if length == 0.0 {
+		return;
+	}
+	// the lines are lengthened by half their width so the
+	// corners of the backward edges join up
+	let along = Vector::new(
+		direction.x / length * width / 2.0,
+		direction.y / length * width / 2.0,
+	);
+	let normal = Vector::new(-along.y, along.x);
+	let start = start - along;
+	let end = end + along;
+	let first_index = mesh.vertices.len() as u32;
+	for point in &[start + normal, start - normal, end - normal, end + normal] {
+		mesh.vertices.push(Vertex2D {
+			position: [point.x, point.y],
+			color,
+		});
+	}
+	mesh.indices.extend_from_slice(&[
+		first_index,
+		first_index + 1,
+		first_index + 2,
+		first_index,
+		first_index + 2,
+		first_index + 3,
+	]);
+}
+
+impl<'a, Message, B: Backend> Widget<Message, Renderer<B>> for StoryGraph<'a> {
+	fn width(&self) -> Length {
+		Length::Fill
+	}
+
+	fn height(&self) -> Length {
+		Length::Shrink
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, limits: &Limits) -> Node {
+		Node::new(
+			limits
+				.width(Length::Fill)
+				.height(Length::Units(self.height() as u16))
+				.resolve(Size::ZERO),
+		)
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		defaults: &Defaults,
+		layout: Layout<'_>,
+		_cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let node_bounds = self.node_bounds(bounds.size());
+		let mut primitives = vec![Primitive::Translate {
+			translation: Vector::new(bounds.x, bounds.y),
+			content: Box::new(Primitive::Mesh2D {
+				buffers: self.edge_mesh(&node_bounds),
+				size: bounds.size(),
+			}),
+		}];
+		for (i, (name, node_bounds)) in self.sections.iter().zip(&node_bounds).enumerate() {
+			let node_bounds = *node_bounds + Vector::new(bounds.x, bounds.y);
+			primitives.push(Primitive::Quad {
+				bounds: node_bounds,
+				background: Background::Color(if self.current == Some(i) {
+					CURRENT_NODE_COLOR.into()
+				} else {
+					NODE_COLOR.into()
+				}),
+				border_radius: 4.0,
+				border_width: if self.pending == Some(i) { 2.0 } else { 1.0 },
+				border_color: if self.pending == Some(i) {
+					PENDING_BORDER_COLOR.into()
+				} else {
+					NODE_BORDER_COLOR.into()
+				},
+			});
+			primitives.push(Primitive::Text {
+				content: name.to_string(),
+				bounds: Rectangle::new(node_bounds.center(), node_bounds.size()),
+				color: defaults.text.color,
+				size: 18.0,
+				font: Default::default(),
+				horizontal_alignment: HorizontalAlignment::Center,
+				vertical_alignment: VerticalAlignment::Center,
+			});
+		}
+		(Primitive::Group { primitives }, Interaction::default())
+	}
+
+	fn hash_layout(&self, state: &mut iced_native::Hasher) {
+		use std::hash::Hash;
+		self.height().to_bits().hash(state)
+	}
+}
+
+impl<'a, Message, B: Backend> From<StoryGraph<'a>> for Element<'a, Message, Renderer<B>> {
+	fn from(story_graph: StoryGraph<'a>) -> Self {
+		Element::new(story_graph)
+	}
+}
@@ -0,0 +1,443 @@
+use std::{error::Error, fmt::Display};
+
+use iced::{pick_list, Align, Column, Length, PickList, Radio, Row, Text};
+use kira::{
+	group::GroupId,
+	instance::{InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::Tween,
+	playable::PlayableSettings,
+	sequence::{EventReceiver, Sequence, SequenceInstanceId, SequenceSettings},
+	sound::SoundId,
+	AudioResult, Duration, Tempo,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, synth},
+	ui::common::{screen_wrapper::ScreenWrapper, transport::Transport},
+};
+
+const EXPLANATION_TEXT: &str = "Pick a chord for each of the four \
+bars and a way to play them, and the progression loops on the \
+metronome's grid: stabs hit the whole chord on every beat, and \
+arpeggios run up and down it in eighth notes. A bass note marks \
+the start of each bar.
+
+The whole progression is one looping sequence. Editing it while \
+it plays doesn't touch that sequence - kira can't change a \
+sequence once it's started. Instead, the screen builds a new one \
+that waits for the next bar on the metronome, stops the old \
+sequence (and the notes it's still ringing), and loops the new \
+progression from the bar the old one would have played next. \
+Because both sequences line up on the same metronome, the \
+handover lands on the downbeat and the music doesn't skip.";
+
+pub const TEMPO: Tempo = Tempo(96.0);
+const BEATS_PER_BAR: usize = 4;
+const NUM_BARS: usize = 4;
+/// The pitch of the chord sound, which all the chords are tuned
+/// from (middle C, in Hz).
+const BASE_FREQUENCY: f64 = 261.63;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Chord {
+	C,
+	Dm,
+	Em,
+	F,
+	G,
+	Am,
+	Bb,
+	E,
+}
+
+impl Chord {
+	const ALL: [Self; 8] = [
+		Self::C,
+		Self::Dm,
+		Self::Em,
+		Self::F,
+		Self::G,
+		Self::Am,
+		Self::Bb,
+		Self::E,
+	];
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::C => "C",
+			Self::Dm => "Dm",
+			Self::Em => "Em",
+			Self::F => "F",
+			Self::G => "G",
+			Self::Am => "Am",
+			Self::Bb => "Bb",
+			Self::E => "E",
+		}
+	}
+
+	/// The notes of the chord, root first (in semitones above
+	/// middle C).
+	fn notes(self) -> [i32; 3] {
+		match self {
+			Self::C => [0, 4, 7],
+			Self::Dm => [2, 5, 9],
+			Self::Em => [4, 7, 11],
+			Self::F => [5, 9, 12],
+			Self::G => [7, 11, 14],
+			Self::Am => [9, 12, 16],
+			Self::Bb => [10, 14, 17],
+			Self::E => [4, 8, 11],
+		}
+	}
+}
+
+impl Display for Chord {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Pattern {
+	Stabs,
+	Arpeggio,
+}
+
+impl Pattern {
+	const ALL: [Self; 2] = [Self::Stabs, Self::Arpeggio];
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::Stabs => "Stabs",
+			Self::Arpeggio => "Arpeggio",
+		}
+	}
+}
+
+/// The order an arpeggio plays a chord's notes in, where 3 is
+/// the root an octave up.
+const ARPEGGIO_ORDER: [usize; 8] = [0, 1, 2, 3, 2, 1, 0, 1];
+
+const DEFAULT_PROGRESSION: [Chord; NUM_BARS] = [Chord::C, Chord::Am, Chord::F, Chord::G];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum ProgressionEvent {
+	/// A bar of the progression started.
+	Bar(usize),
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetMetronomeClick(bool),
+	/// Sets the chord for one of the bars.
+	SetChord(usize, Chord),
+	SetPattern(Pattern),
+}
+
+fn pitch(semitones: i32) -> f64 {
+	2.0f64.powf(semitones as f64 / 12.0)
+}
+
+pub struct ChordProgressionDemo {
+	audio_manager: AudioManager,
+	group_id: GroupId,
+	chord_sound_id: SoundId,
+	bass_sound_id: SoundId,
+	progression: [Chord; NUM_BARS],
+	pattern: Pattern,
+	sequence: Option<(SequenceInstanceId, EventReceiver<ProgressionEvent>)>,
+	/// The bar that's playing, as last reported by the sequence.
+	current_bar: Option<usize>,
+	/// The sequence that's still playing while a newer one waits
+	/// to take over from it at the next bar.
+	outgoing_sequence_id: Option<SequenceInstanceId>,
+	transport: Transport,
+	screen_wrapper: ScreenWrapper<Message>,
+	chord_pick_lists: [pick_list::State<Chord>; NUM_BARS],
+}
+
+impl ChordProgressionDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(AudioManagerSettings {
+			metronome_settings: Transport::metronome_settings(TEMPO),
+			..audio_manager_settings
+		})?;
+		let transport = Transport::new(&mut audio_manager, TEMPO)?;
+		let group_id = audio_manager.add_group([])?;
+		// every note of a chord starts at the same time, which a
+		// cooldown would block
+		let settings = || PlayableSettings {
+			cooldown: None,
+			..PlayableSettings::new().groups([group_id])
+		};
+		let chord_sound_id =
+			audio_manager.add_sound(synth::blip(BASE_FREQUENCY, 0.5, settings()))?;
+		let bass_sound_id =
+			audio_manager.add_sound(synth::blip(BASE_FREQUENCY / 4.0, 1.5, settings()))?;
+		Ok(Self {
+			audio_manager,
+			group_id,
+			chord_sound_id,
+			bass_sound_id,
+			progression: DEFAULT_PROGRESSION,
+			pattern: Pattern::Stabs,
+			sequence: None,
+			current_bar: None,
+			outgoing_sequence_id: None,
+			transport,
+			screen_wrapper: ScreenWrapper::new(
+				"Chord progression demo".into(),
+				Message::GoToDemoSelect,
+			),
+			chord_pick_lists: Default::default(),
+		})
+	}
+
+	/// Adds one bar of a chord, played with the current pattern,
+	/// to a sequence.
+	fn add_bar(&self, sequence: &mut Sequence<ProgressionEvent>, chord: Chord) {
+		let notes = chord.notes();
+		sequence.play(
+			self.bass_sound_id,
+			InstanceSettings::new().pitch(pitch(notes[0])).volume(0.6),
+		);
+		match self.pattern {
+			Pattern::Stabs => {
+				for _ in 0..BEATS_PER_BAR {
+					for note in notes.iter().copied() {
+						sequence.play(
+							self.chord_sound_id,
+							InstanceSettings::new().pitch(pitch(note)).volume(0.3),
+						);
+					}
+					sequence.wait(Duration::Beats(1.0));
+				}
+			}
+			Pattern::Arpeggio => {
+				let steps = BEATS_PER_BAR * 2;
+				for step in 0..steps {
+					let note = match ARPEGGIO_ORDER[step % ARPEGGIO_ORDER.len()] {
+						3 => notes[0] + 12,
+						index => notes[index],
+					};
+					sequence.play(
+						self.chord_sound_id,
+						InstanceSettings::new().pitch(pitch(note)).volume(0.4),
+					);
+					sequence.wait(Duration::Beats(0.5));
+				}
+			}
+		}
+	}
+
+	/// Starts a sequence that waits for the next bar, takes over
+	/// from the sequence that was playing (if any), and loops the
+	/// progression starting from `first_bar`.
+	fn start_progression(&mut self, first_bar: usize) -> AudioResult<()> {
+		let mut sequence = Sequence::new(SequenceSettings::new().groups([self.group_id]));
+		sequence.wait_for_interval(BEATS_PER_BAR as f64);
+		if let Some((previous_sequence_id, _)) = self.sequence.take() {
+			if self.outgoing_sequence_id.is_some() {
+				// the previous edit hasn't been heard yet, so its
+				// sequence can go right away, and this one takes
+				// over from the sequence that's still playing
+				self.audio_manager.stop_sequence(previous_sequence_id)?;
+			} else {
+				self.outgoing_sequence_id = Some(previous_sequence_id);
+			}
+		}
+		if let Some(outgoing_sequence_id) = self.outgoing_sequence_id {
+			sequence.stop_sequence_and_instances(
+				outgoing_sequence_id,
+				StopInstanceSettings::new().fade_tween(Tween::linear(0.05)),
+			);
+		}
+		sequence.start_loop();
+		for i in 0..NUM_BARS {
+			let bar = (first_bar + i) % NUM_BARS;
+			sequence.emit(ProgressionEvent::Bar(bar));
+			self.add_bar(&mut sequence, self.progression[bar]);
+		}
+		self.sequence = Some(
+			self.audio_manager
+				.start_sequence(sequence, Default::default())?,
+		);
+		Ok(())
+	}
+
+	/// Rebuilds the sequence after an edit, if the music is
+	/// playing.
+	fn progression_changed(&mut self) -> AudioResult<()> {
+		if self.sequence.is_none() {
+			return Ok(());
+		}
+		let next_bar = self
+			.current_bar
+			.map_or(0, |current_bar| (current_bar + 1) % NUM_BARS);
+		self.start_progression(next_bar)
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if self.sequence.is_none() => {
+				self.start_progression(0)?;
+				self.transport.start(&mut self.audio_manager)?;
+			}
+			Message::Stop => {
+				self.audio_manager
+					.stop_group(self.group_id, Default::default())?;
+				self.transport.stop(&mut self.audio_manager)?;
+				self.sequence = None;
+				self.current_bar = None;
+				self.outgoing_sequence_id = None;
+			}
+			Message::SetMetronomeClick(enabled) => {
+				self.transport
+					.set_click_enabled(&mut self.audio_manager, enabled)?;
+			}
+			Message::SetChord(bar, chord) if bar < NUM_BARS && self.progression[bar] != chord => {
+				self.progression[bar] = chord;
+				self.progression_changed()?;
+			}
+			Message::SetPattern(pattern) if self.pattern != pattern => {
+				self.pattern = pattern;
+				self.progression_changed()?;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		self.transport.check_for_events(&mut self.audio_manager);
+		if let Some((_, events)) = &mut self.sequence {
+			while let Some(event) = events.pop().copied() {
+				match event {
+					ProgressionEvent::Bar(bar) => {
+						self.current_bar = Some(bar);
+						self.outgoing_sequence_id = None;
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+
+	fn status_text(&self) -> String {
+		match self.current_bar {
+			Some(bar) if self.outgoing_sequence_id.is_some() => format!(
+				"Playing bar {} ({}) - the edit starts at the next bar",
+				bar + 1,
+				self.progression[bar].name()
+			),
+			Some(bar) => format!("Playing bar {} ({})", bar + 1, self.progression[bar].name()),
+			None if self.sequence.is_some() => "Starting at the next bar".into(),
+			None => "Stopped".into(),
+		}
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let current_bar = self.current_bar;
+		let status_text = self.status_text();
+		let pattern = self.pattern;
+		let chord_pickers = self
+			.chord_pick_lists
+			.iter_mut()
+			.zip(self.progression.iter().copied())
+			.enumerate()
+			.fold(Row::new().spacing(16), |row, (bar, (state, chord))| {
+				let label = if current_bar == Some(bar) {
+					format!("> Bar {}", bar + 1)
+				} else {
+					format!("Bar {}", bar + 1)
+				};
+				row.push(
+					Column::new()
+						.spacing(8)
+						.align_items(Align::Center)
+						.width(Length::Units(100))
+						.push(Text::new(label))
+						.push(
+							PickList::new(state, &Chord::ALL[..], Some(chord), move |chord| {
+								Message::SetChord(bar, chord)
+							})
+							.width(Length::Fill),
+						),
+				)
+			});
+		let pattern_radios = Pattern::ALL
+			.iter()
+			.fold(Row::new().spacing(16), |row, option| {
+				row.push(Radio::new(
+					*option,
+					option.name(),
+					Some(pattern),
+					Message::SetPattern,
+				))
+			});
+		self.screen_wrapper.view_with_transport(
+			self.transport
+				.view(Message::Play, Message::Stop, Message::SetMetronomeClick),
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(chord_pickers)
+				.push(pattern_radios)
+				.push(Text::new(status_text))
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for ChordProgressionDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "chord_progression_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		let mut messages = vec![
+			Message::SetMetronomeClick(self.transport.click_enabled()),
+			Message::SetPattern(self.pattern),
+		];
+		for (bar, chord) in self.progression.iter().copied().enumerate() {
+			messages.push(Message::SetChord(bar, chord));
+		}
+		messages
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for ChordProgressionDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.transport.is_playing(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if !self.transport.is_playing() {
+			return None;
+		}
+		let progression = self
+			.progression
+			.iter()
+			.map(|chord| chord.name())
+			.collect::<Vec<_>>()
+			.join(" ");
+		Some(format!("Chord progression demo - {}", progression))
+	}
+}
@@ -0,0 +1,500 @@
+use std::error::Error;
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Row, Slider, Text};
+use kira::{
+	group::GroupId,
+	instance::InstanceSettings,
+	manager::{AudioManager, AudioManagerSettings},
+	mixer::effect::filter::{Filter, FilterSettings},
+	parameter::{Mapping, ParameterId, Tween},
+	playable::PlayableSettings,
+	sequence::{Sequence, SequenceInstanceId, SequenceSettings},
+	sound::SoundId,
+	AudioResult, Duration, Tempo, Value,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, sound_cache::SoundCache, synth},
+	ui::{
+		common::{screen_wrapper::ScreenWrapper, transport::Transport},
+		style::AppStyles,
+	},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &[
+	"underwater demo/pad.ogg",
+	"underwater demo/bass.ogg",
+	"underwater demo/lead.ogg",
+];
+
+const EXPLANATION_TEXT: &str = "Everything about the music \
+follows one number: the threat. Enemies showing up and leaving \
+raise and lower it, and the slider sets it directly, like a \
+script would for a boss fight.
+
+The threat is a single parameter, and the rest of the mix reads \
+it through mappings. The pad backs off a little as it rises, the \
+bass fades in from 20% to 50%, the lead from 60% to 90%, and the \
+cutoff of a low-pass filter on the music's track opens up until \
+80%. None of that needs any code when the threat changes - the \
+screen only moves the parameter.
+
+The drums can't fade between patterns, so each density is its \
+own looping sequence, like the drum fill demo. When the threat \
+crosses into a new density, the screen starts that density's \
+sequence, which waits for the next bar and then stops the old \
+one.";
+
+pub const TEMPO: Tempo = Tempo(85.0);
+const BEATS_PER_BAR: f64 = 4.0;
+/// How long each stem is (in beats).
+const PHRASE_LENGTH: f64 = 16.0;
+/// How long the mix takes to follow a change in threat.
+const THREAT_TWEEN_TIME: f64 = 1.0;
+const MAX_ENEMIES: usize = 8;
+/// How much of the remaining calm each enemy takes away.
+const THREAT_PER_ENEMY: f64 = 0.3;
+
+/// How loud each stem is for a threat level, in the same order
+/// as [`ASSETS`].
+const STEM_MAPPINGS: [(&str, Mapping); 3] = [
+	(
+		"Pad",
+		Mapping {
+			input_range: (0.0, 1.0),
+			output_range: (1.0, 0.6),
+			clamp_bottom: false,
+			clamp_top: false,
+		},
+	),
+	(
+		"Bass",
+		Mapping {
+			input_range: (0.2, 0.5),
+			output_range: (0.0, 1.0),
+			clamp_bottom: true,
+			clamp_top: true,
+		},
+	),
+	(
+		"Lead",
+		Mapping {
+			input_range: (0.6, 0.9),
+			output_range: (0.0, 1.0),
+			clamp_bottom: true,
+			clamp_top: true,
+		},
+	),
+];
+/// The filter cutoff (in Hz) for a threat level.
+const CUTOFF_MAPPING: Mapping = Mapping {
+	input_range: (0.0, 0.8),
+	output_range: (400.0, 20000.0),
+	clamp_bottom: true,
+	clamp_top: true,
+};
+
+/// Works out the threat from how many enemies are around. Each
+/// one raises it by less than the last, so it never quite gets to
+/// the top.
+fn enemy_threat(enemies: usize) -> f64 {
+	1.0 - (1.0 - THREAT_PER_ENEMY).powi(enemies as i32)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Drum {
+	Kick,
+	Snare,
+	Hat,
+}
+
+/// How busy the drums are.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Density {
+	Calm,
+	Alert,
+	Combat,
+}
+
+impl Density {
+	fn for_threat(threat: f64) -> Self {
+		if threat < 0.35 {
+			Self::Calm
+		} else if threat < 0.7 {
+			Self::Alert
+		} else {
+			Self::Combat
+		}
+	}
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::Calm => "Calm",
+			Self::Alert => "Alert",
+			Self::Combat => "Combat",
+		}
+	}
+
+	/// The drum hits in one bar, in order (the times are in
+	/// beats).
+	fn hits(self) -> &'static [(f64, Drum)] {
+		match self {
+			Self::Calm => &[(0.0, Drum::Kick), (2.0, Drum::Hat)],
+			Self::Alert => &[
+				(0.0, Drum::Kick),
+				(1.0, Drum::Hat),
+				(2.0, Drum::Kick),
+				(3.0, Drum::Hat),
+			],
+			Self::Combat => &[
+				(0.0, Drum::Kick),
+				(0.5, Drum::Hat),
+				(1.0, Drum::Snare),
+				(1.5, Drum::Hat),
+				(2.0, Drum::Kick),
+				(2.5, Drum::Kick),
+				(3.0, Drum::Snare),
+				(3.5, Drum::Hat),
+			],
+		}
+	}
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetMetronomeClick(bool),
+	SetEnemies(usize),
+	SetThreat(f64),
+}
+
+pub struct CombatMusicDemo {
+	audio_manager: AudioManager,
+	group_id: GroupId,
+	threat_parameter_id: ParameterId,
+	stem_sound_ids: Vec<SoundId>,
+	kick_sound_id: SoundId,
+	snare_sound_id: SoundId,
+	hat_sound_id: SoundId,
+	drum_sequence_id: Option<SequenceInstanceId>,
+	playing: bool,
+	enemies: usize,
+	threat: f64,
+	/// The density of the newest drum sequence, which is playing
+	/// or will start at the next bar.
+	density: Density,
+	transport: Transport,
+	screen_wrapper: ScreenWrapper<Message>,
+	add_enemy_button: iced::button::State,
+	remove_enemy_button: iced::button::State,
+	threat_slider: iced::slider::State,
+}
+
+impl CombatMusicDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(AudioManagerSettings {
+			metronome_settings: Transport::metronome_settings(TEMPO),
+			..audio_manager_settings
+		})?;
+		let transport = Transport::new(&mut audio_manager, TEMPO)?;
+		let group_id = audio_manager.add_group([])?;
+		let threat_parameter_id = audio_manager.add_parameter(0.0)?;
+		let track_id = audio_manager.add_sub_track(Default::default())?;
+		audio_manager.add_effect_to_track(
+			track_id,
+			Filter::new(
+				FilterSettings::new().cutoff(Value::Parameter(threat_parameter_id, CUTOFF_MAPPING)),
+			),
+			Default::default(),
+		)?;
+		let settings = || {
+			PlayableSettings::new()
+				.default_track(track_id)
+				.groups([group_id])
+		};
+		let assets_base_dir = std::env::current_dir()?.join("assets");
+		let mut stem_sound_ids = vec![];
+		for path in ASSETS {
+			stem_sound_ids.push(
+				audio_manager.add_sound(
+					sound_cache
+						.get(&assets_base_dir.join(path))?
+						.to_sound(settings()),
+				)?,
+			);
+		}
+		let kick_sound_id = audio_manager.add_sound(synth::kick(50.0, 0.5, settings()))?;
+		let snare_sound_id = audio_manager.add_sound(synth::snare(180.0, 0.25, settings()))?;
+		let hat_sound_id = audio_manager.add_sound(synth::noise_hit(0.05, 1.0, settings()))?;
+		Ok(Self {
+			audio_manager,
+			group_id,
+			threat_parameter_id,
+			stem_sound_ids,
+			kick_sound_id,
+			snare_sound_id,
+			hat_sound_id,
+			drum_sequence_id: None,
+			playing: false,
+			enemies: 0,
+			threat: 0.0,
+			density: Density::Calm,
+			transport,
+			screen_wrapper: ScreenWrapper::new("Combat music demo".into(), Message::GoToDemoSelect),
+			add_enemy_button: iced::button::State::new(),
+			remove_enemy_button: iced::button::State::new(),
+			threat_slider: iced::slider::State::new(),
+		})
+	}
+
+	/// Starts a sequence that loops the stems, each with its
+	/// volume mapped from the threat.
+	fn start_stem_sequence(&mut self) -> AudioResult<()> {
+		let mut sequence = Sequence::<()>::new(SequenceSettings::new().groups([self.group_id]));
+		sequence.wait_for_interval(BEATS_PER_BAR);
+		sequence.start_loop();
+		for (sound_id, (_, mapping)) in self.stem_sound_ids.iter().zip(STEM_MAPPINGS.iter()) {
+			sequence.play(
+				*sound_id,
+				InstanceSettings::new()
+					.volume(Value::Parameter(self.threat_parameter_id, *mapping)),
+			);
+		}
+		sequence.wait(Duration::Beats(PHRASE_LENGTH));
+		self.audio_manager
+			.start_sequence(sequence, Default::default())?;
+		Ok(())
+	}
+
+	/// Starts a sequence that waits for the next bar, stops the
+	/// drum sequence that was playing (if any), and loops the
+	/// drums for a density.
+	fn start_drum_sequence(&mut self, density: Density) -> AudioResult<()> {
+		let mut sequence = Sequence::<()>::new(SequenceSettings::new().groups([self.group_id]));
+		sequence.wait_for_interval(BEATS_PER_BAR);
+		if let Some(previous_sequence_id) = self.drum_sequence_id.take() {
+			sequence.stop_sequence(previous_sequence_id);
+		}
+		sequence.start_loop();
+		let mut time = 0.0;
+		for (beat, drum) in density.hits() {
+			if *beat > time {
+				sequence.wait(Duration::Beats(beat - time));
+				time = *beat;
+			}
+			let sound_id = match drum {
+				Drum::Kick => self.kick_sound_id,
+				Drum::Snare => self.snare_sound_id,
+				Drum::Hat => self.hat_sound_id,
+			};
+			sequence.play(sound_id, Default::default());
+		}
+		sequence.wait(Duration::Beats(BEATS_PER_BAR - time));
+		let (sequence_id, _) = self
+			.audio_manager
+			.start_sequence(sequence, Default::default())?;
+		self.drum_sequence_id = Some(sequence_id);
+		self.density = density;
+		Ok(())
+	}
+
+	fn set_threat(&mut self, threat: f64) -> AudioResult<()> {
+		self.threat = threat;
+		self.audio_manager.set_parameter(
+			self.threat_parameter_id,
+			threat,
+			Some(Tween::linear(THREAT_TWEEN_TIME)),
+		)?;
+		let density = Density::for_threat(threat);
+		if density != self.density {
+			if self.playing {
+				self.start_drum_sequence(density)?;
+			} else {
+				self.density = density;
+			}
+		}
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if !self.playing => {
+				self.start_stem_sequence()?;
+				self.start_drum_sequence(self.density)?;
+				self.transport.start(&mut self.audio_manager)?;
+				self.playing = true;
+			}
+			Message::Stop => {
+				self.audio_manager
+					.stop_group(self.group_id, Default::default())?;
+				self.transport.stop(&mut self.audio_manager)?;
+				self.drum_sequence_id = None;
+				self.playing = false;
+			}
+			Message::SetMetronomeClick(enabled) => {
+				self.transport
+					.set_click_enabled(&mut self.audio_manager, enabled)?;
+			}
+			Message::SetEnemies(enemies) => {
+				self.enemies = enemies.min(MAX_ENEMIES);
+				self.set_threat(enemy_threat(self.enemies))?;
+			}
+			Message::SetThreat(threat) => self.set_threat(threat)?,
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		self.transport.check_for_events(&mut self.audio_manager);
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let enemies = self.enemies;
+		let threat = self.threat;
+		let mut add_enemy_button = Button::new(
+			&mut self.add_enemy_button,
+			Text::new("Enemy appears")
+				.width(Length::Fill)
+				.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.width(Length::Units(150))
+		.style(AppStyles);
+		if enemies < MAX_ENEMIES {
+			add_enemy_button = add_enemy_button.on_press(Message::SetEnemies(enemies + 1));
+		}
+		let mut remove_enemy_button = Button::new(
+			&mut self.remove_enemy_button,
+			Text::new("Enemy defeated")
+				.width(Length::Fill)
+				.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.width(Length::Units(150))
+		.style(AppStyles);
+		if enemies > 0 {
+			remove_enemy_button = remove_enemy_button.on_press(Message::SetEnemies(enemies - 1));
+		}
+		let levels = STEM_MAPPINGS
+			.iter()
+			.map(|(name, mapping)| format!("{}: {:.0}%", name, mapping.map(threat) * 100.0))
+			.collect::<Vec<_>>()
+			.join(" - ");
+		self.screen_wrapper.view_with_transport(
+			self.transport
+				.view(Message::Play, Message::Stop, Message::SetMetronomeClick),
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(remove_enemy_button)
+						.push(Text::new(format!("Enemies: {}", enemies)))
+						.push(add_enemy_button),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Threat: {:.0}%", threat * 100.0))
+								.width(Length::Units(150)),
+						)
+						.push(
+							Slider::new(
+								&mut self.threat_slider,
+								0.0..=1.0,
+								threat,
+								Message::SetThreat,
+							)
+							.step(0.01)
+							.style(AppStyles),
+						),
+				)
+				.push(Text::new(levels))
+				.push(Text::new(format!(
+					"Filter cutoff: {:.0} Hz - drums: {}",
+					CUTOFF_MAPPING.map(threat),
+					self.density.name()
+				)))
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for CombatMusicDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "combat_music_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetEnemies(_) => Some("Enemies".into()),
+			Message::SetThreat(_) => Some("Threat".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Enemies" => {
+				let enemies = (value * MAX_ENEMIES as f64).round() as usize;
+				if enemies == self.enemies {
+					vec![]
+				} else {
+					vec![Message::SetEnemies(enemies)]
+				}
+			}
+			"Threat" => vec![Message::SetThreat(value)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for CombatMusicDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "combat_music_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		// the enemies set the threat, so the threat goes second in
+		// case the slider moved it since
+		vec![
+			Message::SetMetronomeClick(self.transport.click_enabled()),
+			Message::SetEnemies(self.enemies),
+			Message::SetThreat(self.threat),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for CombatMusicDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(key, self.playing, Message::Play, Message::Stop)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if self.playing {
+			Some(format!("Combat music demo - {}", self.density.name()))
+		} else {
+			None
+		}
+	}
+}
@@ -0,0 +1,368 @@
+mod gain_reduction_meter;
+
+use std::{error::Error, time::Instant};
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Row, Slider, Text};
+use kira::{
+	instance::{InstanceId, InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	mixer::SubTrackId,
+	parameter::{ParameterId, Tween},
+	playable::PlayableSettings,
+	sound::SoundId,
+};
+use serde::{Deserialize, Serialize};
+
+use self::gain_reduction_meter::GainReductionMeter;
+use crate::{
+	audio::{
+		create_audio_manager,
+		effect::compressor::{Compressor, CompressorSettings, GainReductionReader},
+		sound_cache::SoundCache,
+	},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &["drum fill demo/loop.ogg"];
+
+const EXPLANATION_TEXT: &str = "The drum loop plays on a drum \
+bus (a sub track) with a compressor on it. Whenever the drums \
+get louder than the threshold, the compressor turns them down: \
+at a ratio of 4:1, a hit that goes 8 dB over the threshold comes \
+out only 2 dB over. The meter shows how many decibels are being \
+taken off right now, with the line marking the recent peak. \
+Since compressing makes the loud parts quieter, the makeup gain \
+turns the whole bus back up afterwards, which brings up the \
+quieter parts of the loop - the ghost notes and the tail of each \
+hit - and makes the drums sound denser.
+
+At 1:1 the compressor does nothing. At the top of the range the \
+ratio is so high that almost nothing gets past the threshold, so \
+it's acting as a limiter, the kind used to keep a mix from ever \
+clipping.
+
+Kira doesn't come with a compressor, so this one is a custom \
+effect. An effect only has to turn each input frame into an \
+output frame, and it runs on the audio thread, so it can't \
+report back to the screen through a message. Instead, when the \
+compressor is created it hands back a reader sharing an atomic \
+number with it, and the compressor stores its gain reduction \
+there after every frame. The screen reads that number each time \
+it redraws the meter. The threshold, ratio, and makeup gain are \
+parameters, so the compressor picks up slider changes the same \
+way kira's own effects do.";
+
+const MIN_THRESHOLD: f64 = -40.0;
+const MAX_RATIO: f64 = 20.0;
+/// Ratios this high hold the drums close enough to the threshold
+/// that the compressor is really a limiter.
+const LIMITER_RATIO: f64 = 10.0;
+const MAX_MAKEUP_GAIN: f64 = 24.0;
+const MAX_GAIN_REDUCTION: f32 = 24.0;
+/// How fast the peak line on the meter falls back (in decibels
+/// per second).
+const PEAK_FALL_SPEED: f32 = 6.0;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	/// Sets the threshold (in decibels).
+	SetThreshold(f64),
+	SetRatio(f64),
+	/// Sets the makeup gain (in decibels).
+	SetMakeupGain(f64),
+}
+
+pub struct CompressorDemo {
+	audio_manager: AudioManager,
+	drum_bus_id: SubTrackId,
+	loop_sound_id: SoundId,
+	instance_id: Option<InstanceId>,
+	threshold_parameter_id: ParameterId,
+	ratio_parameter_id: ParameterId,
+	makeup_gain_parameter_id: ParameterId,
+	threshold: f64,
+	ratio: f64,
+	makeup_gain: f64,
+	gain_reduction_reader: GainReductionReader,
+	gain_reduction: f32,
+	peak_gain_reduction: f32,
+	last_update_time: Instant,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_button: iced::button::State,
+	threshold_slider: iced::slider::State,
+	ratio_slider: iced::slider::State,
+	makeup_gain_slider: iced::slider::State,
+}
+
+impl CompressorDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let threshold = -18.0;
+		let ratio = 4.0;
+		let makeup_gain = 6.0;
+		let threshold_parameter_id = audio_manager.add_parameter(threshold)?;
+		let ratio_parameter_id = audio_manager.add_parameter(ratio)?;
+		let makeup_gain_parameter_id = audio_manager.add_parameter(makeup_gain)?;
+		let drum_bus_id = audio_manager.add_sub_track(Default::default())?;
+		// a quick attack catches the front of each hit, and a release
+		// a bit shorter than the gap between hits lets the
+		// compressor recover before the next one
+		let (compressor, gain_reduction_reader) = Compressor::new(
+			CompressorSettings::new()
+				.attack_time(0.002)
+				.release_time(0.1)
+				.threshold(threshold_parameter_id)
+				.ratio(ratio_parameter_id)
+				.makeup_gain(makeup_gain_parameter_id),
+		);
+		audio_manager.add_effect_to_track(drum_bus_id, compressor, Default::default())?;
+		let loop_sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&std::env::current_dir()?.join("assets").join(ASSETS[0]))?
+				.to_sound(PlayableSettings::new().default_loop_start(0.0)),
+		)?;
+		Ok(Self {
+			audio_manager,
+			drum_bus_id,
+			loop_sound_id,
+			instance_id: None,
+			threshold_parameter_id,
+			ratio_parameter_id,
+			makeup_gain_parameter_id,
+			threshold,
+			ratio,
+			makeup_gain,
+			gain_reduction_reader,
+			gain_reduction: 0.0,
+			peak_gain_reduction: 0.0,
+			last_update_time: Instant::now(),
+			screen_wrapper: ScreenWrapper::new("Compressor demo".into(), Message::GoToDemoSelect),
+			play_button: iced::button::State::new(),
+			threshold_slider: iced::slider::State::new(),
+			ratio_slider: iced::slider::State::new(),
+			makeup_gain_slider: iced::slider::State::new(),
+		})
+	}
+
+	fn ratio_text(&self) -> String {
+		if self.ratio >= LIMITER_RATIO {
+			format!("Ratio: {:.1}:1 (limiting)", self.ratio)
+		} else {
+			format!("Ratio: {:.1}:1", self.ratio)
+		}
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		let tween = Some(Tween::linear(0.05));
+		match message {
+			Message::Play if self.instance_id.is_none() => {
+				self.instance_id = Some(self.audio_manager.play(
+					self.loop_sound_id,
+					InstanceSettings::new().track(self.drum_bus_id),
+				)?);
+			}
+			Message::Stop => {
+				if let Some(instance_id) = self.instance_id.take() {
+					self.audio_manager.stop_instance(
+						instance_id,
+						StopInstanceSettings::new().fade_tween(Tween::linear(0.25)),
+					)?;
+				}
+			}
+			Message::SetThreshold(threshold) => {
+				self.threshold = threshold.clamp(MIN_THRESHOLD, 0.0);
+				self.audio_manager.set_parameter(
+					self.threshold_parameter_id,
+					self.threshold,
+					tween,
+				)?;
+			}
+			Message::SetRatio(ratio) => {
+				self.ratio = ratio.clamp(1.0, MAX_RATIO);
+				self.audio_manager
+					.set_parameter(self.ratio_parameter_id, self.ratio, tween)?;
+			}
+			Message::SetMakeupGain(makeup_gain) => {
+				self.makeup_gain = makeup_gain.clamp(0.0, MAX_MAKEUP_GAIN);
+				self.audio_manager.set_parameter(
+					self.makeup_gain_parameter_id,
+					self.makeup_gain,
+					tween,
+				)?;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		let dt = self.last_update_time.elapsed().as_secs_f32();
+		self.last_update_time = Instant::now();
+		self.gain_reduction = self.gain_reduction_reader.gain_reduction();
+		self.peak_gain_reduction = self
+			.gain_reduction
+			.max(self.peak_gain_reduction - PEAK_FALL_SPEED * dt);
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.instance_id.is_some();
+		let ratio_text = self.ratio_text();
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Button::new(
+						&mut self.play_button,
+						Text::new(if playing { "Stop" } else { "Play" })
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(if playing {
+						Message::Stop
+					} else {
+						Message::Play
+					})
+					.width(Length::Units(100))
+					.style(AppStyles),
+				)
+				.push(Text::new(format!(
+					"Gain reduction: {:.1} dB",
+					self.gain_reduction
+				)))
+				.push(GainReductionMeter {
+					gain_reduction: self.gain_reduction,
+					peak: self.peak_gain_reduction,
+					max_gain_reduction: MAX_GAIN_REDUCTION,
+					tick_spacing: 6.0,
+				})
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Threshold: {:.1} dB", self.threshold))
+								.width(Length::Units(200)),
+						)
+						.push(
+							Slider::new(
+								&mut self.threshold_slider,
+								MIN_THRESHOLD..=0.0,
+								self.threshold,
+								Message::SetThreshold,
+							)
+							.step(0.5)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(Text::new(ratio_text).width(Length::Units(200)))
+						.push(
+							Slider::new(
+								&mut self.ratio_slider,
+								1.0..=MAX_RATIO,
+								self.ratio,
+								Message::SetRatio,
+							)
+							.step(0.1)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Makeup gain: {:.1} dB", self.makeup_gain))
+								.width(Length::Units(200)),
+						)
+						.push(
+							Slider::new(
+								&mut self.makeup_gain_slider,
+								0.0..=MAX_MAKEUP_GAIN,
+								self.makeup_gain,
+								Message::SetMakeupGain,
+							)
+							.step(0.5)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for CompressorDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "compressor_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetThreshold(_) => Some("Threshold".into()),
+			Message::SetRatio(_) => Some("Ratio".into()),
+			Message::SetMakeupGain(_) => Some("Makeup gain".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Threshold" => vec![Message::SetThreshold(MIN_THRESHOLD * (1.0 - value))],
+			"Ratio" => vec![Message::SetRatio(1.0 + (MAX_RATIO - 1.0) * value)],
+			"Makeup gain" => vec![Message::SetMakeupGain(MAX_MAKEUP_GAIN * value)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for CompressorDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "compressor_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetThreshold(self.threshold),
+			Message::SetRatio(self.ratio),
+			Message::SetMakeupGain(self.makeup_gain),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for CompressorDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.instance_id.is_some(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		self.instance_id.map(|_| "Compressor demo".into())
+	}
+}
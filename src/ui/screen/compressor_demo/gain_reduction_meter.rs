@@ -0,0 +1,120 @@
+use iced::{mouse::Interaction, Background, Color, Length, Point, Rectangle, Size};
+use iced_graphics::{Backend, Defaults, Primitive, Renderer};
+use iced_native::{
+	layout::{Limits, Node},
+	Element, Layout, Widget,
+};
+
+const WIDTH: u16 = 400;
+const HEIGHT: u16 = 24;
+const TICK_WIDTH: f32 = 1.0;
+const PEAK_WIDTH: f32 = 2.0;
+const BACKGROUND_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.05];
+const TICK_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.2];
+const BAR_COLOR: [f32; 4] = [1.0, 0.6, 0.0, 1.0];
+const PEAK_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.8];
+
+/// Draws how far a compressor is turning the signal down as a
+/// bar growing from the left, with a line where the recent peak
+/// was and a tick at every `tick_spacing` decibels.
+pub struct GainReductionMeter {
+	/// The current gain reduction in decibels.
+	pub gain_reduction: f32,
+	/// The recent peak gain reduction in decibels.
+	pub peak: f32,
+	/// The gain reduction that fills the whole meter.
+	pub max_gain_reduction: f32,
+	pub tick_spacing: f32,
+}
+
+impl GainReductionMeter {
+	fn x(&self, bounds: Rectangle, gain_reduction: f32) -> f32 {
+		bounds.x + bounds.width * (gain_reduction / self.max_gain_reduction).clamp(0.0, 1.0)
+	}
+}
+
+fn quad(bounds: Rectangle, color: [f32; 4]) -> Primitive {
+	Primitive::Quad {
+		bounds,
+		background: Background::Color(color.into()),
+		border_radius: 0.0,
+		border_width: 0.0,
+		border_color: Color::TRANSPARENT,
+	}
+}
+
+impl<Message, B: Backend> Widget<Message, Renderer<B>> for GainReductionMeter {
+	fn width(&self) -> Length {
+		Length::Units(WIDTH)
+	}
+
+	fn height(&self) -> Length {
+		Length::Units(HEIGHT)
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, limits: &Limits) -> Node {
+		Node::new(
+			limits
+				.width(Length::Units(WIDTH))
+				.height(Length::Units(HEIGHT))
+				.resolve(Size::ZERO),
+		)
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		_defaults: &Defaults,
+		layout: Layout<'_>,
+		_cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let mut primitives = vec![
+			quad(bounds, BACKGROUND_COLOR),
+			quad(
+				Rectangle::new(
+					bounds.position(),
+					Size::new(
+						self.x(bounds, self.gain_reduction) - bounds.x,
+						bounds.height,
+					),
+				),
+				BAR_COLOR,
+			),
+		];
+		let mut tick = self.tick_spacing;
+		while tick < self.max_gain_reduction {
+			primitives.push(quad(
+				Rectangle::new(
+					Point::new(self.x(bounds, tick) - TICK_WIDTH / 2.0, bounds.y),
+					Size::new(TICK_WIDTH, bounds.height),
+				),
+				TICK_COLOR,
+			));
+			tick += self.tick_spacing;
+		}
+		if self.peak > 0.0 {
+			primitives.push(quad(
+				Rectangle::new(
+					Point::new(self.x(bounds, self.peak) - PEAK_WIDTH / 2.0, bounds.y),
+					Size::new(PEAK_WIDTH, bounds.height),
+				),
+				PEAK_COLOR,
+			));
+		}
+		(Primitive::Group { primitives }, Interaction::default())
+	}
+
+	fn hash_layout(&self, state: &mut iced_native::Hasher) {
+		use std::hash::Hash;
+		WIDTH.hash(state);
+		HEIGHT.hash(state);
+	}
+}
+
+impl<'a, Message, B: Backend> From<GainReductionMeter> for Element<'a, Message, Renderer<B>> {
+	fn from(meter: GainReductionMeter) -> Self {
+		Element::new(meter)
+	}
+}
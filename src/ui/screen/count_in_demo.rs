@@ -0,0 +1,336 @@
+use std::error::Error;
+
+use iced::{Align, Button, Checkbox, Column, HorizontalAlignment, Length, Text};
+use kira::{
+	instance::{InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::Tween,
+	playable::PlayableSettings,
+	sequence::{EventReceiver, Sequence, SequenceInstanceId},
+	sound::SoundId,
+	AudioResult, Duration,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, sound_cache::SoundCache, synth},
+	ui::{
+		common::{screen_wrapper::ScreenWrapper, transport::Transport},
+		screen::drum_fill_demo::TEMPO,
+		style::AppStyles,
+	},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &["drum fill demo/loop.ogg"];
+
+const BEATS_PER_BAR: usize = 4;
+/// How many clicks play before the music comes in.
+const COUNT_IN: usize = 4;
+
+const EXPLANATION_TEXT: &str = "Press Play and the music is counted \
+in with four clicks, like a drummer clicking their sticks before a \
+song. The clicks and the loop are all in one sequence: it plays a \
+click and emits the count for each beat, waits a beat, and then \
+starts looping the music. Since the numbers come from the same \
+sequence as the clicks, the screen can't show a number the audio \
+hasn't reached yet.
+
+Stopping the music in the middle of a bar sounds like a mistake, \
+so Stop on downbeat doesn't stop it right away. It starts a second \
+sequence that waits for the next whole bar with wait_for_interval, \
+stops the music's sequence and everything it played, and then \
+emits an event so the screen knows the music is over. The count-in \
+is exactly one bar long, so the bars of the metronome line up with \
+the bars of the music.";
+
+/// The events the sequences emit.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum CountInEvent {
+	/// A count-in click (counting from 1).
+	Count(usize),
+	MusicStarted,
+	Stopped,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetMetronomeClick(bool),
+	SetCountIn(bool),
+	StopOnDownbeat,
+}
+
+pub struct CountInDemo {
+	audio_manager: AudioManager,
+	loop_sound_id: SoundId,
+	click_sound_id: SoundId,
+	count_in: bool,
+	music_sequence: Option<(SequenceInstanceId, EventReceiver<CountInEvent>)>,
+	/// The sequence waiting to stop the music on the next
+	/// downbeat.
+	stop_sequence: Option<(SequenceInstanceId, EventReceiver<CountInEvent>)>,
+	/// The last count the music's sequence reached, or `None`
+	/// if it isn't counting in.
+	count: Option<usize>,
+	music_started: bool,
+	/// The metronome beat the music came in on, which is where
+	/// its bars are counted from.
+	music_start_beat: usize,
+	transport: Transport,
+	screen_wrapper: ScreenWrapper<Message>,
+	stop_on_downbeat_button: iced::button::State,
+}
+
+impl CountInDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(AudioManagerSettings {
+			metronome_settings: Transport::metronome_settings(TEMPO),
+			..audio_manager_settings
+		})?;
+		let transport = Transport::new(&mut audio_manager, TEMPO)?;
+		let loop_sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&std::env::current_dir()?.join("assets/drum fill demo/loop.ogg"))?
+				.to_sound(PlayableSettings::new()),
+		)?;
+		let click_sound_id =
+			audio_manager.add_sound(synth::blip(1320.0, 0.05, PlayableSettings::new()))?;
+		Ok(Self {
+			audio_manager,
+			loop_sound_id,
+			click_sound_id,
+			count_in: true,
+			music_sequence: None,
+			stop_sequence: None,
+			count: None,
+			music_started: false,
+			music_start_beat: 0,
+			transport,
+			screen_wrapper: ScreenWrapper::new("Count-in demo".into(), Message::GoToDemoSelect),
+			stop_on_downbeat_button: iced::button::State::new(),
+		})
+	}
+
+	fn start_music_sequence(
+		&mut self,
+	) -> AudioResult<(SequenceInstanceId, EventReceiver<CountInEvent>)> {
+		let mut sequence = Sequence::new(Default::default());
+		sequence.wait_for_interval(1.0);
+		self.music_start_beat = if self.count_in { COUNT_IN } else { 0 };
+		if self.count_in {
+			for count in 1..=COUNT_IN {
+				// the first click is higher so it's clear where
+				// the bar starts
+				let pitch = if count == 1 { 1.5 } else { 1.0 };
+				sequence.emit(CountInEvent::Count(count));
+				sequence.play(self.click_sound_id, InstanceSettings::new().pitch(pitch));
+				sequence.wait(Duration::Beats(1.0));
+			}
+		}
+		sequence.emit(CountInEvent::MusicStarted);
+		sequence.start_loop();
+		sequence.play(self.loop_sound_id, Default::default());
+		sequence.wait(Duration::Beats(BEATS_PER_BAR as f64));
+		self.audio_manager
+			.start_sequence(sequence, Default::default())
+	}
+
+	fn stop_on_downbeat(&mut self) -> AudioResult<()> {
+		let music_sequence_id = match &self.music_sequence {
+			Some((sequence_id, _)) => *sequence_id,
+			None => return Ok(()),
+		};
+		let mut sequence = Sequence::new(Default::default());
+		sequence.wait_for_interval(BEATS_PER_BAR as f64);
+		// a very short fade keeps the cut from clicking without
+		// letting the next bar's first hit through
+		sequence.stop_sequence_and_instances(
+			music_sequence_id,
+			StopInstanceSettings::new().fade_tween(Tween::linear(0.01)),
+		);
+		sequence.emit(CountInEvent::Stopped);
+		self.stop_sequence = Some(
+			self.audio_manager
+				.start_sequence(sequence, Default::default())?,
+		);
+		Ok(())
+	}
+
+	fn stop(&mut self) -> AudioResult<()> {
+		if let Some((sequence_id, _)) = self.music_sequence.take() {
+			self.audio_manager
+				.stop_sequence_and_instances(sequence_id, Default::default())?;
+		}
+		if let Some((sequence_id, _)) = self.stop_sequence.take() {
+			self.audio_manager.stop_sequence(sequence_id)?;
+		}
+		self.count = None;
+		self.music_started = false;
+		self.transport.stop(&mut self.audio_manager)
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if self.music_sequence.is_none() => {
+				self.music_sequence = Some(self.start_music_sequence()?);
+				self.transport.start(&mut self.audio_manager)?;
+			}
+			Message::Stop => self.stop()?,
+			Message::SetMetronomeClick(enabled) => {
+				self.transport
+					.set_click_enabled(&mut self.audio_manager, enabled)?;
+			}
+			Message::SetCountIn(count_in) => self.count_in = count_in,
+			Message::StopOnDownbeat if self.music_started && self.stop_sequence.is_none() => {
+				self.stop_on_downbeat()?;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		self.transport.check_for_events(&mut self.audio_manager);
+		if let Some((_, event_receiver)) = &mut self.music_sequence {
+			while let Some(event) = event_receiver.pop().copied() {
+				match event {
+					CountInEvent::Count(count) => self.count = Some(count),
+					CountInEvent::MusicStarted => {
+						self.count = None;
+						self.music_started = true;
+					}
+					CountInEvent::Stopped => {}
+				}
+			}
+		}
+		let mut stopped = false;
+		if let Some((_, event_receiver)) = &mut self.stop_sequence {
+			while let Some(event) = event_receiver.pop().copied() {
+				stopped |= event == CountInEvent::Stopped;
+			}
+		}
+		if stopped {
+			self.stop()?;
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let count_text = match (self.count, self.music_started) {
+			(Some(count), _) => count.to_string(),
+			(None, true) => "Go!".into(),
+			(None, false) => "".into(),
+		};
+		let status_text = match self.transport.beat_position() {
+			Some(position) if self.music_started => {
+				let music_position = (position - self.music_start_beat as f64).max(0.0);
+				let bar = music_position as usize / BEATS_PER_BAR + 1;
+				let beats_left = BEATS_PER_BAR as f64 - music_position % BEATS_PER_BAR as f64;
+				if self.stop_sequence.is_some() {
+					format!("Bar {} - stopping in {:.2} beats", bar, beats_left)
+				} else {
+					format!("Bar {} - next downbeat in {:.2} beats", bar, beats_left)
+				}
+			}
+			Some(_) => "Counting in...".into(),
+			None => "Press Play to count in the music".into(),
+		};
+		let mut stop_on_downbeat_button = Button::new(
+			&mut self.stop_on_downbeat_button,
+			Text::new("Stop on downbeat")
+				.width(Length::Fill)
+				.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.width(Length::Units(200))
+		.style(AppStyles);
+		if self.music_started && self.stop_sequence.is_none() {
+			stop_on_downbeat_button = stop_on_downbeat_button.on_press(Message::StopOnDownbeat);
+		}
+		self.screen_wrapper.view_with_transport(
+			self.transport
+				.view(Message::Play, Message::Stop, Message::SetMetronomeClick),
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(Checkbox::new(
+					self.count_in,
+					"Count in before the music",
+					Message::SetCountIn,
+				))
+				.push(
+					Text::new(count_text)
+						.size(96)
+						.height(Length::Units(120))
+						.horizontal_alignment(HorizontalAlignment::Center),
+				)
+				.push(Text::new(status_text))
+				.push(stop_on_downbeat_button)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for CountInDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "count_in_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetCountIn(_) => Some("Count in".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Count in" => vec![Message::SetCountIn(value >= 0.5)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for CountInDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "count_in_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetMetronomeClick(self.transport.click_enabled()),
+			Message::SetCountIn(self.count_in),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for CountInDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.transport.is_playing(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if !self.transport.is_playing() {
+			return None;
+		}
+		Some("Count-in demo".into())
+	}
+}
@@ -0,0 +1,371 @@
+use std::{error::Error, time::Instant};
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Radio, Row, Slider, Text};
+use kira::{
+	arrangement::{Arrangement, ArrangementId},
+	instance::{InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::{Mapping, ParameterId, Tween},
+	playable::PlayableSettings,
+	sequence::{Sequence, SequenceInstanceId},
+	Tempo, Value,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, sound_cache::SoundCache},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &[
+	"drum fill demo/loop.ogg",
+	"underwater demo/drums.ogg",
+	"underwater demo/bass.ogg",
+	"underwater demo/pad.ogg",
+	"underwater demo/lead.ogg",
+];
+
+const EXPLANATION_TEXT: &str = "This demo crossfades between \
+two songs with a single parameter. Every instance of the first \
+song has its volume mapped from 1 down to 0 as the parameter \
+goes from 0 to 1, and every instance of the second song has \
+the opposite mapping, so moving the parameter fades one song \
+out while the other fades in.
+
+The slider sets the parameter directly. The transition button \
+tweens it to the other end over the chosen duration. Both songs \
+keep playing the whole time, so fading back picks up wherever \
+the other song is now.";
+
+const DRUM_LOOP_TEMPO: Tempo = Tempo(128.0);
+const UNDERWATER_TEMPO: Tempo = Tempo(85.0);
+const SONG_NAMES: [&str; 2] = ["Drum loop", "Underwater"];
+/// The transition lengths the user can pick from (in seconds).
+const FADE_DURATIONS: [f64; 4] = [1.0, 2.0, 4.0, 8.0];
+const DEFAULT_FADE_DURATION_INDEX: usize = 2;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	/// Sets how far the crossfade is towards the second song
+	/// (from 0 to 1).
+	SetCrossfade(f64),
+	SetFadeDuration(usize),
+	Transition,
+}
+
+/// An automatic crossfade in progress, which the slider follows.
+struct Transition {
+	start_time: Instant,
+	from: f64,
+	to: f64,
+	duration: f64,
+}
+
+impl Transition {
+	fn position(&self) -> f64 {
+		let progress = (self.start_time.elapsed().as_secs_f64() / self.duration).min(1.0);
+		self.from + (self.to - self.from) * progress
+	}
+
+	fn finished(&self) -> bool {
+		self.start_time.elapsed().as_secs_f64() >= self.duration
+	}
+}
+
+pub struct CrossfadeDemo {
+	audio_manager: AudioManager,
+	drum_loop_id: ArrangementId,
+	underwater_loop_ids: Vec<ArrangementId>,
+	crossfade_parameter_id: ParameterId,
+	sequence_id: Option<SequenceInstanceId>,
+	crossfade: f64,
+	fade_duration_index: usize,
+	transition: Option<Transition>,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_button: iced::button::State,
+	transition_button: iced::button::State,
+	crossfade_slider: iced::slider::State,
+}
+
+impl CrossfadeDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let crossfade_parameter_id = audio_manager.add_parameter(0.0)?;
+		let assets_dir = std::env::current_dir()?.join("assets");
+		let drum_loop_sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&assets_dir.join("drum fill demo/loop.ogg"))?
+				.to_sound(
+					PlayableSettings::new()
+						.semantic_duration(DRUM_LOOP_TEMPO.beats_to_seconds(4.0)),
+				),
+		)?;
+		let drum_loop_id = audio_manager.add_arrangement(Arrangement::new_loop(
+			drum_loop_sound_id,
+			Default::default(),
+		))?;
+		let mut underwater_loop_ids = vec![];
+		for stem in &["drums.ogg", "bass.ogg", "pad.ogg", "lead.ogg"] {
+			let sound_id = audio_manager.add_sound(
+				sound_cache
+					.get(&assets_dir.join("underwater demo").join(stem))?
+					.to_sound(
+						PlayableSettings::new()
+							.semantic_duration(UNDERWATER_TEMPO.beats_to_seconds(16.0)),
+					),
+			)?;
+			underwater_loop_ids.push(
+				audio_manager
+					.add_arrangement(Arrangement::new_loop(sound_id, Default::default()))?,
+			);
+		}
+		Ok(Self {
+			audio_manager,
+			drum_loop_id,
+			underwater_loop_ids,
+			crossfade_parameter_id,
+			sequence_id: None,
+			crossfade: 0.0,
+			fade_duration_index: DEFAULT_FADE_DURATION_INDEX,
+			transition: None,
+			screen_wrapper: ScreenWrapper::new("Crossfade demo".into(), Message::GoToDemoSelect),
+			play_button: iced::button::State::new(),
+			transition_button: iced::button::State::new(),
+			crossfade_slider: iced::slider::State::new(),
+		})
+	}
+
+	/// The volume for the song on one side of the crossfade.
+	fn volume(&self, song_index: usize) -> Value<f64> {
+		Value::Parameter(
+			self.crossfade_parameter_id,
+			Mapping {
+				input_range: (0.0, 1.0),
+				output_range: match song_index {
+					0 => (1.0, 0.0),
+					_ => (0.0, 1.0),
+				},
+				..Default::default()
+			},
+		)
+	}
+
+	fn play(&mut self) -> Result<(), Box<dyn Error>> {
+		// both songs start together in one sequence, so the
+		// underwater stems stay in sync with each other
+		let mut sequence = Sequence::<()>::new(Default::default());
+		sequence.play(
+			self.drum_loop_id,
+			InstanceSettings::new().volume(self.volume(0)),
+		);
+		for loop_id in &self.underwater_loop_ids {
+			sequence.play(*loop_id, InstanceSettings::new().volume(self.volume(1)));
+		}
+		let (sequence_id, _) = self
+			.audio_manager
+			.start_sequence(sequence, Default::default())?;
+		self.sequence_id = Some(sequence_id);
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if self.sequence_id.is_none() => self.play()?,
+			Message::Stop => {
+				if let Some(sequence_id) = self.sequence_id.take() {
+					self.audio_manager.stop_sequence_and_instances(
+						sequence_id,
+						StopInstanceSettings::new().fade_tween(Tween::linear(1.0)),
+					)?;
+				}
+			}
+			Message::SetCrossfade(crossfade) => {
+				let crossfade = crossfade.clamp(0.0, 1.0);
+				// a short tween keeps sudden jumps from clicking
+				self.audio_manager.set_parameter(
+					self.crossfade_parameter_id,
+					crossfade,
+					Some(0.05.into()),
+				)?;
+				self.crossfade = crossfade;
+				self.transition = None;
+			}
+			Message::SetFadeDuration(index) => {
+				self.fade_duration_index = index.min(FADE_DURATIONS.len() - 1);
+			}
+			Message::Transition => {
+				// head for whichever song is quieter right now
+				let to = if self.crossfade < 0.5 { 1.0 } else { 0.0 };
+				let duration = FADE_DURATIONS[self.fade_duration_index];
+				self.audio_manager.set_parameter(
+					self.crossfade_parameter_id,
+					to,
+					Some(Tween::linear(duration)),
+				)?;
+				self.transition = Some(Transition {
+					start_time: Instant::now(),
+					from: self.crossfade,
+					to,
+					duration,
+				});
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		if let Some(transition) = &self.transition {
+			self.crossfade = transition.position();
+			if transition.finished() {
+				self.transition = None;
+			}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.sequence_id.is_some();
+		let play_button = Button::new(
+			&mut self.play_button,
+			Text::new(if playing { "Stop" } else { "Play" })
+				.width(Length::Fill)
+				.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.on_press(if playing {
+			Message::Stop
+		} else {
+			Message::Play
+		})
+		.width(Length::Units(120))
+		.style(AppStyles);
+
+		let crossfade_slider = Row::new()
+			.spacing(16)
+			.max_width(600)
+			.align_items(Align::Center)
+			.push(Text::new(SONG_NAMES[0]))
+			.push(
+				Slider::new(
+					&mut self.crossfade_slider,
+					0.0..=1.0,
+					self.crossfade,
+					Message::SetCrossfade,
+				)
+				.step(0.01)
+				.style(AppStyles),
+			)
+			.push(Text::new(SONG_NAMES[1]));
+
+		let fade_duration_index = self.fade_duration_index;
+		let fade_duration_radios = FADE_DURATIONS.iter().enumerate().fold(
+			Row::new().spacing(16).align_items(Align::Center),
+			|row, (index, duration)| {
+				row.push(Radio::new(
+					index,
+					format!("{}s", duration),
+					Some(fade_duration_index),
+					Message::SetFadeDuration,
+				))
+			},
+		);
+		let transition_button = Button::new(
+			&mut self.transition_button,
+			Text::new(if self.crossfade < 0.5 {
+				format!("Transition to {}", SONG_NAMES[1])
+			} else {
+				format!("Transition to {}", SONG_NAMES[0])
+			})
+			.width(Length::Fill)
+			.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.on_press(Message::Transition)
+		.width(Length::Units(280))
+		.style(AppStyles);
+
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(play_button)
+				.push(crossfade_slider)
+				.push(
+					Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(Text::new("Fade duration:"))
+						.push(fade_duration_radios),
+				)
+				.push(transition_button)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for CrossfadeDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "crossfade_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetCrossfade(_) => Some("Crossfade".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Crossfade" => vec![Message::SetCrossfade(value)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for CrossfadeDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "crossfade_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetCrossfade(self.crossfade),
+			Message::SetFadeDuration(self.fade_duration_index),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for CrossfadeDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.sequence_id.is_some(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		self.sequence_id?;
+		Some(if self.crossfade < 0.5 {
+			SONG_NAMES[0].into()
+		} else {
+			SONG_NAMES[1].into()
+		})
+	}
+}
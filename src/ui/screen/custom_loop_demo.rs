@@ -0,0 +1,422 @@
+use std::{error::Error, path::PathBuf};
+
+use iced::{Align, Button, Column, Length, Row, Text, TextInput};
+use kira::{
+	arrangement::{Arrangement, ArrangementId},
+	instance::{InstanceId, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::Tween,
+	playable::PlayableSettings,
+	sound::SoundId,
+	Tempo,
+};
+
+use crate::{
+	audio::{
+		create_audio_manager,
+		decoded_sound::DecodedSound,
+		tempo_detection::{estimate_tempo, TempoEstimate},
+	},
+	preferences::CustomLoop,
+	ui::{
+		common::{screen_wrapper::ScreenWrapper, transport::Transport, waveform::Waveform},
+		style::AppStyles,
+	},
+};
+
+const EXPLANATION_TEXT: &str = "Drop an .ogg loop onto the window \
+(or type its path) to hear it synced to the metronome. The demo \
+guesses the loop's tempo and how many bars long it is, which sets \
+the sound's semantic_duration - the point where the loop restarts, \
+even if the file has a reverb tail after it.
+
+Beat detection often lands on half or double the real tempo, so \
+listen with the metronome on and correct the guess before using \
+it. Confirmed loops are remembered between runs.";
+
+const DEFAULT_TEMPO: Tempo = Tempo(120.0);
+const BEATS_PER_BAR: usize = 4;
+const NUM_PEAKS: usize = 200;
+const LOOP_END_COLOR: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+
+#[derive(Debug, Clone)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetMetronomeClick(bool),
+	SetPath(String),
+	Open,
+	ChangeTempo(f64),
+	HalveTempo,
+	DoubleTempo,
+	ChangeBars(isize),
+	Confirm,
+}
+
+struct LoadedLoop {
+	path: PathBuf,
+	sound: DecodedSound,
+	peaks: Vec<f32>,
+	detected: Option<TempoEstimate>,
+	tempo: Tempo,
+	bars: usize,
+}
+
+impl LoadedLoop {
+	fn semantic_duration(&self) -> f64 {
+		self.tempo
+			.beats_to_seconds((self.bars * BEATS_PER_BAR) as f64)
+	}
+}
+
+struct Playback {
+	sound_id: SoundId,
+	arrangement_id: ArrangementId,
+	instance_id: InstanceId,
+}
+
+pub struct CustomLoopDemo {
+	audio_manager: AudioManager,
+	path_text: String,
+	loaded: Option<LoadedLoop>,
+	confirmed: Option<CustomLoop>,
+	playback: Option<Playback>,
+	transport: Transport,
+	screen_wrapper: ScreenWrapper<Message>,
+	path_input: iced::text_input::State,
+	open_button: iced::button::State,
+	tempo_buttons: [iced::button::State; 4],
+	bar_buttons: [iced::button::State; 2],
+	confirm_button: iced::button::State,
+}
+
+impl CustomLoopDemo {
+	/// Opens the screen with the loop that was confirmed last
+	/// time, if there is one.
+	pub fn new(
+		audio_manager_settings: AudioManagerSettings,
+		custom_loop: Option<CustomLoop>,
+	) -> Result<Self, Box<dyn Error>> {
+		let tempo = custom_loop
+			.as_ref()
+			.map_or(DEFAULT_TEMPO, |custom_loop| Tempo(custom_loop.bpm));
+		let mut audio_manager = create_audio_manager(AudioManagerSettings {
+			metronome_settings: Transport::metronome_settings(tempo),
+			..audio_manager_settings
+		})?;
+		let transport = Transport::new(&mut audio_manager, tempo)?;
+		let mut demo = Self {
+			audio_manager,
+			path_text: String::new(),
+			loaded: None,
+			confirmed: None,
+			playback: None,
+			transport,
+			screen_wrapper: ScreenWrapper::new("Custom loop demo".into(), Message::GoToDemoSelect),
+			path_input: iced::text_input::State::new(),
+			open_button: iced::button::State::new(),
+			tempo_buttons: Default::default(),
+			bar_buttons: Default::default(),
+			confirm_button: iced::button::State::new(),
+		};
+		// if the loop has been moved or deleted since, the screen
+		// just starts empty
+		if let Some(custom_loop) = custom_loop {
+			if demo.open(custom_loop.path.clone()).is_ok() {
+				if let Some(loaded) = &mut demo.loaded {
+					loaded.tempo = Tempo(custom_loop.bpm);
+					loaded.bars = custom_loop.bars;
+				}
+				demo.transport
+					.set_tempo(&mut demo.audio_manager, Tempo(custom_loop.bpm))?;
+				demo.confirmed = Some(custom_loop);
+			}
+		}
+		Ok(demo)
+	}
+
+	/// The loop the user confirmed the tempo of, which should
+	/// be saved to the preferences.
+	pub fn custom_loop(&self) -> Option<&CustomLoop> {
+		self.confirmed.as_ref()
+	}
+
+	/// Loads a loop and guesses its tempo.
+	pub fn open(&mut self, path: PathBuf) -> Result<(), Box<dyn Error>> {
+		self.stop()?;
+		let sound = DecodedSound::from_ogg_file(&path)?;
+		let detected = estimate_tempo(&sound);
+		let (tempo, bars) = match detected {
+			Some(estimate) => (estimate.tempo, estimate.bars),
+			None => (DEFAULT_TEMPO, 1),
+		};
+		self.path_text = path.to_string_lossy().into_owned();
+		self.loaded = Some(LoadedLoop {
+			path,
+			peaks: sound.peaks(NUM_PEAKS),
+			sound,
+			detected,
+			tempo,
+			bars,
+		});
+		self.transport.set_tempo(&mut self.audio_manager, tempo)?;
+		Ok(())
+	}
+
+	fn play(&mut self) -> Result<(), Box<dyn Error>> {
+		let loaded = match &self.loaded {
+			Some(loaded) => loaded,
+			None => return Ok(()),
+		};
+		let sound_id = self.audio_manager.add_sound(
+			loaded
+				.sound
+				.to_sound(PlayableSettings::new().semantic_duration(loaded.semantic_duration())),
+		)?;
+		let arrangement_id = self
+			.audio_manager
+			.add_arrangement(Arrangement::new_loop(sound_id, Default::default()))?;
+		let instance_id = self
+			.audio_manager
+			.play(arrangement_id, Default::default())?;
+		self.playback = Some(Playback {
+			sound_id,
+			arrangement_id,
+			instance_id,
+		});
+		self.transport.start(&mut self.audio_manager)?;
+		Ok(())
+	}
+
+	fn stop(&mut self) -> Result<(), Box<dyn Error>> {
+		if let Some(playback) = self.playback.take() {
+			self.audio_manager.stop_instance(
+				playback.instance_id,
+				StopInstanceSettings::new().fade_tween(Tween::linear(0.05)),
+			)?;
+			self.audio_manager
+				.remove_arrangement(playback.arrangement_id)?;
+			self.audio_manager.remove_sound(playback.sound_id)?;
+			self.transport.stop(&mut self.audio_manager)?;
+		}
+		Ok(())
+	}
+
+	/// Changes the guess and restarts the loop (if it's playing)
+	/// so the change can be heard right away.
+	fn edit(&mut self, edit: impl FnOnce(&mut LoadedLoop)) -> Result<(), Box<dyn Error>> {
+		let loaded = match &mut self.loaded {
+			Some(loaded) => loaded,
+			None => return Ok(()),
+		};
+		edit(loaded);
+		let tempo = loaded.tempo;
+		let playing = self.playback.is_some();
+		self.stop()?;
+		self.transport.set_tempo(&mut self.audio_manager, tempo)?;
+		if playing {
+			self.play()?;
+		}
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play => self.play()?,
+			Message::Stop => self.stop()?,
+			Message::SetMetronomeClick(enabled) => {
+				self.transport
+					.set_click_enabled(&mut self.audio_manager, enabled)?;
+			}
+			Message::SetPath(path_text) => self.path_text = path_text,
+			Message::Open => self.open(PathBuf::from(self.path_text.trim()))?,
+			Message::ChangeTempo(amount) => self.edit(|loaded| {
+				loaded.tempo = Tempo((loaded.tempo.0 + amount).max(1.0));
+			})?,
+			Message::HalveTempo => self.edit(|loaded| {
+				loaded.tempo = Tempo(loaded.tempo.0 / 2.0);
+				loaded.bars = (loaded.bars / 2).max(1);
+			})?,
+			Message::DoubleTempo => self.edit(|loaded| {
+				loaded.tempo = Tempo(loaded.tempo.0 * 2.0);
+				loaded.bars *= 2;
+			})?,
+			Message::ChangeBars(amount) => self.edit(|loaded| {
+				loaded.bars = (loaded.bars as isize + amount).max(1) as usize;
+			})?,
+			Message::Confirm => {
+				if let Some(loaded) = &self.loaded {
+					self.confirmed = Some(CustomLoop {
+						path: loaded.path.clone(),
+						bpm: loaded.tempo.0,
+						bars: loaded.bars,
+					});
+				}
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		self.transport.check_for_events(&mut self.audio_manager);
+		Ok(())
+	}
+
+	fn small_button<'a>(
+		state: &'a mut iced::button::State,
+		label: &str,
+		message: Message,
+	) -> Button<'a, Message> {
+		Button::new(state, Text::new(label))
+			.on_press(message)
+			.style(AppStyles)
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let path_row = Row::new()
+			.spacing(16)
+			.align_items(Align::Center)
+			.max_width(600)
+			.push(
+				TextInput::new(
+					&mut self.path_input,
+					"Path to an .ogg file",
+					&self.path_text,
+					Message::SetPath,
+				)
+				.on_submit(Message::Open)
+				.padding(8),
+			)
+			.push(Self::small_button(
+				&mut self.open_button,
+				"Open",
+				Message::Open,
+			));
+		let mut contents = Column::new()
+			.spacing(16)
+			.align_items(Align::Center)
+			.push(path_row);
+
+		if let Some(loaded) = &self.loaded {
+			let duration = loaded.sound.duration();
+			let semantic_duration = loaded.semantic_duration();
+			let beats = (semantic_duration / loaded.tempo.beats_to_seconds(1.0)).round() as usize;
+			let waveform = Waveform::new(&loaded.peaks)
+				.grid_lines(
+					(0..=beats)
+						.map(|beat| (loaded.tempo.beats_to_seconds(beat as f64) / duration) as f32)
+						.collect(),
+				)
+				.marker((semantic_duration / duration) as f32, LOOP_END_COLOR);
+			let detected_text = match loaded.detected {
+				Some(estimate) => format!(
+					"Detected {:.1} BPM, {} bars",
+					estimate.tempo.0, estimate.bars
+				),
+				None => "The loop is too short to detect its tempo".into(),
+			};
+			let confirmed = self.confirmed.as_ref().is_some_and(|custom_loop| {
+				custom_loop.path == loaded.path
+					&& custom_loop.bpm == loaded.tempo.0
+					&& custom_loop.bars == loaded.bars
+			});
+			let [halve_button, minus_button, plus_button, double_button] = &mut self.tempo_buttons;
+			let [remove_bar_button, add_bar_button] = &mut self.bar_buttons;
+			let mut confirm_button = Button::new(
+				&mut self.confirm_button,
+				Text::new(if confirmed { "Confirmed" } else { "Confirm" }),
+			)
+			.style(AppStyles);
+			if !confirmed {
+				confirm_button = confirm_button.on_press(Message::Confirm);
+			}
+			contents = contents
+				.push(waveform)
+				.push(Text::new(detected_text).size(16))
+				.push(
+					Row::new()
+						.spacing(8)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("{:.1} BPM", loaded.tempo.0))
+								.width(Length::Units(100)),
+						)
+						.push(Self::small_button(
+							halve_button,
+							"Halve",
+							Message::HalveTempo,
+						))
+						.push(Self::small_button(
+							minus_button,
+							"-1",
+							Message::ChangeTempo(-1.0),
+						))
+						.push(Self::small_button(
+							plus_button,
+							"+1",
+							Message::ChangeTempo(1.0),
+						))
+						.push(Self::small_button(
+							double_button,
+							"Double",
+							Message::DoubleTempo,
+						))
+						.push(Text::new(format!("{} bars", loaded.bars)).width(Length::Units(80)))
+						.push(Self::small_button(
+							remove_bar_button,
+							"-",
+							Message::ChangeBars(-1),
+						))
+						.push(Self::small_button(
+							add_bar_button,
+							"+",
+							Message::ChangeBars(1),
+						))
+						.push(confirm_button),
+				)
+				.push(Text::new(format!(
+					"semantic_duration: {:.3}s (the file is {:.3}s long)",
+					semantic_duration, duration
+				)));
+		}
+
+		contents = contents.push(
+			Column::new()
+				.width(Length::Fill)
+				.max_width(600)
+				.push(Text::new(EXPLANATION_TEXT)),
+		);
+		if self.loaded.is_none() {
+			return self.screen_wrapper.view(contents);
+		}
+		self.screen_wrapper.view_with_transport(
+			self.transport
+				.view(Message::Play, Message::Stop, Message::SetMetronomeClick),
+			contents,
+		)
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for CustomLoopDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.transport.is_playing(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if !self.transport.is_playing() {
+			return None;
+		}
+		let loaded = self.loaded.as_ref()?;
+		let file_name = loaded.path.file_name()?.to_string_lossy();
+		Some(format!("{} (custom loop demo)", file_name))
+	}
+}
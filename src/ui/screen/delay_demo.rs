@@ -0,0 +1,431 @@
+use std::error::Error;
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Radio, Row, Slider, Text};
+use kira::{
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::{ParameterId, Tween},
+	playable::PlayableSettings,
+	sequence::{Sequence, SequenceInstanceId},
+	sound::SoundId,
+	Duration, Tempo,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{
+		create_audio_manager,
+		effect::delay::{Delay, DelaySettings},
+		synth,
+		wet_dry::WetDry,
+	},
+	ui::{
+		common::{
+			screen_wrapper::ScreenWrapper, transport::Transport, wet_dry_control::WetDryControl,
+		},
+		style::AppStyles,
+	},
+};
+
+const EXPLANATION_TEXT: &str = "Press play to hear a short \
+phrase of blips repeated every bar, or press the blip button to \
+play one whenever you like. Each blip is played on a dry track \
+and on a track with a delay on it, and bypassing fades out the \
+delayed copy so you can hear the phrase on its own.
+
+The delay time is a parameter, and the screen sets it to the \
+length of the chosen note at the metronome's tempo, so the \
+echoes land on the beat. Changing the tempo changes the \
+metronome and the delay time together. The delay reads between \
+samples, so the parameter can be tweened without clicks, but \
+you'll hear the echoes bend in pitch while it moves.";
+
+/// The tempo the metronome starts at.
+const TEMPO: Tempo = Tempo(120.0);
+const MIN_TEMPO: f64 = 60.0;
+const MAX_TEMPO: f64 = 180.0;
+const INITIAL_DIVISION: Division = Division::DottedEighth;
+const INITIAL_FEEDBACK: f64 = 0.45;
+/// Past this the echoes take a very long time to die out.
+const MAX_FEEDBACK: f64 = 0.9;
+const INITIAL_MIX: f64 = 0.5;
+const DELAY_TIME_TWEEN_DURATION: f64 = 0.1;
+
+/// The note lengths the delay time can be synced to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Division {
+	Sixteenth,
+	Eighth,
+	DottedEighth,
+	Quarter,
+	DottedQuarter,
+	Half,
+}
+
+impl Division {
+	const ALL: [Self; 6] = [
+		Self::Sixteenth,
+		Self::Eighth,
+		Self::DottedEighth,
+		Self::Quarter,
+		Self::DottedQuarter,
+		Self::Half,
+	];
+
+	fn beats(self) -> f64 {
+		match self {
+			Self::Sixteenth => 0.25,
+			Self::Eighth => 0.5,
+			Self::DottedEighth => 0.75,
+			Self::Quarter => 1.0,
+			Self::DottedQuarter => 1.5,
+			Self::Half => 2.0,
+		}
+	}
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::Sixteenth => "1/16",
+			Self::Eighth => "1/8",
+			Self::DottedEighth => "Dotted 1/8",
+			Self::Quarter => "1/4",
+			Self::DottedQuarter => "Dotted 1/4",
+			Self::Half => "1/2",
+		}
+	}
+}
+
+/// The longest the delay time can get: a half note at the
+/// slowest tempo.
+fn max_delay_time() -> f64 {
+	Tempo(MIN_TEMPO).beats_to_seconds(Division::Half.beats())
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetMetronomeClick(bool),
+	PlayBlip,
+	SetTempo(f64),
+	SetDivision(Division),
+	SetFeedback(f64),
+	SetMix(f64),
+	SetBypassed(bool),
+}
+
+pub struct DelayDemo {
+	audio_manager: AudioManager,
+	wet_dry: WetDry,
+	delay_time_parameter_id: ParameterId,
+	feedback_parameter_id: ParameterId,
+	high_blip_sound_id: SoundId,
+	low_blip_sound_id: SoundId,
+	phrase_sequence_id: Option<SequenceInstanceId>,
+	tempo: f64,
+	division: Division,
+	feedback: f64,
+	transport: Transport,
+	screen_wrapper: ScreenWrapper<Message>,
+	wet_dry_control: WetDryControl<Message>,
+	blip_button: iced::button::State,
+	tempo_slider: iced::slider::State,
+	feedback_slider: iced::slider::State,
+}
+
+impl DelayDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(AudioManagerSettings {
+			metronome_settings: Transport::metronome_settings(TEMPO),
+			..audio_manager_settings
+		})?;
+		let transport = Transport::new(&mut audio_manager, TEMPO)?;
+		let wet_dry = WetDry::new(&mut audio_manager, INITIAL_MIX)?;
+		let delay_time_parameter_id =
+			audio_manager.add_parameter(TEMPO.beats_to_seconds(INITIAL_DIVISION.beats()))?;
+		let feedback_parameter_id = audio_manager.add_parameter(INITIAL_FEEDBACK)?;
+		audio_manager.add_effect_to_track(
+			wet_dry.wet_track_id(),
+			Delay::new(
+				DelaySettings::new()
+					.max_delay_time(max_delay_time())
+					.delay_time(delay_time_parameter_id)
+					.feedback(feedback_parameter_id),
+			),
+			Default::default(),
+		)?;
+		// WetDry plays every sound twice at the same time,
+		// which a cooldown would block
+		let blip_settings = || PlayableSettings {
+			cooldown: None,
+			..Default::default()
+		};
+		let high_blip_sound_id =
+			audio_manager.add_sound(synth::blip(880.0, 0.1, blip_settings()))?;
+		let low_blip_sound_id =
+			audio_manager.add_sound(synth::blip(587.33, 0.1, blip_settings()))?;
+		Ok(Self {
+			audio_manager,
+			wet_dry,
+			delay_time_parameter_id,
+			feedback_parameter_id,
+			high_blip_sound_id,
+			low_blip_sound_id,
+			phrase_sequence_id: None,
+			tempo: TEMPO.0,
+			division: INITIAL_DIVISION,
+			feedback: INITIAL_FEEDBACK,
+			transport,
+			screen_wrapper: ScreenWrapper::new("Delay demo".into(), Message::GoToDemoSelect),
+			wet_dry_control: WetDryControl::new(Message::SetMix, Message::SetBypassed),
+			blip_button: iced::button::State::new(),
+			tempo_slider: iced::slider::State::new(),
+			feedback_slider: iced::slider::State::new(),
+		})
+	}
+
+	/// Starts a sequence that plays two blips at the start of
+	/// every bar, leaving the rest of the bar for the echoes.
+	fn start_phrase(&mut self) -> Result<(), Box<dyn Error>> {
+		let mut sequence = Sequence::<()>::new(Default::default());
+		sequence.wait_for_interval(1.0);
+		sequence.start_loop();
+		self.wet_dry
+			.play_in_sequence(&mut sequence, self.high_blip_sound_id, Default::default());
+		sequence.wait(Duration::Beats(0.5));
+		self.wet_dry
+			.play_in_sequence(&mut sequence, self.low_blip_sound_id, Default::default());
+		sequence.wait(Duration::Beats(3.5));
+		let (sequence_id, _) = self
+			.audio_manager
+			.start_sequence(sequence, Default::default())?;
+		self.phrase_sequence_id = Some(sequence_id);
+		Ok(())
+	}
+
+	/// Points the delay time at the chosen note length, which
+	/// depends on both the division and the tempo.
+	fn update_delay_time(&mut self) -> Result<(), Box<dyn Error>> {
+		self.audio_manager.set_parameter(
+			self.delay_time_parameter_id,
+			Tempo(self.tempo).beats_to_seconds(self.division.beats()),
+			Some(Tween::linear(DELAY_TIME_TWEEN_DURATION)),
+		)?;
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if self.phrase_sequence_id.is_none() => {
+				self.start_phrase()?;
+				self.transport.start(&mut self.audio_manager)?;
+			}
+			Message::Stop => {
+				// the echoes are left to ring out
+				if let Some(sequence_id) = self.phrase_sequence_id.take() {
+					self.audio_manager.stop_sequence(sequence_id)?;
+				}
+				self.transport.stop(&mut self.audio_manager)?;
+			}
+			Message::SetMetronomeClick(enabled) => {
+				self.transport
+					.set_click_enabled(&mut self.audio_manager, enabled)?;
+			}
+			Message::PlayBlip => {
+				self.wet_dry.play(
+					&mut self.audio_manager,
+					self.high_blip_sound_id,
+					Default::default(),
+				)?;
+			}
+			Message::SetTempo(tempo) => {
+				self.tempo = tempo.clamp(MIN_TEMPO, MAX_TEMPO).round();
+				self.transport
+					.set_tempo(&mut self.audio_manager, Tempo(self.tempo))?;
+				self.update_delay_time()?;
+			}
+			Message::SetDivision(division) => {
+				self.division = division;
+				self.update_delay_time()?;
+			}
+			Message::SetFeedback(feedback) => {
+				self.feedback = feedback.clamp(0.0, MAX_FEEDBACK);
+				self.audio_manager.set_parameter(
+					self.feedback_parameter_id,
+					self.feedback,
+					Some(Tween::linear(0.05)),
+				)?;
+			}
+			Message::SetMix(mix) => {
+				self.wet_dry.set_mix(
+					&mut self.audio_manager,
+					mix.clamp(0.0, 1.0),
+					Some(Tween::linear(0.05)),
+				)?;
+			}
+			Message::SetBypassed(bypassed) => {
+				self.wet_dry
+					.set_bypassed(&mut self.audio_manager, bypassed)?;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		self.transport.check_for_events(&mut self.audio_manager);
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let division = self.division;
+		let mix = self.wet_dry.mix();
+		let bypassed = self.wet_dry.bypassed();
+		let delay_time = Tempo(self.tempo).beats_to_seconds(self.division.beats());
+		self.screen_wrapper.view_with_transport(
+			self.transport
+				.view(Message::Play, Message::Stop, Message::SetMetronomeClick),
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Button::new(
+						&mut self.blip_button,
+						Text::new("Blip")
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(Message::PlayBlip)
+					.width(Length::Units(120))
+					.style(AppStyles),
+				)
+				.push(
+					Division::ALL
+						.iter()
+						.fold(Row::new().spacing(16), |row, option| {
+							row.push(Radio::new(
+								*option,
+								option.name(),
+								Some(division),
+								Message::SetDivision,
+							))
+						}),
+				)
+				.push(Text::new(format!(
+					"Delay time: {:.0} ms",
+					delay_time * 1000.0
+				)))
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Tempo: {} BPM", self.tempo))
+								.width(Length::Units(150)),
+						)
+						.push(
+							Slider::new(
+								&mut self.tempo_slider,
+								MIN_TEMPO..=MAX_TEMPO,
+								self.tempo,
+								Message::SetTempo,
+							)
+							.step(1.0)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Feedback: {:.0}%", self.feedback * 100.0))
+								.width(Length::Units(150)),
+						)
+						.push(
+							Slider::new(
+								&mut self.feedback_slider,
+								0.0..=MAX_FEEDBACK,
+								self.feedback,
+								Message::SetFeedback,
+							)
+							.step(0.01)
+							.style(AppStyles),
+						),
+				)
+				.push(self.wet_dry_control.view(mix, bypassed))
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for DelayDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "delay_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetTempo(_) => Some("Tempo".into()),
+			Message::SetFeedback(_) => Some("Feedback".into()),
+			Message::SetMix(_) => Some("Wet level".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Tempo" => vec![Message::SetTempo(
+				MIN_TEMPO + (MAX_TEMPO - MIN_TEMPO) * value,
+			)],
+			"Feedback" => vec![Message::SetFeedback(value * MAX_FEEDBACK)],
+			"Wet level" => vec![Message::SetMix(value)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for DelayDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "delay_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetMetronomeClick(self.transport.click_enabled()),
+			Message::SetTempo(self.tempo),
+			Message::SetDivision(self.division),
+			Message::SetFeedback(self.feedback),
+			Message::SetMix(self.wet_dry.mix()),
+			Message::SetBypassed(self.wet_dry.bypassed()),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for DelayDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.transport.is_playing(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if !self.transport.is_playing() {
+			return None;
+		}
+		Some("Delay demo".into())
+	}
+}
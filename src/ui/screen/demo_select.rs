@@ -1,53 +1,458 @@
-use iced::{Align, Button, Column, Container, Length, Text};
+use iced::{Align, Button, Container, Length, Row, Scrollable, Text};
 
-use crate::ui::style::AppStyles;
+use crate::{
+	audio::sound_cache::SoundCache,
+	ui::{
+		screen::{
+			arrangement_editor_demo, beat_repeat_demo, branching_music_demo, combat_music_demo,
+			compressor_demo, count_in_demo, crossfade_demo, distortion_demo, drum_fill_demo,
+			ducking_demo, easing_gallery, filter_demo, granular_demo, group_hierarchy_demo,
+			group_pause_demo, jukebox_demo, layering_demo, loop_region_demo, low_health_demo,
+			mapping_playground, mixer_console_demo, mixer_snapshot_demo, net_sync_demo,
+			occlusion_demo, panning_demo, parametric_eq_demo, pause_menu_demo, pitch_playground,
+			reverb_demo, seek_demo, send_return_demo, spatial_demo, spectrum_demo,
+			start_offset_demo, stem_mute_solo_demo, stereo_width_demo, stinger_demo,
+			stop_fade_demo, surround_demo, tempo_ramp_demo, transition_matrix_demo,
+			underwater_demo, vinyl_brake_demo,
+		},
+		style::AppStyles,
+	},
+};
 
 #[derive(Debug, Copy, Clone)]
+#[allow(clippy::enum_variant_names)]
 pub enum Message {
 	GoToDrumFillDemo,
 	GoToUnderwaterDemo,
+	GoToStartOffsetDemo,
+	GoToRetriggerCooldownDemo,
+	GoToGroupHierarchyDemo,
+	GoToSendReturnDemo,
+	GoToSchedulingAccuracyDemo,
+	GoToStreamingDemo,
+	GoToSettings,
+	GoToLimitsDemo,
+	GoToDualMetronomeDemo,
+	GoToVoiceOverDemo,
+	GoToTuningDemo,
+	GoToStopFadeDemo,
+	GoToEasingGallery,
+	GoToMappingPlayground,
+	GoToCustomLoopDemo,
+	GoToSurroundDemo,
+	GoToNetSyncDemo,
+	GoToCrossfadeDemo,
+	GoToLayeringDemo,
+	GoToStingerDemo,
+	GoToSpatialDemo,
+	GoToDopplerDemo,
+	GoToFootstepDemo,
+	GoToReverbDemo,
+	GoToDuckingDemo,
+	GoToDialogueDemo,
+	GoToPitchPlayground,
+	GoToGranularDemo,
+	GoToTempoRampDemo,
+	GoToLoopRegionDemo,
+	GoToJukeboxDemo,
+	GoToDrumPadDemo,
+	GoToSynthKeyboardDemo,
+	GoToStepSequencerDemo,
+	GoToArrangementEditorDemo,
+	GoToMixerConsoleDemo,
+	GoToFilterDemo,
+	GoToDelayDemo,
+	GoToDistortionDemo,
+	GoToLfoDemo,
+	GoToBranchingMusicDemo,
+	GoToOcclusionDemo,
+	GoToWeatherDemo,
+	GoToCombatMusicDemo,
+	GoToSoundContainerDemo,
+	GoToSeekDemo,
+	GoToPanningDemo,
+	GoToGroupPauseDemo,
+	GoToPolyphonyDemo,
+	GoToTransitionMatrixDemo,
+	GoToRhythmGameDemo,
+	GoToSpectrumDemo,
+	GoToGenerativeAmbientDemo,
+	GoToChordProgressionDemo,
+	GoToSwingDemo,
+	GoToOddTimeSignatureDemo,
+	GoToStereoWidthDemo,
+	GoToParametricEqDemo,
+	GoToCompressorDemo,
+	GoToSignalGeneratorDemo,
+	GoToMixerSnapshotDemo,
+	GoToPauseMenuDemo,
+	GoToRadioVoiceDemo,
+	GoToFootstepSurfaceDemo,
+	GoToEngineDemo,
+	GoToWeaponDemo,
+	GoToUiSoundsDemo,
+	GoToLowHealthDemo,
+	GoToKaraokeDemo,
+	GoToCountInDemo,
+	GoToStemMuteSoloDemo,
+	GoToBeatRepeatDemo,
+	GoToVinylBrakeDemo,
+	GoToWindDemo,
+}
+
+const DEMOS: &[(&str, Message, &[&str])] = &[
+	(
+		"Drum fill demo",
+		Message::GoToDrumFillDemo,
+		drum_fill_demo::ASSETS,
+	),
+	(
+		"Underwater demo",
+		Message::GoToUnderwaterDemo,
+		underwater_demo::ASSETS,
+	),
+	(
+		"Start offset demo",
+		Message::GoToStartOffsetDemo,
+		start_offset_demo::ASSETS,
+	),
+	(
+		"Retrigger cooldown demo",
+		Message::GoToRetriggerCooldownDemo,
+		&[],
+	),
+	(
+		"Group hierarchy demo",
+		Message::GoToGroupHierarchyDemo,
+		group_hierarchy_demo::ASSETS,
+	),
+	(
+		"Send/return demo",
+		Message::GoToSendReturnDemo,
+		send_return_demo::ASSETS,
+	),
+	(
+		"Scheduling accuracy demo",
+		Message::GoToSchedulingAccuracyDemo,
+		&[],
+	),
+	("Streaming demo", Message::GoToStreamingDemo, &[]),
+	("Limits demo", Message::GoToLimitsDemo, &[]),
+	("Dual metronome demo", Message::GoToDualMetronomeDemo, &[]),
+	("Voice-over demo", Message::GoToVoiceOverDemo, &[]),
+	("Tuning demo", Message::GoToTuningDemo, &[]),
+	(
+		"Stop fade demo",
+		Message::GoToStopFadeDemo,
+		stop_fade_demo::ASSETS,
+	),
+	(
+		"Easing gallery",
+		Message::GoToEasingGallery,
+		easing_gallery::ASSETS,
+	),
+	(
+		"Mapping playground",
+		Message::GoToMappingPlayground,
+		mapping_playground::ASSETS,
+	),
+	("Custom loop demo", Message::GoToCustomLoopDemo, &[]),
+	(
+		"Surround demo",
+		Message::GoToSurroundDemo,
+		surround_demo::ASSETS,
+	),
+	(
+		"Network sync demo",
+		Message::GoToNetSyncDemo,
+		net_sync_demo::ASSETS,
+	),
+	(
+		"Crossfade demo",
+		Message::GoToCrossfadeDemo,
+		crossfade_demo::ASSETS,
+	),
+	(
+		"Layering demo",
+		Message::GoToLayeringDemo,
+		layering_demo::ASSETS,
+	),
+	(
+		"Stinger demo",
+		Message::GoToStingerDemo,
+		stinger_demo::ASSETS,
+	),
+	(
+		"Spatial demo",
+		Message::GoToSpatialDemo,
+		spatial_demo::ASSETS,
+	),
+	("Doppler demo", Message::GoToDopplerDemo, &[]),
+	("Footstep demo", Message::GoToFootstepDemo, &[]),
+	("Reverb demo", Message::GoToReverbDemo, reverb_demo::ASSETS),
+	(
+		"Ducking demo",
+		Message::GoToDuckingDemo,
+		ducking_demo::ASSETS,
+	),
+	("Dialogue demo", Message::GoToDialogueDemo, &[]),
+	(
+		"Pitch playground",
+		Message::GoToPitchPlayground,
+		pitch_playground::ASSETS,
+	),
+	(
+		"Granular demo",
+		Message::GoToGranularDemo,
+		granular_demo::ASSETS,
+	),
+	(
+		"Tempo ramp demo",
+		Message::GoToTempoRampDemo,
+		tempo_ramp_demo::ASSETS,
+	),
+	(
+		"Loop region demo",
+		Message::GoToLoopRegionDemo,
+		loop_region_demo::ASSETS,
+	),
+	(
+		"Jukebox demo",
+		Message::GoToJukeboxDemo,
+		jukebox_demo::ASSETS,
+	),
+	("Drum pad demo", Message::GoToDrumPadDemo, &[]),
+	("Synth keyboard demo", Message::GoToSynthKeyboardDemo, &[]),
+	("Step sequencer demo", Message::GoToStepSequencerDemo, &[]),
+	(
+		"Arrangement editor demo",
+		Message::GoToArrangementEditorDemo,
+		arrangement_editor_demo::ASSETS,
+	),
+	(
+		"Mixer console demo",
+		Message::GoToMixerConsoleDemo,
+		mixer_console_demo::ASSETS,
+	),
+	("Filter demo", Message::GoToFilterDemo, filter_demo::ASSETS),
+	("Delay demo", Message::GoToDelayDemo, &[]),
+	(
+		"Distortion demo",
+		Message::GoToDistortionDemo,
+		distortion_demo::ASSETS,
+	),
+	("LFO demo", Message::GoToLfoDemo, &[]),
+	(
+		"Branching music demo",
+		Message::GoToBranchingMusicDemo,
+		branching_music_demo::ASSETS,
+	),
+	(
+		"Occlusion demo",
+		Message::GoToOcclusionDemo,
+		occlusion_demo::ASSETS,
+	),
+	("Weather demo", Message::GoToWeatherDemo, &[]),
+	(
+		"Combat music demo",
+		Message::GoToCombatMusicDemo,
+		combat_music_demo::ASSETS,
+	),
+	("Sound container demo", Message::GoToSoundContainerDemo, &[]),
+	(
+		"Seek and scrub demo",
+		Message::GoToSeekDemo,
+		seek_demo::ASSETS,
+	),
+	(
+		"Panning demo",
+		Message::GoToPanningDemo,
+		panning_demo::ASSETS,
+	),
+	(
+		"Group pause demo",
+		Message::GoToGroupPauseDemo,
+		group_pause_demo::ASSETS,
+	),
+	("Polyphony demo", Message::GoToPolyphonyDemo, &[]),
+	(
+		"Transition matrix demo",
+		Message::GoToTransitionMatrixDemo,
+		transition_matrix_demo::ASSETS,
+	),
+	("Rhythm game demo", Message::GoToRhythmGameDemo, &[]),
+	(
+		"Spectrum demo",
+		Message::GoToSpectrumDemo,
+		spectrum_demo::ASSETS,
+	),
+	(
+		"Generative ambient demo",
+		Message::GoToGenerativeAmbientDemo,
+		&[],
+	),
+	(
+		"Chord progression demo",
+		Message::GoToChordProgressionDemo,
+		&[],
+	),
+	("Swing demo", Message::GoToSwingDemo, &[]),
+	(
+		"Odd time signature demo",
+		Message::GoToOddTimeSignatureDemo,
+		&[],
+	),
+	(
+		"Stereo width demo",
+		Message::GoToStereoWidthDemo,
+		stereo_width_demo::ASSETS,
+	),
+	(
+		"Parametric EQ demo",
+		Message::GoToParametricEqDemo,
+		parametric_eq_demo::ASSETS,
+	),
+	(
+		"Compressor demo",
+		Message::GoToCompressorDemo,
+		compressor_demo::ASSETS,
+	),
+	(
+		"Signal generator demo",
+		Message::GoToSignalGeneratorDemo,
+		&[],
+	),
+	(
+		"Mixer snapshot demo",
+		Message::GoToMixerSnapshotDemo,
+		mixer_snapshot_demo::ASSETS,
+	),
+	(
+		"Pause menu demo",
+		Message::GoToPauseMenuDemo,
+		pause_menu_demo::ASSETS,
+	),
+	("Radio voice demo", Message::GoToRadioVoiceDemo, &[]),
+	(
+		"Footstep surface demo",
+		Message::GoToFootstepSurfaceDemo,
+		&[],
+	),
+	("Engine demo", Message::GoToEngineDemo, &[]),
+	("Weapon demo", Message::GoToWeaponDemo, &[]),
+	("UI sounds demo", Message::GoToUiSoundsDemo, &[]),
+	(
+		"Low health demo",
+		Message::GoToLowHealthDemo,
+		low_health_demo::ASSETS,
+	),
+	("Karaoke demo", Message::GoToKaraokeDemo, &[]),
+	(
+		"Count-in demo",
+		Message::GoToCountInDemo,
+		count_in_demo::ASSETS,
+	),
+	(
+		"Stem mute/solo demo",
+		Message::GoToStemMuteSoloDemo,
+		stem_mute_solo_demo::ASSETS,
+	),
+	(
+		"Beat repeat demo",
+		Message::GoToBeatRepeatDemo,
+		beat_repeat_demo::ASSETS,
+	),
+	(
+		"Vinyl brake demo",
+		Message::GoToVinylBrakeDemo,
+		vinyl_brake_demo::ASSETS,
+	),
+	("Wind demo", Message::GoToWindDemo, &[]),
+];
+
+/// Gets every asset used by the demos (relative to the assets
+/// folder), so they can be preloaded at startup.
+pub fn all_assets() -> impl Iterator<Item = &'static str> {
+	DEMOS
+		.iter()
+		.flat_map(|(_, _, assets)| assets.iter().copied())
+}
+
+/// The names of the demos, in the order they're listed.
+#[cfg(feature = "tray")]
+pub fn demo_names() -> impl Iterator<Item = &'static str> {
+	DEMOS.iter().map(|(name, _, _)| *name)
+}
+
+/// The message that opens the demo at an index in the list.
+#[cfg(feature = "tray")]
+pub fn demo_message(index: usize) -> Option<Message> {
+	DEMOS.get(index).map(|(_, message, _)| *message)
 }
 
 pub struct DemoSelect {
-	drum_fill_demo_button: iced::button::State,
-	underwater_demo_button: iced::button::State,
+	sound_cache: SoundCache,
+	scrollable: iced::scrollable::State,
+	demo_buttons: Vec<iced::button::State>,
+	settings_button: iced::button::State,
 }
 
 impl DemoSelect {
-	pub fn new() -> Self {
+	pub fn new(sound_cache: SoundCache) -> Self {
 		Self {
-			drum_fill_demo_button: iced::button::State::new(),
-			underwater_demo_button: iced::button::State::new(),
+			sound_cache,
+			scrollable: iced::scrollable::State::new(),
+			demo_buttons: DEMOS.iter().map(|_| iced::button::State::new()).collect(),
+			settings_button: iced::button::State::new(),
 		}
 	}
 
+	/// Returns true if some of the demos' assets are still
+	/// being decoded.
+	pub fn is_loading(&self) -> bool {
+		DEMOS
+			.iter()
+			.any(|(_, _, assets)| !self.sound_cache.is_loaded(assets))
+	}
+
 	pub fn view(&mut self) -> iced::Element<'_, Message> {
-		Container::new(
-			Column::new()
-				.spacing(16)
-				.align_items(Align::Center)
-				.push(Text::new("Select a demo").size(48))
-				.push(
-					Button::new(
-						&mut self.drum_fill_demo_button,
-						Text::new("Drum fill demo").size(24),
-					)
-					.on_press(Message::GoToDrumFillDemo)
-					.style(AppStyles),
-				)
-				.push(
-					Button::new(
-						&mut self.underwater_demo_button,
-						Text::new("Underwater demo").size(24),
-					)
-					.on_press(Message::GoToUnderwaterDemo)
-					.style(AppStyles),
-				),
-		)
-		.width(Length::Fill)
-		.height(Length::Fill)
-		.align_x(Align::Center)
-		.align_y(Align::Center)
-		.into()
+		let list = Scrollable::new(&mut self.scrollable)
+			.padding(16)
+			.spacing(16)
+			.align_items(Align::Center)
+			.push(Text::new("Select a demo").size(48));
+		let sound_cache = &self.sound_cache;
+		let list = self.demo_buttons.iter_mut().zip(DEMOS).fold(
+			list,
+			|list, (state, (name, message, assets))| {
+				let mut row = Row::new().spacing(16).align_items(Align::Center).push(
+					Button::new(state, Text::new(*name).size(24))
+						.on_press(*message)
+						.style(AppStyles),
+				);
+				if !assets.is_empty() {
+					row = row.push(
+						Text::new(if sound_cache.is_loaded(assets) {
+							"Ready"
+						} else {
+							"Loading..."
+						})
+						.size(16),
+					);
+				}
+				list.push(row)
+			},
+		);
+		let list = list.push(
+			Button::new(&mut self.settings_button, Text::new("Settings"))
+				.on_press(Message::GoToSettings)
+				.style(AppStyles),
+		);
+		Container::new(list)
+			.width(Length::Fill)
+			.height(Length::Fill)
+			.align_x(Align::Center)
+			.align_y(Align::Center)
+			.into()
 	}
 }
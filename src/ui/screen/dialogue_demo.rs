@@ -0,0 +1,222 @@
+use std::error::Error;
+
+use iced::{
+	Align, Button, Color, Column, Container, HorizontalAlignment, Length, Text, VerticalAlignment,
+};
+use kira::{
+	instance::StopInstanceSettings,
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::Tween,
+	playable::PlayableSettings,
+	sequence::{EventReceiver, Sequence, SequenceInstanceId},
+	sound::SoundId,
+	Duration,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::create_audio_manager,
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+const EXPLANATION_TEXT: &str = "This demo plays a line of \
+dialogue and shows subtitles in time with it. The timing \
+doesn't come from the UI: the sound and the captions are \
+started together in a sequence, which waits until each caption \
+should appear (or disappear) and emits a custom event. The \
+screen pops those events from the sequence's event receiver \
+and shows whatever caption the latest one asks for.
+
+Since the sequence runs on the audio thread, the captions stay \
+in sync with the audio even if the UI stutters. (The voice is \
+placeholder babble.)";
+
+/// The captions for the dialogue, each with the times (in
+/// seconds from the start of the file) it appears and
+/// disappears.
+pub const CAPTIONS: [(f64, f64, &str); 4] = [
+	(0.3, 1.7, "Ah, you're finally awake."),
+	(2.25, 4.6, "The storm blew in faster than anyone expected."),
+	(5.15, 7.35, "We'll have to cross the pass before nightfall."),
+	(7.9, 9.7, "Stay close, and keep your lantern lit."),
+];
+const CURRENT_CAPTION_COLOR: [f32; 3] = [1.0, 1.0, 1.0];
+const OTHER_CAPTION_COLOR: [f32; 3] = [0.5, 0.5, 0.5];
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+}
+
+/// The events the dialogue sequence emits.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum CaptionEvent {
+	/// Shows one of the captions (by its index).
+	Show(usize),
+	Hide,
+	Finished,
+}
+
+pub struct DialogueDemo {
+	audio_manager: AudioManager,
+	sound_id: SoundId,
+	playback: Option<(SequenceInstanceId, EventReceiver<CaptionEvent>)>,
+	current_caption: Option<usize>,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_button: iced::button::State,
+}
+
+impl DialogueDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let sound_id = audio_manager.load_sound(
+			std::env::current_dir()?.join("assets/dialogue demo/dialogue.wav"),
+			PlayableSettings::new(),
+		)?;
+		Ok(Self {
+			audio_manager,
+			sound_id,
+			playback: None,
+			current_caption: None,
+			screen_wrapper: ScreenWrapper::new("Dialogue demo".into(), Message::GoToDemoSelect),
+			play_button: iced::button::State::new(),
+		})
+	}
+
+	fn play(&mut self) -> Result<(), Box<dyn Error>> {
+		let mut sequence = Sequence::new(Default::default());
+		sequence.play(self.sound_id, Default::default());
+		let mut time = 0.0;
+		for (index, (start, end, _)) in CAPTIONS.iter().enumerate() {
+			sequence.wait(Duration::Seconds(start - time));
+			sequence.emit(CaptionEvent::Show(index));
+			sequence.wait(Duration::Seconds(end - start));
+			sequence.emit(CaptionEvent::Hide);
+			time = *end;
+		}
+		sequence.wait(Duration::Seconds(self.sound_id.duration() - time));
+		sequence.emit(CaptionEvent::Finished);
+		self.playback = Some(
+			self.audio_manager
+				.start_sequence(sequence, Default::default())?,
+		);
+		self.current_caption = None;
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if self.playback.is_none() => self.play()?,
+			Message::Stop => {
+				if let Some((sequence_id, _)) = self.playback.take() {
+					self.audio_manager.stop_sequence_and_instances(
+						sequence_id,
+						StopInstanceSettings::new().fade_tween(Tween::linear(0.1)),
+					)?;
+				}
+				self.current_caption = None;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		let mut finished = false;
+		if let Some((_, event_receiver)) = &mut self.playback {
+			while let Some(event) = event_receiver.pop().copied() {
+				match event {
+					CaptionEvent::Show(index) => self.current_caption = Some(index),
+					CaptionEvent::Hide => self.current_caption = None,
+					CaptionEvent::Finished => finished = true,
+				}
+			}
+		}
+		if finished {
+			self.playback = None;
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.playback.is_some();
+		let current_caption = self.current_caption;
+		let caption = current_caption.map_or("", |index| CAPTIONS[index].2);
+		let script = CAPTIONS.iter().enumerate().fold(
+			Column::new().spacing(4).max_width(600),
+			|column, (index, (start, _, text))| {
+				let color = if current_caption == Some(index) {
+					CURRENT_CAPTION_COLOR
+				} else {
+					OTHER_CAPTION_COLOR
+				};
+				column.push(
+					Text::new(format!("{:>5.2}s  {}", start, text))
+						.size(16)
+						.color(Color::from(color)),
+				)
+			},
+		);
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Button::new(
+						&mut self.play_button,
+						Text::new(if playing { "Stop" } else { "Play" })
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(if playing {
+						Message::Stop
+					} else {
+						Message::Play
+					})
+					.width(Length::Units(120))
+					.style(AppStyles),
+				)
+				// the caption area keeps its size when it's empty,
+				// so the rest of the screen doesn't jump around
+				.push(
+					Container::new(
+						Text::new(caption)
+							.size(28)
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center)
+							.vertical_alignment(VerticalAlignment::Center),
+					)
+					.width(Length::Fill)
+					.max_width(600)
+					.height(Length::Units(80))
+					.center_y(),
+				)
+				.push(script)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for DialogueDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.playback.is_some(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		self.playback.as_ref().map(|_| "Dialogue demo".into())
+	}
+}
@@ -0,0 +1,272 @@
+use std::error::Error;
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Row, Slider, Text};
+use kira::{
+	instance::{InstanceId, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::{Mapping, ParameterId, Tween},
+	playable::PlayableSettings,
+	sound::SoundId,
+	Value,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{
+		create_audio_manager,
+		effect::{
+			distortion::{Distortion, DistortionSettings},
+			gain::Gain,
+		},
+		sound_cache::SoundCache,
+		wet_dry::WetDry,
+	},
+	ui::{
+		common::{screen_wrapper::ScreenWrapper, wet_dry_control::WetDryControl},
+		style::AppStyles,
+	},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &["drum fill demo/loop.ogg"];
+
+const EXPLANATION_TEXT: &str = "The distortion effect boosts \
+the signal by the drive amount and then squashes it with a tanh \
+curve, so the louder parts get rounded off and pick up extra \
+harmonics. The drive is a parameter, so the slider can be moved \
+while the loop plays.
+
+Distorting a sound also makes it louder, which makes it hard to \
+tell whether it sounds better or just louder. A gain effect \
+after the distortion reads the same drive parameter through a \
+mapping that turns it down by half as much as the drive turns it \
+up, which keeps the level roughly even. Bypass to compare it \
+with the clean loop, or blend the two for parallel distortion.";
+
+const MAX_DRIVE: f64 = 36.0;
+const INITIAL_DRIVE: f64 = 18.0;
+const INITIAL_MIX: f64 = 1.0;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	PlayLoop,
+	StopLoop,
+	/// Sets the drive (in decibels).
+	SetDrive(f64),
+	SetMix(f64),
+	SetBypassed(bool),
+}
+
+pub struct DistortionDemo {
+	audio_manager: AudioManager,
+	wet_dry: WetDry,
+	drive_parameter_id: ParameterId,
+	drive: f64,
+	loop_sound_id: SoundId,
+	/// The loop's dry and wet instances.
+	loop_instance_ids: Option<[InstanceId; 2]>,
+	screen_wrapper: ScreenWrapper<Message>,
+	wet_dry_control: WetDryControl<Message>,
+	loop_button: iced::button::State,
+	drive_slider: iced::slider::State,
+}
+
+impl DistortionDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let wet_dry = WetDry::new(&mut audio_manager, INITIAL_MIX)?;
+		let drive_parameter_id = audio_manager.add_parameter(INITIAL_DRIVE)?;
+		audio_manager.add_effect_to_track(
+			wet_dry.wet_track_id(),
+			Distortion::new(DistortionSettings::new().drive(drive_parameter_id)),
+			Default::default(),
+		)?;
+		audio_manager.add_effect_to_track(
+			wet_dry.wet_track_id(),
+			Gain::new(Value::Parameter(
+				drive_parameter_id,
+				Mapping {
+					input_range: (0.0, MAX_DRIVE),
+					output_range: (0.0, -MAX_DRIVE / 2.0),
+					clamp_bottom: true,
+					clamp_top: true,
+				},
+			)),
+			Default::default(),
+		)?;
+		// WetDry plays every sound twice at the same time,
+		// which a cooldown would block
+		let loop_sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&std::env::current_dir()?.join("assets/drum fill demo/loop.ogg"))?
+				.to_sound(PlayableSettings {
+					cooldown: None,
+					..PlayableSettings::new().default_loop_start(0.0)
+				}),
+		)?;
+		Ok(Self {
+			audio_manager,
+			wet_dry,
+			drive_parameter_id,
+			drive: INITIAL_DRIVE,
+			loop_sound_id,
+			loop_instance_ids: None,
+			screen_wrapper: ScreenWrapper::new("Distortion demo".into(), Message::GoToDemoSelect),
+			wet_dry_control: WetDryControl::new(Message::SetMix, Message::SetBypassed),
+			loop_button: iced::button::State::new(),
+			drive_slider: iced::slider::State::new(),
+		})
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::PlayLoop if self.loop_instance_ids.is_none() => {
+				self.loop_instance_ids = Some(self.wet_dry.play(
+					&mut self.audio_manager,
+					self.loop_sound_id,
+					Default::default(),
+				)?);
+			}
+			Message::StopLoop => {
+				for instance_id in self.loop_instance_ids.take().iter().flatten() {
+					self.audio_manager.stop_instance(
+						*instance_id,
+						StopInstanceSettings::new().fade_tween(Tween::linear(0.25)),
+					)?;
+				}
+			}
+			Message::SetDrive(drive) => {
+				self.drive = drive.clamp(0.0, MAX_DRIVE);
+				self.audio_manager.set_parameter(
+					self.drive_parameter_id,
+					self.drive,
+					Some(Tween::linear(0.05)),
+				)?;
+			}
+			Message::SetMix(mix) => {
+				self.wet_dry.set_mix(
+					&mut self.audio_manager,
+					mix.clamp(0.0, 1.0),
+					Some(Tween::linear(0.05)),
+				)?;
+			}
+			Message::SetBypassed(bypassed) => {
+				self.wet_dry
+					.set_bypassed(&mut self.audio_manager, bypassed)?;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let looping = self.loop_instance_ids.is_some();
+		let mix = self.wet_dry.mix();
+		let bypassed = self.wet_dry.bypassed();
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Button::new(
+						&mut self.loop_button,
+						Text::new(if looping { "Stop loop" } else { "Play loop" })
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(if looping {
+						Message::StopLoop
+					} else {
+						Message::PlayLoop
+					})
+					.width(Length::Units(120))
+					.style(AppStyles),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Drive: {:.1} dB", self.drive))
+								.width(Length::Units(150)),
+						)
+						.push(
+							Slider::new(
+								&mut self.drive_slider,
+								0.0..=MAX_DRIVE,
+								self.drive,
+								Message::SetDrive,
+							)
+							.step(0.1)
+							.style(AppStyles),
+						),
+				)
+				.push(self.wet_dry_control.view(mix, bypassed))
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for DistortionDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "distortion_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetDrive(_) => Some("Drive".into()),
+			Message::SetMix(_) => Some("Wet level".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Drive" => vec![Message::SetDrive(value * MAX_DRIVE)],
+			"Wet level" => vec![Message::SetMix(value)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for DistortionDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "distortion_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetDrive(self.drive),
+			Message::SetMix(self.wet_dry.mix()),
+			Message::SetBypassed(self.wet_dry.bypassed()),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for DistortionDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.loop_instance_ids.is_some(),
+			Message::PlayLoop,
+			Message::StopLoop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		self.loop_instance_ids.map(|_| "Distortion demo".into())
+	}
+}
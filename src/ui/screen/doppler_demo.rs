@@ -0,0 +1,318 @@
+use std::{error::Error, time::Instant};
+
+use iced::{Align, Button, Checkbox, Column, HorizontalAlignment, Length, Row, Slider, Text};
+use kira::{
+	instance::{InstanceId, InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::{ParameterId, Tween},
+	playable::PlayableSettings,
+	sound::SoundId,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, spatializer::Spatializer, synth},
+	preferences::OutputMode,
+	ui::{
+		common::{
+			emitter_pad::{self, EmitterPad},
+			screen_wrapper::ScreenWrapper,
+		},
+		style::AppStyles,
+	},
+};
+
+const EXPLANATION_TEXT: &str = "This demo sends a humming sound \
+past the listener. As it comes closer, the sound waves bunch up \
+and the pitch goes up; as it moves away, they spread out and the \
+pitch drops. This is the Doppler effect.
+
+Every frame, the demo works out how fast the sound is moving \
+towards or away from the listener and sets a pitch parameter to \
+the speed of sound divided by the speed of sound plus that speed. \
+The position also sets panning and volume parameters, the same \
+way the spatial demo does. The time it takes the sound to reach \
+the listener isn't simulated.";
+
+/// In meters per second.
+const SPEED_OF_SOUND: f64 = 343.0;
+/// How far the sound travels on each side of the listener
+/// (in meters).
+const HALF_PATH_LENGTH: f64 = 100.0;
+/// How close the sound's path comes to the listener (in meters).
+const CLOSEST_DISTANCE: f64 = 5.0;
+/// How many meters make up one unit of distance for the
+/// spatializer, which plays sounds at full volume within 1 unit.
+const METERS_PER_UNIT: f64 = 10.0;
+const MIN_SPEED: f64 = 5.0;
+const MAX_SPEED: f64 = 120.0;
+const DEFAULT_SPEED: f64 = 30.0;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Start,
+	Stop,
+	/// Sets how fast the sound moves (in meters per second).
+	SetSpeed(f64),
+	SetRepeat(bool),
+}
+
+pub struct DopplerDemo {
+	audio_manager: AudioManager,
+	spatializer: Spatializer,
+	sound_id: SoundId,
+	instance_id: Option<InstanceId>,
+	pitch_parameter_id: ParameterId,
+	panning_parameter_id: ParameterId,
+	volume_parameter_id: ParameterId,
+	/// Where the sound is along its path (in meters, from
+	/// left to right).
+	position: f64,
+	speed: f64,
+	pitch: f64,
+	repeat: bool,
+	last_tick: Instant,
+	screen_wrapper: ScreenWrapper<Message>,
+	emitter_pad: emitter_pad::State,
+	start_button: iced::button::State,
+	speed_slider: iced::slider::State,
+}
+
+impl DopplerDemo {
+	pub fn new(
+		audio_manager_settings: AudioManagerSettings,
+		output_mode: OutputMode,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let sound_id = audio_manager.add_sound(synth::hum(
+			220.0,
+			PlayableSettings::new().default_loop_start(0.0),
+		))?;
+		let pitch_parameter_id = audio_manager.add_parameter(1.0)?;
+		let panning_parameter_id = audio_manager.add_parameter(0.5)?;
+		let volume_parameter_id = audio_manager.add_parameter(0.0)?;
+		Ok(Self {
+			audio_manager,
+			spatializer: Spatializer::new(output_mode),
+			sound_id,
+			instance_id: None,
+			pitch_parameter_id,
+			panning_parameter_id,
+			volume_parameter_id,
+			position: -HALF_PATH_LENGTH,
+			speed: DEFAULT_SPEED,
+			pitch: 1.0,
+			repeat: false,
+			last_tick: Instant::now(),
+			screen_wrapper: ScreenWrapper::new("Doppler demo".into(), Message::GoToDemoSelect),
+			emitter_pad: Default::default(),
+			start_button: iced::button::State::new(),
+			speed_slider: iced::slider::State::new(),
+		})
+	}
+
+	/// How far the sound is from the listener (in meters).
+	fn distance(&self) -> f64 {
+		(self.position * self.position + CLOSEST_DISTANCE * CLOSEST_DISTANCE).sqrt()
+	}
+
+	/// Points the parameters at where the sound is now. The
+	/// tween lasts about as long as a frame, so the parameters
+	/// move smoothly from one frame to the next. A tween time of
+	/// 0 sets them right away.
+	fn update_parameters(&mut self, tween_time: f64) -> Result<(), Box<dyn Error>> {
+		// the part of the sound's velocity that's pointed away
+		// from the listener
+		let receding_speed = self.speed * self.position / self.distance();
+		self.pitch = SPEED_OF_SOUND / (SPEED_OF_SOUND + receding_speed);
+		let spatialized = self.spatializer.spatialize(
+			self.position / METERS_PER_UNIT,
+			-CLOSEST_DISTANCE / METERS_PER_UNIT,
+		);
+		let tween = if tween_time > 0.0 {
+			Some(Tween::linear(tween_time))
+		} else {
+			None
+		};
+		self.audio_manager
+			.set_parameter(self.pitch_parameter_id, self.pitch, tween)?;
+		self.audio_manager
+			.set_parameter(self.panning_parameter_id, spatialized.panning, tween)?;
+		self.audio_manager
+			.set_parameter(self.volume_parameter_id, spatialized.volume, tween)?;
+		Ok(())
+	}
+
+	fn stop(&mut self) -> Result<(), Box<dyn Error>> {
+		if let Some(instance_id) = self.instance_id.take() {
+			self.audio_manager.stop_instance(
+				instance_id,
+				StopInstanceSettings::new().fade_tween(Tween::linear(0.1)),
+			)?;
+		}
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Start => {
+				self.stop()?;
+				self.position = -HALF_PATH_LENGTH;
+				self.update_parameters(0.0)?;
+				self.instance_id = Some(
+					self.audio_manager.play(
+						self.sound_id,
+						InstanceSettings::new()
+							.pitch(self.pitch_parameter_id)
+							.panning(self.panning_parameter_id)
+							.volume(self.volume_parameter_id),
+					)?,
+				);
+				self.last_tick = Instant::now();
+			}
+			Message::Stop => self.stop()?,
+			Message::SetSpeed(speed) => {
+				self.speed = speed.clamp(MIN_SPEED, MAX_SPEED);
+			}
+			Message::SetRepeat(repeat) => {
+				self.repeat = repeat;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		let delta_time = self.last_tick.elapsed().as_secs_f64();
+		self.last_tick = Instant::now();
+		if self.instance_id.is_none() {
+			return Ok(());
+		}
+		self.position += self.speed * delta_time;
+		if self.position > HALF_PATH_LENGTH {
+			if !self.repeat {
+				return self.stop();
+			}
+			self.position -= HALF_PATH_LENGTH * 2.0;
+			// the sound jumps back to the start, so the parameters
+			// should too instead of sweeping across
+			return self.update_parameters(0.0);
+		}
+		self.update_parameters(delta_time)
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let moving = self.instance_id.is_some();
+		let status_text = format!(
+			"Distance: {:.0} m - pitch: {:.2}x",
+			self.distance(),
+			if moving { self.pitch } else { 1.0 }
+		);
+		let emitter = (
+			(self.position / METERS_PER_UNIT) as f32,
+			(-CLOSEST_DISTANCE / METERS_PER_UNIT) as f32,
+		);
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					EmitterPad::new(
+						&mut self.emitter_pad,
+						(HALF_PATH_LENGTH / METERS_PER_UNIT) as f32,
+						emitter,
+					)
+					.rings(vec![1.0]),
+				)
+				.push(Text::new(status_text))
+				.push(
+					Row::new()
+						.spacing(16)
+						.max_width(600)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!(
+								"Speed: {:.0} m/s ({:.0} km/h)",
+								self.speed,
+								self.speed * 3.6
+							))
+							.width(Length::Units(250)),
+						)
+						.push(
+							Slider::new(
+								&mut self.speed_slider,
+								MIN_SPEED..=MAX_SPEED,
+								self.speed,
+								Message::SetSpeed,
+							)
+							.step(1.0)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Button::new(
+								&mut self.start_button,
+								Text::new(if moving { "Stop" } else { "Start" })
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(if moving {
+								Message::Stop
+							} else {
+								Message::Start
+							})
+							.width(Length::Units(100))
+							.style(AppStyles),
+						)
+						.push(Checkbox::new(self.repeat, "Repeat", Message::SetRepeat)),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for DopplerDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "doppler_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetSpeed(_) => Some("Speed".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Speed" => vec![Message::SetSpeed(
+				MIN_SPEED + (MAX_SPEED - MIN_SPEED) * value,
+			)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for DopplerDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "doppler_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetSpeed(self.speed),
+			Message::SetRepeat(self.repeat),
+		]
+	}
+}
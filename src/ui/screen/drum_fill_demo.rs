@@ -1,19 +1,38 @@
-mod beat_display;
-
 use std::error::Error;
 
-use beat_display::BeatDisplay;
 use iced::{Align, Button, Column, HorizontalAlignment, Length, Row, Text};
 use kira::{
 	group::GroupId,
+	instance::InstanceSettings,
 	manager::{AudioManager, AudioManagerSettings},
 	playable::PlayableSettings,
 	sequence::{EventReceiver, Sequence, SequenceInstanceId, SequenceSettings},
-	sound::{Sound, SoundId},
-	AudioResult, Duration, MetronomeSettings, Tempo,
+	sound::SoundId,
+	AudioResult, Duration, Tempo,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, sound_cache::SoundCache, synth},
+	preferences::BeatDisplayStyle,
+	ui::{
+		common::{beat_display::BeatDisplay, screen_wrapper::ScreenWrapper, transport::Transport},
+		style::AppStyles,
+	},
 };
 
-use crate::ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles};
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &[
+	"drum fill demo/loop.ogg",
+	"drum fill demo/2 beat fill.ogg",
+	"drum fill demo/3 beat fill.ogg",
+	"drum fill demo/4 beat fill.ogg",
+];
+
+pub const TEMPO: Tempo = Tempo(128.0);
+/// The pitches of the stinger's notes, relative to the first
+/// (which makes a major chord).
+const STINGER_PITCHES: [f64; 3] = [1.0, 1.25, 1.5];
 
 const EXPLANATION_TEXT: &str = "This demo uses \
 a sequence to play a short drum sample repeatedly and \
@@ -23,17 +42,24 @@ to play.
 
 When the drum fill is triggered, a second sequence waits \
 for the right beat, stops the previous sequence, starts \
-the drum fill, and then starts a new loop.";
+the drum fill, and then starts a new loop.
 
-#[derive(Debug, Copy, Clone)]
+The stinger is a short flourish that plays on top of the \
+music. It waits for the next beat too, but it doesn't \
+interrupt the loop.";
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum Message {
 	GoToDemoSelect,
 	Play,
 	PlayDrumFill,
+	PlayStinger,
 	Stop,
+	SetMetronomeClick(bool),
 }
 
 #[derive(Debug, Clone, Copy)]
+#[allow(clippy::enum_variant_names)]
 pub enum DrumFill {
 	TwoBeat,
 	ThreeBeat,
@@ -47,6 +73,15 @@ impl DrumFill {
 			_ => 1.0,
 		}
 	}
+
+	/// The beats of the bar the fill plays over (counting from 0).
+	fn beats(self) -> Vec<usize> {
+		match self {
+			DrumFill::TwoBeat => vec![2, 3],
+			DrumFill::ThreeBeat => vec![1, 2, 3],
+			DrumFill::FourBeat => vec![0, 1, 2, 3],
+		}
+	}
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -97,41 +132,71 @@ pub struct DrumFillDemo {
 	fill_2b_sound_id: SoundId,
 	fill_3b_sound_id: SoundId,
 	fill_4b_sound_id: SoundId,
+	stinger_sound_id: SoundId,
 	playback_state: PlaybackState,
 	beat_tracker_sequence: Option<(SequenceInstanceId, EventReceiver<Beat>)>,
 	loop_sequence: Option<(SequenceInstanceId, EventReceiver<DrumFillEvent>)>,
+	transport: Transport,
+	beat_display_style: BeatDisplayStyle,
 	screen_wrapper: ScreenWrapper<Message>,
-	play_button: iced::button::State,
 	play_drum_fill_button: iced::button::State,
+	play_stinger_button: iced::button::State,
 }
 
 impl DrumFillDemo {
-	pub fn new() -> Result<Self, Box<dyn Error>> {
-		let mut audio_manager = AudioManager::new(AudioManagerSettings {
-			metronome_settings: MetronomeSettings {
-				tempo: Tempo(128.0).into(),
-				..Default::default()
-			},
-			..Default::default()
-		})?;
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+		beat_display_style: BeatDisplayStyle,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut demo = Self::with_audio_manager(
+			create_audio_manager(Self::audio_manager_settings(audio_manager_settings))?,
+			sound_cache,
+		)?;
+		demo.beat_display_style = beat_display_style;
+		Ok(demo)
+	}
+
+	/// Sets the metronome to the tempo of the drum loops.
+	fn audio_manager_settings(settings: AudioManagerSettings) -> AudioManagerSettings {
+		AudioManagerSettings {
+			metronome_settings: Transport::metronome_settings(TEMPO),
+			..settings
+		}
+	}
+
+	fn with_audio_manager(
+		mut audio_manager: AudioManager,
+		sound_cache: &SoundCache,
+	) -> Result<Self, Box<dyn Error>> {
 		let group_id = audio_manager.add_group([])?;
+		let transport = Transport::new(&mut audio_manager, TEMPO)?;
 		let base_assets_dir = std::env::current_dir()?.join("assets/drum fill demo");
-		let loop_sound_id = audio_manager.add_sound(Sound::from_file(
-			base_assets_dir.join("loop.ogg"),
-			PlayableSettings::default().groups([group_id]),
-		)?)?;
-		let fill_2b_sound_id = audio_manager.add_sound(Sound::from_file(
-			base_assets_dir.join("2 beat fill.ogg"),
-			PlayableSettings::default().groups([group_id]),
-		)?)?;
-		let fill_3b_sound_id = audio_manager.add_sound(Sound::from_file(
-			base_assets_dir.join("3 beat fill.ogg"),
-			PlayableSettings::default().groups([group_id]),
-		)?)?;
-		let fill_4b_sound_id = audio_manager.add_sound(Sound::from_file(
-			base_assets_dir.join("4 beat fill.ogg"),
+		let loop_sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&base_assets_dir.join("loop.ogg"))?
+				.to_sound(PlayableSettings::default().groups([group_id])),
+		)?;
+		let fill_2b_sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&base_assets_dir.join("2 beat fill.ogg"))?
+				.to_sound(PlayableSettings::default().groups([group_id])),
+		)?;
+		let fill_3b_sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&base_assets_dir.join("3 beat fill.ogg"))?
+				.to_sound(PlayableSettings::default().groups([group_id])),
+		)?;
+		let fill_4b_sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&base_assets_dir.join("4 beat fill.ogg"))?
+				.to_sound(PlayableSettings::default().groups([group_id])),
+		)?;
+		let stinger_sound_id = audio_manager.add_sound(synth::blip(
+			880.0,
+			0.4,
 			PlayableSettings::default().groups([group_id]),
-		)?)?;
+		))?;
 		Ok(Self {
 			audio_manager,
 			group_id,
@@ -139,12 +204,15 @@ impl DrumFillDemo {
 			fill_2b_sound_id,
 			fill_3b_sound_id,
 			fill_4b_sound_id,
+			stinger_sound_id,
 			playback_state: PlaybackState::Stopped,
 			beat_tracker_sequence: None,
 			loop_sequence: None,
+			transport,
+			beat_display_style: BeatDisplayStyle::default(),
 			screen_wrapper: ScreenWrapper::new("Drum fill demo".into(), Message::GoToDemoSelect),
-			play_button: iced::button::State::new(),
 			play_drum_fill_button: iced::button::State::new(),
+			play_stinger_button: iced::button::State::new(),
 		})
 	}
 
@@ -216,86 +284,109 @@ impl DrumFillDemo {
 		)
 	}
 
+	/// Plays the stinger's notes a quarter of a beat apart,
+	/// starting on the next beat if the music is playing.
+	fn start_stinger_sequence(&mut self) -> AudioResult<()> {
+		let mut sequence = Sequence::<()>::new(SequenceSettings::new().groups([self.group_id]));
+		if self.transport.is_playing() {
+			sequence.wait_for_interval(1.0);
+		}
+		for pitch in &STINGER_PITCHES {
+			sequence.play(self.stinger_sound_id, InstanceSettings::new().pitch(*pitch));
+			sequence.wait(Duration::Beats(0.25));
+		}
+		self.audio_manager
+			.start_sequence(sequence, Default::default())?;
+		Ok(())
+	}
+
 	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
 		match message {
 			Message::Play => {
 				self.playback_state = PlaybackState::PlayingLoop(Beat::One);
 				self.beat_tracker_sequence = Some(self.start_beat_tracker()?);
 				self.loop_sequence = Some(self.start_loop_sequence()?);
-				self.audio_manager.start_metronome()?;
+				self.transport.start(&mut self.audio_manager)?;
 			}
-			Message::PlayDrumFill => match self.playback_state {
-				PlaybackState::PlayingLoop(beat) => {
+			// the button is disabled while a fill is queued or
+			// playing, but a replayed session can still press it
+			Message::PlayDrumFill => {
+				if let PlaybackState::PlayingLoop(beat) = self.playback_state {
 					let fill = beat.fill();
 					self.playback_state = PlaybackState::QueueingFill(beat, fill);
 					self.loop_sequence = Some(self.start_fill_and_loop_sequence(fill)?);
 				}
-				_ => unreachable!(),
-			},
+			}
+			Message::PlayStinger => {
+				self.start_stinger_sequence()?;
+			}
 			Message::Stop => {
 				self.audio_manager
 					.stop_group(self.group_id, Default::default())?;
-				self.audio_manager.stop_metronome()?;
+				self.transport.stop(&mut self.audio_manager)?;
 				self.playback_state = PlaybackState::Stopped;
 				self.beat_tracker_sequence = None;
 				self.loop_sequence = None;
 			}
+			Message::SetMetronomeClick(enabled) => {
+				self.transport
+					.set_click_enabled(&mut self.audio_manager, enabled)?;
+			}
 			_ => {}
 		}
 		Ok(())
 	}
 
-	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
-		if let Some(sequence) = &mut self.beat_tracker_sequence {
-			while let Some(new_beat) = sequence.1.pop() {
-				match &mut self.playback_state {
-					PlaybackState::PlayingLoop(beat) => {
-						*beat = *new_beat;
-					}
-					PlaybackState::QueueingFill(beat, _) | PlaybackState::PlayingFill(beat, _) => {
-						*beat = *new_beat;
-					}
-					_ => {}
-				}
+	fn pop_beat(&mut self) -> Option<Beat> {
+		self.beat_tracker_sequence
+			.as_mut()
+			.and_then(|(_, events)| events.pop().copied())
+	}
+
+	fn pop_drum_fill_event(&mut self) -> Option<DrumFillEvent> {
+		self.loop_sequence
+			.as_mut()
+			.and_then(|(_, events)| events.pop().copied())
+	}
+
+	fn on_beat(&mut self, new_beat: Beat) {
+		match &mut self.playback_state {
+			PlaybackState::PlayingLoop(beat)
+			| PlaybackState::QueueingFill(beat, _)
+			| PlaybackState::PlayingFill(beat, _) => {
+				*beat = new_beat;
 			}
+			_ => {}
 		}
-		if let Some(sequence) = &mut self.loop_sequence {
-			while let Some(event) = sequence.1.pop() {
-				match event {
-					DrumFillEvent::Start => {
-						if let PlaybackState::QueueingFill(beat, fill) = self.playback_state {
-							self.playback_state = PlaybackState::PlayingFill(beat, fill);
-						}
-					}
-					DrumFillEvent::Finish => {
-						if let PlaybackState::PlayingFill(beat, _) = self.playback_state {
-							self.playback_state = PlaybackState::PlayingLoop(beat);
-						}
-					}
+	}
+
+	fn on_drum_fill_event(&mut self, event: DrumFillEvent) {
+		match event {
+			DrumFillEvent::Start => {
+				if let PlaybackState::QueueingFill(beat, fill) = self.playback_state {
+					self.playback_state = PlaybackState::PlayingFill(beat, fill);
+				}
+			}
+			DrumFillEvent::Finish => {
+				if let PlaybackState::PlayingFill(beat, _) = self.playback_state {
+					self.playback_state = PlaybackState::PlayingLoop(beat);
 				}
 			}
 		}
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		self.transport.check_for_events(&mut self.audio_manager);
+		while let Some(beat) = self.pop_beat() {
+			self.on_beat(beat);
+		}
+		while let Some(event) = self.pop_drum_fill_event() {
+			self.on_drum_fill_event(event);
+		}
 		Ok(())
 	}
 
 	pub fn view(&mut self) -> iced::Element<'_, Message> {
-		let play_button = Button::new(
-			&mut self.play_button,
-			Text::new(match self.playback_state {
-				PlaybackState::Stopped => "Play",
-				_ => "Stop",
-			})
-			.width(Length::Fill)
-			.size(24)
-			.horizontal_alignment(HorizontalAlignment::Center),
-		)
-		.on_press(match self.playback_state {
-			PlaybackState::Stopped => Message::Play,
-			_ => Message::Stop,
-		})
-		.width(Length::Fill)
-		.style(AppStyles);
-
 		let mut play_drum_fill_button = Button::new(
 			&mut self.play_drum_fill_button,
 			Text::new("Play drum fill")
@@ -303,38 +394,50 @@ impl DrumFillDemo {
 				.size(24)
 				.horizontal_alignment(HorizontalAlignment::Center),
 		)
-		.width(Length::Fill)
+		.width(Length::Units(200))
 		.style(AppStyles);
-		match self.playback_state {
-			PlaybackState::PlayingLoop(_) => {
-				play_drum_fill_button = play_drum_fill_button.on_press(Message::PlayDrumFill);
-			}
-			_ => {}
+		if let PlaybackState::PlayingLoop(_) = self.playback_state {
+			play_drum_fill_button = play_drum_fill_button.on_press(Message::PlayDrumFill);
 		}
 
-		self.screen_wrapper.view(
+		self.screen_wrapper.view_with_transport(
+			self.transport
+				.view(Message::Play, Message::Stop, Message::SetMetronomeClick),
 			Column::new()
 				.spacing(16)
 				.align_items(Align::Center)
 				.push(
 					Row::new()
-						.max_width(300)
 						.spacing(16)
-						.push(play_button)
-						.push(play_drum_fill_button),
+						.align_items(Align::Center)
+						.push(play_drum_fill_button)
+						.push(
+							Button::new(
+								&mut self.play_stinger_button,
+								Text::new("Play stinger")
+									.width(Length::Fill)
+									.size(24)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(Message::PlayStinger)
+							.width(Length::Units(200))
+							.style(AppStyles),
+						),
 				)
 				.push(BeatDisplay {
-					beat: match self.playback_state {
+					beats_per_bar: 4,
+					current: match self.playback_state {
 						PlaybackState::Stopped => None,
-						PlaybackState::PlayingLoop(beat) => Some(beat),
+						PlaybackState::PlayingLoop(beat) => Some(beat.as_usize() - 1),
 						PlaybackState::QueueingFill(beat, _)
-						| PlaybackState::PlayingFill(beat, _) => Some(beat),
+						| PlaybackState::PlayingFill(beat, _) => Some(beat.as_usize() - 1),
 					},
-					fill: match self.playback_state {
+					marked: match self.playback_state {
 						PlaybackState::QueueingFill(_, fill)
-						| PlaybackState::PlayingFill(_, fill) => Some(fill),
-						_ => None,
+						| PlaybackState::PlayingFill(_, fill) => fill.beats(),
+						_ => vec![],
 					},
+					style: self.beat_display_style,
 				})
 				.push(
 					Column::new()
@@ -345,3 +448,181 @@ impl DrumFillDemo {
 		)
 	}
 }
+
+impl crate::ui::screen_states::RememberControls for DrumFillDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "drum_fill_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![Message::SetMetronomeClick(self.transport.click_enabled())]
+	}
+}
+
+#[cfg(feature = "remote")]
+impl crate::ui::remote_control::RemoteControls for DrumFillDemo {
+	type Message = Message;
+
+	fn remote_messages(&self, command: crate::remote::RemoteCommand) -> Vec<Message> {
+		use crate::remote::RemoteCommand;
+		match command {
+			RemoteCommand::TriggerFill => vec![Message::PlayDrumFill],
+			RemoteCommand::PlayStinger => vec![Message::PlayStinger],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for DrumFillDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.transport.is_playing(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if !self.transport.is_playing() {
+			return None;
+		}
+		Some("Drum fill demo".into())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::audio::offline::{load_session, OfflineRenderer};
+
+	/// How often the app checks for events.
+	const TICK: f64 = 1.0 / 60.0;
+
+	fn log_state_changes(
+		demo: &mut DrumFillDemo,
+		renderer: &mut OfflineRenderer,
+		f: impl FnOnce(&mut DrumFillDemo),
+	) {
+		let previous_state = format!("{:?}", demo.playback_state);
+		f(demo);
+		let state = format!("{:?}", demo.playback_state);
+		if state != previous_state {
+			renderer.log(state);
+		}
+	}
+
+	fn send(demo: &mut DrumFillDemo, renderer: &mut OfflineRenderer, message: Message) {
+		renderer.log(format!("{:?}", message));
+		log_state_changes(demo, renderer, |demo| demo.update(message).unwrap());
+	}
+
+	/// Renders one tick of audio, then handles the events the
+	/// sequences emitted one at a time so each one can be logged.
+	fn tick(demo: &mut DrumFillDemo, renderer: &mut OfflineRenderer) {
+		renderer.render(TICK);
+		while let Some(beat) = demo.pop_beat() {
+			renderer.log(format!("emitted {:?}", beat));
+			log_state_changes(demo, renderer, |demo| demo.on_beat(beat));
+		}
+		while let Some(event) = demo.pop_drum_fill_event() {
+			renderer.log(format!("emitted {:?}", event));
+			log_state_changes(demo, renderer, |demo| demo.on_drum_fill_event(event));
+		}
+	}
+
+	fn run_for(demo: &mut DrumFillDemo, renderer: &mut OfflineRenderer, duration: f64) {
+		let end = renderer.time() + duration;
+		while renderer.time() < end {
+			tick(demo, renderer);
+		}
+	}
+
+	fn current_beat(demo: &DrumFillDemo) -> Option<Beat> {
+		match demo.playback_state {
+			PlaybackState::PlayingLoop(beat) => Some(beat),
+			_ => None,
+		}
+	}
+
+	fn create_demo() -> (DrumFillDemo, OfflineRenderer) {
+		let (audio_manager, renderer) =
+			OfflineRenderer::new(DrumFillDemo::audio_manager_settings(Default::default())).unwrap();
+		let sound_cache = SoundCache::preload(
+			std::env::current_dir().unwrap().join("assets"),
+			std::iter::empty(),
+		);
+		let demo = DrumFillDemo::with_audio_manager(audio_manager, &sound_cache).unwrap();
+		(demo, renderer)
+	}
+
+	/// Queues a drum fill on the given beat of the second bar
+	/// and records everything that happens until the loop
+	/// has started again.
+	fn drum_fill_on_beat(beat: Beat) -> OfflineRenderer {
+		let (mut demo, mut renderer) = create_demo();
+		send(&mut demo, &mut renderer, Message::Play);
+		// wait for the first bar to go by
+		run_for(&mut demo, &mut renderer, TEMPO.beats_to_seconds(4.0));
+		while current_beat(&demo) != Some(beat) {
+			tick(&mut demo, &mut renderer);
+		}
+		send(&mut demo, &mut renderer, Message::PlayDrumFill);
+		while current_beat(&demo).is_none() {
+			tick(&mut demo, &mut renderer);
+		}
+		// one more beat to make sure the loop restarted cleanly
+		run_for(&mut demo, &mut renderer, 0.5);
+		renderer
+	}
+
+	#[test]
+	fn drum_fill_on_beat_one() {
+		drum_fill_on_beat(Beat::One).assert_trace_matches_golden("drum_fill_on_beat_one");
+	}
+
+	#[test]
+	fn drum_fill_on_beat_two() {
+		drum_fill_on_beat(Beat::Two).assert_trace_matches_golden("drum_fill_on_beat_two");
+	}
+
+	#[test]
+	fn drum_fill_on_beat_three() {
+		drum_fill_on_beat(Beat::Three).assert_trace_matches_golden("drum_fill_on_beat_three");
+	}
+
+	#[test]
+	fn drum_fill_on_beat_four() {
+		drum_fill_on_beat(Beat::Four).assert_trace_matches_golden("drum_fill_on_beat_four");
+	}
+
+	#[test]
+	fn drum_fill_waits_for_the_right_beat() {
+		let (mut demo, mut renderer) = create_demo();
+		send(&mut demo, &mut renderer, Message::Play);
+		run_for(&mut demo, &mut renderer, 2.0);
+		// this lands on beat one, so the three beat fill is queued
+		// up and should start on the next beat
+		send(&mut demo, &mut renderer, Message::PlayDrumFill);
+		run_for(&mut demo, &mut renderer, 4.0);
+		send(&mut demo, &mut renderer, Message::Stop);
+		run_for(&mut demo, &mut renderer, 0.5);
+		renderer.assert_matches_golden("drum_fill_demo");
+	}
+
+	#[test]
+	fn replays_recorded_session() {
+		let (mut demo, mut renderer) = create_demo();
+		let mut session = load_session("drum_fill_demo");
+		while !session.is_finished() {
+			for message in session.due::<DrumFillDemo>(renderer.time()).unwrap() {
+				send(&mut demo, &mut renderer, message);
+			}
+			tick(&mut demo, &mut renderer);
+		}
+		run_for(&mut demo, &mut renderer, 0.5);
+		renderer.assert_trace_matches_golden("drum_fill_session");
+	}
+}
@@ -0,0 +1,300 @@
+mod pad_grid;
+
+use std::{error::Error, time::Instant};
+
+use iced::{keyboard::KeyCode, Align, Column, Length, Row, Slider, Text};
+use kira::{
+	instance::InstanceSettings,
+	manager::{AudioManager, AudioManagerSettings},
+	playable::PlayableSettings,
+	sound::SoundId,
+};
+use serde::{Deserialize, Serialize};
+
+use self::pad_grid::{Pad, PadGrid};
+use crate::{
+	audio::{create_audio_manager, synth},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+const EXPLANATION_TEXT: &str = "Click the pads or press their \
+keys to play them. Every hit is a one-shot: a new instance of a \
+short sound that plays once and cleans itself up, so hits can \
+overlap freely.
+
+Hits go straight to the audio manager's play function, which \
+sends a command to the audio thread. It's picked up at the start \
+of the next audio buffer, so the delay between pressing a key \
+and hearing the sound is mostly buffer size, not anything Kira \
+is doing. Each pad has its own volume and pitch, which are \
+passed in the instance settings of every hit. The sliders edit \
+whichever pad was hit last.";
+
+const NUM_PADS: usize = 16;
+/// The names of the pads and the keys that play them, from the
+/// top left, row by row.
+const PADS: [(&str, KeyCode, &str); NUM_PADS] = [
+	("Kick", KeyCode::Key1, "1"),
+	("Low tom", KeyCode::Key2, "2"),
+	("Mid tom", KeyCode::Key3, "3"),
+	("High tom", KeyCode::Key4, "4"),
+	("Snare", KeyCode::Q, "Q"),
+	("Rim", KeyCode::W, "W"),
+	("Clap", KeyCode::E, "E"),
+	("Crash", KeyCode::R, "R"),
+	("Closed hat", KeyCode::A, "A"),
+	("Open hat", KeyCode::S, "S"),
+	("Shaker", KeyCode::D, "D"),
+	("Cowbell", KeyCode::F, "F"),
+	("C", KeyCode::Z, "Z"),
+	("E", KeyCode::X, "X"),
+	("G", KeyCode::C, "C"),
+	("High C", KeyCode::V, "V"),
+];
+/// How long the pads stay lit after they're hit (in seconds).
+const FLASH_TIME: f64 = 0.25;
+/// The quietest and loudest the volume sliders go (in decibels).
+const MIN_VOLUME: f64 = -24.0;
+const MAX_VOLUME: f64 = 6.0;
+/// How far the pitch sliders go in each direction (in semitones).
+const MAX_PITCH: f64 = 12.0;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	/// Plays one of the pads (by its index).
+	Hit(usize),
+	/// Sets the volume of a pad (in decibels).
+	SetVolume(usize, f64),
+	/// Sets the pitch of a pad (in semitones).
+	SetPitch(usize, f64),
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+struct PadSettings {
+	/// In decibels.
+	volume: f64,
+	/// In semitones.
+	pitch: f64,
+}
+
+pub struct DrumPadDemo {
+	audio_manager: AudioManager,
+	sound_ids: Vec<SoundId>,
+	pad_settings: [PadSettings; NUM_PADS],
+	last_hit_times: [Option<Instant>; NUM_PADS],
+	/// The pad the sliders are editing.
+	selected: usize,
+	hits: usize,
+	screen_wrapper: ScreenWrapper<Message>,
+	pad_grid: pad_grid::State,
+	volume_slider: iced::slider::State,
+	pitch_slider: iced::slider::State,
+}
+
+impl DrumPadDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let sounds = vec![
+			synth::kick(50.0, 0.5, PlayableSettings::new()),
+			synth::kick(90.0, 0.4, PlayableSettings::new()),
+			synth::kick(130.0, 0.35, PlayableSettings::new()),
+			synth::kick(180.0, 0.3, PlayableSettings::new()),
+			synth::snare(180.0, 0.25, PlayableSettings::new()),
+			synth::snare(400.0, 0.08, PlayableSettings::new()),
+			synth::noise_hit(0.2, 0.3, PlayableSettings::new()),
+			synth::noise_hit(1.5, 0.7, PlayableSettings::new()),
+			synth::noise_hit(0.05, 1.0, PlayableSettings::new()),
+			synth::noise_hit(0.4, 1.0, PlayableSettings::new()),
+			synth::noise_hit(0.12, 0.6, PlayableSettings::new()),
+			synth::blip(800.0, 0.3, PlayableSettings::new()),
+			synth::blip(261.63, 0.5, PlayableSettings::new()),
+			synth::blip(329.63, 0.5, PlayableSettings::new()),
+			synth::blip(392.0, 0.5, PlayableSettings::new()),
+			synth::blip(523.25, 0.5, PlayableSettings::new()),
+		];
+		let mut sound_ids = vec![];
+		for sound in sounds {
+			sound_ids.push(audio_manager.add_sound(sound)?);
+		}
+		Ok(Self {
+			audio_manager,
+			sound_ids,
+			pad_settings: [PadSettings::default(); NUM_PADS],
+			last_hit_times: [None; NUM_PADS],
+			selected: 0,
+			hits: 0,
+			screen_wrapper: ScreenWrapper::new("Drum pad demo".into(), Message::GoToDemoSelect),
+			pad_grid: Default::default(),
+			volume_slider: iced::slider::State::new(),
+			pitch_slider: iced::slider::State::new(),
+		})
+	}
+
+	fn hit(&mut self, pad: usize) -> Result<(), Box<dyn Error>> {
+		let settings = self.pad_settings[pad];
+		self.audio_manager.play(
+			self.sound_ids[pad],
+			InstanceSettings::new()
+				.volume(10.0f64.powf(settings.volume / 20.0))
+				.pitch(2.0f64.powf(settings.pitch / 12.0)),
+		)?;
+		self.last_hit_times[pad] = Some(Instant::now());
+		self.selected = pad;
+		self.hits += 1;
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Hit(pad) if pad < NUM_PADS => self.hit(pad)?,
+			Message::SetVolume(pad, volume) if pad < NUM_PADS => {
+				self.pad_settings[pad].volume = volume.clamp(MIN_VOLUME, MAX_VOLUME);
+			}
+			Message::SetPitch(pad, pitch) if pad < NUM_PADS => {
+				self.pad_settings[pad].pitch = pitch.clamp(-MAX_PITCH, MAX_PITCH);
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	/// Lets the flashes fade out once they're over, so the
+	/// screen doesn't need redrawing forever.
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		for last_hit_time in &mut self.last_hit_times {
+			if let Some(time) = last_hit_time {
+				if time.elapsed().as_secs_f64() > FLASH_TIME {
+					*last_hit_time = None;
+				}
+			}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let selected = self.selected;
+		let settings = self.pad_settings[selected];
+		let pads = PADS
+			.iter()
+			.zip(self.last_hit_times.iter())
+			.map(|((name, key_code, key_label), last_hit_time)| Pad {
+				name,
+				key: (*key_code, key_label),
+				flash: last_hit_time.map_or(0.0, |time| {
+					(1.0 - time.elapsed().as_secs_f64() / FLASH_TIME).max(0.0) as f32
+				}),
+			})
+			.collect();
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					PadGrid::new(&mut self.pad_grid, pads)
+						.selected(selected)
+						.on_hit(Message::Hit),
+				)
+				.push(Text::new(format!(
+					"{} ({} hits)",
+					PADS[selected].0, self.hits
+				)))
+				.push(
+					Row::new()
+						.spacing(16)
+						.max_width(600)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Volume: {:+.1} dB", settings.volume))
+								.width(Length::Units(200)),
+						)
+						.push(
+							Slider::new(
+								&mut self.volume_slider,
+								MIN_VOLUME..=MAX_VOLUME,
+								settings.volume,
+								move |volume| Message::SetVolume(selected, volume),
+							)
+							.step(0.5)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Row::new()
+						.spacing(16)
+						.max_width(600)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Pitch: {:+.1} semitones", settings.pitch))
+								.width(Length::Units(200)),
+						)
+						.push(
+							Slider::new(
+								&mut self.pitch_slider,
+								-MAX_PITCH..=MAX_PITCH,
+								settings.pitch,
+								move |pitch| Message::SetPitch(selected, pitch),
+							)
+							.step(0.1)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for DrumPadDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "drum_pad_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetVolume(..) => Some("Volume".into()),
+			Message::SetPitch(..) => Some("Pitch".into()),
+			_ => None,
+		}
+	}
+
+	/// The knobs edit whichever pad was hit last, just like the
+	/// sliders.
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Volume" => vec![Message::SetVolume(
+				self.selected,
+				MIN_VOLUME + (MAX_VOLUME - MIN_VOLUME) * value,
+			)],
+			"Pitch" => vec![Message::SetPitch(
+				self.selected,
+				MAX_PITCH * (value * 2.0 - 1.0),
+			)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for DrumPadDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "drum_pad_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		self.pad_settings
+			.iter()
+			.enumerate()
+			.flat_map(|(pad, settings)| {
+				vec![
+					Message::SetVolume(pad, settings.volume),
+					Message::SetPitch(pad, settings.pitch),
+				]
+			})
+			.collect()
+	}
+}
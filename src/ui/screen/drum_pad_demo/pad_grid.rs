@@ -0,0 +1,240 @@
+use iced::{
+	keyboard::KeyCode, mouse::Interaction, Background, Color, Font, HorizontalAlignment, Length,
+	Point, Rectangle, Size, VerticalAlignment,
+};
+use iced_graphics::{Backend, Defaults, Primitive, Renderer};
+use iced_native::{
+	event::Status,
+	keyboard,
+	layout::{Limits, Node},
+	mouse, Clipboard, Element, Event, Layout, Widget,
+};
+
+const COLUMNS: usize = 4;
+const PAD_SIZE: f32 = 90.0;
+const SPACING: f32 = 8.0;
+const PAD_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.1];
+const FLASH_COLOR: [f32; 4] = [1.0, 0.6, 0.0, 1.0];
+const SELECTED_BORDER_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.8];
+const NAME_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const KEY_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.5];
+const NAME_SIZE: f32 = 18.0;
+const KEY_SIZE: f32 = 14.0;
+
+/// One pad on the grid.
+pub struct Pad<'a> {
+	pub name: &'a str,
+	/// The keyboard key that hits the pad, and how to show it.
+	pub key: (KeyCode, &'a str),
+	/// How lit up the pad is (from 0 to 1), which should fade out
+	/// after the pad is hit.
+	pub flash: f32,
+}
+
+/// The keys being held down. Holding a key makes the OS repeat
+/// it, and only the first press should hit a pad. This has to
+/// outlive the widget, which is rebuilt every time the screen
+/// is drawn.
+#[derive(Debug, Default)]
+pub struct State {
+	held_keys: Vec<KeyCode>,
+}
+
+/// A grid of pads that can be hit by clicking them or by
+/// pressing their keys.
+pub struct PadGrid<'a, Message> {
+	state: &'a mut State,
+	pads: Vec<Pad<'a>>,
+	selected: Option<usize>,
+	on_hit: Option<Box<dyn Fn(usize) -> Message + 'a>>,
+}
+
+impl<'a, Message> PadGrid<'a, Message> {
+	pub fn new(state: &'a mut State, pads: Vec<Pad<'a>>) -> Self {
+		Self {
+			state,
+			pads,
+			selected: None,
+			on_hit: None,
+		}
+	}
+
+	/// Outlines one of the pads (by its index).
+	pub fn selected(self, selected: usize) -> Self {
+		Self {
+			selected: Some(selected),
+			..self
+		}
+	}
+
+	pub fn on_hit(self, on_hit: impl Fn(usize) -> Message + 'a) -> Self {
+		Self {
+			on_hit: Some(Box::new(on_hit)),
+			..self
+		}
+	}
+
+	fn rows(&self) -> usize {
+		self.pads.len().div_ceil(COLUMNS)
+	}
+
+	fn size(&self) -> Size {
+		let rows = self.rows() as f32;
+		Size::new(
+			PAD_SIZE * COLUMNS as f32 + SPACING * (COLUMNS - 1) as f32,
+			PAD_SIZE * rows + SPACING * (rows - 1.0).max(0.0),
+		)
+	}
+
+	fn pad_bounds(bounds: Rectangle, index: usize) -> Rectangle {
+		let column = (index % COLUMNS) as f32;
+		let row = (index / COLUMNS) as f32;
+		Rectangle::new(
+			Point::new(
+				bounds.x + column * (PAD_SIZE + SPACING),
+				bounds.y + row * (PAD_SIZE + SPACING),
+			),
+			Size::new(PAD_SIZE, PAD_SIZE),
+		)
+	}
+
+	fn pad_at(&self, bounds: Rectangle, point: Point) -> Option<usize> {
+		(0..self.pads.len()).find(|index| Self::pad_bounds(bounds, *index).contains(point))
+	}
+}
+
+impl<'a, Message, B: Backend> Widget<Message, Renderer<B>> for PadGrid<'a, Message> {
+	fn width(&self) -> Length {
+		Length::Units(self.size().width as u16)
+	}
+
+	fn height(&self) -> Length {
+		Length::Units(self.size().height as u16)
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, limits: &Limits) -> Node {
+		let size = self.size();
+		Node::new(
+			limits
+				.width(Length::Units(size.width as u16))
+				.height(Length::Units(size.height as u16))
+				.resolve(Size::ZERO),
+		)
+	}
+
+	fn on_event(
+		&mut self,
+		event: Event,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		messages: &mut Vec<Message>,
+		_renderer: &Renderer<B>,
+		_clipboard: Option<&dyn Clipboard>,
+	) -> Status {
+		let bounds = layout.bounds();
+		let on_hit = match &self.on_hit {
+			Some(on_hit) => on_hit,
+			None => return Status::Ignored,
+		};
+		match event {
+			Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+				match self.pad_at(bounds, cursor_position) {
+					Some(index) => {
+						messages.push(on_hit(index));
+						Status::Captured
+					}
+					None => Status::Ignored,
+				}
+			}
+			Event::Keyboard(keyboard::Event::KeyPressed { key_code, .. }) => {
+				let index = match self.pads.iter().position(|pad| pad.key.0 == key_code) {
+					Some(index) => index,
+					None => return Status::Ignored,
+				};
+				if !self.state.held_keys.contains(&key_code) {
+					self.state.held_keys.push(key_code);
+					messages.push(on_hit(index));
+				}
+				Status::Captured
+			}
+			Event::Keyboard(keyboard::Event::KeyReleased { key_code, .. }) => {
+				self.state.held_keys.retain(|key| *key != key_code);
+				Status::Ignored
+			}
+			_ => Status::Ignored,
+		}
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		_defaults: &Defaults,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let mut primitives = vec![];
+		for (index, pad) in self.pads.iter().enumerate() {
+			let pad_bounds = Self::pad_bounds(bounds, index);
+			let flash = pad.flash.clamp(0.0, 1.0);
+			let mut color = PAD_COLOR;
+			for (channel, flash_channel) in color.iter_mut().zip(FLASH_COLOR.iter()) {
+				*channel += (flash_channel - *channel) * flash;
+			}
+			let selected = self.selected == Some(index);
+			primitives.push(Primitive::Quad {
+				bounds: pad_bounds,
+				background: Background::Color(color.into()),
+				border_radius: 4.0,
+				border_width: if selected { 2.0 } else { 0.0 },
+				border_color: if selected {
+					SELECTED_BORDER_COLOR.into()
+				} else {
+					Color::TRANSPARENT
+				},
+			});
+			primitives.push(Primitive::Text {
+				content: pad.name.to_string(),
+				bounds: Rectangle::new(pad_bounds.center(), Size::new(PAD_SIZE, 0.0)),
+				color: NAME_COLOR.into(),
+				size: NAME_SIZE,
+				font: Font::Default,
+				horizontal_alignment: HorizontalAlignment::Center,
+				vertical_alignment: VerticalAlignment::Center,
+			});
+			primitives.push(Primitive::Text {
+				content: pad.key.1.to_string(),
+				bounds: Rectangle::new(
+					Point::new(pad_bounds.x + 6.0, pad_bounds.y + 4.0),
+					Size::new(PAD_SIZE, 0.0),
+				),
+				color: KEY_COLOR.into(),
+				size: KEY_SIZE,
+				font: Font::Default,
+				horizontal_alignment: HorizontalAlignment::Left,
+				vertical_alignment: VerticalAlignment::Top,
+			});
+		}
+		let interaction = if self.on_hit.is_some() && self.pad_at(bounds, cursor_position).is_some()
+		{
+			Interaction::Pointer
+		} else {
+			Interaction::default()
+		};
+		(Primitive::Group { primitives }, interaction)
+	}
+
+	fn hash_layout(&self, state: &mut iced_native::Hasher) {
+		use std::hash::Hash;
+		self.pads.len().hash(state)
+	}
+}
+
+impl<'a, Message: 'a, B: Backend + 'a> From<PadGrid<'a, Message>>
+	for Element<'a, Message, Renderer<B>>
+{
+	fn from(pad_grid: PadGrid<'a, Message>) -> Self {
+		Element::new(pad_grid)
+	}
+}
@@ -0,0 +1,225 @@
+use std::error::Error;
+
+use iced::{Align, Button, Column, Length, Radio, Row, Text};
+use kira::{
+	group::GroupId,
+	manager::{AudioManager, AudioManagerSettings},
+	playable::PlayableSettings,
+	sequence::{Sequence, SequenceSettings},
+	sound::SoundId,
+	Duration, Tempo,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, synth},
+	ui::{
+		common::{screen_wrapper::ScreenWrapper, transport::Transport},
+		style::AppStyles,
+	},
+};
+
+const EXPLANATION_TEXT: &str = "This demo runs two metronomes \
+at different tempos, each clicking along with its own sequence.
+
+Each audio manager in Kira has one metronome, so the demo uses \
+two audio managers. A sequence follows the metronome of the \
+audio manager it's started on: its \"wait for interval\" steps \
+and any durations in beats are measured by that metronome.
+
+Pick a metronome and play the phrase to hear it snap to that \
+metronome's next beat and play at its tempo.";
+
+const TEMPOS: [Tempo; 2] = [Tempo(100.0), Tempo(150.0)];
+const PHRASE_FREQUENCIES: [f64; 4] = [523.25, 659.25, 783.99, 1046.5];
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play(usize),
+	Stop(usize),
+	SetMetronomeClick(usize, bool),
+	SetPhraseMetronome(usize),
+	PlayPhrase,
+}
+
+struct Clock {
+	audio_manager: AudioManager,
+	transport: Transport,
+	phrase_group_id: GroupId,
+	phrase_sound_ids: Vec<SoundId>,
+}
+
+impl Clock {
+	fn new(
+		audio_manager_settings: AudioManagerSettings,
+		tempo: Tempo,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(AudioManagerSettings {
+			metronome_settings: Transport::metronome_settings(tempo),
+			..audio_manager_settings
+		})?;
+		let transport = Transport::new(&mut audio_manager, tempo)?;
+		let phrase_group_id = audio_manager.add_group([])?;
+		let mut phrase_sound_ids = vec![];
+		for frequency in &PHRASE_FREQUENCIES {
+			phrase_sound_ids.push(audio_manager.add_sound(synth::blip(
+				*frequency,
+				0.3,
+				PlayableSettings::new(),
+			))?);
+		}
+		Ok(Self {
+			audio_manager,
+			transport,
+			phrase_group_id,
+			phrase_sound_ids,
+		})
+	}
+
+	/// Plays the notes of the phrase half a beat apart,
+	/// starting on this clock's next beat.
+	fn play_phrase(&mut self) -> Result<(), Box<dyn Error>> {
+		let mut sequence =
+			Sequence::<()>::new(SequenceSettings::new().groups([self.phrase_group_id]));
+		sequence.wait_for_interval(1.0);
+		for sound_id in &self.phrase_sound_ids {
+			sequence.play(*sound_id, Default::default());
+			sequence.wait(Duration::Beats(0.5));
+		}
+		self.audio_manager
+			.start_sequence(sequence, Default::default())?;
+		Ok(())
+	}
+}
+
+pub struct DualMetronomeDemo {
+	clocks: Vec<Clock>,
+	phrase_clock: usize,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_phrase_button: iced::button::State,
+}
+
+impl DualMetronomeDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut clocks = vec![];
+		for tempo in &TEMPOS {
+			clocks.push(Clock::new(audio_manager_settings.clone(), *tempo)?);
+		}
+		Ok(Self {
+			clocks,
+			phrase_clock: 0,
+			screen_wrapper: ScreenWrapper::new(
+				"Dual metronome demo".into(),
+				Message::GoToDemoSelect,
+			),
+			play_phrase_button: iced::button::State::new(),
+		})
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play(index) if index < self.clocks.len() => {
+				let clock = &mut self.clocks[index];
+				clock.transport.start(&mut clock.audio_manager)?;
+			}
+			Message::Stop(index) if index < self.clocks.len() => {
+				let clock = &mut self.clocks[index];
+				// stopping the clock stops any phrases waiting on it
+				clock
+					.audio_manager
+					.stop_group(clock.phrase_group_id, Default::default())?;
+				clock.transport.stop(&mut clock.audio_manager)?;
+			}
+			Message::SetMetronomeClick(index, enabled) if index < self.clocks.len() => {
+				let clock = &mut self.clocks[index];
+				clock
+					.transport
+					.set_click_enabled(&mut clock.audio_manager, enabled)?;
+			}
+			Message::SetPhraseMetronome(index) if index < self.clocks.len() => {
+				self.phrase_clock = index;
+			}
+			Message::PlayPhrase => self.clocks[self.phrase_clock].play_phrase()?,
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		for clock in &mut self.clocks {
+			clock.transport.check_for_events(&mut clock.audio_manager);
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let phrase_clock = self.phrase_clock;
+		let phrase_clock_playing = self.clocks[phrase_clock].transport.is_playing();
+		let transports = self.clocks.iter_mut().enumerate().fold(
+			Column::new().spacing(8),
+			|column, (index, clock)| {
+				column.push(clock.transport.view(
+					Message::Play(index),
+					Message::Stop(index),
+					move |enabled| Message::SetMetronomeClick(index, enabled),
+				))
+			},
+		);
+		let phrase_clock_radios = TEMPOS.iter().enumerate().fold(
+			Row::new().spacing(16).align_items(Align::Center),
+			|row, (index, tempo)| {
+				row.push(Radio::new(
+					index,
+					format!("{} BPM", tempo.0),
+					Some(phrase_clock),
+					Message::SetPhraseMetronome,
+				))
+			},
+		);
+		let mut play_phrase_button =
+			Button::new(&mut self.play_phrase_button, Text::new("Play phrase")).style(AppStyles);
+		if phrase_clock_playing {
+			play_phrase_button = play_phrase_button.on_press(Message::PlayPhrase);
+		}
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(transports)
+				.push(
+					Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(Text::new("Quantize phrase to:"))
+						.push(phrase_clock_radios)
+						.push(play_phrase_button),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for DualMetronomeDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "dual_metronome_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		let mut messages: Vec<Message> = self
+			.clocks
+			.iter()
+			.enumerate()
+			.map(|(index, clock)| {
+				Message::SetMetronomeClick(index, clock.transport.click_enabled())
+			})
+			.collect();
+		messages.push(Message::SetPhraseMetronome(self.phrase_clock));
+		messages
+	}
+}
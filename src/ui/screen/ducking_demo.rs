@@ -0,0 +1,488 @@
+use std::{
+	error::Error,
+	time::{Duration as StdDuration, Instant},
+};
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Row, Slider, Text};
+use kira::{
+	arrangement::{Arrangement, ArrangementId},
+	instance::{InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::{ParameterId, Tween},
+	playable::PlayableSettings,
+	sequence::{Sequence, SequenceInstanceId},
+	sound::SoundId,
+	Duration, Tempo,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{
+		create_audio_manager,
+		sound_cache::SoundCache,
+		sound_container::{Selection, SoundContainer},
+		synth,
+	},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &[
+	"underwater demo/drums.ogg",
+	"underwater demo/bass.ogg",
+	"underwater demo/pad.ogg",
+	"underwater demo/lead.ogg",
+];
+
+const EXPLANATION_TEXT: &str = "This demo turns the music down \
+whenever a voice line or sound effect plays, so it's easier to \
+hear, and brings it back up afterwards. This is often called \
+ducking (or sidechaining, after the compressor trick it imitates).
+
+Every music instance has its volume tied to a duck parameter. \
+Each trigger plays its sound and starts a sequence that tweens \
+the parameter down, waits for the sound to finish, and then tweens \
+the parameter back up over the release time. If another sound is \
+still playing, the sequence waits for that one too.
+
+Play line picks a random voice line from a sound container (never \
+the same one twice in a row), like a character chattering over the \
+soundtrack. The attack time sets how quickly the music gets out \
+of the way: too short and the dip is easy to notice, too long and \
+the start of the line gets buried.";
+
+const UNDERWATER_TEMPO: Tempo = Tempo(85.0);
+/// The voice lines from the voice-over demo's English bank.
+const VOICE_FILES: [&str; 3] = ["greeting.wav", "warning.wav", "farewell.wav"];
+const VOICE_NAMES: [&str; 3] = ["Greeting", "Warning", "Farewell"];
+const MIN_ATTACK_TIME: f64 = 0.01;
+const MAX_ATTACK_TIME: f64 = 1.0;
+/// The deepest duck the slider allows (in decibels).
+const MAX_DUCK_DEPTH: f64 = 30.0;
+const MIN_RELEASE_TIME: f64 = 0.1;
+const MAX_RELEASE_TIME: f64 = 3.0;
+
+/// Something that ducks the music when it plays.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Trigger {
+	/// One of the voice lines (by its index).
+	Voice(usize),
+	Sfx,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	Trigger(Trigger),
+	/// Sets how far the music ducks (in decibels).
+	SetDuckDepth(f64),
+	/// Sets how long the music takes to go down (in seconds).
+	SetAttackTime(f64),
+	/// Sets how long the music takes to come back up (in seconds).
+	SetReleaseTime(f64),
+}
+
+pub struct DuckingDemo {
+	audio_manager: AudioManager,
+	music_loop_ids: Vec<ArrangementId>,
+	voice_lines: SoundContainer,
+	/// The line Play line will play. It's picked ahead of time so
+	/// the button's message says which line it is, and a recorded
+	/// session plays the same lines when it's replayed.
+	next_voice: usize,
+	sfx_sound_id: SoundId,
+	duck_parameter_id: ParameterId,
+	music_sequence_id: Option<SequenceInstanceId>,
+	/// The sequence that will bring the music back up.
+	duck_sequence_id: Option<SequenceInstanceId>,
+	/// When the last triggered sound finishes, or `None` if
+	/// nothing is ducking the music.
+	ducked_until: Option<Instant>,
+	/// The sound that ducked the music most recently, if the
+	/// music is ducked.
+	ducking_trigger: Option<Trigger>,
+	duck_depth: f64,
+	attack_time: f64,
+	release_time: f64,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_button: iced::button::State,
+	play_line_button: iced::button::State,
+	trigger_buttons: [iced::button::State; 4],
+	depth_slider: iced::slider::State,
+	attack_slider: iced::slider::State,
+	release_slider: iced::slider::State,
+}
+
+impl DuckingDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let duck_parameter_id = audio_manager.add_parameter(1.0)?;
+		let assets_dir = std::env::current_dir()?.join("assets");
+		let mut music_loop_ids = vec![];
+		for stem in &["drums.ogg", "bass.ogg", "pad.ogg", "lead.ogg"] {
+			let sound_id = audio_manager.add_sound(
+				sound_cache
+					.get(&assets_dir.join("underwater demo").join(stem))?
+					.to_sound(
+						PlayableSettings::new()
+							.semantic_duration(UNDERWATER_TEMPO.beats_to_seconds(16.0)),
+					),
+			)?;
+			music_loop_ids.push(
+				audio_manager
+					.add_arrangement(Arrangement::new_loop(sound_id, Default::default()))?,
+			);
+		}
+		let mut voice_sound_ids = vec![];
+		for file in &VOICE_FILES {
+			voice_sound_ids.push(audio_manager.load_sound(
+				assets_dir.join("voice over demo/en").join(file),
+				PlayableSettings::new(),
+			)?);
+		}
+		let mut voice_lines = SoundContainer::new(voice_sound_ids, Selection::RandomNoRepeat);
+		let next_voice = voice_lines.next_variation();
+		let sfx_sound_id =
+			audio_manager.add_sound(synth::blip(440.0, 0.6, PlayableSettings::new()))?;
+		Ok(Self {
+			audio_manager,
+			music_loop_ids,
+			voice_lines,
+			next_voice,
+			sfx_sound_id,
+			duck_parameter_id,
+			music_sequence_id: None,
+			duck_sequence_id: None,
+			ducked_until: None,
+			ducking_trigger: None,
+			duck_depth: 12.0,
+			attack_time: 0.1,
+			release_time: 1.0,
+			screen_wrapper: ScreenWrapper::new("Ducking demo".into(), Message::GoToDemoSelect),
+			play_button: iced::button::State::new(),
+			play_line_button: iced::button::State::new(),
+			trigger_buttons: Default::default(),
+			depth_slider: iced::slider::State::new(),
+			attack_slider: iced::slider::State::new(),
+			release_slider: iced::slider::State::new(),
+		})
+	}
+
+	/// The volume of the music while it's ducked.
+	fn ducked_volume(&self) -> f64 {
+		10.0f64.powf(-self.duck_depth / 20.0)
+	}
+
+	fn play_music(&mut self) -> Result<(), Box<dyn Error>> {
+		// the stems start together in one sequence so they
+		// stay in sync with each other
+		let mut sequence = Sequence::<()>::new(Default::default());
+		for loop_id in &self.music_loop_ids {
+			sequence.play(
+				*loop_id,
+				InstanceSettings::new().volume(self.duck_parameter_id),
+			);
+		}
+		let (sequence_id, _) = self
+			.audio_manager
+			.start_sequence(sequence, Default::default())?;
+		self.music_sequence_id = Some(sequence_id);
+		Ok(())
+	}
+
+	fn trigger(&mut self, trigger: Trigger) -> Result<(), Box<dyn Error>> {
+		let trigger = match trigger {
+			Trigger::Voice(index) => Trigger::Voice(index.min(VOICE_FILES.len() - 1)),
+			Trigger::Sfx => Trigger::Sfx,
+		};
+		let duration = match trigger {
+			Trigger::Voice(index) => self.voice_lines.sound_id(index).duration(),
+			Trigger::Sfx => self.sfx_sound_id.duration(),
+		};
+		// the old sequence would bring the music back up too early
+		// if the new sound outlasts it, so this one takes over,
+		// waiting for whichever sound finishes last
+		if let Some(sequence_id) = self.duck_sequence_id.take() {
+			self.audio_manager.stop_sequence(sequence_id)?;
+		}
+		let now = Instant::now();
+		let ducked_until =
+			(now + StdDuration::from_secs_f64(duration)).max(self.ducked_until.unwrap_or(now));
+		let mut sequence = Sequence::<()>::new(Default::default());
+		sequence.set_parameter(
+			self.duck_parameter_id,
+			self.ducked_volume(),
+			Some(Tween::linear(self.attack_time)),
+		);
+		sequence.wait(Duration::Seconds((ducked_until - now).as_secs_f64()));
+		sequence.set_parameter(
+			self.duck_parameter_id,
+			1.0,
+			Some(Tween::linear(self.release_time)),
+		);
+		let (sequence_id, _) = self
+			.audio_manager
+			.start_sequence(sequence, Default::default())?;
+		match trigger {
+			Trigger::Voice(index) => {
+				self.voice_lines.play_variation(
+					&mut self.audio_manager,
+					index,
+					Default::default(),
+				)?;
+				self.next_voice = self.voice_lines.next_variation();
+			}
+			Trigger::Sfx => {
+				self.audio_manager
+					.play(self.sfx_sound_id, Default::default())?;
+			}
+		}
+		self.duck_sequence_id = Some(sequence_id);
+		self.ducked_until = Some(ducked_until);
+		self.ducking_trigger = Some(trigger);
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if self.music_sequence_id.is_none() => self.play_music()?,
+			Message::Stop => {
+				if let Some(sequence_id) = self.music_sequence_id.take() {
+					self.audio_manager.stop_sequence_and_instances(
+						sequence_id,
+						StopInstanceSettings::new().fade_tween(Tween::linear(1.0)),
+					)?;
+				}
+			}
+			Message::Trigger(trigger) => self.trigger(trigger)?,
+			Message::SetDuckDepth(depth) => {
+				self.duck_depth = depth.clamp(0.0, MAX_DUCK_DEPTH);
+				// follow the slider right away if the music is ducked
+				if self.ducked_until.is_some() {
+					self.audio_manager.set_parameter(
+						self.duck_parameter_id,
+						self.ducked_volume(),
+						Some(Tween::linear(0.05)),
+					)?;
+				}
+			}
+			Message::SetAttackTime(attack_time) => {
+				self.attack_time = attack_time.clamp(MIN_ATTACK_TIME, MAX_ATTACK_TIME);
+			}
+			Message::SetReleaseTime(release_time) => {
+				self.release_time = release_time.clamp(MIN_RELEASE_TIME, MAX_RELEASE_TIME);
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		if let Some(ducked_until) = self.ducked_until {
+			if Instant::now() >= ducked_until {
+				self.ducked_until = None;
+				self.duck_sequence_id = None;
+				self.ducking_trigger = None;
+			}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.music_sequence_id.is_some();
+		let status_text = match self.ducking_trigger {
+			Some(Trigger::Voice(index)) => format!(
+				"Music ducked by {:.0} dB (line: {})",
+				self.duck_depth, VOICE_NAMES[index]
+			),
+			Some(Trigger::Sfx) => {
+				format!("Music ducked by {:.0} dB (sound effect)", self.duck_depth)
+			}
+			None => "Music at full volume".into(),
+		};
+		let trigger_buttons = self.trigger_buttons.iter_mut().enumerate().fold(
+			Row::new().spacing(8),
+			|row, (index, button)| {
+				let (label, trigger) = match VOICE_NAMES.get(index) {
+					Some(name) => (*name, Trigger::Voice(index)),
+					None => ("Sound effect", Trigger::Sfx),
+				};
+				row.push(
+					Button::new(
+						button,
+						Text::new(label)
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(Message::Trigger(trigger))
+					.width(Length::Units(120))
+					.style(AppStyles),
+				)
+			},
+		);
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Button::new(
+						&mut self.play_button,
+						Text::new(if playing { "Stop music" } else { "Play music" })
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(if playing {
+						Message::Stop
+					} else {
+						Message::Play
+					})
+					.width(Length::Units(120))
+					.style(AppStyles),
+				)
+				.push(
+					Button::new(
+						&mut self.play_line_button,
+						Text::new("Play line")
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(Message::Trigger(Trigger::Voice(self.next_voice)))
+					.width(Length::Units(120))
+					.style(AppStyles),
+				)
+				.push(trigger_buttons)
+				.push(Text::new(status_text))
+				.push(
+					Row::new()
+						.spacing(16)
+						.max_width(600)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Duck depth: -{:.0} dB", self.duck_depth))
+								.width(Length::Units(200)),
+						)
+						.push(
+							Slider::new(
+								&mut self.depth_slider,
+								0.0..=MAX_DUCK_DEPTH,
+								self.duck_depth,
+								Message::SetDuckDepth,
+							)
+							.step(1.0)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Row::new()
+						.spacing(16)
+						.max_width(600)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Attack: {:.2} s", self.attack_time))
+								.width(Length::Units(200)),
+						)
+						.push(
+							Slider::new(
+								&mut self.attack_slider,
+								MIN_ATTACK_TIME..=MAX_ATTACK_TIME,
+								self.attack_time,
+								Message::SetAttackTime,
+							)
+							.step(0.01)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Row::new()
+						.spacing(16)
+						.max_width(600)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Release: {:.1} s", self.release_time))
+								.width(Length::Units(200)),
+						)
+						.push(
+							Slider::new(
+								&mut self.release_slider,
+								MIN_RELEASE_TIME..=MAX_RELEASE_TIME,
+								self.release_time,
+								Message::SetReleaseTime,
+							)
+							.step(0.1)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for DuckingDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "ducking_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetDuckDepth(_) => Some("Duck depth".into()),
+			Message::SetAttackTime(_) => Some("Attack".into()),
+			Message::SetReleaseTime(_) => Some("Release".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Duck depth" => vec![Message::SetDuckDepth(MAX_DUCK_DEPTH * value)],
+			"Attack" => vec![Message::SetAttackTime(
+				MIN_ATTACK_TIME + (MAX_ATTACK_TIME - MIN_ATTACK_TIME) * value,
+			)],
+			"Release" => vec![Message::SetReleaseTime(
+				MIN_RELEASE_TIME + (MAX_RELEASE_TIME - MIN_RELEASE_TIME) * value,
+			)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for DuckingDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "ducking_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetDuckDepth(self.duck_depth),
+			Message::SetAttackTime(self.attack_time),
+			Message::SetReleaseTime(self.release_time),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for DuckingDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.music_sequence_id.is_some(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		self.music_sequence_id.map(|_| "Ducking demo".into())
+	}
+}
@@ -0,0 +1,483 @@
+use std::{error::Error, time::Instant};
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Radio, Row, Slider, Text};
+use kira::{
+	instance::{InstanceId, InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	mixer::{
+		effect::filter::{Filter, FilterSettings},
+		SubTrackId,
+	},
+	parameter::{EaseDirection, Easing, Mapping, ParameterId, Tween},
+	playable::PlayableSettings,
+	sequence::{EventReceiver, Sequence, SequenceInstanceId},
+	sound::SoundId,
+	Duration, Value,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, sound_cache::SoundCache},
+	ui::{
+		common::{curve_plot::CurvePlot, screen_wrapper::ScreenWrapper},
+		style::AppStyles,
+	},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &["drum fill demo/loop.ogg"];
+
+const EXPLANATION_TEXT: &str = "This demo shows every shape a \
+tween can have. A tween's easing curve bends the movement from \
+one value to another, and the direction decides whether the bend \
+happens at the start (in), the end (out), or both (in/out).
+
+Press Play under a curve to sweep the filter cutoff or the \
+volume of the loop from the bottom of its range to the top \
+using that tween, or Play all to hear every curve in a row. \
+Play all builds a sequence that sets the parameter with each \
+tween in turn, waiting out the sweep before moving on, and \
+emits an event as each sweep starts so the screen knows which \
+curve to follow.";
+
+const MIN_SWEEP_DURATION: f64 = 0.25;
+const MAX_SWEEP_DURATION: f64 = 4.0;
+/// How long Play all waits between sweeps (in seconds).
+const PLAY_ALL_GAP: f64 = 0.5;
+const CUTOFF_RANGE: (f64, f64) = (200.0, 10000.0);
+const NUM_POINTS: usize = 50;
+const PLOT_WIDTH: u16 = 120;
+const PLOT_HEIGHT: u16 = 80;
+
+struct Shape {
+	name: &'static str,
+	easing: Easing,
+	ease_direction: EaseDirection,
+}
+
+impl Shape {
+	fn tween(&self, duration: f64) -> Tween {
+		Tween {
+			duration,
+			easing: self.easing,
+			ease_direction: self.ease_direction,
+		}
+	}
+
+	fn points(&self) -> Vec<f32> {
+		let tween = self.tween(1.0);
+		(0..=NUM_POINTS)
+			.map(|i| tween.ease(i as f64 / NUM_POINTS as f64) as f32)
+			.collect()
+	}
+}
+
+// the easing direction doesn't change a linear tween, so it
+// only gets one entry. Each "in" shape starts a new row.
+const SHAPES: [Shape; 10] = [
+	Shape {
+		name: "Linear",
+		easing: Easing::Linear,
+		ease_direction: EaseDirection::In,
+	},
+	Shape {
+		name: "Quadratic in",
+		easing: Easing::PowI(2),
+		ease_direction: EaseDirection::In,
+	},
+	Shape {
+		name: "Quadratic out",
+		easing: Easing::PowI(2),
+		ease_direction: EaseDirection::Out,
+	},
+	Shape {
+		name: "Quadratic in/out",
+		easing: Easing::PowI(2),
+		ease_direction: EaseDirection::InOut,
+	},
+	Shape {
+		name: "Cubic in",
+		easing: Easing::PowI(3),
+		ease_direction: EaseDirection::In,
+	},
+	Shape {
+		name: "Cubic out",
+		easing: Easing::PowI(3),
+		ease_direction: EaseDirection::Out,
+	},
+	Shape {
+		name: "Cubic in/out",
+		easing: Easing::PowI(3),
+		ease_direction: EaseDirection::InOut,
+	},
+	Shape {
+		name: "Square root in",
+		easing: Easing::PowF(0.5),
+		ease_direction: EaseDirection::In,
+	},
+	Shape {
+		name: "Square root out",
+		easing: Easing::PowF(0.5),
+		ease_direction: EaseDirection::Out,
+	},
+	Shape {
+		name: "Square root in/out",
+		easing: Easing::PowF(0.5),
+		ease_direction: EaseDirection::InOut,
+	},
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Target {
+	Cutoff,
+	Volume,
+}
+
+impl Target {
+	const ALL: [Self; 2] = [Self::Cutoff, Self::Volume];
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::Cutoff => "Filter cutoff",
+			Self::Volume => "Volume",
+		}
+	}
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetTarget(Target),
+	/// Sets how long each sweep takes (in seconds).
+	SetSweepDuration(f64),
+	Sweep(usize),
+	PlayAll,
+}
+
+struct Sweep {
+	shape: usize,
+	start_time: Instant,
+	duration: f64,
+}
+
+pub struct EasingGallery {
+	audio_manager: AudioManager,
+	sound_id: SoundId,
+	track_id: SubTrackId,
+	cutoff_parameter_id: ParameterId,
+	volume_parameter_id: ParameterId,
+	instance_id: Option<InstanceId>,
+	target: Target,
+	sweep_duration: f64,
+	sweep: Option<Sweep>,
+	/// The Play all sequence, which emits the index of each
+	/// shape as its sweep starts.
+	play_all_sequence: Option<(SequenceInstanceId, EventReceiver<usize>)>,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_button: iced::button::State,
+	play_all_button: iced::button::State,
+	sweep_duration_slider: iced::slider::State,
+	sweep_buttons: [iced::button::State; 10],
+}
+
+impl EasingGallery {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let cutoff_parameter_id = audio_manager.add_parameter(1.0)?;
+		let volume_parameter_id = audio_manager.add_parameter(1.0)?;
+		let track_id = audio_manager.add_sub_track(Default::default())?;
+		audio_manager.add_effect_to_track(
+			track_id,
+			Filter::new(FilterSettings::new().cutoff(Value::Parameter(
+				cutoff_parameter_id,
+				Mapping {
+					output_range: CUTOFF_RANGE,
+					..Default::default()
+				},
+			))),
+			Default::default(),
+		)?;
+		let assets_base_dir = std::env::current_dir()?.join("assets/drum fill demo");
+		let sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&assets_base_dir.join("loop.ogg"))?
+				.to_sound(PlayableSettings::new().default_loop_start(0.0)),
+		)?;
+		Ok(Self {
+			audio_manager,
+			sound_id,
+			track_id,
+			cutoff_parameter_id,
+			volume_parameter_id,
+			instance_id: None,
+			target: Target::Cutoff,
+			sweep_duration: 2.0,
+			sweep: None,
+			play_all_sequence: None,
+			screen_wrapper: ScreenWrapper::new("Easing gallery".into(), Message::GoToDemoSelect),
+			play_button: iced::button::State::new(),
+			play_all_button: iced::button::State::new(),
+			sweep_duration_slider: iced::slider::State::new(),
+			sweep_buttons: Default::default(),
+		})
+	}
+
+	fn target_parameter_id(&self) -> ParameterId {
+		match self.target {
+			Target::Cutoff => self.cutoff_parameter_id,
+			Target::Volume => self.volume_parameter_id,
+		}
+	}
+
+	/// Stops any sweep (including Play all) and puts both
+	/// parameters back at the top of their range.
+	fn reset_parameters(&mut self) -> Result<(), Box<dyn Error>> {
+		self.sweep = None;
+		if let Some((sequence_id, _)) = self.play_all_sequence.take() {
+			self.audio_manager.stop_sequence(sequence_id)?;
+		}
+		self.audio_manager
+			.set_parameter(self.cutoff_parameter_id, 1.0, None)?;
+		self.audio_manager
+			.set_parameter(self.volume_parameter_id, 1.0, None)?;
+		Ok(())
+	}
+
+	/// How far through the sweep is (from 0 to 1).
+	fn sweep_progress(&self) -> Option<(usize, f32)> {
+		self.sweep.as_ref().map(|sweep| {
+			(
+				sweep.shape,
+				(sweep.start_time.elapsed().as_secs_f64() / sweep.duration) as f32,
+			)
+		})
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play => {
+				self.reset_parameters()?;
+				self.instance_id = Some(
+					self.audio_manager.play(
+						self.sound_id,
+						InstanceSettings::new()
+							.track(self.track_id)
+							.volume(Value::Parameter(
+								self.volume_parameter_id,
+								Default::default(),
+							)),
+					)?,
+				);
+			}
+			Message::Stop => {
+				if let Some(instance_id) = self.instance_id.take() {
+					self.audio_manager.stop_instance(
+						instance_id,
+						StopInstanceSettings::new().fade_tween(Tween::linear(0.1)),
+					)?;
+				}
+				self.reset_parameters()?;
+			}
+			Message::SetTarget(target) => {
+				self.target = target;
+				self.reset_parameters()?;
+			}
+			Message::Sweep(shape) => {
+				self.reset_parameters()?;
+				let parameter_id = self.target_parameter_id();
+				// jump to the bottom of the range, then tween to the top
+				self.audio_manager.set_parameter(parameter_id, 0.0, None)?;
+				self.audio_manager.set_parameter(
+					parameter_id,
+					1.0,
+					Some(SHAPES[shape].tween(self.sweep_duration)),
+				)?;
+				self.sweep = Some(Sweep {
+					shape,
+					start_time: Instant::now(),
+					duration: self.sweep_duration,
+				});
+			}
+			Message::PlayAll => {
+				self.reset_parameters()?;
+				let parameter_id = self.target_parameter_id();
+				let mut sequence = Sequence::new(Default::default());
+				for (index, shape) in SHAPES.iter().enumerate() {
+					sequence.set_parameter(parameter_id, 0.0, None);
+					sequence.set_parameter(
+						parameter_id,
+						1.0,
+						Some(shape.tween(self.sweep_duration)),
+					);
+					sequence.emit(index);
+					sequence.wait(Duration::Seconds(self.sweep_duration + PLAY_ALL_GAP));
+				}
+				self.play_all_sequence = Some(
+					self.audio_manager
+						.start_sequence(sequence, Default::default())?,
+				);
+			}
+			Message::SetSweepDuration(duration) => {
+				self.sweep_duration = duration.clamp(MIN_SWEEP_DURATION, MAX_SWEEP_DURATION);
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		if let Some((_, events)) = &mut self.play_all_sequence {
+			while let Some(shape) = events.pop().copied() {
+				self.sweep = Some(Sweep {
+					shape,
+					start_time: Instant::now(),
+					duration: self.sweep_duration,
+				});
+			}
+		}
+		if let Some((_, progress)) = self.sweep_progress() {
+			if progress >= 1.0 {
+				self.sweep = None;
+			}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.instance_id.is_some();
+		let sweep_progress = self.sweep_progress();
+
+		let play_button = Button::new(
+			&mut self.play_button,
+			Text::new(if playing { "Stop" } else { "Play" })
+				.width(Length::Fill)
+				.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.on_press(if playing {
+			Message::Stop
+		} else {
+			Message::Play
+		})
+		.width(Length::Units(100))
+		.style(AppStyles);
+		let target = self.target;
+		let target_radios = Target::ALL.iter().fold(
+			Row::new().spacing(16).align_items(Align::Center),
+			|row, option| {
+				row.push(Radio::new(
+					*option,
+					option.name(),
+					Some(target),
+					Message::SetTarget,
+				))
+			},
+		);
+
+		let mut play_all_button = Button::new(
+			&mut self.play_all_button,
+			Text::new("Play all")
+				.width(Length::Fill)
+				.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.width(Length::Units(100))
+		.style(AppStyles);
+		if playing {
+			play_all_button = play_all_button.on_press(Message::PlayAll);
+		}
+
+		let mut gallery = Column::new().spacing(16);
+		let mut row = Row::new().spacing(16);
+		for (index, (shape, state)) in SHAPES.iter().zip(self.sweep_buttons.iter_mut()).enumerate()
+		{
+			if index > 0 && shape.ease_direction == EaseDirection::In {
+				gallery = gallery.push(row);
+				row = Row::new().spacing(16);
+			}
+			let mut plot = CurvePlot::new(shape.points(), PLOT_WIDTH, PLOT_HEIGHT);
+			if let Some((sweep_shape, progress)) = sweep_progress {
+				if sweep_shape == index {
+					plot = plot.marker(progress);
+				}
+			}
+			let mut button = Button::new(
+				state,
+				Text::new("Play")
+					.width(Length::Fill)
+					.horizontal_alignment(HorizontalAlignment::Center),
+			)
+			.width(Length::Units(PLOT_WIDTH))
+			.style(AppStyles);
+			if playing {
+				button = button.on_press(Message::Sweep(index));
+			}
+			row = row.push(
+				Column::new()
+					.spacing(4)
+					.push(Text::new(shape.name).size(16))
+					.push(plot)
+					.push(button),
+			);
+		}
+		gallery = gallery.push(row);
+
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(play_button)
+						.push(play_all_button)
+						.push(Text::new("Sweep:"))
+						.push(target_radios),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Sweep time: {:.2}s", self.sweep_duration))
+								.width(Length::Units(150)),
+						)
+						.push(
+							Slider::new(
+								&mut self.sweep_duration_slider,
+								MIN_SWEEP_DURATION..=MAX_SWEEP_DURATION,
+								self.sweep_duration,
+								Message::SetSweepDuration,
+							)
+							.step(0.05)
+							.style(AppStyles),
+						),
+				)
+				.push(gallery)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for EasingGallery {
+	type Message = Message;
+
+	const NAME: &'static str = "easing_gallery";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetTarget(self.target),
+			Message::SetSweepDuration(self.sweep_duration),
+		]
+	}
+}
@@ -0,0 +1,341 @@
+use std::{error::Error, time::Instant};
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Row, Slider, Text};
+use kira::{
+	instance::{InstanceId, InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::{Mapping, ParameterId, Tween},
+	playable::PlayableSettings,
+	sound::SoundId,
+	Value,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, synth},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+const EXPLANATION_TEXT: &str = "Car engines in games are usually \
+built from a few loops recorded at fixed speeds - here idle, \
+mid and high. As the engine speeds up, each loop is pitched up \
+from the speed it was recorded at, and the loops crossfade so \
+the one closest to the current speed is loudest. No loop has \
+to stretch far from where it was recorded, which keeps the \
+engine from sounding like a tape being sped up.
+
+The throttle doesn't set the speed directly. A tiny engine \
+model works out the RPM the throttle is asking for, and the RPM \
+chases it every frame: quickly when revving up, more slowly \
+when coasting back down.
+
+The RPM is a parameter, and each layer's pitch reads it through \
+a mapping that divides by the RPM the layer was recorded at, so \
+the pitch needs no code at all. The crossfade can't be a single \
+mapping, since the mid layer fades in and then out again, so \
+each layer's volume is a parameter of its own that the screen \
+sets along with the RPM.";
+
+const IDLE_RPM: f64 = 800.0;
+const MAX_RPM: f64 = 6000.0;
+/// How long the RPM takes to get most of the way to where the
+/// throttle wants it (in seconds), going up and coming down.
+const REV_UP_TIME: f64 = 0.4;
+const REV_DOWN_TIME: f64 = 1.2;
+/// A four-stroke, four-cylinder engine fires twice per
+/// revolution, so the firing rate is the RPM divided by 30.
+const RPM_PER_FIRING_RATE: f64 = 30.0;
+
+/// The engine loops, as (name, firing rate when recorded,
+/// roughness).
+const LAYERS: [(&str, f64, f64); 3] = [
+	("Idle", 27.0, 0.3),
+	("Mid", 80.0, 0.5),
+	("High", 160.0, 0.7),
+];
+
+/// The RPM a layer was recorded at.
+fn layer_rpm(layer: usize) -> f64 {
+	LAYERS[layer].1 * RPM_PER_FIRING_RATE
+}
+
+/// Works out how loud each layer should be at an RPM. Only the
+/// two layers either side of the RPM play, with an equal-power
+/// crossfade between them so the engine doesn't dip in the middle.
+fn layer_volumes(rpm: f64) -> [f64; 3] {
+	let mut volumes = [0.0; 3];
+	if rpm <= layer_rpm(0) {
+		volumes[0] = 1.0;
+	} else if rpm >= layer_rpm(LAYERS.len() - 1) {
+		volumes[LAYERS.len() - 1] = 1.0;
+	} else {
+		let lower = (0..LAYERS.len() - 1)
+			.rev()
+			.find(|layer| layer_rpm(*layer) <= rpm)
+			.unwrap_or(0);
+		let blend = (rpm - layer_rpm(lower)) / (layer_rpm(lower + 1) - layer_rpm(lower));
+		volumes[lower] = (blend * std::f64::consts::FRAC_PI_2).cos();
+		volumes[lower + 1] = (blend * std::f64::consts::FRAC_PI_2).sin();
+	}
+	volumes
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Start,
+	Stop,
+	/// Sets how far the throttle is open (from 0 to 1).
+	SetThrottle(f64),
+}
+
+pub struct EngineDemo {
+	audio_manager: AudioManager,
+	layer_sound_ids: Vec<SoundId>,
+	rpm_parameter_id: ParameterId,
+	volume_parameter_ids: Vec<ParameterId>,
+	instance_ids: Vec<InstanceId>,
+	throttle: f64,
+	rpm: f64,
+	last_tick: Instant,
+	screen_wrapper: ScreenWrapper<Message>,
+	start_button: iced::button::State,
+	throttle_slider: iced::slider::State,
+}
+
+impl EngineDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let mut layer_sound_ids = vec![];
+		let mut volume_parameter_ids = vec![];
+		let volumes = layer_volumes(IDLE_RPM);
+		for (layer, (_, firing_rate, roughness)) in LAYERS.iter().enumerate() {
+			layer_sound_ids.push(audio_manager.add_sound(synth::engine(
+				*firing_rate,
+				*roughness,
+				PlayableSettings::new().default_loop_start(0.0),
+			))?);
+			volume_parameter_ids.push(audio_manager.add_parameter(volumes[layer])?);
+		}
+		let rpm_parameter_id = audio_manager.add_parameter(IDLE_RPM)?;
+		Ok(Self {
+			audio_manager,
+			layer_sound_ids,
+			rpm_parameter_id,
+			volume_parameter_ids,
+			instance_ids: vec![],
+			throttle: 0.0,
+			rpm: IDLE_RPM,
+			last_tick: Instant::now(),
+			screen_wrapper: ScreenWrapper::new("Engine demo".into(), Message::GoToDemoSelect),
+			start_button: iced::button::State::new(),
+			throttle_slider: iced::slider::State::new(),
+		})
+	}
+
+	/// The RPM the engine settles at for the current throttle.
+	fn target_rpm(&self) -> f64 {
+		IDLE_RPM + (MAX_RPM - IDLE_RPM) * self.throttle
+	}
+
+	/// Points the RPM and layer volume parameters at the current
+	/// RPM. Like the doppler demo, the tween lasts about as long
+	/// as a frame, and a tween time of 0 sets them right away.
+	fn update_parameters(&mut self, tween_time: f64) -> Result<(), Box<dyn Error>> {
+		let tween = if tween_time > 0.0 {
+			Some(Tween::linear(tween_time))
+		} else {
+			None
+		};
+		self.audio_manager
+			.set_parameter(self.rpm_parameter_id, self.rpm, tween)?;
+		for (parameter_id, volume) in self
+			.volume_parameter_ids
+			.iter()
+			.zip(layer_volumes(self.rpm).iter())
+		{
+			self.audio_manager
+				.set_parameter(*parameter_id, *volume, tween)?;
+		}
+		Ok(())
+	}
+
+	fn start(&mut self) -> Result<(), Box<dyn Error>> {
+		self.rpm = IDLE_RPM;
+		self.update_parameters(0.0)?;
+		for layer in 0..LAYERS.len() {
+			let instance_id = self.audio_manager.play(
+				self.layer_sound_ids[layer],
+				InstanceSettings::new()
+					.pitch(Value::Parameter(
+						self.rpm_parameter_id,
+						Mapping {
+							input_range: (0.0, layer_rpm(layer)),
+							output_range: (0.0, 1.0),
+							..Default::default()
+						},
+					))
+					.volume(self.volume_parameter_ids[layer]),
+			)?;
+			self.instance_ids.push(instance_id);
+		}
+		self.last_tick = Instant::now();
+		Ok(())
+	}
+
+	fn stop(&mut self) -> Result<(), Box<dyn Error>> {
+		for instance_id in self.instance_ids.drain(..) {
+			self.audio_manager.stop_instance(
+				instance_id,
+				StopInstanceSettings::new().fade_tween(Tween::linear(0.3)),
+			)?;
+		}
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Start if self.instance_ids.is_empty() => self.start()?,
+			Message::Stop => self.stop()?,
+			Message::SetThrottle(throttle) => self.throttle = throttle.clamp(0.0, 1.0),
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		let delta_time = self.last_tick.elapsed().as_secs_f64();
+		self.last_tick = Instant::now();
+		if self.instance_ids.is_empty() {
+			return Ok(());
+		}
+		let target_rpm = self.target_rpm();
+		let response_time = if target_rpm > self.rpm {
+			REV_UP_TIME
+		} else {
+			REV_DOWN_TIME
+		};
+		self.rpm += (target_rpm - self.rpm) * (1.0 - (-delta_time / response_time).exp());
+		self.update_parameters(delta_time)
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let running = !self.instance_ids.is_empty();
+		let volumes = layer_volumes(self.rpm);
+		let layers = LAYERS.iter().enumerate().fold(
+			Column::new().spacing(4),
+			|column, (layer, (name, _, _))| {
+				column.push(Text::new(format!(
+					"{}: {:>3.0}% - pitch: {:.2}x",
+					name,
+					volumes[layer] * 100.0,
+					self.rpm / layer_rpm(layer)
+				)))
+			},
+		);
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Button::new(
+						&mut self.start_button,
+						Text::new(if running {
+							"Stop engine"
+						} else {
+							"Start engine"
+						})
+						.width(Length::Fill)
+						.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(if running {
+						Message::Stop
+					} else {
+						Message::Start
+					})
+					.width(Length::Units(150))
+					.style(AppStyles),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Throttle: {:.0}%", self.throttle * 100.0))
+								.width(Length::Units(200)),
+						)
+						.push(
+							Slider::new(
+								&mut self.throttle_slider,
+								0.0..=1.0,
+								self.throttle,
+								Message::SetThrottle,
+							)
+							.step(0.01)
+							.style(AppStyles),
+						),
+				)
+				.push(Text::new(format!("{:.0} RPM", self.rpm)).size(32))
+				.push(layers)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for EngineDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "engine_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetThrottle(_) => Some("Throttle".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Throttle" => vec![Message::SetThrottle(value)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for EngineDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "engine_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![Message::SetThrottle(self.throttle)]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for EngineDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			!self.instance_ids.is_empty(),
+			Message::Start,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if self.instance_ids.is_empty() {
+			None
+		} else {
+			Some("Engine demo".into())
+		}
+	}
+}
@@ -0,0 +1,449 @@
+use std::{error::Error, f64::consts::PI};
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Radio, Row, Slider, Text};
+use kira::{
+	instance::{InstanceId, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	mixer::effect::filter::{FilterMode, FilterSettings},
+	parameter::{ParameterId, Tween},
+	playable::PlayableSettings,
+	sound::SoundId,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{
+		create_audio_manager, effect::filter_bank::FilterBank, sound_cache::SoundCache,
+		wet_dry::WetDry,
+	},
+	ui::{
+		common::{
+			curve_plot::CurvePlot, screen_wrapper::ScreenWrapper, wet_dry_control::WetDryControl,
+		},
+		style::AppStyles,
+	},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &["drum fill demo/loop.ogg"];
+
+const EXPLANATION_TEXT: &str = "Kira's filter effect can be a \
+low-pass, high-pass, band-pass, or notch filter. The cutoff and \
+resonance can be tied to parameters, so the sliders just set \
+parameters with a short tween, but the mode is fixed when the \
+filter is created.
+
+So this demo runs four filters side by side, one of each mode, \
+all reading the same cutoff and resonance parameters. Each \
+filter's output is turned up or down by a parameter that's 1 for \
+the selected mode and 0 for the others, and the outputs are added \
+together. Switching modes crossfades between the filters, which is \
+smoother than swapping the effect out. Like the other effect \
+demos, the filters can be blended with the unfiltered loop or \
+bypassed.
+
+The plot shows how much each frequency is boosted or cut, from \
+20 Hz to 20 kHz.";
+
+const MIN_CUTOFF: f64 = 20.0;
+const MAX_CUTOFF: f64 = 20000.0;
+/// Kira's filter becomes unstable with the resonance all the way
+/// up, so the slider stops short of that.
+const MAX_RESONANCE: f64 = 0.95;
+const INITIAL_CUTOFF_POSITION: f64 = 0.6;
+const INITIAL_RESONANCE: f64 = 0.5;
+const INITIAL_MIX: f64 = 1.0;
+const MODE_FADE_TIME: f64 = 0.05;
+const NUM_PLOT_POINTS: usize = 200;
+const PLOT_WIDTH: u16 = 500;
+const PLOT_HEIGHT: u16 = 150;
+/// The range of the plot (in decibels).
+const PLOT_RANGE: (f64, f64) = (-40.0, 30.0);
+/// The sample rate the plot assumes, which only makes a
+/// difference near the top of the range.
+const PLOT_SAMPLE_RATE: f64 = 48000.0;
+
+/// The filter modes. Kira's `FilterMode` doesn't implement the
+/// traits messages need, so the demo has its own.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Mode {
+	LowPass,
+	HighPass,
+	BandPass,
+	Notch,
+}
+
+impl Mode {
+	const ALL: [Self; 4] = [Self::LowPass, Self::HighPass, Self::BandPass, Self::Notch];
+
+	fn index(self) -> usize {
+		Self::ALL.iter().position(|mode| *mode == self).unwrap()
+	}
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::LowPass => "Low-pass",
+			Self::HighPass => "High-pass",
+			Self::BandPass => "Band-pass",
+			Self::Notch => "Notch",
+		}
+	}
+
+	fn filter_mode(self) -> FilterMode {
+		match self {
+			Self::LowPass => FilterMode::LowPass,
+			Self::HighPass => FilterMode::HighPass,
+			Self::BandPass => FilterMode::BandPass,
+			Self::Notch => FilterMode::Notch,
+		}
+	}
+
+	/// How much the filter boosts or cuts a frequency (in
+	/// decibels), worked out from the same formulas Kira's
+	/// filter uses.
+	fn response(self, cutoff: f64, resonance: f64, frequency: f64) -> f64 {
+		let k = 2.0 - 1.9 * resonance.clamp(0.0, 1.0);
+		// the filter is tuned with a tangent, which squeezes
+		// frequencies near the top of the range together
+		let w = (PI * frequency / PLOT_SAMPLE_RATE).tan() / (PI * cutoff / PLOT_SAMPLE_RATE).tan();
+		// the response at s = jw is numerator / (s^2 + ks + 1)
+		let (numerator_re, numerator_im) = match self {
+			Self::LowPass => (1.0, 0.0),
+			Self::HighPass => (-w * w, 0.0),
+			Self::BandPass => (0.0, w),
+			Self::Notch => (1.0 - w * w, 0.0),
+		};
+		let (denominator_re, denominator_im) = (1.0 - w * w, k * w);
+		let magnitude = ((numerator_re * numerator_re + numerator_im * numerator_im)
+			/ (denominator_re * denominator_re + denominator_im * denominator_im))
+			.sqrt();
+		20.0 * magnitude.max(1e-6).log10()
+	}
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	PlayLoop,
+	StopLoop,
+	SetMode(Mode),
+	/// Sets the cutoff (from 0 to 1, spread evenly over the
+	/// octaves from 20 Hz to 20 kHz).
+	SetCutoff(f64),
+	SetResonance(f64),
+	SetMix(f64),
+	SetBypassed(bool),
+}
+
+/// Converts a slider position to a cutoff frequency (in Hz).
+fn cutoff_frequency(position: f64) -> f64 {
+	MIN_CUTOFF * (MAX_CUTOFF / MIN_CUTOFF).powf(position)
+}
+
+pub struct FilterDemo {
+	audio_manager: AudioManager,
+	wet_dry: WetDry,
+	loop_sound_id: SoundId,
+	/// The parameter each mode's filter output is turned up or
+	/// down by, in the same order as [`Mode::ALL`].
+	mode_volume_parameter_ids: Vec<ParameterId>,
+	cutoff_parameter_id: ParameterId,
+	resonance_parameter_id: ParameterId,
+	mode: Mode,
+	cutoff_position: f64,
+	resonance: f64,
+	/// The loop's dry and wet instances.
+	loop_instance_ids: Option<[InstanceId; 2]>,
+	screen_wrapper: ScreenWrapper<Message>,
+	wet_dry_control: WetDryControl<Message>,
+	loop_button: iced::button::State,
+	cutoff_slider: iced::slider::State,
+	resonance_slider: iced::slider::State,
+}
+
+impl FilterDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let cutoff_parameter_id =
+			audio_manager.add_parameter(cutoff_frequency(INITIAL_CUTOFF_POSITION))?;
+		let resonance_parameter_id = audio_manager.add_parameter(INITIAL_RESONANCE)?;
+		let mut mode_volume_parameter_ids = vec![];
+		for mode in Mode::ALL.iter() {
+			mode_volume_parameter_ids.push(
+				audio_manager.add_parameter(if *mode == Mode::LowPass { 1.0 } else { 0.0 })?,
+			);
+		}
+		let wet_dry = WetDry::new(&mut audio_manager, INITIAL_MIX)?;
+		audio_manager.add_effect_to_track(
+			wet_dry.wet_track_id(),
+			FilterBank::new(Mode::ALL.iter().zip(&mode_volume_parameter_ids).map(
+				|(mode, volume_parameter_id)| {
+					(
+						FilterSettings::new()
+							.mode(mode.filter_mode())
+							.cutoff(cutoff_parameter_id)
+							.resonance(resonance_parameter_id),
+						*volume_parameter_id,
+					)
+				},
+			)),
+			Default::default(),
+		)?;
+		// WetDry plays every sound twice at the same time,
+		// which a cooldown would block
+		let loop_sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&std::env::current_dir()?.join("assets/drum fill demo/loop.ogg"))?
+				.to_sound(PlayableSettings {
+					cooldown: None,
+					..PlayableSettings::new().default_loop_start(0.0)
+				}),
+		)?;
+		Ok(Self {
+			audio_manager,
+			wet_dry,
+			loop_sound_id,
+			mode_volume_parameter_ids,
+			cutoff_parameter_id,
+			resonance_parameter_id,
+			mode: Mode::LowPass,
+			cutoff_position: INITIAL_CUTOFF_POSITION,
+			resonance: INITIAL_RESONANCE,
+			loop_instance_ids: None,
+			screen_wrapper: ScreenWrapper::new("Filter demo".into(), Message::GoToDemoSelect),
+			wet_dry_control: WetDryControl::new(Message::SetMix, Message::SetBypassed),
+			loop_button: iced::button::State::new(),
+			cutoff_slider: iced::slider::State::new(),
+			resonance_slider: iced::slider::State::new(),
+		})
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::PlayLoop if self.loop_instance_ids.is_none() => {
+				self.loop_instance_ids = Some(self.wet_dry.play(
+					&mut self.audio_manager,
+					self.loop_sound_id,
+					Default::default(),
+				)?);
+			}
+			Message::StopLoop => {
+				for instance_id in self.loop_instance_ids.take().iter().flatten() {
+					self.audio_manager.stop_instance(
+						*instance_id,
+						StopInstanceSettings::new().fade_tween(Tween::linear(0.25)),
+					)?;
+				}
+			}
+			Message::SetMode(mode) => {
+				self.mode = mode;
+				for (index, volume_parameter_id) in
+					self.mode_volume_parameter_ids.iter().enumerate()
+				{
+					self.audio_manager.set_parameter(
+						*volume_parameter_id,
+						if index == mode.index() { 1.0 } else { 0.0 },
+						Some(Tween::linear(MODE_FADE_TIME)),
+					)?;
+				}
+			}
+			Message::SetCutoff(position) => {
+				self.cutoff_position = position.clamp(0.0, 1.0);
+				self.audio_manager.set_parameter(
+					self.cutoff_parameter_id,
+					cutoff_frequency(self.cutoff_position),
+					Some(Tween::linear(0.05)),
+				)?;
+			}
+			Message::SetResonance(resonance) => {
+				self.resonance = resonance.clamp(0.0, MAX_RESONANCE);
+				self.audio_manager.set_parameter(
+					self.resonance_parameter_id,
+					self.resonance,
+					Some(Tween::linear(0.05)),
+				)?;
+			}
+			Message::SetMix(mix) => {
+				self.wet_dry.set_mix(
+					&mut self.audio_manager,
+					mix.clamp(0.0, 1.0),
+					Some(Tween::linear(0.05)),
+				)?;
+			}
+			Message::SetBypassed(bypassed) => {
+				self.wet_dry
+					.set_bypassed(&mut self.audio_manager, bypassed)?;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	fn plot_points(&self) -> Vec<f32> {
+		let cutoff = cutoff_frequency(self.cutoff_position);
+		let (min, max) = PLOT_RANGE;
+		(0..NUM_PLOT_POINTS)
+			.map(|i| {
+				let frequency = cutoff_frequency(i as f64 / (NUM_PLOT_POINTS - 1) as f64);
+				let response = self.mode.response(cutoff, self.resonance, frequency);
+				((response - min) / (max - min)).clamp(0.0, 1.0) as f32
+			})
+			.collect()
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let looping = self.loop_instance_ids.is_some();
+		let plot_points = self.plot_points();
+		let mix = self.wet_dry.mix();
+		let bypassed = self.wet_dry.bypassed();
+		let mode = self.mode;
+		let modes = Mode::ALL.iter().fold(
+			Row::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(Text::new("Mode:")),
+			|row, option| {
+				row.push(Radio::new(
+					*option,
+					option.name(),
+					Some(mode),
+					Message::SetMode,
+				))
+			},
+		);
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Button::new(
+						&mut self.loop_button,
+						Text::new(if looping { "Stop loop" } else { "Play loop" })
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(if looping {
+						Message::StopLoop
+					} else {
+						Message::PlayLoop
+					})
+					.width(Length::Units(120))
+					.style(AppStyles),
+				)
+				.push(modes)
+				.push(CurvePlot::new(plot_points, PLOT_WIDTH, PLOT_HEIGHT))
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!(
+								"Cutoff: {:.0} Hz",
+								cutoff_frequency(self.cutoff_position)
+							))
+							.width(Length::Units(180)),
+						)
+						.push(
+							Slider::new(
+								&mut self.cutoff_slider,
+								0.0..=1.0,
+								self.cutoff_position,
+								Message::SetCutoff,
+							)
+							.step(0.001)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Resonance: {:.2}", self.resonance))
+								.width(Length::Units(180)),
+						)
+						.push(
+							Slider::new(
+								&mut self.resonance_slider,
+								0.0..=MAX_RESONANCE,
+								self.resonance,
+								Message::SetResonance,
+							)
+							.step(0.01)
+							.style(AppStyles),
+						),
+				)
+				.push(self.wet_dry_control.view(mix, bypassed))
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for FilterDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "filter_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetCutoff(_) => Some("Cutoff".into()),
+			Message::SetResonance(_) => Some("Resonance".into()),
+			Message::SetMix(_) => Some("Wet level".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Cutoff" => vec![Message::SetCutoff(value)],
+			"Resonance" => vec![Message::SetResonance(value * MAX_RESONANCE)],
+			"Wet level" => vec![Message::SetMix(value)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for FilterDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "filter_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetMode(self.mode),
+			Message::SetCutoff(self.cutoff_position),
+			Message::SetResonance(self.resonance),
+			Message::SetMix(self.wet_dry.mix()),
+			Message::SetBypassed(self.wet_dry.bypassed()),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for FilterDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.loop_instance_ids.is_some(),
+			Message::PlayLoop,
+			Message::StopLoop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		self.loop_instance_ids
+			.map(|_| format!("Filter demo ({})", self.mode.name()))
+	}
+}
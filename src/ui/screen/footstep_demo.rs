@@ -0,0 +1,309 @@
+use std::{error::Error, time::Instant};
+
+use iced::{Align, Button, Checkbox, Column, HorizontalAlignment, Length, Row, Slider, Text};
+use kira::{
+	instance::InstanceSettings,
+	manager::{AudioManager, AudioManagerSettings},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{
+		create_audio_manager,
+		sound_bank::SoundBank,
+		sound_container::{Selection, SoundContainer},
+	},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+const EXPLANATION_TEXT: &str = "Playing the exact same sample \
+for every footstep sounds robotic, like a machine gun. Games \
+hide the repetition by picking one of a few recordings at \
+random and nudging the pitch and volume of each instance a \
+little.
+
+Each step here picks one of four samples (never the same one \
+twice in a row) and plays it with a random playback rate and \
+volume within the ranges set by the sliders. Turn everything \
+off to hear the difference. The samples go into a sound \
+container, the same helper the sound container demo shows off.";
+
+/// The footstep samples in "assets/footstep demo".
+const STEP_FILES: [&str; 4] = ["step 1.wav", "step 2.wav", "step 3.wav", "step 4.wav"];
+/// How long to wait between steps while walking (in seconds).
+const STEP_INTERVAL: f64 = 0.5;
+/// The highest pitch variation the slider allows (in semitones).
+const MAX_PITCH_VARIATION: f64 = 4.0;
+/// The highest volume variation the slider allows (in decibels).
+const MAX_VOLUME_VARIATION: f64 = 12.0;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Step,
+	SetWalking(bool),
+	SetRandomSample(bool),
+	/// Sets how far the pitch can stray from the original
+	/// (in semitones, up or down).
+	SetPitchVariation(f64),
+	/// Sets how much quieter than the original a step can be
+	/// (in decibels).
+	SetVolumeVariation(f64),
+}
+
+pub struct FootstepDemo {
+	audio_manager: AudioManager,
+	/// The footstep samples, with the pitch and volume
+	/// variation from the sliders.
+	steps: SoundContainer,
+	random_sample: bool,
+	/// When the last step was taken while walking, or `None`
+	/// if the player is standing still.
+	walking_since: Option<Instant>,
+	screen_wrapper: ScreenWrapper<Message>,
+	step_button: iced::button::State,
+	walk_button: iced::button::State,
+	pitch_slider: iced::slider::State,
+	volume_slider: iced::slider::State,
+}
+
+impl FootstepDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let bank = SoundBank::load(
+			&mut audio_manager,
+			&std::env::current_dir()?.join("assets/footstep demo"),
+			&STEP_FILES,
+		)?;
+		let sound_ids: Vec<_> = STEP_FILES
+			.iter()
+			.filter_map(|file_name| bank.sound_id(file_name.trim_end_matches(".wav")))
+			.collect();
+		if sound_ids.is_empty() {
+			return Err("no footstep samples were loaded".into());
+		}
+		let mut steps = SoundContainer::new(sound_ids, Selection::RandomNoRepeat);
+		steps.set_pitch_variation(1.0);
+		steps.set_volume_variation(3.0);
+		Ok(Self {
+			audio_manager,
+			steps,
+			random_sample: true,
+			walking_since: None,
+			screen_wrapper: ScreenWrapper::new("Footstep demo".into(), Message::GoToDemoSelect),
+			step_button: iced::button::State::new(),
+			walk_button: iced::button::State::new(),
+			pitch_slider: iced::slider::State::new(),
+			volume_slider: iced::slider::State::new(),
+		})
+	}
+
+	/// Plays the next step. Random picks never repeat the sample
+	/// that just played, since hearing the same one twice in a
+	/// row is what gives the repetition away.
+	fn step(&mut self) -> Result<(), Box<dyn Error>> {
+		if self.random_sample {
+			self.steps
+				.play(&mut self.audio_manager, InstanceSettings::new())?;
+		} else {
+			self.steps
+				.play_variation(&mut self.audio_manager, 0, InstanceSettings::new())?;
+		}
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Step => self.step()?,
+			Message::SetWalking(true) if self.walking_since.is_none() => {
+				self.step()?;
+				self.walking_since = Some(Instant::now());
+			}
+			Message::SetWalking(false) => self.walking_since = None,
+			Message::SetRandomSample(random_sample) => self.random_sample = random_sample,
+			Message::SetPitchVariation(variation) => {
+				self.steps
+					.set_pitch_variation(variation.min(MAX_PITCH_VARIATION));
+			}
+			Message::SetVolumeVariation(variation) => {
+				self.steps
+					.set_volume_variation(variation.min(MAX_VOLUME_VARIATION));
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	/// Whether steps are being played from the UI timer.
+	pub fn plays_from_ui_timer(&self) -> bool {
+		self.walking_since.is_some()
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		if let Some(walking_since) = self.walking_since {
+			if walking_since.elapsed().as_secs_f64() >= STEP_INTERVAL {
+				self.step()?;
+				self.walking_since = Some(Instant::now());
+			}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let walking = self.walking_since.is_some();
+		let pitch_variation = self.steps.pitch_variation();
+		let volume_variation = self.steps.volume_variation();
+		let step_text = match self.steps.last_played() {
+			Some(step) => format!(
+				"Sample {} - pitch: {:.2}x - volume: {:.1} dB",
+				step.index + 1,
+				step.pitch,
+				20.0 * step.volume.log10()
+			),
+			None => "No steps yet".into(),
+		};
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Row::new()
+						.spacing(16)
+						.push(
+							Button::new(
+								&mut self.step_button,
+								Text::new("Step")
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(Message::Step)
+							.width(Length::Units(120))
+							.style(AppStyles),
+						)
+						.push(
+							Button::new(
+								&mut self.walk_button,
+								Text::new(if walking { "Stop walking" } else { "Walk" })
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(Message::SetWalking(!walking))
+							.width(Length::Units(120))
+							.style(AppStyles),
+						),
+				)
+				.push(Text::new(step_text))
+				.push(Checkbox::new(
+					self.random_sample,
+					"Pick a random sample",
+					Message::SetRandomSample,
+				))
+				.push(
+					Row::new()
+						.spacing(16)
+						.max_width(600)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!(
+								"Pitch variation: ±{:.1} semitones",
+								pitch_variation
+							))
+							.width(Length::Units(300)),
+						)
+						.push(
+							Slider::new(
+								&mut self.pitch_slider,
+								0.0..=MAX_PITCH_VARIATION,
+								pitch_variation,
+								Message::SetPitchVariation,
+							)
+							.step(0.1)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Row::new()
+						.spacing(16)
+						.max_width(600)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!(
+								"Volume variation: up to -{:.1} dB",
+								volume_variation
+							))
+							.width(Length::Units(300)),
+						)
+						.push(
+							Slider::new(
+								&mut self.volume_slider,
+								0.0..=MAX_VOLUME_VARIATION,
+								volume_variation,
+								Message::SetVolumeVariation,
+							)
+							.step(0.5)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for FootstepDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "footstep_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetPitchVariation(_) => Some("Pitch variation".into()),
+			Message::SetVolumeVariation(_) => Some("Volume variation".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Pitch variation" => vec![Message::SetPitchVariation(MAX_PITCH_VARIATION * value)],
+			"Volume variation" => vec![Message::SetVolumeVariation(MAX_VOLUME_VARIATION * value)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for FootstepDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "footstep_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetRandomSample(self.random_sample),
+			Message::SetPitchVariation(self.steps.pitch_variation()),
+			Message::SetVolumeVariation(self.steps.volume_variation()),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for FootstepDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.walking_since.is_some(),
+			Message::SetWalking(true),
+			Message::SetWalking(false),
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		self.walking_since.map(|_| "Footstep demo".into())
+	}
+}
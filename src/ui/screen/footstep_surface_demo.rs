@@ -0,0 +1,390 @@
+use std::error::Error;
+
+use iced::{
+	keyboard::KeyCode, Align, Color, Column, HorizontalAlignment, Length, Row, Slider, Text,
+};
+use kira::{
+	instance::InstanceSettings,
+	manager::{AudioManager, AudioManagerSettings},
+	playable::PlayableSettings,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{
+		create_audio_manager,
+		sound_bank::SoundBank,
+		sound_container::{Selection, SoundContainer},
+		synth,
+	},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+const EXPLANATION_TEXT: &str = "Walk around the map with the \
+arrow keys. Each step looks up the tile you land on and plays \
+a footstep from that surface's set: recorded steps on the wooden \
+boardwalk, soft rustles on the grass, and splashes in the water.
+
+None of this is built into kira. Each surface is a row in a \
+table that says where its sounds come from and how much each \
+step's pitch and volume can vary, and the demo loads every set \
+up front and picks from the right one when you move. Adding a \
+surface means adding a row to the table and a letter to the map, \
+not writing new playback code. Each set goes into a sound \
+container, like the ones in the sound container demo, so a set \
+never plays the same sound twice in a row.
+
+The variation slider scales every surface's pitch and volume \
+ranges at once, so you can hear how repetitive the sets are \
+without it.";
+
+/// The map, one letter per tile: `g` is grass, `w` is wood,
+/// and `~` is water.
+const MAP: [&str; 7] = [
+	"gggggggwww~~~~",
+	"gggggggwww~~~~",
+	"ggggwwwwwwww~~",
+	"ggggwwwwwwww~~",
+	"ggggggggww~~~~",
+	"gggggggggw~~~~",
+	"ggggggggggg~~~",
+];
+/// Where the player starts, as (column, row).
+const START_POSITION: (usize, usize) = (2, 3);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+	Up,
+	Down,
+	Left,
+	Right,
+}
+
+impl Direction {
+	pub fn from_key_code(key_code: KeyCode) -> Option<Self> {
+		match key_code {
+			KeyCode::Up => Some(Self::Up),
+			KeyCode::Down => Some(Self::Down),
+			KeyCode::Left => Some(Self::Left),
+			KeyCode::Right => Some(Self::Right),
+			_ => None,
+		}
+	}
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Surface {
+	Grass,
+	Wood,
+	Water,
+}
+
+impl Surface {
+	const ALL: [Self; 3] = [Self::Grass, Self::Wood, Self::Water];
+
+	fn from_tile(tile: char) -> Self {
+		match tile {
+			'w' => Self::Wood,
+			'~' => Self::Water,
+			_ => Self::Grass,
+		}
+	}
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::Grass => "Grass",
+			Self::Wood => "Wood",
+			Self::Water => "Water",
+		}
+	}
+
+	fn steps(self) -> &'static SurfaceSteps {
+		&SURFACE_STEPS[self as usize]
+	}
+
+	fn color(self) -> [f32; 3] {
+		match self {
+			Self::Grass => [0.4, 0.7, 0.3],
+			Self::Wood => [0.7, 0.5, 0.3],
+			Self::Water => [0.3, 0.5, 0.9],
+		}
+	}
+}
+
+/// Where a surface's footstep sounds come from.
+enum StepSource {
+	/// Recordings in "assets/footstep demo".
+	Files(&'static [&'static str]),
+	/// Generated bursts of noise, as (duration, brightness) pairs.
+	Noise(&'static [(f64, f64)]),
+}
+
+/// How footsteps on one surface sound.
+struct SurfaceSteps {
+	source: StepSource,
+	/// The playback rate the steps are centered on.
+	pitch: f64,
+	/// How far each step's pitch can stray (in semitones, up
+	/// or down).
+	pitch_variation: f64,
+	/// How much quieter than the original each step can be
+	/// (in decibels).
+	volume_variation: f64,
+}
+
+/// The footstep sets, in the same order as [`Surface::ALL`].
+static SURFACE_STEPS: [SurfaceSteps; 3] = [
+	SurfaceSteps {
+		source: StepSource::Noise(&[(0.08, 0.7), (0.1, 0.6), (0.12, 0.75)]),
+		pitch: 1.0,
+		pitch_variation: 2.0,
+		volume_variation: 6.0,
+	},
+	SurfaceSteps {
+		source: StepSource::Files(&["step 1.wav", "step 2.wav", "step 3.wav", "step 4.wav"]),
+		pitch: 1.0,
+		pitch_variation: 1.0,
+		volume_variation: 3.0,
+	},
+	// slowed down, the noise turns into something like a splash
+	SurfaceSteps {
+		source: StepSource::Noise(&[(0.3, 0.1), (0.35, 0.2), (0.4, 0.05)]),
+		pitch: 0.5,
+		pitch_variation: 3.0,
+		volume_variation: 4.0,
+	},
+];
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Walk(Direction),
+	/// Sets how much of each surface's pitch and volume
+	/// variation is used (from 0 to 1).
+	SetVariation(f64),
+}
+
+pub struct FootstepSurfaceDemo {
+	audio_manager: AudioManager,
+	/// The sounds for each surface, in the same order as
+	/// [`Surface::ALL`].
+	step_sets: Vec<SoundContainer>,
+	position: (usize, usize),
+	variation: f64,
+	/// The surface the last step was on.
+	last_step_surface: Option<Surface>,
+	screen_wrapper: ScreenWrapper<Message>,
+	variation_slider: iced::slider::State,
+}
+
+impl FootstepSurfaceDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let mut step_sets = vec![];
+		for surface in &Surface::ALL {
+			let sound_ids: Vec<_> = match &surface.steps().source {
+				StepSource::Files(file_names) => {
+					let bank = SoundBank::load(
+						&mut audio_manager,
+						&std::env::current_dir()?.join("assets/footstep demo"),
+						file_names,
+					)?;
+					file_names
+						.iter()
+						.filter_map(|file_name| bank.sound_id(file_name.trim_end_matches(".wav")))
+						.collect()
+				}
+				StepSource::Noise(bursts) => {
+					let mut sound_ids = vec![];
+					for &(duration, brightness) in bursts.iter() {
+						sound_ids.push(audio_manager.add_sound(synth::noise_hit(
+							duration,
+							brightness,
+							PlayableSettings::new(),
+						))?);
+					}
+					sound_ids
+				}
+			};
+			if sound_ids.is_empty() {
+				return Err(
+					format!("no footstep sounds were loaded for {}", surface.name()).into(),
+				);
+			}
+			let mut step_set = SoundContainer::new(sound_ids, Selection::RandomNoRepeat);
+			step_set.set_pitch(surface.steps().pitch);
+			step_sets.push(step_set);
+		}
+		let mut demo = Self {
+			audio_manager,
+			step_sets,
+			position: START_POSITION,
+			variation: 1.0,
+			last_step_surface: None,
+			screen_wrapper: ScreenWrapper::new(
+				"Footstep surface demo".into(),
+				Message::GoToDemoSelect,
+			),
+			variation_slider: iced::slider::State::new(),
+		};
+		demo.set_variation(1.0);
+		Ok(demo)
+	}
+
+	fn surface_at(&self, (column, row): (usize, usize)) -> Surface {
+		Surface::from_tile(MAP[row].as_bytes()[column] as char)
+	}
+
+	/// Scales every surface's pitch and volume variation.
+	fn set_variation(&mut self, variation: f64) {
+		self.variation = variation.clamp(0.0, 1.0);
+		for (surface, step_set) in Surface::ALL.iter().zip(&mut self.step_sets) {
+			let steps = surface.steps();
+			step_set.set_pitch_variation(steps.pitch_variation * self.variation);
+			step_set.set_volume_variation(steps.volume_variation * self.variation);
+		}
+	}
+
+	fn step(&mut self) -> Result<(), Box<dyn Error>> {
+		let surface = self.surface_at(self.position);
+		self.step_sets[surface as usize].play(&mut self.audio_manager, InstanceSettings::new())?;
+		self.last_step_surface = Some(surface);
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Walk(direction) => {
+				let (column, row) = self.position;
+				// walking into the edge of the map doesn't take a step
+				let next = match direction {
+					Direction::Up if row > 0 => Some((column, row - 1)),
+					Direction::Down if row < MAP.len() - 1 => Some((column, row + 1)),
+					Direction::Left if column > 0 => Some((column - 1, row)),
+					Direction::Right if column < MAP[row].len() - 1 => Some((column + 1, row)),
+					_ => None,
+				};
+				if let Some(next) = next {
+					self.position = next;
+					self.step()?;
+				}
+			}
+			Message::SetVariation(variation) => self.set_variation(variation),
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let position = self.position;
+		let map = MAP
+			.iter()
+			.enumerate()
+			.fold(Column::new(), |column, (row, tiles)| {
+				column.push(tiles.chars().enumerate().fold(
+					Row::new(),
+					|tile_row, (tile_column, tile)| {
+						let surface = Surface::from_tile(tile);
+						let (text, color) = if (tile_column, row) == position {
+							("@", [1.0, 1.0, 1.0])
+						} else {
+							(
+								match surface {
+									Surface::Grass => "\"",
+									Surface::Wood => "=",
+									Surface::Water => "~",
+								},
+								surface.color(),
+							)
+						};
+						tile_row.push(
+							Text::new(text)
+								.size(32)
+								.width(Length::Units(32))
+								.horizontal_alignment(HorizontalAlignment::Center)
+								.color(Color::from(color)),
+						)
+					},
+				))
+			});
+		let last_step = self.last_step_surface.and_then(|surface| {
+			self.step_sets[surface as usize]
+				.last_played()
+				.map(|step| (surface, step))
+		});
+		let step_text = match last_step {
+			Some((surface, step)) => format!(
+				"{} step {} - pitch: {:.2}x - volume: {:.1} dB",
+				surface.name(),
+				step.index + 1,
+				step.pitch,
+				20.0 * step.volume.log10()
+			),
+			None => "Use the arrow keys to walk".into(),
+		};
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(map)
+				.push(Text::new(step_text))
+				.push(
+					Row::new()
+						.spacing(16)
+						.max_width(600)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Variation: {:.0}%", self.variation * 100.0))
+								.width(Length::Units(200)),
+						)
+						.push(
+							Slider::new(
+								&mut self.variation_slider,
+								0.0..=1.0,
+								self.variation,
+								Message::SetVariation,
+							)
+							.step(0.01)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for FootstepSurfaceDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "footstep_surface_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetVariation(_) => Some("Variation".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Variation" => vec![Message::SetVariation(value)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for FootstepSurfaceDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "footstep_surface_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![Message::SetVariation(self.variation)]
+	}
+}
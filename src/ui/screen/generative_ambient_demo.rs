@@ -0,0 +1,463 @@
+use std::{collections::VecDeque, error::Error, time::Instant};
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Radio, Row, Slider, Text};
+use kira::{
+	group::GroupId,
+	instance::{InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	mixer::effect::filter::{Filter, FilterSettings},
+	parameter::{ParameterId, Tween},
+	playable::PlayableSettings,
+	sequence::{EventReceiver, Sequence, SequenceInstanceId, SequenceSettings},
+	sound::SoundId,
+	AudioResult, Duration,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{
+		create_audio_manager,
+		effect::reverb::{Reverb, ReverbSettings},
+		synth::{self, WaveShape},
+		wet_dry::WetDry,
+	},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+const EXPLANATION_TEXT: &str = "Nothing here is written down ahead \
+of time. A bell plays notes picked at random from the scale, at \
+random times, over a pad whose tone and balance slowly wander. \
+Density sets how many notes there are on average, and the scale \
+sets which notes they can be.
+
+Kira's sequences can't pick their own waits at random, so the \
+screen writes the music a phrase at a time: it rolls the dice for \
+a few seconds' worth of notes and waits, builds a sequence out of \
+them, and starts it. The sequence emits an event for every note \
+so the screen can show it, and another one at the end, which is \
+the screen's cue to write the next phrase. Changes to the controls \
+are picked up there, so they're heard from the next phrase on.
+
+The pad is two looping tones. Every few seconds, the screen picks \
+new targets for the filter cutoff and the volume of each tone \
+and tweens the parameters towards them over the next few \
+seconds.";
+
+/// How long each generated phrase lasts (in seconds).
+const PHRASE_LENGTH: f64 = 8.0;
+/// The pitch of the lowest note (in Hz).
+const ROOT_FREQUENCY: f64 = 220.0;
+/// How many octaves the bell's notes span.
+const NUM_OCTAVES: usize = 2;
+/// How likely a note is to have a second one played with it.
+const CHORD_CHANCE: f64 = 0.2;
+const MIN_DENSITY: f64 = 0.2;
+const MAX_DENSITY: f64 = 3.0;
+/// How often the pad picks new targets (in seconds).
+const PAD_DRIFT_INTERVAL: f64 = 6.0;
+const NOTE_NAMES: [&str; 12] = [
+	"A", "A#", "B", "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#",
+];
+const NUM_RECENT_NOTES: usize = 12;
+const REVERB_MIX: f64 = 0.4;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Scale {
+	MajorPentatonic,
+	MinorPentatonic,
+	Dorian,
+	Lydian,
+	InSen,
+}
+
+impl Scale {
+	const ALL: [Self; 5] = [
+		Self::MajorPentatonic,
+		Self::MinorPentatonic,
+		Self::Dorian,
+		Self::Lydian,
+		Self::InSen,
+	];
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::MajorPentatonic => "Major pentatonic",
+			Self::MinorPentatonic => "Minor pentatonic",
+			Self::Dorian => "Dorian",
+			Self::Lydian => "Lydian",
+			Self::InSen => "In sen",
+		}
+	}
+
+	/// The notes in one octave of the scale (in semitones above
+	/// the root).
+	fn intervals(self) -> &'static [usize] {
+		match self {
+			Self::MajorPentatonic => &[0, 2, 4, 7, 9],
+			Self::MinorPentatonic => &[0, 3, 5, 7, 10],
+			Self::Dorian => &[0, 2, 3, 5, 7, 9, 10],
+			Self::Lydian => &[0, 2, 4, 6, 7, 9, 11],
+			Self::InSen => &[0, 1, 5, 7, 10],
+		}
+	}
+
+	/// Gets a note of the scale, counting up from the root across
+	/// octaves (in semitones above the root).
+	fn note(self, degree: usize) -> usize {
+		let intervals = self.intervals();
+		intervals[degree % intervals.len()] + 12 * (degree / intervals.len())
+	}
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum PhraseEvent {
+	/// A note started (in semitones above the root).
+	Note(usize),
+	Finished,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	/// Sets how many notes play each second, on average.
+	SetDensity(f64),
+	SetScale(Scale),
+}
+
+pub struct GenerativeAmbientDemo {
+	audio_manager: AudioManager,
+	group_id: GroupId,
+	wet_dry: WetDry,
+	bell_sound_id: SoundId,
+	pad_sound_ids: [SoundId; 2],
+	pad_volume_parameter_ids: [ParameterId; 2],
+	cutoff_parameter_id: ParameterId,
+	rng: StdRng,
+	density: f64,
+	scale: Scale,
+	phrase: Option<(SequenceInstanceId, EventReceiver<PhraseEvent>)>,
+	last_drift_time: Instant,
+	recent_notes: VecDeque<usize>,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_button: iced::button::State,
+	density_slider: iced::slider::State,
+}
+
+impl GenerativeAmbientDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let group_id = audio_manager.add_group([])?;
+		let wet_dry = WetDry::new(&mut audio_manager, REVERB_MIX)?;
+		audio_manager.add_effect_to_track(
+			wet_dry.wet_track_id(),
+			Reverb::new(ReverbSettings::new().room_size(0.95).damping(0.6)),
+			Default::default(),
+		)?;
+		// WetDry plays every note twice at the same time, and
+		// chords play two notes at once, which a cooldown would
+		// block
+		let bell_sound_id = audio_manager.add_sound(synth::blip(
+			ROOT_FREQUENCY,
+			3.0,
+			PlayableSettings {
+				cooldown: None,
+				..PlayableSettings::new().groups([group_id])
+			},
+		))?;
+		let pad_track_id = audio_manager.add_sub_track(Default::default())?;
+		let cutoff_parameter_id = audio_manager.add_parameter(800.0)?;
+		audio_manager.add_effect_to_track(
+			pad_track_id,
+			Filter::new(FilterSettings::new().cutoff(cutoff_parameter_id)),
+			Default::default(),
+		)?;
+		let pad_settings = || {
+			PlayableSettings::new()
+				.default_track(pad_track_id)
+				.groups([group_id])
+				.default_loop_start(0.0)
+		};
+		let pad_sound_ids = [
+			audio_manager.add_sound(synth::tone(
+				WaveShape::Saw,
+				ROOT_FREQUENCY / 2.0,
+				pad_settings(),
+			))?,
+			audio_manager.add_sound(synth::tone(
+				WaveShape::Sine,
+				ROOT_FREQUENCY * 0.75,
+				pad_settings(),
+			))?,
+		];
+		let pad_volume_parameter_ids = [
+			audio_manager.add_parameter(0.2)?,
+			audio_manager.add_parameter(0.2)?,
+		];
+		Ok(Self {
+			audio_manager,
+			group_id,
+			wet_dry,
+			bell_sound_id,
+			pad_sound_ids,
+			pad_volume_parameter_ids,
+			cutoff_parameter_id,
+			rng: StdRng::from_entropy(),
+			density: 0.8,
+			scale: Scale::MajorPentatonic,
+			phrase: None,
+			last_drift_time: Instant::now(),
+			recent_notes: VecDeque::new(),
+			screen_wrapper: ScreenWrapper::new(
+				"Generative ambient demo".into(),
+				Message::GoToDemoSelect,
+			),
+			play_button: iced::button::State::new(),
+			density_slider: iced::slider::State::new(),
+		})
+	}
+
+	fn play_note(&self, sequence: &mut Sequence<PhraseEvent>, note: usize) {
+		sequence.emit(PhraseEvent::Note(note));
+		self.wet_dry.play_in_sequence(
+			sequence,
+			self.bell_sound_id,
+			InstanceSettings::new().pitch(2.0f64.powf(note as f64 / 12.0)),
+		);
+	}
+
+	/// Makes up a phrase of notes with the current density and
+	/// scale, and starts a sequence that plays it.
+	fn start_phrase(&mut self) -> AudioResult<()> {
+		let mut sequence = Sequence::new(SequenceSettings::new().groups([self.group_id]));
+		let mean_wait = 1.0 / self.density;
+		let num_degrees = self.scale.intervals().len() * NUM_OCTAVES;
+		let mut time = 0.0;
+		loop {
+			let wait = mean_wait * self.rng.gen_range(0.3, 1.7);
+			if time + wait > PHRASE_LENGTH {
+				break;
+			}
+			sequence.wait(Duration::Seconds(wait));
+			time += wait;
+			let degree = self.rng.gen_range(0, num_degrees);
+			self.play_note(&mut sequence, self.scale.note(degree));
+			if self.rng.gen_bool(CHORD_CHANCE) {
+				// a third or a fourth above, depending on the scale
+				self.play_note(&mut sequence, self.scale.note(degree + 2));
+			}
+		}
+		sequence.wait(Duration::Seconds(PHRASE_LENGTH - time));
+		sequence.emit(PhraseEvent::Finished);
+		self.phrase = Some(
+			self.audio_manager
+				.start_sequence(sequence, Default::default())?,
+		);
+		Ok(())
+	}
+
+	/// Picks new targets for the pad and starts tweening to them.
+	fn drift_pad(&mut self) -> AudioResult<()> {
+		let tween = Some(Tween::linear(PAD_DRIFT_INTERVAL));
+		let cutoff = self.rng.gen_range(300.0, 2500.0);
+		self.audio_manager
+			.set_parameter(self.cutoff_parameter_id, cutoff, tween)?;
+		for parameter_id in self.pad_volume_parameter_ids.iter().copied() {
+			let volume = self.rng.gen_range(0.05, 0.35);
+			self.audio_manager
+				.set_parameter(parameter_id, volume, tween)?;
+		}
+		self.last_drift_time = Instant::now();
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if self.phrase.is_none() => {
+				for (sound_id, parameter_id) in self
+					.pad_sound_ids
+					.iter()
+					.zip(self.pad_volume_parameter_ids.iter())
+				{
+					self.audio_manager.play(
+						*sound_id,
+						InstanceSettings::new()
+							.volume(*parameter_id)
+							.fade_in_tween(Tween::linear(2.0)),
+					)?;
+				}
+				self.drift_pad()?;
+				self.start_phrase()?;
+			}
+			Message::Stop => {
+				self.audio_manager.stop_group(
+					self.group_id,
+					StopInstanceSettings::new().fade_tween(Tween::linear(2.0)),
+				)?;
+				self.phrase = None;
+				self.recent_notes.clear();
+			}
+			Message::SetDensity(density) => {
+				self.density = density.clamp(MIN_DENSITY, MAX_DENSITY);
+			}
+			Message::SetScale(scale) => self.scale = scale,
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		let mut finished = false;
+		if let Some((_, events)) = &mut self.phrase {
+			while let Some(event) = events.pop().copied() {
+				match event {
+					PhraseEvent::Note(note) => {
+						self.recent_notes.push_back(note);
+						if self.recent_notes.len() > NUM_RECENT_NOTES {
+							self.recent_notes.pop_front();
+						}
+					}
+					PhraseEvent::Finished => finished = true,
+				}
+			}
+		}
+		if finished {
+			self.start_phrase()?;
+		}
+		if self.phrase.is_some()
+			&& self.last_drift_time.elapsed().as_secs_f64() >= PAD_DRIFT_INTERVAL
+		{
+			self.drift_pad()?;
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.phrase.is_some();
+		let scale = self.scale;
+		let scale_radios = Scale::ALL
+			.iter()
+			.fold(Row::new().spacing(16), |row, option| {
+				row.push(Radio::new(
+					*option,
+					option.name(),
+					Some(scale),
+					Message::SetScale,
+				))
+			});
+		let recent_notes = if self.recent_notes.is_empty() {
+			"-".to_string()
+		} else {
+			self.recent_notes
+				.iter()
+				.map(|note| NOTE_NAMES[note % 12])
+				.collect::<Vec<_>>()
+				.join(" ")
+		};
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Button::new(
+						&mut self.play_button,
+						Text::new(if playing { "Stop" } else { "Play" })
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(if playing {
+						Message::Stop
+					} else {
+						Message::Play
+					})
+					.width(Length::Units(100))
+					.style(AppStyles),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Density: {:.1} notes/s", self.density))
+								.width(Length::Units(180)),
+						)
+						.push(
+							Slider::new(
+								&mut self.density_slider,
+								MIN_DENSITY..=MAX_DENSITY,
+								self.density,
+								Message::SetDensity,
+							)
+							.step(0.1)
+							.style(AppStyles),
+						),
+				)
+				.push(scale_radios)
+				.push(Text::new(format!("Latest notes: {}", recent_notes)))
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for GenerativeAmbientDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "generative_ambient_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetDensity(_) => Some("Density".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Density" => vec![Message::SetDensity(
+				MIN_DENSITY + (MAX_DENSITY - MIN_DENSITY) * value,
+			)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for GenerativeAmbientDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "generative_ambient_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetDensity(self.density),
+			Message::SetScale(self.scale),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for GenerativeAmbientDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.phrase.is_some(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		self.phrase
+			.as_ref()
+			.map(|_| format!("Generative ambient demo - {}", self.scale.name()))
+	}
+}
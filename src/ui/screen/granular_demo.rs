@@ -0,0 +1,330 @@
+use std::{
+	error::Error,
+	time::{Duration as StdDuration, Instant},
+};
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Row, Slider, Text};
+use kira::{
+	instance::{InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::Tween,
+	playable::PlayableSettings,
+	sequence::Sequence,
+	sound::SoundId,
+	Duration,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, decoded_sound::DecodedSound, sound_cache::SoundCache},
+	ui::{
+		common::{screen_wrapper::ScreenWrapper, waveform::Waveform},
+		style::AppStyles,
+	},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &[
+	"underwater demo/drums.ogg",
+	"underwater demo/bass.ogg",
+	"underwater demo/pad.ogg",
+	"underwater demo/lead.ogg",
+];
+
+const EXPLANATION_TEXT: &str = "This demo chops a song into \
+grains: tiny, overlapping snippets that each fade in and out. \
+Playing grains from the same spot over and over freezes the \
+sound in place, and moving the scrub position moves through \
+the song without changing its pitch.
+
+Every grain is its own short sequence, which plays the song \
+from the scrub position with a fade-in, waits half the grain \
+size, and then stops the instance with a fade-out. A new grain \
+starts every half grain, so there are always two overlapping. \
+The start of each grain is nudged a little at random, which \
+smooths out the buzz of grains repeating at a steady rate.";
+
+const NUM_PEAKS: usize = 200;
+const SCRUB_MARKER_COLOR: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+/// The shortest and longest grains the slider allows (in seconds).
+const MIN_GRAIN_SIZE: f64 = 0.02;
+const MAX_GRAIN_SIZE: f64 = 0.25;
+/// How far the start of each grain can stray from the scrub
+/// position (in seconds, either way).
+const GRAIN_JITTER: f64 = 0.01;
+/// Two grains overlap at all times, so each is a bit quieter
+/// than the song.
+const GRAIN_VOLUME: f64 = 0.7;
+/// If the app falls this far behind (for example, while the
+/// window is being dragged), the missed grains are skipped
+/// instead of all starting at once.
+const MAX_LAG: f64 = 0.1;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Start,
+	Stop,
+	/// Sets where grains are taken from (from 0 to 1).
+	SetScrubPosition(f64),
+	/// Sets how long each grain is (in seconds).
+	SetGrainSize(f64),
+}
+
+pub struct GranularDemo {
+	audio_manager: AudioManager,
+	sound_id: SoundId,
+	duration: f64,
+	peaks: Vec<f32>,
+	rng: StdRng,
+	scrub_position: f64,
+	grain_size: f64,
+	/// When the next grain should start, or `None` if the
+	/// granulator is stopped.
+	next_grain_time: Option<Instant>,
+	grains_played: usize,
+	screen_wrapper: ScreenWrapper<Message>,
+	start_button: iced::button::State,
+	scrub_slider: iced::slider::State,
+	grain_size_slider: iced::slider::State,
+}
+
+impl GranularDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let assets_base_dir = std::env::current_dir()?.join("assets/underwater demo");
+		let song = DecodedSound::mix(&[
+			&*sound_cache.get(&assets_base_dir.join("drums.ogg"))?,
+			&*sound_cache.get(&assets_base_dir.join("bass.ogg"))?,
+			&*sound_cache.get(&assets_base_dir.join("pad.ogg"))?,
+			&*sound_cache.get(&assets_base_dir.join("lead.ogg"))?,
+		]);
+		// a late frame can start two grains at once, which a
+		// cooldown would block
+		let sound_id = audio_manager.add_sound(song.to_sound(PlayableSettings {
+			cooldown: None,
+			..Default::default()
+		}))?;
+		Ok(Self {
+			audio_manager,
+			sound_id,
+			duration: song.duration(),
+			peaks: song.peaks(NUM_PEAKS),
+			rng: StdRng::from_entropy(),
+			scrub_position: 0.25,
+			grain_size: 0.08,
+			next_grain_time: None,
+			grains_played: 0,
+			screen_wrapper: ScreenWrapper::new("Granular demo".into(), Message::GoToDemoSelect),
+			start_button: iced::button::State::new(),
+			scrub_slider: iced::slider::State::new(),
+			grain_size_slider: iced::slider::State::new(),
+		})
+	}
+
+	fn play_grain(&mut self) -> Result<(), Box<dyn Error>> {
+		let half_grain = self.grain_size / 2.0;
+		let start_position = (self.scrub_position * self.duration
+			+ self.rng.gen_range(-GRAIN_JITTER, GRAIN_JITTER))
+		.clamp(0.0, (self.duration - self.grain_size).max(0.0));
+		let mut sequence = Sequence::<()>::new(Default::default());
+		let instance_id = sequence.play(
+			self.sound_id,
+			InstanceSettings::new()
+				.start_position(start_position)
+				.volume(GRAIN_VOLUME)
+				.fade_in_tween(Tween::linear(half_grain)),
+		);
+		sequence.wait(Duration::Seconds(half_grain));
+		sequence.stop_instance(
+			instance_id,
+			StopInstanceSettings::new().fade_tween(Tween::linear(half_grain)),
+		);
+		self.audio_manager
+			.start_sequence(sequence, Default::default())?;
+		self.grains_played += 1;
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Start if self.next_grain_time.is_none() => {
+				self.next_grain_time = Some(Instant::now());
+				self.check_for_events()?;
+			}
+			Message::Stop => self.next_grain_time = None,
+			Message::SetScrubPosition(position) => {
+				self.scrub_position = position.clamp(0.0, 1.0);
+			}
+			Message::SetGrainSize(grain_size) => {
+				self.grain_size = grain_size.clamp(MIN_GRAIN_SIZE, MAX_GRAIN_SIZE);
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	/// Whether grains are being played from the UI timer.
+	pub fn plays_from_ui_timer(&self) -> bool {
+		self.next_grain_time.is_some()
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		let mut next_grain_time = match self.next_grain_time {
+			Some(time) => time,
+			None => return Ok(()),
+		};
+		let now = Instant::now();
+		if now.duration_since(next_grain_time).as_secs_f64() > MAX_LAG {
+			next_grain_time = now;
+		}
+		while next_grain_time <= now {
+			self.play_grain()?;
+			next_grain_time += StdDuration::from_secs_f64(self.grain_size / 2.0);
+		}
+		self.next_grain_time = Some(next_grain_time);
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let running = self.next_grain_time.is_some();
+		let waveform = Waveform::new(&self.peaks)
+			.marker(self.scrub_position as f32, SCRUB_MARKER_COLOR)
+			.on_press(|position| Message::SetScrubPosition(position as f64));
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(waveform)
+				.push(
+					Row::new()
+						.spacing(16)
+						.max_width(600)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!(
+								"Scrub: {:.2}s",
+								self.scrub_position * self.duration
+							))
+							.width(Length::Units(200)),
+						)
+						.push(
+							Slider::new(
+								&mut self.scrub_slider,
+								0.0..=1.0,
+								self.scrub_position,
+								Message::SetScrubPosition,
+							)
+							.step(0.001)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Row::new()
+						.spacing(16)
+						.max_width(600)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Grain size: {:.0}ms", self.grain_size * 1000.0))
+								.width(Length::Units(200)),
+						)
+						.push(
+							Slider::new(
+								&mut self.grain_size_slider,
+								MIN_GRAIN_SIZE..=MAX_GRAIN_SIZE,
+								self.grain_size,
+								Message::SetGrainSize,
+							)
+							.step(0.005)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Button::new(
+								&mut self.start_button,
+								Text::new(if running { "Stop" } else { "Start" })
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(if running {
+								Message::Stop
+							} else {
+								Message::Start
+							})
+							.width(Length::Units(120))
+							.style(AppStyles),
+						)
+						.push(Text::new(format!("{} grains played", self.grains_played))),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for GranularDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "granular_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetScrubPosition(_) => Some("Scrub".into()),
+			Message::SetGrainSize(_) => Some("Grain size".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Scrub" => vec![Message::SetScrubPosition(value)],
+			"Grain size" => vec![Message::SetGrainSize(
+				MIN_GRAIN_SIZE + (MAX_GRAIN_SIZE - MIN_GRAIN_SIZE) * value,
+			)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for GranularDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "granular_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetScrubPosition(self.scrub_position),
+			Message::SetGrainSize(self.grain_size),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for GranularDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.next_grain_time.is_some(),
+			Message::Start,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		self.next_grain_time.map(|_| "Granular demo".into())
+	}
+}
@@ -0,0 +1,495 @@
+use std::error::Error;
+
+use iced::{Align, Button, Column, Length, Row, Space, Text};
+use kira::{
+	arrangement::{Arrangement, ArrangementId, LoopArrangementSettings},
+	group::GroupId,
+	instance::{PauseInstanceSettings, ResumeInstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::Tween,
+	playable::PlayableSettings,
+	sequence::{Sequence, SequenceSettings},
+	sound::SoundId,
+	Duration, Tempo,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, sound_cache::SoundCache, synth},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &[
+	"underwater demo/drums.ogg",
+	"underwater demo/bass.ogg",
+	"underwater demo/pad.ogg",
+	"underwater demo/lead.ogg",
+];
+
+pub const TEMPO: Tempo = Tempo(85.0);
+
+const EXPLANATION_TEXT: &str = "Groups can be nested inside \
+other groups. Pausing, resuming, or stopping a group affects \
+every sound and sequence anywhere underneath it, so pausing \
+\"Music\" pauses both \"Combat\" and \"Explore\", and \
+pausing \"Master\" pauses everything.";
+
+const INDENT: u16 = 24;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	ToggleExpanded(usize),
+	Play(usize),
+	Pause(usize),
+	Resume(usize),
+	Stop(usize),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum LeafState {
+	Stopped,
+	Playing,
+	Paused,
+}
+
+enum Content {
+	Loops(Vec<ArrangementId>),
+	Blips(SoundId),
+}
+
+struct Leaf {
+	content: Content,
+	state: LeafState,
+}
+
+struct GroupNode {
+	name: &'static str,
+	group_id: GroupId,
+	children: Vec<usize>,
+	leaf: Option<Leaf>,
+	expanded: bool,
+	toggle_button: iced::button::State,
+	play_button: iced::button::State,
+	pause_button: iced::button::State,
+	resume_button: iced::button::State,
+	stop_button: iced::button::State,
+}
+
+impl GroupNode {
+	fn new(name: &'static str, group_id: GroupId, children: Vec<usize>) -> Self {
+		Self {
+			name,
+			group_id,
+			children,
+			leaf: None,
+			expanded: true,
+			toggle_button: iced::button::State::new(),
+			play_button: iced::button::State::new(),
+			pause_button: iced::button::State::new(),
+			resume_button: iced::button::State::new(),
+			stop_button: iced::button::State::new(),
+		}
+	}
+
+	fn with_content(self, content: Content) -> Self {
+		Self {
+			leaf: Some(Leaf {
+				content,
+				state: LeafState::Stopped,
+			}),
+			..self
+		}
+	}
+}
+
+pub struct GroupHierarchyDemo {
+	audio_manager: AudioManager,
+	// nodes are stored in depth-first order, so a node's
+	// descendants always come right after it
+	nodes: Vec<GroupNode>,
+	screen_wrapper: ScreenWrapper<Message>,
+}
+
+impl GroupHierarchyDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		Self::with_audio_manager(create_audio_manager(audio_manager_settings)?, sound_cache)
+	}
+
+	fn with_audio_manager(
+		mut audio_manager: AudioManager,
+		sound_cache: &SoundCache,
+	) -> Result<Self, Box<dyn Error>> {
+		let master_group_id = audio_manager.add_group([])?;
+		let music_group_id = audio_manager.add_group([master_group_id])?;
+		let combat_group_id = audio_manager.add_group([music_group_id])?;
+		let explore_group_id = audio_manager.add_group([music_group_id])?;
+		let sfx_group_id = audio_manager.add_group([master_group_id])?;
+		let assets_base_dir = std::env::current_dir()?.join("assets/underwater demo");
+		let mut load_loop = |name: &str,
+		                     group_id: GroupId|
+		 -> Result<ArrangementId, Box<dyn Error>> {
+			let sound_id =
+				audio_manager.add_sound(sound_cache.get(&assets_base_dir.join(name))?.to_sound(
+					PlayableSettings::new().semantic_duration(TEMPO.beats_to_seconds(16.0)),
+				))?;
+			Ok(audio_manager.add_arrangement(Arrangement::new_loop(
+				sound_id,
+				LoopArrangementSettings::new().groups([group_id]),
+			))?)
+		};
+		let combat_loops = vec![
+			load_loop("drums.ogg", combat_group_id)?,
+			load_loop("bass.ogg", combat_group_id)?,
+		];
+		let explore_loops = vec![
+			load_loop("pad.ogg", explore_group_id)?,
+			load_loop("lead.ogg", explore_group_id)?,
+		];
+		let blip_sound_id = audio_manager.add_sound(synth::blip(
+			1320.0,
+			0.2,
+			PlayableSettings::new().groups([sfx_group_id]),
+		))?;
+		Ok(Self {
+			audio_manager,
+			nodes: vec![
+				GroupNode::new("Master", master_group_id, vec![1, 4]),
+				GroupNode::new("Music", music_group_id, vec![2, 3]),
+				GroupNode::new("Combat", combat_group_id, vec![])
+					.with_content(Content::Loops(combat_loops)),
+				GroupNode::new("Explore", explore_group_id, vec![])
+					.with_content(Content::Loops(explore_loops)),
+				GroupNode::new("SFX", sfx_group_id, vec![])
+					.with_content(Content::Blips(blip_sound_id)),
+			],
+			screen_wrapper: ScreenWrapper::new(
+				"Group hierarchy demo".into(),
+				Message::GoToDemoSelect,
+			),
+		})
+	}
+
+	fn leaves_under(&self, index: usize) -> Vec<usize> {
+		if self.nodes[index].leaf.is_some() {
+			return vec![index];
+		}
+		self.nodes[index]
+			.children
+			.iter()
+			.flat_map(|child| self.leaves_under(*child))
+			.collect()
+	}
+
+	fn any_leaf_under(&self, index: usize, state: LeafState) -> bool {
+		self.leaves_under(index)
+			.iter()
+			.any(|leaf| matches!(&self.nodes[*leaf].leaf, Some(leaf) if leaf.state == state))
+	}
+
+	fn set_leaf_states(&mut self, index: usize, from: &[LeafState], to: LeafState) {
+		for leaf_index in self.leaves_under(index) {
+			if let Some(leaf) = &mut self.nodes[leaf_index].leaf {
+				if from.contains(&leaf.state) {
+					leaf.state = to;
+				}
+			}
+		}
+	}
+
+	fn play(&mut self, index: usize) -> Result<(), Box<dyn Error>> {
+		for leaf_index in self.leaves_under(index) {
+			let group_id = self.nodes[leaf_index].group_id;
+			if let Some(leaf) = &mut self.nodes[leaf_index].leaf {
+				if leaf.state != LeafState::Stopped {
+					continue;
+				}
+				match &leaf.content {
+					Content::Loops(arrangement_ids) => {
+						for arrangement_id in arrangement_ids {
+							self.audio_manager
+								.play(*arrangement_id, Default::default())?;
+						}
+					}
+					Content::Blips(sound_id) => {
+						// the sequence belongs to the group too, so
+						// pausing the group pauses the sequence
+						let mut sequence =
+							Sequence::<()>::new(SequenceSettings::new().groups([group_id]));
+						sequence.start_loop();
+						sequence.play(*sound_id, Default::default());
+						sequence.wait(Duration::Seconds(0.75));
+						self.audio_manager
+							.start_sequence(sequence, Default::default())?;
+					}
+				}
+				leaf.state = LeafState::Playing;
+			}
+		}
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			// a recorded session might have been edited or come from
+			// an older tree, so nodes that don't exist are ignored
+			Message::ToggleExpanded(index)
+			| Message::Play(index)
+			| Message::Pause(index)
+			| Message::Resume(index)
+			| Message::Stop(index)
+				if index >= self.nodes.len() => {}
+			Message::ToggleExpanded(index) => {
+				self.nodes[index].expanded = !self.nodes[index].expanded;
+			}
+			Message::Play(index) => self.play(index)?,
+			Message::Pause(index) => {
+				self.audio_manager.pause_group(
+					self.nodes[index].group_id,
+					PauseInstanceSettings::new().fade_tween(Tween::linear(0.25)),
+				)?;
+				self.set_leaf_states(index, &[LeafState::Playing], LeafState::Paused);
+			}
+			// resuming a group resumes everything under it, even if
+			// one of the child groups was paused separately
+			Message::Resume(index) => {
+				self.audio_manager.resume_group(
+					self.nodes[index].group_id,
+					ResumeInstanceSettings::new().fade_tween(Tween::linear(0.25)),
+				)?;
+				self.set_leaf_states(index, &[LeafState::Paused], LeafState::Playing);
+			}
+			Message::Stop(index) => {
+				self.audio_manager.stop_group(
+					self.nodes[index].group_id,
+					StopInstanceSettings::new().fade_tween(Tween::linear(0.5)),
+				)?;
+				self.set_leaf_states(
+					index,
+					&[LeafState::Playing, LeafState::Paused],
+					LeafState::Stopped,
+				);
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	fn status_text(&self, index: usize) -> String {
+		match &self.nodes[index].leaf {
+			Some(leaf) => match leaf.state {
+				LeafState::Stopped => "Stopped".into(),
+				LeafState::Playing => "Playing".into(),
+				LeafState::Paused => "Paused".into(),
+			},
+			None => {
+				let leaves = self.leaves_under(index);
+				let num_playing = leaves
+					.iter()
+					.filter(
+						|leaf| matches!(&self.nodes[**leaf].leaf, Some(leaf) if leaf.state == LeafState::Playing),
+					)
+					.count();
+				format!("{} of {} playing", num_playing, leaves.len())
+			}
+		}
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		// figure out which rows are visible (and how deep they are)
+		// before we start mutably borrowing the button states
+		let mut rows = vec![];
+		let mut stack = vec![(0, 0)];
+		while let Some((index, depth)) = stack.pop() {
+			let node = &self.nodes[index];
+			rows.push((
+				index,
+				depth,
+				self.status_text(index),
+				self.any_leaf_under(index, LeafState::Stopped),
+				self.any_leaf_under(index, LeafState::Playing),
+				self.any_leaf_under(index, LeafState::Paused),
+			));
+			if node.expanded {
+				for child in node.children.iter().rev() {
+					stack.push((*child, depth + 1));
+				}
+			}
+		}
+		rows.sort_by_key(|row| row.0);
+
+		let mut tree = Column::new().spacing(8);
+		let mut rows = rows.into_iter().peekable();
+		for (index, node) in self.nodes.iter_mut().enumerate() {
+			let (_, depth, status_text, can_play, can_pause, can_resume) = match rows.peek() {
+				Some(row) if row.0 == index => rows.next().unwrap(),
+				_ => continue,
+			};
+			let mut row = Row::new()
+				.spacing(8)
+				.align_items(Align::Center)
+				.push(Space::with_width(Length::Units(INDENT * depth)));
+			row = if node.children.is_empty() {
+				row.push(Space::with_width(Length::Units(INDENT)))
+			} else {
+				row.push(
+					Button::new(
+						&mut node.toggle_button,
+						Text::new(if node.expanded { "-" } else { "+" }),
+					)
+					.width(Length::Units(INDENT))
+					.on_press(Message::ToggleExpanded(index))
+					.style(AppStyles),
+				)
+			};
+			let mut play_button =
+				Button::new(&mut node.play_button, Text::new("Play")).style(AppStyles);
+			if can_play {
+				play_button = play_button.on_press(Message::Play(index));
+			}
+			let mut pause_button =
+				Button::new(&mut node.pause_button, Text::new("Pause")).style(AppStyles);
+			if can_pause {
+				pause_button = pause_button.on_press(Message::Pause(index));
+			}
+			let mut resume_button =
+				Button::new(&mut node.resume_button, Text::new("Resume")).style(AppStyles);
+			if can_resume {
+				resume_button = resume_button.on_press(Message::Resume(index));
+			}
+			let mut stop_button =
+				Button::new(&mut node.stop_button, Text::new("Stop")).style(AppStyles);
+			if can_pause || can_resume {
+				stop_button = stop_button.on_press(Message::Stop(index));
+			}
+			tree = tree.push(
+				row.push(Text::new(node.name).width(Length::Units(80)))
+					.push(Text::new(status_text).width(Length::Units(110)))
+					.push(play_button)
+					.push(pause_button)
+					.push(resume_button)
+					.push(stop_button),
+			);
+		}
+
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(tree)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for GroupHierarchyDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "group_hierarchy_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		// nodes start out expanded, so only the collapsed ones
+		// need to be toggled
+		self.nodes
+			.iter()
+			.enumerate()
+			.filter(|(_, node)| !node.expanded)
+			.map(|(index, _)| Message::ToggleExpanded(index))
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::audio::offline::{load_session, OfflineRenderer};
+
+	const MASTER: usize = 0;
+	const MUSIC: usize = 1;
+	const COMBAT: usize = 2;
+	const SFX: usize = 4;
+
+	fn send(demo: &mut GroupHierarchyDemo, renderer: &mut OfflineRenderer, message: Message) {
+		demo.update(message).unwrap();
+		let states: Vec<String> = demo
+			.nodes
+			.iter()
+			.filter_map(|node| {
+				node.leaf
+					.as_ref()
+					.map(|leaf| format!("{}: {:?}", node.name, leaf.state))
+			})
+			.collect();
+		renderer.log(format!("{:?} -> {}", message, states.join(", ")));
+	}
+
+	#[test]
+	fn groups_affect_everything_underneath_them() {
+		let (audio_manager, mut renderer) = OfflineRenderer::new(Default::default()).unwrap();
+		let sound_cache = SoundCache::preload(
+			std::env::current_dir().unwrap().join("assets"),
+			std::iter::empty(),
+		);
+		let mut demo = GroupHierarchyDemo::with_audio_manager(audio_manager, &sound_cache).unwrap();
+		send(&mut demo, &mut renderer, Message::Play(COMBAT));
+		send(&mut demo, &mut renderer, Message::Play(SFX));
+		renderer.render(1.0);
+		// the blips should keep playing while the music is paused
+		send(&mut demo, &mut renderer, Message::Pause(MUSIC));
+		renderer.render(1.0);
+		send(&mut demo, &mut renderer, Message::Resume(MASTER));
+		renderer.render(1.0);
+		send(&mut demo, &mut renderer, Message::Stop(MASTER));
+		renderer.render(0.5);
+		renderer.assert_matches_golden("group_hierarchy_demo");
+	}
+
+	#[test]
+	fn nodes_that_dont_exist_are_ignored() {
+		let (audio_manager, _renderer) = OfflineRenderer::new(Default::default()).unwrap();
+		let sound_cache = SoundCache::preload(
+			std::env::current_dir().unwrap().join("assets"),
+			std::iter::empty(),
+		);
+		let mut demo = GroupHierarchyDemo::with_audio_manager(audio_manager, &sound_cache).unwrap();
+		let index = demo.nodes.len();
+		for message in &[
+			Message::ToggleExpanded(index),
+			Message::Play(index),
+			Message::Pause(index),
+			Message::Resume(index),
+			Message::Stop(index),
+		] {
+			demo.update(*message).unwrap();
+		}
+	}
+
+	#[test]
+	fn replays_recorded_session() {
+		let (audio_manager, mut renderer) = OfflineRenderer::new(Default::default()).unwrap();
+		let sound_cache = SoundCache::preload(
+			std::env::current_dir().unwrap().join("assets"),
+			std::iter::empty(),
+		);
+		let mut demo = GroupHierarchyDemo::with_audio_manager(audio_manager, &sound_cache).unwrap();
+		let mut session = load_session("group_hierarchy_demo");
+		while !session.is_finished() {
+			for message in session.due::<GroupHierarchyDemo>(renderer.time()).unwrap() {
+				send(&mut demo, &mut renderer, message);
+			}
+			// the app checks for events 60 times a second
+			renderer.render(1.0 / 60.0);
+		}
+		renderer.render(0.5);
+		renderer.assert_trace_matches_golden("group_hierarchy_session");
+	}
+}
@@ -0,0 +1,470 @@
+use std::error::Error;
+
+use iced::{Align, Button, Column, Length, Row, Slider, Text};
+use kira::{
+	group::GroupId,
+	instance::{
+		InstanceSettings, PauseInstanceSettings, ResumeInstanceSettings, StopInstanceSettings,
+	},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::Tween,
+	playable::PlayableSettings,
+	sequence::{Sequence, SequenceSettings},
+	sound::SoundId,
+	Duration,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, sound_cache::SoundCache, synth},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &[
+	"underwater demo/pad.ogg",
+	"underwater demo/lead.ogg",
+	"drum fill demo/loop.ogg",
+];
+
+const EXPLANATION_TEXT: &str = "Each row controls one group. \
+Pausing a group pauses every sound and sequence in it, and \
+resuming picks them up from where they left off, so a pause menu \
+can freeze the sound effects and ambience while the music keeps \
+going. The sliders set how long each kind of change fades for.
+
+A sound can be in more than one group. The radio is both music \
+and ambience, so pausing either group pauses it, and resuming \
+either one brings it back, even if the other group is still \
+paused.";
+
+const MAX_FADE_TIME: f64 = 2.0;
+/// How long to wait between blips in the sound effects group
+/// (in seconds).
+const BLIP_INTERVAL: f64 = 0.75;
+
+/// One of the things that can be playing, which belongs to one
+/// or more groups.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Layer {
+	Music,
+	Radio,
+	Ambience,
+	Blips,
+}
+
+impl Layer {
+	const ALL: [Self; 4] = [Self::Music, Self::Radio, Self::Ambience, Self::Blips];
+
+	fn index(self) -> usize {
+		Self::ALL.iter().position(|layer| *layer == self).unwrap()
+	}
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::Music => "music",
+			Self::Radio => "radio",
+			Self::Ambience => "rain",
+			Self::Blips => "blips",
+		}
+	}
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Group {
+	Music,
+	Sfx,
+	Ambience,
+}
+
+impl Group {
+	const ALL: [Self; 3] = [Self::Music, Self::Sfx, Self::Ambience];
+
+	fn index(self) -> usize {
+		Self::ALL.iter().position(|group| *group == self).unwrap()
+	}
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::Music => "Music",
+			Self::Sfx => "SFX",
+			Self::Ambience => "Ambience",
+		}
+	}
+
+	fn layers(self) -> &'static [Layer] {
+		match self {
+			Self::Music => &[Layer::Music, Layer::Radio],
+			Self::Sfx => &[Layer::Blips],
+			Self::Ambience => &[Layer::Ambience, Layer::Radio],
+		}
+	}
+}
+
+/// Which kind of fade a fade time slider is for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Fade {
+	Pause,
+	Resume,
+	Stop,
+}
+
+impl Fade {
+	const ALL: [Self; 3] = [Self::Pause, Self::Resume, Self::Stop];
+
+	fn index(self) -> usize {
+		Self::ALL.iter().position(|fade| *fade == self).unwrap()
+	}
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::Pause => "Pause",
+			Self::Resume => "Resume",
+			Self::Stop => "Stop",
+		}
+	}
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum LayerState {
+	Stopped,
+	Playing,
+	Paused,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play(Group),
+	Pause(Group),
+	Resume(Group),
+	Stop(Group),
+	/// Sets how long a kind of change fades for (in seconds).
+	SetFadeTime(Fade, f64),
+}
+
+#[derive(Debug, Default)]
+struct GroupButtons {
+	play: iced::button::State,
+	pause: iced::button::State,
+	resume: iced::button::State,
+	stop: iced::button::State,
+}
+
+pub struct GroupPauseDemo {
+	audio_manager: AudioManager,
+	group_ids: [GroupId; 3],
+	music_sound_ids: Vec<SoundId>,
+	radio_sound_id: SoundId,
+	rain_sound_id: SoundId,
+	blip_sound_id: SoundId,
+	layer_states: [LayerState; 4],
+	fade_times: [f64; 3],
+	screen_wrapper: ScreenWrapper<Message>,
+	group_buttons: [GroupButtons; 3],
+	fade_sliders: [iced::slider::State; 3],
+}
+
+impl GroupPauseDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let music_group_id = audio_manager.add_group([])?;
+		let sfx_group_id = audio_manager.add_group([])?;
+		let ambience_group_id = audio_manager.add_group([])?;
+		let assets_base_dir = std::env::current_dir()?.join("assets");
+		let mut music_sound_ids = vec![];
+		for path in &ASSETS[..2] {
+			music_sound_ids.push(
+				audio_manager.add_sound(
+					sound_cache.get(&assets_base_dir.join(path))?.to_sound(
+						PlayableSettings::new()
+							.default_loop_start(0.0)
+							.groups([music_group_id]),
+					),
+				)?,
+			);
+		}
+		let radio_sound_id = audio_manager.add_sound(
+			sound_cache.get(&assets_base_dir.join(ASSETS[2]))?.to_sound(
+				PlayableSettings::new()
+					.default_loop_start(0.0)
+					.groups([music_group_id, ambience_group_id]),
+			),
+		)?;
+		let rain_sound_id = audio_manager.add_sound(synth::rain(
+			8.0,
+			PlayableSettings::new()
+				.default_loop_start(0.0)
+				.groups([ambience_group_id]),
+		))?;
+		let blip_sound_id = audio_manager.add_sound(synth::blip(
+			1320.0,
+			0.2,
+			PlayableSettings::new().groups([sfx_group_id]),
+		))?;
+		Ok(Self {
+			audio_manager,
+			group_ids: [music_group_id, sfx_group_id, ambience_group_id],
+			music_sound_ids,
+			radio_sound_id,
+			rain_sound_id,
+			blip_sound_id,
+			layer_states: [LayerState::Stopped; 4],
+			fade_times: [0.25, 0.25, 0.5],
+			screen_wrapper: ScreenWrapper::new("Group pause demo".into(), Message::GoToDemoSelect),
+			group_buttons: Default::default(),
+			fade_sliders: Default::default(),
+		})
+	}
+
+	fn fade_tween(&self, fade: Fade) -> Option<Tween> {
+		let time = self.fade_times[fade.index()];
+		if time > 0.0 {
+			Some(Tween::linear(time))
+		} else {
+			None
+		}
+	}
+
+	fn play_layer(&mut self, layer: Layer) -> Result<(), Box<dyn Error>> {
+		match layer {
+			Layer::Music => {
+				for sound_id in &self.music_sound_ids {
+					self.audio_manager.play(*sound_id, Default::default())?;
+				}
+			}
+			Layer::Radio => {
+				self.audio_manager.play(
+					self.radio_sound_id,
+					InstanceSettings::new().volume(0.4).panning(0.8),
+				)?;
+			}
+			Layer::Ambience => {
+				self.audio_manager
+					.play(self.rain_sound_id, Default::default())?;
+			}
+			Layer::Blips => {
+				// the sequence belongs to the group too, so pausing
+				// the group pauses the sequence
+				let mut sequence = Sequence::<()>::new(
+					SequenceSettings::new().groups([self.group_ids[Group::Sfx.index()]]),
+				);
+				sequence.start_loop();
+				sequence.play(self.blip_sound_id, Default::default());
+				sequence.wait(Duration::Seconds(BLIP_INTERVAL));
+				self.audio_manager
+					.start_sequence(sequence, Default::default())?;
+			}
+		}
+		Ok(())
+	}
+
+	fn set_layer_states(&mut self, group: Group, from: &[LayerState], to: LayerState) {
+		for layer in group.layers() {
+			let state = &mut self.layer_states[layer.index()];
+			if from.contains(state) {
+				*state = to;
+			}
+		}
+	}
+
+	fn any_layer(&self, group: Group, state: LayerState) -> bool {
+		group
+			.layers()
+			.iter()
+			.any(|layer| self.layer_states[layer.index()] == state)
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play(group) => {
+				for layer in group.layers() {
+					if self.layer_states[layer.index()] == LayerState::Stopped {
+						self.play_layer(*layer)?;
+						self.layer_states[layer.index()] = LayerState::Playing;
+					}
+				}
+			}
+			Message::Pause(group) => {
+				let settings = match self.fade_tween(Fade::Pause) {
+					Some(tween) => PauseInstanceSettings::new().fade_tween(tween),
+					None => PauseInstanceSettings::new(),
+				};
+				self.audio_manager
+					.pause_group(self.group_ids[group.index()], settings)?;
+				self.set_layer_states(group, &[LayerState::Playing], LayerState::Paused);
+			}
+			Message::Resume(group) => {
+				let settings = match self.fade_tween(Fade::Resume) {
+					Some(tween) => ResumeInstanceSettings::new().fade_tween(tween),
+					None => ResumeInstanceSettings::new(),
+				};
+				self.audio_manager
+					.resume_group(self.group_ids[group.index()], settings)?;
+				self.set_layer_states(group, &[LayerState::Paused], LayerState::Playing);
+			}
+			Message::Stop(group) => {
+				let settings = match self.fade_tween(Fade::Stop) {
+					Some(tween) => StopInstanceSettings::new().fade_tween(tween),
+					None => StopInstanceSettings::new(),
+				};
+				self.audio_manager
+					.stop_group(self.group_ids[group.index()], settings)?;
+				self.set_layer_states(
+					group,
+					&[LayerState::Playing, LayerState::Paused],
+					LayerState::Stopped,
+				);
+			}
+			Message::SetFadeTime(fade, time) => {
+				self.fade_times[fade.index()] = time.clamp(0.0, MAX_FADE_TIME);
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	fn status_text(&self, group: Group) -> String {
+		group
+			.layers()
+			.iter()
+			.map(|layer| {
+				let state = match self.layer_states[layer.index()] {
+					LayerState::Stopped => "stopped",
+					LayerState::Playing => "playing",
+					LayerState::Paused => "paused",
+				};
+				format!("{} {}", layer.name(), state)
+			})
+			.collect::<Vec<_>>()
+			.join(", ")
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		// work out what each row shows before the button states
+		// are borrowed
+		let rows: Vec<_> = Group::ALL
+			.iter()
+			.map(|group| {
+				(
+					*group,
+					self.status_text(*group),
+					self.any_layer(*group, LayerState::Stopped),
+					self.any_layer(*group, LayerState::Playing),
+					self.any_layer(*group, LayerState::Paused),
+				)
+			})
+			.collect();
+		let table = rows.into_iter().zip(self.group_buttons.iter_mut()).fold(
+			Column::new().spacing(8),
+			|column, ((group, status_text, can_play, can_pause, can_resume), buttons)| {
+				let mut play_button =
+					Button::new(&mut buttons.play, Text::new("Play")).style(AppStyles);
+				if can_play {
+					play_button = play_button.on_press(Message::Play(group));
+				}
+				let mut pause_button =
+					Button::new(&mut buttons.pause, Text::new("Pause")).style(AppStyles);
+				if can_pause {
+					pause_button = pause_button.on_press(Message::Pause(group));
+				}
+				let mut resume_button =
+					Button::new(&mut buttons.resume, Text::new("Resume")).style(AppStyles);
+				if can_resume {
+					resume_button = resume_button.on_press(Message::Resume(group));
+				}
+				let mut stop_button =
+					Button::new(&mut buttons.stop, Text::new("Stop")).style(AppStyles);
+				if can_pause || can_resume {
+					stop_button = stop_button.on_press(Message::Stop(group));
+				}
+				column.push(
+					Row::new()
+						.spacing(8)
+						.align_items(Align::Center)
+						.push(Text::new(group.name()).width(Length::Units(90)))
+						.push(Text::new(status_text).width(Length::Units(260)))
+						.push(play_button)
+						.push(pause_button)
+						.push(resume_button)
+						.push(stop_button),
+				)
+			},
+		);
+		let fade_times = self.fade_times;
+		let sliders = Fade::ALL.iter().zip(self.fade_sliders.iter_mut()).fold(
+			Column::new().spacing(16).max_width(500),
+			|column, (fade, state)| {
+				let fade = *fade;
+				let time = fade_times[fade.index()];
+				column.push(
+					Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("{} fade: {:.2}s", fade.name(), time))
+								.width(Length::Units(150)),
+						)
+						.push(
+							Slider::new(state, 0.0..=MAX_FADE_TIME, time, move |time| {
+								Message::SetFadeTime(fade, time)
+							})
+							.step(0.05)
+							.style(AppStyles),
+						),
+				)
+			},
+		);
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(table)
+				.push(sliders)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for GroupPauseDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "group_pause_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetFadeTime(fade, _) => Some(format!("{} fade", fade.name())),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		Fade::ALL
+			.iter()
+			.filter(|fade| format!("{} fade", fade.name()) == control)
+			.map(|fade| Message::SetFadeTime(*fade, MAX_FADE_TIME * value))
+			.collect()
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for GroupPauseDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "group_pause_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		Fade::ALL
+			.iter()
+			.map(|fade| Message::SetFadeTime(*fade, self.fade_times[fade.index()]))
+			.collect()
+	}
+}
@@ -0,0 +1,452 @@
+use std::{error::Error, time::Instant};
+
+use iced::{Align, Button, Checkbox, Color, Column, HorizontalAlignment, Length, Row, Text};
+use kira::{
+	instance::{InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::Tween,
+	sequence::{EventReceiver, Sequence, SequenceInstanceId},
+	sound::SoundId,
+	Duration,
+};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, decoded_sound::DecodedSound, sound_cache::SoundCache},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &[
+	"underwater demo/drums.ogg",
+	"underwater demo/bass.ogg",
+	"underwater demo/pad.ogg",
+	"underwater demo/lead.ogg",
+	"drum fill demo/loop.ogg",
+];
+
+const EXPLANATION_TEXT: &str = "This demo plays a playlist \
+without any gaps between the tracks.
+
+If the UI started each track when it noticed the last one had \
+finished, there would be a short, uneven gap every time. \
+Instead, the whole rest of the playlist goes into one \
+sequence: play a track, wait exactly as long as the track is, \
+play the next one, and so on. The sequence runs on the audio \
+thread, so each track starts on the very sample the last one \
+ends on. It also emits an event as each track starts, which is \
+how the screen knows what's playing.
+
+Skipping, shuffling or turning repeat on and off stops the \
+sequence and builds a new one for the new play order.";
+
+/// The tracks in the playlist, each mixed from one or more of
+/// the asset files.
+const TRACKS: [(&str, &[&str]); 4] = [
+	(
+		"Underwater",
+		&[
+			"underwater demo/drums.ogg",
+			"underwater demo/bass.ogg",
+			"underwater demo/pad.ogg",
+			"underwater demo/lead.ogg",
+		],
+	),
+	(
+		"Underwater (rhythm section)",
+		&["underwater demo/drums.ogg", "underwater demo/bass.ogg"],
+	),
+	("Drum loop", &["drum fill demo/loop.ogg"]),
+	(
+		"Underwater (ambient mix)",
+		&["underwater demo/pad.ogg", "underwater demo/lead.ogg"],
+	),
+];
+/// Pressing previous this far into a track (in seconds)
+/// restarts it instead of going back a track.
+const RESTART_THRESHOLD: f64 = 3.0;
+/// How long the old sequence fades out (and the new one fades
+/// in) when the play order changes partway through a track.
+const CROSSFADE_TIME: f64 = 0.02;
+const CURRENT_TRACK_COLOR: [f32; 3] = [1.0, 1.0, 1.0];
+const OTHER_TRACK_COLOR: [f32; 3] = [0.6, 0.6, 0.6];
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	Next,
+	Previous,
+	/// Plays one of the tracks (by its index in the playlist).
+	PlayTrack(usize),
+	SetShuffle(bool),
+	SetRepeat(bool),
+}
+
+/// The events the playlist sequence emits.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum PlaylistEvent {
+	/// A track has started (by its position in the play order).
+	TrackStarted(usize),
+	Finished,
+}
+
+struct Track {
+	name: &'static str,
+	sound_id: SoundId,
+	duration: f64,
+}
+
+struct Playback {
+	sequence_id: SequenceInstanceId,
+	events: EventReceiver<PlaylistEvent>,
+	/// How far into the current track playback started (in
+	/// seconds), which is only more than 0 for the first track
+	/// of a sequence.
+	start_position: f64,
+	start_time: Instant,
+}
+
+pub struct JukeboxDemo {
+	audio_manager: AudioManager,
+	tracks: Vec<Track>,
+	/// The order the tracks play in (as indices into the playlist).
+	order: Vec<usize>,
+	/// Where in the play order the current track is.
+	position: usize,
+	shuffle: bool,
+	repeat: bool,
+	rng: StdRng,
+	playback: Option<Playback>,
+	screen_wrapper: ScreenWrapper<Message>,
+	previous_button: iced::button::State,
+	play_button: iced::button::State,
+	next_button: iced::button::State,
+	track_buttons: [iced::button::State; TRACKS.len()],
+}
+
+impl JukeboxDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let assets_dir = std::env::current_dir()?.join("assets");
+		let mut tracks = vec![];
+		for (name, files) in &TRACKS {
+			let mut stems = vec![];
+			for file in files.iter() {
+				stems.push(sound_cache.get(&assets_dir.join(file))?);
+			}
+			let song = DecodedSound::mix(&stems.iter().map(|stem| &**stem).collect::<Vec<_>>());
+			tracks.push(Track {
+				name,
+				sound_id: audio_manager.add_sound(song.to_sound(Default::default()))?,
+				duration: song.duration(),
+			});
+		}
+		Ok(Self {
+			audio_manager,
+			tracks,
+			order: (0..TRACKS.len()).collect(),
+			position: 0,
+			shuffle: false,
+			repeat: true,
+			rng: StdRng::from_entropy(),
+			playback: None,
+			screen_wrapper: ScreenWrapper::new("Jukebox demo".into(), Message::GoToDemoSelect),
+			previous_button: iced::button::State::new(),
+			play_button: iced::button::State::new(),
+			next_button: iced::button::State::new(),
+			track_buttons: Default::default(),
+		})
+	}
+
+	/// How far into the current track playback is (in seconds).
+	fn elapsed(&self) -> Option<f64> {
+		self.playback
+			.as_ref()
+			.map(|playback| playback.start_position + playback.start_time.elapsed().as_secs_f64())
+	}
+
+	/// Queues up the rest of the play order (and, with repeat on,
+	/// the whole order over and over) in a sequence, starting
+	/// the current track from the given position.
+	fn start(&mut self, start_position: f64, fade_in_time: f64) -> Result<(), Box<dyn Error>> {
+		let mut sequence = Sequence::new(Default::default());
+		for position in self.position..self.order.len() {
+			let track = &self.tracks[self.order[position]];
+			if position == self.position {
+				let mut settings = InstanceSettings::new().start_position(start_position);
+				if fade_in_time > 0.0 {
+					settings = settings.fade_in_tween(Tween::linear(fade_in_time));
+				}
+				sequence.play(track.sound_id, settings);
+				sequence.wait(Duration::Seconds(track.duration - start_position));
+			} else {
+				sequence.emit(PlaylistEvent::TrackStarted(position));
+				sequence.play(track.sound_id, Default::default());
+				sequence.wait(Duration::Seconds(track.duration));
+			}
+		}
+		if self.repeat {
+			sequence.start_loop();
+			for (position, track_index) in self.order.iter().enumerate() {
+				let track = &self.tracks[*track_index];
+				sequence.emit(PlaylistEvent::TrackStarted(position));
+				sequence.play(track.sound_id, Default::default());
+				sequence.wait(Duration::Seconds(track.duration));
+			}
+		} else {
+			sequence.emit(PlaylistEvent::Finished);
+		}
+		let (sequence_id, events) = self
+			.audio_manager
+			.start_sequence(sequence, Default::default())?;
+		self.playback = Some(Playback {
+			sequence_id,
+			events,
+			start_position,
+			start_time: Instant::now(),
+		});
+		Ok(())
+	}
+
+	fn stop(&mut self, fade_out_time: f64) -> Result<(), Box<dyn Error>> {
+		if let Some(playback) = self.playback.take() {
+			self.audio_manager.stop_sequence_and_instances(
+				playback.sequence_id,
+				StopInstanceSettings::new().fade_tween(Tween::linear(fade_out_time)),
+			)?;
+		}
+		Ok(())
+	}
+
+	/// Switches to a different position in the play order,
+	/// starting that track from the top if something is playing.
+	fn go_to(&mut self, position: usize) -> Result<(), Box<dyn Error>> {
+		let playing = self.playback.is_some();
+		self.stop(0.1)?;
+		self.position = position;
+		if playing {
+			self.start(0.0, 0.0)?;
+		}
+		Ok(())
+	}
+
+	/// Rebuilds the sequence after the play order changes, keeping
+	/// the current track going from where it is.
+	fn requeue(&mut self) -> Result<(), Box<dyn Error>> {
+		if let Some(elapsed) = self.elapsed() {
+			self.stop(CROSSFADE_TIME)?;
+			self.start(elapsed, CROSSFADE_TIME)?;
+		}
+		Ok(())
+	}
+
+	fn set_shuffle(&mut self, shuffle: bool) -> Result<(), Box<dyn Error>> {
+		let current_track = self.order[self.position];
+		self.shuffle = shuffle;
+		if shuffle {
+			// the current track goes first, so the shuffled order
+			// picks up from it
+			self.order.shuffle(&mut self.rng);
+			let index = self
+				.order
+				.iter()
+				.position(|track| *track == current_track)
+				.unwrap();
+			self.order.swap(0, index);
+			self.position = 0;
+		} else {
+			self.order = (0..self.tracks.len()).collect();
+			self.position = current_track;
+		}
+		self.requeue()
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if self.playback.is_none() => self.start(0.0, 0.0)?,
+			Message::Stop => self.stop(0.1)?,
+			Message::Next => self.go_to((self.position + 1) % self.order.len())?,
+			Message::Previous => {
+				if self.elapsed().unwrap_or_default() > RESTART_THRESHOLD {
+					self.go_to(self.position)?;
+				} else {
+					self.go_to((self.position + self.order.len() - 1) % self.order.len())?;
+				}
+			}
+			Message::PlayTrack(track_index) => {
+				if let Some(position) = self.order.iter().position(|track| *track == track_index) {
+					self.stop(0.1)?;
+					self.position = position;
+					self.start(0.0, 0.0)?;
+				}
+			}
+			Message::SetShuffle(shuffle) => self.set_shuffle(shuffle)?,
+			Message::SetRepeat(repeat) => {
+				self.repeat = repeat;
+				self.requeue()?;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		let mut finished = false;
+		if let Some(playback) = &mut self.playback {
+			while let Some(event) = playback.events.pop().copied() {
+				match event {
+					PlaylistEvent::TrackStarted(position) => {
+						self.position = position;
+						playback.start_position = 0.0;
+						playback.start_time = Instant::now();
+					}
+					PlaylistEvent::Finished => finished = true,
+				}
+			}
+		}
+		if finished {
+			self.playback = None;
+			self.position = 0;
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.playback.is_some();
+		let elapsed = self.elapsed();
+		let current_track = self.order[self.position];
+		let tracks = &self.tracks;
+		let mut track_buttons: Vec<_> = self.track_buttons.iter_mut().map(Some).collect();
+		let track_list = self.order.iter().fold(
+			Column::new().spacing(4).max_width(600),
+			|column, track_index| {
+				let track = &tracks[*track_index];
+				let is_current = *track_index == current_track;
+				let time_text = match elapsed {
+					Some(elapsed) if is_current => format!(
+						"{} / -{}",
+						format_time(elapsed),
+						format_time((track.duration - elapsed).max(0.0))
+					),
+					_ => format_time(track.duration),
+				};
+				let color = if is_current {
+					CURRENT_TRACK_COLOR
+				} else {
+					OTHER_TRACK_COLOR
+				};
+				column.push(
+					Button::new(
+						track_buttons[*track_index].take().unwrap(),
+						Row::new()
+							.push(
+								Text::new(track.name)
+									.size(16)
+									.width(Length::Fill)
+									.color(Color::from(color)),
+							)
+							.push(Text::new(time_text).size(16).color(Color::from(color))),
+					)
+					.on_press(Message::PlayTrack(*track_index))
+					.width(Length::Fill)
+					.style(AppStyles),
+				)
+			},
+		);
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Row::new()
+						.spacing(8)
+						.align_items(Align::Center)
+						.push(
+							Button::new(&mut self.previous_button, Text::new("Previous"))
+								.on_press(Message::Previous)
+								.style(AppStyles),
+						)
+						.push(
+							Button::new(
+								&mut self.play_button,
+								Text::new(if playing { "Stop" } else { "Play" })
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(if playing {
+								Message::Stop
+							} else {
+								Message::Play
+							})
+							.width(Length::Units(120))
+							.style(AppStyles),
+						)
+						.push(
+							Button::new(&mut self.next_button, Text::new("Next"))
+								.on_press(Message::Next)
+								.style(AppStyles),
+						)
+						.push(Checkbox::new(self.shuffle, "Shuffle", Message::SetShuffle))
+						.push(Checkbox::new(self.repeat, "Repeat", Message::SetRepeat)),
+				)
+				.push(track_list)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+/// Formats a time in seconds as minutes and seconds (like 1:05).
+fn format_time(seconds: f64) -> String {
+	let seconds = seconds.floor() as u64;
+	format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+impl crate::ui::screen_states::RememberControls for JukeboxDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "jukebox_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetShuffle(self.shuffle),
+			Message::SetRepeat(self.repeat),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for JukeboxDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		match key {
+			crate::ui::media_keys::MediaKey::Next => vec![Message::Next],
+			crate::ui::media_keys::MediaKey::Previous => vec![Message::Previous],
+			key => crate::ui::media_keys::transport_messages(
+				key,
+				self.playback.is_some(),
+				Message::Play,
+				Message::Stop,
+			),
+		}
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		self.playback.as_ref().map(|_| {
+			format!(
+				"{} (jukebox demo)",
+				self.tracks[self.order[self.position]].name
+			)
+		})
+	}
+}
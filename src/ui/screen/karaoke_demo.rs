@@ -0,0 +1,336 @@
+use std::error::Error;
+
+use iced::{Align, Button, Color, Column, HorizontalAlignment, Length, Row, Slider, Text};
+use kira::{
+	instance::StopInstanceSettings,
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::Tween,
+	playable::PlayableSettings,
+	sequence::{EventReceiver, Sequence, SequenceInstanceId},
+	sound::SoundId,
+	Duration,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::create_audio_manager,
+	ui::{
+		common::screen_wrapper::ScreenWrapper, screen::dialogue_demo::CAPTIONS, style::AppStyles,
+	},
+};
+
+const EXPLANATION_TEXT: &str = "This screen highlights the words \
+of a line as they're spoken, like a karaoke machine. It builds on \
+the dialogue demo: instead of one event per line, the sequence \
+that plays the voice emits an event for every word, at the time \
+that word starts. The screen only ever draws what the latest \
+event says, so the highlighting can't drift away from the audio \
+even if the UI stalls for a moment.
+
+The events are timed on the audio thread, but the screen only \
+sees them the next time it checks, which can be a frame late. \
+Karaoke machines get around this by highlighting a little early \
+so the word is lit by the time it's sung, and the lead slider \
+does the same: the word events are moved that much earlier in \
+the sequence, and the voice is started a little later to make \
+room for them.
+
+The voice is placeholder babble, so the word timings are made \
+up: each line's time is shared out between its words by their \
+length. A real game would get them from a lyrics or lip sync \
+tool.";
+
+/// The most the highlighting can run ahead of the voice (in
+/// seconds). The voice starts this long into the sequence so
+/// early events always have room.
+const MAX_LEAD: f64 = 0.25;
+const INITIAL_LEAD: f64 = 0.05;
+const SUNG_COLOR: [f32; 3] = [1.0, 0.8, 0.2];
+const CURRENT_WORD_COLOR: [f32; 3] = [1.0, 1.0, 1.0];
+const UPCOMING_COLOR: [f32; 3] = [0.6, 0.6, 0.6];
+const OTHER_LINE_COLOR: [f32; 3] = [0.35, 0.35, 0.35];
+
+/// Splits each caption into words, each with the time it starts
+/// (in seconds from the start of the file). Each word gets a
+/// share of its line's time by its length, counting the space
+/// after it.
+fn word_timings() -> Vec<Vec<(f64, &'static str)>> {
+	CAPTIONS
+		.iter()
+		.map(|(start, end, text)| {
+			let words: Vec<&str> = text.split_whitespace().collect();
+			let total_length: usize = words.iter().map(|word| word.len() + 1).sum();
+			let seconds_per_character = (end - start) / total_length as f64;
+			let mut time = *start;
+			words
+				.into_iter()
+				.map(|word| {
+					let word_start = time;
+					time += (word.len() + 1) as f64 * seconds_per_character;
+					(word_start, word)
+				})
+				.collect()
+		})
+		.collect()
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	/// Sets how early the words are highlighted (in seconds).
+	SetLead(f64),
+}
+
+/// The events the karaoke sequence emits.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum KaraokeEvent {
+	/// Highlights a word (by its line and its place in the line).
+	Word(usize, usize),
+	/// Marks a whole line as sung.
+	LineFinished(usize),
+	Finished,
+}
+
+/// How far through the lyrics the voice is.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Position {
+	Word(usize, usize),
+	AfterLine(usize),
+}
+
+pub struct KaraokeDemo {
+	audio_manager: AudioManager,
+	sound_id: SoundId,
+	lines: Vec<Vec<(f64, &'static str)>>,
+	playback: Option<(SequenceInstanceId, EventReceiver<KaraokeEvent>)>,
+	position: Option<Position>,
+	lead: f64,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_button: iced::button::State,
+	lead_slider: iced::slider::State,
+}
+
+impl KaraokeDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let sound_id = audio_manager.load_sound(
+			std::env::current_dir()?.join("assets/dialogue demo/dialogue.wav"),
+			PlayableSettings::new(),
+		)?;
+		Ok(Self {
+			audio_manager,
+			sound_id,
+			lines: word_timings(),
+			playback: None,
+			position: None,
+			lead: INITIAL_LEAD,
+			screen_wrapper: ScreenWrapper::new("Karaoke demo".into(), Message::GoToDemoSelect),
+			play_button: iced::button::State::new(),
+			lead_slider: iced::slider::State::new(),
+		})
+	}
+
+	fn play(&mut self) -> Result<(), Box<dyn Error>> {
+		let mut sequence = Sequence::new(Default::default());
+		sequence.wait(Duration::Seconds(MAX_LEAD));
+		sequence.play(self.sound_id, Default::default());
+		// times from here on are in seconds since the sequence
+		// started, with the voice starting at MAX_LEAD
+		let mut time = MAX_LEAD;
+		let mut wait_until = |sequence: &mut Sequence<KaraokeEvent>, event_time: f64| {
+			sequence.wait(Duration::Seconds((event_time - time).max(0.0)));
+			time = time.max(event_time);
+		};
+		for (line, words) in self.lines.iter().enumerate() {
+			for (word, (start, _)) in words.iter().enumerate() {
+				wait_until(&mut sequence, MAX_LEAD + start - self.lead);
+				sequence.emit(KaraokeEvent::Word(line, word));
+			}
+			wait_until(&mut sequence, MAX_LEAD + CAPTIONS[line].1 - self.lead);
+			sequence.emit(KaraokeEvent::LineFinished(line));
+		}
+		wait_until(&mut sequence, MAX_LEAD + self.sound_id.duration());
+		sequence.emit(KaraokeEvent::Finished);
+		self.playback = Some(
+			self.audio_manager
+				.start_sequence(sequence, Default::default())?,
+		);
+		self.position = None;
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if self.playback.is_none() => self.play()?,
+			Message::Stop => {
+				if let Some((sequence_id, _)) = self.playback.take() {
+					self.audio_manager.stop_sequence_and_instances(
+						sequence_id,
+						StopInstanceSettings::new().fade_tween(Tween::linear(0.1)),
+					)?;
+				}
+				self.position = None;
+			}
+			// takes effect the next time the voice plays, since the
+			// sequence is already scheduled
+			Message::SetLead(lead) => self.lead = lead.clamp(0.0, MAX_LEAD),
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		let mut finished = false;
+		if let Some((_, event_receiver)) = &mut self.playback {
+			while let Some(event) = event_receiver.pop().copied() {
+				match event {
+					KaraokeEvent::Word(line, word) => {
+						self.position = Some(Position::Word(line, word))
+					}
+					KaraokeEvent::LineFinished(line) => {
+						self.position = Some(Position::AfterLine(line))
+					}
+					KaraokeEvent::Finished => finished = true,
+				}
+			}
+		}
+		if finished {
+			self.playback = None;
+			self.position = None;
+		}
+		Ok(())
+	}
+
+	/// Picks the color of a word for where the voice is.
+	fn word_color(&self, line: usize, word: usize) -> [f32; 3] {
+		match self.position {
+			Some(Position::Word(current_line, current_word)) if current_line == line => {
+				if word < current_word {
+					SUNG_COLOR
+				} else if word == current_word {
+					CURRENT_WORD_COLOR
+				} else {
+					UPCOMING_COLOR
+				}
+			}
+			Some(Position::AfterLine(current_line)) if current_line == line => SUNG_COLOR,
+			_ => OTHER_LINE_COLOR,
+		}
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.playback.is_some();
+		let lyrics = self.lines.iter().enumerate().fold(
+			Column::new().spacing(12).align_items(Align::Center),
+			|column, (line, words)| {
+				column.push(words.iter().enumerate().fold(
+					Row::new().spacing(8),
+					|row, (word, (_, text))| {
+						row.push(
+							Text::new(*text)
+								.size(28)
+								.color(Color::from(self.word_color(line, word))),
+						)
+					},
+				))
+			},
+		);
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Button::new(
+						&mut self.play_button,
+						Text::new(if playing { "Stop" } else { "Play" })
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(if playing {
+						Message::Stop
+					} else {
+						Message::Play
+					})
+					.width(Length::Units(120))
+					.style(AppStyles),
+				)
+				.push(lyrics)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Lead: {:.0} ms", self.lead * 1000.0))
+								.width(Length::Units(200)),
+						)
+						.push(
+							Slider::new(
+								&mut self.lead_slider,
+								0.0..=MAX_LEAD,
+								self.lead,
+								Message::SetLead,
+							)
+							.step(0.005)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for KaraokeDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "karaoke_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetLead(_) => Some("Lead".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Lead" => vec![Message::SetLead(value * MAX_LEAD)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for KaraokeDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "karaoke_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![Message::SetLead(self.lead)]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for KaraokeDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.playback.is_some(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		self.playback.as_ref().map(|_| "Karaoke demo".into())
+	}
+}
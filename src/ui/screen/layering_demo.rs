@@ -0,0 +1,295 @@
+use std::error::Error;
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Row, Slider, Text};
+use kira::{
+	arrangement::{Arrangement, ArrangementId},
+	instance::{InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::{Mapping, ParameterId, Tween},
+	playable::PlayableSettings,
+	sequence::{Sequence, SequenceInstanceId},
+	Tempo, Value,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, sound_cache::SoundCache},
+	ui::{
+		common::{screen_wrapper::ScreenWrapper, transport::Transport},
+		style::AppStyles,
+	},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &[
+	"underwater demo/pad.ogg",
+	"underwater demo/bass.ogg",
+	"underwater demo/drums.ogg",
+	"underwater demo/lead.ogg",
+];
+
+pub const TEMPO: Tempo = Tempo(85.0);
+
+const EXPLANATION_TEXT: &str = "This demo plays four stems \
+in sync the whole time and uses a single \"intensity\" \
+parameter to decide which ones can be heard. This is known \
+as vertical layering, and it's a common way for game music \
+to follow what's happening in the game.
+
+The pad is always playing. Each of the other stems has its \
+volume mapped from 0 to 1 over one step of the intensity, \
+with both ends of the mapping clamped, so raising the \
+intensity brings layers in one at a time.";
+
+/// The stems, in the order they're layered in.
+const STEM_NAMES: [&str; 4] = ["Pad", "Bass", "Drums", "Lead"];
+const MAX_INTENSITY: f64 = 3.0;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetMetronomeClick(bool),
+	/// Sets how many layers are playing (from 0 to 3).
+	SetIntensity(f64),
+}
+
+pub struct LayeringDemo {
+	audio_manager: AudioManager,
+	stem_loop_ids: Vec<ArrangementId>,
+	sequence_id: Option<SequenceInstanceId>,
+	intensity_parameter_id: ParameterId,
+	intensity: f64,
+	transport: Transport,
+	screen_wrapper: ScreenWrapper<Message>,
+	intensity_slider: iced::slider::State,
+	level_buttons: [iced::button::State; 4],
+}
+
+impl LayeringDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(AudioManagerSettings {
+			metronome_settings: Transport::metronome_settings(TEMPO),
+			..audio_manager_settings
+		})?;
+		let transport = Transport::new(&mut audio_manager, TEMPO)?;
+		let intensity_parameter_id = audio_manager.add_parameter(0.0)?;
+		let assets_base_dir = std::env::current_dir()?.join("assets");
+		let mut stem_loop_ids = vec![];
+		for path in ASSETS {
+			let sound_id =
+				audio_manager.add_sound(sound_cache.get(&assets_base_dir.join(path))?.to_sound(
+					PlayableSettings::new().semantic_duration(TEMPO.beats_to_seconds(16.0)),
+				))?;
+			stem_loop_ids.push(
+				audio_manager
+					.add_arrangement(Arrangement::new_loop(sound_id, Default::default()))?,
+			);
+		}
+		Ok(Self {
+			audio_manager,
+			stem_loop_ids,
+			sequence_id: None,
+			intensity_parameter_id,
+			intensity: 0.0,
+			transport,
+			screen_wrapper: ScreenWrapper::new("Layering demo".into(), Message::GoToDemoSelect),
+			intensity_slider: iced::slider::State::new(),
+			level_buttons: Default::default(),
+		})
+	}
+
+	/// How the intensity maps to the volume of one of the stems.
+	/// The first stem is the base layer, so it's always at full
+	/// volume.
+	fn stem_mapping(stem_index: usize) -> Mapping {
+		let start = stem_index.max(1) as f64 - 1.0;
+		Mapping {
+			input_range: (start, start + 1.0),
+			output_range: if stem_index == 0 {
+				(1.0, 1.0)
+			} else {
+				(0.0, 1.0)
+			},
+			clamp_bottom: true,
+			clamp_top: true,
+		}
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play => {
+				let mut sequence = Sequence::<()>::new(Default::default());
+				for (index, loop_id) in self.stem_loop_ids.iter().enumerate() {
+					sequence.play(
+						*loop_id,
+						InstanceSettings::new().volume(Value::Parameter(
+							self.intensity_parameter_id,
+							Self::stem_mapping(index),
+						)),
+					);
+				}
+				let (sequence_id, _) = self
+					.audio_manager
+					.start_sequence(sequence, Default::default())?;
+				self.sequence_id = Some(sequence_id);
+				self.transport.start(&mut self.audio_manager)?;
+			}
+			Message::Stop => {
+				if let Some(sequence_id) = self.sequence_id {
+					self.audio_manager.stop_sequence_and_instances(
+						sequence_id,
+						StopInstanceSettings::new().fade_tween(Tween::linear(1.0)),
+					)?;
+					self.sequence_id = None;
+				}
+				self.transport.stop(&mut self.audio_manager)?;
+			}
+			Message::SetMetronomeClick(enabled) => {
+				self.transport
+					.set_click_enabled(&mut self.audio_manager, enabled)?;
+			}
+			Message::SetIntensity(intensity) => {
+				let intensity = intensity.clamp(0.0, MAX_INTENSITY);
+				// a short tween keeps the layers from popping in
+				self.audio_manager.set_parameter(
+					self.intensity_parameter_id,
+					intensity,
+					Some(0.5.into()),
+				)?;
+				self.intensity = intensity;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		self.transport.check_for_events(&mut self.audio_manager);
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let intensity = self.intensity;
+		let level_buttons = self.level_buttons.iter_mut().enumerate().fold(
+			Row::new().spacing(8),
+			|row, (level, button)| {
+				row.push(
+					Button::new(
+						button,
+						Text::new(format!("{} - {}", level, STEM_NAMES[level]))
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(Message::SetIntensity(level as f64))
+					.width(Length::Units(120))
+					.style(AppStyles),
+				)
+			},
+		);
+		let stem_volumes = STEM_NAMES.iter().enumerate().fold(
+			Column::new().spacing(4),
+			|column, (index, name)| {
+				column.push(Text::new(format!(
+					"{}: {}%",
+					name,
+					(Self::stem_mapping(index).map(intensity) * 100.0).round()
+				)))
+			},
+		);
+
+		self.screen_wrapper.view_with_transport(
+			self.transport
+				.view(Message::Play, Message::Stop, Message::SetMetronomeClick),
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Row::new()
+						.spacing(16)
+						.max_width(600)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Intensity: {:.2}", intensity))
+								.width(Length::Units(150)),
+						)
+						.push(
+							Slider::new(
+								&mut self.intensity_slider,
+								0.0..=MAX_INTENSITY,
+								intensity,
+								Message::SetIntensity,
+							)
+							.step(0.01)
+							.style(AppStyles),
+						),
+				)
+				.push(level_buttons)
+				.push(stem_volumes)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for LayeringDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "layering_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetIntensity(_) => Some("Intensity".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Intensity" => vec![Message::SetIntensity(value * MAX_INTENSITY)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for LayeringDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "layering_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetMetronomeClick(self.transport.click_enabled()),
+			Message::SetIntensity(self.intensity),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for LayeringDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.transport.is_playing(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if !self.transport.is_playing() {
+			return None;
+		}
+		Some(format!("Layering demo (intensity {:.0})", self.intensity))
+	}
+}
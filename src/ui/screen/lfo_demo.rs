@@ -0,0 +1,494 @@
+use std::{error::Error, time::Instant};
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Radio, Row, Slider, Text};
+use kira::{
+	instance::{InstanceId, InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	mixer::effect::filter::{Filter, FilterSettings},
+	parameter::{EaseDirection, Easing, Mapping, ParameterId, Tween},
+	playable::PlayableSettings,
+	sequence::{Sequence, SequenceInstanceId},
+	sound::SoundId,
+	Duration, Value,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{
+		create_audio_manager,
+		synth::{self, WaveShape},
+	},
+	ui::{
+		common::{curve_plot::CurvePlot, screen_wrapper::ScreenWrapper},
+		style::AppStyles,
+	},
+};
+
+const EXPLANATION_TEXT: &str = "Kira doesn't have LFOs, but a \
+looping sequence can do the same job: it tweens a parameter down, \
+waits for the tween to finish, tweens it back up, and waits again. \
+The sequence runs on the audio thread, so the wobble keeps going \
+smoothly no matter how busy the UI is. The smooth shape uses an \
+in-out easing curve on each tween, which comes out close to a sine \
+wave.
+
+The LFO's parameter always swings between 0 and 1 (with the depth \
+deciding how far down it goes), and the filter's cutoff and the \
+tone's volume each read their own parameter through a mapping. A \
+sequence can't be changed once it's started, so changing the rate \
+or depth starts a new one, which picks up from wherever the old \
+one left the parameter.
+
+Kira doesn't report a parameter's value, so the dot on the plot \
+is worked out from the time since the sequence started.";
+
+const MIN_CUTOFF: f64 = 200.0;
+const MAX_CUTOFF: f64 = 4000.0;
+const MIN_RATE: f64 = 0.1;
+const MAX_RATE: f64 = 8.0;
+const INITIAL_RATE: f64 = 1.0;
+const INITIAL_DEPTH: f64 = 0.8;
+const NUM_PLOT_POINTS: usize = 100;
+const PLOT_WIDTH: u16 = 400;
+const PLOT_HEIGHT: u16 = 120;
+
+/// What the LFO is modulating.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Target {
+	Cutoff,
+	Volume,
+}
+
+impl Target {
+	const ALL: [Self; 2] = [Self::Cutoff, Self::Volume];
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::Cutoff => "Filter cutoff",
+			Self::Volume => "Volume",
+		}
+	}
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Shape {
+	Triangle,
+	Smooth,
+}
+
+impl Shape {
+	const ALL: [Self; 2] = [Self::Triangle, Self::Smooth];
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::Triangle => "Triangle",
+			Self::Smooth => "Smooth",
+		}
+	}
+
+	/// The tween for each half of a cycle.
+	fn tween(self, duration: f64) -> Tween {
+		match self {
+			Self::Triangle => Tween::linear(duration),
+			Self::Smooth => Tween {
+				duration,
+				easing: Easing::PowI(2),
+				ease_direction: EaseDirection::InOut,
+			},
+		}
+	}
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetTarget(Target),
+	SetShape(Shape),
+	/// Sets the rate (in cycles per second).
+	SetRate(f64),
+	SetDepth(f64),
+}
+
+/// The sequence moving the parameter up and down.
+struct Lfo {
+	sequence_id: SequenceInstanceId,
+	start_time: Instant,
+}
+
+pub struct LfoDemo {
+	audio_manager: AudioManager,
+	tone_sound_id: SoundId,
+	cutoff_parameter_id: ParameterId,
+	volume_parameter_id: ParameterId,
+	tone_instance_id: Option<InstanceId>,
+	lfo: Option<Lfo>,
+	target: Target,
+	shape: Shape,
+	rate: f64,
+	depth: f64,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_button: iced::button::State,
+	rate_slider: iced::slider::State,
+	depth_slider: iced::slider::State,
+}
+
+impl LfoDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let cutoff_parameter_id = audio_manager.add_parameter(1.0)?;
+		let volume_parameter_id = audio_manager.add_parameter(1.0)?;
+		let track_id = audio_manager.add_sub_track(Default::default())?;
+		audio_manager.add_effect_to_track(
+			track_id,
+			Filter::new(FilterSettings::new().cutoff(Value::Parameter(
+				cutoff_parameter_id,
+				Mapping {
+					input_range: (0.0, 1.0),
+					output_range: (MIN_CUTOFF, MAX_CUTOFF),
+					clamp_bottom: true,
+					clamp_top: true,
+				},
+			))),
+			Default::default(),
+		)?;
+		let tone_sound_id = audio_manager.add_sound(synth::tone(
+			WaveShape::Saw,
+			110.0,
+			PlayableSettings::new()
+				.default_track(track_id)
+				.default_loop_start(0.0),
+		))?;
+		Ok(Self {
+			audio_manager,
+			tone_sound_id,
+			cutoff_parameter_id,
+			volume_parameter_id,
+			tone_instance_id: None,
+			lfo: None,
+			target: Target::Cutoff,
+			shape: Shape::Smooth,
+			rate: INITIAL_RATE,
+			depth: INITIAL_DEPTH,
+			screen_wrapper: ScreenWrapper::new("LFO demo".into(), Message::GoToDemoSelect),
+			play_button: iced::button::State::new(),
+			rate_slider: iced::slider::State::new(),
+			depth_slider: iced::slider::State::new(),
+		})
+	}
+
+	fn target_parameter_id(&self, target: Target) -> ParameterId {
+		match target {
+			Target::Cutoff => self.cutoff_parameter_id,
+			Target::Volume => self.volume_parameter_id,
+		}
+	}
+
+	/// The length of one cycle (in seconds).
+	fn period(&self) -> f64 {
+		1.0 / self.rate
+	}
+
+	/// The LFO's value at a time (in seconds) since the start
+	/// of a cycle.
+	fn lfo_value(&self, time: f64) -> f64 {
+		let half_period = self.period() / 2.0;
+		let tween = self.shape.tween(half_period);
+		let low = 1.0 - self.depth;
+		if time < half_period {
+			tween.tween(1.0, low, time)
+		} else {
+			tween.tween(low, 1.0, time - half_period)
+		}
+	}
+
+	/// How far through a cycle the LFO is (from 0 to 1), if
+	/// it's running.
+	fn lfo_phase(&self) -> Option<f64> {
+		let lfo = self.lfo.as_ref()?;
+		let period = self.period();
+		Some((lfo.start_time.elapsed().as_secs_f64() % period) / period)
+	}
+
+	/// Starts a sequence that moves the target parameter down
+	/// and back up once per cycle, replacing the one that was
+	/// running (if any).
+	fn start_lfo(&mut self) -> Result<(), Box<dyn Error>> {
+		self.stop_lfo()?;
+		let half_period = self.period() / 2.0;
+		let tween = self.shape.tween(half_period);
+		let parameter_id = self.target_parameter_id(self.target);
+		let mut sequence = Sequence::<()>::new(Default::default());
+		sequence.start_loop();
+		sequence.set_parameter(parameter_id, 1.0 - self.depth, Some(tween));
+		sequence.wait(Duration::Seconds(half_period));
+		sequence.set_parameter(parameter_id, 1.0, Some(tween));
+		sequence.wait(Duration::Seconds(half_period));
+		let (sequence_id, _) = self
+			.audio_manager
+			.start_sequence(sequence, Default::default())?;
+		self.lfo = Some(Lfo {
+			sequence_id,
+			start_time: Instant::now(),
+		});
+		Ok(())
+	}
+
+	fn stop_lfo(&mut self) -> Result<(), Box<dyn Error>> {
+		if let Some(lfo) = self.lfo.take() {
+			self.audio_manager.stop_sequence(lfo.sequence_id)?;
+		}
+		Ok(())
+	}
+
+	/// Moves a parameter back to the top of its range, where it
+	/// sits when the LFO isn't moving it.
+	fn reset_parameter(&mut self, target: Target) -> Result<(), Box<dyn Error>> {
+		self.audio_manager.set_parameter(
+			self.target_parameter_id(target),
+			1.0,
+			Some(Tween::linear(0.05)),
+		)?;
+		Ok(())
+	}
+
+	fn restart_lfo(&mut self) -> Result<(), Box<dyn Error>> {
+		if self.lfo.is_some() {
+			self.start_lfo()?;
+		}
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if self.tone_instance_id.is_none() => {
+				self.tone_instance_id = Some(self.audio_manager.play(
+					self.tone_sound_id,
+					InstanceSettings::new().volume(Value::Parameter(
+						self.volume_parameter_id,
+						Mapping {
+							clamp_bottom: true,
+							clamp_top: true,
+							..Default::default()
+						},
+					)),
+				)?);
+				self.start_lfo()?;
+			}
+			Message::Stop => {
+				if let Some(instance_id) = self.tone_instance_id.take() {
+					self.audio_manager.stop_instance(
+						instance_id,
+						StopInstanceSettings::new().fade_tween(Tween::linear(0.1)),
+					)?;
+				}
+				self.stop_lfo()?;
+				self.reset_parameter(self.target)?;
+			}
+			Message::SetTarget(target) if target != self.target => {
+				self.reset_parameter(self.target)?;
+				self.target = target;
+				self.restart_lfo()?;
+			}
+			Message::SetShape(shape) => {
+				self.shape = shape;
+				self.restart_lfo()?;
+			}
+			Message::SetRate(rate) => {
+				self.rate = rate.clamp(MIN_RATE, MAX_RATE);
+				self.restart_lfo()?;
+			}
+			Message::SetDepth(depth) => {
+				self.depth = depth.clamp(0.0, 1.0);
+				self.restart_lfo()?;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	// nothing to check, but the screen has to be redrawn
+	// regularly for the plot's dot to move
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		Ok(())
+	}
+
+	fn value_text(&self) -> String {
+		let value = self
+			.lfo_phase()
+			.map_or(1.0, |phase| self.lfo_value(phase * self.period()));
+		match self.target {
+			Target::Cutoff => format!(
+				"Cutoff: {:.0} Hz",
+				MIN_CUTOFF + (MAX_CUTOFF - MIN_CUTOFF) * value
+			),
+			Target::Volume => format!("Volume: {:.0}%", value * 100.0),
+		}
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.tone_instance_id.is_some();
+		let target = self.target;
+		let shape = self.shape;
+		let period = self.period();
+		let points = (0..NUM_PLOT_POINTS)
+			.map(|i| {
+				let time = period * i as f64 / (NUM_PLOT_POINTS - 1) as f64;
+				self.lfo_value(time) as f32
+			})
+			.collect();
+		let mut plot = CurvePlot::new(points, PLOT_WIDTH, PLOT_HEIGHT);
+		if let Some(phase) = self.lfo_phase() {
+			plot = plot.marker(phase as f32);
+		}
+		let value_text = self.value_text();
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Button::new(
+						&mut self.play_button,
+						Text::new(if playing { "Stop" } else { "Play" })
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(if playing {
+						Message::Stop
+					} else {
+						Message::Play
+					})
+					.width(Length::Units(120))
+					.style(AppStyles),
+				)
+				.push(
+					Target::ALL
+						.iter()
+						.fold(Row::new().spacing(16), |row, option| {
+							row.push(Radio::new(
+								*option,
+								option.name(),
+								Some(target),
+								Message::SetTarget,
+							))
+						}),
+				)
+				.push(
+					Shape::ALL
+						.iter()
+						.fold(Row::new().spacing(16), |row, option| {
+							row.push(Radio::new(
+								*option,
+								option.name(),
+								Some(shape),
+								Message::SetShape,
+							))
+						}),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Rate: {:.2} Hz", self.rate))
+								.width(Length::Units(150)),
+						)
+						.push(
+							Slider::new(
+								&mut self.rate_slider,
+								MIN_RATE..=MAX_RATE,
+								self.rate,
+								Message::SetRate,
+							)
+							.step(0.05)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Depth: {:.0}%", self.depth * 100.0))
+								.width(Length::Units(150)),
+						)
+						.push(
+							Slider::new(
+								&mut self.depth_slider,
+								0.0..=1.0,
+								self.depth,
+								Message::SetDepth,
+							)
+							.step(0.01)
+							.style(AppStyles),
+						),
+				)
+				.push(plot)
+				.push(Text::new(value_text))
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for LfoDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "lfo_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetRate(_) => Some("Rate".into()),
+			Message::SetDepth(_) => Some("Depth".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Rate" => vec![Message::SetRate(MIN_RATE + (MAX_RATE - MIN_RATE) * value)],
+			"Depth" => vec![Message::SetDepth(value)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for LfoDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "lfo_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetTarget(self.target),
+			Message::SetShape(self.shape),
+			Message::SetRate(self.rate),
+			Message::SetDepth(self.depth),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for LfoDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.tone_instance_id.is_some(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		self.tone_instance_id.map(|_| "LFO demo".into())
+	}
+}
@@ -0,0 +1,433 @@
+mod benchmark;
+
+use std::error::Error;
+
+use iced::{Align, Button, Column, Length, Row, Text};
+use kira::{
+	instance::InstanceSettings,
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::ParameterId,
+	playable::PlayableSettings,
+	sequence::Sequence,
+	sound::SoundId,
+	Duration,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, synth},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+use self::benchmark::{Benchmark, BenchmarkReport};
+
+const EXPLANATION_TEXT: &str = "This demo goes over each of the \
+audio manager's capacities on purpose. They can be changed on the \
+settings screen.
+
+Instances, sequences, and parameters are sent to the audio thread \
+a few at a time, so the command queue never fills up. Kira doesn't \
+return an error when there's no room left for them - the audio \
+thread allocates more memory instead, which can cause glitches.
+
+Commands are sent all at once. The command queue can't grow, so if \
+the audio thread doesn't get to them in time, Kira returns a \
+CommandQueueFull error.
+
+The benchmark plays more and more instances until the audio thread \
+starts falling behind, then measures how many commands it can take \
+in per second. Kira doesn't report buffer underruns, so stalls are \
+estimated from a sequence that checks in at a steady rate. Results \
+are saved to the benchmarks folder and can be compared with earlier \
+runs.";
+
+/// How long each instance and sequence lasts, so that they all
+/// exist at the same time.
+const HOLD_TIME: f64 = 2.0;
+/// How many earlier reports are listed for comparing.
+const MAX_LISTED_REPORTS: usize = 5;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Limit {
+	Instances,
+	Sequences,
+	Parameters,
+	Commands,
+}
+
+impl Limit {
+	const ALL: [Self; 4] = [
+		Self::Instances,
+		Self::Sequences,
+		Self::Parameters,
+		Self::Commands,
+	];
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::Instances => "instances",
+			Self::Sequences => "sequences",
+			Self::Parameters => "parameters",
+			Self::Commands => "commands",
+		}
+	}
+
+	fn capacity(self, settings: &AudioManagerSettings) -> usize {
+		match self {
+			Self::Instances => settings.num_instances,
+			Self::Sequences => settings.num_sequences,
+			Self::Parameters => settings.num_parameters,
+			Self::Commands => settings.num_commands,
+		}
+	}
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Exhaust(Limit),
+	RunBenchmark,
+	/// Compares the latest report with one of the earlier ones
+	/// (by its index in the list).
+	CompareWith(usize),
+}
+
+struct Run {
+	limit: Limit,
+	sent: usize,
+	target: usize,
+}
+
+pub struct LimitsDemo {
+	audio_manager: AudioManager,
+	settings: AudioManagerSettings,
+	sound_id: SoundId,
+	parameter_id: ParameterId,
+	run: Option<Run>,
+	/// The looping sound the benchmark piles up instances of.
+	voice_sound_id: SoundId,
+	benchmark: Option<Box<Benchmark>>,
+	/// The latest benchmark result, which is the newest saved
+	/// report until the benchmark is run.
+	report: Option<BenchmarkReport>,
+	/// Where the benchmark's result was saved to.
+	saved_to: Option<String>,
+	/// Earlier reports, newest first.
+	previous_reports: Vec<BenchmarkReport>,
+	comparison: Option<Vec<String>>,
+	screen_wrapper: ScreenWrapper<Message>,
+	exhaust_buttons: [iced::button::State; 4],
+	benchmark_button: iced::button::State,
+	compare_buttons: Vec<iced::button::State>,
+}
+
+impl LimitsDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let settings = audio_manager_settings.clone();
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		// without a cooldown, every play command starts a new instance
+		let sound_id = audio_manager.add_sound(synth::blip(
+			440.0,
+			HOLD_TIME,
+			PlayableSettings {
+				cooldown: None,
+				..Default::default()
+			},
+		))?;
+		let voice_sound_id = audio_manager.add_sound(synth::blip(
+			220.0,
+			HOLD_TIME,
+			PlayableSettings {
+				cooldown: None,
+				..PlayableSettings::new().default_loop_start(0.0)
+			},
+		))?;
+		let parameter_id = audio_manager.add_parameter(0.0)?;
+		let mut previous_reports = BenchmarkReport::load_all();
+		let report = if previous_reports.is_empty() {
+			None
+		} else {
+			Some(previous_reports.remove(0))
+		};
+		previous_reports.truncate(MAX_LISTED_REPORTS);
+		Ok(Self {
+			audio_manager,
+			settings,
+			sound_id,
+			parameter_id,
+			run: None,
+			voice_sound_id,
+			benchmark: None,
+			report,
+			saved_to: None,
+			compare_buttons: previous_reports
+				.iter()
+				.map(|_| Default::default())
+				.collect(),
+			previous_reports,
+			comparison: None,
+			screen_wrapper: ScreenWrapper::new("Limits demo".into(), Message::GoToDemoSelect),
+			exhaust_buttons: Default::default(),
+			benchmark_button: Default::default(),
+		})
+	}
+
+	/// Sends one more of the resource the current run is using up.
+	fn send(&mut self, limit: Limit, target: usize) -> Result<(), Box<dyn Error>> {
+		match limit {
+			Limit::Instances => {
+				// every instance plays at once, so they're kept quiet
+				// enough to add up to one normal sound
+				self.audio_manager.play(
+					self.sound_id,
+					InstanceSettings::new().volume(1.0 / target as f64),
+				)?;
+			}
+			Limit::Sequences => {
+				let mut sequence = Sequence::<()>::new(Default::default());
+				sequence.wait(Duration::Seconds(HOLD_TIME));
+				self.audio_manager
+					.start_sequence(sequence, Default::default())?;
+			}
+			Limit::Parameters => {
+				self.audio_manager.add_parameter(0.0)?;
+			}
+			Limit::Commands => {
+				self.audio_manager
+					.set_parameter(self.parameter_id, 0.0, None)?;
+			}
+		}
+		Ok(())
+	}
+
+	fn tick_benchmark(&mut self) -> Result<(), Box<dyn Error>> {
+		let benchmark = match &mut self.benchmark {
+			Some(benchmark) => benchmark,
+			None => return Ok(()),
+		};
+		let report = match benchmark.tick(
+			&mut self.audio_manager,
+			self.voice_sound_id,
+			self.parameter_id,
+		)? {
+			Some(report) => report,
+			None => return Ok(()),
+		};
+		self.benchmark = None;
+		let path = report.save()?;
+		// the report that was just made can be compared against
+		// whatever was there before it
+		if let Some(previous_report) = self.report.take() {
+			self.previous_reports.insert(0, previous_report);
+			self.previous_reports.truncate(MAX_LISTED_REPORTS);
+			self.compare_buttons = self
+				.previous_reports
+				.iter()
+				.map(|_| Default::default())
+				.collect();
+		}
+		self.report = Some(report);
+		self.saved_to = Some(path.display().to_string());
+		self.comparison = None;
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		self.tick_benchmark()?;
+		let (limit, sent, target) = match &self.run {
+			Some(run) if run.limit != Limit::Commands && run.sent < run.target => {
+				(run.limit, run.sent, run.target)
+			}
+			_ => return Ok(()),
+		};
+		// only use a quarter of the command queue each tick so the
+		// audio thread can keep up
+		let batch_size = (self.settings.num_commands / 4).max(1);
+		let count = batch_size.min(target - sent);
+		for _ in 0..count {
+			self.send(limit, target)?;
+		}
+		if let Some(run) = &mut self.run {
+			run.sent += count;
+		}
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			// the benchmark and the limit runs would throw each
+			// other's results off
+			_ if self.benchmark.is_some() => {}
+			Message::RunBenchmark => {
+				self.run = None;
+				self.benchmark = Some(Box::new(Benchmark::start(
+					&mut self.audio_manager,
+					self.settings.clone(),
+				)?));
+			}
+			Message::CompareWith(index) => {
+				if let (Some(report), Some(baseline)) =
+					(&self.report, self.previous_reports.get(index))
+				{
+					self.comparison = Some(report.compare(baseline));
+				}
+			}
+			Message::Exhaust(limit) => self.exhaust(limit)?,
+			Message::GoToDemoSelect => {}
+		}
+		Ok(())
+	}
+
+	fn exhaust(&mut self, limit: Limit) -> Result<(), Box<dyn Error>> {
+		let target = limit.capacity(&self.settings) + 1;
+		self.run = Some(Run {
+			limit,
+			sent: 0,
+			target,
+		});
+		if limit == Limit::Commands {
+			for _ in 0..target {
+				self.send(limit, target)?;
+				if let Some(run) = &mut self.run {
+					run.sent += 1;
+				}
+			}
+		}
+		Ok(())
+	}
+
+	fn status(&self) -> String {
+		if let Some(benchmark) = &self.benchmark {
+			return benchmark.status();
+		}
+		let run = match &self.run {
+			Some(run) => run,
+			None => return "Press a button to go over one of the limits.".into(),
+		};
+		let capacity = run.limit.capacity(&self.settings);
+		if run.limit == Limit::Commands && run.sent < run.target {
+			return format!(
+				"The command queue filled up after {} of {} commands.",
+				run.sent, run.target
+			);
+		}
+		if run.sent < run.target {
+			return format!(
+				"Sent {} of {} {} (room for {})...",
+				run.sent,
+				run.target,
+				run.limit.name(),
+				capacity
+			);
+		}
+		match run.limit {
+			Limit::Commands => format!(
+				"All {} commands fit - the audio thread emptied the queue \
+				before it filled up. Try lowering \"Commands\" in the settings.",
+				run.target
+			),
+			_ => format!(
+				"Sent {} {} with room for {}. No error was returned - the \
+				audio thread made room by allocating more memory.",
+				run.target,
+				run.limit.name(),
+				capacity
+			),
+		}
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let status = self.status();
+		let settings = &self.settings;
+		let buttons = self.exhaust_buttons.iter_mut().zip(&Limit::ALL).fold(
+			Column::new().spacing(8),
+			|column, (button, limit)| {
+				let capacity = limit.capacity(settings);
+				column.push(
+					Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Button::new(
+								button,
+								Text::new(format!("Send {} {}", capacity + 1, limit.name())),
+							)
+							.width(Length::Units(240))
+							.on_press(Message::Exhaust(*limit))
+							.style(AppStyles),
+						)
+						.push(Text::new(format!("Room for {}", capacity))),
+				)
+			},
+		);
+		let mut benchmark_button =
+			Button::new(&mut self.benchmark_button, Text::new("Run benchmark"))
+				.width(Length::Units(240))
+				.style(AppStyles);
+		if self.benchmark.is_none() {
+			benchmark_button = benchmark_button.on_press(Message::RunBenchmark);
+		}
+		let mut results = Column::new()
+			.width(Length::Fill)
+			.max_width(600)
+			.spacing(8)
+			.push(Text::new(status));
+		if let Some(report) = &self.report {
+			let heading = match &self.saved_to {
+				Some(path) => format!("Benchmark results (saved to {}):", path),
+				None => format!("Last benchmark results ({}):", report.label()),
+			};
+			results = results.push(Text::new(heading));
+			for line in report.summary() {
+				results = results.push(Text::new(line).size(16));
+			}
+			if !self.previous_reports.is_empty() {
+				results = results.push(Text::new("Compare with:"));
+			}
+			for (index, (button, previous_report)) in self
+				.compare_buttons
+				.iter_mut()
+				.zip(&self.previous_reports)
+				.enumerate()
+			{
+				results = results.push(
+					Button::new(button, Text::new(previous_report.label()).size(16))
+						.on_press(Message::CompareWith(index))
+						.style(AppStyles),
+				);
+			}
+			if let Some(comparison) = &self.comparison {
+				for line in comparison {
+					results = results.push(Text::new(line).size(16));
+				}
+			}
+		}
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(buttons)
+				.push(benchmark_button)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.spacing(16)
+						.push(results)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for LimitsDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "limits_demo";
+
+	// the screen only has buttons, so there's nothing to put back,
+	// but recorded sessions still need a name for it
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![]
+	}
+}
@@ -0,0 +1,383 @@
+use std::{
+	error::Error,
+	fs,
+	path::{Path, PathBuf},
+	time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use kira::{
+	instance::InstanceSettings,
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::ParameterId,
+	sequence::{EventReceiver, Sequence, SequenceInstanceId},
+	sound::SoundId,
+	AudioError, Duration,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::preferences::AudioCapacities;
+
+/// The version of Kira the app is built with (see Cargo.toml).
+const KIRA_VERSION: &str = "0.3";
+/// How often the heartbeat sequence checks in (in seconds).
+const HEARTBEAT_INTERVAL: f64 = 0.05;
+/// How far the audio thread has to fall behind (in seconds)
+/// during a step for it to count as a stall.
+const STALL_THRESHOLD: f64 = 0.02;
+/// How long each step of the voice test lasts (in seconds).
+const VOICE_STEP_TIME: f64 = 1.0;
+/// How many steps the voice test takes to reach the instance
+/// capacity.
+const NUM_VOICE_STEPS: usize = 16;
+/// How long the command test lasts (in seconds).
+const COMMAND_TEST_TIME: f64 = 1.0;
+
+/// The output device the benchmark ran on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeviceInfo {
+	pub name: String,
+	pub sample_rate: u32,
+	pub channels: u16,
+}
+
+impl DeviceInfo {
+	/// Describes the default output device, which is the one
+	/// the demos play through.
+	fn default_output() -> Option<Self> {
+		use cpal::traits::{DeviceTrait, HostTrait};
+		let device = cpal::default_host().default_output_device()?;
+		let config = device.default_output_config().ok()?;
+		Some(Self {
+			name: device.name().ok()?,
+			sample_rate: config.sample_rate().0,
+			channels: config.channels(),
+		})
+	}
+}
+
+/// The results of a benchmark run, as they're saved to a report.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+	/// When the benchmark was run (as a Unix timestamp).
+	pub created: u64,
+	pub app_version: String,
+	pub kira_version: String,
+	pub os: String,
+	pub arch: String,
+	pub device: Option<DeviceInfo>,
+	pub capacities: AudioCapacities,
+	/// The most instances that played at once without the audio
+	/// thread stalling.
+	pub max_clean_voices: usize,
+	/// Whether the voice test stopped because it reached the
+	/// instance capacity rather than because the audio stalled.
+	pub voices_limited_by_capacity: bool,
+	/// How many commands the audio thread took in per second.
+	pub commands_per_second: f64,
+	/// How many times the audio thread fell behind during the run.
+	/// Kira doesn't report buffer underruns, so these are counted
+	/// from a sequence that checks in at a steady rate.
+	pub stalls: usize,
+}
+
+impl BenchmarkReport {
+	fn dir() -> Result<PathBuf, Box<dyn Error>> {
+		Ok(std::env::current_dir()?.join("benchmarks"))
+	}
+
+	pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+		Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+	}
+
+	/// Loads every report in the `benchmarks` folder, newest first.
+	/// Files that aren't reports are skipped.
+	pub fn load_all() -> Vec<Self> {
+		let entries = match Self::dir().and_then(|dir| Ok(fs::read_dir(dir)?)) {
+			Ok(entries) => entries,
+			Err(_) => return vec![],
+		};
+		let mut reports: Vec<Self> = entries
+			.flatten()
+			.filter_map(|entry| Self::load(&entry.path()).ok())
+			.collect();
+		reports.sort_by_key(|report| std::cmp::Reverse(report.created));
+		reports
+	}
+
+	/// Saves the report to the `benchmarks` folder.
+	pub fn save(&self) -> Result<PathBuf, Box<dyn Error>> {
+		let dir = Self::dir()?;
+		fs::create_dir_all(&dir)?;
+		let path = dir.join(format!("benchmark-{}.json", self.created));
+		fs::write(&path, serde_json::to_string_pretty(self)?)?;
+		Ok(path)
+	}
+
+	pub fn summary(&self) -> Vec<String> {
+		let device = match &self.device {
+			Some(device) => format!(
+				"{} ({} Hz, {} channels)",
+				device.name, device.sample_rate, device.channels
+			),
+			None => "unknown device".into(),
+		};
+		vec![
+			format!(
+				"kira-demo {} with Kira {} on {} {}, {}",
+				self.app_version, self.kira_version, self.os, self.arch, device
+			),
+			format!(
+				"Max clean voices: {}{}",
+				self.max_clean_voices,
+				if self.voices_limited_by_capacity {
+					" (the instance capacity)"
+				} else {
+					""
+				}
+			),
+			format!("Commands per second: {:.0}", self.commands_per_second),
+			format!("Stalls: {}", self.stalls),
+		]
+	}
+
+	/// A short description for picking the report out of a list.
+	pub fn label(&self) -> String {
+		let now = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map_or(self.created, |now| now.as_secs());
+		let age = now.saturating_sub(self.created);
+		let age = match age {
+			0..=59 => "just now".into(),
+			60..=3599 => format!("{} min. ago", age / 60),
+			3600..=86399 => format!("{} hr. ago", age / 3600),
+			_ => format!("{} days ago", age / 86400),
+		};
+		match &self.device {
+			Some(device) => format!("{} on {}", age, device.name),
+			None => age,
+		}
+	}
+
+	/// Describes how this report's results differ from an
+	/// earlier one's.
+	pub fn compare(&self, baseline: &Self) -> Vec<String> {
+		fn change(value: f64, baseline: f64) -> String {
+			if baseline == 0.0 {
+				return "".into();
+			}
+			format!(" ({:+.0}%)", (value / baseline - 1.0) * 100.0)
+		}
+		let mut lines = vec![
+			format!(
+				"Max clean voices: {} vs. {}{}",
+				self.max_clean_voices,
+				baseline.max_clean_voices,
+				change(
+					self.max_clean_voices as f64,
+					baseline.max_clean_voices as f64
+				)
+			),
+			format!(
+				"Commands per second: {:.0} vs. {:.0}{}",
+				self.commands_per_second,
+				baseline.commands_per_second,
+				change(self.commands_per_second, baseline.commands_per_second)
+			),
+			format!("Stalls: {} vs. {}", self.stalls, baseline.stalls),
+		];
+		if self.capacities != baseline.capacities {
+			lines.push(
+				"The reports used different capacities, so the voice \
+				counts may not be comparable."
+					.into(),
+			);
+		}
+		if self.device != baseline.device {
+			lines.push("The reports were made on different devices.".into());
+		}
+		lines
+	}
+}
+
+enum Stage {
+	Voices { step_start: Instant },
+	Commands { start: Instant },
+}
+
+/// Pushes the audio manager harder and harder and measures when
+/// it starts to struggle.
+///
+/// First, more and more instances are played until the instance
+/// capacity is reached or the audio thread stalls. Then as many
+/// commands as will fit are sent for a second. The whole time, a
+/// heartbeat sequence emits an event at a steady rate. Sequences
+/// run on the audio thread's clock, so when the audio thread
+/// stalls, the heartbeat falls behind the real time.
+pub struct Benchmark {
+	settings: AudioManagerSettings,
+	stage: Stage,
+	heartbeat: (SequenceInstanceId, EventReceiver<()>),
+	heartbeat_start: Instant,
+	num_heartbeats: usize,
+	/// The smallest amount the heartbeat was behind the real time
+	/// during the current step. Events are only picked up once per
+	/// UI tick, so the smallest lag is the most accurate.
+	step_lag: Option<f64>,
+	previous_step_lag: Option<f64>,
+	stalls: usize,
+	voices: usize,
+	max_clean_voices: usize,
+	voices_limited_by_capacity: bool,
+	commands_sent: usize,
+}
+
+impl Benchmark {
+	pub fn start(
+		audio_manager: &mut AudioManager,
+		settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut sequence = Sequence::new(Default::default());
+		sequence.start_loop();
+		sequence.emit(());
+		sequence.wait(Duration::Seconds(HEARTBEAT_INTERVAL));
+		let heartbeat = audio_manager.start_sequence(sequence, Default::default())?;
+		Ok(Self {
+			settings,
+			stage: Stage::Voices {
+				step_start: Instant::now(),
+			},
+			heartbeat,
+			heartbeat_start: Instant::now(),
+			num_heartbeats: 0,
+			step_lag: None,
+			previous_step_lag: None,
+			stalls: 0,
+			voices: 0,
+			max_clean_voices: 0,
+			voices_limited_by_capacity: false,
+			commands_sent: 0,
+		})
+	}
+
+	/// Describes what the benchmark is doing.
+	pub fn status(&self) -> String {
+		match self.stage {
+			Stage::Voices { .. } => format!("Testing voices: {} playing...", self.voices),
+			Stage::Commands { .. } => "Testing command throughput...".into(),
+		}
+	}
+
+	fn check_heartbeat(&mut self) {
+		while self.heartbeat.1.pop().is_some() {
+			let expected_time = self.num_heartbeats as f64 * HEARTBEAT_INTERVAL;
+			let lag = self.heartbeat_start.elapsed().as_secs_f64() - expected_time;
+			self.step_lag = Some(self.step_lag.map_or(lag, |step_lag| step_lag.min(lag)));
+			self.num_heartbeats += 1;
+		}
+	}
+
+	/// Checks whether the audio thread fell behind during the
+	/// step that just finished and starts a new one.
+	fn finish_step(&mut self) -> bool {
+		let stalled = match (self.previous_step_lag, self.step_lag) {
+			(Some(previous), Some(current)) => current - previous > STALL_THRESHOLD,
+			// no heartbeats at all means the audio thread is stuck
+			(_, None) => true,
+			(None, Some(_)) => false,
+		};
+		if stalled {
+			self.stalls += 1;
+		}
+		if self.step_lag.is_some() {
+			self.previous_step_lag = self.step_lag;
+		}
+		self.step_lag = None;
+		stalled
+	}
+
+	/// Moves the benchmark along. Returns the report once it's done.
+	pub fn tick(
+		&mut self,
+		audio_manager: &mut AudioManager,
+		voice_sound_id: SoundId,
+		parameter_id: ParameterId,
+	) -> Result<Option<BenchmarkReport>, Box<dyn Error>> {
+		self.check_heartbeat();
+		match self.stage {
+			Stage::Voices { step_start } => {
+				if step_start.elapsed().as_secs_f64() < VOICE_STEP_TIME {
+					return Ok(None);
+				}
+				let stalled = self.finish_step();
+				if !stalled {
+					self.max_clean_voices = self.voices;
+				}
+				let capacity = self.settings.num_instances;
+				if stalled || self.voices >= capacity {
+					self.voices_limited_by_capacity = !stalled;
+					audio_manager.stop_instances_of(voice_sound_id.into(), Default::default())?;
+					self.stage = Stage::Commands {
+						start: Instant::now(),
+					};
+					return Ok(None);
+				}
+				// the plays are sent in one go, so there has to be
+				// room for them in the command queue
+				let step = (capacity / NUM_VOICE_STEPS)
+					.max(1)
+					.min(self.settings.num_commands / 2)
+					.min(capacity - self.voices);
+				for _ in 0..step {
+					audio_manager.play(
+						voice_sound_id,
+						InstanceSettings::new().volume(1.0 / capacity as f64),
+					)?;
+				}
+				self.voices += step;
+				self.stage = Stage::Voices {
+					step_start: Instant::now(),
+				};
+				Ok(None)
+			}
+			Stage::Commands { start } => {
+				let elapsed = start.elapsed().as_secs_f64();
+				if elapsed < COMMAND_TEST_TIME {
+					// fill up whatever room the audio thread made in
+					// the queue since the last tick
+					for _ in 0..self.settings.num_commands {
+						match audio_manager.set_parameter(parameter_id, 0.0, None) {
+							Ok(()) => self.commands_sent += 1,
+							Err(AudioError::CommandQueueFull) => break,
+							Err(error) => return Err(error.into()),
+						}
+					}
+					return Ok(None);
+				}
+				self.finish_step();
+				audio_manager.stop_sequence(self.heartbeat.0)?;
+				Ok(Some(self.report(elapsed)?))
+			}
+		}
+	}
+
+	fn report(&self, command_test_time: f64) -> Result<BenchmarkReport, Box<dyn Error>> {
+		Ok(BenchmarkReport {
+			created: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+			app_version: env!("CARGO_PKG_VERSION").into(),
+			kira_version: KIRA_VERSION.into(),
+			os: std::env::consts::OS.into(),
+			arch: std::env::consts::ARCH.into(),
+			device: DeviceInfo::default_output(),
+			capacities: AudioCapacities {
+				num_instances: self.settings.num_instances,
+				num_sequences: self.settings.num_sequences,
+				num_parameters: self.settings.num_parameters,
+				num_commands: self.settings.num_commands,
+			},
+			max_clean_voices: self.max_clean_voices,
+			voices_limited_by_capacity: self.voices_limited_by_capacity,
+			commands_per_second: self.commands_sent as f64 / command_test_time,
+			stalls: self.stalls,
+		})
+	}
+}
@@ -0,0 +1,409 @@
+mod region_editor;
+
+use std::{error::Error, time::Instant};
+
+use iced::{Align, Button, Checkbox, Column, HorizontalAlignment, Length, Row, Text};
+use kira::{
+	arrangement::{Arrangement, ArrangementId, SoundClip},
+	instance::{InstanceId, InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::Tween,
+	playable::PlayableSettings,
+	sound::SoundId,
+	Tempo,
+};
+use serde::{Deserialize, Serialize};
+
+use self::region_editor::RegionEditor;
+use crate::{
+	audio::{create_audio_manager, decoded_sound::DecodedSound, sound_cache::SoundCache},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &[
+	"underwater demo/drums.ogg",
+	"underwater demo/bass.ogg",
+	"underwater demo/pad.ogg",
+	"underwater demo/lead.ogg",
+];
+
+const EXPLANATION_TEXT: &str = "This demo loops part of a song. \
+Drag the yellow handles to change which part.
+
+The loop is an arrangement with a single clip, which plays the \
+region of the song and jumps back to the start of the \
+arrangement when it reaches the end. Arrangements can't be \
+changed once they're added to the audio manager, so moving a \
+handle builds a new one. The new loop starts from wherever the \
+old one was (or from the top of the region, if the playhead is \
+now outside of it), and the two crossfade over a few \
+milliseconds so there's no click.";
+
+const TEMPO: Tempo = Tempo(85.0);
+const NUM_BEATS: usize = 16;
+const NUM_PEAKS: usize = 200;
+/// The shortest region allowed when it isn't snapped to beats
+/// (in seconds).
+const MIN_REGION_LENGTH: f64 = 0.05;
+/// How long the old and new loops overlap when the region
+/// changes (in seconds).
+const CROSSFADE_TIME: f64 = 0.02;
+/// The loop is rebuilt at most this often while a handle is
+/// dragged (in seconds), so a fast drag doesn't pile up
+/// arrangements.
+const REBUILD_INTERVAL: f64 = 0.05;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	/// Sets the start and end of the loop region (from 0 to 1).
+	SetRegion(f32, f32),
+	SetSnapToBeats(bool),
+}
+
+struct Playback {
+	arrangement_id: ArrangementId,
+	instance_id: InstanceId,
+	/// The region this arrangement loops (in seconds).
+	region: (f64, f64),
+	start_position: f64,
+	start_time: Instant,
+}
+
+pub struct LoopRegionDemo {
+	audio_manager: AudioManager,
+	sound_id: SoundId,
+	duration: f64,
+	peaks: Vec<f32>,
+	/// The start and end of the loop region (in seconds).
+	region: (f64, f64),
+	snap_to_beats: bool,
+	playback: Option<Playback>,
+	/// Set when the region has moved since the loop was last
+	/// rebuilt.
+	region_changed: bool,
+	/// Arrangements that are fading out, with when they can be
+	/// removed.
+	old_arrangements: Vec<(ArrangementId, Instant)>,
+	rebuilds: usize,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_button: iced::button::State,
+	region_editor: region_editor::State,
+}
+
+impl LoopRegionDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let assets_base_dir = std::env::current_dir()?.join("assets/underwater demo");
+		let song = DecodedSound::mix(&[
+			&*sound_cache.get(&assets_base_dir.join("drums.ogg"))?,
+			&*sound_cache.get(&assets_base_dir.join("bass.ogg"))?,
+			&*sound_cache.get(&assets_base_dir.join("pad.ogg"))?,
+			&*sound_cache.get(&assets_base_dir.join("lead.ogg"))?,
+		]);
+		let sound_id = audio_manager.add_sound(song.to_sound(Default::default()))?;
+		let duration = song.duration();
+		Ok(Self {
+			audio_manager,
+			sound_id,
+			duration,
+			peaks: song.peaks(NUM_PEAKS),
+			region: (
+				TEMPO.beats_to_seconds(4.0),
+				TEMPO.beats_to_seconds(8.0).min(duration),
+			),
+			snap_to_beats: true,
+			playback: None,
+			region_changed: false,
+			old_arrangements: vec![],
+			rebuilds: 0,
+			screen_wrapper: ScreenWrapper::new("Loop region demo".into(), Message::GoToDemoSelect),
+			play_button: iced::button::State::new(),
+			region_editor: Default::default(),
+		})
+	}
+
+	/// Kira doesn't report the playback position of instances,
+	/// so we estimate it from when the instance was started.
+	fn playback_position(&self) -> Option<f64> {
+		self.playback.as_ref().map(|playback| {
+			let (start, end) = playback.region;
+			let position_in_loop = (playback.start_position
+				+ playback.start_time.elapsed().as_secs_f64())
+				% (end - start);
+			start + position_in_loop
+		})
+	}
+
+	/// Builds an arrangement that loops the current region and
+	/// starts playing it from the given position (in seconds
+	/// from the start of the region).
+	fn start_loop(&mut self, start_position: f64, fade_in_time: f64) -> Result<(), Box<dyn Error>> {
+		let (start, end) = self.region;
+		let mut arrangement = Arrangement::new(PlayableSettings {
+			default_loop_start: Some(0.0),
+			..Default::default()
+		});
+		arrangement.add_clip(SoundClip {
+			sound_id: self.sound_id,
+			clip_time_range: (0.0, end - start),
+			sound_time_range: (start, end),
+		});
+		let arrangement_id = self.audio_manager.add_arrangement(arrangement)?;
+		let instance_id = self.audio_manager.play(
+			arrangement_id,
+			InstanceSettings::new()
+				.start_position(start_position)
+				.fade_in_tween(Tween::linear(fade_in_time)),
+		)?;
+		self.playback = Some(Playback {
+			arrangement_id,
+			instance_id,
+			region: self.region,
+			start_position,
+			start_time: Instant::now(),
+		});
+		self.region_changed = false;
+		Ok(())
+	}
+
+	/// Fades out the current loop. Removing an arrangement stops
+	/// its instances right away, so the arrangement is only
+	/// removed once the fade is over.
+	fn stop_loop(&mut self, fade_out_time: f64) -> Result<(), Box<dyn Error>> {
+		if let Some(playback) = self.playback.take() {
+			self.audio_manager.stop_instance(
+				playback.instance_id,
+				StopInstanceSettings::new().fade_tween(Tween::linear(fade_out_time)),
+			)?;
+			self.old_arrangements.push((
+				playback.arrangement_id,
+				Instant::now() + std::time::Duration::from_secs_f64(fade_out_time * 2.0),
+			));
+		}
+		Ok(())
+	}
+
+	/// Swaps the playing loop for one with the current region.
+	fn rebuild(&mut self) -> Result<(), Box<dyn Error>> {
+		let position = match self.playback_position() {
+			Some(position) => position,
+			None => return Ok(()),
+		};
+		let (start, end) = self.region;
+		let start_position = if position >= start && position < end {
+			position - start
+		} else {
+			0.0
+		};
+		self.stop_loop(CROSSFADE_TIME)?;
+		self.start_loop(start_position, CROSSFADE_TIME)?;
+		self.rebuilds += 1;
+		Ok(())
+	}
+
+	fn snap(&self, position: f64) -> f64 {
+		if self.snap_to_beats {
+			let beat = TEMPO.beats_to_seconds(1.0);
+			((position / beat).round() * beat).min(self.duration)
+		} else {
+			position
+		}
+	}
+
+	fn set_region(&mut self, start: f64, end: f64) {
+		let min_length = if self.snap_to_beats {
+			TEMPO.beats_to_seconds(1.0)
+		} else {
+			MIN_REGION_LENGTH
+		};
+		let mut start = self.snap(start.clamp(0.0, self.duration));
+		let mut end = self.snap(end.clamp(0.0, self.duration));
+		if end - start < min_length {
+			// push out whichever handle is being dragged
+			if (start - self.region.0).abs() > (end - self.region.1).abs() {
+				start = (end - min_length).max(0.0);
+				end = start + min_length;
+			} else {
+				end = (start + min_length).min(self.duration);
+				start = end - min_length;
+			}
+		}
+		if (start, end) != self.region {
+			self.region = (start, end);
+			self.region_changed = true;
+		}
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if self.playback.is_none() => self.start_loop(0.0, 0.0)?,
+			Message::Stop => self.stop_loop(0.1)?,
+			Message::SetRegion(start, end) => {
+				self.set_region(self.duration * start as f64, self.duration * end as f64);
+			}
+			Message::SetSnapToBeats(snap_to_beats) => {
+				self.snap_to_beats = snap_to_beats;
+				let (start, end) = self.region;
+				self.set_region(start, end);
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		if let Some(playback) = &self.playback {
+			if self.region_changed
+				&& playback.start_time.elapsed().as_secs_f64() >= REBUILD_INTERVAL
+			{
+				self.rebuild()?;
+			}
+		}
+		let now = Instant::now();
+		while let Some(index) = self
+			.old_arrangements
+			.iter()
+			.position(|(_, remove_time)| *remove_time <= now)
+		{
+			let (arrangement_id, _) = self.old_arrangements.remove(index);
+			self.audio_manager.remove_arrangement(arrangement_id)?;
+		}
+		self.audio_manager.free_unused_resources();
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let duration = self.duration;
+		let playing = self.playback.is_some();
+		let (start, end) = self.region;
+		let beat = TEMPO.beats_to_seconds(1.0);
+		let playback_position = self.playback_position();
+		let mut region_editor = RegionEditor::new(
+			&mut self.region_editor,
+			&self.peaks,
+			((start / duration) as f32, (end / duration) as f32),
+		)
+		.grid_lines(
+			(0..=NUM_BEATS)
+				.map(|beat| (TEMPO.beats_to_seconds(beat as f64) / duration) as f32)
+				.collect(),
+		)
+		.on_change(Message::SetRegion);
+		if let Some(position) = playback_position {
+			region_editor = region_editor.playhead((position / duration) as f32);
+		}
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(region_editor)
+				.push(
+					Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Button::new(
+								&mut self.play_button,
+								Text::new(if playing { "Stop" } else { "Play" })
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(if playing {
+								Message::Stop
+							} else {
+								Message::Play
+							})
+							.width(Length::Units(120))
+							.style(AppStyles),
+						)
+						.push(Checkbox::new(
+							self.snap_to_beats,
+							"Snap to beats",
+							Message::SetSnapToBeats,
+						)),
+				)
+				.push(Text::new(format!(
+					"Loop: {:.2}s - {:.2}s ({:.2}s, {:.1} beats)",
+					start,
+					end,
+					end - start,
+					(end - start) / beat,
+				)))
+				.push(Text::new(format!("Loop rebuilt {} times", self.rebuilds)).size(16))
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for LoopRegionDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "loop_region_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetRegion(..) => Some("Loop position".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		let (start, end) = self.region;
+		let length = (end - start) / self.duration;
+		match control {
+			// moves the whole region, keeping its length
+			"Loop position" => {
+				let start = value.min(1.0 - length);
+				vec![Message::SetRegion(start as f32, (start + length) as f32)]
+			}
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for LoopRegionDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "loop_region_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		// the region is set without snapping so it comes back
+		// exactly where it was
+		let (start, end) = self.region;
+		vec![
+			Message::SetSnapToBeats(false),
+			Message::SetRegion((start / self.duration) as f32, (end / self.duration) as f32),
+			Message::SetSnapToBeats(self.snap_to_beats),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for LoopRegionDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.playback.is_some(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		self.playback.as_ref().map(|_| "Loop region demo".into())
+	}
+}
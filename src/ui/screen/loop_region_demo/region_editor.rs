@@ -0,0 +1,297 @@
+use iced::{mouse::Interaction, Background, Color, Length, Point, Rectangle, Size};
+use iced_graphics::{Backend, Defaults, Primitive, Renderer};
+use iced_native::{
+	event::Status,
+	layout::{Limits, Node},
+	mouse, Clipboard, Element, Event, Layout, Widget,
+};
+
+const HEIGHT: u16 = 120;
+const BACKGROUND_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.05];
+const REGION_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.1];
+const PEAK_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.5];
+/// Peaks outside the loop region are dimmed, since they won't
+/// be heard.
+const OUTSIDE_PEAK_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.15];
+const GRID_LINE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.1];
+const HANDLE_COLOR: [f32; 4] = [1.0, 0.8, 0.0, 1.0];
+const PLAYHEAD_COLOR: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+const HANDLE_WIDTH: f32 = 3.0;
+/// The tabs at the top of the handles, which make them easier
+/// to spot (and to grab).
+const TAB_SIZE: f32 = 10.0;
+/// How close (in pixels) the cursor has to be to grab a handle.
+const GRAB_DISTANCE: f32 = 8.0;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Handle {
+	Start,
+	End,
+}
+
+/// Which handle is being dragged. This has to outlive the
+/// widget, which is rebuilt every time the screen is drawn.
+#[derive(Debug, Default)]
+pub struct State {
+	dragging: Option<Handle>,
+}
+
+/// Draws the peaks of a sound with a loop region on top. The
+/// start and end of the region (from 0 to 1) have handles the
+/// user can drag. Clicking elsewhere moves the closest handle
+/// there.
+pub struct RegionEditor<'a, Message> {
+	state: &'a mut State,
+	peaks: &'a [f32],
+	region: (f32, f32),
+	grid_lines: Vec<f32>,
+	playhead: Option<f32>,
+	on_change: Option<Box<dyn Fn(f32, f32) -> Message + 'a>>,
+}
+
+impl<'a, Message> RegionEditor<'a, Message> {
+	pub fn new(state: &'a mut State, peaks: &'a [f32], region: (f32, f32)) -> Self {
+		Self {
+			state,
+			peaks,
+			region,
+			grid_lines: vec![],
+			playhead: None,
+			on_change: None,
+		}
+	}
+
+	/// Draws faint vertical lines at the given positions (from 0 to 1).
+	pub fn grid_lines(self, grid_lines: Vec<f32>) -> Self {
+		Self { grid_lines, ..self }
+	}
+
+	/// Draws the playback position (from 0 to 1).
+	pub fn playhead(self, playhead: f32) -> Self {
+		Self {
+			playhead: Some(playhead),
+			..self
+		}
+	}
+
+	/// Reports the new start and end of the region while a
+	/// handle is dragged.
+	pub fn on_change(self, on_change: impl Fn(f32, f32) -> Message + 'a) -> Self {
+		Self {
+			on_change: Some(Box::new(on_change)),
+			..self
+		}
+	}
+
+	fn x(bounds: Rectangle, position: f32) -> f32 {
+		bounds.x + bounds.width * position.clamp(0.0, 1.0)
+	}
+
+	/// Converts a point on the screen to a position, keeping it
+	/// on the waveform even if the cursor has left it.
+	fn position_at(bounds: Rectangle, point: Point) -> f32 {
+		((point.x - bounds.x) / bounds.width).clamp(0.0, 1.0)
+	}
+
+	fn closest_handle(&self, bounds: Rectangle, point: Point) -> (Handle, f32) {
+		let (start, end) = self.region;
+		let start_distance = (Self::x(bounds, start) - point.x).abs();
+		let end_distance = (Self::x(bounds, end) - point.x).abs();
+		// when the handles overlap, the one on the side the cursor
+		// is on wins, so they can always be pulled apart
+		if start_distance < end_distance
+			|| (start_distance == end_distance && point.x < Self::x(bounds, start))
+		{
+			(Handle::Start, start_distance)
+		} else {
+			(Handle::End, end_distance)
+		}
+	}
+
+	/// Moves a handle to the given position without letting it
+	/// cross the other one.
+	fn moved_region(&self, handle: Handle, position: f32) -> (f32, f32) {
+		let (start, end) = self.region;
+		match handle {
+			Handle::Start => (position.min(end), end),
+			Handle::End => (start, position.max(start)),
+		}
+	}
+
+	fn quad(bounds: Rectangle, color: [f32; 4]) -> Primitive {
+		Primitive::Quad {
+			bounds,
+			background: Background::Color(color.into()),
+			border_radius: 0.0,
+			border_width: 0.0,
+			border_color: Color::TRANSPARENT,
+		}
+	}
+
+	fn vertical_line(bounds: Rectangle, position: f32, width: f32, color: [f32; 4]) -> Primitive {
+		Self::quad(
+			Rectangle::new(
+				Point::new(Self::x(bounds, position) - width / 2.0, bounds.y),
+				Size::new(width, bounds.height),
+			),
+			color,
+		)
+	}
+}
+
+impl<'a, Message, B: Backend> Widget<Message, Renderer<B>> for RegionEditor<'a, Message> {
+	fn width(&self) -> Length {
+		Length::Fill
+	}
+
+	fn height(&self) -> Length {
+		Length::Units(HEIGHT)
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, limits: &Limits) -> Node {
+		Node::new(
+			limits
+				.width(Length::Fill)
+				.height(Length::Units(HEIGHT))
+				.resolve(Size::ZERO),
+		)
+	}
+
+	fn on_event(
+		&mut self,
+		event: Event,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		messages: &mut Vec<Message>,
+		_renderer: &Renderer<B>,
+		_clipboard: Option<&dyn Clipboard>,
+	) -> Status {
+		let bounds = layout.bounds();
+		let on_change = match &self.on_change {
+			Some(on_change) => on_change,
+			None => return Status::Ignored,
+		};
+		match event {
+			Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+				if bounds.contains(cursor_position) =>
+			{
+				let (handle, _) = self.closest_handle(bounds, cursor_position);
+				self.state.dragging = Some(handle);
+				let (start, end) =
+					self.moved_region(handle, Self::position_at(bounds, cursor_position));
+				messages.push(on_change(start, end));
+				Status::Captured
+			}
+			Event::Mouse(mouse::Event::CursorMoved { .. }) => match self.state.dragging {
+				Some(handle) => {
+					let (start, end) =
+						self.moved_region(handle, Self::position_at(bounds, cursor_position));
+					messages.push(on_change(start, end));
+					Status::Captured
+				}
+				None => Status::Ignored,
+			},
+			Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
+				if self.state.dragging.is_some() =>
+			{
+				self.state.dragging = None;
+				Status::Captured
+			}
+			_ => Status::Ignored,
+		}
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		_defaults: &Defaults,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let (start, end) = self.region;
+		let mut primitives = vec![
+			Self::quad(bounds, BACKGROUND_COLOR),
+			Self::quad(
+				Rectangle::new(
+					Point::new(Self::x(bounds, start), bounds.y),
+					Size::new(Self::x(bounds, end) - Self::x(bounds, start), bounds.height),
+				),
+				REGION_COLOR,
+			),
+		];
+		for position in &self.grid_lines {
+			primitives.push(Self::vertical_line(bounds, *position, 1.0, GRID_LINE_COLOR));
+		}
+		let peak_width = bounds.width / self.peaks.len().max(1) as f32;
+		for (i, peak) in self.peaks.iter().enumerate() {
+			let peak_height = bounds.height * peak.min(1.0);
+			let center = (i as f32 + 0.5) / self.peaks.len() as f32;
+			let color = if center >= start && center <= end {
+				PEAK_COLOR
+			} else {
+				OUTSIDE_PEAK_COLOR
+			};
+			primitives.push(Self::quad(
+				Rectangle::new(
+					Point::new(
+						bounds.x + peak_width * i as f32,
+						bounds.y + (bounds.height - peak_height) / 2.0,
+					),
+					Size::new(peak_width.max(1.0), peak_height),
+				),
+				color,
+			));
+		}
+		if let Some(playhead) = self.playhead {
+			primitives.push(Self::vertical_line(bounds, playhead, 2.0, PLAYHEAD_COLOR));
+		}
+		for (handle, position) in &[(Handle::Start, start), (Handle::End, end)] {
+			primitives.push(Self::vertical_line(
+				bounds,
+				*position,
+				HANDLE_WIDTH,
+				HANDLE_COLOR,
+			));
+			// the tabs point into the region
+			let x = Self::x(bounds, *position);
+			let tab_x = match handle {
+				Handle::Start => x,
+				Handle::End => x - TAB_SIZE,
+			};
+			primitives.push(Self::quad(
+				Rectangle::new(Point::new(tab_x, bounds.y), Size::new(TAB_SIZE, TAB_SIZE)),
+				HANDLE_COLOR,
+			));
+		}
+		let interaction = if self.on_change.is_none() {
+			Interaction::default()
+		} else if self.state.dragging.is_some() {
+			Interaction::ResizingHorizontally
+		} else if bounds.contains(cursor_position) {
+			let (_, distance) = self.closest_handle(bounds, cursor_position);
+			if distance <= GRAB_DISTANCE {
+				Interaction::ResizingHorizontally
+			} else {
+				Interaction::Pointer
+			}
+		} else {
+			Interaction::default()
+		};
+		(Primitive::Group { primitives }, interaction)
+	}
+
+	fn hash_layout(&self, state: &mut iced_native::Hasher) {
+		use std::hash::Hash;
+		HEIGHT.hash(state)
+	}
+}
+
+impl<'a, Message: 'a, B: Backend + 'a> From<RegionEditor<'a, Message>>
+	for Element<'a, Message, Renderer<B>>
+{
+	fn from(region_editor: RegionEditor<'a, Message>) -> Self {
+		Element::new(region_editor)
+	}
+}
@@ -0,0 +1,384 @@
+use std::error::Error;
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Row, Slider, Text};
+use kira::{
+	arrangement::{Arrangement, ArrangementId, LoopArrangementSettings},
+	instance::{InstanceId, InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	mixer::effect::filter::{Filter, FilterSettings},
+	parameter::{Mapping, ParameterId, Tween},
+	playable::PlayableSettings,
+	sound::SoundId,
+	Value,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{
+		create_audio_manager,
+		sound_cache::SoundCache,
+		synth::{self, WaveShape},
+	},
+	ui::{common::screen_wrapper::ScreenWrapper, screen::underwater_demo::TEMPO, style::AppStyles},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &[
+	"underwater demo/drums.ogg",
+	"underwater demo/bass.ogg",
+	"underwater demo/pad.ogg",
+	"underwater demo/lead.ogg",
+];
+
+const EXPLANATION_TEXT: &str = "As the player's health runs \
+low, the game starts to sound like it's happening to someone \
+who's about to pass out: a heartbeat fades in and speeds up, \
+the music sounds further and further away, and a thin ringing \
+creeps in over everything.
+
+All of that is one parameter. The health is the only thing the \
+screen sets, and everything else reads it through its own \
+mapping: the heartbeat's volume fades in below 50% health, its \
+playback rate climbs from 1.2x to 2x below 40% (the loop is \
+one beat at 60 BPM, so that's 72 to 120 BPM), the cutoff of a \
+low-pass filter on the music closes below 60%, and the ringing \
+rises below 25%. The mappings are clamped, so nothing keeps \
+going past the end of its range.
+
+Taking a hit and healing move the same parameter, just with \
+tweens, so the heartbeat and the filter glide instead of \
+jumping.";
+
+/// How much health a hit takes away.
+const HIT_DAMAGE: f64 = 0.15;
+const HIT_TWEEN_TIME: f64 = 0.2;
+const HEAL_TWEEN_TIME: f64 = 2.0;
+const RINGING_FREQUENCY: f64 = 3500.0;
+
+/// How loud the heartbeat is for a health level.
+const HEARTBEAT_VOLUME_MAPPING: Mapping = Mapping {
+	input_range: (0.25, 0.5),
+	output_range: (1.0, 0.0),
+	clamp_bottom: true,
+	clamp_top: true,
+};
+/// How fast the heartbeat loop plays for a health level.
+const HEARTBEAT_PITCH_MAPPING: Mapping = Mapping {
+	input_range: (0.0, 0.4),
+	output_range: (2.0, 1.2),
+	clamp_bottom: true,
+	clamp_top: true,
+};
+/// The music's filter cutoff (in Hz) for a health level.
+const CUTOFF_MAPPING: Mapping = Mapping {
+	input_range: (0.05, 0.6),
+	output_range: (300.0, 20000.0),
+	clamp_bottom: true,
+	clamp_top: true,
+};
+/// How loud the ringing is for a health level.
+const RINGING_VOLUME_MAPPING: Mapping = Mapping {
+	input_range: (0.0, 0.25),
+	output_range: (0.1, 0.0),
+	clamp_bottom: true,
+	clamp_top: true,
+};
+
+/// Works out what a mapping gives for an input, for showing
+/// on screen.
+fn map(mapping: Mapping, input: f64) -> f64 {
+	let (input_start, input_end) = mapping.input_range;
+	let (output_start, output_end) = mapping.output_range;
+	let amount = ((input - input_start) / (input_end - input_start)).clamp(0.0, 1.0);
+	output_start + (output_end - output_start) * amount
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	/// Sets the health (from 0 to 1).
+	SetHealth(f64),
+	TakeHit,
+	Heal,
+}
+
+pub struct LowHealthDemo {
+	audio_manager: AudioManager,
+	loop_ids: Vec<ArrangementId>,
+	heartbeat_sound_id: SoundId,
+	ringing_sound_id: SoundId,
+	health_parameter_id: ParameterId,
+	instance_ids: Vec<InstanceId>,
+	playing: bool,
+	health: f64,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_button: iced::button::State,
+	hit_button: iced::button::State,
+	heal_button: iced::button::State,
+	health_slider: iced::slider::State,
+}
+
+impl LowHealthDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let health_parameter_id = audio_manager.add_parameter(1.0)?;
+		let music_track_id = audio_manager.add_sub_track(Default::default())?;
+		audio_manager.add_effect_to_track(
+			music_track_id,
+			Filter::new(
+				FilterSettings::new().cutoff(Value::Parameter(health_parameter_id, CUTOFF_MAPPING)),
+			),
+			Default::default(),
+		)?;
+		let assets_base_dir = std::env::current_dir()?.join("assets");
+		let mut loop_ids = vec![];
+		for asset in ASSETS {
+			let sound_id = audio_manager.add_sound(
+				sound_cache.get(&assets_base_dir.join(asset))?.to_sound(
+					PlayableSettings::new().semantic_duration(TEMPO.beats_to_seconds(16.0)),
+				),
+			)?;
+			loop_ids.push(audio_manager.add_arrangement(Arrangement::new_loop(
+				sound_id,
+				LoopArrangementSettings::new().default_track(music_track_id),
+			))?);
+		}
+		// the heartbeat and the ringing are in the player's head,
+		// so they skip the music's filter
+		let heartbeat_sound_id = audio_manager.add_sound(synth::heartbeat(
+			PlayableSettings::new().default_loop_start(0.0),
+		))?;
+		let ringing_sound_id = audio_manager.add_sound(synth::tone(
+			WaveShape::Sine,
+			RINGING_FREQUENCY,
+			PlayableSettings::new().default_loop_start(0.0),
+		))?;
+		Ok(Self {
+			audio_manager,
+			loop_ids,
+			heartbeat_sound_id,
+			ringing_sound_id,
+			health_parameter_id,
+			instance_ids: vec![],
+			playing: false,
+			health: 1.0,
+			screen_wrapper: ScreenWrapper::new("Low health demo".into(), Message::GoToDemoSelect),
+			play_button: iced::button::State::new(),
+			hit_button: iced::button::State::new(),
+			heal_button: iced::button::State::new(),
+			health_slider: iced::slider::State::new(),
+		})
+	}
+
+	fn set_health(&mut self, health: f64, tween_time: f64) -> Result<(), Box<dyn Error>> {
+		self.health = health.clamp(0.0, 1.0);
+		self.audio_manager.set_parameter(
+			self.health_parameter_id,
+			self.health,
+			Some(Tween::linear(tween_time)),
+		)?;
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			// the commands are all sent at once, so the loops start
+			// on the same sample
+			Message::Play if !self.playing => {
+				for loop_id in &self.loop_ids {
+					self.audio_manager.play(*loop_id, Default::default())?;
+				}
+				self.instance_ids.push(
+					self.audio_manager.play(
+						self.heartbeat_sound_id,
+						InstanceSettings::new()
+							.volume(Value::Parameter(
+								self.health_parameter_id,
+								HEARTBEAT_VOLUME_MAPPING,
+							))
+							.pitch(Value::Parameter(
+								self.health_parameter_id,
+								HEARTBEAT_PITCH_MAPPING,
+							)),
+					)?,
+				);
+				self.instance_ids.push(self.audio_manager.play(
+					self.ringing_sound_id,
+					InstanceSettings::new().volume(Value::Parameter(
+						self.health_parameter_id,
+						RINGING_VOLUME_MAPPING,
+					)),
+				)?);
+				self.playing = true;
+			}
+			Message::Stop => {
+				for loop_id in &self.loop_ids {
+					self.audio_manager.stop_instances_of(
+						(*loop_id).into(),
+						StopInstanceSettings::new().fade_tween(Tween::linear(0.5)),
+					)?;
+				}
+				for instance_id in self.instance_ids.drain(..) {
+					self.audio_manager.stop_instance(
+						instance_id,
+						StopInstanceSettings::new().fade_tween(Tween::linear(0.5)),
+					)?;
+				}
+				self.playing = false;
+			}
+			Message::SetHealth(health) => self.set_health(health, 0.05)?,
+			Message::TakeHit => self.set_health(self.health - HIT_DAMAGE, HIT_TWEEN_TIME)?,
+			Message::Heal => self.set_health(1.0, HEAL_TWEEN_TIME)?,
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.playing;
+		let health = self.health;
+		let readout = Column::new()
+			.spacing(4)
+			.push(Text::new(format!(
+				"Heartbeat: {:.0}% volume, {:.0} BPM",
+				map(HEARTBEAT_VOLUME_MAPPING, health) * 100.0,
+				map(HEARTBEAT_PITCH_MAPPING, health) * 60.0
+			)))
+			.push(Text::new(format!(
+				"Music cutoff: {:.0} Hz",
+				map(CUTOFF_MAPPING, health)
+			)))
+			.push(Text::new(format!(
+				"Ringing: {:.0}% volume",
+				map(RINGING_VOLUME_MAPPING, health) * 100.0
+			)));
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Row::new()
+						.spacing(16)
+						.push(
+							Button::new(
+								&mut self.play_button,
+								Text::new(if playing { "Stop" } else { "Play" })
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(if playing {
+								Message::Stop
+							} else {
+								Message::Play
+							})
+							.width(Length::Units(120))
+							.style(AppStyles),
+						)
+						.push(
+							Button::new(
+								&mut self.hit_button,
+								Text::new("Take a hit")
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(Message::TakeHit)
+							.width(Length::Units(120))
+							.style(AppStyles),
+						)
+						.push(
+							Button::new(
+								&mut self.heal_button,
+								Text::new("Heal")
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(Message::Heal)
+							.width(Length::Units(120))
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Health: {:.0}%", health * 100.0))
+								.width(Length::Units(200)),
+						)
+						.push(
+							Slider::new(
+								&mut self.health_slider,
+								0.0..=1.0,
+								health,
+								Message::SetHealth,
+							)
+							.step(0.01)
+							.style(AppStyles),
+						),
+				)
+				.push(readout)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for LowHealthDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "low_health_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetHealth(_) => Some("Health".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Health" => vec![Message::SetHealth(value)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for LowHealthDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "low_health_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![Message::SetHealth(self.health)]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for LowHealthDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(key, self.playing, Message::Play, Message::Stop)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if self.playing {
+			Some(format!(
+				"Low health demo ({:.0}% health)",
+				self.health * 100.0
+			))
+		} else {
+			None
+		}
+	}
+}
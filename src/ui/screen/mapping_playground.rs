@@ -0,0 +1,481 @@
+use std::error::Error;
+
+use iced::{
+	Align, Button, Checkbox, Column, HorizontalAlignment, Length, Radio, Row, Slider, Text,
+};
+use kira::{
+	instance::{InstanceId, InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	mixer::{
+		effect::{
+			filter::{Filter, FilterSettings},
+			EffectId,
+		},
+		SubTrackId,
+	},
+	parameter::{Mapping, ParameterId, Tween},
+	playable::PlayableSettings,
+	sound::SoundId,
+	Value,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, sound_cache::SoundCache},
+	ui::{
+		common::{curve_plot::CurvePlot, screen_wrapper::ScreenWrapper},
+		style::AppStyles,
+	},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &["drum fill demo/loop.ogg"];
+
+const EXPLANATION_TEXT: &str = "This demo lets you build the \
+Value::Parameter binding that the underwater demo hardcodes. \
+A mapping turns the parameter's value into a setting: the input \
+range is stretched over the output range, so values outside the \
+input range keep going past the ends of the output range unless \
+that end is clamped.
+
+The plot shows the setting for every value the parameter slider \
+can reach, and the dot shows the current value. Volume, pitch, \
+and panning follow the range sliders while they move. The filter \
+has to be replaced to change its mapping, so the cutoff changes \
+when a slider is let go.";
+
+/// The values the parameter slider can reach, which go past
+/// the input range so clamping can be heard.
+const PARAMETER_RANGE: (f64, f64) = (-0.5, 1.5);
+const NUM_POINTS: usize = 50;
+const PLOT_WIDTH: u16 = 400;
+const PLOT_HEIGHT: u16 = 120;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Target {
+	Volume,
+	Pitch,
+	Panning,
+	Cutoff,
+}
+
+impl Target {
+	const ALL: [Self; 4] = [Self::Volume, Self::Pitch, Self::Panning, Self::Cutoff];
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::Volume => "Volume",
+			Self::Pitch => "Pitch",
+			Self::Panning => "Panning",
+			Self::Cutoff => "Filter cutoff",
+		}
+	}
+
+	/// The lowest and highest values the output range sliders
+	/// can be set to.
+	fn limits(self) -> (f64, f64) {
+		match self {
+			Self::Volume => (0.0, 2.0),
+			Self::Pitch => (0.25, 4.0),
+			Self::Panning => (0.0, 1.0),
+			Self::Cutoff => (20.0, 20000.0),
+		}
+	}
+
+	fn default_output_range(self) -> (f64, f64) {
+		match self {
+			Self::Volume => (0.0, 1.0),
+			Self::Pitch => (0.5, 2.0),
+			Self::Panning => (0.0, 1.0),
+			Self::Cutoff => (200.0, 10000.0),
+		}
+	}
+
+	fn step(self) -> f64 {
+		match self {
+			Self::Cutoff => 10.0,
+			_ => 0.01,
+		}
+	}
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetTarget(Target),
+	SetParameter(f64),
+	SetInputMin(f64),
+	SetInputMax(f64),
+	SetOutputMin(f64),
+	SetOutputMax(f64),
+	ApplyMapping,
+	SetClampBottom(bool),
+	SetClampTop(bool),
+}
+
+pub struct MappingPlayground {
+	audio_manager: AudioManager,
+	sound_id: SoundId,
+	track_id: SubTrackId,
+	parameter_id: ParameterId,
+	filter_id: Option<EffectId>,
+	instance_id: Option<InstanceId>,
+	target: Target,
+	parameter: f64,
+	mapping: Mapping,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_button: iced::button::State,
+	parameter_slider: iced::slider::State,
+	range_sliders: [iced::slider::State; 4],
+}
+
+impl MappingPlayground {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let parameter_id = audio_manager.add_parameter(0.5)?;
+		let track_id = audio_manager.add_sub_track(Default::default())?;
+		let assets_base_dir = std::env::current_dir()?.join("assets/drum fill demo");
+		let sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&assets_base_dir.join("loop.ogg"))?
+				.to_sound(PlayableSettings::new().default_loop_start(0.0)),
+		)?;
+		Ok(Self {
+			audio_manager,
+			sound_id,
+			track_id,
+			parameter_id,
+			filter_id: None,
+			instance_id: None,
+			target: Target::Volume,
+			parameter: 0.5,
+			mapping: Mapping {
+				output_range: Target::Volume.default_output_range(),
+				..Default::default()
+			},
+			screen_wrapper: ScreenWrapper::new(
+				"Mapping playground".into(),
+				Message::GoToDemoSelect,
+			),
+			play_button: iced::button::State::new(),
+			parameter_slider: iced::slider::State::new(),
+			range_sliders: Default::default(),
+		})
+	}
+
+	/// Kira divides by the size of the input range, so an
+	/// empty range can't be used.
+	fn mapping_is_valid(&self) -> bool {
+		self.mapping.input_range.0 != self.mapping.input_range.1
+	}
+
+	/// Gets the value a setting should have: the parameter
+	/// binding for the setting being controlled, or a fixed
+	/// value for the others.
+	fn value(&self, target: Target, fixed: f64) -> Value<f64> {
+		if target == self.target {
+			Value::Parameter(self.parameter_id, self.mapping)
+		} else {
+			Value::Fixed(fixed)
+		}
+	}
+
+	/// Binds the parameter to the current target with the
+	/// current mapping.
+	fn apply_mapping(&mut self) -> Result<(), Box<dyn Error>> {
+		if !self.mapping_is_valid() {
+			return Ok(());
+		}
+		// effect settings can't be changed after the effect is
+		// added, so the filter is replaced instead
+		if let Some(filter_id) = self.filter_id.take() {
+			self.audio_manager.remove_effect(filter_id)?;
+		}
+		if self.target == Target::Cutoff {
+			self.filter_id = Some(self.audio_manager.add_effect_to_track(
+				self.track_id,
+				Filter::new(FilterSettings::new().cutoff(self.value(Target::Cutoff, 20000.0))),
+				Default::default(),
+			)?);
+		}
+		self.apply_instance_mapping()
+	}
+
+	/// Rebinds the settings of the playing instance, which
+	/// (unlike the filter) can be done as often as needed.
+	fn apply_instance_mapping(&mut self) -> Result<(), Box<dyn Error>> {
+		if !self.mapping_is_valid() {
+			return Ok(());
+		}
+		if let Some(instance_id) = self.instance_id {
+			self.audio_manager
+				.set_instance_volume(instance_id, self.value(Target::Volume, 1.0))?;
+			self.audio_manager
+				.set_instance_pitch(instance_id, self.value(Target::Pitch, 1.0))?;
+			self.audio_manager
+				.set_instance_panning(instance_id, self.value(Target::Panning, 0.5))?;
+		}
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play => {
+				self.instance_id = Some(
+					self.audio_manager
+						.play(self.sound_id, InstanceSettings::new().track(self.track_id))?,
+				);
+				self.apply_mapping()?;
+			}
+			Message::Stop => {
+				if let Some(instance_id) = self.instance_id.take() {
+					self.audio_manager.stop_instance(
+						instance_id,
+						StopInstanceSettings::new().fade_tween(Tween::linear(0.1)),
+					)?;
+				}
+			}
+			Message::SetTarget(target) => {
+				self.target = target;
+				self.mapping.output_range = target.default_output_range();
+				self.apply_mapping()?;
+			}
+			Message::SetParameter(parameter) => {
+				self.parameter = parameter;
+				self.audio_manager
+					.set_parameter(self.parameter_id, parameter, None)?;
+			}
+			// replacing the filter for every step of a slider would
+			// crackle, so the cutoff only picks up the new mapping
+			// once the slider is released
+			Message::SetInputMin(value) => {
+				self.mapping.input_range.0 = value;
+				self.apply_instance_mapping()?;
+			}
+			Message::SetInputMax(value) => {
+				self.mapping.input_range.1 = value;
+				self.apply_instance_mapping()?;
+			}
+			Message::SetOutputMin(value) => {
+				self.mapping.output_range.0 = value;
+				self.apply_instance_mapping()?;
+			}
+			Message::SetOutputMax(value) => {
+				self.mapping.output_range.1 = value;
+				self.apply_instance_mapping()?;
+			}
+			Message::ApplyMapping => self.apply_mapping()?,
+			Message::SetClampBottom(clamp) => {
+				self.mapping.clamp_bottom = clamp;
+				self.apply_mapping()?;
+			}
+			Message::SetClampTop(clamp) => {
+				self.mapping.clamp_top = clamp;
+				self.apply_mapping()?;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	/// Gets the output of the mapping for every value the
+	/// parameter slider can reach, scaled so the output range
+	/// sliders' limits fill the plot.
+	fn plot_points(&self) -> Vec<f32> {
+		if !self.mapping_is_valid() {
+			return vec![0.0; NUM_POINTS + 1];
+		}
+		let (low, high) = self.target.limits();
+		(0..=NUM_POINTS)
+			.map(|i| {
+				let parameter = PARAMETER_RANGE.0
+					+ (PARAMETER_RANGE.1 - PARAMETER_RANGE.0) * i as f64 / NUM_POINTS as f64;
+				((self.mapping.map(parameter) - low) / (high - low)).clamp(0.0, 1.0) as f32
+			})
+			.collect()
+	}
+
+	fn range_slider<'a>(
+		label: &str,
+		state: &'a mut iced::slider::State,
+		range: (f64, f64),
+		step: f64,
+		value: f64,
+		on_change: fn(f64) -> Message,
+	) -> Row<'a, Message> {
+		Row::new()
+			.spacing(16)
+			.align_items(Align::Center)
+			.push(Text::new(label).width(Length::Units(120)))
+			.push(
+				Slider::new(state, range.0..=range.1, value, on_change)
+					.step(step)
+					.on_release(Message::ApplyMapping)
+					.style(AppStyles),
+			)
+			.push(Text::new(format!("{:.2}", value)).width(Length::Units(80)))
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.instance_id.is_some();
+		let target = self.target;
+		let mapping = self.mapping;
+		let limits = target.limits();
+		let plot_points = self.plot_points();
+		let output_text = if self.mapping_is_valid() {
+			format!(
+				"Parameter {:.2} -> {} {:.2}",
+				self.parameter,
+				target.name().to_lowercase(),
+				mapping.map(self.parameter)
+			)
+		} else {
+			"The input range is empty, so it can't be used".into()
+		};
+
+		let play_button = Button::new(
+			&mut self.play_button,
+			Text::new(if playing { "Stop" } else { "Play" })
+				.width(Length::Fill)
+				.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.on_press(if playing {
+			Message::Stop
+		} else {
+			Message::Play
+		})
+		.width(Length::Units(100))
+		.style(AppStyles);
+		let target_radios = Target::ALL.iter().fold(
+			Row::new().spacing(16).align_items(Align::Center),
+			|row, option| {
+				row.push(Radio::new(
+					*option,
+					option.name(),
+					Some(target),
+					Message::SetTarget,
+				))
+			},
+		);
+
+		let [input_min_slider, input_max_slider, output_min_slider, output_max_slider] =
+			&mut self.range_sliders;
+		let ranges = Column::new()
+			.spacing(8)
+			.max_width(600)
+			.push(Self::range_slider(
+				"Input min",
+				input_min_slider,
+				(0.0, 1.0),
+				0.01,
+				mapping.input_range.0,
+				Message::SetInputMin,
+			))
+			.push(Self::range_slider(
+				"Input max",
+				input_max_slider,
+				(0.0, 1.0),
+				0.01,
+				mapping.input_range.1,
+				Message::SetInputMax,
+			))
+			.push(Self::range_slider(
+				"Output min",
+				output_min_slider,
+				limits,
+				target.step(),
+				mapping.output_range.0,
+				Message::SetOutputMin,
+			))
+			.push(Self::range_slider(
+				"Output max",
+				output_max_slider,
+				limits,
+				target.step(),
+				mapping.output_range.1,
+				Message::SetOutputMax,
+			))
+			.push(
+				Row::new()
+					.spacing(16)
+					.push(Checkbox::new(
+						mapping.clamp_bottom,
+						"Clamp bottom",
+						Message::SetClampBottom,
+					))
+					.push(Checkbox::new(
+						mapping.clamp_top,
+						"Clamp top",
+						Message::SetClampTop,
+					)),
+			);
+
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(play_button)
+						.push(target_radios),
+				)
+				.push(CurvePlot::new(plot_points, PLOT_WIDTH, PLOT_HEIGHT).marker(
+					((self.parameter - PARAMETER_RANGE.0) / (PARAMETER_RANGE.1 - PARAMETER_RANGE.0))
+						as f32,
+				))
+				.push(
+					Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.max_width(600)
+						.push(Text::new("Parameter").width(Length::Units(120)))
+						.push(
+							Slider::new(
+								&mut self.parameter_slider,
+								PARAMETER_RANGE.0..=PARAMETER_RANGE.1,
+								self.parameter,
+								Message::SetParameter,
+							)
+							.step(0.01)
+							.style(AppStyles),
+						),
+				)
+				.push(Text::new(output_text))
+				.push(ranges)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for MappingPlayground {
+	type Message = Message;
+
+	const NAME: &'static str = "mapping_playground";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		// the target goes first, since changing it resets
+		// the output range
+		vec![
+			Message::SetTarget(self.target),
+			Message::SetInputMin(self.mapping.input_range.0),
+			Message::SetInputMax(self.mapping.input_range.1),
+			Message::SetOutputMin(self.mapping.output_range.0),
+			Message::SetOutputMax(self.mapping.output_range.1),
+			Message::SetClampBottom(self.mapping.clamp_bottom),
+			Message::SetClampTop(self.mapping.clamp_top),
+			Message::SetParameter(self.parameter),
+		]
+	}
+}
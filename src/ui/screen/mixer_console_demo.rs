@@ -0,0 +1,522 @@
+mod channel_strip;
+
+use std::error::Error;
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Row, Text};
+use kira::{
+	arrangement::{Arrangement, ArrangementId, LoopArrangementSettings},
+	instance::{InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	mixer::TrackIndex,
+	parameter::{ParameterId, Tween},
+	playable::PlayableSettings,
+	Tempo,
+};
+use serde::{Deserialize, Serialize};
+
+use self::channel_strip::ChannelStrip;
+use crate::{
+	audio::{
+		create_audio_manager, effect::gain::Gain, mix_snapshots::MixSnapshots,
+		sound_cache::SoundCache,
+	},
+	ui::{
+		common::{screen_wrapper::ScreenWrapper, transport::Transport},
+		style::AppStyles,
+		undo_stack::{UndoShortcut, UndoStack},
+	},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &[
+	"underwater demo/drums.ogg",
+	"underwater demo/bass.ogg",
+	"underwater demo/pad.ogg",
+	"underwater demo/lead.ogg",
+];
+
+const EXPLANATION_TEXT: &str = "Each part of the song plays on \
+its own sub-track, and every sub-track feeds into the main track, \
+which has the master fader. Drag the faders to mix the parts \
+(right-click one to put it back at 0 dB).
+
+A track's volume is set when it's created and can't be changed \
+afterwards, so each track has a gain effect whose volume is tied \
+to a parameter, and the faders set those parameters. Muting and \
+soloing fade the instances on the track in and out instead, so \
+the faders stay where they are.
+
+Capturing a snapshot saves every fader's position, and recalling \
+one tweens all of the parameters there at once.
+
+Ctrl+Z and Ctrl+Y undo and redo fader moves, mutes, solos, and \
+snapshot recalls. Dragging a fader sends a new volume for every \
+pixel it moves, so moves of the same fader in a row are undone \
+together.";
+
+pub const TEMPO: Tempo = Tempo(85.0);
+const CHANNEL_NAMES: [&str; 4] = ["Drums", "Bass", "Pad", "Lead"];
+const NUM_CHANNELS: usize = CHANNEL_NAMES.len();
+/// The master fader comes after the channels in the mix.
+const MASTER: usize = NUM_CHANNELS;
+/// The range of the faders (in decibels).
+const MIN_VOLUME: f64 = -60.0;
+const MAX_VOLUME: f64 = 6.0;
+/// How long muting and soloing take to fade (in seconds).
+const MUTE_FADE_TIME: f64 = 0.02;
+/// How long recalling a snapshot takes (in seconds).
+const RECALL_TIME: f64 = 1.0;
+/// The oldest snapshot is thrown away when there are more
+/// than this.
+const MAX_SNAPSHOTS: usize = 4;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetMetronomeClick(bool),
+	/// Sets the volume of a channel, or the master volume if the
+	/// index is the number of channels (in decibels).
+	SetVolume(usize, f64),
+	SetMuted(usize, bool),
+	SetSoloed(usize, bool),
+	CaptureSnapshot,
+	RecallSnapshot(usize),
+	Undo,
+	Redo,
+}
+
+/// What undo and redo bring back.
+#[derive(Debug, Clone, PartialEq)]
+struct ConsoleState {
+	/// The channel faders, then the master fader (in decibels).
+	volumes: Vec<f64>,
+	muted: Vec<bool>,
+	soloed: Vec<bool>,
+}
+
+struct Channel {
+	loop_id: ArrangementId,
+	/// Fades the channel's loop in and out for muting and soloing.
+	audible_parameter_id: ParameterId,
+	muted: bool,
+	soloed: bool,
+	strip: channel_strip::State,
+}
+
+pub struct MixerConsoleDemo {
+	audio_manager: AudioManager,
+	channels: Vec<Channel>,
+	/// The channel faders, then the master fader.
+	mix: MixSnapshots,
+	snapshots_taken: usize,
+	history: UndoStack<ConsoleState>,
+	/// The fader that was moved by the last edit, if that edit
+	/// was a fader move. Moving it again doesn't add another
+	/// undo step.
+	moved_fader: Option<usize>,
+	transport: Transport,
+	screen_wrapper: ScreenWrapper<Message>,
+	master_strip: channel_strip::State,
+	capture_button: iced::button::State,
+	snapshot_buttons: Vec<iced::button::State>,
+	undo_button: iced::button::State,
+	redo_button: iced::button::State,
+}
+
+impl MixerConsoleDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(AudioManagerSettings {
+			metronome_settings: Transport::metronome_settings(TEMPO),
+			..audio_manager_settings
+		})?;
+		let transport = Transport::new(&mut audio_manager, TEMPO)?;
+		let assets_base_dir = std::env::current_dir()?.join("assets");
+		let mut channels = vec![];
+		let mut fader_parameters = vec![];
+		for asset in ASSETS {
+			let volume_parameter_id = audio_manager.add_parameter(0.0)?;
+			let track_id = audio_manager.add_sub_track(Default::default())?;
+			audio_manager.add_effect_to_track(
+				track_id,
+				Gain::new(volume_parameter_id),
+				Default::default(),
+			)?;
+			let sound_id = audio_manager.add_sound(
+				sound_cache.get(&assets_base_dir.join(asset))?.to_sound(
+					PlayableSettings::new().semantic_duration(TEMPO.beats_to_seconds(16.0)),
+				),
+			)?;
+			channels.push(Channel {
+				loop_id: audio_manager.add_arrangement(Arrangement::new_loop(
+					sound_id,
+					LoopArrangementSettings::new().default_track(track_id),
+				))?,
+				audible_parameter_id: audio_manager.add_parameter(1.0)?,
+				muted: false,
+				soloed: false,
+				strip: Default::default(),
+			});
+			fader_parameters.push((volume_parameter_id, 0.0));
+		}
+		let master_parameter_id = audio_manager.add_parameter(0.0)?;
+		audio_manager.add_effect_to_track(
+			TrackIndex::Main,
+			Gain::new(master_parameter_id),
+			Default::default(),
+		)?;
+		fader_parameters.push((master_parameter_id, 0.0));
+		Ok(Self {
+			audio_manager,
+			channels,
+			mix: MixSnapshots::new(&fader_parameters),
+			snapshots_taken: 0,
+			history: UndoStack::new(),
+			moved_fader: None,
+			transport,
+			screen_wrapper: ScreenWrapper::new(
+				"Mixer console demo".into(),
+				Message::GoToDemoSelect,
+			),
+			master_strip: Default::default(),
+			capture_button: iced::button::State::new(),
+			snapshot_buttons: vec![],
+			undo_button: iced::button::State::new(),
+			redo_button: iced::button::State::new(),
+		})
+	}
+
+	fn play(&mut self) -> Result<(), Box<dyn Error>> {
+		// the commands are all sent at once, so the loops start
+		// on the same sample
+		for channel in &self.channels {
+			self.audio_manager.play(
+				channel.loop_id,
+				InstanceSettings::new().volume(channel.audible_parameter_id),
+			)?;
+		}
+		self.transport.start(&mut self.audio_manager)?;
+		Ok(())
+	}
+
+	fn stop(&mut self) -> Result<(), Box<dyn Error>> {
+		for channel in &self.channels {
+			self.audio_manager.stop_instances_of(
+				channel.loop_id.into(),
+				StopInstanceSettings::new().fade_tween(Tween::linear(0.5)),
+			)?;
+		}
+		self.transport.stop(&mut self.audio_manager)?;
+		Ok(())
+	}
+
+	/// A channel can be heard if it isn't muted, and either it's
+	/// soloed or nothing is.
+	fn is_audible(&self, index: usize) -> bool {
+		let any_soloed = self.channels.iter().any(|channel| channel.soloed);
+		let channel = &self.channels[index];
+		!channel.muted && (channel.soloed || !any_soloed)
+	}
+
+	fn update_audible(&mut self) -> Result<(), Box<dyn Error>> {
+		for index in 0..self.channels.len() {
+			let audible = self.is_audible(index);
+			self.audio_manager.set_parameter(
+				self.channels[index].audible_parameter_id,
+				if audible { 1.0 } else { 0.0 },
+				Some(Tween::linear(MUTE_FADE_TIME)),
+			)?;
+		}
+		Ok(())
+	}
+
+	fn console_state(&self) -> ConsoleState {
+		ConsoleState {
+			volumes: (0..=MASTER).map(|index| self.mix.value(index)).collect(),
+			muted: self.channels.iter().map(|channel| channel.muted).collect(),
+			soloed: self.channels.iter().map(|channel| channel.soloed).collect(),
+		}
+	}
+
+	fn set_console_state(&mut self, state: ConsoleState) -> Result<(), Box<dyn Error>> {
+		for (index, volume) in state.volumes.into_iter().enumerate() {
+			if volume != self.mix.value(index) {
+				self.mix.set(&mut self.audio_manager, index, volume)?;
+			}
+		}
+		for ((channel, muted), soloed) in
+			self.channels.iter_mut().zip(state.muted).zip(state.soloed)
+		{
+			channel.muted = muted;
+			channel.soloed = soloed;
+		}
+		self.update_audible()
+	}
+
+	/// Changes the console and remembers the old state for undo.
+	/// `moved_fader` is the fader the edit moves, if it's a fader
+	/// move.
+	fn edit(
+		&mut self,
+		state: ConsoleState,
+		moved_fader: Option<usize>,
+	) -> Result<(), Box<dyn Error>> {
+		let current_state = self.console_state();
+		if state == current_state {
+			return Ok(());
+		}
+		if moved_fader.is_none() || moved_fader != self.moved_fader {
+			self.history.push(current_state);
+		}
+		self.moved_fader = moved_fader;
+		self.set_console_state(state)
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play => self.play()?,
+			Message::Stop => self.stop()?,
+			Message::SetMetronomeClick(enabled) => {
+				self.transport
+					.set_click_enabled(&mut self.audio_manager, enabled)?;
+			}
+			Message::SetVolume(index, volume) if index <= MASTER => {
+				let mut state = self.console_state();
+				state.volumes[index] = volume.clamp(MIN_VOLUME, MAX_VOLUME);
+				self.edit(state, Some(index))?;
+			}
+			Message::SetMuted(index, muted) if index < NUM_CHANNELS => {
+				let mut state = self.console_state();
+				state.muted[index] = muted;
+				self.edit(state, None)?;
+			}
+			Message::SetSoloed(index, soloed) if index < NUM_CHANNELS => {
+				let mut state = self.console_state();
+				state.soloed[index] = soloed;
+				self.edit(state, None)?;
+			}
+			Message::CaptureSnapshot => {
+				if self.mix.snapshots().len() == MAX_SNAPSHOTS {
+					self.mix.remove(0);
+				}
+				self.snapshots_taken += 1;
+				self.mix
+					.capture(format!("Snapshot {}", self.snapshots_taken));
+			}
+			Message::RecallSnapshot(index) if index < self.mix.snapshots().len() => {
+				self.history.push(self.console_state());
+				self.moved_fader = None;
+				self.mix
+					.recall(&mut self.audio_manager, index, RECALL_TIME)?;
+			}
+			Message::Undo => {
+				let mut state = self.console_state();
+				if self.history.undo(&mut state) {
+					self.moved_fader = None;
+					self.set_console_state(state)?;
+				}
+			}
+			Message::Redo => {
+				let mut state = self.console_state();
+				if self.history.redo(&mut state) {
+					self.moved_fader = None;
+					self.set_console_state(state)?;
+				}
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		self.transport.check_for_events(&mut self.audio_manager);
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let audible: Vec<bool> = (0..self.channels.len())
+			.map(|index| self.is_audible(index))
+			.collect();
+		let mix = &self.mix;
+		let master_volume = mix.value(MASTER);
+		let strips = self.channels.iter_mut().enumerate().fold(
+			Row::new().spacing(8),
+			|row, (index, channel)| {
+				row.push(
+					ChannelStrip::new(
+						&mut channel.strip,
+						CHANNEL_NAMES[index],
+						mix.value(index),
+						(MIN_VOLUME, MAX_VOLUME),
+					)
+					.on_change(move |volume| Message::SetVolume(index, volume))
+					.mute(channel.muted, Message::SetMuted(index, !channel.muted))
+					.solo(channel.soloed, Message::SetSoloed(index, !channel.soloed))
+					.audible(audible[index]),
+				)
+			},
+		);
+		let strips = strips.push(Column::new().width(Length::Units(16))).push(
+			ChannelStrip::new(
+				&mut self.master_strip,
+				"Master",
+				master_volume,
+				(MIN_VOLUME, MAX_VOLUME),
+			)
+			.on_change(|volume| Message::SetVolume(MASTER, volume)),
+		);
+		let snapshot_names: Vec<String> = mix
+			.snapshots()
+			.iter()
+			.map(|snapshot| snapshot.name.clone())
+			.collect();
+		self.snapshot_buttons
+			.resize_with(snapshot_names.len(), iced::button::State::new);
+		let snapshots = self
+			.snapshot_buttons
+			.iter_mut()
+			.zip(snapshot_names)
+			.enumerate()
+			.fold(
+				Row::new().spacing(16).align_items(Align::Center).push(
+					Button::new(
+						&mut self.capture_button,
+						Text::new("Capture snapshot")
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(Message::CaptureSnapshot)
+					.width(Length::Units(180))
+					.style(AppStyles),
+				),
+				|row, (index, (button, name))| {
+					row.push(
+						Button::new(button, Text::new(name))
+							.on_press(Message::RecallSnapshot(index))
+							.style(AppStyles),
+					)
+				},
+			);
+		let mut undo_button = Button::new(
+			&mut self.undo_button,
+			Text::new("Undo")
+				.width(Length::Fill)
+				.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.width(Length::Units(100))
+		.style(AppStyles);
+		if self.history.can_undo() {
+			undo_button = undo_button.on_press(Message::Undo);
+		}
+		let mut redo_button = Button::new(
+			&mut self.redo_button,
+			Text::new("Redo")
+				.width(Length::Fill)
+				.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.width(Length::Units(100))
+		.style(AppStyles);
+		if self.history.can_redo() {
+			redo_button = redo_button.on_press(Message::Redo);
+		}
+		self.screen_wrapper.view_with_transport(
+			self.transport
+				.view(Message::Play, Message::Stop, Message::SetMetronomeClick),
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(strips)
+				.push(Row::new().spacing(16).push(undo_button).push(redo_button))
+				.push(snapshots)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for MixerConsoleDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "mixer_console_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetVolume(index, _) => Some(match CHANNEL_NAMES.get(*index) {
+				Some(name) => format!("{} volume", name),
+				None => "Master volume".into(),
+			}),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		let volume = MIN_VOLUME + (MAX_VOLUME - MIN_VOLUME) * value;
+		if control == "Master volume" {
+			return vec![Message::SetVolume(MASTER, volume)];
+		}
+		CHANNEL_NAMES
+			.iter()
+			.position(|name| control == format!("{} volume", name))
+			.map(|index| Message::SetVolume(index, volume))
+			.into_iter()
+			.collect()
+	}
+}
+
+impl crate::ui::undo_stack::UndoControls for MixerConsoleDemo {
+	type Message = Message;
+
+	fn undo_shortcut_message(&self, shortcut: UndoShortcut) -> Option<Message> {
+		match shortcut {
+			UndoShortcut::Undo if self.history.can_undo() => Some(Message::Undo),
+			UndoShortcut::Redo if self.history.can_redo() => Some(Message::Redo),
+			_ => None,
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for MixerConsoleDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "mixer_console_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		let mut messages = vec![Message::SetMetronomeClick(self.transport.click_enabled())];
+		for (index, channel) in self.channels.iter().enumerate() {
+			messages.push(Message::SetVolume(index, self.mix.value(index)));
+			messages.push(Message::SetMuted(index, channel.muted));
+			messages.push(Message::SetSoloed(index, channel.soloed));
+		}
+		messages.push(Message::SetVolume(MASTER, self.mix.value(MASTER)));
+		messages
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for MixerConsoleDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.transport.is_playing(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if !self.transport.is_playing() {
+			return None;
+		}
+		Some("Mixer console demo".into())
+	}
+}
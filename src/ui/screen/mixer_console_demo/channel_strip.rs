@@ -0,0 +1,404 @@
+use iced::{
+	mouse::Interaction, Background, Color, Font, HorizontalAlignment, Length, Point, Rectangle,
+	Size, VerticalAlignment,
+};
+use iced_graphics::{Backend, Defaults, Primitive, Renderer};
+use iced_native::{
+	event::Status,
+	layout::{Limits, Node},
+	mouse, Clipboard, Element, Event, Layout, Widget,
+};
+
+const WIDTH: f32 = 90.0;
+const HEIGHT: f32 = 320.0;
+const NAME_HEIGHT: f32 = 24.0;
+const VALUE_HEIGHT: f32 = 20.0;
+const BUTTON_HEIGHT: f32 = 24.0;
+const SPACING: f32 = 6.0;
+const FADER_TRACK_WIDTH: f32 = 4.0;
+const FADER_CAP_SIZE: Size = Size {
+	width: 36.0,
+	height: 16.0,
+};
+/// The volumes marked next to the fader (in decibels).
+const SCALE_MARKS: [f64; 6] = [6.0, 0.0, -12.0, -24.0, -36.0, -48.0];
+const BACKGROUND_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.05];
+const FADER_TRACK_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.6];
+const FADER_CAP_COLOR: [f32; 4] = [0.85, 0.85, 0.85, 1.0];
+/// The cap is dimmed while the channel can't be heard (because
+/// it's muted or another channel is soloed).
+const SILENT_FADER_CAP_COLOR: [f32; 4] = [0.4, 0.4, 0.4, 1.0];
+const SCALE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.3];
+const BUTTON_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.1];
+const MUTE_COLOR: [f32; 4] = [0.9, 0.2, 0.2, 1.0];
+const SOLO_COLOR: [f32; 4] = [1.0, 0.8, 0.0, 1.0];
+const TEXT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const TEXT_SIZE: f32 = 16.0;
+const SCALE_TEXT_SIZE: f32 = 12.0;
+
+/// Whether the fader is being dragged. This has to outlive the
+/// widget, which is rebuilt every time the screen is drawn.
+#[derive(Debug, Default)]
+pub struct State {
+	dragging: bool,
+}
+
+/// A mixing console channel: a volume fader (in decibels) with
+/// mute and solo buttons underneath. Right-clicking the fader
+/// puts it back at 0 dB.
+pub struct ChannelStrip<'a, Message> {
+	state: &'a mut State,
+	name: &'a str,
+	volume: f64,
+	volume_range: (f64, f64),
+	muted: bool,
+	soloed: bool,
+	audible: bool,
+	on_change: Option<Box<dyn Fn(f64) -> Message + 'a>>,
+	on_toggle_mute: Option<Message>,
+	on_toggle_solo: Option<Message>,
+}
+
+impl<'a, Message: Clone> ChannelStrip<'a, Message> {
+	pub fn new(state: &'a mut State, name: &'a str, volume: f64, volume_range: (f64, f64)) -> Self {
+		Self {
+			state,
+			name,
+			volume,
+			volume_range,
+			muted: false,
+			soloed: false,
+			audible: true,
+			on_change: None,
+			on_toggle_mute: None,
+			on_toggle_solo: None,
+		}
+	}
+
+	pub fn on_change(self, on_change: impl Fn(f64) -> Message + 'a) -> Self {
+		Self {
+			on_change: Some(Box::new(on_change)),
+			..self
+		}
+	}
+
+	/// Shows a mute button, which sends `message` when clicked.
+	pub fn mute(self, muted: bool, message: Message) -> Self {
+		Self {
+			muted,
+			on_toggle_mute: Some(message),
+			..self
+		}
+	}
+
+	/// Shows a solo button, which sends `message` when clicked.
+	pub fn solo(self, soloed: bool, message: Message) -> Self {
+		Self {
+			soloed,
+			on_toggle_solo: Some(message),
+			..self
+		}
+	}
+
+	/// Sets whether the channel can be heard. Muting a channel
+	/// or soloing another one silences it.
+	pub fn audible(self, audible: bool) -> Self {
+		Self { audible, ..self }
+	}
+
+	fn buttons_height(&self) -> f32 {
+		let buttons = [self.on_toggle_mute.is_some(), self.on_toggle_solo.is_some()]
+			.iter()
+			.filter(|shown| **shown)
+			.count() as f32;
+		buttons * (BUTTON_HEIGHT + SPACING)
+	}
+
+	/// The area the fader cap moves in.
+	fn fader_bounds(&self, bounds: Rectangle) -> Rectangle {
+		let top = bounds.y + NAME_HEIGHT + VALUE_HEIGHT + SPACING + FADER_CAP_SIZE.height / 2.0;
+		let bottom = bounds.y + bounds.height
+			- self.buttons_height()
+			- SPACING - FADER_CAP_SIZE.height / 2.0;
+		Rectangle::new(
+			Point::new(bounds.x, top),
+			Size::new(bounds.width, bottom - top),
+		)
+	}
+
+	fn volume_y(&self, fader_bounds: Rectangle, volume: f64) -> f32 {
+		let (min, max) = self.volume_range;
+		let position = ((volume - min) / (max - min)).clamp(0.0, 1.0) as f32;
+		fader_bounds.y + fader_bounds.height * (1.0 - position)
+	}
+
+	fn volume_at(&self, fader_bounds: Rectangle, point: Point) -> f64 {
+		let (min, max) = self.volume_range;
+		let position = (1.0 - (point.y - fader_bounds.y) / fader_bounds.height).clamp(0.0, 1.0);
+		// rounded to a tenth of a decibel, which is what's shown
+		((min + (max - min) * position as f64) * 10.0).round() / 10.0
+	}
+
+	/// Where the mute and solo buttons are, from the bottom up.
+	fn button_bounds(&self, bounds: Rectangle) -> (Option<Rectangle>, Option<Rectangle>) {
+		let button = |index: usize| {
+			Rectangle::new(
+				Point::new(
+					bounds.x + SPACING,
+					bounds.y + bounds.height - (index + 1) as f32 * (BUTTON_HEIGHT + SPACING),
+				),
+				Size::new(bounds.width - SPACING * 2.0, BUTTON_HEIGHT),
+			)
+		};
+		match (self.on_toggle_mute.is_some(), self.on_toggle_solo.is_some()) {
+			(true, true) => (Some(button(1)), Some(button(0))),
+			(true, false) => (Some(button(0)), None),
+			(false, true) => (None, Some(button(0))),
+			(false, false) => (None, None),
+		}
+	}
+
+	fn quad(bounds: Rectangle, color: [f32; 4], border_radius: f32) -> Primitive {
+		Primitive::Quad {
+			bounds,
+			background: Background::Color(color.into()),
+			border_radius,
+			border_width: 0.0,
+			border_color: Color::TRANSPARENT,
+		}
+	}
+
+	fn text(
+		content: String,
+		position: Point,
+		size: f32,
+		color: [f32; 4],
+		horizontal_alignment: HorizontalAlignment,
+	) -> Primitive {
+		Primitive::Text {
+			content,
+			bounds: Rectangle::new(position, Size::new(WIDTH, 0.0)),
+			color: color.into(),
+			size,
+			font: Font::Default,
+			horizontal_alignment,
+			vertical_alignment: VerticalAlignment::Center,
+		}
+	}
+
+	fn draw_button(
+		bounds: Rectangle,
+		label: &str,
+		on: bool,
+		on_color: [f32; 4],
+		primitives: &mut Vec<Primitive>,
+	) {
+		primitives.push(Self::quad(
+			bounds,
+			if on { on_color } else { BUTTON_COLOR },
+			4.0,
+		));
+		primitives.push(Self::text(
+			label.into(),
+			bounds.center(),
+			TEXT_SIZE,
+			if on { [0.0, 0.0, 0.0, 1.0] } else { TEXT_COLOR },
+			HorizontalAlignment::Center,
+		));
+	}
+}
+
+impl<'a, Message: Clone, B: Backend> Widget<Message, Renderer<B>> for ChannelStrip<'a, Message> {
+	fn width(&self) -> Length {
+		Length::Units(WIDTH as u16)
+	}
+
+	fn height(&self) -> Length {
+		Length::Units(HEIGHT as u16)
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, limits: &Limits) -> Node {
+		Node::new(
+			limits
+				.width(Length::Units(WIDTH as u16))
+				.height(Length::Units(HEIGHT as u16))
+				.resolve(Size::ZERO),
+		)
+	}
+
+	fn on_event(
+		&mut self,
+		event: Event,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		messages: &mut Vec<Message>,
+		_renderer: &Renderer<B>,
+		_clipboard: Option<&dyn Clipboard>,
+	) -> Status {
+		let bounds = layout.bounds();
+		let fader_bounds = self.fader_bounds(bounds);
+		// the cap hangs over the ends of the fader's range, so
+		// it can be grabbed there too
+		let fader_hit_bounds = Rectangle {
+			y: fader_bounds.y - FADER_CAP_SIZE.height / 2.0,
+			height: fader_bounds.height + FADER_CAP_SIZE.height,
+			..fader_bounds
+		};
+		match event {
+			Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+				let (mute_bounds, solo_bounds) = self.button_bounds(bounds);
+				if let (Some(mute_bounds), Some(message)) = (mute_bounds, &self.on_toggle_mute) {
+					if mute_bounds.contains(cursor_position) {
+						messages.push(message.clone());
+						return Status::Captured;
+					}
+				}
+				if let (Some(solo_bounds), Some(message)) = (solo_bounds, &self.on_toggle_solo) {
+					if solo_bounds.contains(cursor_position) {
+						messages.push(message.clone());
+						return Status::Captured;
+					}
+				}
+				match &self.on_change {
+					Some(on_change) if fader_hit_bounds.contains(cursor_position) => {
+						self.state.dragging = true;
+						messages.push(on_change(self.volume_at(fader_bounds, cursor_position)));
+						Status::Captured
+					}
+					_ => Status::Ignored,
+				}
+			}
+			Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Right)) => {
+				match &self.on_change {
+					Some(on_change) if fader_hit_bounds.contains(cursor_position) => {
+						messages.push(on_change(0.0));
+						Status::Captured
+					}
+					_ => Status::Ignored,
+				}
+			}
+			Event::Mouse(mouse::Event::CursorMoved { .. }) if self.state.dragging => {
+				if let Some(on_change) = &self.on_change {
+					messages.push(on_change(self.volume_at(fader_bounds, cursor_position)));
+				}
+				Status::Captured
+			}
+			Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
+				if self.state.dragging =>
+			{
+				self.state.dragging = false;
+				Status::Captured
+			}
+			_ => Status::Ignored,
+		}
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		_defaults: &Defaults,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let fader_bounds = self.fader_bounds(bounds);
+		let center_x = bounds.center_x();
+		let mut primitives = vec![
+			Self::quad(bounds, BACKGROUND_COLOR, 4.0),
+			Self::text(
+				self.name.into(),
+				Point::new(center_x, bounds.y + NAME_HEIGHT / 2.0),
+				TEXT_SIZE,
+				TEXT_COLOR,
+				HorizontalAlignment::Center,
+			),
+			Self::text(
+				format!("{:+.1} dB", self.volume),
+				Point::new(center_x, bounds.y + NAME_HEIGHT + VALUE_HEIGHT / 2.0),
+				SCALE_TEXT_SIZE,
+				TEXT_COLOR,
+				HorizontalAlignment::Center,
+			),
+			Self::quad(
+				Rectangle::new(
+					Point::new(center_x - FADER_TRACK_WIDTH / 2.0, fader_bounds.y),
+					Size::new(FADER_TRACK_WIDTH, fader_bounds.height),
+				),
+				FADER_TRACK_COLOR,
+				2.0,
+			),
+		];
+		for mark in SCALE_MARKS.iter() {
+			let y = self.volume_y(fader_bounds, *mark);
+			primitives.push(Self::quad(
+				Rectangle::new(Point::new(center_x + 8.0, y), Size::new(6.0, 1.0)),
+				SCALE_COLOR,
+				0.0,
+			));
+			primitives.push(Self::text(
+				format!("{}", mark),
+				Point::new(center_x + 17.0, y),
+				SCALE_TEXT_SIZE,
+				SCALE_COLOR,
+				HorizontalAlignment::Left,
+			));
+		}
+		let cap_y = self.volume_y(fader_bounds, self.volume);
+		primitives.push(Self::quad(
+			Rectangle::new(
+				Point::new(
+					center_x - FADER_CAP_SIZE.width / 2.0,
+					cap_y - FADER_CAP_SIZE.height / 2.0,
+				),
+				FADER_CAP_SIZE,
+			),
+			if self.audible {
+				FADER_CAP_COLOR
+			} else {
+				SILENT_FADER_CAP_COLOR
+			},
+			2.0,
+		));
+		let (mute_bounds, solo_bounds) = self.button_bounds(bounds);
+		if let Some(mute_bounds) = mute_bounds {
+			Self::draw_button(mute_bounds, "Mute", self.muted, MUTE_COLOR, &mut primitives);
+		}
+		if let Some(solo_bounds) = solo_bounds {
+			Self::draw_button(
+				solo_bounds,
+				"Solo",
+				self.soloed,
+				SOLO_COLOR,
+				&mut primitives,
+			);
+		}
+		let over_button = [mute_bounds, solo_bounds]
+			.iter()
+			.flatten()
+			.any(|button_bounds| button_bounds.contains(cursor_position));
+		let interaction = if self.state.dragging {
+			Interaction::Grabbing
+		} else if over_button {
+			Interaction::Pointer
+		} else if self.on_change.is_some() && fader_bounds.contains(cursor_position) {
+			Interaction::Grab
+		} else {
+			Interaction::default()
+		};
+		(Primitive::Group { primitives }, interaction)
+	}
+
+	fn hash_layout(&self, state: &mut iced_native::Hasher) {
+		use std::hash::Hash;
+		self.name.hash(state)
+	}
+}
+
+impl<'a, Message: Clone + 'a, B: Backend + 'a> From<ChannelStrip<'a, Message>>
+	for Element<'a, Message, Renderer<B>>
+{
+	fn from(channel_strip: ChannelStrip<'a, Message>) -> Self {
+		Element::new(channel_strip)
+	}
+}
@@ -0,0 +1,552 @@
+use std::error::Error;
+
+use iced::{
+	Align, Button, Column, HorizontalAlignment, Length, Radio, Row, Slider, Text, TextInput,
+};
+use kira::{
+	arrangement::{Arrangement, ArrangementId, LoopArrangementSettings},
+	instance::StopInstanceSettings,
+	manager::{AudioManager, AudioManagerSettings},
+	mixer::{
+		effect::filter::{Filter, FilterSettings},
+		TrackIndex,
+	},
+	parameter::Tween,
+	playable::PlayableSettings,
+	Tempo,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{
+		create_audio_manager,
+		effect::{
+			distortion::{Distortion, DistortionSettings},
+			gain::Gain,
+		},
+		mix_snapshots::MixSnapshots,
+		sound_cache::SoundCache,
+	},
+	ui::{
+		common::{screen_wrapper::ScreenWrapper, transport::Transport},
+		style::AppStyles,
+	},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &[
+	"underwater demo/drums.ogg",
+	"underwater demo/bass.ogg",
+	"underwater demo/pad.ogg",
+	"underwater demo/lead.ogg",
+];
+
+const EXPLANATION_TEXT: &str = "A snapshot is a saved copy of \
+the whole mix: the volume of every part, the drive of the \
+distortion on the bass, and the cutoff of the low-pass filter \
+on the main track. Set the controls, give the snapshot a name, \
+and capture it. Going to a snapshot moves every value there at \
+once over the morph time, so a game can switch between moods - \
+exploring, combat, a pause menu - with a single call instead of \
+a dozen separate fades.
+
+Kira doesn't report the current value of a parameter, so the \
+demo keeps its own copy of every value in the mix, and a \
+snapshot is just a copy of that list. Going to a snapshot sets \
+each parameter to the saved value with a tween of the chosen \
+length. The sliders jump straight to where the mix is heading \
+while the audio catches up.
+
+Morphing from A to B jumps to A first and then glides to B, so \
+the same transition can be heard again and again.";
+
+pub const TEMPO: Tempo = Tempo(85.0);
+const PART_NAMES: [&str; 4] = ["Drums", "Bass", "Pad", "Lead"];
+const NUM_PARTS: usize = PART_NAMES.len();
+/// The bass drive and the filter cutoff come after the part
+/// volumes in the mix.
+const DRIVE: usize = NUM_PARTS;
+const CUTOFF: usize = NUM_PARTS + 1;
+/// The range of the volume sliders (in decibels).
+const MIN_VOLUME: f64 = -60.0;
+const MAX_VOLUME: f64 = 6.0;
+/// The range of the bass drive (in decibels).
+const MAX_DRIVE: f64 = 24.0;
+/// The range of the filter cutoff (in Hz).
+const MIN_CUTOFF: f64 = 200.0;
+const MAX_CUTOFF: f64 = 20000.0;
+const MAX_MORPH_TIME: f64 = 8.0;
+const INITIAL_MORPH_TIME: f64 = 2.0;
+/// The snapshots the demo starts with: every part at 0 dB with
+/// the filter open, and a muffled breakdown with no drums.
+const INITIAL_SNAPSHOTS: [(&str, [f64; 6]); 2] = [
+	("Full mix", [0.0, 0.0, 0.0, 0.0, 0.0, MAX_CUTOFF]),
+	("Breakdown", [-60.0, -9.0, 0.0, -6.0, 0.0, 800.0]),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetMetronomeClick(bool),
+	/// Sets one of the values in the mix (a part's volume in
+	/// decibels, the bass drive in decibels, or the cutoff in
+	/// Hz).
+	SetValue(usize, f64),
+	/// Sets how long going to a snapshot takes (in seconds).
+	SetMorphTime(f64),
+	SetSnapshotName(String),
+	CaptureSnapshot,
+	RecallSnapshot(usize),
+	RemoveSnapshot(usize),
+	SetMorphA(usize),
+	SetMorphB(usize),
+	MorphAToB,
+}
+
+struct SnapshotButtons {
+	recall: iced::button::State,
+	remove: iced::button::State,
+}
+
+pub struct MixerSnapshotDemo {
+	audio_manager: AudioManager,
+	loop_ids: Vec<ArrangementId>,
+	/// The part volumes, then the bass drive and the cutoff.
+	mix: MixSnapshots,
+	morph_time: f64,
+	snapshot_name: String,
+	morph_a: Option<usize>,
+	morph_b: Option<usize>,
+	transport: Transport,
+	screen_wrapper: ScreenWrapper<Message>,
+	value_sliders: Vec<iced::slider::State>,
+	morph_time_slider: iced::slider::State,
+	name_input: iced::text_input::State,
+	capture_button: iced::button::State,
+	snapshot_buttons: Vec<SnapshotButtons>,
+	morph_button: iced::button::State,
+}
+
+impl MixerSnapshotDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(AudioManagerSettings {
+			metronome_settings: Transport::metronome_settings(TEMPO),
+			..audio_manager_settings
+		})?;
+		let transport = Transport::new(&mut audio_manager, TEMPO)?;
+		let (_, initial_values) = INITIAL_SNAPSHOTS[0];
+		let drive_parameter_id = audio_manager.add_parameter(initial_values[DRIVE])?;
+		let cutoff_parameter_id = audio_manager.add_parameter(initial_values[CUTOFF])?;
+		let assets_base_dir = std::env::current_dir()?.join("assets");
+		let mut loop_ids = vec![];
+		let mut mix_parameters = vec![];
+		for (index, asset) in ASSETS.iter().enumerate() {
+			let volume_parameter_id = audio_manager.add_parameter(initial_values[index])?;
+			let track_id = audio_manager.add_sub_track(Default::default())?;
+			// the bass is distorted before its fader, so turning
+			// the drive up doesn't change where the fader sits
+			if index == 1 {
+				audio_manager.add_effect_to_track(
+					track_id,
+					Distortion::new(DistortionSettings::new().drive(drive_parameter_id)),
+					Default::default(),
+				)?;
+			}
+			audio_manager.add_effect_to_track(
+				track_id,
+				Gain::new(volume_parameter_id),
+				Default::default(),
+			)?;
+			let sound_id = audio_manager.add_sound(
+				sound_cache.get(&assets_base_dir.join(asset))?.to_sound(
+					PlayableSettings::new().semantic_duration(TEMPO.beats_to_seconds(16.0)),
+				),
+			)?;
+			loop_ids.push(audio_manager.add_arrangement(Arrangement::new_loop(
+				sound_id,
+				LoopArrangementSettings::new().default_track(track_id),
+			))?);
+			mix_parameters.push((volume_parameter_id, initial_values[index]));
+		}
+		mix_parameters.push((drive_parameter_id, initial_values[DRIVE]));
+		mix_parameters.push((cutoff_parameter_id, initial_values[CUTOFF]));
+		audio_manager.add_effect_to_track(
+			TrackIndex::Main,
+			Filter::new(FilterSettings::new().cutoff(cutoff_parameter_id)),
+			Default::default(),
+		)?;
+		// the starting snapshots are captured by setting the mix
+		// to each one in turn, which is fine since nothing is
+		// playing yet
+		let mut mix = MixSnapshots::new(&mix_parameters);
+		for (name, values) in INITIAL_SNAPSHOTS.iter() {
+			for (index, value) in values.iter().enumerate() {
+				mix.set(&mut audio_manager, index, *value)?;
+			}
+			mix.capture(*name);
+		}
+		mix.recall(&mut audio_manager, 0, 0.0)?;
+		Ok(Self {
+			audio_manager,
+			loop_ids,
+			mix,
+			morph_time: INITIAL_MORPH_TIME,
+			snapshot_name: String::new(),
+			morph_a: Some(0),
+			morph_b: Some(1),
+			transport,
+			screen_wrapper: ScreenWrapper::new(
+				"Mixer snapshot demo".into(),
+				Message::GoToDemoSelect,
+			),
+			value_sliders: mix_parameters
+				.iter()
+				.map(|_| iced::slider::State::new())
+				.collect(),
+			morph_time_slider: iced::slider::State::new(),
+			name_input: iced::text_input::State::new(),
+			capture_button: iced::button::State::new(),
+			snapshot_buttons: vec![],
+			morph_button: iced::button::State::new(),
+		})
+	}
+
+	fn play(&mut self) -> Result<(), Box<dyn Error>> {
+		// the commands are all sent at once, so the loops start
+		// on the same sample
+		for loop_id in &self.loop_ids {
+			self.audio_manager.play(*loop_id, Default::default())?;
+		}
+		self.transport.start(&mut self.audio_manager)?;
+		Ok(())
+	}
+
+	fn stop(&mut self) -> Result<(), Box<dyn Error>> {
+		for loop_id in &self.loop_ids {
+			self.audio_manager.stop_instances_of(
+				(*loop_id).into(),
+				StopInstanceSettings::new().fade_tween(Tween::linear(0.5)),
+			)?;
+		}
+		self.transport.stop(&mut self.audio_manager)?;
+		Ok(())
+	}
+
+	fn value_range(index: usize) -> (f64, f64) {
+		match index {
+			DRIVE => (0.0, MAX_DRIVE),
+			CUTOFF => (MIN_CUTOFF, MAX_CUTOFF),
+			_ => (MIN_VOLUME, MAX_VOLUME),
+		}
+	}
+
+	fn value_text(&self, index: usize) -> String {
+		let value = self.mix.value(index);
+		match index {
+			DRIVE => format!("Bass drive: {:.1} dB", value),
+			CUTOFF => format!("Cutoff: {:.0} Hz", value),
+			_ => format!("{}: {:.1} dB", PART_NAMES[index], value),
+		}
+	}
+
+	/// Keeps the A and B choices pointing at the same snapshots
+	/// after the one at `removed` is thrown away.
+	fn shift_morph_choice(choice: Option<usize>, removed: usize) -> Option<usize> {
+		match choice {
+			Some(index) if index == removed => None,
+			Some(index) if index > removed => Some(index - 1),
+			choice => choice,
+		}
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play => self.play()?,
+			Message::Stop => self.stop()?,
+			Message::SetMetronomeClick(enabled) => {
+				self.transport
+					.set_click_enabled(&mut self.audio_manager, enabled)?;
+			}
+			Message::SetValue(index, value) if index <= CUTOFF => {
+				let (min, max) = Self::value_range(index);
+				self.mix
+					.set(&mut self.audio_manager, index, value.clamp(min, max))?;
+			}
+			Message::SetMorphTime(morph_time) => {
+				self.morph_time = morph_time.clamp(0.0, MAX_MORPH_TIME);
+			}
+			Message::SetSnapshotName(name) => {
+				self.snapshot_name = name;
+			}
+			Message::CaptureSnapshot => {
+				let name = match self.snapshot_name.trim() {
+					"" => format!("Snapshot {}", self.mix.snapshots().len() + 1),
+					name => name.to_string(),
+				};
+				self.mix.capture(name);
+				self.snapshot_name.clear();
+			}
+			Message::RecallSnapshot(index) if index < self.mix.snapshots().len() => {
+				self.mix
+					.recall(&mut self.audio_manager, index, self.morph_time)?;
+			}
+			Message::RemoveSnapshot(index) if index < self.mix.snapshots().len() => {
+				self.mix.remove(index);
+				self.morph_a = Self::shift_morph_choice(self.morph_a, index);
+				self.morph_b = Self::shift_morph_choice(self.morph_b, index);
+			}
+			Message::SetMorphA(index) if index < self.mix.snapshots().len() => {
+				self.morph_a = Some(index);
+			}
+			Message::SetMorphB(index) if index < self.mix.snapshots().len() => {
+				self.morph_b = Some(index);
+			}
+			Message::MorphAToB => {
+				if let (Some(a), Some(b)) = (self.morph_a, self.morph_b) {
+					self.mix
+						.morph(&mut self.audio_manager, a, b, self.morph_time)?;
+				}
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		self.transport.check_for_events(&mut self.audio_manager);
+		Ok(())
+	}
+
+	fn small_button<'a>(
+		state: &'a mut iced::button::State,
+		label: &str,
+		message: Message,
+	) -> Button<'a, Message> {
+		Button::new(
+			state,
+			Text::new(label)
+				.width(Length::Fill)
+				.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.on_press(message)
+		.width(Length::Units(90))
+		.style(AppStyles)
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let value_texts: Vec<String> = (0..self.value_sliders.len())
+			.map(|index| self.value_text(index))
+			.collect();
+		let mix = &self.mix;
+		let value_sliders = self
+			.value_sliders
+			.iter_mut()
+			.zip(value_texts)
+			.enumerate()
+			.fold(
+				Column::new().spacing(8).max_width(500),
+				|column, (index, (slider, text))| {
+					let (min, max) = Self::value_range(index);
+					column.push(
+						Row::new()
+							.spacing(16)
+							.align_items(Align::Center)
+							.push(Text::new(text).width(Length::Units(200)))
+							.push(
+								Slider::new(slider, min..=max, mix.value(index), move |value| {
+									Message::SetValue(index, value)
+								})
+								.step(if index == CUTOFF { 10.0 } else { 0.5 })
+								.style(AppStyles),
+							),
+					)
+				},
+			);
+		let capture_row = Row::new()
+			.spacing(16)
+			.align_items(Align::Center)
+			.max_width(500)
+			.push(
+				TextInput::new(
+					&mut self.name_input,
+					"Snapshot name",
+					&self.snapshot_name,
+					Message::SetSnapshotName,
+				)
+				.on_submit(Message::CaptureSnapshot)
+				.padding(8),
+			)
+			.push(Self::small_button(
+				&mut self.capture_button,
+				"Capture",
+				Message::CaptureSnapshot,
+			));
+		let snapshot_names: Vec<String> = mix
+			.snapshots()
+			.iter()
+			.map(|snapshot| snapshot.name.clone())
+			.collect();
+		self.snapshot_buttons
+			.resize_with(snapshot_names.len(), || SnapshotButtons {
+				recall: iced::button::State::new(),
+				remove: iced::button::State::new(),
+			});
+		let (morph_a, morph_b) = (self.morph_a, self.morph_b);
+		let snapshots = self
+			.snapshot_buttons
+			.iter_mut()
+			.zip(&snapshot_names)
+			.enumerate()
+			.fold(
+				Column::new().spacing(8).max_width(500),
+				|column, (index, (buttons, name))| {
+					column.push(
+						Row::new()
+							.spacing(16)
+							.align_items(Align::Center)
+							.push(Text::new(name.as_str()).width(Length::Fill))
+							.push(Radio::new(index, "A", morph_a, Message::SetMorphA))
+							.push(Radio::new(index, "B", morph_b, Message::SetMorphB))
+							.push(Self::small_button(
+								&mut buttons.recall,
+								"Go",
+								Message::RecallSnapshot(index),
+							))
+							.push(Self::small_button(
+								&mut buttons.remove,
+								"Remove",
+								Message::RemoveSnapshot(index),
+							)),
+					)
+				},
+			);
+		let morph_label = match (morph_a, morph_b) {
+			(Some(a), Some(b)) => format!("Morph {} to {}", snapshot_names[a], snapshot_names[b]),
+			_ => "Pick snapshots A and B to morph between".into(),
+		};
+		let mut morph_button = Button::new(
+			&mut self.morph_button,
+			Text::new(morph_label)
+				.width(Length::Fill)
+				.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.width(Length::Units(400))
+		.style(AppStyles);
+		if morph_a.is_some() && morph_b.is_some() {
+			morph_button = morph_button.on_press(Message::MorphAToB);
+		}
+		self.screen_wrapper.view_with_transport(
+			self.transport
+				.view(Message::Play, Message::Stop, Message::SetMetronomeClick),
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(value_sliders)
+				.push(capture_row)
+				.push(snapshots)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Morph time: {:.1} s", self.morph_time))
+								.width(Length::Units(200)),
+						)
+						.push(
+							Slider::new(
+								&mut self.morph_time_slider,
+								0.0..=MAX_MORPH_TIME,
+								self.morph_time,
+								Message::SetMorphTime,
+							)
+							.step(0.1)
+							.style(AppStyles),
+						),
+				)
+				.push(morph_button)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for MixerSnapshotDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "mixer_snapshot_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetValue(DRIVE, _) => Some("Bass drive".into()),
+			Message::SetValue(CUTOFF, _) => Some("Cutoff".into()),
+			Message::SetValue(index, _) if *index < NUM_PARTS => {
+				Some(format!("{} volume", PART_NAMES[*index]))
+			}
+			Message::SetMorphTime(_) => Some("Morph time".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		let index = match control {
+			"Bass drive" => DRIVE,
+			"Cutoff" => CUTOFF,
+			"Morph time" => return vec![Message::SetMorphTime(MAX_MORPH_TIME * value)],
+			_ => match PART_NAMES
+				.iter()
+				.position(|name| control == format!("{} volume", name))
+			{
+				Some(index) => index,
+				None => return vec![],
+			},
+		};
+		let (min, max) = Self::value_range(index);
+		vec![Message::SetValue(index, min + (max - min) * value)]
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for MixerSnapshotDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "mixer_snapshot_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		let mut messages = vec![Message::SetMetronomeClick(self.transport.click_enabled())];
+		for index in 0..self.value_sliders.len() {
+			messages.push(Message::SetValue(index, self.mix.value(index)));
+		}
+		messages.push(Message::SetMorphTime(self.morph_time));
+		messages
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for MixerSnapshotDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.transport.is_playing(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if !self.transport.is_playing() {
+			return None;
+		}
+		Some("Mixer snapshot demo".into())
+	}
+}
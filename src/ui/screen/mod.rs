@@ -1,3 +1,77 @@
+pub mod arrangement_editor_demo;
+pub mod beat_repeat_demo;
+pub mod branching_music_demo;
+pub mod chord_progression_demo;
+pub mod combat_music_demo;
+pub mod compressor_demo;
+pub mod count_in_demo;
+pub mod crossfade_demo;
+pub mod custom_loop_demo;
+pub mod delay_demo;
 pub mod demo_select;
+pub mod dialogue_demo;
+pub mod distortion_demo;
+pub mod doppler_demo;
 pub mod drum_fill_demo;
+pub mod drum_pad_demo;
+pub mod dual_metronome_demo;
+pub mod ducking_demo;
+pub mod easing_gallery;
+pub mod engine_demo;
+pub mod filter_demo;
+pub mod footstep_demo;
+pub mod footstep_surface_demo;
+pub mod generative_ambient_demo;
+pub mod granular_demo;
+pub mod group_hierarchy_demo;
+pub mod group_pause_demo;
+pub mod jukebox_demo;
+pub mod karaoke_demo;
+pub mod layering_demo;
+pub mod lfo_demo;
+pub mod limits_demo;
+pub mod loop_region_demo;
+pub mod low_health_demo;
+pub mod mapping_playground;
+pub mod mixer_console_demo;
+pub mod mixer_snapshot_demo;
+pub mod net_sync_demo;
+pub mod occlusion_demo;
+pub mod odd_time_signature_demo;
+pub mod panning_demo;
+pub mod parametric_eq_demo;
+pub mod pause_menu_demo;
+pub mod pitch_playground;
+pub mod polyphony_demo;
+pub mod radio_voice_demo;
+pub mod retrigger_cooldown_demo;
+pub mod reverb_demo;
+pub mod rhythm_game_demo;
+pub mod scheduling_accuracy_demo;
+pub mod seek_demo;
+pub mod send_return_demo;
+pub mod settings;
+pub mod signal_generator_demo;
+pub mod sound_container_demo;
+pub mod spatial_demo;
+pub mod spectrum_demo;
+pub mod start_offset_demo;
+pub mod stem_mute_solo_demo;
+pub mod step_sequencer_demo;
+pub mod stereo_width_demo;
+pub mod stinger_demo;
+pub mod stop_fade_demo;
+pub mod streaming_demo;
+pub mod surround_demo;
+pub mod swing_demo;
+pub mod synth_keyboard_demo;
+pub mod tempo_ramp_demo;
+pub mod transition_matrix_demo;
+pub mod tuning_demo;
+pub mod ui_sounds_demo;
 pub mod underwater_demo;
+pub mod vinyl_brake_demo;
+pub mod voice_over_demo;
+pub mod weapon_demo;
+pub mod weather_demo;
+pub mod wind_demo;
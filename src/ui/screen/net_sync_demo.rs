@@ -0,0 +1,413 @@
+use std::{error::Error, time::Instant};
+
+use iced::{
+	Align, Button, Column, HorizontalAlignment, Length, Radio, Row, Slider, Text, TextInput,
+};
+use kira::{
+	manager::{AudioManager, AudioManagerSettings},
+	playable::PlayableSettings,
+	sequence::{EventReceiver, Sequence, SequenceInstanceId},
+	sound::SoundId,
+	AudioResult, Duration, Tempo,
+};
+
+use crate::{
+	audio::{create_audio_manager, sound_cache::SoundCache, synth},
+	net_sync::{SyncFollower, SyncLeader, DEFAULT_PORT},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &["drum fill demo/loop.ogg"];
+
+const TEMPO: Tempo = Tempo(128.0);
+const BEATS_PER_BAR: usize = 4;
+/// How far apart (in seconds) the follower can drift from the
+/// leader before it jumps back in time with it.
+const MAX_OFFSET: f64 = 0.03;
+/// The least amount of time (in seconds) the follower leaves
+/// itself to start the music, so the sequence doesn't miss the
+/// start of the bar it's aiming for.
+const MIN_START_DELAY: f64 = 0.1;
+
+const EXPLANATION_TEXT: &str = "This experimental demo keeps \
+two copies of the app playing the drum loop in time over the \
+local network. Start listening as the leader on one machine, then \
+connect to it as a follower from another (or from a second copy \
+on the same machine, using 127.0.0.1).
+
+The follower keeps pinging the leader to ask how far its music \
+has played and measures how long the replies take to come back, \
+so it can make up for the time they spent on the network. When \
+the leader plays, the follower comes in at the start of the next \
+bar, and if the two drift apart, the follower jumps back in time \
+at the next bar. Use the nudge slider to make up for differences \
+in the audio latency of the two machines.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+	Leader,
+	Follower,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+	GoToDemoSelect,
+	SetRole(Role),
+	SetLeaderAddress(String),
+	Connect,
+	Disconnect,
+	Play,
+	Stop,
+	SetNudge(f64),
+}
+
+enum Connection {
+	Leader(SyncLeader),
+	Follower(SyncFollower),
+}
+
+pub struct NetSyncDemo {
+	audio_manager: AudioManager,
+	loop_sound_id: SoundId,
+	click_sound_id: SoundId,
+	role: Role,
+	connection: Option<Connection>,
+	leader_address: String,
+	/// How much earlier (in seconds) the follower plays than the
+	/// leader says it should.
+	nudge: f64,
+	music: Option<(SequenceInstanceId, EventReceiver<usize>)>,
+	/// When the first beat of the music played (or will play).
+	start_time: Instant,
+	/// The beat that's playing, counted from the start of the
+	/// music.
+	beat: Option<usize>,
+	/// How far off the follower was from the leader the last time
+	/// they were compared (in seconds).
+	offset: Option<f64>,
+	screen_wrapper: ScreenWrapper<Message>,
+	leader_address_input: iced::text_input::State,
+	connect_button: iced::button::State,
+	play_button: iced::button::State,
+	nudge_slider: iced::slider::State,
+}
+
+impl NetSyncDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let assets_base_dir = std::env::current_dir()?.join("assets/drum fill demo");
+		let loop_sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&assets_base_dir.join("loop.ogg"))?
+				.to_sound(PlayableSettings::new()),
+		)?;
+		let click_sound_id =
+			audio_manager.add_sound(synth::blip(1760.0, 0.05, PlayableSettings::new()))?;
+		Ok(Self {
+			audio_manager,
+			loop_sound_id,
+			click_sound_id,
+			role: Role::Leader,
+			connection: None,
+			leader_address: "127.0.0.1".into(),
+			nudge: 0.0,
+			music: None,
+			start_time: Instant::now(),
+			beat: None,
+			offset: None,
+			screen_wrapper: ScreenWrapper::new("Network sync demo".into(), Message::GoToDemoSelect),
+			leader_address_input: iced::text_input::State::new(),
+			connect_button: iced::button::State::new(),
+			play_button: iced::button::State::new(),
+			nudge_slider: iced::slider::State::new(),
+		})
+	}
+
+	fn bar_length(tempo: Tempo) -> f64 {
+		tempo.beats_to_seconds(BEATS_PER_BAR as f64)
+	}
+
+	/// How far the music has played (in seconds), if it's playing.
+	fn position(&self) -> Option<f64> {
+		self.music.as_ref().map(|_| {
+			let now = Instant::now();
+			if now >= self.start_time {
+				(now - self.start_time).as_secs_f64()
+			} else {
+				-(self.start_time - now).as_secs_f64()
+			}
+		})
+	}
+
+	/// Starts the music `delay` seconds from now. Each bar plays
+	/// the drum loop and clicks on every beat.
+	fn start_music(&mut self, delay: f64, tempo: Tempo) -> AudioResult<()> {
+		self.stop_music()?;
+		let beat_length = tempo.beats_to_seconds(1.0);
+		let mut sequence = Sequence::new(Default::default());
+		sequence.wait(Duration::Seconds(delay));
+		sequence.start_loop();
+		sequence.play(self.loop_sound_id, Default::default());
+		for beat in 0..BEATS_PER_BAR {
+			sequence.play(self.click_sound_id, Default::default());
+			sequence.emit(beat);
+			sequence.wait(Duration::Seconds(beat_length));
+		}
+		self.music = Some(
+			self.audio_manager
+				.start_sequence(sequence, Default::default())?,
+		);
+		self.start_time = Instant::now() + std::time::Duration::from_secs_f64(delay);
+		self.beat = None;
+		Ok(())
+	}
+
+	fn stop_music(&mut self) -> AudioResult<()> {
+		if let Some((sequence_id, _)) = self.music.take() {
+			self.audio_manager
+				.stop_sequence_and_instances(sequence_id, Default::default())?;
+		}
+		self.beat = None;
+		self.offset = None;
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::SetRole(role) if role != self.role => {
+				self.connection = None;
+				self.stop_music()?;
+				self.role = role;
+			}
+			Message::SetLeaderAddress(address) => {
+				self.leader_address = address;
+			}
+			Message::Connect => {
+				self.connection = Some(match self.role {
+					Role::Leader => Connection::Leader(SyncLeader::bind(DEFAULT_PORT)?),
+					Role::Follower => {
+						Connection::Follower(SyncFollower::connect(&self.leader_address)?)
+					}
+				});
+			}
+			Message::Disconnect => {
+				self.connection = None;
+				if self.role == Role::Follower {
+					self.stop_music()?;
+				}
+			}
+			Message::Play => {
+				self.start_music(0.0, TEMPO)?;
+			}
+			Message::Stop => {
+				self.stop_music()?;
+			}
+			Message::SetNudge(nudge) => {
+				self.nudge = nudge;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	/// Starts, stops, or moves the follower's music so it lines
+	/// up with the leader's.
+	fn follow(&mut self) -> AudioResult<()> {
+		let (leader_position, tempo) = match &self.connection {
+			Some(Connection::Follower(follower)) => (
+				follower.leader_position(),
+				follower.leader_tempo().unwrap_or(TEMPO),
+			),
+			_ => return Ok(()),
+		};
+		let leader_position = match leader_position {
+			Some(position) => position + self.nudge,
+			None => {
+				if self.music.is_some() {
+					self.stop_music()?;
+				}
+				return Ok(());
+			}
+		};
+		let bar_length = Self::bar_length(tempo);
+		if let Some(position) = self.position() {
+			// wrap the offset so a follower that's a whole bar
+			// behind still counts as in time
+			let offset = (position - leader_position + bar_length / 2.0).rem_euclid(bar_length)
+				- bar_length / 2.0;
+			self.offset = Some(offset);
+			if offset.abs() <= MAX_OFFSET {
+				return Ok(());
+			}
+		}
+		// come in at the start of the leader's next bar
+		let mut delay = bar_length - leader_position.rem_euclid(bar_length);
+		if delay < MIN_START_DELAY {
+			delay += bar_length;
+		}
+		self.start_music(delay, tempo)
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		let position = self.position();
+		match &mut self.connection {
+			Some(Connection::Leader(leader)) => leader.update(position, TEMPO)?,
+			Some(Connection::Follower(follower)) => follower.update()?,
+			None => {}
+		}
+		self.follow()?;
+		if let Some((_, events)) = &mut self.music {
+			while let Some(beat) = events.pop() {
+				self.beat = Some(*beat);
+			}
+		}
+		Ok(())
+	}
+
+	fn status_text(&self) -> String {
+		match &self.connection {
+			None => "Not connected".into(),
+			Some(Connection::Leader(leader)) => match leader.port() {
+				Ok(port) => format!(
+					"Listening on port {} - {} follower(s) connected",
+					port,
+					leader.num_followers()
+				),
+				Err(error) => error.to_string(),
+			},
+			Some(Connection::Follower(follower)) => {
+				if !follower.is_connected() {
+					return format!("Waiting for {}", follower.leader_address());
+				}
+				let mut text = format!("Following {}", follower.leader_address());
+				if let Some(latency) = follower.latency() {
+					text += &format!(" - latency {:.1} ms", latency * 1000.0);
+				}
+				if let Some(offset) = self.offset {
+					text += &format!(" - offset {:+.1} ms", offset * 1000.0);
+				}
+				if follower.leader_position().is_none() {
+					text += " - leader stopped";
+				}
+				text
+			}
+		}
+	}
+
+	fn small_button<'a>(
+		state: &'a mut iced::button::State,
+		label: &str,
+		message: Message,
+	) -> Button<'a, Message> {
+		Button::new(
+			state,
+			Text::new(label)
+				.width(Length::Fill)
+				.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.on_press(message)
+		.width(Length::Units(120))
+		.style(AppStyles)
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let role = self.role;
+		let connected = self.connection.is_some();
+		let playing = self.music.is_some();
+		let status_text = self.status_text();
+		let position_text = match self.beat {
+			Some(beat) => format!("Beat {}", beat + 1),
+			None => "Beat -".into(),
+		};
+		let role_radios = [(Role::Leader, "Leader"), (Role::Follower, "Follower")]
+			.iter()
+			.fold(
+				Row::new().spacing(16).align_items(Align::Center),
+				|row, (radio_role, label)| {
+					row.push(Radio::new(
+						*radio_role,
+						*label,
+						Some(role),
+						Message::SetRole,
+					))
+				},
+			);
+		let mut connection_row = Row::new().spacing(16).align_items(Align::Center);
+		if role == Role::Follower {
+			connection_row = connection_row.push(
+				TextInput::new(
+					&mut self.leader_address_input,
+					"Leader address",
+					&self.leader_address,
+					Message::SetLeaderAddress,
+				)
+				.on_submit(Message::Connect)
+				.padding(8)
+				.width(Length::Units(240)),
+			);
+		}
+		connection_row = connection_row.push(Self::small_button(
+			&mut self.connect_button,
+			match (connected, role) {
+				(true, _) => "Disconnect",
+				(false, Role::Leader) => "Listen",
+				(false, Role::Follower) => "Connect",
+			},
+			if connected {
+				Message::Disconnect
+			} else {
+				Message::Connect
+			},
+		));
+		let mut contents = Column::new()
+			.spacing(16)
+			.align_items(Align::Center)
+			.push(role_radios)
+			.push(connection_row)
+			.push(Text::new(status_text).size(18));
+		contents = match role {
+			Role::Leader => contents.push(Self::small_button(
+				&mut self.play_button,
+				if playing { "Stop" } else { "Play" },
+				if playing {
+					Message::Stop
+				} else {
+					Message::Play
+				},
+			)),
+			Role::Follower => contents.push(
+				Row::new()
+					.spacing(16)
+					.align_items(Align::Center)
+					.max_width(600)
+					.push(Text::new("Nudge"))
+					.push(
+						Slider::new(
+							&mut self.nudge_slider,
+							-0.1..=0.1,
+							self.nudge,
+							Message::SetNudge,
+						)
+						.step(0.001)
+						.style(AppStyles),
+					)
+					.push(
+						Text::new(format!("{:+.0} ms", self.nudge * 1000.0))
+							.width(Length::Units(80)),
+					),
+			),
+		};
+		self.screen_wrapper.view(
+			contents.push(Text::new(position_text).size(24)).push(
+				Column::new()
+					.width(Length::Fill)
+					.max_width(600)
+					.push(Text::new(EXPLANATION_TEXT)),
+			),
+		)
+	}
+}
@@ -0,0 +1,386 @@
+mod occlusion_map;
+
+use std::error::Error;
+
+use iced::{Align, Button, Checkbox, Column, HorizontalAlignment, Length, Radio, Row, Text};
+use kira::{
+	instance::{InstanceId, InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	mixer::{
+		effect::filter::{Filter, FilterSettings},
+		SubTrackId,
+	},
+	parameter::{ParameterId, Tween},
+	playable::PlayableSettings,
+	sound::SoundId,
+};
+use serde::{Deserialize, Serialize};
+
+use self::occlusion_map::{self as map, OcclusionMap};
+use crate::{
+	audio::{
+		create_audio_manager,
+		effect::gain::Gain,
+		sound_cache::SoundCache,
+		spatializer::{Spatialized, Spatializer},
+	},
+	preferences::OutputMode,
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &["drum fill demo/loop.ogg"];
+
+const EXPLANATION_TEXT: &str = "Drag the red dot to move the \
+sound and the grey bar to move the wall. When the wall is \
+between the sound and the listener, the sound is muffled and \
+turned down.
+
+The screen works out how much of the straight path is blocked. \
+Near the ends of the wall the sound bends around the edge, so \
+the amount fades in over a short distance instead of switching \
+on. That amount sets two parameters: the cutoff of a low-pass \
+filter and the level of a gain effect, both on the sound's \
+track. The panning and distance are handled the same way as in \
+the spatial demo.
+
+An obstruction (like a pillar) only blocks the direct path, so \
+reflections still get through and the sound stays fairly clear. \
+Occlusion (like a solid wall between rooms) puts the sound \
+through the wall itself, which cuts much more.";
+
+/// How far the edge of the map is from the listener.
+const MAP_RADIUS: f32 = 4.0;
+const WALL_LENGTH: f32 = 3.0;
+/// How far inside the end of the wall the path has to cross
+/// before it's fully blocked.
+const SOFT_EDGE: f64 = 0.5;
+const MAX_CUTOFF: f64 = 20000.0;
+/// How long parameter changes take, which keeps the sound from
+/// zipping while things are dragged.
+const SMOOTHING_TIME: f64 = 0.05;
+
+/// How much a wall muffles the sound.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WallKind {
+	Obstruction,
+	Occlusion,
+}
+
+impl WallKind {
+	const ALL: [Self; 2] = [Self::Obstruction, Self::Occlusion];
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::Obstruction => "Obstruction",
+			Self::Occlusion => "Occlusion",
+		}
+	}
+
+	/// The filter cutoff (in Hz) when the sound is fully blocked.
+	fn blocked_cutoff(self) -> f64 {
+		match self {
+			Self::Obstruction => 2500.0,
+			Self::Occlusion => 400.0,
+		}
+	}
+
+	/// How much the sound is turned down (in decibels) when it's
+	/// fully blocked.
+	fn blocked_reduction(self) -> f64 {
+		match self {
+			Self::Obstruction => 4.0,
+			Self::Occlusion => 18.0,
+		}
+	}
+}
+
+/// How much of the straight line from an emitter to the listener
+/// (at the origin) a horizontal wall blocks, from 0 to 1.
+fn occlusion(emitter: (f32, f32), wall: (f32, f32)) -> f64 {
+	let (emitter_x, emitter_y) = (emitter.0 as f64, emitter.1 as f64);
+	let (wall_x, wall_y) = (wall.0 as f64, wall.1 as f64);
+	if emitter_y == 0.0 {
+		return 0.0;
+	}
+	// how far along the path the wall's line is crossed
+	let crossing = wall_y / emitter_y;
+	if crossing <= 0.0 || crossing >= 1.0 {
+		return 0.0;
+	}
+	let crossing_x = emitter_x * crossing;
+	let inside = WALL_LENGTH as f64 / 2.0 - (crossing_x - wall_x).abs();
+	(inside / SOFT_EDGE).clamp(0.0, 1.0)
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetEmitterPosition(f32, f32),
+	SetWallPosition(f32, f32),
+	SetWallEnabled(bool),
+	SetWallKind(WallKind),
+}
+
+pub struct OcclusionDemo {
+	audio_manager: AudioManager,
+	spatializer: Spatializer,
+	track_id: SubTrackId,
+	sound_id: SoundId,
+	instance_id: Option<InstanceId>,
+	panning_parameter_id: ParameterId,
+	volume_parameter_id: ParameterId,
+	cutoff_parameter_id: ParameterId,
+	/// The gain effect's level (in decibels).
+	gain_parameter_id: ParameterId,
+	emitter: (f32, f32),
+	wall: (f32, f32),
+	wall_enabled: bool,
+	wall_kind: WallKind,
+	spatialized: Spatialized,
+	occlusion: f64,
+	screen_wrapper: ScreenWrapper<Message>,
+	occlusion_map: map::State,
+	play_button: iced::button::State,
+}
+
+impl OcclusionDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+		output_mode: OutputMode,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let spatializer = Spatializer::new(output_mode);
+		let emitter = (0.5, -3.0);
+		let wall = (0.0, -1.5);
+		let spatialized = spatializer.spatialize(emitter.0 as f64, emitter.1 as f64);
+		let panning_parameter_id = audio_manager.add_parameter(spatialized.panning)?;
+		let volume_parameter_id = audio_manager.add_parameter(spatialized.volume)?;
+		let cutoff_parameter_id = audio_manager.add_parameter(MAX_CUTOFF)?;
+		let gain_parameter_id = audio_manager.add_parameter(0.0)?;
+		let track_id = audio_manager.add_sub_track(Default::default())?;
+		audio_manager.add_effect_to_track(
+			track_id,
+			Filter::new(FilterSettings::new().cutoff(cutoff_parameter_id)),
+			Default::default(),
+		)?;
+		audio_manager.add_effect_to_track(
+			track_id,
+			Gain::new(gain_parameter_id),
+			Default::default(),
+		)?;
+		let sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&std::env::current_dir()?.join("assets/drum fill demo/loop.ogg"))?
+				.to_sound(PlayableSettings::new().default_loop_start(0.0)),
+		)?;
+		let mut demo = Self {
+			audio_manager,
+			spatializer,
+			track_id,
+			sound_id,
+			instance_id: None,
+			panning_parameter_id,
+			volume_parameter_id,
+			cutoff_parameter_id,
+			gain_parameter_id,
+			emitter,
+			wall,
+			wall_enabled: true,
+			wall_kind: WallKind::Occlusion,
+			spatialized,
+			occlusion: 0.0,
+			screen_wrapper: ScreenWrapper::new("Occlusion demo".into(), Message::GoToDemoSelect),
+			occlusion_map: Default::default(),
+			play_button: iced::button::State::new(),
+		};
+		demo.update_occlusion()?;
+		Ok(demo)
+	}
+
+	fn move_emitter(&mut self, x: f32, y: f32) -> Result<(), Box<dyn Error>> {
+		self.emitter = (x, y);
+		self.spatialized = self.spatializer.spatialize(x as f64, y as f64);
+		self.audio_manager.set_parameter(
+			self.panning_parameter_id,
+			self.spatialized.panning,
+			Some(Tween::linear(SMOOTHING_TIME)),
+		)?;
+		self.audio_manager.set_parameter(
+			self.volume_parameter_id,
+			self.spatialized.volume,
+			Some(Tween::linear(SMOOTHING_TIME)),
+		)?;
+		self.update_occlusion()
+	}
+
+	/// Works out how blocked the sound is and sets the filter
+	/// and gain to match. The cutoff moves on a log scale, since
+	/// that's how pitch is heard.
+	fn update_occlusion(&mut self) -> Result<(), Box<dyn Error>> {
+		self.occlusion = if self.wall_enabled {
+			occlusion(self.emitter, self.wall)
+		} else {
+			0.0
+		};
+		let cutoff =
+			MAX_CUTOFF * (self.wall_kind.blocked_cutoff() / MAX_CUTOFF).powf(self.occlusion);
+		self.audio_manager.set_parameter(
+			self.cutoff_parameter_id,
+			cutoff,
+			Some(Tween::linear(SMOOTHING_TIME)),
+		)?;
+		self.audio_manager.set_parameter(
+			self.gain_parameter_id,
+			-self.wall_kind.blocked_reduction() * self.occlusion,
+			Some(Tween::linear(SMOOTHING_TIME)),
+		)?;
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if self.instance_id.is_none() => {
+				self.instance_id = Some(
+					self.audio_manager.play(
+						self.sound_id,
+						InstanceSettings::new()
+							.track(self.track_id)
+							.panning(self.panning_parameter_id)
+							.volume(self.volume_parameter_id),
+					)?,
+				);
+			}
+			Message::Stop => {
+				if let Some(instance_id) = self.instance_id.take() {
+					self.audio_manager.stop_instance(
+						instance_id,
+						StopInstanceSettings::new().fade_tween(Tween::linear(0.25)),
+					)?;
+				}
+			}
+			Message::SetEmitterPosition(x, y) => self.move_emitter(x, y)?,
+			Message::SetWallPosition(x, y) => {
+				self.wall = (x, y);
+				self.update_occlusion()?;
+			}
+			Message::SetWallEnabled(enabled) => {
+				self.wall_enabled = enabled;
+				self.update_occlusion()?;
+			}
+			Message::SetWallKind(kind) => {
+				self.wall_kind = kind;
+				self.update_occlusion()?;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.instance_id.is_some();
+		let wall_kind = self.wall_kind;
+		let (x, y) = self.emitter;
+		let distance = (x * x + y * y).sqrt();
+		let mut map = OcclusionMap::new(&mut self.occlusion_map, MAP_RADIUS, self.emitter)
+			.occlusion(self.occlusion as f32)
+			.on_move_emitter(Message::SetEmitterPosition);
+		if self.wall_enabled {
+			map = map
+				.wall(self.wall, WALL_LENGTH)
+				.on_move_wall(Message::SetWallPosition);
+		}
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(map)
+				.push(Text::new(format!(
+					"Distance: {:.2} - volume: {:.1} dB - blocked: {:.0}%",
+					distance,
+					20.0 * self.spatialized.volume.max(f64::EPSILON).log10(),
+					self.occlusion * 100.0
+				)))
+				.push(
+					Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(Checkbox::new(
+							self.wall_enabled,
+							"Wall",
+							Message::SetWallEnabled,
+						))
+						.push(
+							WallKind::ALL
+								.iter()
+								.fold(Row::new().spacing(16), |row, option| {
+									row.push(Radio::new(
+										*option,
+										option.name(),
+										Some(wall_kind),
+										Message::SetWallKind,
+									))
+								}),
+						),
+				)
+				.push(
+					Button::new(
+						&mut self.play_button,
+						Text::new(if playing { "Stop" } else { "Play" })
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(if playing {
+						Message::Stop
+					} else {
+						Message::Play
+					})
+					.width(Length::Units(100))
+					.style(AppStyles),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for OcclusionDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "occlusion_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		let (emitter_x, emitter_y) = self.emitter;
+		let (wall_x, wall_y) = self.wall;
+		vec![
+			Message::SetEmitterPosition(emitter_x, emitter_y),
+			Message::SetWallPosition(wall_x, wall_y),
+			Message::SetWallEnabled(self.wall_enabled),
+			Message::SetWallKind(self.wall_kind),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for OcclusionDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.instance_id.is_some(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		self.instance_id.map(|_| "Occlusion demo".into())
+	}
+}
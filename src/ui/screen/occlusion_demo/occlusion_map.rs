@@ -0,0 +1,358 @@
+use iced::{mouse::Interaction, Background, Color, Length, Point, Rectangle, Size, Vector};
+use iced_graphics::{
+	triangle::{Mesh2D, Vertex2D},
+	Backend, Defaults, Primitive, Renderer,
+};
+use iced_native::{
+	event::Status,
+	layout::{Limits, Node},
+	mouse, Clipboard, Element, Event, Layout, Widget,
+};
+
+const SIZE: u16 = 300;
+const BACKGROUND_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.05];
+const LISTENER_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const EMITTER_COLOR: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+const WALL_COLOR: [f32; 4] = [0.6, 0.6, 0.7, 1.0];
+const CLEAR_PATH_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.5];
+const BLOCKED_PATH_COLOR: [f32; 4] = [1.0, 0.0, 0.0, 0.5];
+const PATH_WIDTH: f32 = 2.0;
+const DOT_RADIUS: f32 = 6.0;
+const WALL_THICKNESS: f32 = 8.0;
+/// How close the cursor has to be to grab something, which is
+/// a bit more forgiving than the shapes themselves.
+const GRAB_RADIUS: f32 = 12.0;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Grabbed {
+	Emitter,
+	Wall,
+}
+
+/// What's being dragged (if anything), and how far it was from
+/// the cursor when it was grabbed, so it doesn't jump to the
+/// cursor. This has to outlive the widget, which is rebuilt
+/// every time the screen is drawn.
+#[derive(Debug, Default)]
+pub struct State {
+	grabbed: Option<(Grabbed, (f32, f32))>,
+}
+
+/// A top-down view of a listener at the origin, a sound emitter,
+/// and a horizontal wall that can sit between them. Both the
+/// emitter and the wall can be dragged around. The line from the
+/// emitter to the listener turns red as the wall blocks it.
+pub struct OcclusionMap<'a, Message> {
+	state: &'a mut State,
+	/// How far the edge of the map is from the listener, in the
+	/// same units as positions.
+	radius: f32,
+	emitter: (f32, f32),
+	/// The center of the wall, if there is one.
+	wall: Option<(f32, f32)>,
+	wall_length: f32,
+	occlusion: f32,
+	on_move_emitter: Option<Box<dyn Fn(f32, f32) -> Message + 'a>>,
+	on_move_wall: Option<Box<dyn Fn(f32, f32) -> Message + 'a>>,
+}
+
+impl<'a, Message> OcclusionMap<'a, Message> {
+	pub fn new(state: &'a mut State, radius: f32, emitter: (f32, f32)) -> Self {
+		Self {
+			state,
+			radius,
+			emitter,
+			wall: None,
+			wall_length: 0.0,
+			occlusion: 0.0,
+			on_move_emitter: None,
+			on_move_wall: None,
+		}
+	}
+
+	pub fn wall(self, center: (f32, f32), length: f32) -> Self {
+		Self {
+			wall: Some(center),
+			wall_length: length,
+			..self
+		}
+	}
+
+	/// How much the wall is blocking the sound (from 0 to 1),
+	/// which sets the color of the path.
+	pub fn occlusion(self, occlusion: f32) -> Self {
+		Self {
+			occlusion: occlusion.clamp(0.0, 1.0),
+			..self
+		}
+	}
+
+	/// Reports the emitter's new position while it's dragged.
+	pub fn on_move_emitter(self, on_move_emitter: impl Fn(f32, f32) -> Message + 'a) -> Self {
+		Self {
+			on_move_emitter: Some(Box::new(on_move_emitter)),
+			..self
+		}
+	}
+
+	/// Reports the new center of the wall while it's dragged.
+	pub fn on_move_wall(self, on_move_wall: impl Fn(f32, f32) -> Message + 'a) -> Self {
+		Self {
+			on_move_wall: Some(Box::new(on_move_wall)),
+			..self
+		}
+	}
+
+	fn scale(&self, bounds: Rectangle) -> f32 {
+		bounds.width / 2.0 / self.radius
+	}
+
+	fn screen_point(&self, bounds: Rectangle, (x, y): (f32, f32)) -> Point {
+		let scale = self.scale(bounds);
+		let center = bounds.center();
+		Point::new(center.x + x * scale, center.y + y * scale)
+	}
+
+	/// Converts a point on the screen to a position, keeping it
+	/// on the map even if the cursor has left it.
+	fn position_at(&self, bounds: Rectangle, point: Point) -> (f32, f32) {
+		let scale = self.scale(bounds);
+		let center = bounds.center();
+		(
+			((point.x - center.x) / scale).clamp(-self.radius, self.radius),
+			((point.y - center.y) / scale).clamp(-self.radius, self.radius),
+		)
+	}
+
+	fn wall_bounds(&self, bounds: Rectangle) -> Option<Rectangle> {
+		let center = self.screen_point(bounds, self.wall?);
+		let length = self.wall_length * self.scale(bounds);
+		Some(Rectangle::new(
+			Point::new(center.x - length / 2.0, center.y - WALL_THICKNESS / 2.0),
+			Size::new(length, WALL_THICKNESS),
+		))
+	}
+
+	/// Works out what's under the cursor. The emitter wins if
+	/// it's over the wall, since it's smaller.
+	fn grabbable_at(&self, bounds: Rectangle, point: Point) -> Option<Grabbed> {
+		let emitter = self.screen_point(bounds, self.emitter);
+		if self.on_move_emitter.is_some() && emitter.distance(point) <= GRAB_RADIUS {
+			return Some(Grabbed::Emitter);
+		}
+		let wall_bounds = self.wall_bounds(bounds)?;
+		let grab_bounds = Rectangle::new(
+			Point::new(wall_bounds.x, wall_bounds.y - GRAB_RADIUS),
+			Size::new(wall_bounds.width, wall_bounds.height + GRAB_RADIUS * 2.0),
+		);
+		if self.on_move_wall.is_some() && grab_bounds.contains(point) {
+			return Some(Grabbed::Wall);
+		}
+		None
+	}
+
+	fn position_of(&self, grabbed: Grabbed) -> (f32, f32) {
+		match grabbed {
+			Grabbed::Emitter => self.emitter,
+			Grabbed::Wall => self.wall.unwrap_or_default(),
+		}
+	}
+
+	fn report_move(
+		&self,
+		bounds: Rectangle,
+		cursor_position: Point,
+		(grabbed, offset): (Grabbed, (f32, f32)),
+		messages: &mut Vec<Message>,
+	) {
+		let callback = match grabbed {
+			Grabbed::Emitter => &self.on_move_emitter,
+			Grabbed::Wall => &self.on_move_wall,
+		};
+		let (x, y) = self.position_at(bounds, cursor_position);
+		if let Some(callback) = callback {
+			messages.push(callback(
+				(x + offset.0).clamp(-self.radius, self.radius),
+				(y + offset.1).clamp(-self.radius, self.radius),
+			));
+		}
+	}
+
+	fn path_mesh(&self, bounds: Rectangle) -> Mesh2D {
+		let start = self.screen_point(bounds, (0.0, 0.0)) - Vector::new(bounds.x, bounds.y);
+		let end = self.screen_point(bounds, self.emitter) - Vector::new(bounds.x, bounds.y);
+		let direction = end - start;
+		let length = (direction.x * direction.x + direction.y * direction.y).sqrt();
+		if length == 0.0 {
+			return Mesh2D {
+				vertices: vec![],
+				indices: vec![],
+			};
+		}
+		let normal = Vector::new(
+			-direction.y / length * PATH_WIDTH / 2.0,
+			direction.x / length * PATH_WIDTH / 2.0,
+		);
+		let mut color = [0.0; 4];
+		for (i, channel) in color.iter_mut().enumerate() {
+			*channel = CLEAR_PATH_COLOR[i]
+				+ (BLOCKED_PATH_COLOR[i] - CLEAR_PATH_COLOR[i]) * self.occlusion;
+		}
+		Mesh2D {
+			vertices: [start + normal, start - normal, end - normal, end + normal]
+				.iter()
+				.map(|point| Vertex2D {
+					position: [point.x, point.y],
+					color,
+				})
+				.collect(),
+			indices: vec![0, 1, 2, 0, 2, 3],
+		}
+	}
+
+	fn circle(center: Point, radius: f32, color: [f32; 4]) -> Primitive {
+		Primitive::Quad {
+			bounds: Rectangle::new(
+				Point::new(center.x - radius, center.y - radius),
+				Size::new(radius * 2.0, radius * 2.0),
+			),
+			background: Background::Color(color.into()),
+			border_radius: radius,
+			border_width: 0.0,
+			border_color: Color::TRANSPARENT,
+		}
+	}
+}
+
+impl<'a, Message, B: Backend> Widget<Message, Renderer<B>> for OcclusionMap<'a, Message> {
+	fn width(&self) -> Length {
+		Length::Units(SIZE)
+	}
+
+	fn height(&self) -> Length {
+		Length::Units(SIZE)
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, limits: &Limits) -> Node {
+		Node::new(
+			limits
+				.width(Length::Units(SIZE))
+				.height(Length::Units(SIZE))
+				.resolve(Size::ZERO),
+		)
+	}
+
+	fn on_event(
+		&mut self,
+		event: Event,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		messages: &mut Vec<Message>,
+		_renderer: &Renderer<B>,
+		_clipboard: Option<&dyn Clipboard>,
+	) -> Status {
+		let bounds = layout.bounds();
+		match event {
+			// clicking an empty spot moves the emitter there, like
+			// the emitter pad
+			Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+				if bounds.contains(cursor_position) =>
+			{
+				let (x, y) = self.position_at(bounds, cursor_position);
+				let grabbed = match self.grabbable_at(bounds, cursor_position) {
+					Some(grabbed) => {
+						let (grabbed_x, grabbed_y) = self.position_of(grabbed);
+						(grabbed, (grabbed_x - x, grabbed_y - y))
+					}
+					None if self.on_move_emitter.is_some() => (Grabbed::Emitter, (0.0, 0.0)),
+					None => return Status::Ignored,
+				};
+				self.state.grabbed = Some(grabbed);
+				self.report_move(bounds, cursor_position, grabbed, messages);
+				Status::Captured
+			}
+			Event::Mouse(mouse::Event::CursorMoved { .. }) => match self.state.grabbed {
+				Some(grabbed) => {
+					self.report_move(bounds, cursor_position, grabbed, messages);
+					Status::Captured
+				}
+				None => Status::Ignored,
+			},
+			Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
+				if self.state.grabbed.is_some() =>
+			{
+				self.state.grabbed = None;
+				Status::Captured
+			}
+			_ => Status::Ignored,
+		}
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		_defaults: &Defaults,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let center = bounds.center();
+		let mut primitives = vec![
+			Primitive::Quad {
+				bounds,
+				background: Background::Color(BACKGROUND_COLOR.into()),
+				border_radius: 0.0,
+				border_width: 0.0,
+				border_color: Color::TRANSPARENT,
+			},
+			Primitive::Translate {
+				translation: Vector::new(bounds.x, bounds.y),
+				content: Box::new(Primitive::Mesh2D {
+					buffers: self.path_mesh(bounds),
+					size: bounds.size(),
+				}),
+			},
+		];
+		if let Some(wall_bounds) = self.wall_bounds(bounds) {
+			primitives.push(Primitive::Quad {
+				bounds: wall_bounds,
+				background: Background::Color(WALL_COLOR.into()),
+				border_radius: 2.0,
+				border_width: 0.0,
+				border_color: Color::TRANSPARENT,
+			});
+		}
+		primitives.push(Self::circle(center, DOT_RADIUS, LISTENER_COLOR));
+		primitives.push(Self::circle(
+			self.screen_point(bounds, self.emitter),
+			DOT_RADIUS,
+			EMITTER_COLOR,
+		));
+		let interaction = match (
+			self.state.grabbed,
+			self.grabbable_at(bounds, cursor_position),
+		) {
+			(Some(_), _) => Interaction::Grabbing,
+			(None, Some(_)) => Interaction::Grab,
+			(None, None) if self.on_move_emitter.is_some() && bounds.contains(cursor_position) => {
+				Interaction::Pointer
+			}
+			_ => Interaction::default(),
+		};
+		(Primitive::Group { primitives }, interaction)
+	}
+
+	fn hash_layout(&self, state: &mut iced_native::Hasher) {
+		use std::hash::Hash;
+		SIZE.hash(state)
+	}
+}
+
+impl<'a, Message: 'a, B: Backend + 'a> From<OcclusionMap<'a, Message>>
+	for Element<'a, Message, Renderer<B>>
+{
+	fn from(occlusion_map: OcclusionMap<'a, Message>) -> Self {
+		Element::new(occlusion_map)
+	}
+}
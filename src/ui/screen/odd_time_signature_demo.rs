@@ -0,0 +1,399 @@
+use std::error::Error;
+
+use iced::{Align, Button, Checkbox, Column, HorizontalAlignment, Length, Radio, Row, Text};
+use kira::{
+	group::GroupId,
+	instance::InstanceSettings,
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::ParameterId,
+	playable::PlayableSettings,
+	sequence::{Sequence, SequenceInstanceId, SequenceSettings},
+	sound::SoundId,
+	AudioResult, Duration, Event, MetronomeSettings, Tempo,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, synth},
+	preferences::BeatDisplayStyle,
+	ui::{
+		common::{beat_display::BeatDisplay, screen_wrapper::ScreenWrapper},
+		style::AppStyles,
+	},
+};
+
+const EXPLANATION_TEXT: &str = "Not all music comes in fours. \
+5/4 has five beats to the bar, felt as a group of three and a \
+group of two; 7/8 has seven quick eighth notes, felt as two, two \
+and three. The marked circles show where each group starts, and \
+the accented clicks and hats fall there too.
+
+Kira's metronome doesn't know about bars or time signatures - it \
+just counts beats at a tempo. So here a metronome \"beat\" is the \
+meter's pulse: a quarter note in 5/4 and an eighth note in 7/8, \
+with the tempo switched to match. The metronome is set up to \
+report every pulse and every whole bar of either meter (intervals \
+can't be changed once the audio manager exists, so both bar \
+lengths are listed), and the screen counts pulses and starts over \
+at each bar event. The loop itself is a sequence that waits a \
+pulse between hits, so it stays on the same grid.
+
+Switching meters while the music plays starts the loop over in \
+the new meter.";
+
+/// The metronome intervals the screen listens for (in pulses):
+/// every pulse, and a whole bar of each time signature.
+const METRONOME_INTERVALS: [f64; 3] = [1.0, 5.0, 7.0];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeSignature {
+	FiveFour,
+	SevenEight,
+}
+
+impl TimeSignature {
+	const ALL: [Self; 2] = [Self::FiveFour, Self::SevenEight];
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::FiveFour => "5/4",
+			Self::SevenEight => "7/8",
+		}
+	}
+
+	/// How many pulses the metronome plays each minute.
+	fn tempo(self) -> Tempo {
+		match self {
+			Self::FiveFour => Tempo(150.0),
+			Self::SevenEight => Tempo(260.0),
+		}
+	}
+
+	fn pulses_per_bar(self) -> usize {
+		self.groups().iter().sum()
+	}
+
+	/// How the pulses of a bar are grouped.
+	fn groups(self) -> &'static [usize] {
+		match self {
+			Self::FiveFour => &[3, 2],
+			Self::SevenEight => &[2, 2, 3],
+		}
+	}
+
+	/// The pulses each group starts on (counting from 0).
+	fn group_starts(self) -> Vec<usize> {
+		self.groups()
+			.iter()
+			.scan(0, |start, size| {
+				let group_start = *start;
+				*start += size;
+				Some(group_start)
+			})
+			.collect()
+	}
+
+	/// The pulses the kick plays on (counting from 0).
+	fn kicks(self) -> &'static [usize] {
+		match self {
+			Self::FiveFour => &[0, 2],
+			Self::SevenEight => &[0, 4],
+		}
+	}
+
+	/// The pulses the snare plays on (counting from 0).
+	fn snares(self) -> &'static [usize] {
+		match self {
+			Self::FiveFour => &[3],
+			Self::SevenEight => &[2, 5],
+		}
+	}
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetTimeSignature(TimeSignature),
+	SetClick(bool),
+}
+
+pub struct OddTimeSignatureDemo {
+	audio_manager: AudioManager,
+	group_id: GroupId,
+	kick_sound_id: SoundId,
+	snare_sound_id: SoundId,
+	hi_hat_sound_id: SoundId,
+	click_sound_id: SoundId,
+	click_volume_parameter_id: ParameterId,
+	time_signature: TimeSignature,
+	click_enabled: bool,
+	sequence_id: Option<SequenceInstanceId>,
+	/// The pulse of the bar that's playing, as counted from the
+	/// metronome's events.
+	pulse: Option<usize>,
+	bars_passed: usize,
+	beat_display_style: BeatDisplayStyle,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_button: iced::button::State,
+}
+
+impl OddTimeSignatureDemo {
+	pub fn new(
+		audio_manager_settings: AudioManagerSettings,
+		beat_display_style: BeatDisplayStyle,
+	) -> Result<Self, Box<dyn Error>> {
+		let time_signature = TimeSignature::FiveFour;
+		let mut audio_manager = create_audio_manager(AudioManagerSettings {
+			metronome_settings: MetronomeSettings {
+				tempo: time_signature.tempo().into(),
+				interval_events_to_emit: METRONOME_INTERVALS.to_vec(),
+			},
+			..audio_manager_settings
+		})?;
+		let group_id = audio_manager.add_group([])?;
+		let settings = || PlayableSettings::new().groups([group_id]);
+		let kick_sound_id = audio_manager.add_sound(synth::kick(50.0, 0.4, settings()))?;
+		let snare_sound_id = audio_manager.add_sound(synth::snare(180.0, 0.2, settings()))?;
+		let hi_hat_sound_id = audio_manager.add_sound(synth::noise_hit(0.05, 1.0, settings()))?;
+		let click_sound_id = audio_manager.add_sound(synth::blip(1760.0, 0.05, settings()))?;
+		let click_volume_parameter_id = audio_manager.add_parameter(0.0)?;
+		Ok(Self {
+			audio_manager,
+			group_id,
+			kick_sound_id,
+			snare_sound_id,
+			hi_hat_sound_id,
+			click_sound_id,
+			click_volume_parameter_id,
+			time_signature,
+			click_enabled: false,
+			sequence_id: None,
+			pulse: None,
+			bars_passed: 0,
+			beat_display_style,
+			screen_wrapper: ScreenWrapper::new(
+				"Odd time signature demo".into(),
+				Message::GoToDemoSelect,
+			),
+			play_button: iced::button::State::new(),
+		})
+	}
+
+	/// The tempo of the metronome, in pulses per minute.
+	pub fn tempo(&self) -> Tempo {
+		self.time_signature.tempo()
+	}
+
+	fn start(&mut self) -> AudioResult<()> {
+		let time_signature = self.time_signature;
+		let group_starts = time_signature.group_starts();
+		// the sequence counts its waits with the metronome's tempo,
+		// so that has to be switched before it starts
+		self.audio_manager
+			.set_metronome_tempo(time_signature.tempo())?;
+		let mut sequence = Sequence::<()>::new(SequenceSettings::new().groups([self.group_id]));
+		sequence.wait_for_interval(1.0);
+		sequence.start_loop();
+		for pulse in 0..time_signature.pulses_per_bar() {
+			let accent = group_starts.contains(&pulse);
+			if time_signature.kicks().contains(&pulse) {
+				sequence.play(self.kick_sound_id, InstanceSettings::new().volume(0.8));
+			}
+			if time_signature.snares().contains(&pulse) {
+				sequence.play(self.snare_sound_id, InstanceSettings::new().volume(0.6));
+			}
+			sequence.play(
+				self.hi_hat_sound_id,
+				InstanceSettings::new().volume(if accent { 0.6 } else { 0.3 }),
+			);
+			sequence.play(
+				self.click_sound_id,
+				InstanceSettings::new()
+					.volume(self.click_volume_parameter_id)
+					.pitch(if accent { 1.0 } else { 0.5 }),
+			);
+			sequence.wait(Duration::Beats(1.0));
+		}
+		let (sequence_id, _) = self
+			.audio_manager
+			.start_sequence(sequence, Default::default())?;
+		self.sequence_id = Some(sequence_id);
+		self.pulse = None;
+		self.bars_passed = 0;
+		self.audio_manager.start_metronome()
+	}
+
+	fn stop(&mut self) -> AudioResult<()> {
+		self.audio_manager
+			.stop_group(self.group_id, Default::default())?;
+		self.audio_manager.stop_metronome()?;
+		self.sequence_id = None;
+		self.pulse = None;
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if self.sequence_id.is_none() => self.start()?,
+			Message::Stop => self.stop()?,
+			Message::SetTimeSignature(time_signature) if time_signature != self.time_signature => {
+				let playing = self.sequence_id.is_some();
+				self.stop()?;
+				self.time_signature = time_signature;
+				if playing {
+					self.start()?;
+				}
+			}
+			Message::SetClick(enabled) => {
+				self.click_enabled = enabled;
+				self.audio_manager.set_parameter(
+					self.click_volume_parameter_id,
+					if enabled { 0.5 } else { 0.0 },
+					None,
+				)?;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		let bar_length = self.time_signature.pulses_per_bar() as f64;
+		while let Some(event) = self.audio_manager.pop_event() {
+			match event {
+				Event::MetronomeIntervalPassed(interval) => {
+					if self.sequence_id.is_none() {
+						continue;
+					}
+					// a pulse's event comes before the bar's when
+					// they land together, so the bar resets the
+					// count after the pulse has added to it
+					if interval == 1.0 {
+						self.pulse = Some(self.pulse.map_or(0, |pulse| pulse + 1));
+					} else if interval == bar_length {
+						self.pulse = Some(0);
+						self.bars_passed += 1;
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+
+	fn position_text(&self) -> String {
+		match self.pulse {
+			Some(pulse) => format!(
+				"Bar {}, pulse {} of {}",
+				self.bars_passed,
+				pulse + 1,
+				self.time_signature.pulses_per_bar()
+			),
+			None => "-".into(),
+		}
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.sequence_id.is_some();
+		let time_signature = self.time_signature;
+		let time_signature_radios =
+			TimeSignature::ALL
+				.iter()
+				.fold(Row::new().spacing(16), |row, option| {
+					row.push(Radio::new(
+						*option,
+						option.name(),
+						Some(time_signature),
+						Message::SetTimeSignature,
+					))
+				});
+		let groups = time_signature
+			.groups()
+			.iter()
+			.map(|size| size.to_string())
+			.collect::<Vec<_>>()
+			.join(" + ");
+		let position_text = self.position_text();
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Button::new(
+								&mut self.play_button,
+								Text::new(if playing { "Stop" } else { "Play" })
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(if playing {
+								Message::Stop
+							} else {
+								Message::Play
+							})
+							.width(Length::Units(100))
+							.style(AppStyles),
+						)
+						.push(time_signature_radios)
+						.push(Checkbox::new(
+							self.click_enabled,
+							"Click",
+							Message::SetClick,
+						)),
+				)
+				.push(BeatDisplay {
+					beats_per_bar: time_signature.pulses_per_bar(),
+					current: self.pulse,
+					marked: time_signature.group_starts(),
+					style: self.beat_display_style,
+				})
+				.push(Text::new(position_text))
+				.push(Text::new(format!(
+					"Grouped {} at {} pulses per minute",
+					groups,
+					time_signature.tempo().0
+				)))
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for OddTimeSignatureDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "odd_time_signature_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetTimeSignature(self.time_signature),
+			Message::SetClick(self.click_enabled),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for OddTimeSignatureDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.sequence_id.is_some(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		self.sequence_id
+			.map(|_| format!("Odd time signature demo - {}", self.time_signature.name()))
+	}
+}
@@ -0,0 +1,320 @@
+use std::error::Error;
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Row, Slider, Text};
+use kira::{
+	instance::{InstanceId, InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::{ParameterId, Tween},
+	playable::PlayableSettings,
+	sequence::Sequence,
+	sound::SoundId,
+	Duration,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, sound_cache::SoundCache, synth},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &["drum fill demo/loop.ogg"];
+
+const EXPLANATION_TEXT: &str = "Panning places a sound between \
+the left speaker (0) and the right one (1), with 0.5 in the \
+middle. Every instance has its own panning, so copies of the \
+same sound can play from different places at once.
+
+The buttons play copies of a blip with a fixed panning set when \
+each one starts, and the sweep plays all five in a row from a \
+sequence. The drum loop's panning is bound to a parameter \
+instead, so the slider can move it while it plays. Each move \
+tweens the parameter over the pan time, which keeps the loop \
+from jumping from one side to the other.";
+
+/// Where the blip buttons place their copies, from left to right.
+const PAN_POSITIONS: [(&str, f64); 5] = [
+	("Hard left", 0.0),
+	("Left", 0.25),
+	("Center", 0.5),
+	("Right", 0.75),
+	("Hard right", 1.0),
+];
+/// How far apart the blips in a sweep are (in seconds).
+const SWEEP_INTERVAL: f64 = 0.15;
+const MAX_PAN_TIME: f64 = 2.0;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	/// Plays a blip at one of the pan positions.
+	PlayBlip(usize),
+	Sweep,
+	Play,
+	Stop,
+	SetPanning(f64),
+	/// Sets how long the loop takes to move to a new panning
+	/// (in seconds).
+	SetPanTime(f64),
+}
+
+pub struct PanningDemo {
+	audio_manager: AudioManager,
+	blip_sound_id: SoundId,
+	loop_sound_id: SoundId,
+	loop_instance_id: Option<InstanceId>,
+	panning_parameter_id: ParameterId,
+	panning: f64,
+	pan_time: f64,
+	screen_wrapper: ScreenWrapper<Message>,
+	blip_buttons: [iced::button::State; 5],
+	sweep_button: iced::button::State,
+	play_button: iced::button::State,
+	panning_slider: iced::slider::State,
+	pan_time_slider: iced::slider::State,
+}
+
+impl PanningDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let blip_sound_id = audio_manager.add_sound(synth::blip(
+			880.0,
+			0.2,
+			// mashing the buttons can start two copies at the same
+			// time, which a cooldown would block
+			PlayableSettings {
+				cooldown: None,
+				..Default::default()
+			},
+		))?;
+		let loop_sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&std::env::current_dir()?.join("assets/drum fill demo/loop.ogg"))?
+				.to_sound(PlayableSettings::new().default_loop_start(0.0)),
+		)?;
+		let panning = 0.5;
+		let panning_parameter_id = audio_manager.add_parameter(panning)?;
+		Ok(Self {
+			audio_manager,
+			blip_sound_id,
+			loop_sound_id,
+			loop_instance_id: None,
+			panning_parameter_id,
+			panning,
+			pan_time: 0.3,
+			screen_wrapper: ScreenWrapper::new("Panning demo".into(), Message::GoToDemoSelect),
+			blip_buttons: Default::default(),
+			sweep_button: iced::button::State::new(),
+			play_button: iced::button::State::new(),
+			panning_slider: iced::slider::State::new(),
+			pan_time_slider: iced::slider::State::new(),
+		})
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::PlayBlip(index) => {
+				if let Some((_, panning)) = PAN_POSITIONS.get(index) {
+					self.audio_manager.play(
+						self.blip_sound_id,
+						InstanceSettings::new().panning(*panning),
+					)?;
+				}
+			}
+			Message::Sweep => {
+				let mut sequence = Sequence::<()>::new(Default::default());
+				for (_, panning) in &PAN_POSITIONS {
+					sequence.play(
+						self.blip_sound_id,
+						InstanceSettings::new().panning(*panning),
+					);
+					sequence.wait(Duration::Seconds(SWEEP_INTERVAL));
+				}
+				self.audio_manager
+					.start_sequence(sequence, Default::default())?;
+			}
+			Message::Play if self.loop_instance_id.is_none() => {
+				self.loop_instance_id = Some(self.audio_manager.play(
+					self.loop_sound_id,
+					InstanceSettings::new().panning(self.panning_parameter_id),
+				)?);
+			}
+			Message::Stop => {
+				if let Some(instance_id) = self.loop_instance_id.take() {
+					self.audio_manager.stop_instance(
+						instance_id,
+						StopInstanceSettings::new().fade_tween(Tween::linear(0.25)),
+					)?;
+				}
+			}
+			Message::SetPanning(panning) => {
+				self.panning = panning.clamp(0.0, 1.0);
+				let tween = if self.pan_time > 0.0 {
+					Some(Tween::linear(self.pan_time))
+				} else {
+					None
+				};
+				self.audio_manager
+					.set_parameter(self.panning_parameter_id, self.panning, tween)?;
+			}
+			Message::SetPanTime(pan_time) => self.pan_time = pan_time.clamp(0.0, MAX_PAN_TIME),
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.loop_instance_id.is_some();
+		let blip_buttons = PAN_POSITIONS
+			.iter()
+			.zip(self.blip_buttons.iter_mut())
+			.enumerate()
+			.fold(Row::new().spacing(8), |row, (index, ((name, _), state))| {
+				row.push(
+					Button::new(
+						state,
+						Text::new(*name)
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(Message::PlayBlip(index))
+					.width(Length::Units(100))
+					.style(AppStyles),
+				)
+			});
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(blip_buttons)
+				.push(
+					Button::new(
+						&mut self.sweep_button,
+						Text::new("Sweep")
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(Message::Sweep)
+					.width(Length::Units(100))
+					.style(AppStyles),
+				)
+				.push(
+					Button::new(
+						&mut self.play_button,
+						Text::new(if playing { "Stop loop" } else { "Play loop" })
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(if playing {
+						Message::Stop
+					} else {
+						Message::Play
+					})
+					.width(Length::Units(120))
+					.style(AppStyles),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Loop panning: {:.2}", self.panning))
+								.width(Length::Units(150)),
+						)
+						.push(
+							Slider::new(
+								&mut self.panning_slider,
+								0.0..=1.0,
+								self.panning,
+								Message::SetPanning,
+							)
+							.step(0.01)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Pan time: {:.2}s", self.pan_time))
+								.width(Length::Units(150)),
+						)
+						.push(
+							Slider::new(
+								&mut self.pan_time_slider,
+								0.0..=MAX_PAN_TIME,
+								self.pan_time,
+								Message::SetPanTime,
+							)
+							.step(0.05)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for PanningDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "panning_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetPanning(_) => Some("Panning".into()),
+			Message::SetPanTime(_) => Some("Pan time".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Panning" => vec![Message::SetPanning(value)],
+			"Pan time" => vec![Message::SetPanTime(MAX_PAN_TIME * value)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for PanningDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "panning_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetPanTime(self.pan_time),
+			Message::SetPanning(self.panning),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for PanningDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.loop_instance_id.is_some(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		self.loop_instance_id.map(|_| "Panning demo".into())
+	}
+}
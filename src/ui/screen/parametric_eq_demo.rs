@@ -0,0 +1,552 @@
+mod response_curve;
+
+use std::{error::Error, f64::consts::PI};
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Row, Slider, Text};
+use kira::{
+	instance::{InstanceId, InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	mixer::{
+		effect::filter::{Filter, FilterMode, FilterSettings},
+		SubTrackId,
+	},
+	parameter::{ParameterId, Tween},
+	playable::PlayableSettings,
+	sound::SoundId,
+};
+use serde::{Deserialize, Serialize};
+
+use self::response_curve::{Handle, ResponseCurve};
+use crate::{
+	audio::{create_audio_manager, sound_cache::SoundCache},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &["drum fill demo/loop.ogg"];
+
+const EXPLANATION_TEXT: &str = "Each band boosts or cuts part of \
+the spectrum: a low shelf raises or lowers everything below its \
+frequency, a high shelf everything above it, and the peak a \
+range around its frequency. Drag the dots on the curve or use \
+the sliders.
+
+Kira's filter only comes in low-pass, high-pass, band-pass and \
+notch modes, but all four come out of the same filter design, \
+and the low-pass, band-pass and high-pass outputs (with the \
+band-pass scaled to peak at 1) add back up to the original \
+sound. So a band that changes the volume of one of those outputs \
+bends the spectrum just there. The loop plays on four tracks at \
+once: one untouched, and one each through a low-pass, band-pass \
+and high-pass filter. The filtered copies' volumes are tied to \
+parameters set to how much to add or take away - negative for a \
+cut, which subtracts that part of the sound from the untouched \
+copy. The curve is worked out from the same formulas.";
+
+const MIN_FREQUENCY: f64 = 20.0;
+const MAX_FREQUENCY: f64 = 20000.0;
+const MAX_GAIN: f64 = 15.0;
+const NUM_PLOT_POINTS: usize = 200;
+/// How far above and below 0 dB the curve shows.
+const PLOT_RANGE: f64 = 20.0;
+/// The sample rate the curve assumes, which only makes a
+/// difference near the top of the range.
+const PLOT_SAMPLE_RATE: f64 = 48000.0;
+const PARAMETER_TWEEN_TIME: f64 = 0.05;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Band {
+	LowShelf,
+	Peak,
+	HighShelf,
+}
+
+impl Band {
+	const ALL: [Self; 3] = [Self::LowShelf, Self::Peak, Self::HighShelf];
+
+	fn index(self) -> usize {
+		Self::ALL.iter().position(|band| *band == self).unwrap()
+	}
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::LowShelf => "Low shelf",
+			Self::Peak => "Peak",
+			Self::HighShelf => "High shelf",
+		}
+	}
+
+	fn color(self) -> [f32; 4] {
+		match self {
+			Self::LowShelf => [0.2, 0.5, 1.0, 1.0],
+			Self::Peak => [0.3, 0.8, 0.3, 1.0],
+			Self::HighShelf => [1.0, 0.6, 0.0, 1.0],
+		}
+	}
+
+	fn filter_mode(self) -> FilterMode {
+		match self {
+			Self::LowShelf => FilterMode::LowPass,
+			Self::Peak => FilterMode::BandPass,
+			Self::HighShelf => FilterMode::HighPass,
+		}
+	}
+
+	/// The filter's damping, which kira works out from the
+	/// resonance as `2 - 1.9 * resonance`. The shelves use
+	/// the flattest filter that doesn't droop, and the peak is
+	/// about an octave and a half wide.
+	fn damping(self) -> f64 {
+		match self {
+			Self::LowShelf | Self::HighShelf => 2.0f64.sqrt(),
+			Self::Peak => 1.0,
+		}
+	}
+
+	fn resonance(self) -> f64 {
+		(2.0 - self.damping()) / 1.9
+	}
+
+	/// How much of the filter's output to add for a gain (in
+	/// decibels). The band-pass output peaks at one over the
+	/// damping, so it's scaled back to 1 first.
+	fn volume(self, gain: f64) -> f64 {
+		let amplitude = 10.0f64.powf(gain / 20.0);
+		match self {
+			Self::LowShelf | Self::HighShelf => amplitude - 1.0,
+			Self::Peak => (amplitude - 1.0) * self.damping(),
+		}
+	}
+
+	/// The response of this band's filter at a frequency, as a
+	/// complex number (real, imaginary), from the same formulas
+	/// kira's filter uses.
+	fn filter_response(self, cutoff: f64, frequency: f64) -> (f64, f64) {
+		let k = self.damping();
+		// the filter is tuned with a tangent, which squeezes
+		// frequencies near the top of the range together
+		let w = (PI * frequency / PLOT_SAMPLE_RATE).tan() / (PI * cutoff / PLOT_SAMPLE_RATE).tan();
+		// the response at s = jw is numerator / (s^2 + ks + 1)
+		let (numerator_re, numerator_im) = match self {
+			Self::LowShelf => (1.0, 0.0),
+			Self::Peak => (0.0, w),
+			Self::HighShelf => (-w * w, 0.0),
+		};
+		let (denominator_re, denominator_im) = (1.0 - w * w, k * w);
+		let denominator = denominator_re * denominator_re + denominator_im * denominator_im;
+		(
+			(numerator_re * denominator_re + numerator_im * denominator_im) / denominator,
+			(numerator_im * denominator_re - numerator_re * denominator_im) / denominator,
+		)
+	}
+
+	fn default_settings(self) -> BandSettings {
+		match self {
+			Self::LowShelf => BandSettings {
+				frequency_position: frequency_position(120.0),
+				gain: 0.0,
+			},
+			Self::Peak => BandSettings {
+				frequency_position: frequency_position(1000.0),
+				gain: 0.0,
+			},
+			Self::HighShelf => BandSettings {
+				frequency_position: frequency_position(6000.0),
+				gain: 0.0,
+			},
+		}
+	}
+}
+
+/// Converts a position (from 0 to 1, spread evenly over the
+/// octaves from 20 Hz to 20 kHz) to a frequency (in Hz).
+fn frequency(position: f64) -> f64 {
+	MIN_FREQUENCY * (MAX_FREQUENCY / MIN_FREQUENCY).powf(position)
+}
+
+fn frequency_position(frequency: f64) -> f64 {
+	(frequency / MIN_FREQUENCY).ln() / (MAX_FREQUENCY / MIN_FREQUENCY).ln()
+}
+
+#[derive(Debug, Copy, Clone)]
+struct BandSettings {
+	frequency_position: f64,
+	/// The boost or cut (in decibels).
+	gain: f64,
+}
+
+/// The tracks and parameters behind one band.
+struct BandControls {
+	track_id: SubTrackId,
+	cutoff_parameter_id: ParameterId,
+	volume_parameter_id: ParameterId,
+	frequency_slider: iced::slider::State,
+	gain_slider: iced::slider::State,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	/// Sets a band's frequency (from 0 to 1, spread evenly over
+	/// the octaves from 20 Hz to 20 kHz).
+	SetFrequency(Band, f64),
+	/// Sets a band's boost or cut (in decibels).
+	SetGain(Band, f64),
+	/// Sent when a band's handle is dragged, with its new
+	/// frequency and gain.
+	MoveBand(Band, f64, f64),
+	Reset,
+}
+
+pub struct ParametricEqDemo {
+	audio_manager: AudioManager,
+	loop_sound_id: SoundId,
+	bands: [BandSettings; 3],
+	band_controls: Vec<BandControls>,
+	instance_ids: Vec<InstanceId>,
+	screen_wrapper: ScreenWrapper<Message>,
+	response_curve: response_curve::State,
+	play_button: iced::button::State,
+	reset_button: iced::button::State,
+}
+
+impl ParametricEqDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let bands = [
+			Band::LowShelf.default_settings(),
+			Band::Peak.default_settings(),
+			Band::HighShelf.default_settings(),
+		];
+		let mut band_controls = vec![];
+		for (band, settings) in Band::ALL.iter().zip(bands.iter()) {
+			let cutoff_parameter_id =
+				audio_manager.add_parameter(frequency(settings.frequency_position))?;
+			let volume_parameter_id = audio_manager.add_parameter(band.volume(settings.gain))?;
+			let track_id = audio_manager.add_sub_track(Default::default())?;
+			audio_manager.add_effect_to_track(
+				track_id,
+				Filter::new(
+					FilterSettings::new()
+						.mode(band.filter_mode())
+						.cutoff(cutoff_parameter_id)
+						.resonance(band.resonance()),
+				),
+				Default::default(),
+			)?;
+			band_controls.push(BandControls {
+				track_id,
+				cutoff_parameter_id,
+				volume_parameter_id,
+				frequency_slider: iced::slider::State::new(),
+				gain_slider: iced::slider::State::new(),
+			});
+		}
+		// the loop is played on every track at the same time,
+		// which a cooldown would block
+		let loop_sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&std::env::current_dir()?.join("assets").join(ASSETS[0]))?
+				.to_sound(PlayableSettings {
+					cooldown: None,
+					..PlayableSettings::new().default_loop_start(0.0)
+				}),
+		)?;
+		Ok(Self {
+			audio_manager,
+			loop_sound_id,
+			bands,
+			band_controls,
+			instance_ids: vec![],
+			screen_wrapper: ScreenWrapper::new(
+				"Parametric EQ demo".into(),
+				Message::GoToDemoSelect,
+			),
+			response_curve: Default::default(),
+			play_button: iced::button::State::new(),
+			reset_button: iced::button::State::new(),
+		})
+	}
+
+	/// How much the EQ boosts or cuts a frequency (in decibels):
+	/// the untouched copy plus each band's filtered copy at its
+	/// volume, added up with their phases.
+	fn response(&self, frequency_hz: f64) -> f64 {
+		let (mut re, mut im) = (1.0, 0.0);
+		for (band, settings) in Band::ALL.iter().zip(self.bands.iter()) {
+			let volume = band.volume(settings.gain);
+			let (band_re, band_im) =
+				band.filter_response(frequency(settings.frequency_position), frequency_hz);
+			re += volume * band_re;
+			im += volume * band_im;
+		}
+		20.0 * (re * re + im * im).sqrt().max(1e-6).log10()
+	}
+
+	fn plot_points(&self) -> Vec<f32> {
+		(0..NUM_PLOT_POINTS)
+			.map(|i| {
+				let response = self.response(frequency(i as f64 / (NUM_PLOT_POINTS - 1) as f64));
+				(0.5 + response / PLOT_RANGE / 2.0).clamp(0.0, 1.0) as f32
+			})
+			.collect()
+	}
+
+	fn set_frequency(&mut self, band: Band, position: f64) -> Result<(), Box<dyn Error>> {
+		let position = position.clamp(0.0, 1.0);
+		self.bands[band.index()].frequency_position = position;
+		self.audio_manager.set_parameter(
+			self.band_controls[band.index()].cutoff_parameter_id,
+			frequency(position),
+			Some(Tween::linear(PARAMETER_TWEEN_TIME)),
+		)?;
+		Ok(())
+	}
+
+	fn set_gain(&mut self, band: Band, gain: f64) -> Result<(), Box<dyn Error>> {
+		let gain = gain.clamp(-MAX_GAIN, MAX_GAIN);
+		self.bands[band.index()].gain = gain;
+		self.audio_manager.set_parameter(
+			self.band_controls[band.index()].volume_parameter_id,
+			band.volume(gain),
+			Some(Tween::linear(PARAMETER_TWEEN_TIME)),
+		)?;
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			// the commands are all sent at once, so the instances
+			// start on the same sample, which the cuts rely on
+			Message::Play if self.instance_ids.is_empty() => {
+				self.instance_ids.push(
+					self.audio_manager
+						.play(self.loop_sound_id, Default::default())?,
+				);
+				for controls in &self.band_controls {
+					self.instance_ids.push(
+						self.audio_manager.play(
+							self.loop_sound_id,
+							InstanceSettings::new()
+								.track(controls.track_id)
+								.volume(controls.volume_parameter_id),
+						)?,
+					);
+				}
+			}
+			Message::Stop => {
+				for instance_id in self.instance_ids.drain(..) {
+					self.audio_manager.stop_instance(
+						instance_id,
+						StopInstanceSettings::new().fade_tween(Tween::linear(0.25)),
+					)?;
+				}
+			}
+			Message::SetFrequency(band, position) => self.set_frequency(band, position)?,
+			Message::SetGain(band, gain) => self.set_gain(band, gain)?,
+			Message::MoveBand(band, position, gain) => {
+				self.set_frequency(band, position)?;
+				self.set_gain(band, gain)?;
+			}
+			Message::Reset => {
+				for band in Band::ALL.iter().copied() {
+					let settings = band.default_settings();
+					self.set_frequency(band, settings.frequency_position)?;
+					self.set_gain(band, settings.gain)?;
+				}
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = !self.instance_ids.is_empty();
+		let plot_points = self.plot_points();
+		let handles = Band::ALL
+			.iter()
+			.zip(self.bands.iter())
+			.map(|(band, settings)| Handle {
+				x: settings.frequency_position as f32,
+				y: (0.5 + settings.gain / PLOT_RANGE / 2.0) as f32,
+				color: band.color(),
+			})
+			.collect();
+		let curve = ResponseCurve::new(&mut self.response_curve, plot_points, handles).on_drag(
+			|index, x, y| {
+				Message::MoveBand(
+					Band::ALL[index],
+					x as f64,
+					(y as f64 * 2.0 - 1.0) * PLOT_RANGE,
+				)
+			},
+		);
+		let band_rows = Band::ALL
+			.iter()
+			.zip(self.bands.iter())
+			.zip(self.band_controls.iter_mut())
+			.fold(
+				Column::new().spacing(8),
+				|column, ((band, settings), controls)| {
+					let band = *band;
+					column.push(
+						Row::new()
+							.spacing(16)
+							.align_items(Align::Center)
+							.push(Text::new(band.name()).width(Length::Units(100)))
+							.push(
+								Text::new(format!(
+									"{:.0} Hz",
+									frequency(settings.frequency_position)
+								))
+								.width(Length::Units(80)),
+							)
+							.push(
+								Slider::new(
+									&mut controls.frequency_slider,
+									0.0..=1.0,
+									settings.frequency_position,
+									move |position| Message::SetFrequency(band, position),
+								)
+								.step(0.001)
+								.width(Length::Units(180))
+								.style(AppStyles),
+							)
+							.push(
+								Text::new(format!("{:+.1} dB", settings.gain))
+									.width(Length::Units(70)),
+							)
+							.push(
+								Slider::new(
+									&mut controls.gain_slider,
+									-MAX_GAIN..=MAX_GAIN,
+									settings.gain,
+									move |gain| Message::SetGain(band, gain),
+								)
+								.step(0.1)
+								.width(Length::Units(180))
+								.style(AppStyles),
+							),
+					)
+				},
+			);
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Row::new()
+						.spacing(16)
+						.push(
+							Button::new(
+								&mut self.play_button,
+								Text::new(if playing { "Stop" } else { "Play" })
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(if playing {
+								Message::Stop
+							} else {
+								Message::Play
+							})
+							.width(Length::Units(100))
+							.style(AppStyles),
+						)
+						.push(
+							Button::new(
+								&mut self.reset_button,
+								Text::new("Reset")
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(Message::Reset)
+							.width(Length::Units(100))
+							.style(AppStyles),
+						),
+				)
+				.push(curve)
+				.push(band_rows)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for ParametricEqDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "parametric_eq_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetFrequency(band, _) => Some(format!("{} frequency", band.name())),
+			Message::SetGain(band, _) => Some(format!("{} gain", band.name())),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		Band::ALL
+			.iter()
+			.copied()
+			.filter_map(|band| {
+				if control == format!("{} frequency", band.name()) {
+					Some(Message::SetFrequency(band, value))
+				} else if control == format!("{} gain", band.name()) {
+					Some(Message::SetGain(band, (value * 2.0 - 1.0) * MAX_GAIN))
+				} else {
+					None
+				}
+			})
+			.collect()
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for ParametricEqDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "parametric_eq_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		Band::ALL
+			.iter()
+			.zip(self.bands.iter())
+			.flat_map(|(band, settings)| {
+				vec![
+					Message::SetFrequency(*band, settings.frequency_position),
+					Message::SetGain(*band, settings.gain),
+				]
+			})
+			.collect()
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for ParametricEqDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			!self.instance_ids.is_empty(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if self.instance_ids.is_empty() {
+			return None;
+		}
+		Some("Parametric EQ demo".into())
+	}
+}
@@ -0,0 +1,273 @@
+use iced::{mouse::Interaction, Background, Color, Length, Point, Rectangle, Size, Vector};
+use iced_graphics::{
+	triangle::{Mesh2D, Vertex2D},
+	Backend, Defaults, Primitive, Renderer,
+};
+use iced_native::{
+	event::Status,
+	layout::{Limits, Node},
+	mouse, Clipboard, Element, Event, Layout, Widget,
+};
+
+const WIDTH: u16 = 500;
+const HEIGHT: u16 = 200;
+const BACKGROUND_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.05];
+const ZERO_LINE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.2];
+const LINE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.75];
+const LINE_WIDTH: f32 = 2.0;
+const HANDLE_RADIUS: f32 = 6.0;
+/// How close the cursor has to be to a handle to grab it.
+const GRAB_RADIUS: f32 = 12.0;
+// keeps the handles from being cut off by the edges
+const PADDING: f32 = HANDLE_RADIUS;
+
+/// Which handle is being dragged. This has to outlive the
+/// widget, which is rebuilt every time the screen is drawn.
+#[derive(Debug, Default)]
+pub struct State {
+	dragging: Option<usize>,
+}
+
+/// Makes the message for a handle being dragged, from the
+/// handle's index and its new position.
+type OnDrag<'a, Message> = Box<dyn Fn(usize, f32, f32) -> Message + 'a>;
+
+/// A handle on the curve, positioned from 0 to 1 across and up
+/// the plot.
+pub struct Handle {
+	pub x: f32,
+	pub y: f32,
+	pub color: [f32; 4],
+}
+
+/// Plots an EQ's response from evenly spaced samples (from 0 at
+/// the bottom to 1 at the top, with 0.5 as no change) and draws
+/// a handle for each band that can be dragged to move the band.
+pub struct ResponseCurve<'a, Message> {
+	state: &'a mut State,
+	points: Vec<f32>,
+	handles: Vec<Handle>,
+	on_drag: Option<OnDrag<'a, Message>>,
+}
+
+impl<'a, Message> ResponseCurve<'a, Message> {
+	pub fn new(state: &'a mut State, points: Vec<f32>, handles: Vec<Handle>) -> Self {
+		Self {
+			state,
+			points,
+			handles,
+			on_drag: None,
+		}
+	}
+
+	/// Reports a handle's index and new position while it's
+	/// dragged.
+	pub fn on_drag(self, on_drag: impl Fn(usize, f32, f32) -> Message + 'a) -> Self {
+		Self {
+			on_drag: Some(Box::new(on_drag)),
+			..self
+		}
+	}
+
+	fn screen_point(bounds: Rectangle, x: f32, y: f32) -> Point {
+		Point::new(
+			bounds.x + PADDING + (bounds.width - PADDING * 2.0) * x,
+			bounds.y + PADDING + (bounds.height - PADDING * 2.0) * (1.0 - y),
+		)
+	}
+
+	/// Converts a point on the screen to a position on the plot,
+	/// keeping it on the plot even if the cursor has left it.
+	fn position_at(bounds: Rectangle, point: Point) -> (f32, f32) {
+		(
+			((point.x - bounds.x - PADDING) / (bounds.width - PADDING * 2.0)).clamp(0.0, 1.0),
+			(1.0 - (point.y - bounds.y - PADDING) / (bounds.height - PADDING * 2.0))
+				.clamp(0.0, 1.0),
+		)
+	}
+
+	fn handle_at(&self, bounds: Rectangle, point: Point) -> Option<usize> {
+		self.handles
+			.iter()
+			.enumerate()
+			.map(|(i, handle)| {
+				let distance = Self::screen_point(bounds, handle.x, handle.y).distance(point);
+				(i, distance)
+			})
+			.filter(|(_, distance)| *distance <= GRAB_RADIUS)
+			.min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+			.map(|(i, _)| i)
+	}
+
+	fn line_mesh(&self, bounds: Rectangle) -> Mesh2D {
+		let mut vertices = vec![];
+		let mut indices = vec![];
+		let last = self.points.len().saturating_sub(1).max(1) as f32;
+		// the mesh is drawn relative to the widget's corner
+		let origin = Vector::new(bounds.x, bounds.y);
+		let point = |i: usize| {
+			Self::screen_point(bounds, i as f32 / last, self.points[i].clamp(0.0, 1.0)) - origin
+		};
+		for i in 1..self.points.len() {
+			let start = point(i - 1);
+			let end = point(i);
+			let direction = end - start;
+			let length = (direction.x * direction.x + direction.y * direction.y).sqrt();
+			if length == 0.0 {
+				continue;
+			}
+			let normal = Vector::new(
+				-direction.y / length * LINE_WIDTH / 2.0,
+				direction.x / length * LINE_WIDTH / 2.0,
+			);
+			let first_index = vertices.len() as u32;
+			for point in &[start + normal, start - normal, end - normal, end + normal] {
+				vertices.push(Vertex2D {
+					position: [point.x, point.y],
+					color: LINE_COLOR,
+				});
+			}
+			indices.extend_from_slice(&[
+				first_index,
+				first_index + 1,
+				first_index + 2,
+				first_index,
+				first_index + 2,
+				first_index + 3,
+			]);
+		}
+		Mesh2D { vertices, indices }
+	}
+}
+
+impl<'a, Message, B: Backend> Widget<Message, Renderer<B>> for ResponseCurve<'a, Message> {
+	fn width(&self) -> Length {
+		Length::Units(WIDTH)
+	}
+
+	fn height(&self) -> Length {
+		Length::Units(HEIGHT)
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, limits: &Limits) -> Node {
+		Node::new(
+			limits
+				.width(Length::Units(WIDTH))
+				.height(Length::Units(HEIGHT))
+				.resolve(Size::ZERO),
+		)
+	}
+
+	fn on_event(
+		&mut self,
+		event: Event,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		messages: &mut Vec<Message>,
+		_renderer: &Renderer<B>,
+		_clipboard: Option<&dyn Clipboard>,
+	) -> Status {
+		let bounds = layout.bounds();
+		let on_drag = match &self.on_drag {
+			Some(on_drag) => on_drag,
+			None => return Status::Ignored,
+		};
+		match event {
+			Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+				match self.handle_at(bounds, cursor_position) {
+					Some(index) => {
+						self.state.dragging = Some(index);
+						Status::Captured
+					}
+					None => Status::Ignored,
+				}
+			}
+			Event::Mouse(mouse::Event::CursorMoved { .. }) => match self.state.dragging {
+				Some(index) => {
+					let (x, y) = Self::position_at(bounds, cursor_position);
+					messages.push(on_drag(index, x, y));
+					Status::Captured
+				}
+				None => Status::Ignored,
+			},
+			Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
+				if self.state.dragging.is_some() =>
+			{
+				self.state.dragging = None;
+				Status::Captured
+			}
+			_ => Status::Ignored,
+		}
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		_defaults: &Defaults,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let zero_y = Self::screen_point(bounds, 0.0, 0.5).y;
+		let mut primitives = vec![
+			Primitive::Quad {
+				bounds,
+				background: Background::Color(BACKGROUND_COLOR.into()),
+				border_radius: 0.0,
+				border_width: 0.0,
+				border_color: Color::TRANSPARENT,
+			},
+			Primitive::Quad {
+				bounds: Rectangle::new(
+					Point::new(bounds.x, zero_y - 0.5),
+					Size::new(bounds.width, 1.0),
+				),
+				background: Background::Color(ZERO_LINE_COLOR.into()),
+				border_radius: 0.0,
+				border_width: 0.0,
+				border_color: Color::TRANSPARENT,
+			},
+			Primitive::Translate {
+				translation: Vector::new(bounds.x, bounds.y),
+				content: Box::new(Primitive::Mesh2D {
+					buffers: self.line_mesh(bounds),
+					size: bounds.size(),
+				}),
+			},
+		];
+		for handle in &self.handles {
+			let center = Self::screen_point(bounds, handle.x, handle.y);
+			primitives.push(Primitive::Quad {
+				bounds: Rectangle::new(
+					Point::new(center.x - HANDLE_RADIUS, center.y - HANDLE_RADIUS),
+					Size::new(HANDLE_RADIUS * 2.0, HANDLE_RADIUS * 2.0),
+				),
+				background: Background::Color(handle.color.into()),
+				border_radius: HANDLE_RADIUS,
+				border_width: 0.0,
+				border_color: Color::TRANSPARENT,
+			});
+		}
+		let interaction = match &self.on_drag {
+			Some(_) if self.state.dragging.is_some() => Interaction::Grabbing,
+			Some(_) if self.handle_at(bounds, cursor_position).is_some() => Interaction::Grab,
+			_ => Interaction::default(),
+		};
+		(Primitive::Group { primitives }, interaction)
+	}
+
+	fn hash_layout(&self, state: &mut iced_native::Hasher) {
+		use std::hash::Hash;
+		WIDTH.hash(state);
+		HEIGHT.hash(state);
+	}
+}
+
+impl<'a, Message: 'a, B: Backend + 'a> From<ResponseCurve<'a, Message>>
+	for Element<'a, Message, Renderer<B>>
+{
+	fn from(response_curve: ResponseCurve<'a, Message>) -> Self {
+		Element::new(response_curve)
+	}
+}
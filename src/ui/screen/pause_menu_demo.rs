@@ -0,0 +1,399 @@
+use std::error::Error;
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Row, Slider, Text};
+use kira::{
+	arrangement::{Arrangement, ArrangementId, LoopArrangementSettings},
+	instance::StopInstanceSettings,
+	manager::{AudioManager, AudioManagerSettings},
+	mixer::effect::filter::{Filter, FilterSettings},
+	parameter::{EaseDirection, Easing, ParameterId, Tween},
+	playable::PlayableSettings,
+	sound::SoundId,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, effect::gain::Gain, sound_cache::SoundCache, synth},
+	ui::{common::screen_wrapper::ScreenWrapper, screen::underwater_demo::TEMPO, style::AppStyles},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &[
+	"underwater demo/drums.ogg",
+	"underwater demo/bass.ogg",
+	"underwater demo/pad.ogg",
+	"underwater demo/lead.ogg",
+];
+
+const EXPLANATION_TEXT: &str = "Pausing a game rarely stops the \
+music outright. Instead, the music is pushed into the \
+background - muffled and turned down - so the menu feels like \
+it's in front of the game, and resuming brings it back.
+
+The music plays on a sub-track with a low-pass filter and a \
+gain effect, whose cutoff and volume are tied to parameters. \
+Pausing tweens both parameters to the paused settings over the \
+transition time, and resuming tweens them back. The menu blip \
+plays on the main track, so it stays clear while the music is \
+muffled.
+
+The cutoff doesn't move in a straight line: we hear pitch in \
+octaves, so a linear sweep from 20 kHz would spend most of its \
+time above anything audible. Easing the tween out on the way \
+down and in on the way back up makes the sweep sound even.";
+
+/// The cutoff when the game isn't paused (in Hz), which lets
+/// everything through.
+const OPEN_CUTOFF: f64 = 20000.0;
+const MIN_PAUSED_CUTOFF: f64 = 200.0;
+const MAX_PAUSED_CUTOFF: f64 = 5000.0;
+const INITIAL_PAUSED_CUTOFF: f64 = 800.0;
+/// The range of the volume dip (in decibels).
+const MIN_PAUSED_VOLUME: f64 = -24.0;
+const INITIAL_PAUSED_VOLUME: f64 = -8.0;
+const MAX_TRANSITION_TIME: f64 = 2.0;
+const INITIAL_TRANSITION_TIME: f64 = 0.4;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	Pause,
+	Resume,
+	PlayMenuBlip,
+	/// Sets how long pausing and resuming take (in seconds).
+	SetTransitionTime(f64),
+	/// Sets the cutoff while paused (in Hz).
+	SetPausedCutoff(f64),
+	/// Sets the volume while paused (in decibels).
+	SetPausedVolume(f64),
+}
+
+pub struct PauseMenuDemo {
+	audio_manager: AudioManager,
+	loop_ids: Vec<ArrangementId>,
+	blip_sound_id: SoundId,
+	cutoff_parameter_id: ParameterId,
+	volume_parameter_id: ParameterId,
+	playing: bool,
+	paused: bool,
+	transition_time: f64,
+	paused_cutoff: f64,
+	paused_volume: f64,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_button: iced::button::State,
+	pause_button: iced::button::State,
+	blip_button: iced::button::State,
+	transition_time_slider: iced::slider::State,
+	paused_cutoff_slider: iced::slider::State,
+	paused_volume_slider: iced::slider::State,
+}
+
+impl PauseMenuDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let cutoff_parameter_id = audio_manager.add_parameter(OPEN_CUTOFF)?;
+		let volume_parameter_id = audio_manager.add_parameter(0.0)?;
+		let music_track_id = audio_manager.add_sub_track(Default::default())?;
+		audio_manager.add_effect_to_track(
+			music_track_id,
+			Filter::new(FilterSettings::new().cutoff(cutoff_parameter_id)),
+			Default::default(),
+		)?;
+		audio_manager.add_effect_to_track(
+			music_track_id,
+			Gain::new(volume_parameter_id),
+			Default::default(),
+		)?;
+		let assets_base_dir = std::env::current_dir()?.join("assets");
+		let mut loop_ids = vec![];
+		for asset in ASSETS {
+			let sound_id = audio_manager.add_sound(
+				sound_cache.get(&assets_base_dir.join(asset))?.to_sound(
+					PlayableSettings::new().semantic_duration(TEMPO.beats_to_seconds(16.0)),
+				),
+			)?;
+			loop_ids.push(audio_manager.add_arrangement(Arrangement::new_loop(
+				sound_id,
+				LoopArrangementSettings::new().default_track(music_track_id),
+			))?);
+		}
+		// sounds play on the main track unless told otherwise, so
+		// the blip skips the music's filter
+		let blip_sound_id =
+			audio_manager.add_sound(synth::blip(1320.0, 0.08, PlayableSettings::new()))?;
+		Ok(Self {
+			audio_manager,
+			loop_ids,
+			blip_sound_id,
+			cutoff_parameter_id,
+			volume_parameter_id,
+			playing: false,
+			paused: false,
+			transition_time: INITIAL_TRANSITION_TIME,
+			paused_cutoff: INITIAL_PAUSED_CUTOFF,
+			paused_volume: INITIAL_PAUSED_VOLUME,
+			screen_wrapper: ScreenWrapper::new("Pause menu demo".into(), Message::GoToDemoSelect),
+			play_button: iced::button::State::new(),
+			pause_button: iced::button::State::new(),
+			blip_button: iced::button::State::new(),
+			transition_time_slider: iced::slider::State::new(),
+			paused_cutoff_slider: iced::slider::State::new(),
+			paused_volume_slider: iced::slider::State::new(),
+		})
+	}
+
+	/// Tweens the cutoff and volume to the paused or unpaused
+	/// settings. A transition time of 0 jumps straight there.
+	fn apply_pause(&mut self, duration: f64) -> Result<(), Box<dyn Error>> {
+		let (cutoff, volume, ease_direction) = if self.paused {
+			(self.paused_cutoff, self.paused_volume, EaseDirection::Out)
+		} else {
+			(OPEN_CUTOFF, 0.0, EaseDirection::In)
+		};
+		let (cutoff_tween, volume_tween) = if duration > 0.0 {
+			(
+				Some(Tween {
+					duration,
+					easing: Easing::PowI(3),
+					ease_direction,
+				}),
+				Some(Tween::linear(duration)),
+			)
+		} else {
+			(None, None)
+		};
+		self.audio_manager
+			.set_parameter(self.cutoff_parameter_id, cutoff, cutoff_tween)?;
+		self.audio_manager
+			.set_parameter(self.volume_parameter_id, volume, volume_tween)?;
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			// the commands are all sent at once, so the loops start
+			// on the same sample
+			Message::Play if !self.playing => {
+				for loop_id in &self.loop_ids {
+					self.audio_manager.play(*loop_id, Default::default())?;
+				}
+				self.playing = true;
+			}
+			Message::Stop => {
+				for loop_id in &self.loop_ids {
+					self.audio_manager.stop_instances_of(
+						(*loop_id).into(),
+						StopInstanceSettings::new().fade_tween(Tween::linear(0.5)),
+					)?;
+				}
+				self.playing = false;
+			}
+			Message::Pause if !self.paused => {
+				self.paused = true;
+				self.apply_pause(self.transition_time)?;
+			}
+			Message::Resume if self.paused => {
+				self.paused = false;
+				self.apply_pause(self.transition_time)?;
+			}
+			Message::PlayMenuBlip => {
+				self.audio_manager
+					.play(self.blip_sound_id, Default::default())?;
+			}
+			Message::SetTransitionTime(transition_time) => {
+				self.transition_time = transition_time.clamp(0.0, MAX_TRANSITION_TIME);
+			}
+			// changing the paused settings while paused is heard
+			// right away, with a short tween to avoid clicks
+			Message::SetPausedCutoff(cutoff) => {
+				self.paused_cutoff = cutoff.clamp(MIN_PAUSED_CUTOFF, MAX_PAUSED_CUTOFF);
+				if self.paused {
+					self.apply_pause(0.05)?;
+				}
+			}
+			Message::SetPausedVolume(volume) => {
+				self.paused_volume = volume.clamp(MIN_PAUSED_VOLUME, 0.0);
+				if self.paused {
+					self.apply_pause(0.05)?;
+				}
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	fn slider_row<'a>(text: String, slider: Slider<'a, f64, Message>) -> Row<'a, Message> {
+		Row::new()
+			.max_width(500)
+			.spacing(16)
+			.align_items(Align::Center)
+			.push(Text::new(text).width(Length::Units(220)))
+			.push(slider)
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.playing;
+		let paused = self.paused;
+		let mut blip_button = Button::new(
+			&mut self.blip_button,
+			Text::new("Menu blip")
+				.width(Length::Fill)
+				.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.width(Length::Units(120))
+		.style(AppStyles);
+		// the menu only exists while the game is paused
+		if paused {
+			blip_button = blip_button.on_press(Message::PlayMenuBlip);
+		}
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Row::new()
+						.spacing(16)
+						.push(
+							Button::new(
+								&mut self.play_button,
+								Text::new(if playing { "Stop music" } else { "Play music" })
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(if playing {
+								Message::Stop
+							} else {
+								Message::Play
+							})
+							.width(Length::Units(120))
+							.style(AppStyles),
+						)
+						.push(
+							Button::new(
+								&mut self.pause_button,
+								Text::new(if paused { "Resume" } else { "Pause" })
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(if paused {
+								Message::Resume
+							} else {
+								Message::Pause
+							})
+							.width(Length::Units(120))
+							.style(AppStyles),
+						)
+						.push(blip_button),
+				)
+				.push(Text::new(if paused {
+					"Game paused"
+				} else {
+					"Game running"
+				}))
+				.push(Self::slider_row(
+					format!("Transition time: {:.2} s", self.transition_time),
+					Slider::new(
+						&mut self.transition_time_slider,
+						0.0..=MAX_TRANSITION_TIME,
+						self.transition_time,
+						Message::SetTransitionTime,
+					)
+					.step(0.05)
+					.style(AppStyles),
+				))
+				.push(Self::slider_row(
+					format!("Paused cutoff: {:.0} Hz", self.paused_cutoff),
+					Slider::new(
+						&mut self.paused_cutoff_slider,
+						MIN_PAUSED_CUTOFF..=MAX_PAUSED_CUTOFF,
+						self.paused_cutoff,
+						Message::SetPausedCutoff,
+					)
+					.step(10.0)
+					.style(AppStyles),
+				))
+				.push(Self::slider_row(
+					format!("Paused volume: {:.1} dB", self.paused_volume),
+					Slider::new(
+						&mut self.paused_volume_slider,
+						MIN_PAUSED_VOLUME..=0.0,
+						self.paused_volume,
+						Message::SetPausedVolume,
+					)
+					.step(0.5)
+					.style(AppStyles),
+				))
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for PauseMenuDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "pause_menu_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetTransitionTime(_) => Some("Transition time".into()),
+			Message::SetPausedCutoff(_) => Some("Paused cutoff".into()),
+			Message::SetPausedVolume(_) => Some("Paused volume".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Transition time" => vec![Message::SetTransitionTime(MAX_TRANSITION_TIME * value)],
+			"Paused cutoff" => vec![Message::SetPausedCutoff(
+				MIN_PAUSED_CUTOFF + (MAX_PAUSED_CUTOFF - MIN_PAUSED_CUTOFF) * value,
+			)],
+			"Paused volume" => vec![Message::SetPausedVolume(MIN_PAUSED_VOLUME * (1.0 - value))],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for PauseMenuDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "pause_menu_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetTransitionTime(self.transition_time),
+			Message::SetPausedCutoff(self.paused_cutoff),
+			Message::SetPausedVolume(self.paused_volume),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for PauseMenuDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(key, self.playing, Message::Play, Message::Stop)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if !self.playing {
+			return None;
+		}
+		Some(if self.paused {
+			"Pause menu demo (paused)".into()
+		} else {
+			"Pause menu demo".into()
+		})
+	}
+}
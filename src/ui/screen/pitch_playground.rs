@@ -0,0 +1,299 @@
+use std::error::Error;
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Radio, Row, Slider, Text};
+use kira::{
+	arrangement::{Arrangement, ArrangementId},
+	instance::{InstanceId, InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::{ParameterId, Tween},
+	playable::PlayableSettings,
+	Tempo,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, sound_cache::SoundCache},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &["underwater demo/pad.ogg", "underwater demo/lead.ogg"];
+
+const EXPLANATION_TEXT: &str = "This demo plays a loop with its \
+pitch tied to a parameter. The pitch is a playback rate, so \
+doubling it raises the sound by an octave and also makes it \
+play twice as fast. One semitone up is a rate of about 1.059 \
+(the twelfth root of 2).
+
+The slider sets the parameter with a very short tween to avoid \
+clicks. The interval buttons tween it over the glide time, \
+which slides smoothly from one pitch to the next.";
+
+const UNDERWATER_TEMPO: Tempo = Tempo(85.0);
+/// How far the slider goes in each direction (in semitones).
+const MAX_SEMITONES: f64 = 24.0;
+/// The intervals the buttons move the pitch by (in semitones).
+const INTERVALS: [(&str, f64); 6] = [
+	("-1 octave", -12.0),
+	("-1 semitone", -1.0),
+	("+1 semitone", 1.0),
+	("+5th", 7.0),
+	("+1 octave", 12.0),
+	("Reset", 0.0),
+];
+/// How long interval changes take (in seconds).
+const GLIDE_TIMES: [f64; 4] = [0.0, 0.1, 0.5, 2.0];
+const DEFAULT_GLIDE_TIME_INDEX: usize = 1;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	/// Sets the pitch (in semitones from the original).
+	SetSemitones(f64),
+	/// Moves the pitch by one of the intervals (by its index).
+	Shift(usize),
+	SetGlideTime(usize),
+}
+
+pub struct PitchPlayground {
+	audio_manager: AudioManager,
+	loop_ids: Vec<ArrangementId>,
+	instance_ids: Vec<InstanceId>,
+	pitch_parameter_id: ParameterId,
+	semitones: f64,
+	glide_time_index: usize,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_button: iced::button::State,
+	interval_buttons: [iced::button::State; 6],
+	pitch_slider: iced::slider::State,
+}
+
+impl PitchPlayground {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let pitch_parameter_id = audio_manager.add_parameter(1.0)?;
+		let assets_dir = std::env::current_dir()?.join("assets/underwater demo");
+		let mut loop_ids = vec![];
+		for stem in &["pad.ogg", "lead.ogg"] {
+			let sound_id = audio_manager.add_sound(
+				sound_cache.get(&assets_dir.join(stem))?.to_sound(
+					PlayableSettings::new()
+						.semantic_duration(UNDERWATER_TEMPO.beats_to_seconds(16.0)),
+				),
+			)?;
+			loop_ids.push(
+				audio_manager
+					.add_arrangement(Arrangement::new_loop(sound_id, Default::default()))?,
+			);
+		}
+		Ok(Self {
+			audio_manager,
+			loop_ids,
+			instance_ids: vec![],
+			pitch_parameter_id,
+			semitones: 0.0,
+			glide_time_index: DEFAULT_GLIDE_TIME_INDEX,
+			screen_wrapper: ScreenWrapper::new("Pitch playground".into(), Message::GoToDemoSelect),
+			play_button: iced::button::State::new(),
+			interval_buttons: Default::default(),
+			pitch_slider: iced::slider::State::new(),
+		})
+	}
+
+	fn pitch(&self) -> f64 {
+		2.0f64.powf(self.semitones / 12.0)
+	}
+
+	fn set_semitones(&mut self, semitones: f64, tween_time: f64) -> Result<(), Box<dyn Error>> {
+		self.semitones = semitones.clamp(-MAX_SEMITONES, MAX_SEMITONES);
+		let tween = if tween_time > 0.0 {
+			Some(Tween::linear(tween_time))
+		} else {
+			None
+		};
+		self.audio_manager
+			.set_parameter(self.pitch_parameter_id, self.pitch(), tween)?;
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if self.instance_ids.is_empty() => {
+				for loop_id in &self.loop_ids {
+					self.instance_ids.push(self.audio_manager.play(
+						*loop_id,
+						InstanceSettings::new().pitch(self.pitch_parameter_id),
+					)?);
+				}
+			}
+			Message::Stop => {
+				for instance_id in self.instance_ids.drain(..) {
+					self.audio_manager.stop_instance(
+						instance_id,
+						StopInstanceSettings::new().fade_tween(Tween::linear(0.5)),
+					)?;
+				}
+			}
+			// a short tween keeps dragging the slider from clicking
+			Message::SetSemitones(semitones) => self.set_semitones(semitones, 0.05)?,
+			Message::Shift(index) => {
+				let (_, interval) = INTERVALS[index.min(INTERVALS.len() - 1)];
+				let semitones = if interval == 0.0 {
+					0.0
+				} else {
+					self.semitones + interval
+				};
+				self.set_semitones(semitones, GLIDE_TIMES[self.glide_time_index])?;
+			}
+			Message::SetGlideTime(index) => {
+				self.glide_time_index = index.min(GLIDE_TIMES.len() - 1);
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = !self.instance_ids.is_empty();
+		let semitones = self.semitones;
+		let pitch = self.pitch();
+		let interval_buttons = self.interval_buttons.iter_mut().enumerate().fold(
+			Row::new().spacing(8),
+			|row, (index, button)| {
+				let (label, interval) = INTERVALS[index];
+				let target = semitones + interval;
+				let mut button = Button::new(
+					button,
+					Text::new(label)
+						.size(16)
+						.width(Length::Fill)
+						.horizontal_alignment(HorizontalAlignment::Center),
+				)
+				.width(Length::Units(100))
+				.style(AppStyles);
+				if interval == 0.0 || target.abs() <= MAX_SEMITONES {
+					button = button.on_press(Message::Shift(index));
+				}
+				row.push(button)
+			},
+		);
+		let glide_time_index = self.glide_time_index;
+		let glide_time_radios = GLIDE_TIMES.iter().enumerate().fold(
+			Row::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(Text::new("Glide time:")),
+			|row, (index, glide_time)| {
+				row.push(Radio::new(
+					index,
+					format!("{}s", glide_time),
+					Some(glide_time_index),
+					Message::SetGlideTime,
+				))
+			},
+		);
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Button::new(
+						&mut self.play_button,
+						Text::new(if playing { "Stop" } else { "Play" })
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(if playing {
+						Message::Stop
+					} else {
+						Message::Play
+					})
+					.width(Length::Units(120))
+					.style(AppStyles),
+				)
+				.push(Text::new(format!(
+					"Pitch: {:+.2} semitones ({:.3}x)",
+					semitones, pitch
+				)))
+				.push(
+					Row::new().max_width(600).push(
+						Slider::new(
+							&mut self.pitch_slider,
+							-MAX_SEMITONES..=MAX_SEMITONES,
+							semitones,
+							Message::SetSemitones,
+						)
+						.step(0.01)
+						.style(AppStyles),
+					),
+				)
+				.push(interval_buttons)
+				.push(glide_time_radios)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for PitchPlayground {
+	type Message = Message;
+
+	const NAME: &'static str = "pitch_playground";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetSemitones(_) => Some("Pitch".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Pitch" => vec![Message::SetSemitones(MAX_SEMITONES * (value * 2.0 - 1.0))],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for PitchPlayground {
+	type Message = Message;
+
+	const NAME: &'static str = "pitch_playground";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetSemitones(self.semitones),
+			Message::SetGlideTime(self.glide_time_index),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for PitchPlayground {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			!self.instance_ids.is_empty(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if self.instance_ids.is_empty() {
+			return None;
+		}
+		Some("Pitch playground".into())
+	}
+}
@@ -0,0 +1,330 @@
+mod voice_meters;
+mod voice_pool;
+
+use std::{error::Error, time::Instant};
+
+use iced::{
+	Align, Button, Checkbox, Column, HorizontalAlignment, Length, Radio, Row, Slider, Text,
+};
+use kira::{
+	manager::{AudioManager, AudioManagerSettings},
+	playable::PlayableSettings,
+};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use voice_meters::VoiceMeters;
+use voice_pool::{StartResult, StealPolicy, VoicePool};
+
+use crate::{
+	audio::{create_audio_manager, synth},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+const EXPLANATION_TEXT: &str = "Every press plays a plucked \
+note at a random pitch and volume, but only so many notes can \
+ring at once. When all of the voices are busy, the steal policy \
+decides what happens: the oldest note or the quietest one is \
+faded out to make room, or the new note is thrown away.
+
+Kira will play as many instances as you ask for, so the limit \
+lives in the demo. It keeps a list of the instances it started \
+and, since the note decays at a known rate, works out how loud \
+each one is from how long ago it started. That's the same \
+bookkeeping a game would do to keep a rapid-fire sound from \
+piling up.";
+
+/// How long each note rings (in seconds).
+const NOTE_DURATION: f64 = 1.5;
+/// How fast [`synth::blip`] fades out over its duration.
+const NOTE_DECAY: f64 = 5.0;
+/// The notes the demo picks from (in semitones above the base
+/// note).
+const NOTES: [f64; 6] = [0.0, 2.0, 4.0, 7.0, 9.0, 12.0];
+const MIN_VOLUME: f64 = 0.3;
+const MAX_VOICES: usize = 16;
+const MIN_TRIGGER_INTERVAL: f64 = 0.02;
+const MAX_TRIGGER_INTERVAL: f64 = 0.5;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Trigger,
+	SetAutoTrigger(bool),
+	/// Sets how often the auto-trigger plays a note (in seconds).
+	SetTriggerInterval(f64),
+	SetMaxVoices(f64),
+	SetPolicy(StealPolicy),
+	ResetCounts,
+}
+
+#[derive(Default)]
+struct Counts {
+	started: usize,
+	stolen: usize,
+	rejected: usize,
+}
+
+pub struct PolyphonyDemo {
+	audio_manager: AudioManager,
+	voice_pool: VoicePool,
+	rng: StdRng,
+	auto_trigger: bool,
+	trigger_interval: f64,
+	last_trigger_time: Instant,
+	counts: Counts,
+	screen_wrapper: ScreenWrapper<Message>,
+	trigger_button: iced::button::State,
+	reset_button: iced::button::State,
+	trigger_interval_slider: iced::slider::State,
+	max_voices_slider: iced::slider::State,
+}
+
+impl PolyphonyDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let sound_id = audio_manager.add_sound(synth::blip(
+			440.0,
+			NOTE_DURATION,
+			// the pool decides which notes get to play, so the sound
+			// shouldn't block any on its own
+			PlayableSettings {
+				cooldown: None,
+				..Default::default()
+			},
+		))?;
+		Ok(Self {
+			audio_manager,
+			voice_pool: VoicePool::new(sound_id, NOTE_DURATION, NOTE_DECAY, 4),
+			rng: StdRng::from_entropy(),
+			auto_trigger: false,
+			trigger_interval: 0.1,
+			last_trigger_time: Instant::now(),
+			counts: Counts::default(),
+			screen_wrapper: ScreenWrapper::new("Polyphony demo".into(), Message::GoToDemoSelect),
+			trigger_button: iced::button::State::new(),
+			reset_button: iced::button::State::new(),
+			trigger_interval_slider: iced::slider::State::new(),
+			max_voices_slider: iced::slider::State::new(),
+		})
+	}
+
+	fn trigger(&mut self) -> Result<(), Box<dyn Error>> {
+		let semitones = NOTES.choose(&mut self.rng).copied().unwrap_or(0.0);
+		let pitch = 2.0f64.powf(semitones / 12.0);
+		let volume = self.rng.gen_range(MIN_VOLUME, 1.0);
+		match self
+			.voice_pool
+			.start(&mut self.audio_manager, volume, pitch)?
+		{
+			StartResult::Started => self.counts.started += 1,
+			StartResult::Stole => {
+				self.counts.started += 1;
+				self.counts.stolen += 1;
+			}
+			StartResult::Rejected => self.counts.rejected += 1,
+		}
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Trigger => self.trigger()?,
+			Message::SetAutoTrigger(auto_trigger) => {
+				self.auto_trigger = auto_trigger;
+				self.last_trigger_time = Instant::now();
+			}
+			Message::SetTriggerInterval(interval) => {
+				self.trigger_interval = interval.clamp(MIN_TRIGGER_INTERVAL, MAX_TRIGGER_INTERVAL);
+			}
+			Message::SetMaxVoices(max_voices) => {
+				let max_voices = (max_voices.round() as usize).clamp(1, MAX_VOICES);
+				self.voice_pool
+					.set_max_voices(&mut self.audio_manager, max_voices)?;
+			}
+			Message::SetPolicy(policy) => self.voice_pool.set_policy(policy),
+			Message::ResetCounts => self.counts = Counts::default(),
+			_ => {}
+		}
+		Ok(())
+	}
+
+	/// Whether notes are being triggered from the UI timer.
+	pub fn plays_from_ui_timer(&self) -> bool {
+		self.auto_trigger
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		if self.auto_trigger
+			&& self.last_trigger_time.elapsed().as_secs_f64() >= self.trigger_interval
+		{
+			self.last_trigger_time = Instant::now();
+			self.trigger()?;
+		}
+		self.voice_pool.remove_finished_voices();
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let max_voices = self.voice_pool.max_voices();
+		let policy = self.voice_pool.policy();
+		let policy_radios = StealPolicy::ALL
+			.iter()
+			.fold(Row::new().spacing(16), |row, option| {
+				row.push(Radio::new(
+					*option,
+					option.name(),
+					Some(policy),
+					Message::SetPolicy,
+				))
+			});
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Button::new(
+								&mut self.trigger_button,
+								Text::new("Play note")
+									.width(Length::Fill)
+									.size(24)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(Message::Trigger)
+							.width(Length::Units(150))
+							.style(AppStyles),
+						)
+						.push(Checkbox::new(
+							self.auto_trigger,
+							"Auto-trigger",
+							Message::SetAutoTrigger,
+						)),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!(
+								"Every {}ms",
+								(self.trigger_interval * 1000.0).round()
+							))
+							.width(Length::Units(150)),
+						)
+						.push(
+							Slider::new(
+								&mut self.trigger_interval_slider,
+								MIN_TRIGGER_INTERVAL..=MAX_TRIGGER_INTERVAL,
+								self.trigger_interval,
+								Message::SetTriggerInterval,
+							)
+							.step(0.01)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Max voices: {}", max_voices))
+								.width(Length::Units(150)),
+						)
+						.push(
+							Slider::new(
+								&mut self.max_voices_slider,
+								1.0..=MAX_VOICES as f64,
+								max_voices as f64,
+								Message::SetMaxVoices,
+							)
+							.step(1.0)
+							.style(AppStyles),
+						),
+				)
+				.push(policy_radios)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(500)
+						.push(VoiceMeters {
+							max_voices,
+							loudnesses: self
+								.voice_pool
+								.loudnesses()
+								.iter()
+								.map(|loudness| *loudness as f32)
+								.collect(),
+						}),
+				)
+				.push(Text::new(format!(
+					"Voices: {} / {}",
+					self.voice_pool.num_voices(),
+					max_voices
+				)))
+				.push(
+					Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(Text::new(format!(
+							"Started: {}  Stolen: {}  Rejected: {}",
+							self.counts.started, self.counts.stolen, self.counts.rejected
+						)))
+						.push(
+							Button::new(&mut self.reset_button, Text::new("Reset"))
+								.on_press(Message::ResetCounts)
+								.style(AppStyles),
+						),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for PolyphonyDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "polyphony_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetTriggerInterval(_) => Some("Trigger interval".into()),
+			Message::SetMaxVoices(_) => Some("Max voices".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Trigger interval" => vec![Message::SetTriggerInterval(
+				MIN_TRIGGER_INTERVAL + (MAX_TRIGGER_INTERVAL - MIN_TRIGGER_INTERVAL) * value,
+			)],
+			"Max voices" => vec![Message::SetMaxVoices(1.0 + (MAX_VOICES - 1) as f64 * value)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for PolyphonyDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "polyphony_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetTriggerInterval(self.trigger_interval),
+			Message::SetMaxVoices(self.voice_pool.max_voices() as f64),
+			Message::SetPolicy(self.voice_pool.policy()),
+		]
+	}
+}
@@ -0,0 +1,91 @@
+use iced::{mouse::Interaction, Background, Color, Length, Point, Rectangle, Size};
+use iced_graphics::{Backend, Defaults, Primitive, Renderer};
+use iced_native::{
+	layout::{Limits, Node},
+	Element, Layout, Widget,
+};
+
+const HEIGHT: f32 = 80.0;
+const SPACING: f32 = 4.0;
+const SLOT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.05];
+const VOICE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.75];
+
+/// Shows one slot for every voice the pool allows, with a bar in
+/// each busy slot as tall as that voice is loud.
+pub struct VoiceMeters {
+	pub max_voices: usize,
+	/// The loudness of each playing voice (from 0 to 1), oldest
+	/// first.
+	pub loudnesses: Vec<f32>,
+}
+
+impl<Message, B: Backend> Widget<Message, Renderer<B>> for VoiceMeters {
+	fn width(&self) -> Length {
+		Length::Fill
+	}
+
+	fn height(&self) -> Length {
+		Length::Shrink
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, limits: &Limits) -> Node {
+		Node::new(
+			limits
+				.width(Length::Fill)
+				.height(Length::Units(HEIGHT as u16))
+				.resolve(Size::ZERO),
+		)
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		_defaults: &Defaults,
+		layout: Layout<'_>,
+		_cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let num_slots = self.max_voices.max(1);
+		let slot_width = (bounds.width - SPACING * (num_slots - 1) as f32) / num_slots as f32;
+		let mut primitives = vec![];
+		for slot in 0..num_slots {
+			let x = bounds.x + (slot_width + SPACING) * slot as f32;
+			primitives.push(Primitive::Quad {
+				bounds: Rectangle::new(
+					Point::new(x, bounds.y),
+					Size::new(slot_width, bounds.height),
+				),
+				background: Background::Color(SLOT_COLOR.into()),
+				border_radius: 0.0,
+				border_width: 0.0,
+				border_color: Color::TRANSPARENT,
+			});
+			if let Some(loudness) = self.loudnesses.get(slot) {
+				let height = bounds.height * loudness.clamp(0.0, 1.0);
+				primitives.push(Primitive::Quad {
+					bounds: Rectangle::new(
+						Point::new(x, bounds.y + bounds.height - height),
+						Size::new(slot_width, height),
+					),
+					background: Background::Color(VOICE_COLOR.into()),
+					border_radius: 0.0,
+					border_width: 0.0,
+					border_color: Color::TRANSPARENT,
+				});
+			}
+		}
+		(Primitive::Group { primitives }, Interaction::default())
+	}
+
+	fn hash_layout(&self, state: &mut iced_native::Hasher) {
+		use std::hash::Hash;
+		HEIGHT.to_bits().hash(state)
+	}
+}
+
+impl<'a, Message, B: Backend> From<VoiceMeters> for Element<'a, Message, Renderer<B>> {
+	fn from(voice_meters: VoiceMeters) -> Self {
+		Element::new(voice_meters)
+	}
+}
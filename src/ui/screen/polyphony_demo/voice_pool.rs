@@ -0,0 +1,201 @@
+use std::time::Instant;
+
+use kira::{
+	instance::{InstanceId, InstanceSettings, StopInstanceSettings},
+	manager::AudioManager,
+	parameter::Tween,
+	sound::SoundId,
+	AudioResult,
+};
+use serde::{Deserialize, Serialize};
+
+/// How long a stolen voice takes to fade out (in seconds). Cutting
+/// it off instantly would click.
+const STEAL_FADE_TIME: f64 = 0.03;
+
+/// Which voice makes room when a new one starts and every voice
+/// is busy.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StealPolicy {
+	/// Stops the voice that started first.
+	Oldest,
+	/// Stops the voice that's currently the softest.
+	Quietest,
+	/// Doesn't stop anything and drops the new voice instead.
+	Reject,
+}
+
+impl StealPolicy {
+	pub const ALL: [Self; 3] = [Self::Oldest, Self::Quietest, Self::Reject];
+
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Oldest => "Steal oldest",
+			Self::Quietest => "Steal quietest",
+			Self::Reject => "Reject new",
+		}
+	}
+}
+
+/// What happened when the pool was asked to start a voice.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StartResult {
+	Started,
+	/// The voice started after another one was stopped.
+	Stole,
+	Rejected,
+}
+
+struct Voice {
+	instance_id: InstanceId,
+	start_time: Instant,
+	volume: f64,
+	/// How long this voice lasts (in seconds). Pitching a sound up
+	/// makes it shorter.
+	duration: f64,
+}
+
+/// Keeps track of the instances of one decaying sound and holds
+/// them to a maximum count.
+///
+/// Kira doesn't report when instances finish or how loud they are,
+/// so the pool works both out itself: the sound fades out
+/// exponentially over a known duration, and a voice's loudness is
+/// its volume times how far along that curve it is.
+pub struct VoicePool {
+	sound_id: SoundId,
+	/// How long the sound lasts (in seconds).
+	sound_duration: f64,
+	/// How fast the sound decays, as the exponent it reaches at the
+	/// end of its duration.
+	decay: f64,
+	voices: Vec<Voice>,
+	max_voices: usize,
+	policy: StealPolicy,
+}
+
+impl VoicePool {
+	pub fn new(sound_id: SoundId, sound_duration: f64, decay: f64, max_voices: usize) -> Self {
+		Self {
+			sound_id,
+			sound_duration,
+			decay,
+			voices: vec![],
+			max_voices: max_voices.max(1),
+			policy: StealPolicy::Oldest,
+		}
+	}
+
+	pub fn max_voices(&self) -> usize {
+		self.max_voices
+	}
+
+	pub fn policy(&self) -> StealPolicy {
+		self.policy
+	}
+
+	pub fn set_policy(&mut self, policy: StealPolicy) {
+		self.policy = policy;
+	}
+
+	pub fn num_voices(&self) -> usize {
+		self.voices.len()
+	}
+
+	/// How loud each playing voice is right now (from 0 to 1), in
+	/// the order they started.
+	pub fn loudnesses(&self) -> Vec<f64> {
+		self.voices
+			.iter()
+			.map(|voice| self.loudness(voice))
+			.collect()
+	}
+
+	fn loudness(&self, voice: &Voice) -> f64 {
+		let age = voice.start_time.elapsed().as_secs_f64();
+		voice.volume * (-age * self.decay / voice.duration).exp()
+	}
+
+	/// Forgets about voices that have finished playing.
+	pub fn remove_finished_voices(&mut self) {
+		self.voices
+			.retain(|voice| voice.start_time.elapsed().as_secs_f64() < voice.duration);
+	}
+
+	/// The voice that should be stopped to make room, if the policy
+	/// allows stealing.
+	fn victim(&self) -> Option<usize> {
+		match self.policy {
+			// voices are stored in the order they started
+			StealPolicy::Oldest => Some(0),
+			StealPolicy::Quietest => self
+				.voices
+				.iter()
+				.enumerate()
+				.min_by(|(_, a), (_, b)| {
+					self.loudness(a)
+						.partial_cmp(&self.loudness(b))
+						.unwrap_or(std::cmp::Ordering::Equal)
+				})
+				.map(|(index, _)| index),
+			StealPolicy::Reject => None,
+		}
+	}
+
+	fn stop_voice(&mut self, audio_manager: &mut AudioManager, index: usize) -> AudioResult<()> {
+		let voice = self.voices.remove(index);
+		audio_manager.stop_instance(
+			voice.instance_id,
+			StopInstanceSettings::new().fade_tween(Tween::linear(STEAL_FADE_TIME)),
+		)
+	}
+
+	/// Changes the voice limit, stopping voices right away if there
+	/// are more than the new limit allows. Voices that are over the
+	/// limit are stopped even with the reject policy, oldest first.
+	pub fn set_max_voices(
+		&mut self,
+		audio_manager: &mut AudioManager,
+		max_voices: usize,
+	) -> AudioResult<()> {
+		self.max_voices = max_voices.max(1);
+		self.remove_finished_voices();
+		while self.voices.len() > self.max_voices {
+			let index = self.victim().unwrap_or(0);
+			self.stop_voice(audio_manager, index)?;
+		}
+		Ok(())
+	}
+
+	/// Starts a new voice at the given volume and pitch, or doesn't
+	/// if the pool is full and the policy rejects new voices.
+	pub fn start(
+		&mut self,
+		audio_manager: &mut AudioManager,
+		volume: f64,
+		pitch: f64,
+	) -> AudioResult<StartResult> {
+		self.remove_finished_voices();
+		let mut result = StartResult::Started;
+		if self.voices.len() >= self.max_voices {
+			match self.victim() {
+				Some(index) => {
+					self.stop_voice(audio_manager, index)?;
+					result = StartResult::Stole;
+				}
+				None => return Ok(StartResult::Rejected),
+			}
+		}
+		let instance_id = audio_manager.play(
+			self.sound_id,
+			InstanceSettings::new().volume(volume).pitch(pitch),
+		)?;
+		self.voices.push(Voice {
+			instance_id,
+			start_time: Instant::now(),
+			volume,
+			duration: self.sound_duration / pitch,
+		});
+		Ok(result)
+	}
+}
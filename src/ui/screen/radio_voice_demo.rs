@@ -0,0 +1,433 @@
+use std::error::Error;
+
+use iced::{Align, Button, Checkbox, Column, HorizontalAlignment, Length, Row, Slider, Text};
+use kira::{
+	instance::{InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	mixer::effect::filter::{Filter, FilterMode, FilterSettings},
+	parameter::{Mapping, ParameterId, Tween},
+	playable::PlayableSettings,
+	sequence::{EventReceiver, Sequence, SequenceInstanceId},
+	sound::SoundId,
+	Duration, Value,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{
+		create_audio_manager,
+		effect::{
+			distortion::{Distortion, DistortionSettings},
+			gain::Gain,
+		},
+		synth,
+		wet_dry::WetDry,
+	},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+const EXPLANATION_TEXT: &str = "A voice heard over a radio or a \
+telephone sounds thin and a little gritty, because the line \
+only carries a narrow band of frequencies and the cheap speaker \
+at the other end distorts. So the radio processing is a \
+band-pass filter, which keeps the middle frequencies and cuts \
+the lows and highs, followed by a distortion effect. A gain \
+effect after them turns the distorted voice back down, reading \
+the drive parameter through a mapping like in the distortion \
+demo.
+
+The voice is played on a clean track and a radio track at the \
+same time, and switching between them crossfades the two, so \
+the processing can be flipped on and off in the middle of a \
+line.
+
+The static is a sequence started alongside the voice: a squelch \
+when the transmission opens, pops and crackles at random times \
+for as long as the line lasts, and another squelch when it \
+closes. The crackle pattern is rolled again each time the voice \
+is played, so it never repeats exactly. The static only makes \
+sense on the radio, so it fades out with the processing.";
+
+/// The band-pass filter's center frequency (in Hz).
+const MIN_BAND_CENTER: f64 = 500.0;
+const MAX_BAND_CENTER: f64 = 3000.0;
+const INITIAL_BAND_CENTER: f64 = 1500.0;
+/// A resonance this high keeps the band narrow.
+const BAND_RESONANCE: f64 = 0.6;
+/// The range of the drive (in decibels).
+const MAX_DRIVE: f64 = 30.0;
+const INITIAL_DRIVE: f64 = 18.0;
+/// How much the band-pass filter's output is turned up to make
+/// up for the frequencies it cuts (in decibels).
+const FILTER_MAKEUP_GAIN: f64 = 6.0;
+/// The shortest and longest gap between crackles (in seconds).
+const CRACKLE_GAP: (f64, f64) = (0.05, 0.6);
+/// How long the squelches take (in seconds).
+const SQUELCH_DURATION: f64 = 0.15;
+const SWITCH_FADE_TIME: f64 = 0.05;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetRadio(bool),
+	SetStatic(bool),
+	/// Sets the band-pass filter's center frequency (in Hz).
+	SetBandCenter(f64),
+	/// Sets the drive (in decibels).
+	SetDrive(f64),
+}
+
+/// The events the transmission sequence emits.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum TransmissionEvent {
+	Finished,
+}
+
+pub struct RadioVoiceDemo {
+	audio_manager: AudioManager,
+	/// The dry track is the clean voice, and the wet track is
+	/// the radio.
+	wet_dry: WetDry,
+	voice_sound_id: SoundId,
+	squelch_sound_id: SoundId,
+	crackle_sound_ids: Vec<SoundId>,
+	/// How loud the static is, which is 1 while it's switched
+	/// on and the radio processing is too.
+	static_parameter_id: ParameterId,
+	band_center_parameter_id: ParameterId,
+	drive_parameter_id: ParameterId,
+	playback: Option<(SequenceInstanceId, EventReceiver<TransmissionEvent>)>,
+	static_enabled: bool,
+	band_center: f64,
+	drive: f64,
+	rng: StdRng,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_button: iced::button::State,
+	band_center_slider: iced::slider::State,
+	drive_slider: iced::slider::State,
+}
+
+impl RadioVoiceDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let wet_dry = WetDry::new(&mut audio_manager, 1.0)?;
+		let band_center_parameter_id = audio_manager.add_parameter(INITIAL_BAND_CENTER)?;
+		let drive_parameter_id = audio_manager.add_parameter(INITIAL_DRIVE)?;
+		audio_manager.add_effect_to_track(
+			wet_dry.wet_track_id(),
+			Filter::new(
+				FilterSettings::new()
+					.mode(FilterMode::BandPass)
+					.cutoff(band_center_parameter_id)
+					.resonance(BAND_RESONANCE),
+			),
+			Default::default(),
+		)?;
+		audio_manager.add_effect_to_track(
+			wet_dry.wet_track_id(),
+			Distortion::new(DistortionSettings::new().drive(drive_parameter_id)),
+			Default::default(),
+		)?;
+		audio_manager.add_effect_to_track(
+			wet_dry.wet_track_id(),
+			Gain::new(Value::Parameter(
+				drive_parameter_id,
+				Mapping {
+					input_range: (0.0, MAX_DRIVE),
+					output_range: (FILTER_MAKEUP_GAIN, FILTER_MAKEUP_GAIN - MAX_DRIVE / 2.0),
+					clamp_bottom: true,
+					clamp_top: true,
+				},
+			)),
+			Default::default(),
+		)?;
+		// WetDry plays the voice twice at the same time, which a
+		// cooldown would block
+		let voice_sound_id = audio_manager.load_sound(
+			std::env::current_dir()?.join("assets/dialogue demo/dialogue.wav"),
+			PlayableSettings {
+				cooldown: None,
+				..PlayableSettings::new()
+			},
+		)?;
+		let squelch_sound_id = audio_manager.add_sound(synth::noise_hit(
+			SQUELCH_DURATION,
+			0.3,
+			PlayableSettings::new(),
+		))?;
+		// a few short, bright bursts of different lengths, so the
+		// crackles don't all sound the same
+		let mut crackle_sound_ids = vec![];
+		for (duration, brightness) in &[(0.01, 0.9), (0.02, 0.7), (0.04, 0.8)] {
+			crackle_sound_ids.push(audio_manager.add_sound(synth::noise_hit(
+				*duration,
+				*brightness,
+				PlayableSettings {
+					cooldown: None,
+					..PlayableSettings::new()
+				},
+			))?);
+		}
+		let static_parameter_id = audio_manager.add_parameter(1.0)?;
+		Ok(Self {
+			audio_manager,
+			wet_dry,
+			voice_sound_id,
+			squelch_sound_id,
+			crackle_sound_ids,
+			static_parameter_id,
+			band_center_parameter_id,
+			drive_parameter_id,
+			playback: None,
+			static_enabled: true,
+			band_center: INITIAL_BAND_CENTER,
+			drive: INITIAL_DRIVE,
+			rng: StdRng::from_entropy(),
+			screen_wrapper: ScreenWrapper::new("Radio voice demo".into(), Message::GoToDemoSelect),
+			play_button: iced::button::State::new(),
+			band_center_slider: iced::slider::State::new(),
+			drive_slider: iced::slider::State::new(),
+		})
+	}
+
+	/// Starts a sequence that plays the voice along with the
+	/// static: a squelch, crackles at random times until the
+	/// voice ends, and another squelch.
+	fn play(&mut self) -> Result<(), Box<dyn Error>> {
+		let static_parameter_id = self.static_parameter_id;
+		let static_settings = || {
+			InstanceSettings::new()
+				.volume(Value::Parameter(static_parameter_id, Mapping::default()))
+		};
+		let mut sequence = Sequence::new(Default::default());
+		sequence.play(self.squelch_sound_id, static_settings());
+		self.wet_dry
+			.play_in_sequence(&mut sequence, self.voice_sound_id, Default::default());
+		let duration = self.voice_sound_id.duration();
+		let mut time = self.rng.gen_range(CRACKLE_GAP.0, CRACKLE_GAP.1);
+		sequence.wait(Duration::Seconds(time));
+		while time < duration {
+			let crackle_sound_id =
+				self.crackle_sound_ids[self.rng.gen_range(0, self.crackle_sound_ids.len())];
+			sequence.play(crackle_sound_id, static_settings());
+			let gap = self.rng.gen_range(CRACKLE_GAP.0, CRACKLE_GAP.1);
+			sequence.wait(Duration::Seconds(gap));
+			time += gap;
+		}
+		sequence.play(self.squelch_sound_id, static_settings());
+		sequence.wait(Duration::Seconds(SQUELCH_DURATION));
+		sequence.emit(TransmissionEvent::Finished);
+		self.playback = Some(
+			self.audio_manager
+				.start_sequence(sequence, Default::default())?,
+		);
+		Ok(())
+	}
+
+	/// Fades the static in or out to match the static and radio
+	/// switches.
+	fn update_static(&mut self) -> Result<(), Box<dyn Error>> {
+		let audible = self.static_enabled && !self.wet_dry.bypassed();
+		self.audio_manager.set_parameter(
+			self.static_parameter_id,
+			if audible { 1.0 } else { 0.0 },
+			Some(Tween::linear(SWITCH_FADE_TIME)),
+		)?;
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if self.playback.is_none() => self.play()?,
+			Message::Stop => {
+				if let Some((sequence_id, _)) = self.playback.take() {
+					self.audio_manager.stop_sequence_and_instances(
+						sequence_id,
+						StopInstanceSettings::new().fade_tween(Tween::linear(0.1)),
+					)?;
+				}
+			}
+			// the clean voice is the radio processing bypassed
+			Message::SetRadio(radio) => {
+				self.wet_dry.set_bypassed(&mut self.audio_manager, !radio)?;
+				self.update_static()?;
+			}
+			Message::SetStatic(enabled) => {
+				self.static_enabled = enabled;
+				self.update_static()?;
+			}
+			Message::SetBandCenter(band_center) => {
+				self.band_center = band_center.clamp(MIN_BAND_CENTER, MAX_BAND_CENTER);
+				self.audio_manager.set_parameter(
+					self.band_center_parameter_id,
+					self.band_center,
+					Some(Tween::linear(0.05)),
+				)?;
+			}
+			Message::SetDrive(drive) => {
+				self.drive = drive.clamp(0.0, MAX_DRIVE);
+				self.audio_manager.set_parameter(
+					self.drive_parameter_id,
+					self.drive,
+					Some(Tween::linear(0.05)),
+				)?;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		let mut finished = false;
+		if let Some((_, event_receiver)) = &mut self.playback {
+			while let Some(TransmissionEvent::Finished) = event_receiver.pop().copied() {
+				finished = true;
+			}
+		}
+		if finished {
+			self.playback = None;
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.playback.is_some();
+		let radio = !self.wet_dry.bypassed();
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Button::new(
+						&mut self.play_button,
+						Text::new(if playing { "Stop" } else { "Play" })
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(if playing {
+						Message::Stop
+					} else {
+						Message::Play
+					})
+					.width(Length::Units(120))
+					.style(AppStyles),
+				)
+				.push(
+					Row::new()
+						.spacing(32)
+						.push(Checkbox::new(radio, "Radio processing", Message::SetRadio))
+						.push(Checkbox::new(
+							self.static_enabled,
+							"Static",
+							Message::SetStatic,
+						)),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Band center: {:.0} Hz", self.band_center))
+								.width(Length::Units(200)),
+						)
+						.push(
+							Slider::new(
+								&mut self.band_center_slider,
+								MIN_BAND_CENTER..=MAX_BAND_CENTER,
+								self.band_center,
+								Message::SetBandCenter,
+							)
+							.step(10.0)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Drive: {:.1} dB", self.drive))
+								.width(Length::Units(200)),
+						)
+						.push(
+							Slider::new(
+								&mut self.drive_slider,
+								0.0..=MAX_DRIVE,
+								self.drive,
+								Message::SetDrive,
+							)
+							.step(0.1)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for RadioVoiceDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "radio_voice_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetBandCenter(_) => Some("Band center".into()),
+			Message::SetDrive(_) => Some("Drive".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Band center" => vec![Message::SetBandCenter(
+				MIN_BAND_CENTER + (MAX_BAND_CENTER - MIN_BAND_CENTER) * value,
+			)],
+			"Drive" => vec![Message::SetDrive(value * MAX_DRIVE)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for RadioVoiceDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "radio_voice_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetRadio(!self.wet_dry.bypassed()),
+			Message::SetStatic(self.static_enabled),
+			Message::SetBandCenter(self.band_center),
+			Message::SetDrive(self.drive),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for RadioVoiceDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.playback.is_some(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		self.playback.as_ref().map(|_| "Radio voice demo".into())
+	}
+}
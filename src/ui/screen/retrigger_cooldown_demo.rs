@@ -0,0 +1,265 @@
+mod trigger_history;
+
+use std::{
+	collections::VecDeque,
+	error::Error,
+	time::{Duration, Instant},
+};
+
+use iced::{Align, Button, Checkbox, Column, HorizontalAlignment, Length, Row, Slider, Text};
+use kira::{
+	manager::{AudioManager, AudioManagerSettings},
+	playable::PlayableSettings,
+	sound::SoundId,
+};
+use serde::{Deserialize, Serialize};
+use trigger_history::{Trigger, TriggerHistory};
+
+use crate::{
+	audio::{create_audio_manager, synth},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+const EXPLANATION_TEXT: &str = "This demo plays a sound \
+with a cooldown. After an instance of the sound starts, \
+any attempts to play it again are ignored until the \
+cooldown is over.
+
+Cooldowns keep sounds that get triggered many times at \
+once (like a hail of bullets hitting a wall) from \
+stacking up into one loud, phasey mess.";
+
+const HISTORY_LENGTH: Duration = Duration::from_secs(3);
+const SPAM_INTERVAL: Duration = Duration::from_millis(30);
+/// The longest cooldown the slider goes up to (in seconds).
+const MAX_COOLDOWN: f64 = 0.5;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Trigger,
+	SetSpamming(bool),
+	SetCooldown(f64),
+	ApplyCooldown,
+}
+
+pub struct RetriggerCooldownDemo {
+	audio_manager: AudioManager,
+	sound_id: SoundId,
+	/// The cooldown on the slider.
+	cooldown: f64,
+	/// The cooldown the sound was created with, which doesn't
+	/// change until the slider is let go.
+	applied_cooldown: f64,
+	spamming: bool,
+	last_spam_time: Instant,
+	last_accepted_time: Option<Instant>,
+	triggers: VecDeque<(Instant, bool)>,
+	screen_wrapper: ScreenWrapper<Message>,
+	trigger_button: iced::button::State,
+	cooldown_slider: iced::slider::State,
+}
+
+impl RetriggerCooldownDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let cooldown = 0.1;
+		let sound_id = audio_manager.add_sound(Self::create_sound(cooldown))?;
+		Ok(Self {
+			audio_manager,
+			sound_id,
+			cooldown,
+			applied_cooldown: cooldown,
+			spamming: false,
+			last_spam_time: Instant::now(),
+			last_accepted_time: None,
+			triggers: VecDeque::new(),
+			screen_wrapper: ScreenWrapper::new(
+				"Retrigger cooldown demo".into(),
+				Message::GoToDemoSelect,
+			),
+			trigger_button: iced::button::State::new(),
+			cooldown_slider: iced::slider::State::new(),
+		})
+	}
+
+	fn create_sound(cooldown: f64) -> kira::sound::Sound {
+		let mut settings = PlayableSettings::new();
+		if cooldown > 0.0 {
+			settings = settings.cooldown(cooldown);
+		}
+		synth::blip(880.0, 0.25, settings)
+	}
+
+	/// Kira doesn't tell us whether a play command was ignored
+	/// because of the cooldown, so we keep our own timer that
+	/// follows the same rule to visualize what's happening.
+	fn trigger(&mut self) -> Result<(), Box<dyn Error>> {
+		self.audio_manager.play(self.sound_id, Default::default())?;
+		let now = Instant::now();
+		let accepted = match self.last_accepted_time {
+			Some(time) => now.duration_since(time).as_secs_f64() >= self.applied_cooldown,
+			None => true,
+		};
+		if accepted {
+			self.last_accepted_time = Some(now);
+		}
+		self.triggers.push_back((now, accepted));
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Trigger => self.trigger()?,
+			Message::SetSpamming(spamming) => {
+				self.spamming = spamming;
+			}
+			Message::SetCooldown(cooldown) => {
+				self.cooldown = cooldown.clamp(0.0, MAX_COOLDOWN);
+			}
+			Message::ApplyCooldown => {
+				// the cooldown is part of the sound's settings, so we
+				// have to replace the sound to change it
+				self.audio_manager.remove_sound(self.sound_id)?;
+				self.sound_id = self
+					.audio_manager
+					.add_sound(Self::create_sound(self.cooldown))?;
+				// the new sound hasn't played yet, so its cooldown
+				// starts out over
+				self.applied_cooldown = self.cooldown;
+				self.last_accepted_time = None;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	/// Whether the sound is being spammed from the UI timer.
+	pub fn plays_from_ui_timer(&self) -> bool {
+		self.spamming
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		if self.spamming && self.last_spam_time.elapsed() >= SPAM_INTERVAL {
+			self.last_spam_time = Instant::now();
+			self.trigger()?;
+		}
+		while let Some((time, _)) = self.triggers.front() {
+			if time.elapsed() > HISTORY_LENGTH {
+				self.triggers.pop_front();
+			} else {
+				break;
+			}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let trigger_button = Button::new(
+			&mut self.trigger_button,
+			Text::new("Play sound")
+				.width(Length::Fill)
+				.size(24)
+				.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.on_press(Message::Trigger)
+		.width(Length::Units(150))
+		.style(AppStyles);
+
+		let num_blocked = self
+			.triggers
+			.iter()
+			.filter(|(_, accepted)| !accepted)
+			.count();
+
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(trigger_button)
+						.push(Checkbox::new(self.spamming, "Spam", Message::SetSpamming)),
+				)
+				.push(
+					Row::new()
+						.max_width(400)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(Text::new(format!(
+							"Cooldown: {}ms",
+							(self.cooldown * 1000.0).round()
+						)))
+						.push(
+							Slider::new(
+								&mut self.cooldown_slider,
+								0.0..=MAX_COOLDOWN,
+								self.cooldown,
+								Message::SetCooldown,
+							)
+							.step(0.01)
+							.on_release(Message::ApplyCooldown)
+							.style(AppStyles),
+						),
+				)
+				.push(TriggerHistory {
+					triggers: self
+						.triggers
+						.iter()
+						.map(|(time, accepted)| Trigger {
+							age: time.elapsed().as_secs_f32() / HISTORY_LENGTH.as_secs_f32(),
+							accepted: *accepted,
+						})
+						.collect(),
+					cooldown: (self.applied_cooldown / HISTORY_LENGTH.as_secs_f64()) as f32,
+				})
+				.push(Text::new(format!(
+					"{} of the last {} triggers were blocked",
+					num_blocked,
+					self.triggers.len()
+				)))
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for RetriggerCooldownDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "retrigger_cooldown_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetCooldown(_) => Some("cooldown".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"cooldown" => vec![
+				Message::SetCooldown((value * MAX_COOLDOWN * 100.0).round() / 100.0),
+				Message::ApplyCooldown,
+			],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for RetriggerCooldownDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "retrigger_cooldown_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![Message::SetCooldown(self.cooldown), Message::ApplyCooldown]
+	}
+}
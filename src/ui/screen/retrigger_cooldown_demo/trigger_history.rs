@@ -0,0 +1,122 @@
+use iced::{mouse::Interaction, Background, Color, Length, Point, Rectangle, Size};
+use iced_graphics::{Backend, Defaults, Primitive, Renderer};
+use iced_native::{
+	layout::{Limits, Node},
+	Element, Layout, Widget,
+};
+
+const HEIGHT: f32 = 40.0;
+const DOT_RADIUS: f32 = 5.0;
+const BACKGROUND_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.05];
+const COOLDOWN_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.15];
+const ACCEPTED_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const BLOCKED_COLOR: [f32; 4] = [1.0, 0.0, 0.0, 0.75];
+
+/// A trigger attempt, positioned by how long ago it happened
+/// (0 = just now, 1 = the oldest moment shown).
+#[derive(Debug, Clone, Copy)]
+pub struct Trigger {
+	pub age: f32,
+	pub accepted: bool,
+}
+
+/// Shows recent trigger attempts scrolling from right to left,
+/// with the cooldown window after each accepted trigger shaded in.
+pub struct TriggerHistory {
+	pub triggers: Vec<Trigger>,
+	/// The length of the cooldown relative to the length of the history.
+	pub cooldown: f32,
+}
+
+impl<Message, B: Backend> Widget<Message, Renderer<B>> for TriggerHistory {
+	fn width(&self) -> Length {
+		Length::Fill
+	}
+
+	fn height(&self) -> Length {
+		Length::Shrink
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, limits: &Limits) -> Node {
+		Node::new(
+			limits
+				.width(Length::Fill)
+				.height(Length::Units(HEIGHT as u16))
+				.resolve(Size::ZERO),
+		)
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		_defaults: &Defaults,
+		layout: Layout<'_>,
+		_cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let x_of = |age: f32| bounds.x + bounds.width * (1.0 - age);
+		let mut primitives = vec![Primitive::Quad {
+			bounds,
+			background: Background::Color(BACKGROUND_COLOR.into()),
+			border_radius: 0.0,
+			border_width: 0.0,
+			border_color: Color::TRANSPARENT,
+		}];
+		for trigger in self.triggers.iter().filter(|trigger| trigger.accepted) {
+			let start = x_of(trigger.age);
+			let end = x_of((trigger.age - self.cooldown).max(0.0));
+			primitives.push(Primitive::Quad {
+				bounds: Rectangle::new(
+					Point::new(start, bounds.y),
+					Size::new(end - start, bounds.height),
+				),
+				background: Background::Color(COOLDOWN_COLOR.into()),
+				border_radius: 0.0,
+				border_width: 0.0,
+				border_color: Color::TRANSPARENT,
+			});
+		}
+		for trigger in &self.triggers {
+			primitives.push(Primitive::Quad {
+				bounds: Rectangle::new(
+					Point::new(
+						x_of(trigger.age) - DOT_RADIUS,
+						bounds.y + bounds.height / 2.0 - DOT_RADIUS,
+					),
+					Size::new(DOT_RADIUS * 2.0, DOT_RADIUS * 2.0),
+				),
+				background: Background::Color(
+					if trigger.accepted {
+						ACCEPTED_COLOR
+					} else {
+						BLOCKED_COLOR
+					}
+					.into(),
+				),
+				border_radius: DOT_RADIUS,
+				border_width: 0.0,
+				border_color: Color::TRANSPARENT,
+			});
+		}
+		(
+			Primitive::Clip {
+				bounds,
+				offset: Default::default(),
+				content: Box::new(Primitive::Group { primitives }),
+			},
+			Interaction::default(),
+		)
+	}
+
+	fn hash_layout(&self, state: &mut iced_native::Hasher) {
+		use std::hash::Hash;
+		HEIGHT.to_bits().hash(state)
+	}
+}
+
+impl<'a, Message, B: Backend> From<TriggerHistory> for Element<'a, Message, Renderer<B>> {
+	fn from(trigger_history: TriggerHistory) -> Self {
+		Element::new(trigger_history)
+	}
+}
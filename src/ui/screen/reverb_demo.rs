@@ -0,0 +1,337 @@
+use std::error::Error;
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Row, Slider, Text};
+use kira::{
+	instance::{InstanceId, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::{ParameterId, Tween},
+	playable::PlayableSettings,
+	sound::SoundId,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{
+		create_audio_manager,
+		effect::{
+			delay::{Delay, DelaySettings},
+			reverb::{Reverb, ReverbSettings},
+		},
+		sound_cache::SoundCache,
+		synth,
+		wet_dry::WetDry,
+	},
+	ui::{
+		common::{
+			mixer_graph::MixerGraph, screen_wrapper::ScreenWrapper, wet_dry_control::WetDryControl,
+		},
+		style::AppStyles,
+	},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &["drum fill demo/loop.ogg"];
+
+const EXPLANATION_TEXT: &str = "This demo puts a short delay \
+(as a pre-delay) and a reverb on a sub-track and plays each \
+sound twice at once: once on a dry track and once on the \
+reverb track.
+
+The wet level is a parameter that both instances' volumes are \
+tied to, with opposite mappings, so moving the slider is a \
+single parameter change no matter how many sounds are playing. \
+The room size is another parameter, read by the reverb itself. \
+Bypassing fades the wet level to 0 instead of removing the \
+effects, so the reverb tail isn't cut off with a click.";
+
+/// The time between the dry sound and the start of the reverb
+/// (in seconds), which keeps the sound clear in a big room.
+const PRE_DELAY_TIME: f64 = 0.03;
+const INITIAL_MIX: f64 = 0.4;
+const INITIAL_ROOM_SIZE: f64 = 0.85;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	PlayLoop,
+	StopLoop,
+	PlayBlip,
+	SetMix(f64),
+	SetBypassed(bool),
+	SetRoomSize(f64),
+}
+
+pub struct ReverbDemo {
+	audio_manager: AudioManager,
+	wet_dry: WetDry,
+	room_size_parameter_id: ParameterId,
+	room_size: f64,
+	loop_sound_id: SoundId,
+	blip_sound_id: SoundId,
+	/// The loop's dry and wet instances.
+	loop_instance_ids: Option<[InstanceId; 2]>,
+	screen_wrapper: ScreenWrapper<Message>,
+	wet_dry_control: WetDryControl<Message>,
+	loop_button: iced::button::State,
+	blip_button: iced::button::State,
+	room_size_slider: iced::slider::State,
+}
+
+impl ReverbDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let wet_dry = WetDry::new(&mut audio_manager, INITIAL_MIX)?;
+		let room_size_parameter_id = audio_manager.add_parameter(INITIAL_ROOM_SIZE)?;
+		audio_manager.add_effect_to_track(
+			wet_dry.wet_track_id(),
+			Delay::new(
+				DelaySettings::new()
+					.max_delay_time(PRE_DELAY_TIME)
+					.delay_time(PRE_DELAY_TIME)
+					.feedback(0.0),
+			),
+			Default::default(),
+		)?;
+		audio_manager.add_effect_to_track(
+			wet_dry.wet_track_id(),
+			Reverb::new(
+				ReverbSettings::new()
+					.room_size(room_size_parameter_id)
+					.damping(0.4),
+			),
+			Default::default(),
+		)?;
+		// WetDry plays every sound twice at the same time,
+		// which a cooldown would block
+		let loop_sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&std::env::current_dir()?.join("assets/drum fill demo/loop.ogg"))?
+				.to_sound(PlayableSettings {
+					cooldown: None,
+					..PlayableSettings::new().default_loop_start(0.0)
+				}),
+		)?;
+		let blip_sound_id = audio_manager.add_sound(synth::blip(
+			660.0,
+			0.3,
+			PlayableSettings {
+				cooldown: None,
+				..Default::default()
+			},
+		))?;
+		Ok(Self {
+			audio_manager,
+			wet_dry,
+			room_size_parameter_id,
+			room_size: INITIAL_ROOM_SIZE,
+			loop_sound_id,
+			blip_sound_id,
+			loop_instance_ids: None,
+			screen_wrapper: ScreenWrapper::new("Reverb demo".into(), Message::GoToDemoSelect),
+			wet_dry_control: WetDryControl::new(Message::SetMix, Message::SetBypassed),
+			loop_button: iced::button::State::new(),
+			blip_button: iced::button::State::new(),
+			room_size_slider: iced::slider::State::new(),
+		})
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::PlayLoop if self.loop_instance_ids.is_none() => {
+				self.loop_instance_ids = Some(self.wet_dry.play(
+					&mut self.audio_manager,
+					self.loop_sound_id,
+					Default::default(),
+				)?);
+			}
+			Message::StopLoop => {
+				for instance_id in self.loop_instance_ids.take().iter().flatten() {
+					self.audio_manager.stop_instance(
+						*instance_id,
+						StopInstanceSettings::new().fade_tween(Tween::linear(0.25)),
+					)?;
+				}
+			}
+			Message::PlayBlip => {
+				self.wet_dry.play(
+					&mut self.audio_manager,
+					self.blip_sound_id,
+					Default::default(),
+				)?;
+			}
+			Message::SetMix(mix) => {
+				self.wet_dry.set_mix(
+					&mut self.audio_manager,
+					mix.clamp(0.0, 1.0),
+					Some(Tween::linear(0.05)),
+				)?;
+			}
+			Message::SetBypassed(bypassed) => {
+				self.wet_dry
+					.set_bypassed(&mut self.audio_manager, bypassed)?;
+			}
+			Message::SetRoomSize(room_size) => {
+				self.room_size = room_size.clamp(0.0, 1.0);
+				self.audio_manager.set_parameter(
+					self.room_size_parameter_id,
+					self.room_size,
+					Some(Tween::linear(0.05)),
+				)?;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	fn mixer_graph(&self) -> MixerGraph {
+		let mix = if self.wet_dry.bypassed() {
+			0.0
+		} else {
+			self.wet_dry.mix() as f32
+		};
+		let loop_amount = if self.loop_instance_ids.is_some() {
+			1.0
+		} else {
+			0.0
+		};
+		MixerGraph::new()
+			.node("Drum loop", 0)
+			.node("Blip", 0)
+			.node("Dry track", 1)
+			.node("Pre-delay + reverb", 1)
+			.node("Main", 2)
+			.edge(0, 2, loop_amount * (1.0 - mix))
+			.edge(0, 3, loop_amount * mix)
+			.edge(1, 2, 1.0 - mix)
+			.edge(1, 3, mix)
+			.edge(2, 4, 1.0)
+			.edge(3, 4, 1.0)
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let mixer_graph = self.mixer_graph();
+		let looping = self.loop_instance_ids.is_some();
+		let mix = self.wet_dry.mix();
+		let bypassed = self.wet_dry.bypassed();
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Row::new()
+						.spacing(16)
+						.push(
+							Button::new(
+								&mut self.loop_button,
+								Text::new(if looping { "Stop loop" } else { "Play loop" })
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(if looping {
+								Message::StopLoop
+							} else {
+								Message::PlayLoop
+							})
+							.width(Length::Units(120))
+							.style(AppStyles),
+						)
+						.push(
+							Button::new(
+								&mut self.blip_button,
+								Text::new("Blip")
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(Message::PlayBlip)
+							.width(Length::Units(120))
+							.style(AppStyles),
+						),
+				)
+				.push(self.wet_dry_control.view(mix, bypassed))
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Room size: {:.2}", self.room_size))
+								.width(Length::Units(150)),
+						)
+						.push(
+							Slider::new(
+								&mut self.room_size_slider,
+								0.0..=1.0,
+								self.room_size,
+								Message::SetRoomSize,
+							)
+							.step(0.01)
+							.style(AppStyles),
+						),
+				)
+				.push(Column::new().max_width(600).push(mixer_graph))
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for ReverbDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "reverb_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetMix(_) => Some("Wet level".into()),
+			Message::SetRoomSize(_) => Some("Room size".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Wet level" => vec![Message::SetMix(value)],
+			"Room size" => vec![Message::SetRoomSize(value)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for ReverbDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "reverb_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetMix(self.wet_dry.mix()),
+			Message::SetBypassed(self.wet_dry.bypassed()),
+			Message::SetRoomSize(self.room_size),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for ReverbDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.loop_instance_ids.is_some(),
+			Message::PlayLoop,
+			Message::StopLoop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		self.loop_instance_ids.map(|_| "Reverb demo".into())
+	}
+}
@@ -0,0 +1,436 @@
+mod beat_lane;
+
+use std::{
+	collections::VecDeque,
+	error::Error,
+	time::{Duration, Instant},
+};
+
+use iced::{keyboard::KeyCode, Align, Button, Column, Length, Row, Slider, Text};
+use kira::{
+	manager::{AudioManager, AudioManagerSettings},
+	Tempo,
+};
+use serde::{Deserialize, Serialize};
+
+use self::beat_lane::{BeatLane, Hit};
+use crate::{
+	audio::create_audio_manager,
+	ui::{
+		common::{screen_wrapper::ScreenWrapper, transport::Transport},
+		style::AppStyles,
+	},
+};
+
+const EXPLANATION_TEXT: &str = "Press Play, wait for the four \
+count-in clicks to pass, and then press the space bar (or click \
+the lane) on every beat. Each press is scored by how many \
+milliseconds it was off the nearest beat, and beats that go by \
+without a press count as misses.
+
+The beat grid comes from the metronome. Kira reports every beat it \
+passes as an event, but the screen only checks for events once a \
+frame, so each one shows up a little late by a different amount. \
+The screen works out when the music started from every beat it \
+hears about and keeps the earliest answer, since that's the beat \
+that was delayed the least. Presses are timed against that grid.
+
+There's also some delay between kira reaching a beat and the \
+click coming out of the speakers, and between pressing a key and \
+the app seeing it. The offset slider takes that out of every \
+score. Play a few bars and press Calibrate to set it from your \
+average.";
+
+pub const TEMPO: Tempo = Tempo(100.0);
+/// The beats at the start that only count the player in.
+const COUNT_IN: usize = 4;
+const MAX_OFFSET: f64 = 150.0;
+/// How many of the latest presses the averages are taken from.
+const NUM_RECENT_RESULTS: usize = 16;
+/// How long after a beat a press still counts for it (in
+/// milliseconds).
+const MISS_WINDOW: f64 = 150.0;
+const HIT_KEY: KeyCode = KeyCode::Space;
+
+/// How well a press lined up with its beat.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Judgement {
+	Perfect,
+	Good,
+	Ok,
+	Miss,
+}
+
+impl Judgement {
+	const ALL: [Self; 4] = [Self::Perfect, Self::Good, Self::Ok, Self::Miss];
+
+	/// Judges a press by how far it was from its beat (in
+	/// milliseconds).
+	fn from_error(error: f64) -> Self {
+		match error.abs() {
+			error if error <= 30.0 => Self::Perfect,
+			error if error <= 70.0 => Self::Good,
+			error if error <= MISS_WINDOW => Self::Ok,
+			_ => Self::Miss,
+		}
+	}
+
+	fn index(self) -> usize {
+		Self::ALL
+			.iter()
+			.position(|judgement| *judgement == self)
+			.unwrap()
+	}
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::Perfect => "Perfect",
+			Self::Good => "Good",
+			Self::Ok => "OK",
+			Self::Miss => "Miss",
+		}
+	}
+
+	fn color(self) -> [f32; 4] {
+		match self {
+			Self::Perfect => [0.3, 1.0, 0.4, 1.0],
+			Self::Good => [0.6, 0.9, 1.0, 1.0],
+			Self::Ok => [1.0, 0.8, 0.2, 1.0],
+			Self::Miss => [1.0, 0.2, 0.2, 1.0],
+		}
+	}
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetMetronomeClick(bool),
+	Hit,
+	/// Sets how much later than the beat a press has to be to
+	/// count as on time (in milliseconds).
+	SetOffset(f64),
+	Calibrate,
+}
+
+pub struct RhythmGameDemo {
+	audio_manager: AudioManager,
+	transport: Transport,
+	/// When the metronome's first beat happened, as best the
+	/// screen can tell.
+	grid_start: Option<Instant>,
+	/// The first target beat that hasn't been hit or missed yet.
+	next_target: usize,
+	offset: f64,
+	counts: [usize; 4],
+	/// How far off the latest presses were (in milliseconds,
+	/// after the offset), not counting misses.
+	recent_errors: VecDeque<f64>,
+	/// The latest presses, for the lane.
+	recent_hits: VecDeque<Hit>,
+	last_result: Option<(Judgement, Option<f64>)>,
+	screen_wrapper: ScreenWrapper<Message>,
+	beat_lane: beat_lane::State,
+	offset_slider: iced::slider::State,
+	calibrate_button: iced::button::State,
+}
+
+impl RhythmGameDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(AudioManagerSettings {
+			metronome_settings: Transport::metronome_settings(TEMPO),
+			..audio_manager_settings
+		})?;
+		let mut transport = Transport::new(&mut audio_manager, TEMPO)?;
+		// there's nothing to play along to without the click
+		transport.set_click_enabled(&mut audio_manager, true)?;
+		Ok(Self {
+			audio_manager,
+			transport,
+			grid_start: None,
+			next_target: COUNT_IN,
+			offset: 0.0,
+			counts: [0; 4],
+			recent_errors: VecDeque::new(),
+			recent_hits: VecDeque::new(),
+			last_result: None,
+			screen_wrapper: ScreenWrapper::new("Rhythm game demo".into(), Message::GoToDemoSelect),
+			beat_lane: beat_lane::State::default(),
+			offset_slider: iced::slider::State::new(),
+			calibrate_button: iced::button::State::new(),
+		})
+	}
+
+	fn beat_position(&self) -> Option<f64> {
+		let grid_start = self.grid_start?;
+		Some(grid_start.elapsed().as_secs_f64() / TEMPO.beats_to_seconds(1.0))
+	}
+
+	fn record(&mut self, judgement: Judgement, beat: Option<f64>, error: Option<f64>) {
+		self.counts[judgement.index()] += 1;
+		self.last_result = Some((judgement, error));
+		if let Some(error) = error.filter(|_| judgement != Judgement::Miss) {
+			self.recent_errors.push_back(error);
+			if self.recent_errors.len() > NUM_RECENT_RESULTS {
+				self.recent_errors.pop_front();
+			}
+		}
+		if let Some(beat) = beat {
+			self.recent_hits.push_back(Hit {
+				beat,
+				color: judgement.color(),
+			});
+			if self.recent_hits.len() > NUM_RECENT_RESULTS {
+				self.recent_hits.pop_front();
+			}
+		}
+	}
+
+	fn hit(&mut self) {
+		let beat = match self.beat_position() {
+			Some(beat) if self.transport.is_playing() => beat,
+			_ => return,
+		};
+		let beat_length = TEMPO.beats_to_seconds(1.0) * 1000.0;
+		// the press counts for the beat it's closest to once the
+		// offset is taken out
+		let adjusted_beat = beat - self.offset / beat_length;
+		let target = adjusted_beat.round().max(0.0) as usize;
+		let error = (adjusted_beat - target as f64) * beat_length;
+		if target < self.next_target {
+			// the beat was already hit, missed, or part of the
+			// count-in
+			if target >= COUNT_IN {
+				self.record(Judgement::Miss, Some(beat), Some(error));
+			}
+			return;
+		}
+		self.miss_targets_before(target);
+		self.next_target = target + 1;
+		self.record(Judgement::from_error(error), Some(beat), Some(error));
+	}
+
+	fn miss_targets_before(&mut self, beat: usize) {
+		while self.next_target < beat {
+			self.record(Judgement::Miss, None, None);
+			self.next_target += 1;
+		}
+	}
+
+	fn reset(&mut self) {
+		self.grid_start = None;
+		self.next_target = COUNT_IN;
+		self.counts = [0; 4];
+		self.recent_errors.clear();
+		self.recent_hits.clear();
+		self.last_result = None;
+	}
+
+	fn average_error(&self) -> Option<f64> {
+		if self.recent_errors.is_empty() {
+			return None;
+		}
+		Some(self.recent_errors.iter().sum::<f64>() / self.recent_errors.len() as f64)
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if !self.transport.is_playing() => {
+				self.reset();
+				self.transport.start(&mut self.audio_manager)?;
+			}
+			Message::Stop => {
+				self.transport.stop(&mut self.audio_manager)?;
+			}
+			Message::SetMetronomeClick(enabled) => {
+				self.transport
+					.set_click_enabled(&mut self.audio_manager, enabled)?;
+			}
+			Message::Hit => self.hit(),
+			Message::SetOffset(offset) => self.offset = offset.clamp(-MAX_OFFSET, MAX_OFFSET),
+			Message::Calibrate => {
+				if let Some(average_error) = self.average_error() {
+					self.offset = (self.offset + average_error)
+						.round()
+						.clamp(-MAX_OFFSET, MAX_OFFSET);
+					self.recent_errors.clear();
+				}
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		self.transport.check_for_events(&mut self.audio_manager);
+		if !self.transport.is_playing() {
+			return Ok(());
+		}
+		// every estimate is the time of the last beat event minus
+		// the beats before it, so the earliest one is the event
+		// that arrived with the least delay
+		if let Some(position) = self.transport.beat_position() {
+			let estimate =
+				Instant::now() - Duration::from_secs_f64(TEMPO.beats_to_seconds(position));
+			self.grid_start = Some(match self.grid_start {
+				Some(grid_start) => grid_start.min(estimate),
+				None => estimate,
+			});
+		}
+		if let Some(beat) = self.beat_position() {
+			let beat_length = TEMPO.beats_to_seconds(1.0) * 1000.0;
+			let adjusted_beat = beat - (self.offset + MISS_WINDOW) / beat_length;
+			if adjusted_beat > 0.0 {
+				self.miss_targets_before(adjusted_beat.ceil() as usize);
+			}
+		}
+		Ok(())
+	}
+
+	fn result_text(&self) -> String {
+		match self.last_result {
+			Some((judgement, Some(error))) => format!(
+				"{} ({:+.0}ms, {})",
+				judgement.name(),
+				error,
+				if error < 0.0 { "early" } else { "late" }
+			),
+			Some((judgement, None)) => judgement.name().into(),
+			None if self.transport.is_playing() => "Get ready...".into(),
+			None => "Press Play to start".into(),
+		}
+	}
+
+	fn stats_text(&self) -> String {
+		let counts = Judgement::ALL
+			.iter()
+			.map(|judgement| format!("{}: {}", judgement.name(), self.counts[judgement.index()]))
+			.collect::<Vec<_>>()
+			.join("  ");
+		match self.average_error() {
+			Some(average_error) => {
+				let average_distance = self
+					.recent_errors
+					.iter()
+					.map(|error| error.abs())
+					.sum::<f64>() / self.recent_errors.len() as f64;
+				format!(
+					"{}\nLast {} presses: {:+.0}ms on average, {:.0}ms off the beat",
+					counts,
+					self.recent_errors.len(),
+					average_error,
+					average_distance
+				)
+			}
+			None => counts,
+		}
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let result_text = self.result_text();
+		let stats_text = self.stats_text();
+		let beat_position = self.beat_position().filter(|_| self.transport.is_playing());
+		let lane = BeatLane::new(&mut self.beat_lane, HIT_KEY)
+			.beat_position(beat_position, COUNT_IN)
+			.hits(self.recent_hits.iter().copied().collect())
+			.on_press(Message::Hit);
+		let mut calibrate_button =
+			Button::new(&mut self.calibrate_button, Text::new("Calibrate")).style(AppStyles);
+		if !self.recent_errors.is_empty() {
+			calibrate_button = calibrate_button.on_press(Message::Calibrate);
+		}
+		self.screen_wrapper.view_with_transport(
+			self.transport
+				.view(Message::Play, Message::Stop, Message::SetMetronomeClick),
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(Column::new().max_width(600).push(lane))
+				.push(Text::new(result_text).size(32))
+				.push(Text::new(stats_text))
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Offset: {:+.0}ms", self.offset))
+								.width(Length::Units(150)),
+						)
+						.push(
+							Slider::new(
+								&mut self.offset_slider,
+								-MAX_OFFSET..=MAX_OFFSET,
+								self.offset,
+								Message::SetOffset,
+							)
+							.step(1.0)
+							.style(AppStyles),
+						)
+						.push(calibrate_button),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for RhythmGameDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "rhythm_game_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetOffset(_) => Some("Offset".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Offset" => vec![Message::SetOffset(MAX_OFFSET * (value * 2.0 - 1.0))],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for RhythmGameDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "rhythm_game_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetMetronomeClick(self.transport.click_enabled()),
+			Message::SetOffset(self.offset),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for RhythmGameDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.transport.is_playing(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if self.transport.is_playing() {
+			Some("Rhythm game demo".into())
+		} else {
+			None
+		}
+	}
+}
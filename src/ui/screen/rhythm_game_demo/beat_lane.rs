@@ -0,0 +1,226 @@
+use iced::{
+	keyboard::KeyCode, mouse::Interaction, Background, Color, Length, Point, Rectangle, Size,
+};
+use iced_graphics::{Backend, Defaults, Primitive, Renderer};
+use iced_native::{
+	event::Status,
+	keyboard,
+	layout::{Limits, Node},
+	mouse, Clipboard, Element, Event, Layout, Widget,
+};
+
+const HEIGHT: f32 = 60.0;
+/// How far across the lane the hit line is (from 0 to 1).
+const HIT_LINE_POSITION: f32 = 0.25;
+/// How many beats fit across the lane.
+const BEATS_SHOWN: f32 = 4.0;
+const TARGET_RADIUS: f32 = 10.0;
+const HIT_RADIUS: f32 = 5.0;
+const BACKGROUND_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.05];
+const HIT_LINE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.5];
+const TARGET_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.3];
+const COUNT_IN_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.1];
+
+/// The keys being held down. Holding a key makes the OS repeat
+/// it, and only the first press should count as a hit. This has
+/// to outlive the widget, which is rebuilt every time the screen
+/// is drawn.
+#[derive(Debug, Default)]
+pub struct State {
+	held: bool,
+}
+
+/// A press the player made, and where it landed.
+#[derive(Debug, Copy, Clone)]
+pub struct Hit {
+	/// When the press happened (in beats).
+	pub beat: f64,
+	pub color: [f32; 4],
+}
+
+/// Scrolls the target beats from right to left towards a line,
+/// and marks where each press landed. Pressing the key (or
+/// clicking the lane) sends a message.
+pub struct BeatLane<'a, Message> {
+	state: &'a mut State,
+	key: KeyCode,
+	/// Where the music is (in beats), if it's playing.
+	beat_position: Option<f64>,
+	/// How many beats at the start are only there to count the
+	/// player in, and aren't targets.
+	count_in: usize,
+	hits: Vec<Hit>,
+	on_press: Option<Message>,
+}
+
+impl<'a, Message> BeatLane<'a, Message> {
+	pub fn new(state: &'a mut State, key: KeyCode) -> Self {
+		Self {
+			state,
+			key,
+			beat_position: None,
+			count_in: 0,
+			hits: vec![],
+			on_press: None,
+		}
+	}
+
+	pub fn beat_position(self, beat_position: Option<f64>, count_in: usize) -> Self {
+		Self {
+			beat_position,
+			count_in,
+			..self
+		}
+	}
+
+	pub fn hits(self, hits: Vec<Hit>) -> Self {
+		Self { hits, ..self }
+	}
+
+	pub fn on_press(self, on_press: Message) -> Self {
+		Self {
+			on_press: Some(on_press),
+			..self
+		}
+	}
+
+	fn dot(bounds: Rectangle, x: f32, radius: f32, color: [f32; 4]) -> Primitive {
+		Primitive::Quad {
+			bounds: Rectangle::new(
+				Point::new(x - radius, bounds.center_y() - radius),
+				Size::new(radius * 2.0, radius * 2.0),
+			),
+			background: Background::Color(color.into()),
+			border_radius: radius,
+			border_width: 0.0,
+			border_color: Color::TRANSPARENT,
+		}
+	}
+}
+
+impl<'a, Message: Clone, B: Backend> Widget<Message, Renderer<B>> for BeatLane<'a, Message> {
+	fn width(&self) -> Length {
+		Length::Fill
+	}
+
+	fn height(&self) -> Length {
+		Length::Shrink
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, limits: &Limits) -> Node {
+		Node::new(
+			limits
+				.width(Length::Fill)
+				.height(Length::Units(HEIGHT as u16))
+				.resolve(Size::ZERO),
+		)
+	}
+
+	fn on_event(
+		&mut self,
+		event: Event,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		messages: &mut Vec<Message>,
+		_renderer: &Renderer<B>,
+		_clipboard: Option<&dyn Clipboard>,
+	) -> Status {
+		let on_press = match &self.on_press {
+			Some(on_press) => on_press,
+			None => return Status::Ignored,
+		};
+		match event {
+			Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+				if layout.bounds().contains(cursor_position) =>
+			{
+				messages.push(on_press.clone());
+				Status::Captured
+			}
+			Event::Keyboard(keyboard::Event::KeyPressed { key_code, .. })
+				if key_code == self.key =>
+			{
+				if !self.state.held {
+					self.state.held = true;
+					messages.push(on_press.clone());
+				}
+				Status::Captured
+			}
+			Event::Keyboard(keyboard::Event::KeyReleased { key_code, .. })
+				if key_code == self.key =>
+			{
+				self.state.held = false;
+				Status::Ignored
+			}
+			_ => Status::Ignored,
+		}
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		_defaults: &Defaults,
+		layout: Layout<'_>,
+		_cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let hit_line_x = bounds.x + bounds.width * HIT_LINE_POSITION;
+		let beat_width = bounds.width / BEATS_SHOWN;
+		let mut primitives = vec![
+			Primitive::Quad {
+				bounds,
+				background: Background::Color(BACKGROUND_COLOR.into()),
+				border_radius: 0.0,
+				border_width: 0.0,
+				border_color: Color::TRANSPARENT,
+			},
+			Primitive::Quad {
+				bounds: Rectangle::new(
+					Point::new(hit_line_x - 1.0, bounds.y),
+					Size::new(2.0, bounds.height),
+				),
+				background: Background::Color(HIT_LINE_COLOR.into()),
+				border_radius: 0.0,
+				border_width: 0.0,
+				border_color: Color::TRANSPARENT,
+			},
+		];
+		if let Some(position) = self.beat_position {
+			let x_of = |beat: f64| hit_line_x + (beat - position) as f32 * beat_width;
+			let first_beat = (position - BEATS_SHOWN as f64).floor().max(0.0) as usize;
+			let last_beat = (position + BEATS_SHOWN as f64).ceil() as usize;
+			for beat in first_beat..=last_beat {
+				let color = if beat < self.count_in {
+					COUNT_IN_COLOR
+				} else {
+					TARGET_COLOR
+				};
+				primitives.push(Self::dot(bounds, x_of(beat as f64), TARGET_RADIUS, color));
+			}
+			for hit in &self.hits {
+				primitives.push(Self::dot(bounds, x_of(hit.beat), HIT_RADIUS, hit.color));
+			}
+		}
+		(
+			Primitive::Clip {
+				bounds,
+				offset: Default::default(),
+				content: Box::new(Primitive::Group { primitives }),
+			},
+			Interaction::default(),
+		)
+	}
+
+	fn hash_layout(&self, state: &mut iced_native::Hasher) {
+		use std::hash::Hash;
+		HEIGHT.to_bits().hash(state)
+	}
+}
+
+impl<'a, Message: Clone + 'a, B: Backend + 'a> From<BeatLane<'a, Message>>
+	for Element<'a, Message, Renderer<B>>
+{
+	fn from(beat_lane: BeatLane<'a, Message>) -> Self {
+		Element::new(beat_lane)
+	}
+}
@@ -0,0 +1,229 @@
+mod drift_graph;
+
+use std::{
+	collections::VecDeque,
+	error::Error,
+	time::{Duration, Instant},
+};
+
+use drift_graph::DriftGraph;
+use iced::{Align, Button, Checkbox, Column, HorizontalAlignment, Length, Text};
+use kira::{
+	instance::{InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::Tween,
+	sequence::{Sequence, SequenceInstanceId},
+	sound::SoundId,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, synth},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+const EXPLANATION_TEXT: &str = "This demo plays two clicks \
+at the same steady rate. The left click is scheduled by a \
+Kira sequence, which counts samples on the audio thread. \
+The right click is played by the UI whenever a timer says \
+it's due, which is only checked once per frame.
+
+The timer clicks wander around (jitter) and, unless drift \
+correction is on, slowly fall further and further behind \
+(drift), since each small delay is added to the next wait.";
+
+const CLICK_INTERVAL: Duration = Duration::from_millis(500);
+const HISTORY_LENGTH: usize = 60;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetCorrectDrift(bool),
+}
+
+struct Playback {
+	sequence_id: SequenceInstanceId,
+	start_time: Instant,
+	last_timer_click_time: Instant,
+	num_timer_clicks: u32,
+}
+
+pub struct SchedulingAccuracyDemo {
+	audio_manager: AudioManager,
+	// the clicks are separate sounds so the first two (which
+	// play at the same time) don't run into each other's cooldown
+	sequence_click_id: SoundId,
+	timer_click_id: SoundId,
+	correct_drift: bool,
+	playback: Option<Playback>,
+	drifts: VecDeque<f32>,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_button: iced::button::State,
+}
+
+impl SchedulingAccuracyDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let sequence_click_id =
+			audio_manager.add_sound(synth::blip(1000.0, 0.05, Default::default()))?;
+		let timer_click_id =
+			audio_manager.add_sound(synth::blip(1000.0, 0.05, Default::default()))?;
+		Ok(Self {
+			audio_manager,
+			sequence_click_id,
+			timer_click_id,
+			correct_drift: false,
+			playback: None,
+			drifts: VecDeque::new(),
+			screen_wrapper: ScreenWrapper::new(
+				"Scheduling accuracy demo".into(),
+				Message::GoToDemoSelect,
+			),
+			play_button: iced::button::State::new(),
+		})
+	}
+
+	fn play(&mut self) -> Result<(), Box<dyn Error>> {
+		let mut sequence = Sequence::<()>::new(Default::default());
+		sequence.start_loop();
+		sequence.play(self.sequence_click_id, InstanceSettings::new().panning(0.0));
+		sequence.wait(kira::Duration::Seconds(CLICK_INTERVAL.as_secs_f64()));
+		let (sequence_id, _) = self
+			.audio_manager
+			.start_sequence(sequence, Default::default())?;
+		self.audio_manager
+			.play(self.timer_click_id, InstanceSettings::new().panning(1.0))?;
+		let now = Instant::now();
+		self.playback = Some(Playback {
+			sequence_id,
+			start_time: now,
+			last_timer_click_time: now,
+			num_timer_clicks: 1,
+		});
+		self.drifts.clear();
+		Ok(())
+	}
+
+	fn stop(&mut self) -> Result<(), Box<dyn Error>> {
+		if let Some(playback) = self.playback.take() {
+			self.audio_manager.stop_sequence_and_instances(
+				playback.sequence_id,
+				StopInstanceSettings::new().fade_tween(Tween::linear(0.05)),
+			)?;
+		}
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play => self.play()?,
+			Message::Stop => self.stop()?,
+			Message::SetCorrectDrift(correct_drift) => {
+				self.correct_drift = correct_drift;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	/// Whether the timer clicks are being played from the UI
+	/// timer.
+	pub fn plays_from_ui_timer(&self) -> bool {
+		self.playback.is_some()
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		if let Some(playback) = &mut self.playback {
+			let scheduled_time = playback.start_time + CLICK_INTERVAL * playback.num_timer_clicks;
+			// the naive approach waits a full interval after the last
+			// click actually played, so lateness piles up
+			let due_time = if self.correct_drift {
+				scheduled_time
+			} else {
+				playback.last_timer_click_time + CLICK_INTERVAL
+			};
+			let now = Instant::now();
+			if now >= due_time {
+				self.audio_manager
+					.play(self.timer_click_id, InstanceSettings::new().panning(1.0))?;
+				playback.last_timer_click_time = now;
+				playback.num_timer_clicks += 1;
+				self.drifts
+					.push_back(now.duration_since(scheduled_time).as_secs_f32() * 1000.0);
+				if self.drifts.len() > HISTORY_LENGTH {
+					self.drifts.pop_front();
+				}
+			}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let play_button = Button::new(
+			&mut self.play_button,
+			Text::new(match self.playback {
+				Some(_) => "Stop",
+				None => "Play",
+			})
+			.width(Length::Fill)
+			.size(24)
+			.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.on_press(match self.playback {
+			Some(_) => Message::Stop,
+			None => Message::Play,
+		})
+		.width(Length::Units(150))
+		.style(AppStyles);
+
+		let latest_drift = self.drifts.back().copied().unwrap_or(0.0);
+		let average_jitter = if self.drifts.len() > 1 {
+			self.drifts
+				.iter()
+				.zip(self.drifts.iter().skip(1))
+				.map(|(previous, next)| (next - previous).abs())
+				.sum::<f32>()
+				/ (self.drifts.len() - 1) as f32
+		} else {
+			0.0
+		};
+
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(play_button)
+				.push(Checkbox::new(
+					self.correct_drift,
+					"Correct timer drift",
+					Message::SetCorrectDrift,
+				))
+				.push(Column::new().max_width(600).push(DriftGraph {
+					drifts: self.drifts.iter().copied().collect(),
+					capacity: HISTORY_LENGTH,
+				}))
+				.push(Text::new(format!(
+					"Timer click is {:.1}ms late (average jitter {:.1}ms), sequence click is sample-accurate",
+					latest_drift, average_jitter
+				)))
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for SchedulingAccuracyDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "scheduling_accuracy_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![Message::SetCorrectDrift(self.correct_drift)]
+	}
+}
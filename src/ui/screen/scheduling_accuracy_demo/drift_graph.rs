@@ -0,0 +1,130 @@
+use iced::{mouse::Interaction, Background, Color, Length, Point, Rectangle, Size};
+use iced_graphics::{Backend, Defaults, Primitive, Renderer};
+use iced_native::{
+	layout::{Limits, Node},
+	Element, HorizontalAlignment, Layout, VerticalAlignment, Widget,
+};
+
+const HEIGHT: f32 = 120.0;
+const DOT_RADIUS: f32 = 3.0;
+const BACKGROUND_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.05];
+const SEQUENCE_LINE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.75];
+const TIMER_DOT_COLOR: [f32; 4] = [1.0, 0.0, 0.0, 0.75];
+const LABEL_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.5];
+
+/// Plots how late each timer click was compared to when it
+/// should have played, with the sequence's clicks (which are
+/// always on time) as a flat line at zero.
+pub struct DriftGraph {
+	/// How late each timer click was in milliseconds, oldest first.
+	pub drifts: Vec<f32>,
+	/// The number of clicks that fit across the graph.
+	pub capacity: usize,
+}
+
+impl DriftGraph {
+	// the graph always shows at least 50ms so small amounts of
+	// jitter don't get blown up to fill the whole height
+	fn scale(&self) -> f32 {
+		self.drifts
+			.iter()
+			.fold(50.0, |scale, drift| f32::max(scale, drift.abs()))
+	}
+}
+
+impl<Message, B: Backend> Widget<Message, Renderer<B>> for DriftGraph {
+	fn width(&self) -> Length {
+		Length::Fill
+	}
+
+	fn height(&self) -> Length {
+		Length::Shrink
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, limits: &Limits) -> Node {
+		Node::new(
+			limits
+				.width(Length::Fill)
+				.height(Length::Units(HEIGHT as u16))
+				.resolve(Size::ZERO),
+		)
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		_defaults: &Defaults,
+		layout: Layout<'_>,
+		_cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let scale = self.scale();
+		// zero is in the middle so early clicks can be shown too
+		let y_of =
+			|drift: f32| bounds.y + bounds.height / 2.0 - drift / scale * bounds.height / 2.0;
+		let mut primitives = vec![
+			Primitive::Quad {
+				bounds,
+				background: Background::Color(BACKGROUND_COLOR.into()),
+				border_radius: 0.0,
+				border_width: 0.0,
+				border_color: Color::TRANSPARENT,
+			},
+			Primitive::Quad {
+				bounds: Rectangle::new(
+					Point::new(bounds.x, y_of(0.0) - 1.0),
+					Size::new(bounds.width, 2.0),
+				),
+				background: Background::Color(SEQUENCE_LINE_COLOR.into()),
+				border_radius: 0.0,
+				border_width: 0.0,
+				border_color: Color::TRANSPARENT,
+			},
+			Primitive::Text {
+				content: format!("+{}ms", scale.round()),
+				bounds: Rectangle::new(Point::new(bounds.x + 4.0, bounds.y + 4.0), bounds.size()),
+				color: LABEL_COLOR.into(),
+				size: 16.0,
+				font: Default::default(),
+				horizontal_alignment: HorizontalAlignment::Left,
+				vertical_alignment: VerticalAlignment::Top,
+			},
+		];
+		let spacing = bounds.width / self.capacity.max(1) as f32;
+		for (i, drift) in self.drifts.iter().enumerate() {
+			primitives.push(Primitive::Quad {
+				bounds: Rectangle::new(
+					Point::new(
+						bounds.x + spacing * (i as f32 + 0.5) - DOT_RADIUS,
+						y_of(*drift) - DOT_RADIUS,
+					),
+					Size::new(DOT_RADIUS * 2.0, DOT_RADIUS * 2.0),
+				),
+				background: Background::Color(TIMER_DOT_COLOR.into()),
+				border_radius: DOT_RADIUS,
+				border_width: 0.0,
+				border_color: Color::TRANSPARENT,
+			});
+		}
+		(
+			Primitive::Clip {
+				bounds,
+				offset: Default::default(),
+				content: Box::new(Primitive::Group { primitives }),
+			},
+			Interaction::default(),
+		)
+	}
+
+	fn hash_layout(&self, state: &mut iced_native::Hasher) {
+		use std::hash::Hash;
+		HEIGHT.to_bits().hash(state)
+	}
+}
+
+impl<'a, Message, B: Backend> From<DriftGraph> for Element<'a, Message, Renderer<B>> {
+	fn from(drift_graph: DriftGraph) -> Self {
+		Element::new(drift_graph)
+	}
+}
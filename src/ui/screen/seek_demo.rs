@@ -0,0 +1,246 @@
+use std::{error::Error, time::Instant};
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Text};
+use kira::{
+	instance::{InstanceId, InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::Tween,
+	playable::PlayableSettings,
+	sound::SoundId,
+	Tempo,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, decoded_sound::DecodedSound, sound_cache::SoundCache},
+	ui::{
+		common::{
+			screen_wrapper::ScreenWrapper,
+			waveform::{self, Waveform},
+		},
+		style::AppStyles,
+	},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &[
+	"underwater demo/drums.ogg",
+	"underwater demo/bass.ogg",
+	"underwater demo/pad.ogg",
+	"underwater demo/lead.ogg",
+];
+
+const EXPLANATION_TEXT: &str = "Click the waveform to jump to \
+a new spot, or hold the mouse down and drag to scrub through the \
+song. While the song is stopped, clicking picks where it will \
+start.
+
+Jumping doesn't restart anything: the screen calls \
+seek_instance_to, which moves the playing instance to the new \
+position. Kira doesn't report where an instance is, so the \
+screen keeps track of the position itself, starting over from \
+the new spot every time it seeks. Scrubbing is just a lot of \
+seeks in a row, one for every mouse movement.";
+
+pub const TEMPO: Tempo = Tempo(85.0);
+const BEATS_PER_BAR: f64 = 4.0;
+const NUM_BEATS: usize = 16;
+const NUM_PEAKS: usize = 200;
+const PLAYHEAD_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	/// Moves the playhead to a point in the song (as a fraction of
+	/// its length, from 0 to 1).
+	Seek(f32),
+}
+
+struct Playback {
+	instance_id: InstanceId,
+	/// Where the instance was the last time it was started or
+	/// moved (in seconds).
+	position: f64,
+	since: Instant,
+}
+
+pub struct SeekDemo {
+	audio_manager: AudioManager,
+	sound_id: SoundId,
+	duration: f64,
+	peaks: Vec<f32>,
+	/// Where playback starts when the song is stopped (in seconds).
+	cued_position: f64,
+	playback: Option<Playback>,
+	screen_wrapper: ScreenWrapper<Message>,
+	waveform: waveform::State,
+	play_button: iced::button::State,
+}
+
+impl SeekDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let assets_base_dir = std::env::current_dir()?.join("assets");
+		let mut stems = vec![];
+		for path in ASSETS {
+			stems.push(sound_cache.get(&assets_base_dir.join(path))?);
+		}
+		let song = DecodedSound::mix(&stems.iter().map(|stem| &**stem).collect::<Vec<_>>());
+		let sound_id = audio_manager
+			.add_sound(song.to_sound(PlayableSettings::new().default_loop_start(0.0)))?;
+		Ok(Self {
+			audio_manager,
+			sound_id,
+			duration: song.duration(),
+			peaks: song.peaks(NUM_PEAKS),
+			cued_position: 0.0,
+			playback: None,
+			screen_wrapper: ScreenWrapper::new(
+				"Seek and scrub demo".into(),
+				Message::GoToDemoSelect,
+			),
+			waveform: waveform::State::default(),
+			play_button: iced::button::State::new(),
+		})
+	}
+
+	/// Kira doesn't report the playback position of instances,
+	/// so we work it out from when the instance was last moved.
+	/// The song loops, so the position wraps around at the end.
+	fn position(&self) -> f64 {
+		match &self.playback {
+			Some(playback) => {
+				(playback.position + playback.since.elapsed().as_secs_f64()) % self.duration
+			}
+			None => self.cued_position,
+		}
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if self.playback.is_none() => {
+				let instance_id = self.audio_manager.play(
+					self.sound_id,
+					InstanceSettings::new().start_position(self.cued_position),
+				)?;
+				self.playback = Some(Playback {
+					instance_id,
+					position: self.cued_position,
+					since: Instant::now(),
+				});
+			}
+			Message::Stop => {
+				self.cued_position = self.position();
+				if let Some(playback) = self.playback.take() {
+					self.audio_manager.stop_instance(
+						playback.instance_id,
+						StopInstanceSettings::new().fade_tween(Tween::linear(0.1)),
+					)?;
+				}
+			}
+			Message::Seek(fraction) => {
+				let position = self.duration * fraction.clamp(0.0, 1.0) as f64;
+				match &mut self.playback {
+					Some(playback) => {
+						self.audio_manager
+							.seek_instance_to(playback.instance_id, position)?;
+						playback.position = position;
+						playback.since = Instant::now();
+					}
+					None => self.cued_position = position,
+				}
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	// nothing to check, but the screen has to be redrawn
+	// regularly for the playhead to move
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let duration = self.duration;
+		let position = self.position();
+		let playing = self.playback.is_some();
+		let beat = position / TEMPO.beats_to_seconds(1.0);
+		let waveform = Waveform::new(&self.peaks)
+			.grid_lines(
+				(0..=NUM_BEATS)
+					.map(|beat| (TEMPO.beats_to_seconds(beat as f64) / duration) as f32)
+					.collect(),
+			)
+			.marker((position / duration) as f32, PLAYHEAD_COLOR)
+			.on_press(Message::Seek)
+			.draggable(&mut self.waveform);
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(waveform)
+				.push(
+					Button::new(
+						&mut self.play_button,
+						Text::new(if playing { "Stop" } else { "Play" })
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(if playing {
+						Message::Stop
+					} else {
+						Message::Play
+					})
+					.width(Length::Units(100))
+					.style(AppStyles),
+				)
+				.push(Text::new(format!(
+					"{:.2}s / {:.2}s - beat {:.2} (bar {}, beat {:.2})",
+					position,
+					duration,
+					beat,
+					(beat / BEATS_PER_BAR).floor() + 1.0,
+					beat % BEATS_PER_BAR + 1.0,
+				)))
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for SeekDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "seek_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![Message::Seek((self.position() / self.duration) as f32)]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for SeekDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.playback.is_some(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		self.playback.as_ref().map(|_| "Seek and scrub demo".into())
+	}
+}
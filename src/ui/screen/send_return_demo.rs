@@ -0,0 +1,424 @@
+use std::error::Error;
+
+use iced::{Align, Column, Length, Row, Slider, Text};
+use kira::{
+	arrangement::{Arrangement, ArrangementId, LoopArrangementSettings},
+	instance::{InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	mixer::SubTrackId,
+	parameter::{Mapping, ParameterId, Tween},
+	playable::PlayableSettings,
+	Tempo, Value,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{
+		create_audio_manager,
+		effect::{
+			delay::{Delay, DelaySettings},
+			reverb::{Reverb, ReverbSettings},
+		},
+		sound_cache::SoundCache,
+	},
+	ui::{
+		common::{mixer_graph::MixerGraph, screen_wrapper::ScreenWrapper, transport::Transport},
+		style::AppStyles,
+	},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &[
+	"underwater demo/drums.ogg",
+	"underwater demo/bass.ogg",
+	"underwater demo/pad.ogg",
+	"underwater demo/lead.ogg",
+];
+
+const EXPLANATION_TEXT: &str = "This demo sends different \
+amounts of each stem to two shared return tracks, one with a \
+delay on it and one with a reverb. Instead of every sound \
+getting its own copy of the effects, they all share one of \
+each, and the send amounts decide how much echo and how much \
+room each sound gets. Keeping the effects on separate returns \
+means a stem can be sent to just one of them - the lead can \
+echo without the drums washing out in the reverb.
+
+Kira's sub-tracks always output to the main track, so each \
+send is another synced instance of the stem playing on the \
+return track, with its volume tied to a parameter. With two \
+returns, each stem plays three times: once dry and once per \
+return. The diagram shows every route, with thicker lines for \
+higher send levels.";
+
+pub const TEMPO: Tempo = Tempo(85.0);
+const SOURCE_NAMES: [&str; 4] = ["Drums", "Bass", "Pad", "Lead"];
+const RETURN_NAMES: [&str; 2] = ["Delay", "Reverb"];
+/// How much each source starts out sending to each return.
+const INITIAL_SEND_AMOUNTS: [[f64; 2]; 4] = [[0.0, 0.2], [0.0, 0.0], [0.2, 0.6], [0.5, 0.3]];
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetMetronomeClick(bool),
+	/// Sets how much a source (the first index) sends to a
+	/// return (the second index).
+	SetSendAmount(usize, usize, f64),
+}
+
+/// A copy of a source playing on one of the return tracks.
+struct Send {
+	loop_id: ArrangementId,
+	parameter_id: ParameterId,
+	amount: f64,
+	slider: iced::slider::State,
+}
+
+struct Source {
+	dry_loop_id: ArrangementId,
+	/// The source's send to each return, in the same order as
+	/// `RETURN_NAMES`.
+	sends: Vec<Send>,
+}
+
+pub struct SendReturnDemo {
+	audio_manager: AudioManager,
+	sources: Vec<Source>,
+	transport: Transport,
+	screen_wrapper: ScreenWrapper<Message>,
+}
+
+impl SendReturnDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(AudioManagerSettings {
+			metronome_settings: Transport::metronome_settings(TEMPO),
+			..audio_manager_settings
+		})?;
+		let transport = Transport::new(&mut audio_manager, TEMPO)?;
+		let delay_track_id = audio_manager.add_sub_track(Default::default())?;
+		audio_manager.add_effect_to_track(
+			delay_track_id,
+			Delay::new(
+				DelaySettings::new()
+					.max_delay_time(1.0)
+					.delay_time(TEMPO.beats_to_seconds(0.75))
+					.feedback(0.35),
+			),
+			Default::default(),
+		)?;
+		let reverb_track_id = audio_manager.add_sub_track(Default::default())?;
+		audio_manager.add_effect_to_track(
+			reverb_track_id,
+			Reverb::new(ReverbSettings::new().room_size(0.85).damping(0.4)),
+			Default::default(),
+		)?;
+		let return_track_ids = [delay_track_id, reverb_track_id];
+		let assets_base_dir = std::env::current_dir()?.join("assets/underwater demo");
+		let mut sources = vec![];
+		for (name, send_amounts) in SOURCE_NAMES.iter().zip(&INITIAL_SEND_AMOUNTS) {
+			let sound_id = audio_manager.add_sound(
+				sound_cache
+					.get(&assets_base_dir.join(format!("{}.ogg", name.to_lowercase())))?
+					.to_sound(
+						PlayableSettings::new().semantic_duration(TEMPO.beats_to_seconds(16.0)),
+					),
+			)?;
+			sources.push(Self::create_source(
+				&mut audio_manager,
+				sound_id,
+				&return_track_ids,
+				send_amounts,
+			)?);
+		}
+		Ok(Self {
+			audio_manager,
+			sources,
+			transport,
+			screen_wrapper: ScreenWrapper::new("Send/return demo".into(), Message::GoToDemoSelect),
+		})
+	}
+
+	// the dry and send loops have to be separate arrangements,
+	// since playing the same one twice at once would run into
+	// its cooldown
+	fn create_source(
+		audio_manager: &mut AudioManager,
+		sound_id: kira::sound::SoundId,
+		return_track_ids: &[SubTrackId],
+		send_amounts: &[f64],
+	) -> Result<Source, Box<dyn Error>> {
+		let mut sends = vec![];
+		for (return_track_id, amount) in return_track_ids.iter().zip(send_amounts) {
+			sends.push(Send {
+				loop_id: audio_manager.add_arrangement(Arrangement::new_loop(
+					sound_id,
+					LoopArrangementSettings::new().default_track(*return_track_id),
+				))?,
+				parameter_id: audio_manager.add_parameter(*amount)?,
+				amount: *amount,
+				slider: iced::slider::State::new(),
+			});
+		}
+		Ok(Source {
+			dry_loop_id: audio_manager
+				.add_arrangement(Arrangement::new_loop(sound_id, Default::default()))?,
+			sends,
+		})
+	}
+
+	fn play(&mut self) -> Result<(), Box<dyn Error>> {
+		// the commands are all sent at once, so the audio thread
+		// starts the dry and send instances on the same sample
+		for source in &self.sources {
+			self.audio_manager
+				.play(source.dry_loop_id, Default::default())?;
+			for send in &source.sends {
+				self.audio_manager.play(
+					send.loop_id,
+					InstanceSettings::new()
+						.volume(Value::Parameter(send.parameter_id, Mapping::default())),
+				)?;
+			}
+		}
+		self.transport.start(&mut self.audio_manager)?;
+		Ok(())
+	}
+
+	fn stop(&mut self) -> Result<(), Box<dyn Error>> {
+		for source in &self.sources {
+			let send_loop_ids = source.sends.iter().map(|send| send.loop_id);
+			for arrangement_id in std::iter::once(source.dry_loop_id).chain(send_loop_ids) {
+				self.audio_manager.stop_instances_of(
+					arrangement_id.into(),
+					StopInstanceSettings::new().fade_tween(Tween::linear(0.5)),
+				)?;
+			}
+		}
+		self.transport.stop(&mut self.audio_manager)?;
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play => self.play()?,
+			Message::Stop => self.stop()?,
+			Message::SetMetronomeClick(enabled) => {
+				self.transport
+					.set_click_enabled(&mut self.audio_manager, enabled)?;
+			}
+			Message::SetSendAmount(source_index, return_index, amount) => {
+				// session files and MIDI bindings can name a send that
+				// doesn't exist, which is ignored
+				let send = match self
+					.sources
+					.get_mut(source_index)
+					.and_then(|source| source.sends.get_mut(return_index))
+				{
+					Some(send) => send,
+					None => return Ok(()),
+				};
+				send.amount = amount.clamp(0.0, 1.0);
+				self.audio_manager.set_parameter(
+					send.parameter_id,
+					send.amount,
+					Some(Tween::linear(0.05)),
+				)?;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		self.transport.check_for_events(&mut self.audio_manager);
+		Ok(())
+	}
+
+	fn mixer_graph(&self) -> MixerGraph {
+		let first_return_node = self.sources.len();
+		let main_node = first_return_node + RETURN_NAMES.len();
+		let graph = SOURCE_NAMES
+			.iter()
+			.fold(MixerGraph::new(), |graph, name| graph.node(*name, 0));
+		let graph = RETURN_NAMES
+			.iter()
+			.fold(graph, |graph, name| {
+				graph.node(format!("{} return", name), 1)
+			})
+			.node("Main", 2);
+		let graph = (0..RETURN_NAMES.len()).fold(graph, |graph, return_index| {
+			graph.edge(first_return_node + return_index, main_node, 1.0)
+		});
+		self.sources
+			.iter()
+			.enumerate()
+			.fold(graph, |graph, (source_index, source)| {
+				source.sends.iter().enumerate().fold(
+					graph.edge(source_index, main_node, 1.0),
+					|graph, (return_index, send)| {
+						graph.edge(
+							source_index,
+							first_return_node + return_index,
+							send.amount as f32,
+						)
+					},
+				)
+			})
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let mixer_graph = self.mixer_graph();
+
+		let header = RETURN_NAMES.iter().fold(
+			Row::new()
+				.spacing(16)
+				.push(Text::new("").width(Length::Units(80))),
+			|row, name| row.push(Text::new(format!("{} send", name)).width(Length::Fill)),
+		);
+		let send_sliders = self
+			.sources
+			.iter_mut()
+			.zip(SOURCE_NAMES.iter())
+			.enumerate()
+			.fold(
+				Column::new().spacing(8).max_width(600).push(header),
+				|column, (source_index, (source, name))| {
+					let row = Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(Text::new(*name).width(Length::Units(80)));
+					column.push(source.sends.iter_mut().enumerate().fold(
+						row,
+						|row, (return_index, send)| {
+							row.push(
+								Row::new()
+									.width(Length::Fill)
+									.spacing(8)
+									.align_items(Align::Center)
+									.push(
+										Text::new(format!("{}%", (send.amount * 100.0).round()))
+											.width(Length::Units(50)),
+									)
+									.push(
+										Slider::new(
+											&mut send.slider,
+											0.0..=1.0,
+											send.amount,
+											move |amount| {
+												Message::SetSendAmount(
+													source_index,
+													return_index,
+													amount,
+												)
+											},
+										)
+										.step(0.01)
+										.style(AppStyles),
+									),
+							)
+						},
+					))
+				},
+			);
+
+		self.screen_wrapper.view_with_transport(
+			self.transport
+				.view(Message::Play, Message::Stop, Message::SetMetronomeClick),
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(send_sliders)
+				.push(Column::new().max_width(600).push(mixer_graph))
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+/// The MIDI learn name for one source's send to one return,
+/// like "Lead delay send".
+#[cfg(feature = "midi")]
+fn send_control_name(source_index: usize, return_index: usize) -> String {
+	format!(
+		"{} {} send",
+		SOURCE_NAMES[source_index],
+		RETURN_NAMES[return_index].to_lowercase()
+	)
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for SendReturnDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "send_return_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetSendAmount(source_index, return_index, _) => {
+				Some(send_control_name(*source_index, *return_index))
+			}
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		let mut messages = vec![];
+		for source_index in 0..SOURCE_NAMES.len() {
+			for return_index in 0..RETURN_NAMES.len() {
+				if control == send_control_name(source_index, return_index) {
+					messages.push(Message::SetSendAmount(source_index, return_index, value));
+				}
+			}
+		}
+		messages
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for SendReturnDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "send_return_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		let mut messages = vec![Message::SetMetronomeClick(self.transport.click_enabled())];
+		for (source_index, source) in self.sources.iter().enumerate() {
+			for (return_index, send) in source.sends.iter().enumerate() {
+				messages.push(Message::SetSendAmount(
+					source_index,
+					return_index,
+					send.amount,
+				));
+			}
+		}
+		messages
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for SendReturnDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.transport.is_playing(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if !self.transport.is_playing() {
+			return None;
+		}
+		Some("Send/return demo".into())
+	}
+}
@@ -0,0 +1,365 @@
+use std::error::Error;
+
+use iced::{Align, Button, Checkbox, Column, HorizontalAlignment, Length, Radio, Row, Text};
+
+use crate::{
+	preferences::{AudioCapacities, BeatDisplayPalette, BeatDisplayStyle, OutputMode, Preferences},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+const EXPLANATION_TEXT: &str = "These settings control how much \
+room each demo's AudioManager sets aside for instances, sequences, \
+parameters, and commands. They take effect the next time you open \
+a demo.
+
+The command queue has a fixed size: if a demo sends more commands \
+than it can hold before the audio thread gets to them, Kira returns \
+a CommandQueueFull error, which is shown at the top of the window. \
+The other capacities are only preallocated - going over them makes \
+the audio thread allocate more memory, which can cause glitches \
+on slower machines.
+
+The beat display options change how the drum fill and odd time \
+signature demos show the current beat and the beats they mark \
+(a coming drum fill, or where each group starts), and the \
+output mode changes how the spatial demos pan sounds.";
+
+const MIN_CAPACITY: usize = 1;
+const MAX_CAPACITY: usize = 4096;
+
+#[derive(Debug, Copy, Clone)]
+pub enum Capacity {
+	Instances,
+	Sequences,
+	Parameters,
+	Commands,
+}
+
+impl Capacity {
+	const ALL: [Self; 4] = [
+		Self::Instances,
+		Self::Sequences,
+		Self::Parameters,
+		Self::Commands,
+	];
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::Instances => "Instances",
+			Self::Sequences => "Sequences",
+			Self::Parameters => "Parameters",
+			Self::Commands => "Commands",
+		}
+	}
+
+	fn value(self, capacities: &AudioCapacities) -> usize {
+		match self {
+			Self::Instances => capacities.num_instances,
+			Self::Sequences => capacities.num_sequences,
+			Self::Parameters => capacities.num_parameters,
+			Self::Commands => capacities.num_commands,
+		}
+	}
+
+	fn value_mut(self, capacities: &mut AudioCapacities) -> &mut usize {
+		match self {
+			Self::Instances => &mut capacities.num_instances,
+			Self::Sequences => &mut capacities.num_sequences,
+			Self::Parameters => &mut capacities.num_parameters,
+			Self::Commands => &mut capacities.num_commands,
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+	GoToDemoSelect,
+	Halve(Capacity),
+	Double(Capacity),
+	SetBeatDisplayPalette(BeatDisplayPalette),
+	SetBeatDisplayShapes(bool),
+	SetOutputMode(OutputMode),
+	#[cfg(feature = "remote")]
+	SetRemoteCommandAllowed(&'static str, bool),
+	#[cfg(feature = "remote")]
+	SetRemoteOriginInput(String),
+	/// Adds the origin in the text box to the web pages allowed to
+	/// connect to the remote control server.
+	#[cfg(feature = "remote")]
+	AllowRemoteOrigin,
+	#[cfg(feature = "remote")]
+	RemoveRemoteOrigin(usize),
+	ResetToDefaults,
+}
+
+#[derive(Default)]
+struct CapacityButtons {
+	halve: iced::button::State,
+	double: iced::button::State,
+}
+
+pub struct Settings {
+	preferences: Preferences,
+	screen_wrapper: ScreenWrapper<Message>,
+	capacity_buttons: [CapacityButtons; 4],
+	reset_button: iced::button::State,
+	#[cfg(feature = "remote")]
+	remote_origin_input: String,
+	#[cfg(feature = "remote")]
+	remote_origin_input_state: iced::text_input::State,
+	#[cfg(feature = "remote")]
+	allow_remote_origin_button: iced::button::State,
+	#[cfg(feature = "remote")]
+	remove_remote_origin_buttons: Vec<iced::button::State>,
+}
+
+impl Settings {
+	pub fn new(preferences: Preferences) -> Self {
+		Self {
+			preferences,
+			screen_wrapper: ScreenWrapper::new("Settings".into(), Message::GoToDemoSelect),
+			capacity_buttons: Default::default(),
+			reset_button: iced::button::State::new(),
+			#[cfg(feature = "remote")]
+			remote_origin_input: String::new(),
+			#[cfg(feature = "remote")]
+			remote_origin_input_state: iced::text_input::State::new(),
+			#[cfg(feature = "remote")]
+			allow_remote_origin_button: iced::button::State::new(),
+			#[cfg(feature = "remote")]
+			remove_remote_origin_buttons: vec![],
+		}
+	}
+
+	/// The preferences with the changes made on this screen.
+	pub fn preferences(&self) -> &Preferences {
+		&self.preferences
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Halve(capacity) => {
+				let value = capacity.value_mut(&mut self.preferences.audio_capacities);
+				*value = (*value / 2).max(MIN_CAPACITY);
+			}
+			Message::Double(capacity) => {
+				let value = capacity.value_mut(&mut self.preferences.audio_capacities);
+				*value = (*value * 2).min(MAX_CAPACITY);
+			}
+			Message::SetBeatDisplayPalette(palette) => {
+				self.preferences.beat_display_style.palette = palette;
+			}
+			Message::SetBeatDisplayShapes(shapes) => {
+				self.preferences.beat_display_style.shapes = shapes;
+			}
+			Message::SetOutputMode(output_mode) => {
+				self.preferences.output_mode = output_mode;
+			}
+			#[cfg(feature = "remote")]
+			Message::SetRemoteCommandAllowed(command, allowed) => {
+				let allowed_commands = &mut self.preferences.remote_allowed_commands;
+				allowed_commands.retain(|allowed_command| allowed_command != command);
+				if allowed {
+					allowed_commands.push(command.into());
+				}
+			}
+			#[cfg(feature = "remote")]
+			Message::SetRemoteOriginInput(origin) => self.remote_origin_input = origin,
+			#[cfg(feature = "remote")]
+			Message::AllowRemoteOrigin => {
+				// browsers send the origin without a trailing slash
+				let origin = self.remote_origin_input.trim().trim_end_matches('/');
+				let allowed_origins = &mut self.preferences.remote_allowed_origins;
+				if !origin.is_empty() && !allowed_origins.iter().any(|allowed| allowed == origin) {
+					allowed_origins.push(origin.into());
+				}
+				self.remote_origin_input.clear();
+			}
+			#[cfg(feature = "remote")]
+			Message::RemoveRemoteOrigin(index)
+				if index < self.preferences.remote_allowed_origins.len() =>
+			{
+				self.preferences.remote_allowed_origins.remove(index);
+			}
+			Message::ResetToDefaults => {
+				self.preferences.audio_capacities = AudioCapacities::default();
+				self.preferences.beat_display_style = BeatDisplayStyle::default();
+				self.preferences.output_mode = OutputMode::default();
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let capacities = self.preferences.audio_capacities;
+		let rows = self.capacity_buttons.iter_mut().zip(&Capacity::ALL).fold(
+			Column::new().spacing(8),
+			|column, (buttons, capacity)| {
+				let value = capacity.value(&capacities);
+				let mut halve_button = Button::new(&mut buttons.halve, Text::new("/2"))
+					.width(Length::Units(40))
+					.style(AppStyles);
+				if value > MIN_CAPACITY {
+					halve_button = halve_button.on_press(Message::Halve(*capacity));
+				}
+				let mut double_button = Button::new(&mut buttons.double, Text::new("x2"))
+					.width(Length::Units(40))
+					.style(AppStyles);
+				if value < MAX_CAPACITY {
+					double_button = double_button.on_press(Message::Double(*capacity));
+				}
+				column.push(
+					Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(Text::new(capacity.name()).width(Length::Units(120)))
+						.push(halve_button)
+						.push(
+							Text::new(value.to_string())
+								.width(Length::Units(60))
+								.horizontal_alignment(HorizontalAlignment::Center),
+						)
+						.push(double_button),
+				)
+			},
+		);
+		let beat_display_style = self.preferences.beat_display_style;
+		let output_mode = self.preferences.output_mode;
+		let output_mode_radios = OutputMode::ALL.iter().fold(
+			Row::new().spacing(16).align_items(Align::Center),
+			|row, option| {
+				row.push(Radio::new(
+					*option,
+					option.name(),
+					Some(output_mode),
+					Message::SetOutputMode,
+				))
+			},
+		);
+		let palette_radios = BeatDisplayPalette::ALL.iter().fold(
+			Row::new().spacing(16).align_items(Align::Center),
+			|row, palette| {
+				row.push(Radio::new(
+					*palette,
+					palette.name(),
+					Some(beat_display_style.palette),
+					Message::SetBeatDisplayPalette,
+				))
+			},
+		);
+		#[cfg(feature = "remote")]
+		let allowed_commands = &self.preferences.remote_allowed_commands;
+		#[cfg(feature = "remote")]
+		let remote_checkboxes = crate::remote::RemoteCommand::NAMES.iter().fold(
+			Row::new().spacing(16).align_items(Align::Center),
+			|row, &command| {
+				let allowed = allowed_commands
+					.iter()
+					.any(|allowed_command| allowed_command == command);
+				row.push(Checkbox::new(allowed, command, move |allowed| {
+					Message::SetRemoteCommandAllowed(command, allowed)
+				}))
+			},
+		);
+		let contents = Column::new()
+			.spacing(16)
+			.align_items(Align::Center)
+			.push(rows)
+			.push(
+				Row::new()
+					.spacing(16)
+					.align_items(Align::Center)
+					.push(Text::new("Beat display").width(Length::Units(120)))
+					.push(palette_radios),
+			)
+			.push(Checkbox::new(
+				beat_display_style.shapes,
+				"Use shapes as well as colors",
+				Message::SetBeatDisplayShapes,
+			))
+			.push(
+				Row::new()
+					.spacing(16)
+					.align_items(Align::Center)
+					.push(Text::new("Output").width(Length::Units(120)))
+					.push(output_mode_radios),
+			);
+		#[cfg(feature = "remote")]
+		let contents = {
+			let allowed_origins = &self.preferences.remote_allowed_origins;
+			self.remove_remote_origin_buttons
+				.resize_with(allowed_origins.len(), iced::button::State::new);
+			let origins = self
+				.remove_remote_origin_buttons
+				.iter_mut()
+				.zip(allowed_origins)
+				.enumerate()
+				.fold(
+					Column::new().spacing(8),
+					|column, (index, (button, origin))| {
+						column.push(
+							Row::new()
+								.spacing(16)
+								.align_items(Align::Center)
+								.push(Text::new(origin.as_str()).width(Length::Units(250)))
+								.push(
+									Button::new(button, Text::new("Remove"))
+										.on_press(Message::RemoveRemoteOrigin(index))
+										.style(AppStyles),
+								),
+						)
+					},
+				)
+				.push(
+					Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							iced::TextInput::new(
+								&mut self.remote_origin_input_state,
+								"http://localhost:8080",
+								&self.remote_origin_input,
+								Message::SetRemoteOriginInput,
+							)
+							.on_submit(Message::AllowRemoteOrigin)
+							.width(Length::Units(250))
+							.padding(8),
+						)
+						.push(
+							Button::new(&mut self.allow_remote_origin_button, Text::new("Allow"))
+								.on_press(Message::AllowRemoteOrigin)
+								.style(AppStyles),
+						),
+				);
+			contents
+				.push(
+					Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(Text::new("Remote commands").width(Length::Units(120)))
+						.push(remote_checkboxes),
+				)
+				.push(
+					Row::new()
+						.spacing(16)
+						.push(Text::new("Remote web pages").width(Length::Units(120)))
+						.push(origins),
+				)
+		};
+		self.screen_wrapper.view(
+			contents
+				.push(
+					Button::new(&mut self.reset_button, Text::new("Reset to defaults"))
+						.on_press(Message::ResetToDefaults)
+						.style(AppStyles),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
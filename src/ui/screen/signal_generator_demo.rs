@@ -0,0 +1,311 @@
+use std::error::Error;
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Radio, Row, Slider, Text};
+use kira::{
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::{ParameterId, Tween},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{
+		create_audio_manager,
+		effect::gain::Gain,
+		signal_generator::{Channels, Signal, SignalGenerator, SignalGeneratorHandle},
+	},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+const EXPLANATION_TEXT: &str = "This screen plays test signals: \
+white noise, pink noise, or a sine wave at any frequency, out of \
+the left speaker, the right speaker, or both. It's handy for \
+checking that an output device is wired up the right way round, \
+finding the lowest and highest frequencies your speakers or \
+headphones can reproduce, and hearing the difference between \
+the two kinds of noise.
+
+None of these signals come from a file. The generator is a \
+custom audio stream, which kira asks for one frame at a time on \
+the audio thread, so it can play forever and change on the fly. \
+Streams don't read parameters, so the controls reach the \
+generator through a handle that shares a few atomic values with \
+it, and the generator fades briefly whenever the signal or \
+channels change so switching doesn't click. The level is a gain \
+effect on the track the stream plays on.
+
+Test signals are much harsher than music at the same level, so \
+start quiet and turn up slowly.";
+
+const MIN_FREQUENCY: f64 = 20.0;
+const MAX_FREQUENCY: f64 = 20000.0;
+/// Where the frequency slider starts, which works out to 440 Hz.
+const INITIAL_FREQUENCY_POSITION: f64 = 0.4474;
+const MIN_LEVEL: f64 = -60.0;
+const INITIAL_LEVEL: f64 = -24.0;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetSignal(Signal),
+	SetChannels(Channels),
+	/// Sets the sine's frequency (from 0 to 1, spread evenly
+	/// over the octaves from 20 Hz to 20 kHz).
+	SetFrequency(f64),
+	/// Sets the level (in decibels).
+	SetLevel(f64),
+}
+
+/// Converts a slider position to a frequency (in Hz).
+fn frequency(position: f64) -> f64 {
+	MIN_FREQUENCY * (MAX_FREQUENCY / MIN_FREQUENCY).powf(position)
+}
+
+pub struct SignalGeneratorDemo {
+	audio_manager: AudioManager,
+	generator_handle: SignalGeneratorHandle,
+	level_parameter_id: ParameterId,
+	playing: bool,
+	signal: Signal,
+	channels: Channels,
+	frequency_position: f64,
+	level: f64,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_button: iced::button::State,
+	frequency_slider: iced::slider::State,
+	level_slider: iced::slider::State,
+}
+
+impl SignalGeneratorDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let level_parameter_id = audio_manager.add_parameter(INITIAL_LEVEL)?;
+		let track_id = audio_manager.add_sub_track(Default::default())?;
+		audio_manager.add_effect_to_track(
+			track_id,
+			Gain::new(level_parameter_id),
+			Default::default(),
+		)?;
+		// the stream runs as long as the audio manager does, and
+		// stays silent until it's switched on
+		let (generator, generator_handle) = SignalGenerator::new(
+			Signal::PinkNoise,
+			Channels::Both,
+			frequency(INITIAL_FREQUENCY_POSITION),
+		);
+		audio_manager.add_stream(track_id, generator)?;
+		Ok(Self {
+			audio_manager,
+			generator_handle,
+			level_parameter_id,
+			playing: false,
+			signal: Signal::PinkNoise,
+			channels: Channels::Both,
+			frequency_position: INITIAL_FREQUENCY_POSITION,
+			level: INITIAL_LEVEL,
+			screen_wrapper: ScreenWrapper::new(
+				"Signal generator demo".into(),
+				Message::GoToDemoSelect,
+			),
+			play_button: iced::button::State::new(),
+			frequency_slider: iced::slider::State::new(),
+			level_slider: iced::slider::State::new(),
+		})
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play => {
+				self.playing = true;
+				self.generator_handle.set_playing(true);
+			}
+			Message::Stop => {
+				self.playing = false;
+				self.generator_handle.set_playing(false);
+			}
+			Message::SetSignal(signal) => {
+				self.signal = signal;
+				self.generator_handle.set_signal(signal);
+			}
+			Message::SetChannels(channels) => {
+				self.channels = channels;
+				self.generator_handle.set_channels(channels);
+			}
+			Message::SetFrequency(position) => {
+				self.frequency_position = position.clamp(0.0, 1.0);
+				self.generator_handle
+					.set_frequency(frequency(self.frequency_position));
+			}
+			Message::SetLevel(level) => {
+				self.level = level.clamp(MIN_LEVEL, 0.0);
+				self.audio_manager.set_parameter(
+					self.level_parameter_id,
+					self.level,
+					Some(Tween::linear(0.05)),
+				)?;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.playing;
+		let signal = self.signal;
+		let channels = self.channels;
+		let signals = Signal::ALL.iter().fold(
+			Row::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(Text::new("Signal:")),
+			|row, option| {
+				row.push(Radio::new(
+					*option,
+					option.name(),
+					Some(signal),
+					Message::SetSignal,
+				))
+			},
+		);
+		let channel_options = Channels::ALL.iter().fold(
+			Row::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(Text::new("Channels:")),
+			|row, option| {
+				row.push(Radio::new(
+					*option,
+					option.name(),
+					Some(channels),
+					Message::SetChannels,
+				))
+			},
+		);
+		// the frequency only matters for the sine
+		let frequency_slider = Slider::new(
+			&mut self.frequency_slider,
+			0.0..=1.0,
+			self.frequency_position,
+			Message::SetFrequency,
+		)
+		.step(0.001)
+		.style(AppStyles);
+		let frequency_row = Row::new()
+			.max_width(500)
+			.spacing(16)
+			.align_items(Align::Center)
+			.push(
+				Text::new(if signal == Signal::Sine {
+					format!("Frequency: {:.0} Hz", frequency(self.frequency_position))
+				} else {
+					"Frequency: (sine only)".into()
+				})
+				.width(Length::Units(200)),
+			)
+			.push(frequency_slider);
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Button::new(
+						&mut self.play_button,
+						Text::new(if playing { "Stop" } else { "Play" })
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(if playing {
+						Message::Stop
+					} else {
+						Message::Play
+					})
+					.width(Length::Units(100))
+					.style(AppStyles),
+				)
+				.push(signals)
+				.push(channel_options)
+				.push(frequency_row)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Level: {:.1} dB", self.level))
+								.width(Length::Units(200)),
+						)
+						.push(
+							Slider::new(
+								&mut self.level_slider,
+								MIN_LEVEL..=0.0,
+								self.level,
+								Message::SetLevel,
+							)
+							.step(0.5)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for SignalGeneratorDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "signal_generator_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetFrequency(_) => Some("Frequency".into()),
+			Message::SetLevel(_) => Some("Level".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Frequency" => vec![Message::SetFrequency(value)],
+			"Level" => vec![Message::SetLevel(MIN_LEVEL * (1.0 - value))],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for SignalGeneratorDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "signal_generator_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetSignal(self.signal),
+			Message::SetChannels(self.channels),
+			Message::SetFrequency(self.frequency_position),
+			Message::SetLevel(self.level),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for SignalGeneratorDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(key, self.playing, Message::Play, Message::Stop)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if self.playing {
+			Some(format!("Signal generator ({})", self.signal.name()))
+		} else {
+			None
+		}
+	}
+}
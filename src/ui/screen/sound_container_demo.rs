@@ -0,0 +1,414 @@
+use std::{collections::VecDeque, error::Error, time::Instant};
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Radio, Row, Slider, Text};
+use kira::{
+	manager::{AudioManager, AudioManagerSettings},
+	playable::PlayableSettings,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{
+		create_audio_manager,
+		sound_container::{Selection, SoundContainer},
+		synth,
+	},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+const EXPLANATION_TEXT: &str = "A sound container holds a few \
+variations of a sound and plays them as one. Each time it's \
+triggered, it picks a variation and gives it a random pitch and \
+volume within the ranges set by the sliders.
+
+Round robin goes through the variations in order, which is easy \
+to hear with the blips. Random can land on the same variation \
+twice in a row, which is what gives repetition away, so random \
+(no repeat) skips the one that just played. Shuffle plays every \
+variation once before any of them comes back.
+
+The container is a small struct in the demo's audio code, so \
+any screen can hand it some sounds and play it instead of \
+picking sounds itself.";
+
+/// How long to wait between triggers while repeating (in seconds).
+const REPEAT_INTERVAL: f64 = 0.35;
+/// How many of the most recent picks are shown.
+const HISTORY_LENGTH: usize = 16;
+const MAX_PITCH_VARIATION: f64 = 4.0;
+const MAX_VOLUME_VARIATION: f64 = 12.0;
+/// The notes of the blip variations (in Hz), a major seventh chord
+/// so round robin plays an arpeggio.
+const BLIP_FREQUENCIES: [f64; 4] = [523.25, 659.25, 783.99, 987.77];
+
+/// Which set of variations the container plays.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Source {
+	Footsteps,
+	Blips,
+}
+
+impl Source {
+	const ALL: [Self; 2] = [Self::Footsteps, Self::Blips];
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::Footsteps => "Footsteps",
+			Self::Blips => "Blips",
+		}
+	}
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Trigger,
+	SetRepeating(bool),
+	SetSource(Source),
+	SetSelection(Selection),
+	/// Sets how far the pitch can stray from the original
+	/// (in semitones, up or down).
+	SetPitchVariation(f64),
+	/// Sets how much quieter than the original a variation can be
+	/// (in decibels).
+	SetVolumeVariation(f64),
+}
+
+pub struct SoundContainerDemo {
+	audio_manager: AudioManager,
+	footsteps: SoundContainer,
+	blips: SoundContainer,
+	source: Source,
+	/// When the container was last triggered while repeating, or
+	/// `None` if it isn't repeating.
+	repeating_since: Option<Instant>,
+	/// The variations that played most recently, oldest first.
+	history: VecDeque<usize>,
+	screen_wrapper: ScreenWrapper<Message>,
+	trigger_button: iced::button::State,
+	repeat_button: iced::button::State,
+	pitch_slider: iced::slider::State,
+	volume_slider: iced::slider::State,
+}
+
+impl SoundContainerDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let footstep_dir = std::env::current_dir()?.join("assets/footstep demo");
+		let mut footstep_sound_ids = vec![];
+		for i in 1..=4 {
+			footstep_sound_ids.push(audio_manager.load_sound(
+				footstep_dir.join(format!("step {}.wav", i)),
+				PlayableSettings::new(),
+			)?);
+		}
+		let mut blip_sound_ids = vec![];
+		for frequency in &BLIP_FREQUENCIES {
+			blip_sound_ids.push(audio_manager.add_sound(synth::blip(
+				*frequency,
+				0.3,
+				PlayableSettings::new(),
+			))?);
+		}
+		let mut footsteps = SoundContainer::new(footstep_sound_ids, Selection::RandomNoRepeat);
+		let mut blips = SoundContainer::new(blip_sound_ids, Selection::RandomNoRepeat);
+		for container in [&mut footsteps, &mut blips].iter_mut() {
+			container.set_pitch_variation(1.0);
+			container.set_volume_variation(3.0);
+		}
+		Ok(Self {
+			audio_manager,
+			footsteps,
+			blips,
+			source: Source::Footsteps,
+			repeating_since: None,
+			history: VecDeque::new(),
+			screen_wrapper: ScreenWrapper::new(
+				"Sound container demo".into(),
+				Message::GoToDemoSelect,
+			),
+			trigger_button: iced::button::State::new(),
+			repeat_button: iced::button::State::new(),
+			pitch_slider: iced::slider::State::new(),
+			volume_slider: iced::slider::State::new(),
+		})
+	}
+
+	fn container(&self) -> &SoundContainer {
+		match self.source {
+			Source::Footsteps => &self.footsteps,
+			Source::Blips => &self.blips,
+		}
+	}
+
+	/// Both containers share the settings, so switching sources
+	/// keeps them.
+	fn containers_mut(&mut self) -> [&mut SoundContainer; 2] {
+		[&mut self.footsteps, &mut self.blips]
+	}
+
+	fn trigger(&mut self) -> Result<(), Box<dyn Error>> {
+		let container = match self.source {
+			Source::Footsteps => &mut self.footsteps,
+			Source::Blips => &mut self.blips,
+		};
+		container.play(&mut self.audio_manager, Default::default())?;
+		if let Some(played) = container.last_played() {
+			if self.history.len() == HISTORY_LENGTH {
+				self.history.pop_front();
+			}
+			self.history.push_back(played.index);
+		}
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Trigger => self.trigger()?,
+			Message::SetRepeating(true) if self.repeating_since.is_none() => {
+				self.trigger()?;
+				self.repeating_since = Some(Instant::now());
+			}
+			Message::SetRepeating(false) => self.repeating_since = None,
+			Message::SetSource(source) => {
+				self.source = source;
+				self.history.clear();
+			}
+			Message::SetSelection(selection) => {
+				for container in self.containers_mut().iter_mut() {
+					container.set_selection(selection);
+				}
+				self.history.clear();
+			}
+			Message::SetPitchVariation(variation) => {
+				for container in self.containers_mut().iter_mut() {
+					container.set_pitch_variation(variation.min(MAX_PITCH_VARIATION));
+				}
+			}
+			Message::SetVolumeVariation(variation) => {
+				for container in self.containers_mut().iter_mut() {
+					container.set_volume_variation(variation.min(MAX_VOLUME_VARIATION));
+				}
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	/// Whether the container is being triggered from the UI timer.
+	pub fn plays_from_ui_timer(&self) -> bool {
+		self.repeating_since.is_some()
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		if let Some(repeating_since) = self.repeating_since {
+			if repeating_since.elapsed().as_secs_f64() >= REPEAT_INTERVAL {
+				self.trigger()?;
+				self.repeating_since = Some(Instant::now());
+			}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let repeating = self.repeating_since.is_some();
+		let source = self.source;
+		let container = self.container();
+		let selection = container.selection();
+		let pitch_variation = container.pitch_variation();
+		let volume_variation = container.volume_variation();
+		let last_index = self.history.back().copied();
+		let variations = (0..container.num_variations())
+			.map(|index| {
+				if Some(index) == last_index {
+					format!("[{}]", index + 1)
+				} else {
+					format!(" {} ", index + 1)
+				}
+			})
+			.collect::<Vec<_>>()
+			.join(" ");
+		let last_played_text = match container.last_played() {
+			Some(played) if last_index.is_some() => format!(
+				"Variation {} - pitch: {:.2}x - volume: {:.1} dB",
+				played.index + 1,
+				played.pitch,
+				20.0 * played.volume.log10()
+			),
+			_ => "Nothing played yet".into(),
+		};
+		let history_text = self
+			.history
+			.iter()
+			.map(|index| (index + 1).to_string())
+			.collect::<Vec<_>>()
+			.join(" ");
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Source::ALL
+						.iter()
+						.fold(Row::new().spacing(16), |row, option| {
+							row.push(Radio::new(
+								*option,
+								option.name(),
+								Some(source),
+								Message::SetSource,
+							))
+						}),
+				)
+				.push(
+					Selection::ALL
+						.iter()
+						.fold(Row::new().spacing(16), |row, option| {
+							row.push(Radio::new(
+								*option,
+								option.name(),
+								Some(selection),
+								Message::SetSelection,
+							))
+						}),
+				)
+				.push(
+					Row::new()
+						.spacing(16)
+						.push(
+							Button::new(
+								&mut self.trigger_button,
+								Text::new("Trigger")
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(Message::Trigger)
+							.width(Length::Units(120))
+							.style(AppStyles),
+						)
+						.push(
+							Button::new(
+								&mut self.repeat_button,
+								Text::new(if repeating { "Stop" } else { "Repeat" })
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(Message::SetRepeating(!repeating))
+							.width(Length::Units(120))
+							.style(AppStyles),
+						),
+				)
+				.push(Text::new(variations).size(32))
+				.push(Text::new(last_played_text))
+				.push(Text::new(format!("Recent picks: {}", history_text)))
+				.push(
+					Row::new()
+						.spacing(16)
+						.max_width(600)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!(
+								"Pitch variation: ±{:.1} semitones",
+								pitch_variation
+							))
+							.width(Length::Units(300)),
+						)
+						.push(
+							Slider::new(
+								&mut self.pitch_slider,
+								0.0..=MAX_PITCH_VARIATION,
+								pitch_variation,
+								Message::SetPitchVariation,
+							)
+							.step(0.1)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Row::new()
+						.spacing(16)
+						.max_width(600)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!(
+								"Volume variation: up to -{:.1} dB",
+								volume_variation
+							))
+							.width(Length::Units(300)),
+						)
+						.push(
+							Slider::new(
+								&mut self.volume_slider,
+								0.0..=MAX_VOLUME_VARIATION,
+								volume_variation,
+								Message::SetVolumeVariation,
+							)
+							.step(0.5)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for SoundContainerDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "sound_container_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetPitchVariation(_) => Some("Pitch variation".into()),
+			Message::SetVolumeVariation(_) => Some("Volume variation".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Pitch variation" => vec![Message::SetPitchVariation(MAX_PITCH_VARIATION * value)],
+			"Volume variation" => vec![Message::SetVolumeVariation(MAX_VOLUME_VARIATION * value)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for SoundContainerDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "sound_container_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		let container = self.container();
+		vec![
+			Message::SetSource(self.source),
+			Message::SetSelection(container.selection()),
+			Message::SetPitchVariation(container.pitch_variation()),
+			Message::SetVolumeVariation(container.volume_variation()),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for SoundContainerDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.repeating_since.is_some(),
+			Message::SetRepeating(true),
+			Message::SetRepeating(false),
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		self.repeating_since
+			.map(|_| format!("Sound container demo - {}", self.source.name()))
+	}
+}
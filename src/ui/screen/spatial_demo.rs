@@ -0,0 +1,199 @@
+use std::error::Error;
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Text};
+use kira::{
+	instance::{InstanceId, InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::{ParameterId, Tween},
+	playable::PlayableSettings,
+	sound::SoundId,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{
+		create_audio_manager,
+		sound_cache::SoundCache,
+		spatializer::{Spatialized, Spatializer},
+	},
+	preferences::OutputMode,
+	ui::{
+		common::{
+			emitter_pad::{self, EmitterPad},
+			screen_wrapper::ScreenWrapper,
+		},
+		style::AppStyles,
+	},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &["drum fill demo/loop.ogg"];
+
+const EXPLANATION_TEXT: &str = "This demo plays a loop from \
+wherever you drag the red dot. The listener is the white dot \
+in the middle, facing up.
+
+The position is turned into a panning and a volume, which are \
+sent to two parameters that the instance's panning and volume \
+are tied to. Inside the inner circle, the sound plays at full \
+volume. Further out, the volume drops off with the distance. \
+The output mode on the settings screen changes how wide the \
+panning is.";
+
+/// How far the edge of the pad is from the listener.
+const PAD_RADIUS: f32 = 4.0;
+/// How long parameter changes take, which keeps the sound from
+/// zipping while the emitter is dragged.
+const SMOOTHING_TIME: f64 = 0.05;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetPosition(f32, f32),
+}
+
+pub struct SpatialDemo {
+	audio_manager: AudioManager,
+	spatializer: Spatializer,
+	sound_id: SoundId,
+	instance_id: Option<InstanceId>,
+	panning_parameter_id: ParameterId,
+	volume_parameter_id: ParameterId,
+	position: (f32, f32),
+	spatialized: Spatialized,
+	screen_wrapper: ScreenWrapper<Message>,
+	emitter_pad: emitter_pad::State,
+	play_button: iced::button::State,
+}
+
+impl SpatialDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+		output_mode: OutputMode,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let spatializer = Spatializer::new(output_mode);
+		let position = (1.0, -1.0);
+		let spatialized = spatializer.spatialize(position.0 as f64, position.1 as f64);
+		let panning_parameter_id = audio_manager.add_parameter(spatialized.panning)?;
+		let volume_parameter_id = audio_manager.add_parameter(spatialized.volume)?;
+		let sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&std::env::current_dir()?.join("assets/drum fill demo/loop.ogg"))?
+				.to_sound(PlayableSettings::new().default_loop_start(0.0)),
+		)?;
+		Ok(Self {
+			audio_manager,
+			spatializer,
+			sound_id,
+			instance_id: None,
+			panning_parameter_id,
+			volume_parameter_id,
+			position,
+			spatialized,
+			screen_wrapper: ScreenWrapper::new("Spatial demo".into(), Message::GoToDemoSelect),
+			emitter_pad: Default::default(),
+			play_button: iced::button::State::new(),
+		})
+	}
+
+	fn move_emitter(&mut self, x: f32, y: f32) -> Result<(), Box<dyn Error>> {
+		self.position = (x, y);
+		self.spatialized = self.spatializer.spatialize(x as f64, y as f64);
+		self.audio_manager.set_parameter(
+			self.panning_parameter_id,
+			self.spatialized.panning,
+			Some(Tween::linear(SMOOTHING_TIME)),
+		)?;
+		self.audio_manager.set_parameter(
+			self.volume_parameter_id,
+			self.spatialized.volume,
+			Some(Tween::linear(SMOOTHING_TIME)),
+		)?;
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if self.instance_id.is_none() => {
+				self.instance_id = Some(
+					self.audio_manager.play(
+						self.sound_id,
+						InstanceSettings::new()
+							.panning(self.panning_parameter_id)
+							.volume(self.volume_parameter_id),
+					)?,
+				);
+			}
+			Message::Stop => {
+				if let Some(instance_id) = self.instance_id.take() {
+					self.audio_manager.stop_instance(
+						instance_id,
+						StopInstanceSettings::new().fade_tween(Tween::linear(0.25)),
+					)?;
+				}
+			}
+			Message::SetPosition(x, y) => self.move_emitter(x, y)?,
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.instance_id.is_some();
+		let (x, y) = self.position;
+		let Spatialized { panning, volume } = self.spatialized;
+		let distance = (x * x + y * y).sqrt();
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					EmitterPad::new(&mut self.emitter_pad, PAD_RADIUS, self.position)
+						.rings(vec![1.0, 2.0, 3.0])
+						.on_drag(Message::SetPosition),
+				)
+				.push(Text::new(format!(
+					"Distance: {:.2} - panning: {:.2} - volume: {:.1} dB",
+					distance,
+					panning,
+					20.0 * volume.max(f64::EPSILON).log10()
+				)))
+				.push(
+					Button::new(
+						&mut self.play_button,
+						Text::new(if playing { "Stop" } else { "Play" })
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(if playing {
+						Message::Stop
+					} else {
+						Message::Play
+					})
+					.width(Length::Units(100))
+					.style(AppStyles),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for SpatialDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "spatial_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		let (x, y) = self.position;
+		vec![Message::SetPosition(x, y)]
+	}
+}
@@ -0,0 +1,340 @@
+mod spectrum_bars;
+
+use std::{error::Error, time::Instant};
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Radio, Row, Slider, Text};
+use kira::{
+	instance::{InstanceId, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	mixer::TrackIndex,
+	parameter::Tween,
+	playable::PlayableSettings,
+	sound::SoundId,
+};
+use serde::{Deserialize, Serialize};
+
+use self::spectrum_bars::SpectrumBars;
+use crate::{
+	audio::{
+		create_audio_manager,
+		decoded_sound::DecodedSound,
+		effect::tap::{Tap, TapReader},
+		sound_cache::SoundCache,
+		spectrum,
+	},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &[
+	"underwater demo/drums.ogg",
+	"underwater demo/bass.ogg",
+	"underwater demo/pad.ogg",
+	"underwater demo/lead.ogg",
+	"drum fill demo/loop.ogg",
+];
+
+const EXPLANATION_TEXT: &str = "The bars show how loud each range \
+of frequencies is in whatever's playing, from the lows on the \
+left to the highs on the right.
+
+Kira doesn't hand the app the audio it plays, so the screen puts \
+a tap on the main track: an effect that passes the sound through \
+untouched and copies the latest samples somewhere the screen can \
+read them. Every frame, the screen takes the newest block of \
+samples, runs an FFT over it to get the level of each frequency, \
+and groups those into bands spaced the way pitch is. The bars \
+jump up right away but fall back slowly (set by the fall speed), \
+which is what keeps them readable.
+
+Because the tap sits at the end of the mix, it hears exactly what \
+kira sends to the speakers, so the bars follow the music (give \
+or take the audio device's buffer) without the screen having to \
+track the playback position.";
+
+/// How many samples each FFT looks at. At 48kHz this is about
+/// 43ms, enough to tell the bass notes apart.
+const FFT_SIZE: usize = 2048;
+const NUM_BANDS: usize = 32;
+const MIN_FREQUENCY: f32 = 40.0;
+const MAX_FREQUENCY: f32 = 16000.0;
+const MIN_FALL_SPEED: f32 = 0.25;
+const MAX_FALL_SPEED: f32 = 4.0;
+/// How fast the peak markers fall, relative to the bars.
+const PEAK_FALL_RATIO: f32 = 0.25;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Song {
+	Underwater,
+	DrumLoop,
+}
+
+impl Song {
+	const ALL: [Self; 2] = [Self::Underwater, Self::DrumLoop];
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::Underwater => "Underwater song",
+			Self::DrumLoop => "Drum loop",
+		}
+	}
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetSong(Song),
+	/// Sets how far the bars fall each second (as a fraction of
+	/// their full height).
+	SetFallSpeed(f32),
+}
+
+pub struct SpectrumDemo {
+	audio_manager: AudioManager,
+	underwater_sound_id: SoundId,
+	drum_loop_sound_id: SoundId,
+	song: Song,
+	instance_id: Option<InstanceId>,
+	tap_reader: TapReader,
+	samples: Vec<f32>,
+	levels: Vec<f32>,
+	peaks: Vec<f32>,
+	fall_speed: f32,
+	last_update_time: Instant,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_button: iced::button::State,
+	fall_speed_slider: iced::slider::State,
+}
+
+impl SpectrumDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let (tap, tap_reader) = Tap::new(FFT_SIZE);
+		audio_manager.add_effect_to_track(TrackIndex::Main, tap, Default::default())?;
+		let assets_base_dir = std::env::current_dir()?.join("assets");
+		let mut stems = vec![];
+		for path in &ASSETS[..4] {
+			stems.push(sound_cache.get(&assets_base_dir.join(path))?);
+		}
+		let underwater = DecodedSound::mix(&stems.iter().map(|stem| &**stem).collect::<Vec<_>>());
+		let underwater_sound_id = audio_manager
+			.add_sound(underwater.to_sound(PlayableSettings::new().default_loop_start(0.0)))?;
+		let drum_loop_sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&assets_base_dir.join(ASSETS[4]))?
+				.to_sound(PlayableSettings::new().default_loop_start(0.0)),
+		)?;
+		Ok(Self {
+			audio_manager,
+			underwater_sound_id,
+			drum_loop_sound_id,
+			song: Song::Underwater,
+			instance_id: None,
+			tap_reader,
+			samples: vec![0.0; FFT_SIZE],
+			levels: vec![0.0; NUM_BANDS],
+			peaks: vec![0.0; NUM_BANDS],
+			fall_speed: 1.5,
+			last_update_time: Instant::now(),
+			screen_wrapper: ScreenWrapper::new("Spectrum demo".into(), Message::GoToDemoSelect),
+			play_button: iced::button::State::new(),
+			fall_speed_slider: iced::slider::State::new(),
+		})
+	}
+
+	fn song_sound_id(&self) -> SoundId {
+		match self.song {
+			Song::Underwater => self.underwater_sound_id,
+			Song::DrumLoop => self.drum_loop_sound_id,
+		}
+	}
+
+	fn stop(&mut self) -> Result<(), Box<dyn Error>> {
+		if let Some(instance_id) = self.instance_id.take() {
+			self.audio_manager.stop_instance(
+				instance_id,
+				StopInstanceSettings::new().fade_tween(Tween::linear(0.25)),
+			)?;
+		}
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if self.instance_id.is_none() => {
+				self.instance_id = Some(
+					self.audio_manager
+						.play(self.song_sound_id(), Default::default())?,
+				);
+			}
+			Message::Stop => self.stop()?,
+			Message::SetSong(song) => {
+				let playing = self.instance_id.is_some();
+				self.stop()?;
+				self.song = song;
+				if playing {
+					self.update(Message::Play)?;
+				}
+			}
+			Message::SetFallSpeed(fall_speed) => {
+				self.fall_speed = fall_speed.clamp(MIN_FALL_SPEED, MAX_FALL_SPEED);
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		let dt = self.last_update_time.elapsed().as_secs_f32();
+		self.last_update_time = Instant::now();
+		let sample_rate = match self.tap_reader.sample_rate() {
+			Some(sample_rate) => sample_rate,
+			None => return Ok(()),
+		};
+		self.tap_reader.read_latest(&mut self.samples);
+		let magnitudes = spectrum::magnitudes(&self.samples);
+		let bands = spectrum::bands(
+			&magnitudes,
+			sample_rate,
+			NUM_BANDS,
+			MIN_FREQUENCY,
+			MAX_FREQUENCY,
+		);
+		let fall = self.fall_speed * dt;
+		for ((level, peak), band) in self.levels.iter_mut().zip(&mut self.peaks).zip(bands) {
+			*level = band.max(*level - fall);
+			*peak = level.max(*peak - fall * PEAK_FALL_RATIO);
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.instance_id.is_some();
+		let song = self.song;
+		let song_radios = Song::ALL
+			.iter()
+			.fold(Row::new().spacing(16), |row, option| {
+				row.push(Radio::new(
+					*option,
+					option.name(),
+					Some(song),
+					Message::SetSong,
+				))
+			});
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(Column::new().max_width(700).push(SpectrumBars {
+					levels: &self.levels,
+					peaks: &self.peaks,
+				}))
+				.push(
+					Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Button::new(
+								&mut self.play_button,
+								Text::new(if playing { "Stop" } else { "Play" })
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(if playing {
+								Message::Stop
+							} else {
+								Message::Play
+							})
+							.width(Length::Units(100))
+							.style(AppStyles),
+						)
+						.push(song_radios),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Fall speed: {:.2}", self.fall_speed))
+								.width(Length::Units(150)),
+						)
+						.push(
+							Slider::new(
+								&mut self.fall_speed_slider,
+								MIN_FALL_SPEED..=MAX_FALL_SPEED,
+								self.fall_speed,
+								Message::SetFallSpeed,
+							)
+							.step(0.05)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for SpectrumDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "spectrum_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetFallSpeed(_) => Some("Fall speed".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Fall speed" => vec![Message::SetFallSpeed(
+				MIN_FALL_SPEED + (MAX_FALL_SPEED - MIN_FALL_SPEED) * value as f32,
+			)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for SpectrumDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "spectrum_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetSong(self.song),
+			Message::SetFallSpeed(self.fall_speed),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for SpectrumDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.instance_id.is_some(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		self.instance_id
+			.map(|_| format!("Spectrum demo - {}", self.song.name()))
+	}
+}
@@ -0,0 +1,111 @@
+use iced::{mouse::Interaction, Background, Color, Length, Point, Rectangle, Size};
+use iced_graphics::{Backend, Defaults, Primitive, Renderer};
+use iced_native::{
+	layout::{Limits, Node},
+	Element, Layout, Widget,
+};
+
+const HEIGHT: f32 = 200.0;
+const SPACING: f32 = 3.0;
+const PEAK_HEIGHT: f32 = 2.0;
+const BACKGROUND_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.05];
+const LOW_COLOR: [f32; 4] = [0.2, 0.5, 1.0, 1.0];
+const HIGH_COLOR: [f32; 4] = [1.0, 0.6, 0.0, 1.0];
+const PEAK_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.8];
+
+/// Draws a level (from 0 to 1) for each frequency band as a bar,
+/// lowest frequencies on the left, with a line above each bar
+/// where its recent peak was.
+pub struct SpectrumBars<'a> {
+	pub levels: &'a [f32],
+	pub peaks: &'a [f32],
+}
+
+/// Blends between the low and high colors, so louder bars are
+/// warmer.
+fn bar_color(level: f32) -> Color {
+	let mut color = LOW_COLOR;
+	for (channel, high_channel) in color.iter_mut().zip(HIGH_COLOR.iter()) {
+		*channel += (high_channel - *channel) * level;
+	}
+	color.into()
+}
+
+impl<'a, Message, B: Backend> Widget<Message, Renderer<B>> for SpectrumBars<'a> {
+	fn width(&self) -> Length {
+		Length::Fill
+	}
+
+	fn height(&self) -> Length {
+		Length::Shrink
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, limits: &Limits) -> Node {
+		Node::new(
+			limits
+				.width(Length::Fill)
+				.height(Length::Units(HEIGHT as u16))
+				.resolve(Size::ZERO),
+		)
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		_defaults: &Defaults,
+		layout: Layout<'_>,
+		_cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let mut primitives = vec![Primitive::Quad {
+			bounds,
+			background: Background::Color(BACKGROUND_COLOR.into()),
+			border_radius: 0.0,
+			border_width: 0.0,
+			border_color: Color::TRANSPARENT,
+		}];
+		let num_bars = self.levels.len().max(1);
+		let bar_width = (bounds.width - SPACING * (num_bars + 1) as f32) / num_bars as f32;
+		for (i, level) in self.levels.iter().enumerate() {
+			let level = level.clamp(0.0, 1.0);
+			let x = bounds.x + SPACING + (bar_width + SPACING) * i as f32;
+			let height = bounds.height * level;
+			primitives.push(Primitive::Quad {
+				bounds: Rectangle::new(
+					Point::new(x, bounds.y + bounds.height - height),
+					Size::new(bar_width, height),
+				),
+				background: Background::Color(bar_color(level)),
+				border_radius: 0.0,
+				border_width: 0.0,
+				border_color: Color::TRANSPARENT,
+			});
+			if let Some(peak) = self.peaks.get(i) {
+				let y = bounds.y + bounds.height * (1.0 - peak.clamp(0.0, 1.0));
+				primitives.push(Primitive::Quad {
+					bounds: Rectangle::new(
+						Point::new(x, (y - PEAK_HEIGHT).max(bounds.y)),
+						Size::new(bar_width, PEAK_HEIGHT),
+					),
+					background: Background::Color(PEAK_COLOR.into()),
+					border_radius: 0.0,
+					border_width: 0.0,
+					border_color: Color::TRANSPARENT,
+				});
+			}
+		}
+		(Primitive::Group { primitives }, Interaction::default())
+	}
+
+	fn hash_layout(&self, state: &mut iced_native::Hasher) {
+		use std::hash::Hash;
+		HEIGHT.to_bits().hash(state)
+	}
+}
+
+impl<'a, Message, B: Backend> From<SpectrumBars<'a>> for Element<'a, Message, Renderer<B>> {
+	fn from(spectrum_bars: SpectrumBars<'a>) -> Self {
+		Element::new(spectrum_bars)
+	}
+}
@@ -0,0 +1,256 @@
+use std::{error::Error, time::Instant};
+
+use iced::{Align, Button, Checkbox, Column, HorizontalAlignment, Length, Row, Text};
+use kira::{
+	instance::{InstanceId, InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::Tween,
+	sound::SoundId,
+	Tempo,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, decoded_sound::DecodedSound, sound_cache::SoundCache},
+	ui::{
+		common::{screen_wrapper::ScreenWrapper, waveform::Waveform},
+		style::AppStyles,
+	},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &[
+	"underwater demo/drums.ogg",
+	"underwater demo/bass.ogg",
+	"underwater demo/pad.ogg",
+	"underwater demo/lead.ogg",
+];
+
+const EXPLANATION_TEXT: &str = "This demo starts an instance \
+partway through a sound using the start_position setting. \
+Click the waveform to pick where playback should begin, \
+or use the bar buttons to jump to a musically sensible point.
+
+Unlike scrubbing, the start position only takes effect \
+the next time the sound is played.";
+
+pub const TEMPO: Tempo = Tempo(85.0);
+const BEATS_PER_BAR: usize = 4;
+const NUM_BEATS: usize = 16;
+const NUM_PEAKS: usize = 200;
+const START_MARKER_COLOR: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+const PLAYHEAD_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	PickStartPosition(f32),
+	JumpToBar(usize),
+	SetSnapToBeat(bool),
+}
+
+struct Playback {
+	instance_id: InstanceId,
+	start_position: f64,
+	start_time: Instant,
+}
+
+pub struct StartOffsetDemo {
+	audio_manager: AudioManager,
+	sound_id: SoundId,
+	duration: f64,
+	peaks: Vec<f32>,
+	start_position: f64,
+	snap_to_beat: bool,
+	playback: Option<Playback>,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_button: iced::button::State,
+	bar_buttons: [iced::button::State; NUM_BEATS / BEATS_PER_BAR],
+}
+
+impl StartOffsetDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let assets_base_dir = std::env::current_dir()?.join("assets/underwater demo");
+		let song = DecodedSound::mix(&[
+			&*sound_cache.get(&assets_base_dir.join("drums.ogg"))?,
+			&*sound_cache.get(&assets_base_dir.join("bass.ogg"))?,
+			&*sound_cache.get(&assets_base_dir.join("pad.ogg"))?,
+			&*sound_cache.get(&assets_base_dir.join("lead.ogg"))?,
+		]);
+		let sound_id = audio_manager.add_sound(song.to_sound(Default::default()))?;
+		Ok(Self {
+			audio_manager,
+			sound_id,
+			duration: song.duration(),
+			peaks: song.peaks(NUM_PEAKS),
+			start_position: 0.0,
+			snap_to_beat: true,
+			playback: None,
+			screen_wrapper: ScreenWrapper::new("Start offset demo".into(), Message::GoToDemoSelect),
+			play_button: iced::button::State::new(),
+			bar_buttons: Default::default(),
+		})
+	}
+
+	fn play(&mut self) -> Result<(), Box<dyn Error>> {
+		self.stop()?;
+		let instance_id = self.audio_manager.play(
+			self.sound_id,
+			InstanceSettings::new().start_position(self.start_position),
+		)?;
+		self.playback = Some(Playback {
+			instance_id,
+			start_position: self.start_position,
+			start_time: Instant::now(),
+		});
+		Ok(())
+	}
+
+	fn stop(&mut self) -> Result<(), Box<dyn Error>> {
+		if let Some(playback) = self.playback.take() {
+			self.audio_manager.stop_instance(
+				playback.instance_id,
+				StopInstanceSettings::new().fade_tween(Tween::linear(0.1)),
+			)?;
+		}
+		Ok(())
+	}
+
+	/// Kira doesn't report the playback position of instances,
+	/// so we estimate it from when the instance was started.
+	fn playback_position(&self) -> Option<f64> {
+		self.playback
+			.as_ref()
+			.map(|playback| playback.start_position + playback.start_time.elapsed().as_secs_f64())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play => self.play()?,
+			Message::Stop => self.stop()?,
+			Message::PickStartPosition(fraction) => {
+				let position = self.duration * fraction as f64;
+				self.start_position = if self.snap_to_beat {
+					let beat = TEMPO.beats_to_seconds(1.0);
+					((position / beat).round() * beat).min(TEMPO.beats_to_seconds(NUM_BEATS as f64))
+				} else {
+					position
+				};
+			}
+			Message::JumpToBar(bar) => {
+				self.start_position = TEMPO.beats_to_seconds((bar * BEATS_PER_BAR) as f64);
+			}
+			Message::SetSnapToBeat(snap_to_beat) => {
+				self.snap_to_beat = snap_to_beat;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		if let Some(position) = self.playback_position() {
+			if position >= self.duration {
+				self.playback = None;
+			}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let duration = self.duration;
+		let playback_position = self.playback_position();
+
+		let play_button = Button::new(
+			&mut self.play_button,
+			Text::new(match self.playback {
+				Some(_) => "Stop",
+				None => "Play",
+			})
+			.width(Length::Fill)
+			.size(24)
+			.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.on_press(match self.playback {
+			Some(_) => Message::Stop,
+			None => Message::Play,
+		})
+		.width(Length::Units(150))
+		.style(AppStyles);
+
+		let bar_buttons = self.bar_buttons.iter_mut().enumerate().fold(
+			Row::new().spacing(8),
+			|row, (bar, state)| {
+				row.push(
+					Button::new(state, Text::new(format!("Bar {}", bar + 1)))
+						.on_press(Message::JumpToBar(bar))
+						.style(AppStyles),
+				)
+			},
+		);
+
+		let mut waveform = Waveform::new(&self.peaks)
+			.grid_lines(
+				(0..=NUM_BEATS)
+					.map(|beat| (TEMPO.beats_to_seconds(beat as f64) / duration) as f32)
+					.collect(),
+			)
+			.marker((self.start_position / duration) as f32, START_MARKER_COLOR)
+			.on_press(Message::PickStartPosition);
+		if let Some(position) = playback_position {
+			waveform = waveform.marker((position / duration) as f32, PLAYHEAD_COLOR);
+		}
+
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(waveform)
+				.push(
+					Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(play_button)
+						.push(bar_buttons)
+						.push(Checkbox::new(
+							self.snap_to_beat,
+							"Snap to beat",
+							Message::SetSnapToBeat,
+						)),
+				)
+				.push(Text::new(format!(
+					"Start position: {:.2}s (beat {:.1})",
+					self.start_position,
+					self.start_position / TEMPO.beats_to_seconds(1.0) + 1.0,
+				)))
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for StartOffsetDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "start_offset_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		// the start position is picked without snapping so it
+		// comes back exactly where it was
+		vec![
+			Message::SetSnapToBeat(false),
+			Message::PickStartPosition((self.start_position / self.duration) as f32),
+			Message::SetSnapToBeat(self.snap_to_beat),
+		]
+	}
+}
@@ -0,0 +1,468 @@
+use std::error::Error;
+
+use iced::{Align, Button, Color, Column, HorizontalAlignment, Length, Row, Slider, Text};
+use kira::{
+	arrangement::{Arrangement, ArrangementId, LoopArrangementSettings},
+	instance::{InstanceId, InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::{ParameterId, Tween},
+	playable::PlayableSettings,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, mix_snapshots::MixSnapshots, sound_cache::SoundCache},
+	ui::{
+		common::{screen_wrapper::ScreenWrapper, transport::Transport},
+		screen::underwater_demo::TEMPO,
+		style::AppStyles,
+	},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &[
+	"underwater demo/drums.ogg",
+	"underwater demo/bass.ogg",
+	"underwater demo/pad.ogg",
+	"underwater demo/lead.ogg",
+];
+
+const EXPLANATION_TEXT: &str = "The song is split into four \
+stems that are started together and loop in lockstep. Muting a \
+stem silences it, and soloing one silences everything that isn't \
+soloed. The presets set the mutes for common combinations and \
+clear the solos.
+
+Each preset is a mix snapshot, like the ones in the mixer \
+snapshot demo, captured when the screen opens. Choosing one \
+recalls the snapshot over the fade time.
+
+Starting a stem gives back the ID of its instance, which the \
+screen keeps so it can stop each stem on its own. The instances \
+can't be faded directly (setting an instance's volume jumps \
+straight to the new value), so each one's volume is tied to a \
+parameter, and muting tweens that parameter instead. Set the \
+fade time to 0 to hear why: cutting a stem off in the middle of \
+a note makes a click.";
+
+const STEM_NAMES: [&str; 4] = ["Drums", "Bass", "Pad", "Lead"];
+const NUM_STEMS: usize = STEM_NAMES.len();
+/// The longest fade the slider goes up to (in seconds).
+const MAX_FADE_TIME: f64 = 0.5;
+const INITIAL_FADE_TIME: f64 = 0.05;
+const AUDIBLE_COLOR: [f32; 3] = [0.3, 1.0, 0.4];
+const SILENT_COLOR: [f32; 3] = [0.5, 0.5, 0.5];
+
+/// A set of stems to hear.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Preset {
+	OnlyDrums,
+	RhythmSection,
+	NoLead,
+	FullMix,
+}
+
+impl Preset {
+	const ALL: [Self; 4] = [
+		Self::OnlyDrums,
+		Self::RhythmSection,
+		Self::NoLead,
+		Self::FullMix,
+	];
+
+	/// Where this preset's snapshot is in the mix.
+	fn snapshot_index(self) -> usize {
+		self as usize
+	}
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::OnlyDrums => "Only drums",
+			Self::RhythmSection => "Drums and bass",
+			Self::NoLead => "No lead",
+			Self::FullMix => "Full mix",
+		}
+	}
+
+	/// Whether each stem can be heard with this preset.
+	fn audible_stems(self) -> [bool; NUM_STEMS] {
+		match self {
+			Self::OnlyDrums => [true, false, false, false],
+			Self::RhythmSection => [true, true, false, false],
+			Self::NoLead => [true, true, true, false],
+			Self::FullMix => [true, true, true, true],
+		}
+	}
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetMetronomeClick(bool),
+	SetMuted(usize, bool),
+	SetSoloed(usize, bool),
+	ApplyPreset(Preset),
+	/// Sets how long muting and soloing take to fade (in
+	/// seconds).
+	SetFadeTime(f64),
+}
+
+struct Stem {
+	loop_id: ArrangementId,
+	/// The stem's instance, if the music is playing.
+	instance_id: Option<InstanceId>,
+	/// Fades the stem's instance in and out. It's also the
+	/// stem's value in the mix.
+	audible_parameter_id: ParameterId,
+	muted: bool,
+	soloed: bool,
+	mute_button: iced::button::State,
+	solo_button: iced::button::State,
+}
+
+pub struct StemMuteSoloDemo {
+	audio_manager: AudioManager,
+	stems: Vec<Stem>,
+	/// The audible parameter of each stem, with one snapshot per
+	/// preset.
+	mix: MixSnapshots,
+	fade_time: f64,
+	transport: Transport,
+	screen_wrapper: ScreenWrapper<Message>,
+	preset_buttons: [iced::button::State; 4],
+	fade_time_slider: iced::slider::State,
+}
+
+impl StemMuteSoloDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(AudioManagerSettings {
+			metronome_settings: Transport::metronome_settings(TEMPO),
+			..audio_manager_settings
+		})?;
+		let transport = Transport::new(&mut audio_manager, TEMPO)?;
+		let assets_base_dir = std::env::current_dir()?.join("assets");
+		let mut stems = vec![];
+		for asset in ASSETS {
+			let sound_id = audio_manager.add_sound(
+				sound_cache.get(&assets_base_dir.join(asset))?.to_sound(
+					PlayableSettings::new().semantic_duration(TEMPO.beats_to_seconds(16.0)),
+				),
+			)?;
+			stems.push(Stem {
+				loop_id: audio_manager.add_arrangement(Arrangement::new_loop(
+					sound_id,
+					LoopArrangementSettings::new(),
+				))?,
+				instance_id: None,
+				audible_parameter_id: audio_manager.add_parameter(1.0)?,
+				muted: false,
+				soloed: false,
+				mute_button: iced::button::State::new(),
+				solo_button: iced::button::State::new(),
+			});
+		}
+		let mix_parameters: Vec<(ParameterId, f64)> = stems
+			.iter()
+			.map(|stem| (stem.audible_parameter_id, 1.0))
+			.collect();
+		let mut mix = MixSnapshots::new(&mix_parameters);
+		// the presets are captured in the same order as
+		// `Preset::ALL`, ending on the full mix the stems start at
+		for preset in &Preset::ALL {
+			for (index, audible) in preset.audible_stems().iter().enumerate() {
+				mix.set(&mut audio_manager, index, if *audible { 1.0 } else { 0.0 })?;
+			}
+			mix.capture(preset.name());
+		}
+		Ok(Self {
+			audio_manager,
+			stems,
+			mix,
+			fade_time: INITIAL_FADE_TIME,
+			transport,
+			screen_wrapper: ScreenWrapper::new(
+				"Stem mute/solo demo".into(),
+				Message::GoToDemoSelect,
+			),
+			preset_buttons: Default::default(),
+			fade_time_slider: iced::slider::State::new(),
+		})
+	}
+
+	fn play(&mut self) -> Result<(), Box<dyn Error>> {
+		// the commands are all sent at once, so the stems start
+		// on the same sample
+		for stem in &mut self.stems {
+			stem.instance_id = Some(self.audio_manager.play(
+				stem.loop_id,
+				InstanceSettings::new().volume(stem.audible_parameter_id),
+			)?);
+		}
+		self.transport.start(&mut self.audio_manager)?;
+		Ok(())
+	}
+
+	fn stop(&mut self) -> Result<(), Box<dyn Error>> {
+		for stem in &mut self.stems {
+			if let Some(instance_id) = stem.instance_id.take() {
+				self.audio_manager.stop_instance(
+					instance_id,
+					StopInstanceSettings::new().fade_tween(Tween::linear(0.5)),
+				)?;
+			}
+		}
+		self.transport.stop(&mut self.audio_manager)?;
+		Ok(())
+	}
+
+	/// A stem can be heard if it isn't muted, and either it's
+	/// soloed or nothing is.
+	fn is_audible(&self, index: usize) -> bool {
+		let any_soloed = self.stems.iter().any(|stem| stem.soloed);
+		let stem = &self.stems[index];
+		!stem.muted && (stem.soloed || !any_soloed)
+	}
+
+	/// The preset that matches what can be heard right now, if
+	/// any does.
+	fn current_preset(&self) -> Option<Preset> {
+		let audible: Vec<bool> = (0..NUM_STEMS).map(|index| self.is_audible(index)).collect();
+		Preset::ALL
+			.iter()
+			.copied()
+			.find(|preset| preset.audible_stems()[..] == audible[..])
+	}
+
+	fn update_audible(&mut self) -> Result<(), Box<dyn Error>> {
+		for index in 0..self.stems.len() {
+			let audible = self.is_audible(index);
+			self.mix.glide(
+				&mut self.audio_manager,
+				index,
+				if audible { 1.0 } else { 0.0 },
+				self.fade_time,
+			)?;
+		}
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if !self.transport.is_playing() => self.play()?,
+			Message::Stop => self.stop()?,
+			Message::SetMetronomeClick(enabled) => {
+				self.transport
+					.set_click_enabled(&mut self.audio_manager, enabled)?;
+			}
+			Message::SetMuted(index, muted) if index < NUM_STEMS => {
+				self.stems[index].muted = muted;
+				self.update_audible()?;
+			}
+			Message::SetSoloed(index, soloed) if index < NUM_STEMS => {
+				self.stems[index].soloed = soloed;
+				self.update_audible()?;
+			}
+			Message::ApplyPreset(preset) => {
+				for (stem, audible) in self.stems.iter_mut().zip(preset.audible_stems().iter()) {
+					stem.muted = !audible;
+					stem.soloed = false;
+				}
+				self.mix.recall(
+					&mut self.audio_manager,
+					preset.snapshot_index(),
+					self.fade_time,
+				)?;
+			}
+			Message::SetFadeTime(fade_time) => {
+				self.fade_time = fade_time.clamp(0.0, MAX_FADE_TIME);
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		self.transport.check_for_events(&mut self.audio_manager);
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let audible: Vec<bool> = (0..NUM_STEMS).map(|index| self.is_audible(index)).collect();
+		let current_preset_text = match self.current_preset() {
+			Some(preset) => format!("Hearing: {}", preset.name()),
+			None => "Hearing: custom mix".into(),
+		};
+		let presets = self.preset_buttons.iter_mut().zip(Preset::ALL.iter()).fold(
+			Row::new().spacing(8),
+			|row, (button, preset)| {
+				row.push(
+					Button::new(
+						button,
+						Text::new(preset.name())
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(Message::ApplyPreset(*preset))
+					.width(Length::Units(150))
+					.style(AppStyles),
+				)
+			},
+		);
+		let stems = self.stems.iter_mut().enumerate().fold(
+			Column::new().spacing(8),
+			|column, (index, stem)| {
+				column.push(
+					Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(Text::new(STEM_NAMES[index]).width(Length::Units(80)))
+						.push(
+							Button::new(
+								&mut stem.mute_button,
+								Text::new(if stem.muted { "Unmute" } else { "Mute" })
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(Message::SetMuted(index, !stem.muted))
+							.width(Length::Units(100))
+							.style(AppStyles),
+						)
+						.push(
+							Button::new(
+								&mut stem.solo_button,
+								Text::new(if stem.soloed { "Unsolo" } else { "Solo" })
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(Message::SetSoloed(index, !stem.soloed))
+							.width(Length::Units(100))
+							.style(AppStyles),
+						)
+						.push(
+							Text::new(if audible[index] { "Audible" } else { "Silent" })
+								.color(Color::from(if audible[index] {
+									AUDIBLE_COLOR
+								} else {
+									SILENT_COLOR
+								}))
+								.width(Length::Units(80)),
+						),
+				)
+			},
+		);
+		self.screen_wrapper.view_with_transport(
+			self.transport
+				.view(Message::Play, Message::Stop, Message::SetMetronomeClick),
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(presets)
+				.push(Text::new(current_preset_text))
+				.push(stems)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Fade time: {:.0} ms", self.fade_time * 1000.0))
+								.width(Length::Units(200)),
+						)
+						.push(
+							Slider::new(
+								&mut self.fade_time_slider,
+								0.0..=MAX_FADE_TIME,
+								self.fade_time,
+								Message::SetFadeTime,
+							)
+							.step(0.005)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for StemMuteSoloDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "stem_mute_solo_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetMuted(index, _) => {
+				STEM_NAMES.get(*index).map(|name| format!("{} mute", name))
+			}
+			Message::SetSoloed(index, _) => {
+				STEM_NAMES.get(*index).map(|name| format!("{} solo", name))
+			}
+			Message::SetFadeTime(_) => Some("Fade time".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		if control == "Fade time" {
+			return vec![Message::SetFadeTime(value * MAX_FADE_TIME)];
+		}
+		for (index, name) in STEM_NAMES.iter().enumerate() {
+			if control == format!("{} mute", name) {
+				return vec![Message::SetMuted(index, value >= 0.5)];
+			}
+			if control == format!("{} solo", name) {
+				return vec![Message::SetSoloed(index, value >= 0.5)];
+			}
+		}
+		vec![]
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for StemMuteSoloDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "stem_mute_solo_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		let mut messages = vec![
+			Message::SetMetronomeClick(self.transport.click_enabled()),
+			Message::SetFadeTime(self.fade_time),
+		];
+		for (index, stem) in self.stems.iter().enumerate() {
+			messages.push(Message::SetMuted(index, stem.muted));
+			messages.push(Message::SetSoloed(index, stem.soloed));
+		}
+		messages
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for StemMuteSoloDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.transport.is_playing(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if !self.transport.is_playing() {
+			return None;
+		}
+		Some("Stem mute/solo demo".into())
+	}
+}
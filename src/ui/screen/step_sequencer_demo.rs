@@ -0,0 +1,369 @@
+mod step_grid;
+
+use std::error::Error;
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Row, Text};
+use kira::{
+	manager::{AudioManager, AudioManagerSettings},
+	playable::PlayableSettings,
+	sequence::{EventReceiver, Sequence, SequenceInstanceId},
+	sound::SoundId,
+	Duration, Tempo,
+};
+use serde::{Deserialize, Serialize};
+
+use self::step_grid::{StepGrid, Track};
+use crate::{
+	audio::{create_audio_manager, synth},
+	ui::{
+		common::{screen_wrapper::ScreenWrapper, transport::Transport},
+		style::AppStyles,
+		undo_stack::{UndoShortcut, UndoStack},
+	},
+};
+
+const EXPLANATION_TEXT: &str = "Click the steps to switch them on \
+and off, even while the pattern is playing. Ctrl+Z and Ctrl+Y undo \
+and redo.
+
+The pattern is played by a single looping sequence that plays \
+every active sound on each step and then waits a sixteenth note. \
+Sequences measure their waits in beats, so the pattern stays in \
+time with the metronome.
+
+A sequence can't be changed once it's started, so every edit \
+replaces it. The new sequence waits for the next sixteenth note \
+on the metronome, plays the rest of the bar from the step after \
+the one that just played, and then loops the whole pattern. The \
+old sequence is stopped without stopping its sounds, so nothing \
+gets cut off. Each sequence emits an event on every step, which \
+is how the screen knows which step is playing.";
+
+pub const TEMPO: Tempo = Tempo(120.0);
+const NUM_TRACKS: usize = 6;
+const NUM_STEPS: usize = 16;
+/// How long each step lasts (in beats).
+const STEP_LENGTH: f64 = 0.25;
+const TRACK_NAMES: [&str; NUM_TRACKS] = ["Kick", "Snare", "Clap", "Closed hat", "Open hat", "Tom"];
+
+/// Which steps each track plays on.
+type Pattern = [[bool; NUM_STEPS]; NUM_TRACKS];
+
+const EMPTY_PATTERN: Pattern = [[false; NUM_STEPS]; NUM_TRACKS];
+
+/// A basic rock beat to start from.
+fn default_pattern() -> Pattern {
+	let mut pattern = EMPTY_PATTERN;
+	for step in [0, 8, 10].iter() {
+		pattern[0][*step] = true;
+	}
+	pattern[1][4] = true;
+	pattern[1][12] = true;
+	for step in (0..NUM_STEPS).step_by(2).filter(|step| *step != 14) {
+		pattern[3][step] = true;
+	}
+	pattern[4][14] = true;
+	pattern
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetMetronomeClick(bool),
+	/// Switches one step of a track on or off.
+	SetStep(usize, usize, bool),
+	SetPattern(Pattern),
+	Undo,
+	Redo,
+}
+
+/// The sequence playing the pattern, which emits the index of
+/// each step as it plays.
+struct Playback {
+	sequence_id: SequenceInstanceId,
+	events: EventReceiver<usize>,
+}
+
+pub struct StepSequencerDemo {
+	audio_manager: AudioManager,
+	sound_ids: Vec<SoundId>,
+	pattern: Pattern,
+	history: UndoStack<Pattern>,
+	playback: Option<Playback>,
+	/// The step that played last, if the pattern is playing.
+	current_step: Option<usize>,
+	transport: Transport,
+	screen_wrapper: ScreenWrapper<Message>,
+	clear_button: iced::button::State,
+	reset_button: iced::button::State,
+	undo_button: iced::button::State,
+	redo_button: iced::button::State,
+}
+
+impl StepSequencerDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(AudioManagerSettings {
+			metronome_settings: Transport::metronome_settings(TEMPO),
+			..audio_manager_settings
+		})?;
+		let transport = Transport::new(&mut audio_manager, TEMPO)?;
+		let sounds = vec![
+			synth::kick(50.0, 0.5, PlayableSettings::new()),
+			synth::snare(180.0, 0.25, PlayableSettings::new()),
+			synth::noise_hit(0.2, 0.3, PlayableSettings::new()),
+			synth::noise_hit(0.05, 1.0, PlayableSettings::new()),
+			synth::noise_hit(0.4, 1.0, PlayableSettings::new()),
+			synth::kick(130.0, 0.35, PlayableSettings::new()),
+		];
+		let mut sound_ids = vec![];
+		for sound in sounds {
+			sound_ids.push(audio_manager.add_sound(sound)?);
+		}
+		Ok(Self {
+			audio_manager,
+			sound_ids,
+			pattern: default_pattern(),
+			history: UndoStack::new(),
+			playback: None,
+			current_step: None,
+			transport,
+			screen_wrapper: ScreenWrapper::new(
+				"Step sequencer demo".into(),
+				Message::GoToDemoSelect,
+			),
+			clear_button: iced::button::State::new(),
+			reset_button: iced::button::State::new(),
+			undo_button: iced::button::State::new(),
+			redo_button: iced::button::State::new(),
+		})
+	}
+
+	fn add_step(&self, sequence: &mut Sequence<usize>, step: usize) {
+		sequence.emit(step);
+		for (track, steps) in self.pattern.iter().enumerate() {
+			if steps[step] {
+				sequence.play(self.sound_ids[track], Default::default());
+			}
+		}
+		sequence.wait(Duration::Beats(STEP_LENGTH));
+	}
+
+	/// Starts a sequence that plays the pattern from a step
+	/// to the end of the bar and then loops it, replacing the
+	/// sequence that was playing (if any).
+	fn start_sequence(&mut self, first_step: usize) -> Result<(), Box<dyn Error>> {
+		let mut sequence = Sequence::new(Default::default());
+		sequence.wait_for_interval(STEP_LENGTH);
+		for step in first_step..NUM_STEPS {
+			self.add_step(&mut sequence, step);
+		}
+		sequence.start_loop();
+		for step in 0..NUM_STEPS {
+			self.add_step(&mut sequence, step);
+		}
+		// the old sequence's sounds are left to ring out
+		if let Some(playback) = self.playback.take() {
+			self.audio_manager.stop_sequence(playback.sequence_id)?;
+		}
+		let (sequence_id, events) = self
+			.audio_manager
+			.start_sequence(sequence, Default::default())?;
+		self.playback = Some(Playback {
+			sequence_id,
+			events,
+		});
+		Ok(())
+	}
+
+	fn pop_step_events(&mut self) {
+		if let Some(playback) = &mut self.playback {
+			while let Some(step) = playback.events.pop().copied() {
+				self.current_step = Some(step);
+			}
+		}
+	}
+
+	/// Picks up the new pattern on the next step.
+	fn pattern_changed(&mut self) -> Result<(), Box<dyn Error>> {
+		if self.playback.is_none() {
+			return Ok(());
+		}
+		// the step might have moved on since the last redraw
+		self.pop_step_events();
+		let next_step = self.current_step.map_or(0, |step| (step + 1) % NUM_STEPS);
+		self.start_sequence(next_step)
+	}
+
+	fn edit(&mut self, pattern: Pattern) -> Result<(), Box<dyn Error>> {
+		if pattern == self.pattern {
+			return Ok(());
+		}
+		self.history.push(self.pattern);
+		self.pattern = pattern;
+		self.pattern_changed()
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play => {
+				self.current_step = None;
+				self.start_sequence(0)?;
+				self.transport.start(&mut self.audio_manager)?;
+			}
+			Message::Stop => {
+				if let Some(playback) = self.playback.take() {
+					self.audio_manager
+						.stop_sequence_and_instances(playback.sequence_id, Default::default())?;
+				}
+				self.current_step = None;
+				self.transport.stop(&mut self.audio_manager)?;
+			}
+			Message::SetMetronomeClick(enabled) => {
+				self.transport
+					.set_click_enabled(&mut self.audio_manager, enabled)?;
+			}
+			Message::SetStep(track, step, active) if track < NUM_TRACKS && step < NUM_STEPS => {
+				let mut pattern = self.pattern;
+				pattern[track][step] = active;
+				self.edit(pattern)?;
+			}
+			Message::SetPattern(pattern) => self.edit(pattern)?,
+			Message::Undo if self.history.undo(&mut self.pattern) => self.pattern_changed()?,
+			Message::Redo if self.history.redo(&mut self.pattern) => self.pattern_changed()?,
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		self.transport.check_for_events(&mut self.audio_manager);
+		self.pop_step_events();
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let pattern = self.pattern;
+		let tracks = TRACK_NAMES
+			.iter()
+			.zip(self.pattern.iter())
+			.map(|(name, steps)| Track {
+				name,
+				steps: &steps[..],
+			})
+			.collect();
+		let mut undo_button = Button::new(
+			&mut self.undo_button,
+			Text::new("Undo")
+				.width(Length::Fill)
+				.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.width(Length::Units(100))
+		.style(AppStyles);
+		if self.history.can_undo() {
+			undo_button = undo_button.on_press(Message::Undo);
+		}
+		let mut redo_button = Button::new(
+			&mut self.redo_button,
+			Text::new("Redo")
+				.width(Length::Fill)
+				.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.width(Length::Units(100))
+		.style(AppStyles);
+		if self.history.can_redo() {
+			redo_button = redo_button.on_press(Message::Redo);
+		}
+
+		self.screen_wrapper.view_with_transport(
+			self.transport
+				.view(Message::Play, Message::Stop, Message::SetMetronomeClick),
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(StepGrid::new(tracks).playhead(self.current_step).on_toggle(
+					move |track, step| Message::SetStep(track, step, !pattern[track][step]),
+				))
+				.push(
+					Row::new()
+						.spacing(16)
+						.push(undo_button)
+						.push(redo_button)
+						.push(
+							Button::new(
+								&mut self.clear_button,
+								Text::new("Clear")
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(Message::SetPattern(EMPTY_PATTERN))
+							.width(Length::Units(100))
+							.style(AppStyles),
+						)
+						.push(
+							Button::new(
+								&mut self.reset_button,
+								Text::new("Reset")
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(Message::SetPattern(default_pattern()))
+							.width(Length::Units(100))
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+impl crate::ui::undo_stack::UndoControls for StepSequencerDemo {
+	type Message = Message;
+
+	fn undo_shortcut_message(&self, shortcut: UndoShortcut) -> Option<Message> {
+		match shortcut {
+			UndoShortcut::Undo if self.history.can_undo() => Some(Message::Undo),
+			UndoShortcut::Redo if self.history.can_redo() => Some(Message::Redo),
+			_ => None,
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for StepSequencerDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "step_sequencer_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetMetronomeClick(self.transport.click_enabled()),
+			Message::SetPattern(self.pattern),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for StepSequencerDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.transport.is_playing(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if !self.transport.is_playing() {
+			return None;
+		}
+		Some("Step sequencer demo".into())
+	}
+}
@@ -0,0 +1,223 @@
+use iced::{
+	mouse::Interaction, Background, Color, Font, HorizontalAlignment, Length, Point, Rectangle,
+	Size, VerticalAlignment,
+};
+use iced_graphics::{Backend, Defaults, Primitive, Renderer};
+use iced_native::{
+	event::Status,
+	layout::{Limits, Node},
+	mouse, Clipboard, Element, Event, Layout, Widget,
+};
+
+const STEPS_PER_BEAT: usize = 4;
+const NAME_WIDTH: f32 = 100.0;
+const CELL_SIZE: f32 = 32.0;
+const SPACING: f32 = 4.0;
+/// The extra space between beats, so the steps read in groups.
+const BEAT_SPACING: f32 = 8.0;
+const CELL_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.1];
+/// Every other beat is a little lighter.
+const ALTERNATE_BEAT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.15];
+const ACTIVE_COLOR: [f32; 4] = [1.0, 0.6, 0.0, 1.0];
+const PLAYHEAD_BORDER_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.8];
+const NAME_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const NAME_SIZE: f32 = 18.0;
+
+/// One sound's row of steps.
+pub struct Track<'a> {
+	pub name: &'a str,
+	pub steps: &'a [bool],
+}
+
+/// A grid of steps, one row per sound, that can be switched on
+/// and off by clicking them.
+pub struct StepGrid<'a, Message> {
+	tracks: Vec<Track<'a>>,
+	/// The step that's playing, which is outlined.
+	playhead: Option<usize>,
+	on_toggle: Option<Box<dyn Fn(usize, usize) -> Message + 'a>>,
+}
+
+impl<'a, Message> StepGrid<'a, Message> {
+	pub fn new(tracks: Vec<Track<'a>>) -> Self {
+		Self {
+			tracks,
+			playhead: None,
+			on_toggle: None,
+		}
+	}
+
+	pub fn playhead(self, playhead: Option<usize>) -> Self {
+		Self { playhead, ..self }
+	}
+
+	/// Sets the message to send when a step is clicked, given
+	/// the track and step indices.
+	pub fn on_toggle(self, on_toggle: impl Fn(usize, usize) -> Message + 'a) -> Self {
+		Self {
+			on_toggle: Some(Box::new(on_toggle)),
+			..self
+		}
+	}
+
+	fn num_steps(&self) -> usize {
+		self.tracks
+			.iter()
+			.map(|track| track.steps.len())
+			.max()
+			.unwrap_or(0)
+	}
+
+	fn size(&self) -> Size {
+		let cells_width = self
+			.num_steps()
+			.checked_sub(1)
+			.map_or(0.0, |last_step| Self::step_x(last_step) + CELL_SIZE);
+		let rows = self.tracks.len() as f32;
+		Size::new(
+			NAME_WIDTH + cells_width,
+			CELL_SIZE * rows + SPACING * (rows - 1.0).max(0.0),
+		)
+	}
+
+	/// How far from the left of the cells a step starts.
+	fn step_x(step: usize) -> f32 {
+		step as f32 * (CELL_SIZE + SPACING) + (step / STEPS_PER_BEAT) as f32 * BEAT_SPACING
+	}
+
+	fn cell_bounds(bounds: Rectangle, track: usize, step: usize) -> Rectangle {
+		Rectangle::new(
+			Point::new(
+				bounds.x + NAME_WIDTH + Self::step_x(step),
+				bounds.y + track as f32 * (CELL_SIZE + SPACING),
+			),
+			Size::new(CELL_SIZE, CELL_SIZE),
+		)
+	}
+
+	fn cell_at(&self, bounds: Rectangle, point: Point) -> Option<(usize, usize)> {
+		self.tracks
+			.iter()
+			.enumerate()
+			.flat_map(|(index, track)| (0..track.steps.len()).map(move |step| (index, step)))
+			.find(|(track, step)| Self::cell_bounds(bounds, *track, *step).contains(point))
+	}
+}
+
+impl<'a, Message, B: Backend> Widget<Message, Renderer<B>> for StepGrid<'a, Message> {
+	fn width(&self) -> Length {
+		Length::Units(self.size().width as u16)
+	}
+
+	fn height(&self) -> Length {
+		Length::Units(self.size().height as u16)
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, limits: &Limits) -> Node {
+		let size = self.size();
+		Node::new(
+			limits
+				.width(Length::Units(size.width as u16))
+				.height(Length::Units(size.height as u16))
+				.resolve(Size::ZERO),
+		)
+	}
+
+	fn on_event(
+		&mut self,
+		event: Event,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		messages: &mut Vec<Message>,
+		_renderer: &Renderer<B>,
+		_clipboard: Option<&dyn Clipboard>,
+	) -> Status {
+		let on_toggle = match &self.on_toggle {
+			Some(on_toggle) => on_toggle,
+			None => return Status::Ignored,
+		};
+		match event {
+			Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+				match self.cell_at(layout.bounds(), cursor_position) {
+					Some((track, step)) => {
+						messages.push(on_toggle(track, step));
+						Status::Captured
+					}
+					None => Status::Ignored,
+				}
+			}
+			_ => Status::Ignored,
+		}
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		_defaults: &Defaults,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let mut primitives = vec![];
+		for (index, track) in self.tracks.iter().enumerate() {
+			primitives.push(Primitive::Text {
+				content: track.name.to_string(),
+				bounds: Rectangle::new(
+					Point::new(
+						bounds.x,
+						bounds.y + index as f32 * (CELL_SIZE + SPACING) + CELL_SIZE / 2.0,
+					),
+					Size::new(NAME_WIDTH, 0.0),
+				),
+				color: NAME_COLOR.into(),
+				size: NAME_SIZE,
+				font: Font::Default,
+				horizontal_alignment: HorizontalAlignment::Left,
+				vertical_alignment: VerticalAlignment::Center,
+			});
+			for (step, active) in track.steps.iter().enumerate() {
+				let color = if *active {
+					ACTIVE_COLOR
+				} else if (step / STEPS_PER_BEAT) % 2 == 1 {
+					ALTERNATE_BEAT_COLOR
+				} else {
+					CELL_COLOR
+				};
+				let playing = self.playhead == Some(step);
+				primitives.push(Primitive::Quad {
+					bounds: Self::cell_bounds(bounds, index, step),
+					background: Background::Color(color.into()),
+					border_radius: 4.0,
+					border_width: if playing { 2.0 } else { 0.0 },
+					border_color: if playing {
+						PLAYHEAD_BORDER_COLOR.into()
+					} else {
+						Color::TRANSPARENT
+					},
+				});
+			}
+		}
+		let interaction =
+			if self.on_toggle.is_some() && self.cell_at(bounds, cursor_position).is_some() {
+				Interaction::Pointer
+			} else {
+				Interaction::default()
+			};
+		(Primitive::Group { primitives }, interaction)
+	}
+
+	fn hash_layout(&self, state: &mut iced_native::Hasher) {
+		use std::hash::Hash;
+		self.tracks.len().hash(state);
+		self.num_steps().hash(state);
+	}
+}
+
+impl<'a, Message: 'a, B: Backend + 'a> From<StepGrid<'a, Message>>
+	for Element<'a, Message, Renderer<B>>
+{
+	fn from(step_grid: StepGrid<'a, Message>) -> Self {
+		Element::new(step_grid)
+	}
+}
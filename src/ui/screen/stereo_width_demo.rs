@@ -0,0 +1,297 @@
+use std::error::Error;
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Row, Slider, Text};
+use kira::{
+	instance::{InstanceId, InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::{ParameterId, Tween},
+	playable::PlayableSettings,
+	sound::SoundId,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, sound_cache::SoundCache},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &["drum fill demo/loop.ogg"];
+
+const EXPLANATION_TEXT: &str = "The drum loop is mixed down to \
+mono and played twice at once, one copy panned left and one \
+panned right, with the right one running a little behind. Width \
+sets how far apart the two pans are, and delay sets how far \
+behind the right copy is.
+
+Ears decide where a sound comes from by whichever side hears it \
+first, so a delay of a millisecond or less pulls the loop \
+towards the left. Between a few milliseconds and about 30, the \
+two copies still fuse into one sound, but it seems to come from \
+everywhere at once - this is the Haas effect. Any longer and the \
+right copy turns into a separate echo. With the width at 0 both \
+copies come out of the middle, and the delay makes the loop \
+sound hollow instead, because the copies cancel out some \
+frequencies and reinforce others.
+
+The right copy starts from the top of the loop, and the left one \
+starts the delay's worth of seconds in, so it's ahead by that \
+much. Changing the delay seeks the left copy by the difference, \
+which keeps both copies in place without restarting them. Their \
+pans are parameters, so width changes are tweened.";
+
+const MAX_DELAY: f64 = 50.0;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	/// Sets how far behind the right copy plays (in
+	/// milliseconds).
+	SetDelay(f64),
+	/// Sets how far apart the copies are panned (from 0 to 1).
+	SetWidth(f64),
+}
+
+pub struct StereoWidthDemo {
+	audio_manager: AudioManager,
+	loop_sound_id: SoundId,
+	/// The left and right copies of the loop, if it's playing.
+	instance_ids: Option<(InstanceId, InstanceId)>,
+	left_panning_parameter_id: ParameterId,
+	right_panning_parameter_id: ParameterId,
+	delay: f64,
+	width: f64,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_button: iced::button::State,
+	delay_slider: iced::slider::State,
+	width_slider: iced::slider::State,
+}
+
+impl StereoWidthDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		// both copies start at the same time, which a cooldown
+		// would block
+		let loop_sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&std::env::current_dir()?.join("assets").join(ASSETS[0]))?
+				.to_mono()
+				.to_sound(PlayableSettings {
+					cooldown: None,
+					..PlayableSettings::new().default_loop_start(0.0)
+				}),
+		)?;
+		let width = 1.0;
+		let left_panning_parameter_id = audio_manager.add_parameter(0.5 - width / 2.0)?;
+		let right_panning_parameter_id = audio_manager.add_parameter(0.5 + width / 2.0)?;
+		Ok(Self {
+			audio_manager,
+			loop_sound_id,
+			instance_ids: None,
+			left_panning_parameter_id,
+			right_panning_parameter_id,
+			delay: 15.0,
+			width,
+			screen_wrapper: ScreenWrapper::new("Stereo width demo".into(), Message::GoToDemoSelect),
+			play_button: iced::button::State::new(),
+			delay_slider: iced::slider::State::new(),
+			width_slider: iced::slider::State::new(),
+		})
+	}
+
+	/// Describes what the current settings should sound like.
+	fn effect_text(&self) -> &'static str {
+		if self.width == 0.0 {
+			if self.delay == 0.0 {
+				"Plain mono"
+			} else {
+				"Mono, with a hollow, phasey tone"
+			}
+		} else if self.delay <= 1.0 {
+			"Panned, leaning towards the left"
+		} else if self.delay <= 30.0 {
+			"Wide - one sound from both sides"
+		} else {
+			"An echo on the right"
+		}
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if self.instance_ids.is_none() => {
+				let left_instance_id = self.audio_manager.play(
+					self.loop_sound_id,
+					InstanceSettings::new()
+						.panning(self.left_panning_parameter_id)
+						.start_position(self.delay / 1000.0),
+				)?;
+				let right_instance_id = self.audio_manager.play(
+					self.loop_sound_id,
+					InstanceSettings::new().panning(self.right_panning_parameter_id),
+				)?;
+				self.instance_ids = Some((left_instance_id, right_instance_id));
+			}
+			Message::Stop => {
+				if let Some((left_instance_id, right_instance_id)) = self.instance_ids.take() {
+					for instance_id in [left_instance_id, right_instance_id].iter().copied() {
+						self.audio_manager.stop_instance(
+							instance_id,
+							StopInstanceSettings::new().fade_tween(Tween::linear(0.25)),
+						)?;
+					}
+				}
+			}
+			Message::SetDelay(delay) => {
+				let delay = delay.clamp(0.0, MAX_DELAY);
+				if let Some((left_instance_id, _)) = self.instance_ids {
+					self.audio_manager
+						.seek_instance(left_instance_id, (delay - self.delay) / 1000.0)?;
+				}
+				self.delay = delay;
+			}
+			Message::SetWidth(width) => {
+				self.width = width.clamp(0.0, 1.0);
+				let tween = Some(Tween::linear(0.05));
+				self.audio_manager.set_parameter(
+					self.left_panning_parameter_id,
+					0.5 - self.width / 2.0,
+					tween,
+				)?;
+				self.audio_manager.set_parameter(
+					self.right_panning_parameter_id,
+					0.5 + self.width / 2.0,
+					tween,
+				)?;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.instance_ids.is_some();
+		let effect_text = self.effect_text();
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Button::new(
+						&mut self.play_button,
+						Text::new(if playing { "Stop" } else { "Play" })
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(if playing {
+						Message::Stop
+					} else {
+						Message::Play
+					})
+					.width(Length::Units(100))
+					.style(AppStyles),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Delay: {:.1} ms", self.delay))
+								.width(Length::Units(150)),
+						)
+						.push(
+							Slider::new(
+								&mut self.delay_slider,
+								0.0..=MAX_DELAY,
+								self.delay,
+								Message::SetDelay,
+							)
+							.step(0.5)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Width: {:.2}", self.width))
+								.width(Length::Units(150)),
+						)
+						.push(
+							Slider::new(
+								&mut self.width_slider,
+								0.0..=1.0,
+								self.width,
+								Message::SetWidth,
+							)
+							.step(0.01)
+							.style(AppStyles),
+						),
+				)
+				.push(Text::new(effect_text))
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for StereoWidthDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "stereo_width_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetDelay(_) => Some("Delay".into()),
+			Message::SetWidth(_) => Some("Width".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Delay" => vec![Message::SetDelay(MAX_DELAY * value)],
+			"Width" => vec![Message::SetWidth(value)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for StereoWidthDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "stereo_width_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![Message::SetDelay(self.delay), Message::SetWidth(self.width)]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for StereoWidthDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.instance_ids.is_some(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		self.instance_ids.map(|_| "Stereo width demo".to_string())
+	}
+}
@@ -0,0 +1,357 @@
+mod countdown;
+
+use std::error::Error;
+
+use countdown::Countdown;
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Radio, Row, Text};
+use kira::{
+	instance::InstanceSettings,
+	manager::{AudioManager, AudioManagerSettings},
+	playable::PlayableSettings,
+	sequence::{EventReceiver, Sequence, SequenceInstanceId},
+	sound::SoundId,
+	AudioResult, Duration, Tempo,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, sound_cache::SoundCache, synth},
+	ui::{
+		common::{screen_wrapper::ScreenWrapper, transport::Transport},
+		style::AppStyles,
+	},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &["drum fill demo/loop.ogg"];
+
+pub const TEMPO: Tempo = Tempo(128.0);
+const BEATS_PER_BAR: usize = 4;
+
+const EXPLANATION_TEXT: &str = "Stingers are short musical \
+phrases that play over the music when something happens in \
+a game. They sound best when they land in time with the \
+music, so instead of playing right away, each one is queued \
+in a sequence that starts with wait_for_interval.
+
+The sequence waits until the metronome reaches the next \
+multiple of the interval (1 beat, 2 beats, or a whole bar), \
+then plays the stinger. The bar above shows how far away \
+that point is.";
+
+/// The stingers, each played as a few notes of a blip
+/// a quarter of a beat apart.
+const STINGERS: [(&str, &[f64]); 3] = [
+	("Hit", &[0.5]),
+	("Rise", &[1.0, 1.25, 1.5, 2.0]),
+	("Fall", &[2.0, 1.5, 1.25, 1.0]),
+];
+
+/// Where queued stingers land.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Quantize {
+	Beat,
+	HalfBar,
+	Bar,
+}
+
+impl Quantize {
+	const ALL: [Self; 3] = [Self::Beat, Self::HalfBar, Self::Bar];
+
+	/// The length of the interval (in beats).
+	fn beats(self) -> usize {
+		match self {
+			Self::Beat => 1,
+			Self::HalfBar => BEATS_PER_BAR / 2,
+			Self::Bar => BEATS_PER_BAR,
+		}
+	}
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::Beat => "Next beat",
+			Self::HalfBar => "Next half-bar",
+			Self::Bar => "Next bar",
+		}
+	}
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetMetronomeClick(bool),
+	SetQuantize(Quantize),
+	/// Queues up one of the stingers (by its index).
+	PlayStinger(usize),
+}
+
+pub struct StingerDemo {
+	audio_manager: AudioManager,
+	loop_sound_id: SoundId,
+	stinger_sound_id: SoundId,
+	loop_sequence_id: Option<SequenceInstanceId>,
+	/// The stingers that are waiting to land. The sequences
+	/// emit the stinger's index when they start playing it.
+	queued_stingers: Vec<(SequenceInstanceId, EventReceiver<usize>)>,
+	/// The last stinger that landed and the beat it landed on.
+	last_landed: Option<(usize, usize)>,
+	quantize: Quantize,
+	transport: Transport,
+	screen_wrapper: ScreenWrapper<Message>,
+	stinger_buttons: [iced::button::State; 3],
+}
+
+impl StingerDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(AudioManagerSettings {
+			metronome_settings: Transport::metronome_settings(TEMPO),
+			..audio_manager_settings
+		})?;
+		let transport = Transport::new(&mut audio_manager, TEMPO)?;
+		let loop_sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&std::env::current_dir()?.join("assets/drum fill demo/loop.ogg"))?
+				.to_sound(PlayableSettings::new()),
+		)?;
+		// without a cooldown, the notes of a stinger can overlap
+		let stinger_sound_id = audio_manager.add_sound(synth::blip(
+			880.0,
+			0.4,
+			PlayableSettings {
+				cooldown: None,
+				..Default::default()
+			},
+		))?;
+		Ok(Self {
+			audio_manager,
+			loop_sound_id,
+			stinger_sound_id,
+			loop_sequence_id: None,
+			queued_stingers: vec![],
+			last_landed: None,
+			quantize: Quantize::Beat,
+			transport,
+			screen_wrapper: ScreenWrapper::new("Stinger demo".into(), Message::GoToDemoSelect),
+			stinger_buttons: Default::default(),
+		})
+	}
+
+	fn start_loop_sequence(&mut self) -> AudioResult<SequenceInstanceId> {
+		let mut sequence = Sequence::<()>::new(Default::default());
+		sequence.wait_for_interval(1.0);
+		sequence.start_loop();
+		sequence.play(self.loop_sound_id, Default::default());
+		sequence.wait(Duration::Beats(BEATS_PER_BAR as f64));
+		let (sequence_id, _) = self
+			.audio_manager
+			.start_sequence(sequence, Default::default())?;
+		Ok(sequence_id)
+	}
+
+	fn queue_stinger(&mut self, index: usize) -> AudioResult<()> {
+		let mut sequence = Sequence::new(Default::default());
+		sequence.wait_for_interval(self.quantize.beats() as f64);
+		sequence.emit(index);
+		for pitch in STINGERS[index].1 {
+			sequence.play(self.stinger_sound_id, InstanceSettings::new().pitch(*pitch));
+			sequence.wait(Duration::Beats(0.25));
+		}
+		self.queued_stingers.push(
+			self.audio_manager
+				.start_sequence(sequence, Default::default())?,
+		);
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play => {
+				self.loop_sequence_id = Some(self.start_loop_sequence()?);
+				self.transport.start(&mut self.audio_manager)?;
+			}
+			Message::Stop => {
+				if let Some(sequence_id) = self.loop_sequence_id.take() {
+					self.audio_manager
+						.stop_sequence_and_instances(sequence_id, Default::default())?;
+				}
+				// queued stingers would otherwise wait forever
+				// for the stopped metronome
+				for (sequence_id, _) in self.queued_stingers.drain(..) {
+					self.audio_manager.stop_sequence(sequence_id)?;
+				}
+				self.last_landed = None;
+				self.transport.stop(&mut self.audio_manager)?;
+			}
+			Message::SetMetronomeClick(enabled) => {
+				self.transport
+					.set_click_enabled(&mut self.audio_manager, enabled)?;
+			}
+			Message::SetQuantize(quantize) => {
+				self.quantize = quantize;
+			}
+			Message::PlayStinger(index) if self.transport.is_playing() => {
+				self.queue_stinger(index.min(STINGERS.len() - 1))?;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		self.transport.check_for_events(&mut self.audio_manager);
+		let beat = self
+			.transport
+			.beat_position()
+			.map(|position| position.round() as usize);
+		let mut last_landed = self.last_landed;
+		self.queued_stingers
+			.retain_mut(|(_, event_receiver)| match event_receiver.pop() {
+				Some(index) => {
+					if let Some(beat) = beat {
+						last_landed = Some((*index, beat));
+					}
+					false
+				}
+				None => true,
+			});
+		self.last_landed = last_landed;
+		Ok(())
+	}
+
+	/// How far the music is through the current interval
+	/// (from 0 to 1) and how many beats are left until the next
+	/// quantization point.
+	fn countdown(&self) -> Option<(f64, f64)> {
+		let beats = self.quantize.beats() as f64;
+		let position = self.transport.beat_position()?;
+		let progress = (position % beats) / beats;
+		Some((progress, beats - position % beats))
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.transport.is_playing();
+		let countdown = self.countdown();
+		let stinger_buttons = self.stinger_buttons.iter_mut().enumerate().fold(
+			Row::new().spacing(8),
+			|row, (index, button)| {
+				let mut button = Button::new(
+					button,
+					Text::new(STINGERS[index].0)
+						.width(Length::Fill)
+						.horizontal_alignment(HorizontalAlignment::Center),
+				)
+				.width(Length::Units(120))
+				.style(AppStyles);
+				if playing {
+					button = button.on_press(Message::PlayStinger(index));
+				}
+				row.push(button)
+			},
+		);
+		let quantize = self.quantize;
+		let quantize_radios = Quantize::ALL.iter().fold(
+			Row::new().spacing(16).align_items(Align::Center),
+			|row, option| {
+				row.push(Radio::new(
+					*option,
+					option.name(),
+					Some(quantize),
+					Message::SetQuantize,
+				))
+			},
+		);
+		let countdown_text = match countdown {
+			Some((_, beats_left)) => format!(
+				"{} in {:.2} beats ({} ms)",
+				quantize.name(),
+				beats_left,
+				(TEMPO.beats_to_seconds(beats_left) * 1000.0).round()
+			),
+			None => "Press Play to start the music".into(),
+		};
+		let status_text = match (self.queued_stingers.len(), self.last_landed) {
+			(0, None) => "No stingers queued".to_string(),
+			(0, Some((index, beat))) => format!(
+				"{} landed on {}:{}",
+				STINGERS[index].0,
+				beat / BEATS_PER_BAR + 1,
+				beat % BEATS_PER_BAR + 1
+			),
+			(1, _) => "1 stinger queued".to_string(),
+			(queued, _) => format!("{} stingers queued", queued),
+		};
+
+		self.screen_wrapper.view_with_transport(
+			self.transport
+				.view(Message::Play, Message::Stop, Message::SetMetronomeClick),
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(quantize_radios)
+				.push(stinger_buttons)
+				.push(Countdown {
+					beats: quantize.beats(),
+					progress: countdown.map(|(progress, _)| progress),
+					pending: !self.queued_stingers.is_empty(),
+				})
+				.push(Text::new(countdown_text))
+				.push(Text::new(status_text))
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for StingerDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "stinger_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetMetronomeClick(self.transport.click_enabled()),
+			Message::SetQuantize(self.quantize),
+		]
+	}
+}
+
+#[cfg(feature = "remote")]
+impl crate::ui::remote_control::RemoteControls for StingerDemo {
+	type Message = Message;
+
+	fn remote_messages(&self, command: crate::remote::RemoteCommand) -> Vec<Message> {
+		match command {
+			crate::remote::RemoteCommand::PlayStinger => vec![Message::PlayStinger(0)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for StingerDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.transport.is_playing(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if !self.transport.is_playing() {
+			return None;
+		}
+		Some("Stinger demo".into())
+	}
+}
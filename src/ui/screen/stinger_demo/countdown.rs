@@ -0,0 +1,99 @@
+use iced::{mouse::Interaction, Background, Color, Length, Point, Rectangle, Size};
+use iced_graphics::{Backend, Defaults, Primitive, Renderer};
+use iced_native::{
+	layout::{Limits, Node},
+	Element, Layout, Widget,
+};
+
+const SIZE: Size = Size::new(400.0, 16.0);
+const GAP: f32 = 4.0;
+const IDLE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.1];
+const PASSED_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.5];
+const PENDING_COLOR: [f32; 4] = [1.0, 0.0, 0.0, 0.75];
+
+/// Shows how far the music is through the current stretch
+/// between quantization points, with one segment per beat.
+/// The bar fills up as the next quantization point gets closer.
+pub struct Countdown {
+	/// How many beats there are between quantization points.
+	pub beats: usize,
+	/// How far through the stretch the music is (from 0 to 1),
+	/// or `None` if it's not playing.
+	pub progress: Option<f64>,
+	/// Whether a stinger is waiting for the next quantization
+	/// point, which colors the bar in.
+	pub pending: bool,
+}
+
+impl Countdown {
+	fn quad(bounds: Rectangle, color: [f32; 4]) -> Primitive {
+		Primitive::Quad {
+			bounds,
+			background: Background::Color(color.into()),
+			border_radius: 2.0,
+			border_width: 0.0,
+			border_color: Color::TRANSPARENT,
+		}
+	}
+}
+
+impl<Message, B: Backend> Widget<Message, Renderer<B>> for Countdown {
+	fn width(&self) -> Length {
+		Length::Shrink
+	}
+
+	fn height(&self) -> Length {
+		Length::Shrink
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, _limits: &Limits) -> Node {
+		Node::new(SIZE)
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		_defaults: &Defaults,
+		layout: Layout<'_>,
+		_cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let beats = self.beats.max(1);
+		let segment_width = (bounds.width - GAP * (beats - 1) as f32) / beats as f32;
+		let filled_beats = self.progress.unwrap_or(0.0) as f32 * beats as f32;
+		let filled_color = if self.pending {
+			PENDING_COLOR
+		} else {
+			PASSED_COLOR
+		};
+		let mut primitives = vec![];
+		for beat in 0..beats {
+			let x = bounds.x + (segment_width + GAP) * beat as f32;
+			let segment = Rectangle::new(
+				Point::new(x, bounds.y),
+				Size::new(segment_width, bounds.height),
+			);
+			primitives.push(Self::quad(segment, IDLE_COLOR));
+			let filled = (filled_beats - beat as f32).clamp(0.0, 1.0);
+			if filled > 0.0 {
+				primitives.push(Self::quad(
+					Rectangle::new(
+						segment.position(),
+						Size::new(segment_width * filled, bounds.height),
+					),
+					filled_color,
+				));
+			}
+		}
+		(Primitive::Group { primitives }, Interaction::default())
+	}
+
+	fn hash_layout(&self, _state: &mut iced_native::Hasher) {}
+}
+
+impl<'a, Message, B: Backend> From<Countdown> for Element<'a, Message, Renderer<B>> {
+	fn from(countdown: Countdown) -> Self {
+		Element::new(countdown)
+	}
+}
@@ -0,0 +1,270 @@
+use std::{error::Error, time::Instant};
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Row, Text};
+use kira::{
+	instance::{InstanceId, InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::{EaseDirection, Easing, Tween},
+	playable::PlayableSettings,
+	sound::SoundId,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, sound_cache::SoundCache},
+	ui::{
+		common::{screen_wrapper::ScreenWrapper, waveform::Waveform},
+		style::AppStyles,
+	},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &["drum fill demo/loop.ogg"];
+
+const EXPLANATION_TEXT: &str = "This demo stops the same loop \
+with different fade-out tweens using StopInstanceSettings. \
+The plot shows the volume of the instance over time, starting \
+just before it's stopped.
+
+A linear fade sounds like it holds on and then drops out \
+suddenly at the end, because we hear loudness on a roughly \
+logarithmic scale. An eased curve that falls fast and tails \
+off sounds more even. Very short fades are good for cutting a sound off \
+without a click, and very long fades work for letting music \
+die away.";
+
+/// How much of the plot comes before the instance is stopped.
+const PRE_STOP_DURATION: f64 = 0.5;
+const PLOT_DURATION: f64 = 5.0;
+const NUM_POINTS: usize = 200;
+const PLAYHEAD_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Fade {
+	Linear,
+	Exponential,
+	VeryShort,
+	VeryLong,
+}
+
+impl Fade {
+	const ALL: [Self; 4] = [
+		Self::Linear,
+		Self::Exponential,
+		Self::VeryShort,
+		Self::VeryLong,
+	];
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::Linear => "Linear (1s)",
+			Self::Exponential => "Exponential-ish (1s)",
+			Self::VeryShort => "Very short (1ms)",
+			Self::VeryLong => "Very long (4s)",
+		}
+	}
+
+	fn tween(self) -> Tween {
+		match self {
+			Self::Linear => Tween::linear(1.0),
+			// the volume goes from 1 to 0, so easing out with a
+			// cubic curve gives (1 - t)^3: a fast drop with a long tail
+			Self::Exponential => Tween {
+				duration: 1.0,
+				easing: Easing::PowI(3),
+				ease_direction: EaseDirection::Out,
+			},
+			// the same fade Kira uses by default
+			Self::VeryShort => Tween::linear(0.001),
+			Self::VeryLong => Tween::linear(4.0),
+		}
+	}
+
+	/// Gets the instance's volume at a time (in seconds) after
+	/// it was stopped.
+	fn volume(self, time: f64) -> f64 {
+		let tween = self.tween();
+		if time <= 0.0 {
+			1.0
+		} else if time >= tween.duration {
+			0.0
+		} else {
+			tween.tween(1.0, 0.0, time)
+		}
+	}
+
+	/// Gets the volume over the length of the plot.
+	fn envelope(self) -> Vec<f32> {
+		(0..NUM_POINTS)
+			.map(|i| {
+				let time = PLOT_DURATION * i as f64 / NUM_POINTS as f64 - PRE_STOP_DURATION;
+				self.volume(time) as f32
+			})
+			.collect()
+	}
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop(Fade),
+}
+
+pub struct StopFadeDemo {
+	audio_manager: AudioManager,
+	sound_id: SoundId,
+	instance_id: Option<InstanceId>,
+	last_fade: Option<Fade>,
+	envelope: Vec<f32>,
+	stop_time: Option<Instant>,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_button: iced::button::State,
+	fade_buttons: [iced::button::State; 4],
+}
+
+impl StopFadeDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let assets_base_dir = std::env::current_dir()?.join("assets/drum fill demo");
+		let sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&assets_base_dir.join("loop.ogg"))?
+				.to_sound(PlayableSettings::new().default_loop_start(0.0)),
+		)?;
+		Ok(Self {
+			audio_manager,
+			sound_id,
+			instance_id: None,
+			last_fade: None,
+			envelope: vec![1.0; NUM_POINTS],
+			stop_time: None,
+			screen_wrapper: ScreenWrapper::new("Stop fade demo".into(), Message::GoToDemoSelect),
+			play_button: iced::button::State::new(),
+			fade_buttons: Default::default(),
+		})
+	}
+
+	/// How long ago the instance was stopped, as long as the
+	/// plot still covers that time.
+	fn time_since_stop(&self) -> Option<f64> {
+		self.stop_time
+			.map(|stop_time| stop_time.elapsed().as_secs_f64())
+			.filter(|time| *time < PLOT_DURATION - PRE_STOP_DURATION)
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if self.instance_id.is_none() => {
+				self.instance_id = Some(
+					self.audio_manager
+						.play(self.sound_id, InstanceSettings::new())?,
+				);
+				self.stop_time = None;
+			}
+			Message::Stop(fade) => {
+				if let Some(instance_id) = self.instance_id.take() {
+					self.audio_manager.stop_instance(
+						instance_id,
+						StopInstanceSettings::new().fade_tween(fade.tween()),
+					)?;
+					self.last_fade = Some(fade);
+					self.envelope = fade.envelope();
+					self.stop_time = Some(Instant::now());
+				}
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		if self.time_since_stop().is_none() {
+			self.stop_time = None;
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.instance_id.is_some();
+		let time_since_stop = self.time_since_stop();
+
+		let mut play_button = Button::new(
+			&mut self.play_button,
+			Text::new("Play")
+				.width(Length::Fill)
+				.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.width(Length::Units(100))
+		.style(AppStyles);
+		if !playing {
+			play_button = play_button.on_press(Message::Play);
+		}
+		let fade_buttons = self.fade_buttons.iter_mut().zip(Fade::ALL.iter()).fold(
+			Row::new().spacing(8),
+			|row, (state, fade)| {
+				let mut button = Button::new(state, Text::new(fade.name())).style(AppStyles);
+				if playing {
+					button = button.on_press(Message::Stop(*fade));
+				}
+				row.push(button)
+			},
+		);
+
+		let mut plot = Waveform::new(&self.envelope).grid_lines(
+			(0..PLOT_DURATION as usize)
+				.map(|second| ((second as f64 + PRE_STOP_DURATION) / PLOT_DURATION) as f32)
+				.collect(),
+		);
+		if let Some(time) = time_since_stop {
+			plot = plot.marker(
+				((time + PRE_STOP_DURATION) / PLOT_DURATION) as f32,
+				PLAYHEAD_COLOR,
+			);
+		}
+		let plot_text = match self.last_fade {
+			Some(fade) => format!(
+				"{} - the lines mark each second after stopping",
+				fade.name()
+			),
+			None => "Stop the loop to plot its fade".into(),
+		};
+
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(plot)
+				.push(Text::new(plot_text).size(16))
+				.push(
+					Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(play_button)
+						.push(Text::new("Stop with:"))
+						.push(fade_buttons),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for StopFadeDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "stop_fade_demo";
+
+	// the screen only has buttons, so there's nothing to put back,
+	// but recorded sessions still need a name for it
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![]
+	}
+}
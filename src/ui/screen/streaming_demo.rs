@@ -0,0 +1,281 @@
+use std::{
+	error::Error,
+	path::PathBuf,
+	time::{Duration, Instant},
+};
+
+use iced::{Align, Checkbox, Column, Length, Row, Text, TextInput};
+use kira::{
+	audio_stream::AudioStreamId,
+	instance::InstanceSettings,
+	manager::{AudioManager, AudioManagerSettings},
+	mixer::TrackIndex,
+	playable::PlayableSettings,
+	sound::SoundId,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, decoded_sound::DecodedSound, streaming_sound::StreamingSound},
+	ui::{
+		common::{screen_wrapper::ScreenWrapper, transport::Transport},
+		screen::underwater_demo::TEMPO,
+	},
+};
+
+const EXPLANATION_TEXT: &str = "This demo plays a piece of \
+music either by decoding the whole file before playback starts \
+or by streaming it, decoding a little at a time on a background \
+thread and feeding it to an audio stream.
+
+Decoding up front takes longer and keeps the entire song in \
+memory, but the result can be played with all of Kira's instance \
+controls. Streaming starts right away and only keeps a few \
+packets in memory, which makes it a better fit for long \
+background music.
+
+The file is read from disk each time you press Play so the two \
+approaches can be compared fairly. The difference grows with the \
+length of the song, so try typing the path of a few minutes of \
+music in an .ogg file.";
+
+/// The size of a frame of audio in memory.
+const BYTES_PER_FRAME: usize = std::mem::size_of::<kira::Frame>();
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	SetPath(String),
+	SetStreaming(bool),
+	Play,
+	Stop,
+	SetMetronomeClick(bool),
+}
+
+enum Playback {
+	Decoded(SoundId),
+	Streaming(AudioStreamId),
+}
+
+/// How long a playback took to start and how much audio it
+/// kept in memory.
+struct LoadStats {
+	start_time: Duration,
+	/// For streams, this is the most that can be buffered.
+	bytes_in_memory: usize,
+	/// The length of the song (in seconds), which is only known
+	/// once it's been decoded.
+	duration: Option<f64>,
+}
+
+impl LoadStats {
+	fn text(&self) -> String {
+		format!(
+			"started in {:.1}ms, keeping {:.1}MB in memory",
+			self.start_time.as_secs_f64() * 1000.0,
+			self.bytes_in_memory as f64 / 1_000_000.0,
+		)
+	}
+}
+
+pub struct StreamingDemo {
+	audio_manager: AudioManager,
+	path_text: String,
+	streaming: bool,
+	playback: Option<Playback>,
+	/// The last stats for each approach, so they can be shown
+	/// side by side.
+	decoded_stats: Option<LoadStats>,
+	streaming_stats: Option<LoadStats>,
+	transport: Transport,
+	screen_wrapper: ScreenWrapper<Message>,
+	path_input: iced::text_input::State,
+}
+
+impl StreamingDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		// the song is the pad from the underwater demo
+		let mut audio_manager = create_audio_manager(AudioManagerSettings {
+			metronome_settings: Transport::metronome_settings(TEMPO),
+			..audio_manager_settings
+		})?;
+		let transport = Transport::new(&mut audio_manager, TEMPO)?;
+		Ok(Self {
+			audio_manager,
+			path_text: std::env::current_dir()?
+				.join("assets/underwater demo/pad.ogg")
+				.to_string_lossy()
+				.into_owned(),
+			streaming: true,
+			playback: None,
+			decoded_stats: None,
+			streaming_stats: None,
+			transport,
+			screen_wrapper: ScreenWrapper::new("Streaming demo".into(), Message::GoToDemoSelect),
+			path_input: iced::text_input::State::new(),
+		})
+	}
+
+	fn play(&mut self) -> Result<(), Box<dyn Error>> {
+		self.stop()?;
+		let path = PathBuf::from(self.path_text.trim());
+		let start = Instant::now();
+		if self.streaming {
+			let stream_id = self
+				.audio_manager
+				.add_stream(TrackIndex::Main, StreamingSound::new(&path)?)?;
+			self.playback = Some(Playback::Streaming(stream_id));
+			self.streaming_stats = Some(LoadStats {
+				start_time: start.elapsed(),
+				bytes_in_memory: StreamingSound::MAX_BUFFERED_FRAMES * BYTES_PER_FRAME,
+				duration: None,
+			});
+		} else {
+			let sound = DecodedSound::from_ogg_file(&path)?;
+			let sound_id = self
+				.audio_manager
+				.add_sound(sound.to_sound(PlayableSettings::new().default_loop_start(0.0)))?;
+			self.audio_manager
+				.play(sound_id, InstanceSettings::default())?;
+			self.playback = Some(Playback::Decoded(sound_id));
+			self.decoded_stats = Some(LoadStats {
+				start_time: start.elapsed(),
+				bytes_in_memory: sound.num_frames() * BYTES_PER_FRAME,
+				duration: Some(sound.duration()),
+			});
+		}
+		self.transport.start(&mut self.audio_manager)?;
+		Ok(())
+	}
+
+	fn stop(&mut self) -> Result<(), Box<dyn Error>> {
+		// the audio thread sends removed sounds and streams back
+		// to be dropped here, which frees the decoded audio and
+		// stops the streaming thread
+		self.audio_manager.free_unused_resources();
+		match self.playback.take() {
+			// removing the sound also stops its instances
+			Some(Playback::Decoded(sound_id)) => self.audio_manager.remove_sound(sound_id)?,
+			Some(Playback::Streaming(stream_id)) => self.audio_manager.remove_stream(stream_id)?,
+			None => {}
+		}
+		self.transport.stop(&mut self.audio_manager)?;
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			// the old stats were for a different song
+			Message::SetPath(path_text) => {
+				self.path_text = path_text;
+				self.decoded_stats = None;
+				self.streaming_stats = None;
+			}
+			Message::SetStreaming(streaming) => {
+				self.streaming = streaming;
+				if self.playback.is_some() {
+					self.play()?;
+				}
+			}
+			Message::Play => self.play()?,
+			Message::Stop => self.stop()?,
+			Message::SetMetronomeClick(enabled) => {
+				self.transport
+					.set_click_enabled(&mut self.audio_manager, enabled)?;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		self.transport.check_for_events(&mut self.audio_manager);
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let decoded_text = match &self.decoded_stats {
+			Some(stats) => format!("Decoding {}", stats.text()),
+			None => "Decoding hasn't been tried yet".into(),
+		};
+		let streaming_text = match &self.streaming_stats {
+			Some(stats) => format!("Streaming {} at most", stats.text()),
+			None => "Streaming hasn't been tried yet".into(),
+		};
+		let duration_text = match self.decoded_stats.as_ref().and_then(|stats| stats.duration) {
+			Some(duration) => format!(
+				"The song is {}:{:04.1} long",
+				(duration / 60.0).floor(),
+				duration % 60.0
+			),
+			None => "The song's length is found when it's decoded".into(),
+		};
+
+		self.screen_wrapper.view_with_transport(
+			self.transport
+				.view(Message::Play, Message::Stop, Message::SetMetronomeClick),
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Row::new().max_width(600).push(
+						TextInput::new(
+							&mut self.path_input,
+							"Path to an .ogg file",
+							&self.path_text,
+							Message::SetPath,
+						)
+						.padding(8),
+					),
+				)
+				.push(Checkbox::new(
+					self.streaming,
+					"Stream from disk",
+					Message::SetStreaming,
+				))
+				.push(Text::new(duration_text))
+				.push(Text::new(decoded_text))
+				.push(Text::new(streaming_text))
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for StreamingDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "streaming_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetPath(self.path_text.clone()),
+			Message::SetStreaming(self.streaming),
+			Message::SetMetronomeClick(self.transport.click_enabled()),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for StreamingDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.transport.is_playing(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if !self.transport.is_playing() {
+			return None;
+		}
+		Some("Streaming demo".into())
+	}
+}
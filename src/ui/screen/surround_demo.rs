@@ -0,0 +1,197 @@
+mod speaker_map;
+
+use std::{error::Error, f64::consts::TAU, time::Instant};
+
+use iced::{Align, Button, Checkbox, Column, HorizontalAlignment, Length, Row, Text};
+use kira::{manager::AudioManagerSettings, playable::PlayableSettings};
+use serde::{Deserialize, Serialize};
+
+use self::speaker_map::SpeakerMap;
+use crate::{
+	audio::{
+		sound_cache::SoundCache,
+		surround::{SurroundInstance, SurroundOutput, SurroundSound},
+	},
+	preferences::OutputMode,
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &["drum fill demo/loop.ogg"];
+
+const EXPLANATION_TEXT: &str = "This demo plays a loop from a \
+point around you. Click the map to move the sound, or check \
+Orbit to send it circling around the room.
+
+Kira mixes everything down to two channels, so when the output \
+device has four or more, the demo runs two audio managers side by \
+side, one feeding the front speakers and one feeding the rear, and \
+crossfades the sound between them as it moves. With only two \
+channels, the sound is panned in stereo and gets quieter as it \
+moves away, but front and back sound the same.";
+
+/// How far from the listener the sound orbits.
+const ORBIT_RADIUS: f64 = 0.8;
+/// How long it takes the sound to go all the way around (in seconds).
+const ORBIT_PERIOD: f64 = 8.0;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetPosition(f32, f32),
+	SetOrbiting(bool),
+}
+
+pub struct SurroundDemo {
+	output: SurroundOutput,
+	sound: SurroundSound,
+	instance: Option<SurroundInstance>,
+	position: (f64, f64),
+	orbiting: bool,
+	/// The angle of the sound around the listener, in radians
+	/// clockwise from straight ahead.
+	orbit_angle: f64,
+	last_tick: Instant,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_button: iced::button::State,
+}
+
+impl SurroundDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+		output_mode: OutputMode,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut output = SurroundOutput::new(audio_manager_settings, output_mode)?;
+		let assets_base_dir = std::env::current_dir()?.join("assets/drum fill demo");
+		let sound = output.add_sound(
+			sound_cache
+				.get(&assets_base_dir.join("loop.ogg"))?
+				.to_sound(PlayableSettings::new().default_loop_start(0.0)),
+		)?;
+		Ok(Self {
+			output,
+			sound,
+			instance: None,
+			position: (0.0, -ORBIT_RADIUS),
+			orbiting: false,
+			orbit_angle: 0.0,
+			last_tick: Instant::now(),
+			screen_wrapper: ScreenWrapper::new("Surround demo".into(), Message::GoToDemoSelect),
+			play_button: iced::button::State::new(),
+		})
+	}
+
+	fn move_sound(&mut self, x: f64, y: f64) -> Result<(), Box<dyn Error>> {
+		self.position = (x, y);
+		if let Some(instance) = self.instance {
+			self.output.set_position(instance, x, y)?;
+		}
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play => {
+				let (x, y) = self.position;
+				self.instance = Some(self.output.play(self.sound, x, y)?);
+			}
+			Message::Stop => {
+				if let Some(instance) = self.instance.take() {
+					self.output.stop(instance)?;
+				}
+			}
+			Message::SetPosition(x, y) => {
+				self.orbiting = false;
+				self.move_sound(x as f64, y as f64)?;
+			}
+			Message::SetOrbiting(orbiting) => {
+				self.orbiting = orbiting;
+				if orbiting {
+					// pick up the orbit from wherever the sound is now
+					let (x, y) = self.position;
+					self.orbit_angle = x.atan2(-y);
+					self.last_tick = Instant::now();
+				}
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		let delta_time = self.last_tick.elapsed().as_secs_f64();
+		self.last_tick = Instant::now();
+		if self.orbiting {
+			self.orbit_angle = (self.orbit_angle + TAU * delta_time / ORBIT_PERIOD) % TAU;
+			self.move_sound(
+				self.orbit_angle.sin() * ORBIT_RADIUS,
+				-self.orbit_angle.cos() * ORBIT_RADIUS,
+			)?;
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.instance.is_some();
+		let layout = self.output.layout();
+		let output_text = match self.output.fallback_reason() {
+			Some(reason) => format!("Output: {} ({})", layout.name(), reason),
+			None => format!("Output: {}", layout.name()),
+		};
+		let (x, y) = self.position;
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(Text::new(output_text).size(18))
+				.push(
+					SpeakerMap::new(layout.speakers(), (x as f32, y as f32))
+						.on_press(Message::SetPosition),
+				)
+				.push(
+					Row::new()
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Button::new(
+								&mut self.play_button,
+								Text::new(if playing { "Stop" } else { "Play" })
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(if playing {
+								Message::Stop
+							} else {
+								Message::Play
+							})
+							.width(Length::Units(100))
+							.style(AppStyles),
+						)
+						.push(Checkbox::new(self.orbiting, "Orbit", Message::SetOrbiting)),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for SurroundDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "surround_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		let (x, y) = self.position;
+		vec![
+			Message::SetPosition(x as f32, y as f32),
+			Message::SetOrbiting(self.orbiting),
+		]
+	}
+}
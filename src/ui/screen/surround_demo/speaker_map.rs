@@ -0,0 +1,188 @@
+use iced::{
+	mouse::Interaction, Background, Color, Font, HorizontalAlignment, Length, Point, Rectangle,
+	Size, VerticalAlignment,
+};
+use iced_graphics::{Backend, Defaults, Primitive, Renderer};
+use iced_native::{
+	event::Status,
+	layout::{Limits, Node},
+	mouse, Clipboard, Element, Event, Layout, Widget,
+};
+
+const SIZE: u16 = 300;
+/// How far the edge of the map is from the listener, in the
+/// same units as positions.
+const MAP_RADIUS: f32 = 1.25;
+const BACKGROUND_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.05];
+const SPEAKER_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.5];
+const LISTENER_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const EMITTER_COLOR: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+const SPEAKER_SIZE: f32 = 12.0;
+const DOT_RADIUS: f32 = 6.0;
+const LABEL_SIZE: f32 = 16.0;
+
+/// A top-down view of the speakers around the listener and the
+/// position a sound is playing from. Clicking the map reports the
+/// position under the cursor, with the listener at the origin
+/// facing towards negative y.
+pub struct SpeakerMap<'a, Message> {
+	speakers: &'a [(f32, &'a str)],
+	emitter: (f32, f32),
+	on_press: Option<Box<dyn Fn(f32, f32) -> Message + 'a>>,
+}
+
+impl<'a, Message> SpeakerMap<'a, Message> {
+	pub fn new(speakers: &'a [(f32, &'a str)], emitter: (f32, f32)) -> Self {
+		Self {
+			speakers,
+			emitter,
+			on_press: None,
+		}
+	}
+
+	pub fn on_press(self, on_press: impl Fn(f32, f32) -> Message + 'a) -> Self {
+		Self {
+			on_press: Some(Box::new(on_press)),
+			..self
+		}
+	}
+
+	fn to_screen(bounds: Rectangle, x: f32, y: f32) -> Point {
+		let scale = bounds.width / 2.0 / MAP_RADIUS;
+		let center = bounds.center();
+		Point::new(center.x + x * scale, center.y + y * scale)
+	}
+
+	fn from_screen(bounds: Rectangle, point: Point) -> (f32, f32) {
+		let scale = bounds.width / 2.0 / MAP_RADIUS;
+		let center = bounds.center();
+		((point.x - center.x) / scale, (point.y - center.y) / scale)
+	}
+
+	fn dot(center: Point, radius: f32, color: [f32; 4]) -> Primitive {
+		Primitive::Quad {
+			bounds: Rectangle::new(
+				Point::new(center.x - radius, center.y - radius),
+				Size::new(radius * 2.0, radius * 2.0),
+			),
+			background: Background::Color(color.into()),
+			border_radius: radius,
+			border_width: 0.0,
+			border_color: Color::TRANSPARENT,
+		}
+	}
+}
+
+impl<'a, Message, B: Backend> Widget<Message, Renderer<B>> for SpeakerMap<'a, Message> {
+	fn width(&self) -> Length {
+		Length::Units(SIZE)
+	}
+
+	fn height(&self) -> Length {
+		Length::Units(SIZE)
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, limits: &Limits) -> Node {
+		Node::new(
+			limits
+				.width(Length::Units(SIZE))
+				.height(Length::Units(SIZE))
+				.resolve(Size::ZERO),
+		)
+	}
+
+	fn on_event(
+		&mut self,
+		event: Event,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		messages: &mut Vec<Message>,
+		_renderer: &Renderer<B>,
+		_clipboard: Option<&dyn Clipboard>,
+	) -> Status {
+		let bounds = layout.bounds();
+		if let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event {
+			if let Some(on_press) = &self.on_press {
+				if bounds.contains(cursor_position) {
+					let (x, y) = Self::from_screen(bounds, cursor_position);
+					messages.push(on_press(x, y));
+					return Status::Captured;
+				}
+			}
+		}
+		Status::Ignored
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		_defaults: &Defaults,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let mut primitives = vec![Primitive::Quad {
+			bounds,
+			background: Background::Color(BACKGROUND_COLOR.into()),
+			border_radius: 0.0,
+			border_width: 0.0,
+			border_color: Color::TRANSPARENT,
+		}];
+		for (angle, name) in self.speakers {
+			let angle = angle.to_radians();
+			let position = Self::to_screen(bounds, angle.sin(), -angle.cos());
+			primitives.push(Primitive::Quad {
+				bounds: Rectangle::new(
+					Point::new(
+						position.x - SPEAKER_SIZE / 2.0,
+						position.y - SPEAKER_SIZE / 2.0,
+					),
+					Size::new(SPEAKER_SIZE, SPEAKER_SIZE),
+				),
+				background: Background::Color(SPEAKER_COLOR.into()),
+				border_radius: 0.0,
+				border_width: 0.0,
+				border_color: Color::TRANSPARENT,
+			});
+			// labels go on the outside of the speakers so they
+			// don't cover the emitter when it's on top of one
+			let label_position = Self::to_screen(bounds, angle.sin() * 1.15, -angle.cos() * 1.15);
+			primitives.push(Primitive::Text {
+				content: name.to_string(),
+				bounds: Rectangle::new(label_position, Size::new(0.0, 0.0)),
+				color: SPEAKER_COLOR.into(),
+				size: LABEL_SIZE,
+				font: Font::Default,
+				horizontal_alignment: HorizontalAlignment::Center,
+				vertical_alignment: VerticalAlignment::Center,
+			});
+		}
+		primitives.push(Self::dot(bounds.center(), DOT_RADIUS, LISTENER_COLOR));
+		let (x, y) = self.emitter;
+		primitives.push(Self::dot(
+			Self::to_screen(bounds, x, y),
+			DOT_RADIUS,
+			EMITTER_COLOR,
+		));
+		let interaction = if self.on_press.is_some() && bounds.contains(cursor_position) {
+			Interaction::Pointer
+		} else {
+			Interaction::default()
+		};
+		(Primitive::Group { primitives }, interaction)
+	}
+
+	fn hash_layout(&self, state: &mut iced_native::Hasher) {
+		use std::hash::Hash;
+		SIZE.hash(state)
+	}
+}
+
+impl<'a, Message: 'a, B: Backend + 'a> From<SpeakerMap<'a, Message>>
+	for Element<'a, Message, Renderer<B>>
+{
+	fn from(speaker_map: SpeakerMap<'a, Message>) -> Self {
+		Element::new(speaker_map)
+	}
+}
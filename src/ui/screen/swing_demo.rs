@@ -0,0 +1,405 @@
+use std::error::Error;
+
+use iced::{Align, Checkbox, Column, Length, Radio, Row, Slider, Text};
+use kira::{
+	group::GroupId,
+	instance::{InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::Tween,
+	playable::PlayableSettings,
+	sequence::{EventReceiver, Sequence, SequenceInstanceId, SequenceSettings},
+	sound::SoundId,
+	AudioResult, Duration, Tempo,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, synth},
+	ui::{
+		common::{screen_wrapper::ScreenWrapper, transport::Transport},
+		style::AppStyles,
+	},
+};
+
+const EXPLANATION_TEXT: &str = "Swing moves every other hi-hat \
+late. The hats come in pairs, and the swing amount is how much of \
+each pair the first hat gets: at 50% they're evenly spaced \
+(straight), at 67% they fall on a triplet grid (the classic shuffle), \
+and at 75% the second hat is squeezed right up against the next \
+pair.
+
+Each hat is a play command followed by a wait in a sequence, so \
+swinging them just means splitting each pair's time unevenly \
+between the two waits - a longer Duration::Beats after the first \
+hat and a shorter one after the second. The waits are still \
+counted in beats, so the pairs stay locked to the metronome and \
+the kick and snare land where they always did. Moving the slider \
+builds a new sequence with the new waits, which takes over from \
+the old one on the next beat.";
+
+pub const TEMPO: Tempo = Tempo(90.0);
+const BEATS_PER_BAR: usize = 4;
+const MIN_SWING: f64 = 0.5;
+const MAX_SWING: f64 = 0.75;
+/// The share of each pair the first hat gets on a triplet grid.
+const TRIPLET_SWING: f64 = 2.0 / 3.0;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Subdivision {
+	Eighths,
+	Sixteenths,
+}
+
+impl Subdivision {
+	const ALL: [Self; 2] = [Self::Eighths, Self::Sixteenths];
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::Eighths => "Eighth notes",
+			Self::Sixteenths => "Sixteenth notes",
+		}
+	}
+
+	/// How long each pair of hats lasts (in beats).
+	fn pair_length(self) -> f64 {
+		match self {
+			Self::Eighths => 1.0,
+			Self::Sixteenths => 0.5,
+		}
+	}
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum GrooveEvent {
+	/// A beat of the bar started.
+	Beat(usize),
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetMetronomeClick(bool),
+	/// Sets the share of each pair of hats the first one gets
+	/// (from 0.5 to 0.75).
+	SetSwing(f64),
+	SetSubdivision(Subdivision),
+	SetDrums(bool),
+}
+
+pub struct SwingDemo {
+	audio_manager: AudioManager,
+	group_id: GroupId,
+	hi_hat_sound_id: SoundId,
+	kick_sound_id: SoundId,
+	snare_sound_id: SoundId,
+	swing: f64,
+	subdivision: Subdivision,
+	drums: bool,
+	sequence: Option<(SequenceInstanceId, EventReceiver<GrooveEvent>)>,
+	/// The beat of the bar that's playing, as last reported by
+	/// the sequence.
+	current_beat: Option<usize>,
+	/// The sequence that's still playing while a newer one waits
+	/// to take over from it at the next beat.
+	outgoing_sequence_id: Option<SequenceInstanceId>,
+	transport: Transport,
+	screen_wrapper: ScreenWrapper<Message>,
+	swing_slider: iced::slider::State,
+}
+
+impl SwingDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(AudioManagerSettings {
+			metronome_settings: Transport::metronome_settings(TEMPO),
+			..audio_manager_settings
+		})?;
+		let transport = Transport::new(&mut audio_manager, TEMPO)?;
+		let group_id = audio_manager.add_group([])?;
+		// at full swing on sixteenths, two hats are only a few
+		// milliseconds apart, and a handover can land a hat from
+		// each sequence at once, either of which a cooldown would
+		// block
+		let settings = || PlayableSettings {
+			cooldown: None,
+			..PlayableSettings::new().groups([group_id])
+		};
+		let hi_hat_sound_id = audio_manager.add_sound(synth::noise_hit(0.05, 1.0, settings()))?;
+		let kick_sound_id = audio_manager.add_sound(synth::kick(50.0, 0.5, settings()))?;
+		let snare_sound_id = audio_manager.add_sound(synth::snare(180.0, 0.2, settings()))?;
+		Ok(Self {
+			audio_manager,
+			group_id,
+			hi_hat_sound_id,
+			kick_sound_id,
+			snare_sound_id,
+			swing: TRIPLET_SWING,
+			subdivision: Subdivision::Eighths,
+			drums: true,
+			sequence: None,
+			current_beat: None,
+			outgoing_sequence_id: None,
+			transport,
+			screen_wrapper: ScreenWrapper::new("Swing demo".into(), Message::GoToDemoSelect),
+			swing_slider: iced::slider::State::new(),
+		})
+	}
+
+	/// Adds one beat of the groove to a sequence: the kick or
+	/// snare (if they're on), then the swung pairs of hats.
+	fn add_beat(&self, sequence: &mut Sequence<GrooveEvent>, beat: usize) {
+		if self.drums {
+			let sound_id = if beat % 2 == 1 {
+				self.snare_sound_id
+			} else {
+				self.kick_sound_id
+			};
+			sequence.play(sound_id, InstanceSettings::new().volume(0.7));
+		}
+		let pair_length = self.subdivision.pair_length();
+		let num_pairs = (1.0 / pair_length).round() as usize;
+		for _ in 0..num_pairs {
+			sequence.play(self.hi_hat_sound_id, InstanceSettings::new().volume(0.6));
+			sequence.wait(Duration::Beats(pair_length * self.swing));
+			// the off-beat hat is a bit softer, which makes the
+			// swing easier to hear
+			sequence.play(self.hi_hat_sound_id, InstanceSettings::new().volume(0.35));
+			sequence.wait(Duration::Beats(pair_length * (1.0 - self.swing)));
+		}
+	}
+
+	/// Starts a sequence that waits for the next beat, takes over
+	/// from the sequence that was playing (if any), and loops a
+	/// bar of the groove starting from `first_beat`.
+	fn start_groove(&mut self, first_beat: usize) -> AudioResult<()> {
+		let mut sequence = Sequence::new(SequenceSettings::new().groups([self.group_id]));
+		sequence.wait_for_interval(1.0);
+		if let Some((previous_sequence_id, _)) = self.sequence.take() {
+			if self.outgoing_sequence_id.is_some() {
+				// the slider moved again before the last change was
+				// heard, so that change's sequence can go right away
+				self.audio_manager.stop_sequence(previous_sequence_id)?;
+			} else {
+				self.outgoing_sequence_id = Some(previous_sequence_id);
+			}
+		}
+		if let Some(outgoing_sequence_id) = self.outgoing_sequence_id {
+			sequence.stop_sequence_and_instances(
+				outgoing_sequence_id,
+				StopInstanceSettings::new().fade_tween(Tween::linear(0.01)),
+			);
+		}
+		sequence.start_loop();
+		for i in 0..BEATS_PER_BAR {
+			let beat = (first_beat + i) % BEATS_PER_BAR;
+			sequence.emit(GrooveEvent::Beat(beat));
+			self.add_beat(&mut sequence, beat);
+		}
+		self.sequence = Some(
+			self.audio_manager
+				.start_sequence(sequence, Default::default())?,
+		);
+		Ok(())
+	}
+
+	/// Rebuilds the sequence after a change, if the music is
+	/// playing.
+	fn groove_changed(&mut self) -> AudioResult<()> {
+		if self.sequence.is_none() {
+			return Ok(());
+		}
+		let next_beat = self
+			.current_beat
+			.map_or(0, |current_beat| (current_beat + 1) % BEATS_PER_BAR);
+		self.start_groove(next_beat)
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if self.sequence.is_none() => {
+				self.start_groove(0)?;
+				self.transport.start(&mut self.audio_manager)?;
+			}
+			Message::Stop => {
+				self.audio_manager
+					.stop_group(self.group_id, Default::default())?;
+				self.transport.stop(&mut self.audio_manager)?;
+				self.sequence = None;
+				self.current_beat = None;
+				self.outgoing_sequence_id = None;
+			}
+			Message::SetMetronomeClick(enabled) => {
+				self.transport
+					.set_click_enabled(&mut self.audio_manager, enabled)?;
+			}
+			Message::SetSwing(swing) => {
+				let swing = swing.clamp(MIN_SWING, MAX_SWING);
+				if swing != self.swing {
+					self.swing = swing;
+					self.groove_changed()?;
+				}
+			}
+			Message::SetSubdivision(subdivision) if self.subdivision != subdivision => {
+				self.subdivision = subdivision;
+				self.groove_changed()?;
+			}
+			Message::SetDrums(drums) if self.drums != drums => {
+				self.drums = drums;
+				self.groove_changed()?;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		self.transport.check_for_events(&mut self.audio_manager);
+		if let Some((_, events)) = &mut self.sequence {
+			while let Some(event) = events.pop().copied() {
+				match event {
+					GrooveEvent::Beat(beat) => {
+						self.current_beat = Some(beat);
+						self.outgoing_sequence_id = None;
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+
+	fn swing_text(&self) -> String {
+		let feel = if (self.swing - MIN_SWING).abs() < 0.005 {
+			" (straight)"
+		} else if (self.swing - TRIPLET_SWING).abs() < 0.005 {
+			" (triplet)"
+		} else {
+			""
+		};
+		format!("Swing: {:.0}%{}", self.swing * 100.0, feel)
+	}
+
+	/// How far behind the straight grid the off-beat hats land
+	/// (in milliseconds).
+	fn offbeat_delay(&self) -> f64 {
+		let beats = self.subdivision.pair_length() * (self.swing - 0.5);
+		TEMPO.beats_to_seconds(beats) * 1000.0
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let subdivision = self.subdivision;
+		let subdivision_radios =
+			Subdivision::ALL
+				.iter()
+				.fold(Row::new().spacing(16), |row, option| {
+					row.push(Radio::new(
+						*option,
+						option.name(),
+						Some(subdivision),
+						Message::SetSubdivision,
+					))
+				});
+		let swing_text = self.swing_text();
+		let delay_text = format!("The off-beat hats land {:.0} ms late", self.offbeat_delay());
+		self.screen_wrapper.view_with_transport(
+			self.transport
+				.view(Message::Play, Message::Stop, Message::SetMetronomeClick),
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(Text::new(swing_text).width(Length::Units(170)))
+						.push(
+							Slider::new(
+								&mut self.swing_slider,
+								MIN_SWING..=MAX_SWING,
+								self.swing,
+								Message::SetSwing,
+							)
+							.step(0.01)
+							.style(AppStyles),
+						),
+				)
+				.push(Text::new(delay_text))
+				.push(
+					Row::new()
+						.spacing(32)
+						.align_items(Align::Center)
+						.push(subdivision_radios)
+						.push(Checkbox::new(
+							self.drums,
+							"Kick and snare",
+							Message::SetDrums,
+						)),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for SwingDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "swing_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetSwing(_) => Some("Swing".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Swing" => vec![Message::SetSwing(
+				MIN_SWING + (MAX_SWING - MIN_SWING) * value,
+			)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for SwingDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "swing_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetMetronomeClick(self.transport.click_enabled()),
+			Message::SetSwing(self.swing),
+			Message::SetSubdivision(self.subdivision),
+			Message::SetDrums(self.drums),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for SwingDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.transport.is_playing(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if !self.transport.is_playing() {
+			return None;
+		}
+		Some(format!("Swing demo - {:.0}% swing", self.swing * 100.0))
+	}
+}
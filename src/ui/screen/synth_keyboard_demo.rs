@@ -0,0 +1,408 @@
+mod piano;
+
+use std::error::Error;
+
+use iced::{keyboard::KeyCode, Align, Column, Length, Radio, Row, Slider, Text};
+use kira::{
+	instance::{InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::{ParameterId, Tween},
+	playable::PlayableSettings,
+	sequence::{Sequence, SequenceInstanceId},
+	sound::SoundId,
+	Duration,
+};
+use serde::{Deserialize, Serialize};
+
+use self::piano::Piano;
+use crate::{
+	audio::{
+		create_audio_manager,
+		synth::{self, WaveShape},
+	},
+	ui::{
+		common::{curve_plot::CurvePlot, screen_wrapper::ScreenWrapper},
+		style::AppStyles,
+	},
+};
+
+const EXPLANATION_TEXT: &str = "Play the keyboard with the mouse \
+or your computer keys. None of the sounds come from files: each \
+wave shape is one second of a tone built by adding up sine waves \
+and turned into a Kira sound with Sound::from_frames. The tone is \
+middle C, and every other note plays the same sound with a \
+different pitch. Since the tone is a whole number of cycles \
+long, it can loop for as long as a key is held.
+
+The envelope comes from Kira too. The attack is the instance's \
+fade-in, and each note's volume is a parameter that a short \
+sequence tweens down to the sustain level once the attack is \
+over. Letting go of a key stops the note with a fade-out as long \
+as the release.";
+
+/// The frequency of the lowest key (middle C).
+const BASE_FREQUENCY: f64 = 261.63;
+/// Two octaves, from middle C up to the C two octaves above.
+const NUM_NOTES: usize = 25;
+/// The computer keys that play notes, laid out like a piano
+/// keyboard on the home row.
+const KEYS: [(KeyCode, &str, usize); 17] = [
+	(KeyCode::A, "A", 0),
+	(KeyCode::W, "W", 1),
+	(KeyCode::S, "S", 2),
+	(KeyCode::E, "E", 3),
+	(KeyCode::D, "D", 4),
+	(KeyCode::F, "F", 5),
+	(KeyCode::T, "T", 6),
+	(KeyCode::G, "G", 7),
+	(KeyCode::Y, "Y", 8),
+	(KeyCode::H, "H", 9),
+	(KeyCode::U, "U", 10),
+	(KeyCode::J, "J", 11),
+	(KeyCode::K, "K", 12),
+	(KeyCode::O, "O", 13),
+	(KeyCode::L, "L", 14),
+	(KeyCode::P, "P", 15),
+	(KeyCode::Semicolon, ";", 16),
+];
+/// The longest attack, decay and release the sliders allow (in
+/// seconds).
+const MAX_ATTACK: f64 = 1.0;
+const MAX_DECAY: f64 = 2.0;
+const MAX_RELEASE: f64 = 2.0;
+/// Even a release of 0 gets a tiny fade-out, since stopping a
+/// wave partway through a cycle clicks.
+const MIN_RELEASE: f64 = 0.005;
+/// How long the envelope plot holds the sustain level (in
+/// seconds) before it shows the release.
+const PLOT_SUSTAIN_TIME: f64 = 0.5;
+const PLOT_WIDTH: u16 = 300;
+const PLOT_HEIGHT: u16 = 100;
+const NUM_PLOT_POINTS: usize = 100;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	/// Starts a note (in semitones above middle C).
+	NoteOn(usize),
+	NoteOff(usize),
+	SetShape(WaveShape),
+	/// Sets how long notes take to fade in (in seconds).
+	SetAttack(f64),
+	/// Sets how long notes take to fall to the sustain level
+	/// after the attack (in seconds).
+	SetDecay(f64),
+	/// Sets the volume notes hold while their key is down (from
+	/// 0 to 1).
+	SetSustain(f64),
+	/// Sets how long notes take to fade out after their key is
+	/// let go (in seconds).
+	SetRelease(f64),
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Envelope {
+	attack: f64,
+	decay: f64,
+	sustain: f64,
+	release: f64,
+}
+
+impl Envelope {
+	/// Gets the volume at a time after the note starts, if the
+	/// key is let go after the decay and [`PLOT_SUSTAIN_TIME`].
+	fn volume_at(&self, time: f64) -> f64 {
+		let release_start = self.attack + self.decay + PLOT_SUSTAIN_TIME;
+		if time < self.attack {
+			time / self.attack
+		} else if time < self.attack + self.decay {
+			1.0 - (1.0 - self.sustain) * (time - self.attack) / self.decay
+		} else if time < release_start {
+			self.sustain
+		} else {
+			(self.sustain * (1.0 - (time - release_start) / self.release.max(MIN_RELEASE))).max(0.0)
+		}
+	}
+
+	fn plot_points(&self) -> Vec<f32> {
+		let duration = self.attack + self.decay + PLOT_SUSTAIN_TIME + self.release;
+		(0..NUM_PLOT_POINTS)
+			.map(|i| {
+				let time = duration * i as f64 / (NUM_PLOT_POINTS - 1) as f64;
+				self.volume_at(time) as f32
+			})
+			.collect()
+	}
+}
+
+pub struct SynthKeyboardDemo {
+	audio_manager: AudioManager,
+	tone_ids: Vec<(WaveShape, SoundId)>,
+	/// Each note's volume, which its sequence tweens through the
+	/// decay. A note that's played again while it's still
+	/// releasing shares the parameter with the new note, but
+	/// the old one is almost silent by then anyway.
+	volume_parameter_ids: Vec<ParameterId>,
+	/// The sequence playing each note, while its key is down.
+	voices: Vec<Option<SequenceInstanceId>>,
+	held_notes: Vec<bool>,
+	shape: WaveShape,
+	envelope: Envelope,
+	screen_wrapper: ScreenWrapper<Message>,
+	piano: piano::State,
+	attack_slider: iced::slider::State,
+	decay_slider: iced::slider::State,
+	sustain_slider: iced::slider::State,
+	release_slider: iced::slider::State,
+}
+
+impl SynthKeyboardDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let mut tone_ids = vec![];
+		for shape in WaveShape::ALL.iter() {
+			let sound = synth::tone(
+				*shape,
+				BASE_FREQUENCY,
+				PlayableSettings {
+					default_loop_start: Some(0.0),
+					..Default::default()
+				},
+			);
+			tone_ids.push((*shape, audio_manager.add_sound(sound)?));
+		}
+		let mut volume_parameter_ids = vec![];
+		for _ in 0..NUM_NOTES {
+			volume_parameter_ids.push(audio_manager.add_parameter(1.0)?);
+		}
+		Ok(Self {
+			audio_manager,
+			tone_ids,
+			volume_parameter_ids,
+			voices: vec![None; NUM_NOTES],
+			held_notes: vec![false; NUM_NOTES],
+			shape: WaveShape::Saw,
+			envelope: Envelope {
+				attack: 0.01,
+				decay: 0.3,
+				sustain: 0.5,
+				release: 0.3,
+			},
+			screen_wrapper: ScreenWrapper::new(
+				"Synth keyboard demo".into(),
+				Message::GoToDemoSelect,
+			),
+			piano: Default::default(),
+			attack_slider: iced::slider::State::new(),
+			decay_slider: iced::slider::State::new(),
+			sustain_slider: iced::slider::State::new(),
+			release_slider: iced::slider::State::new(),
+		})
+	}
+
+	fn tone_id(&self) -> SoundId {
+		self.tone_ids
+			.iter()
+			.find(|(shape, _)| *shape == self.shape)
+			.map(|(_, sound_id)| *sound_id)
+			.unwrap()
+	}
+
+	fn note_on(&mut self, note: usize) -> Result<(), Box<dyn Error>> {
+		self.note_off(note)?;
+		let Envelope {
+			attack,
+			decay,
+			sustain,
+			..
+		} = self.envelope;
+		let volume_parameter_id = self.volume_parameter_ids[note];
+		let mut settings = InstanceSettings::new()
+			.pitch(2.0f64.powf(note as f64 / 12.0))
+			.volume(volume_parameter_id);
+		if attack > 0.0 {
+			settings = settings.fade_in_tween(Tween::linear(attack));
+		}
+		let mut sequence = Sequence::<()>::new(Default::default());
+		sequence.set_parameter(volume_parameter_id, 1.0, None);
+		sequence.play(self.tone_id(), settings);
+		sequence.wait(Duration::Seconds(attack));
+		sequence.set_parameter(
+			volume_parameter_id,
+			sustain,
+			if decay > 0.0 {
+				Some(Tween::linear(decay))
+			} else {
+				None
+			},
+		);
+		let (sequence_id, _) = self
+			.audio_manager
+			.start_sequence(sequence, Default::default())?;
+		self.voices[note] = Some(sequence_id);
+		self.held_notes[note] = true;
+		Ok(())
+	}
+
+	fn note_off(&mut self, note: usize) -> Result<(), Box<dyn Error>> {
+		if let Some(sequence_id) = self.voices[note].take() {
+			self.audio_manager.stop_sequence_and_instances(
+				sequence_id,
+				StopInstanceSettings::new()
+					.fade_tween(Tween::linear(self.envelope.release.max(MIN_RELEASE))),
+			)?;
+		}
+		self.held_notes[note] = false;
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::NoteOn(note) if note < NUM_NOTES => self.note_on(note)?,
+			Message::NoteOff(note) if note < NUM_NOTES => self.note_off(note)?,
+			Message::SetShape(shape) => self.shape = shape,
+			Message::SetAttack(attack) => self.envelope.attack = attack.clamp(0.0, MAX_ATTACK),
+			Message::SetDecay(decay) => self.envelope.decay = decay.clamp(0.0, MAX_DECAY),
+			Message::SetSustain(sustain) => self.envelope.sustain = sustain.clamp(0.0, 1.0),
+			Message::SetRelease(release) => self.envelope.release = release.clamp(0.0, MAX_RELEASE),
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let envelope = self.envelope;
+		let shape = self.shape;
+		let shape_radios = WaveShape::ALL.iter().fold(
+			Row::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(Text::new("Wave:")),
+			|row, option| {
+				row.push(Radio::new(
+					*option,
+					option.name(),
+					Some(shape),
+					Message::SetShape,
+				))
+			},
+		);
+		let envelope_slider =
+			|state, label: String, range, value, on_change: fn(f64) -> Message| {
+				Row::new()
+					.spacing(16)
+					.align_items(Align::Center)
+					.push(Text::new(label).width(Length::Units(160)))
+					.push(
+						Slider::new(state, range, value, on_change)
+							.step(0.005)
+							.style(AppStyles),
+					)
+			};
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Piano::new(&mut self.piano, NUM_NOTES, &self.held_notes)
+						.keys(&KEYS)
+						.on_press(Message::NoteOn)
+						.on_release(Message::NoteOff),
+				)
+				.push(shape_radios)
+				.push(
+					Row::new()
+						.spacing(32)
+						.max_width(800)
+						.align_items(Align::Center)
+						.push(
+							Column::new()
+								.spacing(8)
+								.width(Length::Fill)
+								.push(envelope_slider(
+									&mut self.attack_slider,
+									format!("Attack: {:.2}s", envelope.attack),
+									0.0..=MAX_ATTACK,
+									envelope.attack,
+									Message::SetAttack,
+								))
+								.push(envelope_slider(
+									&mut self.decay_slider,
+									format!("Decay: {:.2}s", envelope.decay),
+									0.0..=MAX_DECAY,
+									envelope.decay,
+									Message::SetDecay,
+								))
+								.push(envelope_slider(
+									&mut self.sustain_slider,
+									format!("Sustain: {:.0}%", envelope.sustain * 100.0),
+									0.0..=1.0,
+									envelope.sustain,
+									Message::SetSustain,
+								))
+								.push(envelope_slider(
+									&mut self.release_slider,
+									format!("Release: {:.2}s", envelope.release),
+									0.0..=MAX_RELEASE,
+									envelope.release,
+									Message::SetRelease,
+								)),
+						)
+						.push(CurvePlot::new(
+							envelope.plot_points(),
+							PLOT_WIDTH,
+							PLOT_HEIGHT,
+						)),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for SynthKeyboardDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "synth_keyboard_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetAttack(_) => Some("Attack".into()),
+			Message::SetDecay(_) => Some("Decay".into()),
+			Message::SetSustain(_) => Some("Sustain".into()),
+			Message::SetRelease(_) => Some("Release".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Attack" => vec![Message::SetAttack(MAX_ATTACK * value)],
+			"Decay" => vec![Message::SetDecay(MAX_DECAY * value)],
+			"Sustain" => vec![Message::SetSustain(value)],
+			"Release" => vec![Message::SetRelease(MAX_RELEASE * value)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for SynthKeyboardDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "synth_keyboard_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetShape(self.shape),
+			Message::SetAttack(self.envelope.attack),
+			Message::SetDecay(self.envelope.decay),
+			Message::SetSustain(self.envelope.sustain),
+			Message::SetRelease(self.envelope.release),
+		]
+	}
+}
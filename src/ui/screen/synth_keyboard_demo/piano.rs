@@ -0,0 +1,306 @@
+use iced::{
+	keyboard::KeyCode, mouse::Interaction, Background, Font, HorizontalAlignment, Length, Point,
+	Rectangle, Size, VerticalAlignment,
+};
+use iced_graphics::{Backend, Defaults, Primitive, Renderer};
+use iced_native::{
+	event::Status,
+	keyboard,
+	layout::{Limits, Node},
+	mouse, Clipboard, Element, Event, Layout, Widget,
+};
+
+const WHITE_KEY_WIDTH: f32 = 36.0;
+const WHITE_KEY_HEIGHT: f32 = 150.0;
+const BLACK_KEY_WIDTH: f32 = 22.0;
+const BLACK_KEY_HEIGHT: f32 = 90.0;
+const WHITE_KEY_COLOR: [f32; 4] = [0.9, 0.9, 0.9, 1.0];
+const BLACK_KEY_COLOR: [f32; 4] = [0.1, 0.1, 0.1, 1.0];
+const HELD_KEY_COLOR: [f32; 4] = [1.0, 0.6, 0.0, 1.0];
+const BORDER_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+const WHITE_LABEL_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.6];
+const BLACK_LABEL_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.6];
+const LABEL_SIZE: f32 = 14.0;
+
+/// Whether a note (in semitones above the lowest C) is on a
+/// black key.
+fn is_black(note: usize) -> bool {
+	matches!(note % 12, 1 | 3 | 6 | 8 | 10)
+}
+
+/// The note being played with the mouse and the keyboard keys
+/// being held down (which the OS repeats, but only the first
+/// press should play a note). This has to outlive the widget,
+/// which is rebuilt every time the screen is drawn.
+#[derive(Debug, Default)]
+pub struct State {
+	mouse_note: Option<usize>,
+	held_keys: Vec<KeyCode>,
+}
+
+/// An on-screen piano keyboard starting on a C. Notes are
+/// numbered in semitones from the lowest key. They can be played
+/// with the mouse (dragging across the keys slides from note to
+/// note) or with keys on the computer keyboard.
+pub struct Piano<'a, Message> {
+	state: &'a mut State,
+	num_notes: usize,
+	/// Which notes to draw as held down.
+	held_notes: &'a [bool],
+	keys: &'a [(KeyCode, &'a str, usize)],
+	on_press: Option<Box<dyn Fn(usize) -> Message + 'a>>,
+	on_release: Option<Box<dyn Fn(usize) -> Message + 'a>>,
+}
+
+impl<'a, Message> Piano<'a, Message> {
+	pub fn new(state: &'a mut State, num_notes: usize, held_notes: &'a [bool]) -> Self {
+		Self {
+			state,
+			num_notes,
+			held_notes,
+			keys: &[],
+			on_press: None,
+			on_release: None,
+		}
+	}
+
+	/// Sets which computer keys play which notes, along with the
+	/// labels to draw on the piano keys.
+	pub fn keys(self, keys: &'a [(KeyCode, &'a str, usize)]) -> Self {
+		Self { keys, ..self }
+	}
+
+	pub fn on_press(self, on_press: impl Fn(usize) -> Message + 'a) -> Self {
+		Self {
+			on_press: Some(Box::new(on_press)),
+			..self
+		}
+	}
+
+	pub fn on_release(self, on_release: impl Fn(usize) -> Message + 'a) -> Self {
+		Self {
+			on_release: Some(Box::new(on_release)),
+			..self
+		}
+	}
+
+	fn num_white_keys(&self) -> usize {
+		(0..self.num_notes).filter(|note| !is_black(*note)).count()
+	}
+
+	fn size(&self) -> Size {
+		Size::new(
+			WHITE_KEY_WIDTH * self.num_white_keys() as f32,
+			WHITE_KEY_HEIGHT,
+		)
+	}
+
+	fn key_bounds(bounds: Rectangle, note: usize) -> Rectangle {
+		let white_keys_before = (0..note).filter(|note| !is_black(*note)).count() as f32;
+		if is_black(note) {
+			// black keys sit on the line between two white keys
+			Rectangle::new(
+				Point::new(
+					bounds.x + white_keys_before * WHITE_KEY_WIDTH - BLACK_KEY_WIDTH / 2.0,
+					bounds.y,
+				),
+				Size::new(BLACK_KEY_WIDTH, BLACK_KEY_HEIGHT),
+			)
+		} else {
+			Rectangle::new(
+				Point::new(bounds.x + white_keys_before * WHITE_KEY_WIDTH, bounds.y),
+				Size::new(WHITE_KEY_WIDTH, WHITE_KEY_HEIGHT),
+			)
+		}
+	}
+
+	/// Finds the key under a point. Black keys are on top of the
+	/// white ones, so they're checked first.
+	fn note_at(&self, bounds: Rectangle, point: Point) -> Option<usize> {
+		(0..self.num_notes)
+			.filter(|note| is_black(*note))
+			.chain((0..self.num_notes).filter(|note| !is_black(*note)))
+			.find(|note| Self::key_bounds(bounds, *note).contains(point))
+	}
+
+	fn press(&self, note: usize, messages: &mut Vec<Message>) {
+		if let Some(on_press) = &self.on_press {
+			messages.push(on_press(note));
+		}
+	}
+
+	fn release(&self, note: usize, messages: &mut Vec<Message>) {
+		if let Some(on_release) = &self.on_release {
+			messages.push(on_release(note));
+		}
+	}
+
+	fn draw_key(&self, bounds: Rectangle, note: usize, primitives: &mut Vec<Primitive>) {
+		let key_bounds = Self::key_bounds(bounds, note);
+		let black = is_black(note);
+		let color = if self.held_notes.get(note).copied().unwrap_or(false) {
+			HELD_KEY_COLOR
+		} else if black {
+			BLACK_KEY_COLOR
+		} else {
+			WHITE_KEY_COLOR
+		};
+		primitives.push(Primitive::Quad {
+			bounds: key_bounds,
+			background: Background::Color(color.into()),
+			border_radius: 2.0,
+			border_width: 1.0,
+			border_color: BORDER_COLOR.into(),
+		});
+		if let Some((_, label, _)) = self.keys.iter().find(|(_, _, key_note)| *key_note == note) {
+			primitives.push(Primitive::Text {
+				content: label.to_string(),
+				bounds: Rectangle::new(
+					Point::new(
+						key_bounds.center_x(),
+						key_bounds.y + key_bounds.height - LABEL_SIZE,
+					),
+					Size::new(key_bounds.width, 0.0),
+				),
+				color: if black {
+					BLACK_LABEL_COLOR.into()
+				} else {
+					WHITE_LABEL_COLOR.into()
+				},
+				size: LABEL_SIZE,
+				font: Font::Default,
+				horizontal_alignment: HorizontalAlignment::Center,
+				vertical_alignment: VerticalAlignment::Center,
+			});
+		}
+	}
+}
+
+impl<'a, Message, B: Backend> Widget<Message, Renderer<B>> for Piano<'a, Message> {
+	fn width(&self) -> Length {
+		Length::Units(self.size().width as u16)
+	}
+
+	fn height(&self) -> Length {
+		Length::Units(self.size().height as u16)
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, limits: &Limits) -> Node {
+		let size = self.size();
+		Node::new(
+			limits
+				.width(Length::Units(size.width as u16))
+				.height(Length::Units(size.height as u16))
+				.resolve(Size::ZERO),
+		)
+	}
+
+	fn on_event(
+		&mut self,
+		event: Event,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		messages: &mut Vec<Message>,
+		_renderer: &Renderer<B>,
+		_clipboard: Option<&dyn Clipboard>,
+	) -> Status {
+		let bounds = layout.bounds();
+		match event {
+			Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+				match self.note_at(bounds, cursor_position) {
+					Some(note) => {
+						self.state.mouse_note = Some(note);
+						self.press(note, messages);
+						Status::Captured
+					}
+					None => Status::Ignored,
+				}
+			}
+			Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+				let mouse_note = match self.state.mouse_note {
+					Some(note) => note,
+					None => return Status::Ignored,
+				};
+				match self.note_at(bounds, cursor_position) {
+					Some(note) if note != mouse_note => {
+						self.release(mouse_note, messages);
+						self.press(note, messages);
+						self.state.mouse_note = Some(note);
+					}
+					// the note keeps playing if the cursor leaves
+					// the piano, until the button is released
+					_ => {}
+				}
+				Status::Captured
+			}
+			Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+				match self.state.mouse_note.take() {
+					Some(note) => {
+						self.release(note, messages);
+						Status::Captured
+					}
+					None => Status::Ignored,
+				}
+			}
+			Event::Keyboard(keyboard::Event::KeyPressed { key_code, .. }) => {
+				let note = match self.keys.iter().find(|(key, _, _)| *key == key_code) {
+					Some((_, _, note)) => *note,
+					None => return Status::Ignored,
+				};
+				if !self.state.held_keys.contains(&key_code) {
+					self.state.held_keys.push(key_code);
+					self.press(note, messages);
+				}
+				Status::Captured
+			}
+			Event::Keyboard(keyboard::Event::KeyReleased { key_code, .. }) => {
+				if !self.state.held_keys.contains(&key_code) {
+					return Status::Ignored;
+				}
+				self.state.held_keys.retain(|key| *key != key_code);
+				if let Some((_, _, note)) = self.keys.iter().find(|(key, _, _)| *key == key_code) {
+					self.release(*note, messages);
+				}
+				Status::Captured
+			}
+			_ => Status::Ignored,
+		}
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		_defaults: &Defaults,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let mut primitives = vec![];
+		for note in (0..self.num_notes).filter(|note| !is_black(*note)) {
+			self.draw_key(bounds, note, &mut primitives);
+		}
+		for note in (0..self.num_notes).filter(|note| is_black(*note)) {
+			self.draw_key(bounds, note, &mut primitives);
+		}
+		let interaction = if self.on_press.is_some() && bounds.contains(cursor_position) {
+			Interaction::Pointer
+		} else {
+			Interaction::default()
+		};
+		(Primitive::Group { primitives }, interaction)
+	}
+
+	fn hash_layout(&self, state: &mut iced_native::Hasher) {
+		use std::hash::Hash;
+		self.num_notes.hash(state)
+	}
+}
+
+impl<'a, Message: 'a, B: Backend + 'a> From<Piano<'a, Message>>
+	for Element<'a, Message, Renderer<B>>
+{
+	fn from(piano: Piano<'a, Message>) -> Self {
+		Element::new(piano)
+	}
+}
@@ -0,0 +1,293 @@
+use std::{error::Error, time::Instant};
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Radio, Row, Text};
+use kira::{
+	instance::InstanceSettings,
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::{Mapping, ParameterId, Tween},
+	playable::PlayableSettings,
+	sequence::{Sequence, SequenceInstanceId},
+	sound::SoundId,
+	Duration, Tempo, Value,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, sound_cache::SoundCache},
+	ui::{
+		common::{screen_wrapper::ScreenWrapper, transport::Transport},
+		style::AppStyles,
+	},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &["drum fill demo/loop.ogg"];
+
+const EXPLANATION_TEXT: &str = "This demo speeds the metronome \
+up and slows it down while the drum loop plays. The metronome's \
+tempo is tied to a parameter, and ramping the tempo is just a \
+tween of that parameter.
+
+The loop is restarted every bar by a sequence, which measures \
+its waits in beats, so it keeps landing on the bar no matter \
+how fast the metronome is going. The loop's pitch is tied to \
+the same parameter (scaled by the tempo it was recorded at), \
+so it speeds up and slows down with the metronome and each \
+repetition lasts exactly one bar.";
+
+/// The tempo the metronome starts at.
+pub const TEMPO: Tempo = Tempo(90.0);
+const FAST_TEMPO: Tempo = Tempo(160.0);
+/// The tempo the drum loop was recorded at.
+const LOOP_TEMPO: Tempo = Tempo(128.0);
+const BEATS_PER_BAR: f64 = 4.0;
+/// The ramp lengths the user can pick from (in seconds).
+const RAMP_DURATIONS: [f64; 4] = [2.0, 4.0, 8.0, 16.0];
+const DEFAULT_RAMP_DURATION_INDEX: usize = 1;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetMetronomeClick(bool),
+	SetRampDuration(usize),
+	Ramp,
+}
+
+/// A tempo ramp in progress, which the tempo display follows.
+struct Ramp {
+	start_time: Instant,
+	from: f64,
+	to: f64,
+	duration: f64,
+}
+
+impl Ramp {
+	fn tempo(&self) -> f64 {
+		let progress = (self.start_time.elapsed().as_secs_f64() / self.duration).min(1.0);
+		self.from + (self.to - self.from) * progress
+	}
+
+	fn finished(&self) -> bool {
+		self.start_time.elapsed().as_secs_f64() >= self.duration
+	}
+}
+
+pub struct TempoRampDemo {
+	audio_manager: AudioManager,
+	loop_sound_id: SoundId,
+	tempo_parameter_id: ParameterId,
+	loop_sequence_id: Option<SequenceInstanceId>,
+	tempo: f64,
+	ramp_duration_index: usize,
+	ramp: Option<Ramp>,
+	transport: Transport,
+	screen_wrapper: ScreenWrapper<Message>,
+	ramp_button: iced::button::State,
+}
+
+impl TempoRampDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(AudioManagerSettings {
+			metronome_settings: Transport::metronome_settings(TEMPO),
+			..audio_manager_settings
+		})?;
+		let transport = Transport::new(&mut audio_manager, TEMPO)?;
+		let tempo_parameter_id = audio_manager.add_parameter(TEMPO.0)?;
+		audio_manager.set_metronome_tempo(tempo_parameter_id)?;
+		let loop_sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&std::env::current_dir()?.join("assets/drum fill demo/loop.ogg"))?
+				.to_sound(PlayableSettings::new()),
+		)?;
+		Ok(Self {
+			audio_manager,
+			loop_sound_id,
+			tempo_parameter_id,
+			loop_sequence_id: None,
+			tempo: TEMPO.0,
+			ramp_duration_index: DEFAULT_RAMP_DURATION_INDEX,
+			ramp: None,
+			transport,
+			screen_wrapper: ScreenWrapper::new("Tempo ramp demo".into(), Message::GoToDemoSelect),
+			ramp_button: iced::button::State::new(),
+		})
+	}
+
+	fn start_loop_sequence(&mut self) -> Result<(), Box<dyn Error>> {
+		let mut sequence = Sequence::<()>::new(Default::default());
+		sequence.wait_for_interval(1.0);
+		sequence.start_loop();
+		sequence.play(
+			self.loop_sound_id,
+			InstanceSettings::new().pitch(Value::Parameter(
+				self.tempo_parameter_id,
+				Mapping {
+					input_range: (0.0, LOOP_TEMPO.0),
+					output_range: (0.0, 1.0),
+					..Default::default()
+				},
+			)),
+		);
+		sequence.wait(Duration::Beats(BEATS_PER_BAR));
+		let (sequence_id, _) = self
+			.audio_manager
+			.start_sequence(sequence, Default::default())?;
+		self.loop_sequence_id = Some(sequence_id);
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play => {
+				self.start_loop_sequence()?;
+				self.transport.start(&mut self.audio_manager)?;
+			}
+			Message::Stop => {
+				if let Some(sequence_id) = self.loop_sequence_id.take() {
+					self.audio_manager
+						.stop_sequence_and_instances(sequence_id, Default::default())?;
+				}
+				self.transport.stop(&mut self.audio_manager)?;
+			}
+			Message::SetMetronomeClick(enabled) => {
+				self.transport
+					.set_click_enabled(&mut self.audio_manager, enabled)?;
+			}
+			Message::SetRampDuration(index) => {
+				self.ramp_duration_index = index.min(RAMP_DURATIONS.len() - 1);
+			}
+			Message::Ramp => {
+				// head for whichever end is further away
+				let to = if self.tempo < (TEMPO.0 + FAST_TEMPO.0) / 2.0 {
+					FAST_TEMPO.0
+				} else {
+					TEMPO.0
+				};
+				let duration = RAMP_DURATIONS[self.ramp_duration_index];
+				self.audio_manager.set_parameter(
+					self.tempo_parameter_id,
+					to,
+					Some(Tween::linear(duration)),
+				)?;
+				self.ramp = Some(Ramp {
+					start_time: Instant::now(),
+					from: self.tempo,
+					to,
+					duration,
+				});
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		self.transport.check_for_events(&mut self.audio_manager);
+		if let Some(ramp) = &self.ramp {
+			self.tempo = ramp.tempo();
+			if ramp.finished() {
+				self.ramp = None;
+			}
+			// rounded so the transport doesn't show a long
+			// string of decimals
+			self.transport
+				.set_live_tempo(Tempo((self.tempo * 10.0).round() / 10.0));
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let ramping = self.ramp.is_some();
+		let target = if self.tempo < (TEMPO.0 + FAST_TEMPO.0) / 2.0 {
+			FAST_TEMPO
+		} else {
+			TEMPO
+		};
+		let ramp_duration_index = self.ramp_duration_index;
+		let ramp_duration_radios = RAMP_DURATIONS.iter().enumerate().fold(
+			Row::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(Text::new("Ramp over:")),
+			|row, (index, duration)| {
+				row.push(Radio::new(
+					index,
+					format!("{}s", duration),
+					Some(ramp_duration_index),
+					Message::SetRampDuration,
+				))
+			},
+		);
+		let mut ramp_button = Button::new(
+			&mut self.ramp_button,
+			Text::new(format!("Ramp to {} BPM", target.0))
+				.width(Length::Fill)
+				.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.width(Length::Units(200))
+		.style(AppStyles);
+		if !ramping {
+			ramp_button = ramp_button.on_press(Message::Ramp);
+		}
+		let tempo_text = format!(
+			"{:.1} BPM - loop playback rate {:.2}x",
+			self.tempo,
+			self.tempo / LOOP_TEMPO.0
+		);
+
+		self.screen_wrapper.view_with_transport(
+			self.transport
+				.view(Message::Play, Message::Stop, Message::SetMetronomeClick),
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(Text::new(tempo_text).size(32))
+				.push(ramp_button)
+				.push(ramp_duration_radios)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for TempoRampDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "tempo_ramp_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetMetronomeClick(self.transport.click_enabled()),
+			Message::SetRampDuration(self.ramp_duration_index),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for TempoRampDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.transport.is_playing(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if !self.transport.is_playing() {
+			return None;
+		}
+		Some("Tempo ramp demo".into())
+	}
+}
@@ -0,0 +1,505 @@
+mod transition_grid;
+
+use std::error::Error;
+
+use iced::{Align, Checkbox, Column, Length, Radio, Row, Text};
+use kira::{
+	group::GroupId,
+	instance::{InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::Tween,
+	playable::PlayableSettings,
+	sequence::{EventReceiver, Sequence, SequenceInstanceId, SequenceSettings},
+	sound::SoundId,
+	AudioResult, Duration, Tempo,
+};
+use serde::{Deserialize, Serialize};
+
+use self::transition_grid::TransitionGrid;
+use crate::{
+	audio::{create_audio_manager, sound_cache::SoundCache, synth},
+	ui::common::{screen_wrapper::ScreenWrapper, transport::Transport},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &[
+	"underwater demo/drums.ogg",
+	"underwater demo/bass.ogg",
+	"underwater demo/pad.ogg",
+	"underwater demo/lead.ogg",
+];
+
+const EXPLANATION_TEXT: &str = "Every section can move to every \
+other one, and each move has its own rule. The grid has a row for \
+the section the music leaves and a column for the one it goes to. \
+Click a cell in the highlighted row to make that move, or any cell \
+to edit its rule below.
+
+A rule picks when the move happens - right away, on the next \
+beat, or at the start of the next bar - and whether a drum fill \
+leads into the new section. Each move starts a sequence like the \
+drum fill demo does: it waits for the rule's beat interval on the \
+metronome, stops the old section, plays the fill if there is one, \
+and then loops the new section. A fill on a bar move takes up the \
+end of the bar, so the new section still comes in on the one. \
+Moves that don't wait for the bar start the new section from the \
+top wherever they land, which is quicker but can drop it in \
+mid-bar.";
+
+pub const TEMPO: Tempo = Tempo(85.0);
+const BEATS_PER_BAR: f64 = 4.0;
+/// How long each stem is (in beats).
+const PHRASE_LENGTH: f64 = 16.0;
+/// How long a drum fill lasts (in beats).
+const FILL_LENGTH: f64 = 2.0;
+/// How many hits there are in each beat of a fill.
+const FILL_HITS_PER_BEAT: usize = 4;
+const DRUMS: usize = 0;
+const BASS: usize = 1;
+const PAD: usize = 2;
+const LEAD: usize = 3;
+
+/// A section of the music.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Section {
+	Calm,
+	Explore,
+	Tension,
+	Combat,
+}
+
+impl Section {
+	const ALL: [Self; 4] = [Self::Calm, Self::Explore, Self::Tension, Self::Combat];
+	const NAMES: [&'static str; 4] = ["Calm", "Explore", "Tension", "Combat"];
+
+	fn index(self) -> usize {
+		Self::ALL
+			.iter()
+			.position(|section| *section == self)
+			.unwrap()
+	}
+
+	fn name(self) -> &'static str {
+		Self::NAMES[self.index()]
+	}
+
+	/// The stems that play during the section.
+	fn stems(self) -> &'static [usize] {
+		match self {
+			Self::Calm => &[PAD],
+			Self::Explore => &[PAD, LEAD],
+			Self::Tension => &[PAD, BASS],
+			Self::Combat => &[DRUMS, BASS, PAD, LEAD],
+		}
+	}
+}
+
+/// When a move from one section to another happens.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Timing {
+	Immediate,
+	NextBeat,
+	NextBar,
+}
+
+impl Timing {
+	const ALL: [Self; 3] = [Self::Immediate, Self::NextBeat, Self::NextBar];
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::Immediate => "Immediate",
+			Self::NextBeat => "Next beat",
+			Self::NextBar => "Next bar",
+		}
+	}
+
+	/// The metronome interval the move waits for, if any.
+	fn interval(self) -> Option<f64> {
+		match self {
+			Self::Immediate => None,
+			Self::NextBeat => Some(1.0),
+			Self::NextBar => Some(BEATS_PER_BAR),
+		}
+	}
+}
+
+/// How the music moves from one section to another.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rule {
+	timing: Timing,
+	fill: bool,
+}
+
+impl Rule {
+	const fn new(timing: Timing, fill: bool) -> Self {
+		Self { timing, fill }
+	}
+
+	/// A short description for the grid.
+	fn label(self) -> String {
+		if self.fill {
+			format!("{} + fill", self.timing.name())
+		} else {
+			self.timing.name().into()
+		}
+	}
+}
+
+/// The rules the demo starts with, by the section the music
+/// moves from and then the one it moves to. The diagonal is
+/// never used.
+const DEFAULT_RULES: [[Rule; 4]; 4] = [
+	[
+		Rule::new(Timing::NextBar, false),
+		Rule::new(Timing::NextBar, false),
+		Rule::new(Timing::NextBar, false),
+		Rule::new(Timing::NextBeat, true),
+	],
+	[
+		Rule::new(Timing::NextBar, false),
+		Rule::new(Timing::NextBar, false),
+		Rule::new(Timing::NextBar, false),
+		Rule::new(Timing::NextBar, true),
+	],
+	[
+		Rule::new(Timing::NextBar, false),
+		Rule::new(Timing::NextBar, false),
+		Rule::new(Timing::NextBar, false),
+		Rule::new(Timing::Immediate, true),
+	],
+	[
+		Rule::new(Timing::NextBar, true),
+		Rule::new(Timing::NextBar, true),
+		Rule::new(Timing::NextBeat, false),
+		Rule::new(Timing::NextBar, false),
+	],
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum TransitionEvent {
+	/// The old section has stopped and the fill has started.
+	Fill,
+	/// The new section has started.
+	Arrived(Section),
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetMetronomeClick(bool),
+	/// Sent when a cell of the grid is clicked, with the section the
+	/// music would move from and the one it would move to.
+	PressCell(Section, Section),
+	SetTiming(Section, Section, Timing),
+	SetFill(Section, Section, bool),
+}
+
+pub struct TransitionMatrixDemo {
+	audio_manager: AudioManager,
+	group_id: GroupId,
+	stem_sound_ids: Vec<SoundId>,
+	fill_sound_id: SoundId,
+	rules: [[Rule; 4]; 4],
+	/// The newest sequence, which reports the fill and the new
+	/// section as they start.
+	sequence: Option<(SequenceInstanceId, EventReceiver<TransitionEvent>)>,
+	/// The section that's playing, once the music has started.
+	current: Option<Section>,
+	/// The section the music is moving to.
+	pending: Option<Section>,
+	filling: bool,
+	/// The move whose rule is being edited.
+	selected: (Section, Section),
+	transport: Transport,
+	screen_wrapper: ScreenWrapper<Message>,
+}
+
+impl TransitionMatrixDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(AudioManagerSettings {
+			metronome_settings: Transport::metronome_settings(TEMPO),
+			..audio_manager_settings
+		})?;
+		let transport = Transport::new(&mut audio_manager, TEMPO)?;
+		let group_id = audio_manager.add_group([])?;
+		let assets_base_dir = std::env::current_dir()?.join("assets");
+		let mut stem_sound_ids = vec![];
+		for path in ASSETS {
+			// a move on the first bar of a phrase can play a stem
+			// at the same moment the old section loops it, which
+			// a cooldown would block
+			stem_sound_ids.push(
+				audio_manager.add_sound(sound_cache.get(&assets_base_dir.join(path))?.to_sound(
+					PlayableSettings {
+						cooldown: None,
+						..PlayableSettings::new().groups([group_id])
+					},
+				))?,
+			);
+		}
+		let fill_sound_id = audio_manager.add_sound(synth::snare(
+			200.0,
+			0.15,
+			PlayableSettings {
+				cooldown: None,
+				..PlayableSettings::new().groups([group_id])
+			},
+		))?;
+		Ok(Self {
+			audio_manager,
+			group_id,
+			stem_sound_ids,
+			fill_sound_id,
+			rules: DEFAULT_RULES,
+			sequence: None,
+			current: None,
+			pending: None,
+			filling: false,
+			selected: (Section::Calm, Section::Explore),
+			transport,
+			screen_wrapper: ScreenWrapper::new(
+				"Transition matrix demo".into(),
+				Message::GoToDemoSelect,
+			),
+		})
+	}
+
+	fn rule(&self, from: Section, to: Section) -> Rule {
+		self.rules[from.index()][to.index()]
+	}
+
+	fn rule_mut(&mut self, from: Section, to: Section) -> &mut Rule {
+		&mut self.rules[from.index()][to.index()]
+	}
+
+	/// Adds a drum fill to a sequence that gets louder towards the
+	/// end.
+	fn add_fill(&self, sequence: &mut Sequence<TransitionEvent>) {
+		let num_hits = (FILL_LENGTH as usize) * FILL_HITS_PER_BEAT;
+		for hit in 0..num_hits {
+			let volume = 0.4 + 0.6 * (hit + 1) as f64 / num_hits as f64;
+			sequence.play(self.fill_sound_id, InstanceSettings::new().volume(volume));
+			sequence.wait(Duration::Beats(1.0 / FILL_HITS_PER_BEAT as f64));
+		}
+	}
+
+	/// Starts a sequence that waits for the point the rule picks,
+	/// stops the sequence that was playing (if any), plays a fill
+	/// if the rule asks for one, and loops the new section.
+	fn start_transition(&mut self, to: Section, rule: Rule) -> AudioResult<()> {
+		let mut sequence = Sequence::new(SequenceSettings::new().groups([self.group_id]));
+		if let Some(interval) = rule.timing.interval() {
+			sequence.wait_for_interval(interval);
+		}
+		if rule.fill && rule.timing == Timing::NextBar {
+			sequence.wait(Duration::Beats(BEATS_PER_BAR - FILL_LENGTH));
+		}
+		if let Some((previous_sequence_id, _)) = self.sequence.take() {
+			sequence.stop_sequence_and_instances(
+				previous_sequence_id,
+				StopInstanceSettings::new().fade_tween(Tween::linear(0.05)),
+			);
+		}
+		if rule.fill {
+			sequence.emit(TransitionEvent::Fill);
+			self.add_fill(&mut sequence);
+		}
+		sequence.start_loop();
+		sequence.emit(TransitionEvent::Arrived(to));
+		for stem in to.stems() {
+			sequence.play(self.stem_sound_ids[*stem], Default::default());
+		}
+		sequence.wait(Duration::Beats(PHRASE_LENGTH));
+		self.sequence = Some(
+			self.audio_manager
+				.start_sequence(sequence, Default::default())?,
+		);
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if self.sequence.is_none() => {
+				self.pending = Some(Section::Calm);
+				self.start_transition(Section::Calm, Rule::new(Timing::NextBar, false))?;
+				self.transport.start(&mut self.audio_manager)?;
+			}
+			Message::Stop => {
+				self.audio_manager
+					.stop_group(self.group_id, Default::default())?;
+				self.transport.stop(&mut self.audio_manager)?;
+				self.sequence = None;
+				self.current = None;
+				self.pending = None;
+				self.filling = false;
+			}
+			Message::SetMetronomeClick(enabled) => {
+				self.transport
+					.set_click_enabled(&mut self.audio_manager, enabled)?;
+			}
+			Message::PressCell(from, to) if from != to => {
+				self.selected = (from, to);
+				// only one move can be waiting at a time
+				if self.pending.is_none() && self.current == Some(from) {
+					self.pending = Some(to);
+					self.start_transition(to, self.rule(from, to))?;
+				}
+			}
+			Message::SetTiming(from, to, timing) => self.rule_mut(from, to).timing = timing,
+			Message::SetFill(from, to, fill) => self.rule_mut(from, to).fill = fill,
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		self.transport.check_for_events(&mut self.audio_manager);
+		if let Some((_, events)) = &mut self.sequence {
+			while let Some(event) = events.pop().copied() {
+				match event {
+					TransitionEvent::Fill => self.filling = true,
+					TransitionEvent::Arrived(section) => {
+						self.current = Some(section);
+						self.pending = None;
+						self.filling = false;
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+
+	fn status_text(&self) -> String {
+		match (self.current, self.pending) {
+			(Some(current), Some(pending)) if self.filling => format!(
+				"Playing a fill from {} into {}",
+				current.name(),
+				pending.name()
+			),
+			(Some(current), Some(pending)) => format!(
+				"Moving from {} to {} ({})",
+				current.name(),
+				pending.name(),
+				self.rule(current, pending).label().to_lowercase()
+			),
+			(Some(current), None) => format!("Playing {}", current.name()),
+			(None, Some(pending)) => format!("Starting with {}", pending.name()),
+			(None, None) => "Stopped".into(),
+		}
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let labels = Section::ALL
+			.iter()
+			.map(|from| {
+				Section::ALL
+					.iter()
+					.map(|to| {
+						if from == to {
+							String::new()
+						} else {
+							self.rule(*from, *to).label()
+						}
+					})
+					.collect()
+			})
+			.collect();
+		let pending = match (self.current, self.pending) {
+			(Some(current), Some(pending)) => Some((current.index(), pending.index())),
+			_ => None,
+		};
+		let (selected_from, selected_to) = self.selected;
+		let grid = TransitionGrid::new(&Section::NAMES, labels)
+			.current(self.current.map(Section::index))
+			.pending(pending)
+			.selected(Some((selected_from.index(), selected_to.index())))
+			.on_press(|from, to| Message::PressCell(Section::ALL[from], Section::ALL[to]));
+		let selected_rule = self.rule(selected_from, selected_to);
+		let timing_radios = Timing::ALL
+			.iter()
+			.fold(Row::new().spacing(16), |row, timing| {
+				row.push(Radio::new(
+					*timing,
+					timing.name(),
+					Some(selected_rule.timing),
+					move |timing| Message::SetTiming(selected_from, selected_to, timing),
+				))
+			});
+		let status_text = self.status_text();
+		self.screen_wrapper.view_with_transport(
+			self.transport
+				.view(Message::Play, Message::Stop, Message::SetMetronomeClick),
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(Column::new().max_width(600).push(grid))
+				.push(Text::new(status_text))
+				.push(Text::new(format!(
+					"Rule for {} to {}:",
+					selected_from.name(),
+					selected_to.name()
+				)))
+				.push(
+					Row::new()
+						.spacing(32)
+						.align_items(Align::Center)
+						.push(timing_radios)
+						.push(Checkbox::new(selected_rule.fill, "Fill", move |fill| {
+							Message::SetFill(selected_from, selected_to, fill)
+						})),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for TransitionMatrixDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "transition_matrix_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		let mut messages = vec![Message::SetMetronomeClick(self.transport.click_enabled())];
+		for from in Section::ALL.iter().copied() {
+			for to in Section::ALL.iter().copied().filter(|to| *to != from) {
+				let rule = self.rule(from, to);
+				messages.push(Message::SetTiming(from, to, rule.timing));
+				messages.push(Message::SetFill(from, to, rule.fill));
+			}
+		}
+		messages
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for TransitionMatrixDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.transport.is_playing(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if !self.transport.is_playing() {
+			return None;
+		}
+		Some(match self.current {
+			Some(section) => format!("Transition matrix demo - {}", section.name()),
+			None => "Transition matrix demo".into(),
+		})
+	}
+}
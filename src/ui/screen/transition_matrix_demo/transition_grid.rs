@@ -0,0 +1,259 @@
+use iced::{mouse::Interaction, Background, Color, Length, Point, Rectangle, Size, Vector};
+use iced_graphics::{Backend, Defaults, Primitive, Renderer};
+use iced_native::{
+	event::Status,
+	layout::{Limits, Node},
+	mouse, Clipboard, Element, Event, HorizontalAlignment, Layout, VerticalAlignment, Widget,
+};
+
+const HEADER_WIDTH: f32 = 100.0;
+const CELL_HEIGHT: f32 = 44.0;
+const SPACING: f32 = 4.0;
+const CELL_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.08];
+const CURRENT_ROW_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.18];
+const DIAGONAL_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.02];
+const PENDING_COLOR: [f32; 4] = [1.0, 0.6, 0.0, 0.8];
+const SELECTED_BORDER_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.8];
+const CURRENT_HEADER_COLOR: [f32; 4] = [1.0, 0.6, 0.0, 1.0];
+
+/// Draws every possible move between sections as a grid, with a
+/// row for the section the music moves from and a column for the
+/// section it moves to. Each cell shows the rule for its move.
+///
+/// The row of the section that's playing is highlighted, and the
+/// cell of a move that's waiting to happen is filled in. Cells on
+/// the diagonal would move a section to itself, so they're left
+/// blank and can't be clicked.
+pub struct TransitionGrid<'a, Message> {
+	sections: &'a [&'a str],
+	/// The label for each cell, by row and then column.
+	labels: Vec<Vec<String>>,
+	current: Option<usize>,
+	pending: Option<(usize, usize)>,
+	selected: Option<(usize, usize)>,
+	on_press: Option<Box<dyn Fn(usize, usize) -> Message + 'a>>,
+}
+
+impl<'a, Message> TransitionGrid<'a, Message> {
+	pub fn new(sections: &'a [&'a str], labels: Vec<Vec<String>>) -> Self {
+		Self {
+			sections,
+			labels,
+			current: None,
+			pending: None,
+			selected: None,
+			on_press: None,
+		}
+	}
+
+	/// Highlights the row of the section that's playing.
+	pub fn current(self, current: Option<usize>) -> Self {
+		Self { current, ..self }
+	}
+
+	/// Fills in the cell of the move that's waiting to happen.
+	pub fn pending(self, pending: Option<(usize, usize)>) -> Self {
+		Self { pending, ..self }
+	}
+
+	/// Outlines the cell whose rule is being edited.
+	pub fn selected(self, selected: Option<(usize, usize)>) -> Self {
+		Self { selected, ..self }
+	}
+
+	/// Sets the message to send when a cell is clicked, given the
+	/// row and the column of the cell.
+	pub fn on_press(self, on_press: impl Fn(usize, usize) -> Message + 'a) -> Self {
+		Self {
+			on_press: Some(Box::new(on_press)),
+			..self
+		}
+	}
+
+	fn height(&self) -> f32 {
+		(CELL_HEIGHT + SPACING) * (self.sections.len() + 1) as f32
+	}
+
+	fn cell_width(&self, width: f32) -> f32 {
+		let num_sections = self.sections.len().max(1) as f32;
+		(width - HEADER_WIDTH - SPACING * num_sections) / num_sections
+	}
+
+	/// Gets the bounds of a cell relative to the top-left corner of
+	/// the widget. Row and column 0 are the headers.
+	fn cell_bounds(&self, width: f32, row: usize, column: usize) -> Rectangle {
+		let cell_width = self.cell_width(width);
+		let x = if column == 0 {
+			0.0
+		} else {
+			HEADER_WIDTH + SPACING + (cell_width + SPACING) * (column - 1) as f32
+		};
+		Rectangle::new(
+			Point::new(x, (CELL_HEIGHT + SPACING) * row as f32),
+			Size::new(
+				if column == 0 {
+					HEADER_WIDTH
+				} else {
+					cell_width
+				},
+				CELL_HEIGHT,
+			),
+		)
+	}
+
+	/// Finds the move under a point relative to the top-left corner
+	/// of the widget.
+	fn cell_at(&self, width: f32, point: Point) -> Option<(usize, usize)> {
+		let num_sections = self.sections.len();
+		(0..num_sections)
+			.flat_map(|from| (0..num_sections).map(move |to| (from, to)))
+			.filter(|(from, to)| from != to)
+			.find(|(from, to)| self.cell_bounds(width, from + 1, to + 1).contains(point))
+	}
+}
+
+fn text(content: &str, bounds: Rectangle, color: Color, size: f32) -> Primitive {
+	Primitive::Text {
+		content: content.to_string(),
+		bounds: Rectangle::new(bounds.center(), bounds.size()),
+		color,
+		size,
+		font: Default::default(),
+		horizontal_alignment: HorizontalAlignment::Center,
+		vertical_alignment: VerticalAlignment::Center,
+	}
+}
+
+impl<'a, Message, B: Backend> Widget<Message, Renderer<B>> for TransitionGrid<'a, Message> {
+	fn width(&self) -> Length {
+		Length::Fill
+	}
+
+	fn height(&self) -> Length {
+		Length::Shrink
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, limits: &Limits) -> Node {
+		Node::new(
+			limits
+				.width(Length::Fill)
+				.height(Length::Units(self.height() as u16))
+				.resolve(Size::ZERO),
+		)
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		defaults: &Defaults,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let offset = Vector::new(bounds.x, bounds.y);
+		let mut primitives = vec![];
+		for (i, name) in self.sections.iter().enumerate() {
+			let header_color = if self.current == Some(i) {
+				CURRENT_HEADER_COLOR.into()
+			} else {
+				defaults.text.color
+			};
+			primitives.push(text(
+				name,
+				self.cell_bounds(bounds.width, 0, i + 1) + offset,
+				defaults.text.color,
+				16.0,
+			));
+			primitives.push(text(
+				name,
+				self.cell_bounds(bounds.width, i + 1, 0) + offset,
+				header_color,
+				16.0,
+			));
+		}
+		for from in 0..self.sections.len() {
+			for to in 0..self.sections.len() {
+				let cell_bounds = self.cell_bounds(bounds.width, from + 1, to + 1) + offset;
+				let color = if from == to {
+					DIAGONAL_COLOR
+				} else if self.pending == Some((from, to)) {
+					PENDING_COLOR
+				} else if self.current == Some(from) {
+					CURRENT_ROW_COLOR
+				} else {
+					CELL_COLOR
+				};
+				let selected = self.selected == Some((from, to));
+				primitives.push(Primitive::Quad {
+					bounds: cell_bounds,
+					background: Background::Color(color.into()),
+					border_radius: 4.0,
+					border_width: if selected { 2.0 } else { 0.0 },
+					border_color: if selected {
+						SELECTED_BORDER_COLOR.into()
+					} else {
+						Color::TRANSPARENT
+					},
+				});
+				if let Some(label) = self.labels.get(from).and_then(|row| row.get(to)) {
+					primitives.push(text(label, cell_bounds, defaults.text.color, 14.0));
+				}
+			}
+		}
+		let hovering_cell = self.on_press.is_some()
+			&& self
+				.cell_at(bounds.width, cursor_position - offset)
+				.is_some();
+		(
+			Primitive::Group { primitives },
+			if hovering_cell {
+				Interaction::Pointer
+			} else {
+				Interaction::default()
+			},
+		)
+	}
+
+	fn on_event(
+		&mut self,
+		event: Event,
+		layout: Layout<'_>,
+		cursor_position: Point,
+		messages: &mut Vec<Message>,
+		_renderer: &Renderer<B>,
+		_clipboard: Option<&dyn Clipboard>,
+	) -> Status {
+		let bounds = layout.bounds();
+		let on_press = match &self.on_press {
+			Some(on_press) => on_press,
+			None => return Status::Ignored,
+		};
+		match event {
+			Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+				let point = cursor_position - Vector::new(bounds.x, bounds.y);
+				match self.cell_at(bounds.width, point) {
+					Some((from, to)) => {
+						messages.push(on_press(from, to));
+						Status::Captured
+					}
+					None => Status::Ignored,
+				}
+			}
+			_ => Status::Ignored,
+		}
+	}
+
+	fn hash_layout(&self, state: &mut iced_native::Hasher) {
+		use std::hash::Hash;
+		self.height().to_bits().hash(state)
+	}
+}
+
+impl<'a, Message: 'a, B: Backend + 'a> From<TransitionGrid<'a, Message>>
+	for Element<'a, Message, Renderer<B>>
+{
+	fn from(transition_grid: TransitionGrid<'a, Message>) -> Self {
+		Element::new(transition_grid)
+	}
+}
@@ -0,0 +1,389 @@
+use std::{collections::VecDeque, error::Error};
+
+use iced::{Align, Column, Length, Radio, Row, Text};
+use kira::{
+	group::GroupId,
+	instance::InstanceSettings,
+	manager::{AudioManager, AudioManagerSettings},
+	playable::PlayableSettings,
+	sequence::{EventReceiver, Sequence, SequenceSettings},
+	sound::SoundId,
+	Duration, Tempo,
+};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, synth},
+	ui::common::{screen_wrapper::ScreenWrapper, transport::Transport},
+};
+
+const EXPLANATION_TEXT: &str = "This demo makes up melodies \
+in different tunings. Every note is the same sound played at \
+a different playback rate: doubling the rate raises the pitch \
+by an octave, and a ratio like 3/2 gives a perfectly pure fifth.
+
+Equal temperament splits the octave into steps of the same \
+ratio, so every key sounds alike, while just intonation and the \
+harmonic series use whole-number ratios that sound smoother \
+but drift away from the piano's notes.";
+
+pub const TEMPO: Tempo = Tempo(110.0);
+const BEATS_PER_PHRASE: f64 = 4.0;
+/// The pitch of the sound at a playback rate of 1.
+const BASE_FREQUENCY: f64 = 220.0;
+const OCTAVES: usize = 2;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Tuning {
+	EqualTemperament,
+	JustIntonation,
+	Pythagorean,
+	NineteenTone,
+	HarmonicSeries,
+}
+
+impl Tuning {
+	const ALL: [Self; 5] = [
+		Self::EqualTemperament,
+		Self::JustIntonation,
+		Self::Pythagorean,
+		Self::NineteenTone,
+		Self::HarmonicSeries,
+	];
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::EqualTemperament => "12-tone equal temperament",
+			Self::JustIntonation => "Just intonation",
+			Self::Pythagorean => "Pythagorean",
+			Self::NineteenTone => "19-tone equal temperament",
+			Self::HarmonicSeries => "Harmonic series",
+		}
+	}
+
+	/// The notes of the scale within one octave, from the root up.
+	fn scale(self) -> Vec<Interval> {
+		match self {
+			Self::EqualTemperament => [0, 2, 4, 5, 7, 9, 11]
+				.iter()
+				.map(|step| Interval::equal(*step, 12))
+				.collect(),
+			Self::JustIntonation => [(1, 1), (9, 8), (5, 4), (4, 3), (3, 2), (5, 3), (15, 8)]
+				.iter()
+				.map(|(numerator, denominator)| Interval::just(*numerator, *denominator))
+				.collect(),
+			Self::Pythagorean => [
+				(1, 1),
+				(9, 8),
+				(81, 64),
+				(4, 3),
+				(3, 2),
+				(27, 16),
+				(243, 128),
+			]
+			.iter()
+			.map(|(numerator, denominator)| Interval::just(*numerator, *denominator))
+			.collect(),
+			Self::NineteenTone => [0, 3, 6, 8, 11, 14, 17]
+				.iter()
+				.map(|step| Interval::equal(*step, 19))
+				.collect(),
+			// the 8th to 15th partials, which all fit in one octave
+			Self::HarmonicSeries => (8..16).map(|partial| Interval::just(partial, 8)).collect(),
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+struct Interval {
+	ratio: f64,
+	label: String,
+}
+
+impl Interval {
+	fn equal(step: u32, divisions: u32) -> Self {
+		Self {
+			ratio: 2.0f64.powf(step as f64 / divisions as f64),
+			label: format!("{}\\{}", step, divisions),
+		}
+	}
+
+	fn just(numerator: u32, denominator: u32) -> Self {
+		Self {
+			ratio: numerator as f64 / denominator as f64,
+			label: format!("{}/{}", numerator, denominator),
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+struct Note {
+	interval: Interval,
+	octave: usize,
+	duration: f64,
+}
+
+impl Note {
+	/// The playback rate that gives this note's pitch.
+	fn playback_rate(&self) -> f64 {
+		self.interval.ratio * 2.0f64.powi(self.octave as i32)
+	}
+
+	fn cents(&self) -> f64 {
+		1200.0 * self.playback_rate().log2()
+	}
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+struct NoteStarted(usize);
+
+struct Phrase {
+	notes: Vec<Note>,
+	events: EventReceiver<NoteStarted>,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetMetronomeClick(bool),
+	SetTuning(Tuning),
+}
+
+pub struct TuningDemo {
+	audio_manager: AudioManager,
+	sound_id: SoundId,
+	group_id: GroupId,
+	tuning: Tuning,
+	rng: StdRng,
+	/// Where the melody is in the scale (counting up
+	/// through every octave).
+	position: usize,
+	/// The phrase that's playing, followed by the one that's
+	/// waiting for it to finish.
+	phrases: VecDeque<Phrase>,
+	current_note: Option<Note>,
+	transport: Transport,
+	screen_wrapper: ScreenWrapper<Message>,
+}
+
+impl TuningDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(AudioManagerSettings {
+			metronome_settings: Transport::metronome_settings(TEMPO),
+			..audio_manager_settings
+		})?;
+		let transport = Transport::new(&mut audio_manager, TEMPO)?;
+		let sound_id =
+			audio_manager.add_sound(synth::blip(BASE_FREQUENCY, 0.8, PlayableSettings::new()))?;
+		let group_id = audio_manager.add_group([])?;
+		Ok(Self {
+			audio_manager,
+			sound_id,
+			group_id,
+			tuning: Tuning::JustIntonation,
+			rng: StdRng::from_entropy(),
+			position: 0,
+			phrases: VecDeque::new(),
+			current_note: None,
+			transport,
+			screen_wrapper: ScreenWrapper::new("Tuning demo".into(), Message::GoToDemoSelect),
+		})
+	}
+
+	/// Makes up a bar of melody by wandering up and down the scale.
+	fn generate_notes(&mut self) -> Vec<Note> {
+		let scale = self.tuning.scale();
+		let num_positions = scale.len() * OCTAVES + 1;
+		let mut notes = vec![];
+		let mut beats_left = BEATS_PER_PHRASE;
+		while beats_left > 0.0 {
+			let step = *[-2, -1, -1, 1, 1, 2].choose(&mut self.rng).unwrap();
+			self.position = (self.position as i32 + step)
+				.max(0)
+				.min(num_positions as i32 - 1) as usize;
+			let duration = if beats_left < 1.0 || self.rng.gen_bool(0.6) {
+				0.5
+			} else {
+				1.0
+			};
+			beats_left -= duration;
+			notes.push(Note {
+				interval: scale[self.position % scale.len()].clone(),
+				octave: self.position / scale.len(),
+				duration,
+			});
+		}
+		notes
+	}
+
+	/// Starts a phrase that waits for the next bar, so it
+	/// plays right after the current one.
+	fn queue_phrase(&mut self) -> Result<(), Box<dyn Error>> {
+		let notes = self.generate_notes();
+		let mut sequence = Sequence::new(SequenceSettings::new().groups([self.group_id]));
+		sequence.wait_for_interval(BEATS_PER_PHRASE);
+		for (index, note) in notes.iter().enumerate() {
+			sequence.emit(NoteStarted(index));
+			sequence.play(
+				self.sound_id,
+				InstanceSettings::new().pitch(note.playback_rate()),
+			);
+			sequence.wait(Duration::Beats(note.duration));
+		}
+		let (_, events) = self
+			.audio_manager
+			.start_sequence(sequence, Default::default())?;
+		self.phrases.push_back(Phrase { notes, events });
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play => {
+				self.position = 0;
+				self.queue_phrase()?;
+				self.transport.start(&mut self.audio_manager)?;
+			}
+			Message::Stop => {
+				self.audio_manager
+					.stop_group(self.group_id, Default::default())?;
+				self.transport.stop(&mut self.audio_manager)?;
+				self.phrases.clear();
+				self.current_note = None;
+			}
+			Message::SetMetronomeClick(enabled) => {
+				self.transport
+					.set_click_enabled(&mut self.audio_manager, enabled)?;
+			}
+			// the new tuning is used from the next phrase on
+			Message::SetTuning(tuning) => {
+				self.tuning = tuning;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		self.transport.check_for_events(&mut self.audio_manager);
+		let mut newest_phrase_started = false;
+		let mut finished_phrases = 0;
+		for (phrase_index, phrase) in self.phrases.iter_mut().enumerate() {
+			while let Some(NoteStarted(note_index)) = phrase.events.pop().copied() {
+				self.current_note = Some(phrase.notes[note_index].clone());
+				if note_index == 0 {
+					// everything before this phrase is done playing
+					finished_phrases = phrase_index;
+					newest_phrase_started = true;
+				}
+			}
+		}
+		self.phrases.drain(..finished_phrases);
+		if newest_phrase_started && self.phrases.len() == 1 {
+			self.queue_phrase()?;
+		}
+		Ok(())
+	}
+
+	fn note_text(&self) -> String {
+		let note = match &self.current_note {
+			Some(note) => note,
+			None => return "Press Play to start the melody".into(),
+		};
+		let cents = note.cents();
+		// how far the note is from the nearest piano key
+		let offset = cents - (cents / 100.0).round() * 100.0;
+		format!(
+			"{} (octave {}) - playback rate {:.4} - {:.1}Hz - {:.1} cents, {:+.1} from equal temperament",
+			note.interval.label,
+			note.octave + 1,
+			note.playback_rate(),
+			BASE_FREQUENCY * note.playback_rate(),
+			cents,
+			offset
+		)
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let tuning = self.tuning;
+		let tuning_radios = Tuning::ALL
+			.iter()
+			.fold(Column::new().spacing(8), |column, option| {
+				column.push(Radio::new(
+					*option,
+					option.name(),
+					Some(tuning),
+					Message::SetTuning,
+				))
+			});
+		let scale_text = tuning
+			.scale()
+			.iter()
+			.map(|interval| interval.label.clone())
+			.collect::<Vec<_>>()
+			.join("  ");
+		let note_text = self.note_text();
+		self.screen_wrapper.view_with_transport(
+			self.transport
+				.view(Message::Play, Message::Stop, Message::SetMetronomeClick),
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Row::new()
+						.spacing(32)
+						.align_items(Align::Center)
+						.push(tuning_radios)
+						.push(
+							Column::new()
+								.spacing(8)
+								.max_width(300)
+								.push(Text::new(format!("Scale: {}", scale_text)))
+								.push(Text::new(note_text).size(16)),
+						),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for TuningDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "tuning_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetTuning(self.tuning),
+			Message::SetMetronomeClick(self.transport.click_enabled()),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for TuningDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.transport.is_playing(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if !self.transport.is_playing() {
+			return None;
+		}
+		Some(format!("Tuning demo ({})", self.tuning.name()))
+	}
+}
@@ -0,0 +1,470 @@
+use std::{error::Error, time::Instant};
+
+use iced::{Align, Button, Checkbox, Column, HorizontalAlignment, Length, Row, Slider, Text};
+use kira::{
+	instance::InstanceSettings,
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::{ParameterId, Tween},
+	playable::PlayableSettings,
+	sequence::Sequence,
+	sound::SoundId,
+	Duration,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, effect::gain::Gain, synth},
+	ui::{
+		common::{hover, hover::Hover, screen_wrapper::ScreenWrapper},
+		style::AppStyles,
+	},
+};
+
+const EXPLANATION_TEXT: &str = "Every control on this screen \
+makes a sound: a soft tick when the cursor moves onto a button \
+or checkbox, a click when it's pressed, a rising or falling \
+chirp when a checkbox is switched on or off, and a double bonk \
+when something can't be done, like continuing without a saved \
+game or going past the ends of the counter.
+
+Pressing the same button quickly raises the pitch of its click \
+a semitone at a time, which makes repeated actions feel like \
+they're adding up. The ramp starts over once you pause. The \
+sliders tick each time they cross a notch, higher towards the \
+top.
+
+iced doesn't report hovering, so the buttons and checkboxes are \
+wrapped in a small widget that sends a message when the cursor \
+moves onto them. Everything else is the ordinary messages the \
+widgets already send: the screen plays the sound for a message \
+when it handles it. All the sounds go through one track with a \
+gain effect, which the UI volume slider controls.";
+
+const MENU_ITEMS: [&str; 4] = ["New game", "Continue", "Options", "Quit"];
+/// The menu item that can't be used, since there's no saved game.
+const UNAVAILABLE_MENU_ITEM: usize = 1;
+const TOGGLE_NAMES: [&str; 3] = ["Music", "Subtitles", "Vibration"];
+const MAX_COUNT: u32 = 10;
+/// How soon a repeated press has to come to keep the pitch
+/// rising (in seconds).
+const RAMP_RESET_TIME: f64 = 0.5;
+/// The most semitones a repeated click can rise.
+const MAX_RAMP_STEPS: u32 = 12;
+const MIN_UI_VOLUME: f64 = -40.0;
+/// How many notches the sliders tick at.
+const SLIDER_NOTCHES: f64 = 10.0;
+
+/// Raises the pitch of a sound a little each time it's played
+/// in quick succession.
+#[derive(Debug, Default)]
+struct PitchRamp {
+	last_played: Option<Instant>,
+	steps: u32,
+}
+
+impl PitchRamp {
+	/// Gets the pitch for the next play.
+	fn next_pitch(&mut self) -> f64 {
+		self.steps = match self.last_played {
+			Some(last_played) if last_played.elapsed().as_secs_f64() < RAMP_RESET_TIME => {
+				(self.steps + 1).min(MAX_RAMP_STEPS)
+			}
+			_ => 0,
+		};
+		self.last_played = Some(Instant::now());
+		2.0f64.powf(self.steps as f64 / 12.0)
+	}
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Hovered,
+	PressMenuItem(usize),
+	Decrement,
+	Increment,
+	SetToggle(usize, bool),
+	SetHoverSounds(bool),
+	/// Sets the volume of the UI sounds (in decibels).
+	SetUiVolume(f64),
+	/// Sets the brightness (from 0 to 1), which only exists to
+	/// have something to slide.
+	SetBrightness(f64),
+}
+
+pub struct UiSoundsDemo {
+	audio_manager: AudioManager,
+	volume_parameter_id: ParameterId,
+	hover_sound_id: SoundId,
+	click_sound_id: SoundId,
+	toggle_on_sound_id: SoundId,
+	toggle_off_sound_id: SoundId,
+	error_sound_id: SoundId,
+	tick_sound_id: SoundId,
+	/// A pitch ramp for each menu item, then the decrement and
+	/// increment buttons.
+	ramps: Vec<PitchRamp>,
+	count: u32,
+	toggles: [bool; 3],
+	hover_sounds: bool,
+	ui_volume: f64,
+	brightness: f64,
+	last_action: String,
+	screen_wrapper: ScreenWrapper<Message>,
+	menu_buttons: Vec<(hover::State, iced::button::State)>,
+	decrement_button: (hover::State, iced::button::State),
+	increment_button: (hover::State, iced::button::State),
+	toggle_hovers: Vec<hover::State>,
+	ui_volume_slider: iced::slider::State,
+	brightness_slider: iced::slider::State,
+}
+
+impl UiSoundsDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let volume_parameter_id = audio_manager.add_parameter(0.0)?;
+		let track_id = audio_manager.add_sub_track(Default::default())?;
+		audio_manager.add_effect_to_track(
+			track_id,
+			Gain::new(volume_parameter_id),
+			Default::default(),
+		)?;
+		// fast clicking and sweeping over the buttons plays the
+		// same sounds over each other
+		let settings = || PlayableSettings {
+			cooldown: None,
+			..PlayableSettings::new().default_track(track_id)
+		};
+		let hover_sound_id = audio_manager.add_sound(synth::blip(1800.0, 0.03, settings()))?;
+		let click_sound_id = audio_manager.add_sound(synth::blip(1000.0, 0.06, settings()))?;
+		let toggle_on_sound_id = audio_manager.add_sound(synth::blip(1320.0, 0.1, settings()))?;
+		let toggle_off_sound_id = audio_manager.add_sound(synth::blip(880.0, 0.1, settings()))?;
+		let error_sound_id = audio_manager.add_sound(synth::blip(200.0, 0.15, settings()))?;
+		let tick_sound_id = audio_manager.add_sound(synth::noise_hit(0.01, 0.95, settings()))?;
+		Ok(Self {
+			audio_manager,
+			volume_parameter_id,
+			hover_sound_id,
+			click_sound_id,
+			toggle_on_sound_id,
+			toggle_off_sound_id,
+			error_sound_id,
+			tick_sound_id,
+			ramps: (0..MENU_ITEMS.len() + 2)
+				.map(|_| PitchRamp::default())
+				.collect(),
+			count: 0,
+			toggles: [true, false, true],
+			hover_sounds: true,
+			ui_volume: 0.0,
+			brightness: 0.5,
+			last_action: "".into(),
+			screen_wrapper: ScreenWrapper::new("UI sounds demo".into(), Message::GoToDemoSelect),
+			menu_buttons: MENU_ITEMS.iter().map(|_| Default::default()).collect(),
+			decrement_button: Default::default(),
+			increment_button: Default::default(),
+			toggle_hovers: TOGGLE_NAMES.iter().map(|_| Default::default()).collect(),
+			ui_volume_slider: iced::slider::State::new(),
+			brightness_slider: iced::slider::State::new(),
+		})
+	}
+
+	fn play(&mut self, sound_id: SoundId, pitch: f64) -> Result<(), Box<dyn Error>> {
+		self.audio_manager
+			.play(sound_id, InstanceSettings::new().pitch(pitch))?;
+		Ok(())
+	}
+
+	/// Plays a click, ramping up the pitch if the same control
+	/// was just pressed.
+	fn click(&mut self, ramp: usize) -> Result<(), Box<dyn Error>> {
+		let pitch = self.ramps[ramp].next_pitch();
+		self.play(self.click_sound_id, pitch)
+	}
+
+	/// Plays the error sound: the same low blip twice, the
+	/// second one a little lower.
+	fn error(&mut self) -> Result<(), Box<dyn Error>> {
+		let mut sequence = Sequence::<()>::new(Default::default());
+		sequence.play(self.error_sound_id, Default::default());
+		sequence.wait(Duration::Seconds(0.1));
+		sequence.play(self.error_sound_id, InstanceSettings::new().pitch(0.8));
+		self.audio_manager
+			.start_sequence(sequence, Default::default())?;
+		Ok(())
+	}
+
+	/// Plays a tick if a slider has crossed a notch. Higher
+	/// values tick at a higher pitch.
+	fn slider_tick(&mut self, old_position: f64, new_position: f64) -> Result<(), Box<dyn Error>> {
+		let notch = |position: f64| (position * SLIDER_NOTCHES).round();
+		if notch(old_position) != notch(new_position) {
+			self.play(self.tick_sound_id, 0.75 + 0.5 * new_position)?;
+		}
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Hovered if self.hover_sounds => self.play(self.hover_sound_id, 1.0)?,
+			Message::PressMenuItem(item) if item < MENU_ITEMS.len() => {
+				if item == UNAVAILABLE_MENU_ITEM {
+					self.error()?;
+					self.last_action = "There's no saved game to continue".into();
+				} else {
+					self.click(item)?;
+					self.last_action = format!("Pressed \"{}\"", MENU_ITEMS[item]);
+				}
+			}
+			Message::Decrement => {
+				if self.count == 0 {
+					self.error()?;
+				} else {
+					self.count -= 1;
+					self.click(MENU_ITEMS.len())?;
+				}
+				self.last_action = format!("Count: {}", self.count);
+			}
+			Message::Increment => {
+				if self.count == MAX_COUNT {
+					self.error()?;
+				} else {
+					self.count += 1;
+					self.click(MENU_ITEMS.len() + 1)?;
+				}
+				self.last_action = format!("Count: {}", self.count);
+			}
+			Message::SetToggle(toggle, enabled) if toggle < TOGGLE_NAMES.len() => {
+				self.toggles[toggle] = enabled;
+				self.play(
+					if enabled {
+						self.toggle_on_sound_id
+					} else {
+						self.toggle_off_sound_id
+					},
+					1.0,
+				)?;
+				self.last_action = format!(
+					"{} {}",
+					TOGGLE_NAMES[toggle],
+					if enabled { "on" } else { "off" }
+				);
+			}
+			Message::SetHoverSounds(hover_sounds) => {
+				self.hover_sounds = hover_sounds;
+				self.play(
+					if hover_sounds {
+						self.toggle_on_sound_id
+					} else {
+						self.toggle_off_sound_id
+					},
+					1.0,
+				)?;
+			}
+			Message::SetUiVolume(volume) => {
+				let volume = volume.clamp(MIN_UI_VOLUME, 0.0);
+				// the volume changes before the tick, so the tick is
+				// heard at the new volume
+				self.audio_manager.set_parameter(
+					self.volume_parameter_id,
+					volume,
+					Some(Tween::linear(0.05)),
+				)?;
+				let old_position = 1.0 - self.ui_volume / MIN_UI_VOLUME;
+				self.ui_volume = volume;
+				self.slider_tick(old_position, 1.0 - volume / MIN_UI_VOLUME)?;
+			}
+			Message::SetBrightness(brightness) => {
+				let brightness = brightness.clamp(0.0, 1.0);
+				self.slider_tick(self.brightness, brightness)?;
+				self.brightness = brightness;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let menu = self.menu_buttons.iter_mut().enumerate().fold(
+			Column::new().spacing(8),
+			|column, (item, (hover_state, button_state))| {
+				column.push(
+					Hover::new(
+						hover_state,
+						Button::new(
+							button_state,
+							Text::new(MENU_ITEMS[item])
+								.width(Length::Fill)
+								.horizontal_alignment(HorizontalAlignment::Center),
+						)
+						.on_press(Message::PressMenuItem(item))
+						.width(Length::Units(200))
+						.style(AppStyles),
+					)
+					.on_enter(Message::Hovered),
+				)
+			},
+		);
+		let toggles = self
+			.toggle_hovers
+			.iter_mut()
+			.zip(self.toggles.iter())
+			.enumerate()
+			.fold(
+				Column::new().spacing(16),
+				|column, (toggle, (hover_state, enabled))| {
+					column.push(
+						Hover::new(
+							hover_state,
+							Checkbox::new(*enabled, TOGGLE_NAMES[toggle], move |enabled| {
+								Message::SetToggle(toggle, enabled)
+							}),
+						)
+						.on_enter(Message::Hovered),
+					)
+				},
+			);
+		let (decrement_hover, decrement_button) = &mut self.decrement_button;
+		let (increment_hover, increment_button) = &mut self.increment_button;
+		let counter = Row::new()
+			.spacing(16)
+			.align_items(Align::Center)
+			.push(
+				Hover::new(
+					decrement_hover,
+					Button::new(
+						decrement_button,
+						Text::new("-")
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(Message::Decrement)
+					.width(Length::Units(50))
+					.style(AppStyles),
+				)
+				.on_enter(Message::Hovered),
+			)
+			.push(
+				Text::new(format!("{}", self.count))
+					.size(32)
+					.width(Length::Units(50))
+					.horizontal_alignment(HorizontalAlignment::Center),
+			)
+			.push(
+				Hover::new(
+					increment_hover,
+					Button::new(
+						increment_button,
+						Text::new("+")
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(Message::Increment)
+					.width(Length::Units(50))
+					.style(AppStyles),
+				)
+				.on_enter(Message::Hovered),
+			);
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Row::new().spacing(48).push(menu).push(
+						Column::new()
+							.spacing(16)
+							.align_items(Align::Center)
+							.push(counter)
+							.push(toggles),
+					),
+				)
+				.push(Text::new(&self.last_action))
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("UI volume: {:.1} dB", self.ui_volume))
+								.width(Length::Units(200)),
+						)
+						.push(
+							Slider::new(
+								&mut self.ui_volume_slider,
+								MIN_UI_VOLUME..=0.0,
+								self.ui_volume,
+								Message::SetUiVolume,
+							)
+							.step(0.5)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Brightness: {:.0}%", self.brightness * 100.0))
+								.width(Length::Units(200)),
+						)
+						.push(
+							Slider::new(
+								&mut self.brightness_slider,
+								0.0..=1.0,
+								self.brightness,
+								Message::SetBrightness,
+							)
+							.step(0.01)
+							.style(AppStyles),
+						),
+				)
+				.push(Checkbox::new(
+					self.hover_sounds,
+					"Hover sounds",
+					Message::SetHoverSounds,
+				))
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for UiSoundsDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "ui_sounds_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetUiVolume(_) => Some("UI volume".into()),
+			Message::SetBrightness(_) => Some("Brightness".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"UI volume" => vec![Message::SetUiVolume(MIN_UI_VOLUME * (1.0 - value))],
+			"Brightness" => vec![Message::SetBrightness(value)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for UiSoundsDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "ui_sounds_demo";
+
+	// the toggles make sounds, so restoring them would play a
+	// burst of chirps on the way in
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![Message::SetUiVolume(self.ui_volume)]
+	}
+}
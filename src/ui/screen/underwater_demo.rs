@@ -1,20 +1,36 @@
 use std::error::Error;
 
-use iced::{Align, Button, Column, HorizontalAlignment, Length, Row, Text};
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Text};
 use kira::{
 	arrangement::{Arrangement, ArrangementId, LoopArrangementSettings},
 	instance::{InstanceSettings, StopInstanceSettings},
-	manager::AudioManager,
+	manager::{AudioManager, AudioManagerSettings},
 	mixer::effect::filter::{Filter, FilterSettings},
 	parameter::Mapping,
 	parameter::{ParameterId, Tween},
 	playable::PlayableSettings,
 	sequence::{Sequence, SequenceInstanceId},
-	sound::Sound,
 	Tempo, Value,
 };
+use serde::{Deserialize, Serialize};
 
-use crate::ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles};
+use crate::{
+	audio::{create_audio_manager, sound_cache::SoundCache},
+	ui::{
+		common::{screen_wrapper::ScreenWrapper, transport::Transport},
+		style::AppStyles,
+	},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &[
+	"underwater demo/drums.ogg",
+	"underwater demo/bass.ogg",
+	"underwater demo/pad.ogg",
+	"underwater demo/lead.ogg",
+];
+
+pub const TEMPO: Tempo = Tempo(85.0);
 
 const EXPLANATION_TEXT: &str = "This demo uses a single \
 parameter to control the cutoff frequency of a filter, \
@@ -23,13 +39,16 @@ the volume of the drums, and the volume of the pad.
 Each of these values uses a different mapping to properly \
 respond to the change in the \"underwater\" parameter.";
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum Message {
 	GoToDemoSelect,
 	Play,
 	Stop,
+	SetMetronomeClick(bool),
 	Submerge,
 	Resurface,
+	/// Sets how far underwater the music is (from 0 to 1).
+	SetUnderwaterAmount(f64),
 }
 
 pub struct UnderwaterDemo {
@@ -41,14 +60,21 @@ pub struct UnderwaterDemo {
 	sequence_id: Option<SequenceInstanceId>,
 	underwater_parameter_id: ParameterId,
 	underwater: bool,
+	transport: Transport,
 	screen_wrapper: ScreenWrapper<Message>,
-	play_button: iced::button::State,
 	underwater_button: iced::button::State,
 }
 
 impl UnderwaterDemo {
-	pub fn new() -> Result<Self, Box<dyn Error>> {
-		let mut audio_manager = AudioManager::new(Default::default())?;
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(AudioManagerSettings {
+			metronome_settings: Transport::metronome_settings(TEMPO),
+			..audio_manager_settings
+		})?;
+		let transport = Transport::new(&mut audio_manager, TEMPO)?;
 		let underwater_parameter_id = audio_manager.add_parameter(0.0)?;
 		let lead_track_id = audio_manager.add_sub_track(Default::default())?;
 		audio_manager.add_effect_to_track(
@@ -64,28 +90,32 @@ impl UnderwaterDemo {
 			Default::default(),
 		)?;
 		let assets_base_dir = std::env::current_dir()?.join("assets/underwater demo");
-		let drums_sound_id = audio_manager.add_sound(Sound::from_file(
-			assets_base_dir.join("drums.ogg"),
-			PlayableSettings::new().semantic_duration(Tempo(85.0).beats_to_seconds(16.0)),
-		)?)?;
+		let drums_sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&assets_base_dir.join("drums.ogg"))?
+				.to_sound(PlayableSettings::new().semantic_duration(TEMPO.beats_to_seconds(16.0))),
+		)?;
 		let drums_loop_id = audio_manager
 			.add_arrangement(Arrangement::new_loop(drums_sound_id, Default::default()))?;
-		let bass_sound_id = audio_manager.add_sound(Sound::from_file(
-			assets_base_dir.join("bass.ogg"),
-			PlayableSettings::new().semantic_duration(Tempo(85.0).beats_to_seconds(16.0)),
-		)?)?;
+		let bass_sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&assets_base_dir.join("bass.ogg"))?
+				.to_sound(PlayableSettings::new().semantic_duration(TEMPO.beats_to_seconds(16.0))),
+		)?;
 		let bass_loop_id = audio_manager
 			.add_arrangement(Arrangement::new_loop(bass_sound_id, Default::default()))?;
-		let pad_sound_id = audio_manager.add_sound(Sound::from_file(
-			assets_base_dir.join("pad.ogg"),
-			PlayableSettings::new().semantic_duration(Tempo(85.0).beats_to_seconds(16.0)),
-		)?)?;
+		let pad_sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&assets_base_dir.join("pad.ogg"))?
+				.to_sound(PlayableSettings::new().semantic_duration(TEMPO.beats_to_seconds(16.0))),
+		)?;
 		let pad_loop_id = audio_manager
 			.add_arrangement(Arrangement::new_loop(pad_sound_id, Default::default()))?;
-		let lead_sound_id = audio_manager.add_sound(Sound::from_file(
-			assets_base_dir.join("lead.ogg"),
-			PlayableSettings::new().semantic_duration(Tempo(85.0).beats_to_seconds(16.0)),
-		)?)?;
+		let lead_sound_id = audio_manager.add_sound(
+			sound_cache
+				.get(&assets_base_dir.join("lead.ogg"))?
+				.to_sound(PlayableSettings::new().semantic_duration(TEMPO.beats_to_seconds(16.0))),
+		)?;
 		let lead_loop_id = audio_manager.add_arrangement(Arrangement::new_loop(
 			lead_sound_id,
 			LoopArrangementSettings::new().default_track(lead_track_id),
@@ -99,8 +129,8 @@ impl UnderwaterDemo {
 			sequence_id: None,
 			underwater_parameter_id,
 			underwater: false,
+			transport,
 			screen_wrapper: ScreenWrapper::new("Underwater demo".into(), Message::GoToDemoSelect),
-			play_button: iced::button::State::new(),
 			underwater_button: iced::button::State::new(),
 		})
 	}
@@ -132,7 +162,8 @@ impl UnderwaterDemo {
 					},
 					Default::default(),
 				)?;
-				self.sequence_id = Some(sequence_id)
+				self.sequence_id = Some(sequence_id);
+				self.transport.start(&mut self.audio_manager)?;
 			}
 			Message::Stop => {
 				if let Some(sequence_id) = self.sequence_id {
@@ -142,6 +173,11 @@ impl UnderwaterDemo {
 					)?;
 					self.sequence_id = None;
 				}
+				self.transport.stop(&mut self.audio_manager)?;
+			}
+			Message::SetMetronomeClick(enabled) => {
+				self.transport
+					.set_click_enabled(&mut self.audio_manager, enabled)?;
 			}
 			Message::Submerge => {
 				self.audio_manager.set_parameter(
@@ -159,29 +195,27 @@ impl UnderwaterDemo {
 				)?;
 				self.underwater = false;
 			}
+			Message::SetUnderwaterAmount(amount) => {
+				let amount = amount.clamp(0.0, 1.0);
+				// a short tween keeps sudden jumps from clicking
+				self.audio_manager.set_parameter(
+					self.underwater_parameter_id,
+					amount,
+					Some(0.25.into()),
+				)?;
+				self.underwater = amount >= 0.5;
+			}
 			_ => {}
 		}
 		Ok(())
 	}
 
-	pub fn view(&mut self) -> iced::Element<'_, Message> {
-		let play_button = Button::new(
-			&mut self.play_button,
-			Text::new(match self.sequence_id {
-				Some(_) => "Stop",
-				None => "Play",
-			})
-			.width(Length::Fill)
-			.size(24)
-			.horizontal_alignment(HorizontalAlignment::Center),
-		)
-		.on_press(match self.sequence_id {
-			Some(_) => Message::Stop,
-			None => Message::Play,
-		})
-		.width(Length::Fill)
-		.style(AppStyles);
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		self.transport.check_for_events(&mut self.audio_manager);
+		Ok(())
+	}
 
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
 		let underwater_button = Button::new(
 			&mut self.underwater_button,
 			Text::new(match self.underwater {
@@ -196,20 +230,16 @@ impl UnderwaterDemo {
 			false => Message::Submerge,
 			true => Message::Resurface,
 		})
-		.width(Length::Fill)
+		.width(Length::Units(200))
 		.style(AppStyles);
 
-		self.screen_wrapper.view(
+		self.screen_wrapper.view_with_transport(
+			self.transport
+				.view(Message::Play, Message::Stop, Message::SetMetronomeClick),
 			Column::new()
 				.spacing(16)
 				.align_items(Align::Center)
-				.push(
-					Row::new()
-						.max_width(300)
-						.spacing(16)
-						.push(play_button)
-						.push(underwater_button),
-				)
+				.push(underwater_button)
 				.push(
 					Column::new()
 						.width(Length::Fill)
@@ -219,3 +249,47 @@ impl UnderwaterDemo {
 		)
 	}
 }
+
+impl crate::ui::screen_states::RememberControls for UnderwaterDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "underwater_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![Message::SetMetronomeClick(self.transport.click_enabled())]
+	}
+}
+
+#[cfg(feature = "remote")]
+impl crate::ui::remote_control::RemoteControls for UnderwaterDemo {
+	type Message = Message;
+
+	fn remote_messages(&self, command: crate::remote::RemoteCommand) -> Vec<Message> {
+		match command {
+			crate::remote::RemoteCommand::SetUnderwater { amount } => {
+				vec![Message::SetUnderwaterAmount(amount)]
+			}
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for UnderwaterDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.transport.is_playing(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if !self.transport.is_playing() {
+			return None;
+		}
+		Some("Underwater demo".into())
+	}
+}
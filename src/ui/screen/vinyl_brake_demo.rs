@@ -0,0 +1,439 @@
+mod turntable;
+
+use std::{error::Error, f64::consts::TAU, time::Instant};
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Row, Slider, Text};
+use kira::{
+	arrangement::{Arrangement, ArrangementId, LoopArrangementSettings},
+	instance::{InstanceId, InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::{EaseDirection, Easing, Mapping, ParameterId, Tween},
+	playable::PlayableSettings,
+	Value,
+};
+use serde::{Deserialize, Serialize};
+
+use self::turntable::Turntable;
+use crate::{
+	audio::{create_audio_manager, sound_cache::SoundCache},
+	ui::{common::screen_wrapper::ScreenWrapper, screen::underwater_demo::TEMPO, style::AppStyles},
+};
+
+/// The files this demo loads, relative to the assets folder.
+pub const ASSETS: &[&str] = &[
+	"underwater demo/drums.ogg",
+	"underwater demo/bass.ogg",
+	"underwater demo/pad.ogg",
+	"underwater demo/lead.ogg",
+];
+
+const EXPLANATION_TEXT: &str = "Press Brake to stop the record \
+like a DJ cutting the power to a turntable, and Spin up to start \
+it again.
+
+Every part of the song plays at a pitch tied to one speed \
+parameter, so slowing the parameter down drags the pitch and the \
+tempo down together, just like a record slowing down. Braking \
+tweens the speed to 0 in a straight line, since the platter loses \
+speed at a steady rate once the motor is off. Spinning up uses an \
+ease-out curve instead: the motor pulls hard at first and eases off \
+as the record gets up to speed. Both take less time if the record \
+only has part of the way to go.
+
+The volume is tied to the same parameter through a second mapping \
+that only starts turning it down near the end of a brake. Without \
+it, the music would turn into a low rumble instead of fading away.";
+
+/// Below this speed, the music gets quieter as the record slows
+/// down.
+const FADE_BELOW_SPEED: f64 = 0.3;
+/// How fast the record turns at full speed (in turns per
+/// second), for the turntable drawing.
+const TURNS_PER_SECOND: f64 = 100.0 / 3.0 / 60.0;
+const MIN_BRAKE_TIME: f64 = 0.2;
+const MAX_BRAKE_TIME: f64 = 4.0;
+const MIN_SPIN_UP_TIME: f64 = 0.1;
+const MAX_SPIN_UP_TIME: f64 = 2.0;
+
+/// A change in speed that's in progress, so the screen can
+/// follow along with the audio thread.
+struct SpeedRamp {
+	from: f64,
+	to: f64,
+	tween: Tween,
+	start_time: Instant,
+}
+
+impl SpeedRamp {
+	fn speed(&self) -> f64 {
+		let time = self
+			.start_time
+			.elapsed()
+			.as_secs_f64()
+			.min(self.tween.duration);
+		self.tween.tween(self.from, self.to, time)
+	}
+
+	fn finished(&self) -> bool {
+		self.start_time.elapsed().as_secs_f64() >= self.tween.duration
+	}
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	Brake,
+	SpinUp,
+	/// Sets how long a full brake takes (in seconds).
+	SetBrakeTime(f64),
+	/// Sets how long spinning up from a stop takes (in seconds).
+	SetSpinUpTime(f64),
+}
+
+pub struct VinylBrakeDemo {
+	audio_manager: AudioManager,
+	loop_ids: Vec<ArrangementId>,
+	instance_ids: Vec<InstanceId>,
+	speed_parameter_id: ParameterId,
+	/// The speed the record is turning at (or was when the last
+	/// ramp finished).
+	speed: f64,
+	ramp: Option<SpeedRamp>,
+	brake_time: f64,
+	spin_up_time: f64,
+	/// How far the record has turned (in radians), for the
+	/// turntable drawing.
+	angle: f64,
+	/// When the angle was last moved forward.
+	last_update_time: Instant,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_button: iced::button::State,
+	brake_button: iced::button::State,
+	spin_up_button: iced::button::State,
+	brake_time_slider: iced::slider::State,
+	spin_up_time_slider: iced::slider::State,
+}
+
+impl VinylBrakeDemo {
+	pub fn new(
+		sound_cache: &SoundCache,
+		audio_manager_settings: AudioManagerSettings,
+	) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let assets_base_dir = std::env::current_dir()?.join("assets");
+		let mut loop_ids = vec![];
+		for asset in ASSETS {
+			let sound_id = audio_manager.add_sound(
+				sound_cache.get(&assets_base_dir.join(asset))?.to_sound(
+					PlayableSettings::new().semantic_duration(TEMPO.beats_to_seconds(16.0)),
+				),
+			)?;
+			loop_ids.push(audio_manager.add_arrangement(Arrangement::new_loop(
+				sound_id,
+				LoopArrangementSettings::new(),
+			))?);
+		}
+		let speed_parameter_id = audio_manager.add_parameter(1.0)?;
+		Ok(Self {
+			audio_manager,
+			loop_ids,
+			instance_ids: vec![],
+			speed_parameter_id,
+			speed: 1.0,
+			ramp: None,
+			brake_time: 1.5,
+			spin_up_time: 0.5,
+			angle: 0.0,
+			last_update_time: Instant::now(),
+			screen_wrapper: ScreenWrapper::new("Vinyl brake demo".into(), Message::GoToDemoSelect),
+			play_button: iced::button::State::new(),
+			brake_button: iced::button::State::new(),
+			spin_up_button: iced::button::State::new(),
+			brake_time_slider: iced::slider::State::new(),
+			spin_up_time_slider: iced::slider::State::new(),
+		})
+	}
+
+	fn is_playing(&self) -> bool {
+		!self.instance_ids.is_empty()
+	}
+
+	/// The speed the record is turning at, as best the screen
+	/// can tell.
+	fn current_speed(&self) -> f64 {
+		match &self.ramp {
+			Some(ramp) => ramp.speed(),
+			None => self.speed,
+		}
+	}
+
+	fn play(&mut self) -> Result<(), Box<dyn Error>> {
+		self.set_speed(1.0, None)?;
+		let settings = InstanceSettings::new()
+			.pitch(Value::Parameter(
+				self.speed_parameter_id,
+				Mapping::default(),
+			))
+			.volume(Value::Parameter(
+				self.speed_parameter_id,
+				Mapping {
+					input_range: (0.0, FADE_BELOW_SPEED),
+					output_range: (0.0, 1.0),
+					clamp_bottom: true,
+					clamp_top: true,
+				},
+			));
+		// the commands are all sent at once, so the parts start
+		// on the same sample
+		for loop_id in &self.loop_ids {
+			self.instance_ids
+				.push(self.audio_manager.play(*loop_id, settings)?);
+		}
+		Ok(())
+	}
+
+	fn stop(&mut self) -> Result<(), Box<dyn Error>> {
+		for instance_id in self.instance_ids.drain(..) {
+			self.audio_manager.stop_instance(
+				instance_id,
+				StopInstanceSettings::new().fade_tween(Tween::linear(0.1)),
+			)?;
+		}
+		self.set_speed(1.0, None)
+	}
+
+	/// Moves the speed to a new value, either right away or with
+	/// a tween.
+	fn set_speed(&mut self, speed: f64, tween: Option<Tween>) -> Result<(), Box<dyn Error>> {
+		let from = self.current_speed();
+		self.audio_manager
+			.set_parameter(self.speed_parameter_id, speed, tween)?;
+		self.speed = speed;
+		self.ramp = tween.map(|tween| SpeedRamp {
+			from,
+			to: speed,
+			tween,
+			start_time: Instant::now(),
+		});
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if !self.is_playing() => self.play()?,
+			Message::Stop => self.stop()?,
+			Message::Brake if self.is_playing() => {
+				// a record that's already slowing down has less
+				// speed to lose
+				let duration = self.brake_time * self.current_speed();
+				if duration > 0.0 {
+					self.set_speed(0.0, Some(Tween::linear(duration)))?;
+				}
+			}
+			Message::SpinUp if self.is_playing() => {
+				let duration = self.spin_up_time * (1.0 - self.current_speed());
+				if duration > 0.0 {
+					self.set_speed(
+						1.0,
+						Some(Tween {
+							duration,
+							easing: Easing::PowI(2),
+							ease_direction: EaseDirection::Out,
+						}),
+					)?;
+				}
+			}
+			Message::SetBrakeTime(time) => {
+				self.brake_time = time.clamp(MIN_BRAKE_TIME, MAX_BRAKE_TIME);
+			}
+			Message::SetSpinUpTime(time) => {
+				self.spin_up_time = time.clamp(MIN_SPIN_UP_TIME, MAX_SPIN_UP_TIME);
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		let now = Instant::now();
+		let dt = (now - self.last_update_time).as_secs_f64();
+		self.last_update_time = now;
+		if self.is_playing() {
+			self.angle = (self.angle + self.current_speed() * TURNS_PER_SECOND * TAU * dt) % TAU;
+		}
+		if self.ramp.as_ref().is_some_and(SpeedRamp::finished) {
+			self.ramp = None;
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.is_playing();
+		let speed = self.current_speed();
+		let status_text = if !playing {
+			"Press Play to drop the needle".into()
+		} else if speed <= 0.0 {
+			"Stopped".into()
+		} else {
+			format!("{:.0}% speed", speed * 100.0)
+		};
+		let mut brake_button = Button::new(
+			&mut self.brake_button,
+			Text::new("Brake")
+				.width(Length::Fill)
+				.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.width(Length::Units(120))
+		.style(AppStyles);
+		let mut spin_up_button = Button::new(
+			&mut self.spin_up_button,
+			Text::new("Spin up")
+				.width(Length::Fill)
+				.horizontal_alignment(HorizontalAlignment::Center),
+		)
+		.width(Length::Units(120))
+		.style(AppStyles);
+		if playing {
+			brake_button = brake_button.on_press(Message::Brake);
+			spin_up_button = spin_up_button.on_press(Message::SpinUp);
+		}
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Button::new(
+						&mut self.play_button,
+						Text::new(if playing { "Stop" } else { "Play" })
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(if playing {
+						Message::Stop
+					} else {
+						Message::Play
+					})
+					.width(Length::Units(120))
+					.style(AppStyles),
+				)
+				.push(Turntable {
+					angle: self.angle as f32,
+				})
+				.push(Text::new(status_text))
+				.push(
+					Row::new()
+						.spacing(16)
+						.push(brake_button)
+						.push(spin_up_button),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Brake time: {:.1} s", self.brake_time))
+								.width(Length::Units(200)),
+						)
+						.push(
+							Slider::new(
+								&mut self.brake_time_slider,
+								MIN_BRAKE_TIME..=MAX_BRAKE_TIME,
+								self.brake_time,
+								Message::SetBrakeTime,
+							)
+							.step(0.1)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Spin-up time: {:.1} s", self.spin_up_time))
+								.width(Length::Units(200)),
+						)
+						.push(
+							Slider::new(
+								&mut self.spin_up_time_slider,
+								MIN_SPIN_UP_TIME..=MAX_SPIN_UP_TIME,
+								self.spin_up_time,
+								Message::SetSpinUpTime,
+							)
+							.step(0.1)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for VinylBrakeDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "vinyl_brake_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetBrakeTime(_) => Some("Brake time".into()),
+			Message::SetSpinUpTime(_) => Some("Spin-up time".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Brake time" => vec![Message::SetBrakeTime(
+				MIN_BRAKE_TIME + (MAX_BRAKE_TIME - MIN_BRAKE_TIME) * value,
+			)],
+			"Spin-up time" => vec![Message::SetSpinUpTime(
+				MIN_SPIN_UP_TIME + (MAX_SPIN_UP_TIME - MIN_SPIN_UP_TIME) * value,
+			)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for VinylBrakeDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "vinyl_brake_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetBrakeTime(self.brake_time),
+			Message::SetSpinUpTime(self.spin_up_time),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for VinylBrakeDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.is_playing(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if !self.is_playing() {
+			return None;
+		}
+		Some("Vinyl brake demo".into())
+	}
+}
@@ -0,0 +1,108 @@
+use iced::{mouse::Interaction, Background, Color, Length, Point, Rectangle, Size};
+use iced_graphics::{Backend, Defaults, Primitive, Renderer};
+use iced_native::{
+	layout::{Limits, Node},
+	Element, Layout, Widget,
+};
+
+const SIZE: f32 = 220.0;
+const RECORD_SIZE: f32 = 190.0;
+const LABEL_SIZE: f32 = 70.0;
+const SPINDLE_SIZE: f32 = 8.0;
+const MARKER_SIZE: f32 = 14.0;
+/// How far the marker is from the middle of the record (in
+/// pixels).
+const MARKER_DISTANCE: f32 = 60.0;
+const PLATTER_COLOR: [f32; 4] = [0.3, 0.3, 0.3, 1.0];
+const RECORD_COLOR: [f32; 4] = [0.08, 0.08, 0.08, 1.0];
+const GROOVE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.08];
+const LABEL_COLOR: [f32; 4] = [0.8, 0.3, 0.2, 1.0];
+const MARKER_COLOR: [f32; 4] = [1.0, 0.9, 0.6, 1.0];
+const SPINDLE_COLOR: [f32; 4] = [0.8, 0.8, 0.8, 1.0];
+
+/// A record on a turntable, drawn from above. A marker on the
+/// label shows how far the record has turned.
+pub struct Turntable {
+	/// How far the record has turned (in radians).
+	pub angle: f32,
+}
+
+impl Turntable {
+	fn circle(center: Point, size: f32, color: [f32; 4]) -> Primitive {
+		Primitive::Quad {
+			bounds: Rectangle::new(
+				Point::new(center.x - size / 2.0, center.y - size / 2.0),
+				Size::new(size, size),
+			),
+			background: Background::Color(color.into()),
+			border_radius: size / 2.0,
+			border_width: 0.0,
+			border_color: Color::TRANSPARENT,
+		}
+	}
+
+	fn ring(center: Point, size: f32, color: [f32; 4]) -> Primitive {
+		Primitive::Quad {
+			bounds: Rectangle::new(
+				Point::new(center.x - size / 2.0, center.y - size / 2.0),
+				Size::new(size, size),
+			),
+			background: Background::Color(Color::TRANSPARENT),
+			border_radius: size / 2.0,
+			border_width: 1.0,
+			border_color: color.into(),
+		}
+	}
+}
+
+impl<Message, B: Backend> Widget<Message, Renderer<B>> for Turntable {
+	fn width(&self) -> Length {
+		Length::Shrink
+	}
+
+	fn height(&self) -> Length {
+		Length::Shrink
+	}
+
+	fn layout(&self, _renderer: &Renderer<B>, _limits: &Limits) -> Node {
+		Node::new(Size::new(SIZE, SIZE))
+	}
+
+	fn draw(
+		&self,
+		_renderer: &mut Renderer<B>,
+		_defaults: &Defaults,
+		layout: Layout<'_>,
+		_cursor_position: Point,
+		_viewport: &Rectangle,
+	) -> (Primitive, Interaction) {
+		let bounds = layout.bounds();
+		let center = Point::new(bounds.center_x(), bounds.center_y());
+		let mut primitives = vec![
+			Self::circle(center, SIZE, PLATTER_COLOR),
+			Self::circle(center, RECORD_SIZE, RECORD_COLOR),
+		];
+		// a few grooves so it looks like a record
+		for groove in 1..=4 {
+			let size = LABEL_SIZE + (RECORD_SIZE - LABEL_SIZE) * groove as f32 / 5.0;
+			primitives.push(Self::ring(center, size, GROOVE_COLOR));
+		}
+		primitives.push(Self::circle(center, LABEL_SIZE, LABEL_COLOR));
+		// the marker sits out past the label so it's easy to follow
+		let marker = Point::new(
+			center.x + MARKER_DISTANCE * self.angle.cos(),
+			center.y + MARKER_DISTANCE * self.angle.sin(),
+		);
+		primitives.push(Self::circle(marker, MARKER_SIZE, MARKER_COLOR));
+		primitives.push(Self::circle(center, SPINDLE_SIZE, SPINDLE_COLOR));
+		(Primitive::Group { primitives }, Interaction::default())
+	}
+
+	fn hash_layout(&self, _state: &mut iced_native::Hasher) {}
+}
+
+impl<'a, Message, B: Backend> From<Turntable> for Element<'a, Message, Renderer<B>> {
+	fn from(turntable: Turntable) -> Self {
+		Element::new(turntable)
+	}
+}
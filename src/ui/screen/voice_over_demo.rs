@@ -0,0 +1,182 @@
+use std::{error::Error, path::PathBuf};
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Radio, Row, Text};
+use kira::manager::{AudioManager, AudioManagerSettings};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, sound_bank::SoundBank},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+const EXPLANATION_TEXT: &str = "This demo keeps the voice \
+lines for each language in their own folder. Picking a \
+language unloads the current bank and loads the new one, \
+but the buttons always ask for the same line names, so the \
+code that triggers the lines doesn't know which language \
+is loaded.
+
+Only one bank is in memory at a time, which matters when a \
+game has hours of dialogue in many languages. (The voices \
+here are placeholder babble.)";
+
+/// The language folders in "assets/voice over demo" and
+/// the names shown for them.
+const LANGUAGES: [(&str, &str); 3] = [("en", "English"), ("fr", "Français"), ("de", "Deutsch")];
+
+/// The file every bank has for each line.
+const LINE_FILES: [&str; 3] = ["greeting.wav", "warning.wav", "farewell.wav"];
+const LINE_NAMES: [&str; 3] = ["Greeting", "Warning", "Farewell"];
+
+/// The captions for each line, in the same order as the languages.
+const CAPTIONS: [[&str; 3]; 3] = [
+	[
+		"Hello there, traveler.",
+		"Bonjour, voyageur.",
+		"Hallo, Reisender.",
+	],
+	[
+		"Watch out, something's coming!",
+		"Attention, quelque chose arrive !",
+		"Vorsicht, da kommt etwas!",
+	],
+	["Safe travels.", "Bon voyage.", "Gute Reise."],
+];
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	SetLanguage(usize),
+	PlayLine(usize),
+}
+
+pub struct VoiceOverDemo {
+	audio_manager: AudioManager,
+	assets_dir: PathBuf,
+	language: usize,
+	bank: Option<SoundBank>,
+	current_line: Option<usize>,
+	screen_wrapper: ScreenWrapper<Message>,
+	line_buttons: [iced::button::State; 3],
+}
+
+impl VoiceOverDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut demo = Self {
+			audio_manager: create_audio_manager(audio_manager_settings)?,
+			assets_dir: std::env::current_dir()?.join("assets/voice over demo"),
+			language: 0,
+			bank: None,
+			current_line: None,
+			screen_wrapper: ScreenWrapper::new("Voice-over demo".into(), Message::GoToDemoSelect),
+			line_buttons: Default::default(),
+		};
+		demo.load_bank(0)?;
+		Ok(demo)
+	}
+
+	fn load_bank(&mut self, language: usize) -> Result<(), Box<dyn Error>> {
+		if let Some(bank) = self.bank.take() {
+			bank.unload(&mut self.audio_manager)?;
+		}
+		self.current_line = None;
+		self.language = language;
+		let (folder, _) = LANGUAGES[language];
+		self.bank = Some(SoundBank::load(
+			&mut self.audio_manager,
+			&self.assets_dir.join(folder),
+			&LINE_FILES,
+		)?);
+		Ok(())
+	}
+
+	/// Plays a line from whichever bank is loaded.
+	fn play_line(&mut self, line: usize) -> Result<(), Box<dyn Error>> {
+		let key = LINE_FILES[line].trim_end_matches(".wav");
+		if let Some(sound_id) = self.bank.as_ref().and_then(|bank| bank.sound_id(key)) {
+			self.audio_manager.play(sound_id, Default::default())?;
+			self.current_line = Some(line);
+		}
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::SetLanguage(language) => self.load_bank(language)?,
+			Message::PlayLine(line) => self.play_line(line)?,
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let language = self.language;
+		let language_radios = LANGUAGES.iter().enumerate().fold(
+			Row::new().spacing(16).align_items(Align::Center),
+			|row, (index, (_, name))| {
+				row.push(Radio::new(
+					index,
+					*name,
+					Some(language),
+					Message::SetLanguage,
+				))
+			},
+		);
+		let line_buttons = self
+			.line_buttons
+			.iter_mut()
+			.zip(LINE_NAMES.iter())
+			.enumerate()
+			.fold(Row::new().spacing(16), |row, (index, (button, name))| {
+				row.push(
+					Button::new(
+						button,
+						Text::new(*name)
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(Message::PlayLine(index))
+					.width(Length::Units(120))
+					.style(AppStyles),
+				)
+			});
+		let bank_text = match &self.bank {
+			Some(bank) => format!(
+				"Loaded the {} bank ({} lines) in {:.1}ms",
+				LANGUAGES[language].1,
+				bank.len(),
+				bank.load_time().as_secs_f64() * 1000.0
+			),
+			None => "No bank loaded".into(),
+		};
+		let caption = match self.current_line {
+			Some(line) => CAPTIONS[line][language],
+			None => "",
+		};
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(language_radios)
+				.push(Text::new(bank_text).size(16))
+				.push(line_buttons)
+				.push(Text::new(caption).size(24))
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for VoiceOverDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "voice_over_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![Message::SetLanguage(self.language)]
+	}
+}
@@ -0,0 +1,357 @@
+use std::{error::Error, time::Instant};
+
+use iced::{Align, Button, Checkbox, Column, HorizontalAlignment, Length, Row, Slider, Text};
+use kira::{
+	manager::{AudioManager, AudioManagerSettings},
+	playable::PlayableSettings,
+	sound::Sound,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{
+		create_audio_manager,
+		layered_sound::LayeredSound,
+		sound_container::{Selection, SoundContainer},
+		synth,
+	},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+const EXPLANATION_TEXT: &str = "A gunshot in a game is rarely one \
+recording. Sound designers build it from layers that each cover \
+part of the sound: a sharp transient for the crack, a body that \
+gives it weight, and a tail for the echo rolling off into the \
+distance. Each layer can be swapped, tuned and balanced on its \
+own, and the same body can be shared by several weapons with \
+different tails.
+
+Here each layer is a sound container with three variations, and \
+the three containers are wrapped in one layered sound, so the \
+screen fires the weapon with a single call. Every shot picks a \
+variation for each layer and nudges its pitch and volume, and \
+since the layers are picked separately, the combinations add up \
+to 27 different shots before the random nudges.
+
+Each layer plays on its own track with a gain effect, so the \
+sliders change the balance even while tails are still ringing.";
+
+const LAYER_NAMES: [&str; 3] = ["Transient", "Body", "Tail"];
+/// How far each layer's pitch can stray (in semitones, up or
+/// down) when randomizing.
+const LAYER_PITCH_VARIATIONS: [f64; 3] = [1.5, 1.0, 2.0];
+/// How much quieter than the original each layer can be (in
+/// decibels) when randomizing.
+const LAYER_VOLUME_VARIATIONS: [f64; 3] = [2.0, 2.0, 4.0];
+const MIN_LAYER_VOLUME: f64 = -40.0;
+const MAX_LAYER_VOLUME: f64 = 6.0;
+/// How long to wait between shots while holding the trigger
+/// (in seconds).
+const FIRE_INTERVAL: f64 = 0.15;
+
+/// Generates a layer's variations.
+fn layer_sounds(layer: usize) -> Vec<Sound> {
+	match layer {
+		// short, bright clicks for the crack
+		0 => vec![
+			synth::noise_hit(0.02, 0.9, PlayableSettings::new()),
+			synth::noise_hit(0.03, 0.85, PlayableSettings::new()),
+			synth::noise_hit(0.025, 0.95, PlayableSettings::new()),
+		],
+		1 => vec![
+			synth::snare(120.0, 0.3, PlayableSettings::new()),
+			synth::snare(150.0, 0.25, PlayableSettings::new()),
+			synth::snare(100.0, 0.35, PlayableSettings::new()),
+		],
+		// thunder claps are close enough to an echo rolling off
+		// buildings
+		_ => vec![
+			synth::thunder(1.2, 1, PlayableSettings::new()),
+			synth::thunder(1.5, 2, PlayableSettings::new()),
+			synth::thunder(1.0, 3, PlayableSettings::new()),
+		],
+	}
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Fire,
+	SetHoldingTrigger(bool),
+	SetRandomize(bool),
+	/// Sets a layer's volume (in decibels).
+	SetLayerVolume(usize, f64),
+}
+
+pub struct WeaponDemo {
+	audio_manager: AudioManager,
+	weapon: LayeredSound,
+	randomize: bool,
+	/// When the last shot was fired while holding the trigger,
+	/// or `None` if the trigger isn't held.
+	holding_since: Option<Instant>,
+	shots: usize,
+	screen_wrapper: ScreenWrapper<Message>,
+	fire_button: iced::button::State,
+	hold_button: iced::button::State,
+	layer_sliders: Vec<iced::slider::State>,
+}
+
+impl WeaponDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let mut containers = vec![];
+		for layer in 0..LAYER_NAMES.len() {
+			let mut sound_ids = vec![];
+			for sound in layer_sounds(layer) {
+				sound_ids.push(audio_manager.add_sound(sound)?);
+			}
+			containers.push(SoundContainer::new(sound_ids, Selection::RandomNoRepeat));
+		}
+		let weapon = LayeredSound::new(&mut audio_manager, containers)?;
+		let mut demo = Self {
+			audio_manager,
+			weapon,
+			randomize: true,
+			holding_since: None,
+			shots: 0,
+			screen_wrapper: ScreenWrapper::new("Weapon demo".into(), Message::GoToDemoSelect),
+			fire_button: iced::button::State::new(),
+			hold_button: iced::button::State::new(),
+			layer_sliders: LAYER_NAMES
+				.iter()
+				.map(|_| iced::slider::State::new())
+				.collect(),
+		};
+		demo.update_variations();
+		Ok(demo)
+	}
+
+	/// Turns each layer's random pitch and volume nudges on or
+	/// off. The layers still pick a random variation either way.
+	fn update_variations(&mut self) {
+		for (layer, layer_state) in self.weapon.layers_mut().iter_mut().enumerate() {
+			let (pitch_variation, volume_variation) = if self.randomize {
+				(
+					LAYER_PITCH_VARIATIONS[layer],
+					LAYER_VOLUME_VARIATIONS[layer],
+				)
+			} else {
+				(0.0, 0.0)
+			};
+			layer_state.container.set_pitch_variation(pitch_variation);
+			layer_state.container.set_volume_variation(volume_variation);
+		}
+	}
+
+	fn fire(&mut self) -> Result<(), Box<dyn Error>> {
+		self.weapon
+			.play(&mut self.audio_manager, Default::default())?;
+		self.shots += 1;
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Fire => self.fire()?,
+			Message::SetHoldingTrigger(true) if self.holding_since.is_none() => {
+				self.fire()?;
+				self.holding_since = Some(Instant::now());
+			}
+			Message::SetHoldingTrigger(false) => self.holding_since = None,
+			Message::SetRandomize(randomize) => {
+				self.randomize = randomize;
+				self.update_variations();
+			}
+			Message::SetLayerVolume(layer, volume) if layer < LAYER_NAMES.len() => {
+				self.weapon.set_layer_volume(
+					&mut self.audio_manager,
+					layer,
+					volume.clamp(MIN_LAYER_VOLUME, MAX_LAYER_VOLUME),
+				)?;
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	/// Whether shots are being fired from the UI timer.
+	pub fn plays_from_ui_timer(&self) -> bool {
+		self.holding_since.is_some()
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		if let Some(holding_since) = self.holding_since {
+			if holding_since.elapsed().as_secs_f64() >= FIRE_INTERVAL {
+				self.fire()?;
+				self.holding_since = Some(Instant::now());
+			}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let holding = self.holding_since.is_some();
+		let shots_text = match self.shots {
+			0 => "No shots yet".into(),
+			1 => "1 shot".into(),
+			shots => format!("{} shots", shots),
+		};
+		let layers = self
+			.weapon
+			.layers()
+			.iter()
+			.zip(self.layer_sliders.iter_mut())
+			.enumerate()
+			.fold(
+				Column::new().spacing(8).max_width(700),
+				|column, (index, (layer, slider))| {
+					let last_played_text = match layer.container.last_played() {
+						Some(played) => format!(
+							"Variation {} - {:.2}x - {:.1} dB",
+							played.index + 1,
+							played.pitch,
+							20.0 * played.volume.log10()
+						),
+						None => "".into(),
+					};
+					column.push(
+						Row::new()
+							.spacing(16)
+							.align_items(Align::Center)
+							.push(
+								Text::new(format!(
+									"{}: {:.1} dB",
+									LAYER_NAMES[index],
+									layer.volume()
+								))
+								.width(Length::Units(160)),
+							)
+							.push(
+								Slider::new(
+									slider,
+									MIN_LAYER_VOLUME..=MAX_LAYER_VOLUME,
+									layer.volume(),
+									move |volume| Message::SetLayerVolume(index, volume),
+								)
+								.step(0.5)
+								.style(AppStyles),
+							)
+							.push(
+								Text::new(last_played_text)
+									.size(16)
+									.width(Length::Units(240)),
+							),
+					)
+				},
+			);
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Row::new()
+						.spacing(16)
+						.push(
+							Button::new(
+								&mut self.fire_button,
+								Text::new("Fire")
+									.width(Length::Fill)
+									.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(Message::Fire)
+							.width(Length::Units(150))
+							.style(AppStyles),
+						)
+						.push(
+							Button::new(
+								&mut self.hold_button,
+								Text::new(if holding {
+									"Release trigger"
+								} else {
+									"Hold trigger"
+								})
+								.width(Length::Fill)
+								.horizontal_alignment(HorizontalAlignment::Center),
+							)
+							.on_press(Message::SetHoldingTrigger(!holding))
+							.width(Length::Units(150))
+							.style(AppStyles),
+						),
+				)
+				.push(Text::new(shots_text))
+				.push(Checkbox::new(
+					self.randomize,
+					"Randomize pitch and volume",
+					Message::SetRandomize,
+				))
+				.push(layers)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for WeaponDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "weapon_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetLayerVolume(layer, _) => LAYER_NAMES
+				.get(*layer)
+				.map(|name| format!("{} volume", name)),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		LAYER_NAMES
+			.iter()
+			.position(|name| control == format!("{} volume", name))
+			.map(|layer| {
+				vec![Message::SetLayerVolume(
+					layer,
+					MIN_LAYER_VOLUME + (MAX_LAYER_VOLUME - MIN_LAYER_VOLUME) * value,
+				)]
+			})
+			.unwrap_or_default()
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for WeaponDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "weapon_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		let mut messages = vec![Message::SetRandomize(self.randomize)];
+		for (index, layer) in self.weapon.layers().iter().enumerate() {
+			messages.push(Message::SetLayerVolume(index, layer.volume()));
+		}
+		messages
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for WeaponDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.holding_since.is_some(),
+			Message::SetHoldingTrigger(true),
+			Message::SetHoldingTrigger(false),
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		self.holding_since.map(|_| "Weapon demo".into())
+	}
+}
@@ -0,0 +1,396 @@
+use std::{error::Error, time::Instant};
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Row, Slider, Text};
+use kira::{
+	group::GroupId,
+	instance::{InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	parameter::{ParameterId, Tween},
+	playable::PlayableSettings,
+	sequence::{EventReceiver, Sequence, SequenceInstanceId, SequenceSettings},
+	sound::SoundId,
+	AudioResult, Duration, Value,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, synth},
+	ui::{common::screen_wrapper::ScreenWrapper, style::AppStyles},
+};
+
+const EXPLANATION_TEXT: &str = "The rain, wind, and distant \
+thunder are endless beds that loop in the background, and each \
+slider sets how loud one of them is. Every so often a thunder \
+clap breaks through the thunder bed, louder the closer the storm \
+is.
+
+The beds are always playing while the weather is on. Their \
+volumes come from parameters, so the sliders just move the \
+parameters. The claps come from a sequence that waits a random \
+time, plays one of three claps at a random pitch and position, \
+and does that a few times before emitting an event saying it's \
+done. A sequence can't pick its own waits, so the screen rolls \
+them when it builds the sequence, and builds a fresh one with \
+new waits every time the last one finishes. Looping a single \
+sequence would repeat the same pattern of claps over and over.";
+
+/// How long each bed is (in seconds) before it loops.
+const BED_LENGTH: f64 = 8.0;
+/// How long the volumes take to follow the sliders.
+const INTENSITY_TWEEN_TIME: f64 = 0.5;
+/// How many claps each sequence plays before a new one is built.
+const STRIKES_PER_SEQUENCE: usize = 4;
+const MIN_STRIKE_GAP: f64 = 3.0;
+const MAX_STRIKE_GAP: f64 = 30.0;
+/// How long "Thunder!" stays on screen after a clap.
+const STRIKE_FLASH_TIME: f64 = 1.5;
+
+/// One of the continuous sounds that make up the weather.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Layer {
+	Rain,
+	Wind,
+	Thunder,
+}
+
+impl Layer {
+	const ALL: [Self; 3] = [Self::Rain, Self::Wind, Self::Thunder];
+
+	fn index(self) -> usize {
+		Self::ALL.iter().position(|layer| *layer == self).unwrap()
+	}
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::Rain => "Rain",
+			Self::Wind => "Wind",
+			Self::Thunder => "Thunder",
+		}
+	}
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum ThunderEvent {
+	Strike,
+	Finished,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	SetIntensity(Layer, f64),
+	/// Sets the average time between thunder claps (in seconds).
+	SetStrikeGap(f64),
+}
+
+pub struct WeatherDemo {
+	audio_manager: AudioManager,
+	group_id: GroupId,
+	bed_sound_ids: [SoundId; 3],
+	clap_sound_ids: Vec<SoundId>,
+	intensity_parameter_ids: [ParameterId; 3],
+	intensities: [f64; 3],
+	strike_gap: f64,
+	rng: StdRng,
+	playing: bool,
+	thunder_sequence: Option<(SequenceInstanceId, EventReceiver<ThunderEvent>)>,
+	last_strike: Option<Instant>,
+	strikes: usize,
+	screen_wrapper: ScreenWrapper<Message>,
+	intensity_sliders: [iced::slider::State; 3],
+	strike_gap_slider: iced::slider::State,
+	play_button: iced::button::State,
+}
+
+impl WeatherDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let group_id = audio_manager.add_group([])?;
+		let bed_settings = || {
+			PlayableSettings::new()
+				.default_loop_start(0.0)
+				.groups([group_id])
+		};
+		let bed_sound_ids = [
+			audio_manager.add_sound(synth::rain(BED_LENGTH, bed_settings()))?,
+			audio_manager.add_sound(synth::wind(BED_LENGTH, bed_settings()))?,
+			audio_manager.add_sound(synth::rumble(BED_LENGTH, bed_settings()))?,
+		];
+		let mut clap_sound_ids = vec![];
+		for (seed, duration) in [(1, 3.0), (2, 4.0), (3, 5.0)].iter() {
+			clap_sound_ids.push(audio_manager.add_sound(synth::thunder(
+				*duration,
+				*seed,
+				PlayableSettings::new().groups([group_id]),
+			))?);
+		}
+		let intensities = [0.6, 0.3, 0.4];
+		let intensity_parameter_ids = [
+			audio_manager.add_parameter(intensities[0])?,
+			audio_manager.add_parameter(intensities[1])?,
+			audio_manager.add_parameter(intensities[2])?,
+		];
+		Ok(Self {
+			audio_manager,
+			group_id,
+			bed_sound_ids,
+			clap_sound_ids,
+			intensity_parameter_ids,
+			intensities,
+			strike_gap: 10.0,
+			rng: StdRng::from_entropy(),
+			playing: false,
+			thunder_sequence: None,
+			last_strike: None,
+			strikes: 0,
+			screen_wrapper: ScreenWrapper::new("Weather demo".into(), Message::GoToDemoSelect),
+			intensity_sliders: Default::default(),
+			strike_gap_slider: iced::slider::State::new(),
+			play_button: iced::button::State::new(),
+		})
+	}
+
+	/// Starts a sequence that plays a few thunder claps with
+	/// random gaps in between, replacing the one that was
+	/// playing (if any). Claps that are still ringing out are
+	/// left alone.
+	fn start_thunder_sequence(&mut self) -> AudioResult<()> {
+		if let Some((sequence_id, _)) = self.thunder_sequence.take() {
+			self.audio_manager.stop_sequence(sequence_id)?;
+		}
+		let mut sequence = Sequence::new(SequenceSettings::new().groups([self.group_id]));
+		let choices: Vec<_> = self.clap_sound_ids.iter().map(|id| (*id).into()).collect();
+		for _ in 0..STRIKES_PER_SEQUENCE {
+			let gap = self
+				.rng
+				.gen_range(self.strike_gap * 0.5, self.strike_gap * 1.5);
+			sequence.wait(Duration::Seconds(gap));
+			sequence.emit(ThunderEvent::Strike);
+			sequence.play_random(
+				choices.clone(),
+				InstanceSettings::new()
+					.volume(self.intensity_parameter_ids[Layer::Thunder.index()])
+					.pitch(Value::Random(0.8, 1.1))
+					.panning(Value::Random(0.2, 0.8)),
+			);
+		}
+		sequence.emit(ThunderEvent::Finished);
+		self.thunder_sequence = Some(
+			self.audio_manager
+				.start_sequence(sequence, Default::default())?,
+		);
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if !self.playing => {
+				for (sound_id, parameter_id) in self
+					.bed_sound_ids
+					.iter()
+					.zip(self.intensity_parameter_ids.iter())
+				{
+					self.audio_manager.play(
+						*sound_id,
+						InstanceSettings::new()
+							.volume(*parameter_id)
+							.fade_in_tween(Tween::linear(1.0)),
+					)?;
+				}
+				self.start_thunder_sequence()?;
+				self.playing = true;
+			}
+			Message::Stop => {
+				self.audio_manager.stop_group(
+					self.group_id,
+					StopInstanceSettings::new().fade_tween(Tween::linear(1.0)),
+				)?;
+				self.thunder_sequence = None;
+				self.playing = false;
+			}
+			Message::SetIntensity(layer, intensity) => {
+				self.intensities[layer.index()] = intensity;
+				self.audio_manager.set_parameter(
+					self.intensity_parameter_ids[layer.index()],
+					intensity,
+					Some(Tween::linear(INTENSITY_TWEEN_TIME)),
+				)?;
+			}
+			Message::SetStrikeGap(gap) => {
+				self.strike_gap = gap;
+				// the waits in the current sequence were rolled for
+				// the old gap, so roll new ones
+				if self.playing {
+					self.start_thunder_sequence()?;
+				}
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		let mut finished = false;
+		if let Some((_, events)) = &mut self.thunder_sequence {
+			while let Some(event) = events.pop() {
+				match event {
+					ThunderEvent::Strike => {
+						self.last_strike = Some(Instant::now());
+						self.strikes += 1;
+					}
+					ThunderEvent::Finished => finished = true,
+				}
+			}
+		}
+		if finished {
+			self.start_thunder_sequence()?;
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.playing;
+		let striking = matches!(self.last_strike, Some(time) if time.elapsed().as_secs_f64() < STRIKE_FLASH_TIME);
+		let intensities = self.intensities;
+		let sliders = Layer::ALL
+			.iter()
+			.zip(self.intensity_sliders.iter_mut())
+			.fold(
+				Column::new().spacing(16).max_width(500),
+				|column, (layer, state)| {
+					let layer = *layer;
+					let intensity = intensities[layer.index()];
+					column.push(
+						Row::new()
+							.spacing(16)
+							.align_items(Align::Center)
+							.push(
+								Text::new(format!("{}: {:.0}%", layer.name(), intensity * 100.0))
+									.width(Length::Units(150)),
+							)
+							.push(
+								Slider::new(state, 0.0..=1.0, intensity, move |intensity| {
+									Message::SetIntensity(layer, intensity)
+								})
+								.step(0.01)
+								.style(AppStyles),
+							),
+					)
+				},
+			);
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(sliders)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Claps every {:.0} s", self.strike_gap))
+								.width(Length::Units(150)),
+						)
+						.push(
+							Slider::new(
+								&mut self.strike_gap_slider,
+								MIN_STRIKE_GAP..=MAX_STRIKE_GAP,
+								self.strike_gap,
+								Message::SetStrikeGap,
+							)
+							.step(1.0)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Button::new(
+						&mut self.play_button,
+						Text::new(if playing { "Stop" } else { "Play" })
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(if playing {
+						Message::Stop
+					} else {
+						Message::Play
+					})
+					.width(Length::Units(100))
+					.style(AppStyles),
+				)
+				.push(Text::new(format!(
+					"{} claps so far{}",
+					self.strikes,
+					if striking { " - Thunder!" } else { "" }
+				)))
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for WeatherDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "weather_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetIntensity(layer, _) => Some(layer.name().into()),
+			Message::SetStrikeGap(_) => Some("Clap gap".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		if control == "Clap gap" {
+			return vec![Message::SetStrikeGap(
+				(MIN_STRIKE_GAP + (MAX_STRIKE_GAP - MIN_STRIKE_GAP) * value).round(),
+			)];
+		}
+		Layer::ALL
+			.iter()
+			.filter(|layer| layer.name() == control)
+			.map(|layer| Message::SetIntensity(*layer, value))
+			.collect()
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for WeatherDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "weather_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		let mut messages: Vec<Message> = Layer::ALL
+			.iter()
+			.map(|layer| Message::SetIntensity(*layer, self.intensities[layer.index()]))
+			.collect();
+		messages.push(Message::SetStrikeGap(self.strike_gap));
+		messages
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for WeatherDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(key, self.playing, Message::Play, Message::Stop)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if self.playing {
+			Some("Weather demo".into())
+		} else {
+			None
+		}
+	}
+}
@@ -0,0 +1,463 @@
+use std::{
+	collections::VecDeque,
+	error::Error,
+	time::{Duration, Instant},
+};
+
+use iced::{Align, Button, Column, HorizontalAlignment, Length, Row, Slider, Text};
+use kira::{
+	instance::{InstanceId, InstanceSettings, StopInstanceSettings},
+	manager::{AudioManager, AudioManagerSettings},
+	mixer::{
+		effect::filter::{Filter, FilterMode, FilterSettings},
+		SubTrackId,
+	},
+	parameter::{EaseDirection, Easing, Mapping, ParameterId, Tween},
+	playable::PlayableSettings,
+	sound::SoundId,
+	Value,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	audio::{create_audio_manager, synth},
+	ui::{
+		common::{curve_plot::CurvePlot, screen_wrapper::ScreenWrapper},
+		style::AppStyles,
+	},
+};
+
+const EXPLANATION_TEXT: &str = "There are no recordings in this \
+demo: the wind is a few seconds of white noise generated when the \
+screen opens, played through two filters at once. A low-pass filter \
+turns the noise into a soft rumble, and a resonant band-pass filter \
+picks out a narrow band of it, which sounds like wind whistling past \
+a corner.
+
+Both filters read the same wind speed parameter through their own \
+mappings. As the wind picks up, the rumble opens up and gets louder, \
+and the whistle rises in pitch and fades in once the wind is strong \
+enough. The speed drifts on its own: every few seconds, the screen \
+picks a new speed near the base speed and tweens the parameter to it \
+over a random amount of time, and the gustiness decides how far from \
+the base speed it can wander. The tweens run on the audio thread, so \
+the screen only has to step in once per gust.";
+
+/// How long the noise loop is (in seconds). Long enough that the
+/// loop point doesn't stand out.
+const NOISE_LENGTH: f64 = 5.0;
+const MIN_RUMBLE_CUTOFF: f64 = 150.0;
+const MAX_RUMBLE_CUTOFF: f64 = 1200.0;
+const MIN_WHISTLE_CUTOFF: f64 = 300.0;
+const MAX_WHISTLE_CUTOFF: f64 = 1600.0;
+const WHISTLE_RESONANCE: f64 = 0.8;
+/// Below this wind speed, the whistle is silent.
+const WHISTLE_START_SPEED: f64 = 0.3;
+const MIN_GUST_TIME: f64 = 1.0;
+const MAX_GUST_TIME: f64 = 4.0;
+const INITIAL_BASE_SPEED: f64 = 0.4;
+const INITIAL_GUSTINESS: f64 = 0.3;
+/// How often the wind speed is added to the plot (in seconds).
+const HISTORY_INTERVAL: f64 = 0.05;
+/// How many wind speeds the plot shows (10 seconds' worth).
+const HISTORY_LENGTH: usize = 200;
+const PLOT_WIDTH: u16 = 500;
+const PLOT_HEIGHT: u16 = 150;
+
+/// A gust that's in progress, so the screen can follow along
+/// with the audio thread.
+struct Gust {
+	from: f64,
+	to: f64,
+	tween: Tween,
+	start_time: Instant,
+}
+
+impl Gust {
+	fn speed(&self) -> f64 {
+		let time = self
+			.start_time
+			.elapsed()
+			.as_secs_f64()
+			.min(self.tween.duration);
+		self.tween.tween(self.from, self.to, time)
+	}
+
+	fn finished(&self) -> bool {
+		self.start_time.elapsed().as_secs_f64() >= self.tween.duration
+	}
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Message {
+	GoToDemoSelect,
+	Play,
+	Stop,
+	/// Sets the speed the wind drifts around (from 0 to 1).
+	SetBaseSpeed(f64),
+	/// Sets how far the wind can drift from the base speed.
+	SetGustiness(f64),
+}
+
+pub struct WindDemo {
+	audio_manager: AudioManager,
+	noise_sound_id: SoundId,
+	rumble_track_id: SubTrackId,
+	whistle_track_id: SubTrackId,
+	wind_speed_parameter_id: ParameterId,
+	instance_ids: Vec<InstanceId>,
+	base_speed: f64,
+	gustiness: f64,
+	/// The wind speed (or where the last gust was headed).
+	speed: f64,
+	gust: Option<Gust>,
+	/// When to start the next gust.
+	next_gust_time: Instant,
+	rng: StdRng,
+	/// The wind speed over the last few seconds, oldest first.
+	history: VecDeque<f32>,
+	last_history_time: Instant,
+	screen_wrapper: ScreenWrapper<Message>,
+	play_button: iced::button::State,
+	base_speed_slider: iced::slider::State,
+	gustiness_slider: iced::slider::State,
+}
+
+impl WindDemo {
+	pub fn new(audio_manager_settings: AudioManagerSettings) -> Result<Self, Box<dyn Error>> {
+		let mut audio_manager = create_audio_manager(audio_manager_settings)?;
+		let wind_speed_parameter_id = audio_manager.add_parameter(INITIAL_BASE_SPEED)?;
+		let rumble_track_id = audio_manager.add_sub_track(Default::default())?;
+		audio_manager.add_effect_to_track(
+			rumble_track_id,
+			Filter::new(FilterSettings::new().cutoff(Value::Parameter(
+				wind_speed_parameter_id,
+				Mapping {
+					input_range: (0.0, 1.0),
+					output_range: (MIN_RUMBLE_CUTOFF, MAX_RUMBLE_CUTOFF),
+					clamp_bottom: true,
+					clamp_top: true,
+				},
+			))),
+			Default::default(),
+		)?;
+		let whistle_track_id = audio_manager.add_sub_track(Default::default())?;
+		audio_manager.add_effect_to_track(
+			whistle_track_id,
+			Filter::new(
+				FilterSettings::new()
+					.mode(FilterMode::BandPass)
+					.cutoff(Value::Parameter(
+						wind_speed_parameter_id,
+						Mapping {
+							input_range: (0.0, 1.0),
+							output_range: (MIN_WHISTLE_CUTOFF, MAX_WHISTLE_CUTOFF),
+							clamp_bottom: true,
+							clamp_top: true,
+						},
+					))
+					.resonance(WHISTLE_RESONANCE),
+			),
+			Default::default(),
+		)?;
+		// the noise is played on both tracks at the same time,
+		// which a cooldown would block
+		let noise_sound_id = audio_manager.add_sound(synth::noise(
+			NOISE_LENGTH,
+			PlayableSettings {
+				cooldown: None,
+				..PlayableSettings::new().default_loop_start(0.0)
+			},
+		))?;
+		Ok(Self {
+			audio_manager,
+			noise_sound_id,
+			rumble_track_id,
+			whistle_track_id,
+			wind_speed_parameter_id,
+			instance_ids: vec![],
+			base_speed: INITIAL_BASE_SPEED,
+			gustiness: INITIAL_GUSTINESS,
+			speed: INITIAL_BASE_SPEED,
+			gust: None,
+			next_gust_time: Instant::now(),
+			rng: StdRng::from_entropy(),
+			history: VecDeque::new(),
+			last_history_time: Instant::now(),
+			screen_wrapper: ScreenWrapper::new("Wind demo".into(), Message::GoToDemoSelect),
+			play_button: iced::button::State::new(),
+			base_speed_slider: iced::slider::State::new(),
+			gustiness_slider: iced::slider::State::new(),
+		})
+	}
+
+	fn is_playing(&self) -> bool {
+		!self.instance_ids.is_empty()
+	}
+
+	/// The wind speed, as best the screen can tell.
+	fn current_speed(&self) -> f64 {
+		match &self.gust {
+			Some(gust) => gust.speed(),
+			None => self.speed,
+		}
+	}
+
+	fn play(&mut self) -> Result<(), Box<dyn Error>> {
+		// the wind starts out at the base speed and starts
+		// drifting right away
+		self.audio_manager
+			.set_parameter(self.wind_speed_parameter_id, self.base_speed, None)?;
+		self.speed = self.base_speed;
+		self.gust = None;
+		self.next_gust_time = Instant::now();
+		self.history.clear();
+		let rumble_settings = InstanceSettings::new()
+			.track(self.rumble_track_id)
+			.volume(Value::Parameter(
+				self.wind_speed_parameter_id,
+				Mapping {
+					input_range: (0.0, 1.0),
+					output_range: (0.15, 1.0),
+					clamp_bottom: true,
+					clamp_top: true,
+				},
+			))
+			.fade_in_tween(Tween::linear(1.0));
+		let whistle_settings = InstanceSettings::new()
+			.track(self.whistle_track_id)
+			.volume(Value::Parameter(
+				self.wind_speed_parameter_id,
+				Mapping {
+					input_range: (WHISTLE_START_SPEED, 1.0),
+					output_range: (0.0, 0.5),
+					clamp_bottom: true,
+					clamp_top: true,
+				},
+			))
+			.fade_in_tween(Tween::linear(1.0));
+		self.instance_ids.push(
+			self.audio_manager
+				.play(self.noise_sound_id, rumble_settings)?,
+		);
+		self.instance_ids.push(
+			self.audio_manager
+				.play(self.noise_sound_id, whistle_settings)?,
+		);
+		Ok(())
+	}
+
+	fn stop(&mut self) -> Result<(), Box<dyn Error>> {
+		for instance_id in self.instance_ids.drain(..) {
+			self.audio_manager.stop_instance(
+				instance_id,
+				StopInstanceSettings::new().fade_tween(Tween::linear(1.0)),
+			)?;
+		}
+		Ok(())
+	}
+
+	/// Tweens the wind speed to a random speed near the base
+	/// speed over a random amount of time.
+	fn start_gust(&mut self) -> Result<(), Box<dyn Error>> {
+		let duration = self.rng.gen_range(MIN_GUST_TIME, MAX_GUST_TIME);
+		let to = (self.base_speed + self.rng.gen_range(-1.0, 1.0) * self.gustiness).clamp(0.0, 1.0);
+		let tween = Tween {
+			duration,
+			easing: Easing::PowI(2),
+			ease_direction: EaseDirection::InOut,
+		};
+		let from = self.current_speed();
+		self.audio_manager
+			.set_parameter(self.wind_speed_parameter_id, to, Some(tween))?;
+		self.speed = to;
+		self.gust = Some(Gust {
+			from,
+			to,
+			tween,
+			start_time: Instant::now(),
+		});
+		self.next_gust_time = Instant::now() + Duration::from_secs_f64(duration);
+		Ok(())
+	}
+
+	pub fn update(&mut self, message: Message) -> Result<(), Box<dyn Error>> {
+		match message {
+			Message::Play if !self.is_playing() => self.play()?,
+			Message::Stop => self.stop()?,
+			Message::SetBaseSpeed(speed) => {
+				self.base_speed = speed.clamp(0.0, 1.0);
+				// head for the new base speed right away instead of
+				// finishing the current gust
+				self.next_gust_time = Instant::now();
+			}
+			Message::SetGustiness(gustiness) => {
+				self.gustiness = gustiness.clamp(0.0, 1.0);
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	pub fn check_for_events(&mut self) -> Result<(), Box<dyn Error>> {
+		if !self.is_playing() {
+			return Ok(());
+		}
+		if Instant::now() >= self.next_gust_time {
+			self.start_gust()?;
+		}
+		if self.gust.as_ref().is_some_and(Gust::finished) {
+			self.gust = None;
+		}
+		if self.last_history_time.elapsed().as_secs_f64() >= HISTORY_INTERVAL {
+			self.last_history_time = Instant::now();
+			self.history.push_back(self.current_speed() as f32);
+			while self.history.len() > HISTORY_LENGTH {
+				self.history.pop_front();
+			}
+		}
+		Ok(())
+	}
+
+	pub fn view(&mut self) -> iced::Element<'_, Message> {
+		let playing = self.is_playing();
+		let status_text = if playing {
+			format!("Wind speed: {:.0}%", self.current_speed() * 100.0)
+		} else {
+			"Press Play to start the wind".into()
+		};
+		// the plot fills in from the right, so the newest speed is
+		// always at the marker
+		let mut plot_points = vec![0.0; HISTORY_LENGTH - self.history.len()];
+		plot_points.extend(self.history.iter().copied());
+		let mut plot = CurvePlot::new(plot_points, PLOT_WIDTH, PLOT_HEIGHT);
+		if playing {
+			plot = plot.marker(1.0);
+		}
+		self.screen_wrapper.view(
+			Column::new()
+				.spacing(16)
+				.align_items(Align::Center)
+				.push(
+					Button::new(
+						&mut self.play_button,
+						Text::new(if playing { "Stop" } else { "Play" })
+							.width(Length::Fill)
+							.horizontal_alignment(HorizontalAlignment::Center),
+					)
+					.on_press(if playing {
+						Message::Stop
+					} else {
+						Message::Play
+					})
+					.width(Length::Units(120))
+					.style(AppStyles),
+				)
+				.push(Text::new(status_text))
+				.push(plot)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Base speed: {:.0}%", self.base_speed * 100.0))
+								.width(Length::Units(200)),
+						)
+						.push(
+							Slider::new(
+								&mut self.base_speed_slider,
+								0.0..=1.0,
+								self.base_speed,
+								Message::SetBaseSpeed,
+							)
+							.step(0.01)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Row::new()
+						.max_width(500)
+						.spacing(16)
+						.align_items(Align::Center)
+						.push(
+							Text::new(format!("Gustiness: {:.0}%", self.gustiness * 100.0))
+								.width(Length::Units(200)),
+						)
+						.push(
+							Slider::new(
+								&mut self.gustiness_slider,
+								0.0..=1.0,
+								self.gustiness,
+								Message::SetGustiness,
+							)
+							.step(0.01)
+							.style(AppStyles),
+						),
+				)
+				.push(
+					Column::new()
+						.width(Length::Fill)
+						.max_width(600)
+						.push(Text::new(EXPLANATION_TEXT)),
+				),
+		)
+	}
+}
+
+#[cfg(feature = "midi")]
+impl crate::ui::midi_learn::MidiControls for WindDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "wind_demo";
+
+	fn control_for_message(message: &Message) -> Option<String> {
+		match message {
+			Message::SetBaseSpeed(_) => Some("Wind speed".into()),
+			Message::SetGustiness(_) => Some("Gustiness".into()),
+			_ => None,
+		}
+	}
+
+	fn control_messages(&self, control: &str, value: f64) -> Vec<Message> {
+		match control {
+			"Wind speed" => vec![Message::SetBaseSpeed(value)],
+			"Gustiness" => vec![Message::SetGustiness(value)],
+			_ => vec![],
+		}
+	}
+}
+
+impl crate::ui::screen_states::RememberControls for WindDemo {
+	type Message = Message;
+
+	const NAME: &'static str = "wind_demo";
+
+	fn restore_messages(&self) -> Vec<Message> {
+		vec![
+			Message::SetBaseSpeed(self.base_speed),
+			Message::SetGustiness(self.gustiness),
+		]
+	}
+}
+
+impl crate::ui::media_keys::MediaControls for WindDemo {
+	type Message = Message;
+
+	fn media_key_messages(&self, key: crate::ui::media_keys::MediaKey) -> Vec<Message> {
+		crate::ui::media_keys::transport_messages(
+			key,
+			self.is_playing(),
+			Message::Play,
+			Message::Stop,
+		)
+	}
+
+	fn now_playing(&self) -> Option<String> {
+		if !self.is_playing() {
+			return None;
+		}
+		Some("Wind demo".into())
+	}
+}
@@ -0,0 +1,45 @@
+use std::{any::Any, collections::HashMap};
+
+/// Implemented by screens with controls that should be put back
+/// the way the user left them when they come back to the screen.
+pub trait RememberControls {
+	type Message: 'static;
+
+	/// A name that keeps the screen's state separate from
+	/// other screens'.
+	const NAME: &'static str;
+
+	/// Creates the messages that set the controls to how they
+	/// are now. These should only change settings - not start
+	/// playback - since they're sent to a freshly created screen.
+	fn restore_messages(&self) -> Vec<Self::Message>;
+}
+
+/// Remembers the controls of the screens the user has left.
+///
+/// Screens (and their audio managers) are still created from
+/// scratch every time they're opened, so instead of keeping the
+/// screens around, each one is asked for the messages that would
+/// set its controls back up, and those messages are sent to the
+/// new screen. That way the audio ends up matching the controls.
+#[derive(Default)]
+pub struct ScreenStates {
+	messages: HashMap<&'static str, Box<dyn Any>>,
+}
+
+impl ScreenStates {
+	pub fn save<S: RememberControls>(&mut self, screen: &S) {
+		self.messages
+			.insert(S::NAME, Box::new(screen.restore_messages()));
+	}
+
+	/// Gets the messages that restore a screen's controls (if it
+	/// was opened before).
+	pub fn take<S: RememberControls>(&mut self) -> Vec<S::Message> {
+		self.messages
+			.remove(S::NAME)
+			.and_then(|messages| messages.downcast::<Vec<S::Message>>().ok())
+			.map(|messages| *messages)
+			.unwrap_or_default()
+	}
+}
@@ -0,0 +1,137 @@
+use std::{error::Error, path::Path};
+
+use serde::de::DeserializeOwned;
+
+use crate::ui::{screen_states::RememberControls, session_recorder::SessionScript};
+
+/// Plays back a recorded session by handing out its messages
+/// once the time they were recorded at comes around.
+///
+/// In the app, the time is real time since the replay started.
+/// Tests drive it with the time of an offline renderer instead,
+/// so the same session always produces the same results.
+pub struct SessionPlayer {
+	script: SessionScript,
+	next_action: usize,
+}
+
+impl SessionPlayer {
+	pub fn new(script: SessionScript) -> Self {
+		Self {
+			script,
+			next_action: 0,
+		}
+	}
+
+	pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+		Ok(Self::new(SessionScript::load(path)?))
+	}
+
+	/// The name of the screen the session was recorded on.
+	pub fn screen(&self) -> &str {
+		&self.script.screen
+	}
+
+	pub fn is_finished(&self) -> bool {
+		self.next_action >= self.script.actions.len()
+	}
+
+	/// How many of the session's actions have been played so far,
+	/// and how many there are in total.
+	pub fn progress(&self) -> (usize, usize) {
+		(self.next_action, self.script.actions.len())
+	}
+
+	/// Gets the messages that were recorded at or before `time`
+	/// (in seconds since the replay started) and haven't been
+	/// handed out yet.
+	pub fn due<S: RememberControls>(&mut self, time: f64) -> Result<Vec<S::Message>, Box<dyn Error>>
+	where
+		S::Message: DeserializeOwned,
+	{
+		if self.script.screen != S::NAME {
+			return Err(format!(
+				"the session was recorded on {}, not {}",
+				self.script.screen,
+				S::NAME
+			)
+			.into());
+		}
+		let mut messages = vec![];
+		while let Some(action) = self.script.actions.get(self.next_action) {
+			if action.time > time {
+				break;
+			}
+			messages.push(serde_json::from_value(action.message.clone())?);
+			self.next_action += 1;
+		}
+		Ok(messages)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde::{Deserialize, Serialize};
+
+	use super::*;
+	use crate::ui::session_recorder::RecordedAction;
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	enum TestMessage {
+		Press(usize),
+	}
+
+	struct TestScreen;
+
+	impl RememberControls for TestScreen {
+		type Message = TestMessage;
+
+		const NAME: &'static str = "test_screen";
+
+		fn restore_messages(&self) -> Vec<TestMessage> {
+			vec![]
+		}
+	}
+
+	fn script(screen: &str, times: &[f64]) -> SessionScript {
+		SessionScript {
+			screen: screen.into(),
+			tempo: 120.0,
+			actions: times
+				.iter()
+				.enumerate()
+				.map(|(index, time)| RecordedAction {
+					time: *time,
+					beat: time * 2.0,
+					message: serde_json::to_value(TestMessage::Press(index)).unwrap(),
+				})
+				.collect(),
+		}
+	}
+
+	#[test]
+	fn messages_are_handed_out_when_they_were_recorded() {
+		let mut player = SessionPlayer::new(script("test_screen", &[0.0, 0.5, 0.5, 2.0]));
+		assert_eq!(
+			player.due::<TestScreen>(0.0).unwrap(),
+			vec![TestMessage::Press(0)]
+		);
+		assert_eq!(player.due::<TestScreen>(0.25).unwrap(), vec![]);
+		assert_eq!(
+			player.due::<TestScreen>(1.0).unwrap(),
+			vec![TestMessage::Press(1), TestMessage::Press(2)]
+		);
+		assert!(!player.is_finished());
+		assert_eq!(
+			player.due::<TestScreen>(2.0).unwrap(),
+			vec![TestMessage::Press(3)]
+		);
+		assert!(player.is_finished());
+	}
+
+	#[test]
+	fn sessions_only_play_on_the_screen_they_were_recorded_on() {
+		let mut player = SessionPlayer::new(script("other_screen", &[0.0]));
+		assert!(player.due::<TestScreen>(0.0).is_err());
+	}
+}
@@ -0,0 +1,191 @@
+use std::{
+	error::Error,
+	fs,
+	path::{Path, PathBuf},
+	time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use iced::{Align, Button, Row, Text};
+use kira::Tempo;
+use serde::{Deserialize, Serialize};
+
+use crate::ui::{screen_states::RememberControls, style::AppStyles};
+
+/// Something the user did on a screen during a recorded session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedAction {
+	/// When the action happened, in seconds since the recording
+	/// started.
+	pub time: f64,
+	/// The same time in beats at the demo's tempo.
+	pub beat: f64,
+	/// The screen message the action sent.
+	pub message: serde_json::Value,
+}
+
+/// A recorded session, as it's saved to a script file.
+///
+/// The first actions (at time 0) put the screen's controls
+/// back how they were when the recording started, so playing
+/// the script on a freshly opened screen gets the same results.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionScript {
+	/// The [`RememberControls::NAME`] of the screen the session
+	/// was recorded on.
+	pub screen: String,
+	/// The tempo the beat timestamps were counted at.
+	pub tempo: f64,
+	pub actions: Vec<RecordedAction>,
+}
+
+impl SessionScript {
+	pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+		Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+	}
+}
+
+struct Recording {
+	script: SessionScript,
+	start_time: Instant,
+}
+
+/// Records everything the user does on a screen and saves it
+/// as a script, so a session can be attached to a bug report
+/// or played back later.
+pub struct SessionRecorder {
+	recording: Option<Recording>,
+	/// Where the last recording was saved, or why it couldn't be.
+	last_export: Option<Result<PathBuf, String>>,
+	record_button: iced::button::State,
+}
+
+impl SessionRecorder {
+	pub fn new() -> Self {
+		Self {
+			recording: None,
+			last_export: None,
+			record_button: iced::button::State::new(),
+		}
+	}
+
+	pub fn is_recording(&self) -> bool {
+		self.recording.is_some()
+	}
+
+	pub fn start<S: RememberControls>(
+		&mut self,
+		screen: &S,
+		tempo: Tempo,
+	) -> Result<(), Box<dyn Error>>
+	where
+		S::Message: Serialize,
+	{
+		let actions = screen
+			.restore_messages()
+			.iter()
+			.map(|message| {
+				Ok(RecordedAction {
+					time: 0.0,
+					beat: 0.0,
+					message: serde_json::to_value(message)?,
+				})
+			})
+			.collect::<Result<_, serde_json::Error>>()?;
+		self.recording = Some(Recording {
+			script: SessionScript {
+				screen: S::NAME.into(),
+				tempo: tempo.0,
+				actions,
+			},
+			start_time: Instant::now(),
+		});
+		Ok(())
+	}
+
+	/// Should be called with every message sent to a screen.
+	/// Messages for screens other than the one being recorded
+	/// are ignored.
+	pub fn record<S: RememberControls>(
+		&mut self,
+		message: &S::Message,
+	) -> Result<(), Box<dyn Error>>
+	where
+		S::Message: Serialize,
+	{
+		if let Some(recording) = &mut self.recording {
+			if recording.script.screen == S::NAME {
+				let time = recording.start_time.elapsed().as_secs_f64();
+				recording.script.actions.push(RecordedAction {
+					time,
+					beat: time * recording.script.tempo / 60.0,
+					message: serde_json::to_value(message)?,
+				});
+			}
+		}
+		Ok(())
+	}
+
+	/// Stops recording and saves the script to the `recordings`
+	/// folder. Whether it saved is shown next to the record button.
+	pub fn stop(&mut self) {
+		if let Some(recording) = self.recording.take() {
+			self.last_export =
+				Some(Self::export(&recording.script).map_err(|error| error.to_string()));
+		}
+	}
+
+	fn export(script: &SessionScript) -> Result<PathBuf, Box<dyn Error>> {
+		let dir = std::env::current_dir()?.join("recordings");
+		fs::create_dir_all(&dir)?;
+		let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+		let path = dir.join(format!("{}-{}.json", script.screen, timestamp));
+		fs::write(&path, serde_json::to_string_pretty(script)?)?;
+		Ok(path)
+	}
+
+	/// Shows the record button and what the recorder is doing.
+	/// `replay_progress` is how far through a session that's being
+	/// replayed the app is (see [`SessionPlayer::progress`]).
+	///
+	/// [`SessionPlayer::progress`]: crate::ui::session_player::SessionPlayer::progress
+	pub fn view<Message: Clone + 'static>(
+		&mut self,
+		toggle_recording_message: Message,
+		replay_progress: Option<(usize, usize)>,
+	) -> iced::Element<'_, Message> {
+		let status = match (replay_progress, &self.recording, &self.last_export) {
+			(Some((played, total)), _, _) => {
+				format!("Replaying a session ({}/{} actions)", played, total)
+			}
+			(None, Some(recording), _) => {
+				format!("Recording ({} actions)", recording.script.actions.len())
+			}
+			(None, None, Some(Ok(path))) => format!("Saved {}", path.display()),
+			(None, None, Some(Err(error))) => format!("Couldn't save the recording: {}", error),
+			(None, None, None) => {
+				"Record a session to save everything you do on this screen, or drop a \
+				recording here to replay it"
+					.into()
+			}
+		};
+		let recording = self.is_recording();
+		Row::new()
+			.padding(8)
+			.spacing(16)
+			.align_items(Align::Center)
+			.push(
+				Button::new(
+					&mut self.record_button,
+					Text::new(if recording {
+						"Stop recording"
+					} else {
+						"Record session"
+					}),
+				)
+				.on_press(toggle_recording_message)
+				.style(AppStyles),
+			)
+			.push(Text::new(status).size(16))
+			.into()
+	}
+}
@@ -57,3 +57,44 @@ impl iced::button::StyleSheet for AppStyles {
 		}
 	}
 }
+
+impl iced::slider::StyleSheet for AppStyles {
+	fn active(&self) -> iced::slider::Style {
+		iced::slider::Style {
+			rail_colors: (
+				Color::from_rgb(0.5, 0.5, 0.5),
+				Color::from_rgb(0.25, 0.25, 0.25),
+			),
+			handle: iced::slider::Handle {
+				shape: iced::slider::HandleShape::Circle { radius: 8.0 },
+				color: Color::from_rgb(0.9, 0.9, 0.9),
+				border_width: 0.0,
+				border_color: Color::TRANSPARENT,
+			},
+		}
+	}
+
+	fn hovered(&self) -> iced::slider::Style {
+		let active = self.active();
+
+		iced::slider::Style {
+			handle: iced::slider::Handle {
+				color: Color::WHITE,
+				..active.handle
+			},
+			..active
+		}
+	}
+
+	fn dragging(&self) -> iced::slider::Style {
+		let active = self.active();
+
+		iced::slider::Style {
+			handle: iced::slider::Handle {
+				color: Color::from_rgb(0.75, 0.75, 0.75),
+				..active.handle
+			},
+			..active
+		}
+	}
+}
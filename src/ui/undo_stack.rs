@@ -0,0 +1,162 @@
+use iced::keyboard::{KeyCode, Modifiers};
+
+/// The most edits an editor remembers.
+const MAX_HISTORY: usize = 100;
+
+/// Keeps earlier versions of an editor's state so edits can be
+/// undone and redone.
+///
+/// Editors store their whole state (patterns, routings, effect
+/// settings) in one `Clone` value and push a copy of it before
+/// every destructive edit.
+pub struct UndoStack<T> {
+	undo: Vec<T>,
+	redo: Vec<T>,
+}
+
+impl<T> UndoStack<T> {
+	pub fn new() -> Self {
+		Self {
+			undo: vec![],
+			redo: vec![],
+		}
+	}
+
+	/// Remembers the state from before an edit. Anything that
+	/// was undone can't be redone after this.
+	pub fn push(&mut self, state: T) {
+		if self.undo.len() == MAX_HISTORY {
+			self.undo.remove(0);
+		}
+		self.undo.push(state);
+		self.redo.clear();
+	}
+
+	pub fn can_undo(&self) -> bool {
+		!self.undo.is_empty()
+	}
+
+	pub fn can_redo(&self) -> bool {
+		!self.redo.is_empty()
+	}
+
+	/// Replaces the state with the one from before the last edit.
+	/// Returns `false` if there's nothing to undo.
+	pub fn undo(&mut self, state: &mut T) -> bool {
+		match self.undo.pop() {
+			Some(previous) => {
+				self.redo.push(std::mem::replace(state, previous));
+				true
+			}
+			None => false,
+		}
+	}
+
+	/// Brings back the state from before the last undo.
+	/// Returns `false` if there's nothing to redo.
+	pub fn redo(&mut self, state: &mut T) -> bool {
+		match self.redo.pop() {
+			Some(next) => {
+				self.undo.push(std::mem::replace(state, next));
+				true
+			}
+			None => false,
+		}
+	}
+}
+
+/// The keyboard shortcuts for undoing and redoing, which the
+/// app sends to whichever editor is open.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UndoShortcut {
+	Undo,
+	Redo,
+}
+
+impl UndoShortcut {
+	/// Ctrl+Z undoes, and Ctrl+Y or Ctrl+Shift+Z redoes (with
+	/// Cmd instead of Ctrl on macOS).
+	pub fn from_key_press(key_code: KeyCode, modifiers: Modifiers) -> Option<Self> {
+		if !modifiers.is_command_pressed() {
+			return None;
+		}
+		match key_code {
+			KeyCode::Z if modifiers.shift => Some(Self::Redo),
+			KeyCode::Z => Some(Self::Undo),
+			KeyCode::Y => Some(Self::Redo),
+			_ => None,
+		}
+	}
+}
+
+/// Implemented by editor screens so the undo shortcuts work on
+/// them.
+pub trait UndoControls {
+	type Message;
+
+	/// Creates the message an undo shortcut should send, or `None`
+	/// if there's nothing to undo or redo.
+	fn undo_shortcut_message(&self, shortcut: UndoShortcut) -> Option<Self::Message>;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn pushing_makes_the_old_state_undoable() {
+		let mut history = UndoStack::new();
+		assert!(!history.can_undo());
+		history.push(1);
+		assert!(history.can_undo());
+		assert!(!history.can_redo());
+	}
+
+	#[test]
+	fn undo_brings_back_the_state_from_before_the_edit() {
+		let mut history = UndoStack::new();
+		let mut state = 1;
+		history.push(state);
+		state = 2;
+		assert!(history.undo(&mut state));
+		assert_eq!(state, 1);
+		assert!(!history.undo(&mut state));
+		assert_eq!(state, 1);
+	}
+
+	#[test]
+	fn redo_brings_back_the_undone_state() {
+		let mut history = UndoStack::new();
+		let mut state = 1;
+		assert!(!history.redo(&mut state));
+		history.push(state);
+		state = 2;
+		history.undo(&mut state);
+		assert!(history.redo(&mut state));
+		assert_eq!(state, 2);
+		assert!(!history.can_redo());
+		assert!(history.undo(&mut state));
+		assert_eq!(state, 1);
+	}
+
+	#[test]
+	fn a_new_edit_throws_away_what_was_undone() {
+		let mut history = UndoStack::new();
+		let mut state = 1;
+		history.push(state);
+		state = 2;
+		history.push(state);
+		state = 3;
+		history.undo(&mut state);
+		assert_eq!(state, 2);
+		history.push(state);
+		state = 4;
+		assert!(!history.can_redo());
+		assert!(!history.redo(&mut state));
+		assert_eq!(state, 4);
+		history.undo(&mut state);
+		assert_eq!(state, 2);
+		history.undo(&mut state);
+		assert_eq!(state, 1);
+	}
+}